@@ -3,15 +3,20 @@ use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
 use tracing::Span;
 
 pub mod app;
+pub mod borders;
 pub mod config;
 pub mod config_watcher;
 pub mod drag_swap;
+pub mod drop_zone_overlay;
 pub mod event_tap;
 pub mod gesture_tap;
+pub mod hint_overlay;
+pub mod hooks;
 pub mod menu_bar;
 pub mod mission_control;
 pub mod mission_control_observer;
 pub mod notification_center;
+pub mod preselect_overlay;
 pub mod process;
 pub mod raise_manager;
 pub mod reactor;
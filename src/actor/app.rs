@@ -24,6 +24,7 @@ use crate::actor;
 use crate::actor::reactor::transaction_manager::TransactionId;
 use crate::actor::reactor::{self, Event, Requested};
 use crate::common::collections::HashMap;
+use crate::common::metrics;
 use crate::model::tx_store::WindowTxStore;
 use crate::sys::app::NSRunningApplicationExt;
 pub use crate::sys::app::{AppInfo, WindowInfo, pid_t};
@@ -39,6 +40,10 @@ use crate::sys::skylight::{G_CONNECTION, SLSDisableUpdate, SLSReenableUpdate};
 use crate::sys::timer::Timer;
 use crate::sys::window_server::{self, WindowServerId, WindowServerInfo};
 
+mod circuit_breaker;
+
+use circuit_breaker::{CircuitBreaker, REQUEST_TIMEOUT, Transition};
+
 const kAXApplicationActivatedNotification: &str = "AXApplicationActivated";
 const kAXApplicationDeactivatedNotification: &str = "AXApplicationDeactivated";
 const kAXApplicationHiddenNotification: &str = "AXApplicationHidden";
@@ -323,6 +328,14 @@ pub enum Request {
     GetVisibleWindows,
     WindowMaybeDestroyed(WindowId),
     CloseWindow(Option<WindowServerId>),
+    /// Minimize or deminiaturize a window. The resulting AX notification drives
+    /// the layout, just as when the user clicks the minimize button.
+    SetMinimized(WindowId, bool),
+    /// Move a window into or out of its own macOS fullscreen space.
+    ToggleNativeFullscreen(WindowId),
+    /// Order a window above other windows with an accessibility raise,
+    /// without activating the app or moving focus to it.
+    RaiseWithoutFocus(WindowId),
 
     SetWindowFrame(WindowId, CGRect, TransactionId, bool),
     SetBatchWindowFrame(Vec<(WindowId, CGRect)>, TransactionId, bool),
@@ -346,6 +359,30 @@ pub enum Request {
     Raise(Vec<WindowId>, CancellationToken, u64, Quiet),
 }
 
+impl Request {
+    /// Frame writes, which are dropped while the app is unresponsive: the next
+    /// layout after it recovers sends fresh ones anyway.
+    fn can_be_skipped(&self) -> bool {
+        matches!(
+            self,
+            Request::SetWindowFrame(..)
+                | Request::SetBatchWindowFrame(..)
+                | Request::SetWindowPos(..)
+                | Request::AnimationFrame { .. }
+        )
+    }
+
+    /// Requests answered by accessibility calls to the app, whose duration
+    /// tells whether it is still responsive. Raises are handed to the raise
+    /// thread and animation frames are only queued until the next flush.
+    fn calls_app(&self) -> bool {
+        !matches!(
+            self,
+            Request::Terminate | Request::Raise(..) | Request::AnimationFrame { .. }
+        )
+    }
+}
+
 struct RaiseRequest(Vec<WindowId>, CancellationToken, u64, Quiet);
 
 #[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
@@ -385,6 +422,7 @@ struct State {
     raises_tx: actor::Sender<RaiseRequest>,
     tx_store: Option<WindowTxStore>,
     pending_frames: HashMap<WindowId, PendingFrame>,
+    breaker: CircuitBreaker,
 }
 
 struct AppWindowState {
@@ -516,9 +554,15 @@ impl State {
         mut requests_rx: actor::Receiver<Request>,
         mut notifications_rx: actor::Receiver<(AXUIElement, AxNotificationKind, Option<WindowId>)>,
     ) {
+        let mut probe_timer = Timer::manual();
         loop {
             let batch = select! {
                 biased;
+                _ = probe_timer.next(), if this.borrow().breaker.is_open() => {
+                    this.borrow_mut().probe_responsiveness();
+                    Self::schedule_probe(this, &probe_timer);
+                    continue;
+                }
                 req = requests_rx.recv() => {
                     let Some(req) = req else { break };
                     let mut batch = vec![req];
@@ -536,6 +580,7 @@ impl State {
             if Self::handle_request_batch(this, batch) {
                 break;
             }
+            Self::schedule_probe(this, &probe_timer);
         }
     }
 
@@ -544,8 +589,20 @@ impl State {
             let mut this = this.borrow_mut();
             let _guard = span.enter();
             debug!(?this.bundle_id, ?this.pid, ?request, "Got request");
+            if request.can_be_skipped() && !this.breaker.allows(Instant::now()) {
+                trace!(?this.pid, ?request, "Dropping request for unresponsive app");
+                continue;
+            }
             let request_dbg = format!("{request:?}");
-            match this.handle_request(request) {
+            let calls_app = request.calls_app();
+            let started = Instant::now();
+            let result = this.handle_request(request);
+            let elapsed = started.elapsed();
+            metrics::AX_REQUEST_LATENCY.record(elapsed);
+            if calls_app {
+                this.record_request_time(elapsed);
+            }
+            match result {
                 Ok(should_terminate) if should_terminate => return true,
                 Ok(_) => (),
                 #[allow(non_upper_case_globals)]
@@ -559,10 +616,46 @@ impl State {
                 }
             }
         }
-        this.borrow_mut().flush_all_frames();
+        let mut this = this.borrow_mut();
+        if !this.pending_frames.is_empty() {
+            let started = Instant::now();
+            this.flush_all_frames();
+            this.record_request_time(started.elapsed());
+        }
         false
     }
 
+    fn schedule_probe(this: &RefCell<Self>, probe_timer: &Timer) {
+        if let Some(delay) = this.borrow().breaker.retry_in(Instant::now()) {
+            probe_timer.set_next_fire(delay);
+        }
+    }
+
+    /// Check whether an unresponsive app answers again once its backoff has
+    /// passed, since its windows get no requests while it is skipped.
+    fn probe_responsiveness(&mut self) {
+        if !self.breaker.allows(Instant::now()) {
+            return;
+        }
+        let started = Instant::now();
+        let _ = self.app.frontmost();
+        self.record_request_time(started.elapsed());
+    }
+
+    fn record_request_time(&mut self, elapsed: Duration) {
+        match self.breaker.record_request_time(elapsed, Instant::now()) {
+            Some(Transition::Unresponsive) => {
+                warn!(?self.bundle_id, ?self.pid, ?elapsed, "App stopped responding to requests");
+                self.send_event(Event::ApplicationUnresponsive(self.pid));
+            }
+            Some(Transition::Recovered) => {
+                info!(?self.bundle_id, ?self.pid, "App is responding to requests again");
+                self.send_event(Event::ApplicationResponsive(self.pid));
+            }
+            None => {}
+        }
+    }
+
     fn flush_frames(&mut self, wid: WindowId) -> Result<(), AxError> {
         let Some(PendingFrame { span, frame, set_size, txid }) = self.pending_frames.remove(&wid)
         else {
@@ -606,6 +699,7 @@ impl State {
     #[instrument(skip_all, fields(?info))]
     #[must_use]
     fn init(&mut self, handle: AppThreadHandle, info: AppInfo) -> bool {
+        self.set_request_timeout(&self.app);
         let extended_timeout_prefixes = ["com.jetbrains.", "org.gnu.Emacs"];
         let timeout = Instant::now()
             + match info.bundle_id.as_deref() {
@@ -731,6 +825,58 @@ impl State {
                     warn!(pid = self.pid, ?window_server_id, "Failed to post Command-W");
                 }
             }
+            Request::SetMinimized(wid, minimized) => {
+                let elem = match self.window_mut(wid) {
+                    Ok(window) => window.elem.clone(),
+                    Err(AxError::NotFound) => return Ok(false),
+                    Err(AxError::Ax(code)) => {
+                        if self.handle_ax_error(wid, &code) {
+                            return Ok(false);
+                        }
+                        return Err(AxError::Ax(code));
+                    }
+                };
+                if let Err(err) = elem.set_minimized(minimized) {
+                    warn!(
+                        pid = self.pid,
+                        ?wid,
+                        minimized,
+                        ?err,
+                        "Failed to set minimized state"
+                    );
+                }
+            }
+            Request::RaiseWithoutFocus(wid) => {
+                let elem = match self.window_mut(wid) {
+                    Ok(window) => window.elem.clone(),
+                    Err(AxError::NotFound) => return Ok(false),
+                    Err(AxError::Ax(code)) => {
+                        if self.handle_ax_error(wid, &code) {
+                            return Ok(false);
+                        }
+                        return Err(AxError::Ax(code));
+                    }
+                };
+                if let Err(err) = elem.raise() {
+                    debug!(pid = self.pid, ?wid, ?err, "Failed to raise window");
+                }
+            }
+            Request::ToggleNativeFullscreen(wid) => {
+                let elem = match self.window_mut(wid) {
+                    Ok(window) => window.elem.clone(),
+                    Err(AxError::NotFound) => return Ok(false),
+                    Err(AxError::Ax(code)) => {
+                        if self.handle_ax_error(wid, &code) {
+                            return Ok(false);
+                        }
+                        return Err(AxError::Ax(code));
+                    }
+                };
+                let fullscreen = elem.fullscreen().unwrap_or(false);
+                if let Err(err) = elem.set_fullscreen(!fullscreen) {
+                    warn!(pid = self.pid, ?wid, ?err, "Failed to toggle native fullscreen");
+                }
+            }
             Request::GetVisibleWindows => {
                 self.refresh_visible_windows()?;
             }
@@ -1453,12 +1599,21 @@ impl State {
         }
     }
 
+    /// Messaging timeouts apply per element, so the app element and every
+    /// window element need their own.
+    fn set_request_timeout(&self, elem: &AXUIElement) {
+        if let Err(err) = elem.set_messaging_timeout(REQUEST_TIMEOUT) {
+            debug!(pid = ?self.pid, ?err, "Failed to set the accessibility request timeout");
+        }
+    }
+
     #[must_use]
     fn register_window(
         &mut self,
         elem: AXUIElement,
         server_info_hint: Option<WindowServerInfo>,
     ) -> Option<(WindowInfo, WindowId, Option<WindowServerInfo>)> {
+        self.set_request_timeout(&elem);
         let Ok((mut info, server_info)) = WindowInfo::from_ax_element(&elem, server_info_hint)
         else {
             return None;
@@ -1584,6 +1739,7 @@ impl State {
         if old_elem == elem {
             return;
         }
+        self.set_request_timeout(&elem);
 
         // Move observer ownership before replacing the handle. Removing from an
         // invalid old element can legitimately fail; Observer retains its callback
@@ -1858,6 +2014,7 @@ fn app_thread_main(
         raises_tx,
         tx_store,
         pending_frames: HashMap::default(),
+        breaker: CircuitBreaker::default(),
     };
 
     let (requests_tx, requests_rx) = actor::channel();
@@ -0,0 +1,171 @@
+//! Detects apps that stop answering accessibility requests.
+//!
+//! Every request that times out counts as a failure. After a few in a row the
+//! breaker opens: frame writes to the app are dropped and the reactor leaves
+//! its windows alone. Once the backoff has passed, a single probe is let
+//! through; if it succeeds the app is responsive again, otherwise the breaker
+//! reopens for twice as long.
+
+use std::time::{Duration, Instant};
+
+/// How long a single accessibility request may take before it is abandoned and
+/// counted as a failure.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Consecutive timed-out requests before an app is considered unresponsive.
+const FAILURE_THRESHOLD: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    failures: u32,
+    state: State,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum State {
+    #[default]
+    Closed,
+    Open {
+        retry_at: Instant,
+        backoff: Duration,
+    },
+    /// The backoff has passed and the next request decides whether the app
+    /// has recovered.
+    Probing { backoff: Duration },
+}
+
+/// A change in the app's responsiveness the reactor should hear about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    Unresponsive,
+    Recovered,
+}
+
+impl CircuitBreaker {
+    /// Whether a request that can be skipped should be sent to the app now.
+    pub fn allows(&mut self, now: Instant) -> bool {
+        match self.state {
+            State::Closed | State::Probing { .. } => true,
+            State::Open { retry_at, backoff } if now >= retry_at => {
+                self.state = State::Probing { backoff };
+                true
+            }
+            State::Open { .. } => false,
+        }
+    }
+
+    pub fn is_open(&self) -> bool { !matches!(self.state, State::Closed) }
+
+    /// How long until a probe should be sent, while the breaker is open.
+    pub fn retry_in(&self, now: Instant) -> Option<Duration> {
+        match self.state {
+            State::Open { retry_at, .. } => Some(retry_at.saturating_duration_since(now)),
+            State::Probing { .. } => Some(Duration::ZERO),
+            State::Closed => None,
+        }
+    }
+
+    /// Records a request that took `elapsed`, counting it as a timeout once it
+    /// reached [`REQUEST_TIMEOUT`].
+    pub fn record_request_time(&mut self, elapsed: Duration, now: Instant) -> Option<Transition> {
+        if elapsed >= REQUEST_TIMEOUT {
+            self.record_timeout(now)
+        } else {
+            self.record_success()
+        }
+    }
+
+    pub fn record_success(&mut self) -> Option<Transition> {
+        self.failures = 0;
+        match std::mem::take(&mut self.state) {
+            State::Closed => None,
+            State::Open { .. } | State::Probing { .. } => Some(Transition::Recovered),
+        }
+    }
+
+    pub fn record_timeout(&mut self, now: Instant) -> Option<Transition> {
+        self.failures += 1;
+        match self.state {
+            State::Closed if self.failures >= FAILURE_THRESHOLD => {
+                self.open(now, INITIAL_BACKOFF);
+                Some(Transition::Unresponsive)
+            }
+            State::Closed | State::Open { .. } => None,
+            State::Probing { backoff } => {
+                self.open(now, (backoff * 2).min(MAX_BACKOFF));
+                None
+            }
+        }
+    }
+
+    fn open(&mut self, now: Instant, backoff: Duration) {
+        self.state = State::Open {
+            retry_at: now + backoff,
+            backoff,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_repeated_timeouts_and_recovers_on_success() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::default();
+        assert_eq!(breaker.record_timeout(now), None);
+        assert_eq!(breaker.record_timeout(now), None);
+        assert_eq!(breaker.record_timeout(now), Some(Transition::Unresponsive));
+        assert!(!breaker.allows(now));
+
+        assert!(breaker.allows(now + INITIAL_BACKOFF));
+        assert_eq!(breaker.record_success(), Some(Transition::Recovered));
+        assert!(!breaker.is_open());
+        assert_eq!(breaker.record_success(), None);
+    }
+
+    #[test]
+    fn slow_requests_open_the_breaker_until_a_fast_probe() {
+        let now = Instant::now();
+        let fast = REQUEST_TIMEOUT / 10;
+        let mut breaker = CircuitBreaker::default();
+        for _ in 1..FAILURE_THRESHOLD {
+            assert_eq!(breaker.record_request_time(REQUEST_TIMEOUT, now), None);
+        }
+        assert_eq!(
+            breaker.record_request_time(REQUEST_TIMEOUT, now),
+            Some(Transition::Unresponsive)
+        );
+        assert!(breaker.is_open());
+        assert!(!breaker.allows(now));
+
+        let probe_at = now + INITIAL_BACKOFF;
+        assert_eq!(breaker.retry_in(now), Some(INITIAL_BACKOFF));
+        assert!(breaker.allows(probe_at));
+        assert_eq!(breaker.retry_in(probe_at), Some(Duration::ZERO));
+        assert_eq!(
+            breaker.record_request_time(fast, probe_at),
+            Some(Transition::Recovered)
+        );
+        assert!(!breaker.is_open());
+        assert_eq!(breaker.record_request_time(fast, probe_at), None);
+    }
+
+    #[test]
+    fn failed_probe_doubles_the_backoff() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::default();
+        for _ in 0..FAILURE_THRESHOLD {
+            breaker.record_timeout(now);
+        }
+
+        let probe_at = now + INITIAL_BACKOFF;
+        assert!(breaker.allows(probe_at));
+        assert_eq!(breaker.record_timeout(probe_at), None);
+        assert_eq!(breaker.retry_in(probe_at), Some(INITIAL_BACKOFF * 2));
+        assert!(!breaker.allows(probe_at + INITIAL_BACKOFF));
+    }
+}
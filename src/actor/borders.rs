@@ -0,0 +1,194 @@
+use objc2::MainThreadMarker;
+use objc2_core_foundation::CGRect;
+use tracing::instrument;
+
+use crate::actor;
+use crate::actor::app::WindowId;
+use crate::common::collections::HashMap;
+use crate::common::collections::hash_map::Entry;
+use crate::common::config::Config;
+use crate::sys::window_server::WindowServerId;
+use crate::ui::borders::{BorderStyle, BorderWindow};
+use crate::ui::stack_line::Color;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorderTarget {
+    pub window: WindowId,
+    pub window_server_id: Option<WindowServerId>,
+    pub frame: CGRect,
+}
+
+/// Kinds of highlight that can surround a window at the same time. Variants
+/// are declared in priority order: the last kind present gets the innermost
+/// ring, directly around the window, and earlier kinds are nested outward
+/// around it. Each ring is ordered above the ring inside it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DecorationKind {
+    Inactive,
+    AlwaysOnTop,
+    Group,
+    DragTarget,
+    Urgent,
+    Focused,
+}
+
+/// An extra highlight requested for a window on top of the focus/inactive
+/// borders derived from `Update::focused`. Without a color the active border
+/// color is used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoration {
+    pub window: WindowId,
+    pub kind: DecorationKind,
+    pub color: Option<Color>,
+}
+
+/// Snapshot of the windows visible on active spaces, which one has focus, and
+/// any additional decorations.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Update {
+    pub focused: Option<WindowId>,
+    pub windows: Vec<BorderTarget>,
+    pub decorations: Vec<Decoration>,
+}
+
+#[derive(Debug)]
+pub enum Event {
+    Update(Update),
+    ConfigUpdated(Config),
+}
+
+pub type Sender = actor::Sender<Event>;
+pub type Receiver = actor::Receiver<Event>;
+
+pub struct Borders {
+    config: Config,
+    rx: Receiver,
+    #[allow(dead_code)]
+    mtm: MainThreadMarker,
+    borders: HashMap<(WindowId, DecorationKind), BorderWindow>,
+    last_update: Update,
+}
+
+impl Borders {
+    pub fn new(config: Config, rx: Receiver, mtm: MainThreadMarker) -> Self {
+        Self {
+            config,
+            rx,
+            mtm,
+            borders: HashMap::default(),
+            last_update: Update::default(),
+        }
+    }
+
+    pub async fn run(mut self) {
+        while let Some((span, event)) = self.rx.recv().await {
+            let _guard = span.enter();
+            self.handle_event(event);
+        }
+    }
+
+    fn is_enabled(&self) -> bool { self.config.settings.ui.borders.enabled }
+
+    #[instrument(name = "borders::handle_event", skip(self))]
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Update(update) => {
+                if update == self.last_update {
+                    return;
+                }
+                self.last_update = update;
+                self.redraw();
+            }
+            Event::ConfigUpdated(config) => {
+                self.config = config;
+                self.redraw();
+            }
+        }
+    }
+
+    fn redraw(&mut self) {
+        if !self.is_enabled() {
+            self.clear();
+            return;
+        }
+
+        let targets: Vec<(BorderTarget, Vec<(DecorationKind, BorderStyle)>)> = self
+            .last_update
+            .windows
+            .iter()
+            .map(|target| (target.clone(), self.decorations_for(target.window)))
+            .filter(|(_, decorations)| !decorations.is_empty())
+            .collect();
+
+        self.borders.retain(|(window, kind), border| {
+            let keep = targets.iter().any(|(target, decorations)| {
+                target.window == *window && decorations.iter().any(|(k, _)| k == kind)
+            });
+            if !keep && let Err(err) = border.hide() {
+                tracing::warn!(?err, "failed to hide window border");
+            }
+            keep
+        });
+
+        for (target, decorations) in targets {
+            // Rings nest outward from the window, highest z-order innermost; each is
+            // then ordered above the ring inside it so overlapping corners stay correct.
+            let mut inner_frame = target.frame;
+            let mut above = target.window_server_id.map(|id| id.as_u32());
+            for (kind, style) in decorations {
+                let frame = style.outer_frame(inner_frame);
+                inner_frame = frame;
+                let border = match self.borders.entry((target.window, kind)) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => match BorderWindow::new(frame) {
+                        Ok(border) => entry.insert(border),
+                        Err(err) => {
+                            tracing::warn!(?err, "failed to create window border");
+                            continue;
+                        }
+                    },
+                };
+                if let Err(err) = border.update(frame, style, above) {
+                    tracing::warn!(?err, window = ?target.window, ?kind, "failed to update window border");
+                }
+                above = Some(border.id());
+            }
+        }
+    }
+
+    /// Decorations for one window, highest z-order first.
+    fn decorations_for(&self, window: WindowId) -> Vec<(DecorationKind, BorderStyle)> {
+        let settings = &self.config.settings.ui.borders;
+        let active = BorderStyle::active(settings);
+        let mut decorations: Vec<(DecorationKind, BorderStyle)> = self
+            .last_update
+            .decorations
+            .iter()
+            .filter(|decoration| decoration.window == window)
+            .map(|decoration| {
+                let style = match decoration.color {
+                    Some(color) => BorderStyle { color, ..active },
+                    None => active,
+                };
+                (decoration.kind, style)
+            })
+            .collect();
+        if Some(window) == self.last_update.focused {
+            decorations.push((DecorationKind::Focused, active));
+        } else if let Some(inactive) = BorderStyle::inactive(settings) {
+            decorations.push((DecorationKind::Inactive, inactive));
+        }
+        decorations.sort_by(|a, b| b.0.cmp(&a.0));
+        decorations.dedup_by_key(|(kind, _)| *kind);
+        decorations
+    }
+
+    fn clear(&mut self) {
+        for border in self.borders.values() {
+            if let Err(err) = border.hide() {
+                tracing::warn!(?err, "failed to hide window border");
+            }
+        }
+        self.borders.clear();
+    }
+}
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
 use crate::actor::{self, reactor};
-use crate::common::config::{Config, ConfigCommand, MAX_WORKSPACES};
+use crate::common::config::{Config, ConfigCommand, GapAdjustment, MAX_WORKSPACES};
 
 pub type Sender = actor::Sender<Event>;
 pub type Receiver = actor::Receiver<Event>;
@@ -13,6 +13,10 @@ pub type Receiver = actor::Receiver<Event>;
 pub enum Event {
     #[serde(skip)]
     QueryConfig(r#continue::Sender<Config>),
+    /// Loads the config file as a reload would, answering with its
+    /// validation issues or the error it failed to load with.
+    #[serde(skip)]
+    CheckConfigFile(r#continue::Sender<Result<Vec<String>, String>>),
     #[serde(skip)]
     ApplyConfig {
         cmd: ConfigCommand,
@@ -25,6 +29,8 @@ pub struct ConfigActor {
     config: Config,
     reactor_tx: reactor::Sender,
     config_path: PathBuf,
+    /// Profile applied on top of the config file, kept across reloads.
+    profile: Option<String>,
 }
 
 impl ConfigActor {
@@ -45,6 +51,7 @@ impl ConfigActor {
                     config,
                     reactor_tx,
                     config_path,
+                    profile: None,
                 };
                 crate::sys::executor::Executor::run(actor.run(rx));
             })
@@ -59,6 +66,9 @@ impl ConfigActor {
                     let v = self.handle_config_query();
                     let _ = resp.send(v);
                 }
+                Event::CheckConfigFile(resp) => {
+                    let _ = resp.send(self.check_config_file());
+                }
                 Event::ApplyConfig { cmd, response } => {
                     let res = self.handle_config_command(cmd);
                     let _ = response.send(res);
@@ -69,11 +79,25 @@ impl ConfigActor {
 
     fn handle_config_query(&self) -> Config { self.config.clone() }
 
+    fn check_config_file(&self) -> Result<Vec<String>, String> {
+        if !self.config_path.exists() {
+            return Err(format!(
+                "{} does not exist; using the default config",
+                self.config_path.display()
+            ));
+        }
+        Config::read_with_profile(&self.config_path, self.profile.as_deref())
+            .map(|config| config.validate())
+            .map_err(|error| format!("{error:#}"))
+    }
+
     fn handle_config_command(&mut self, cmd: ConfigCommand) -> Result<(), String> {
         debug!("Applying config command: {:?}", cmd);
 
         let mut new_config = self.config.clone();
         let mut config_changed = false;
+        let mut switched_profile = None;
+        let mut save_after = false;
         let mut errors: Vec<String> = Vec::new();
 
         macro_rules! set_flag {
@@ -171,6 +195,38 @@ impl ConfigActor {
                     errors.push("Invalid inner gap values. All values must be >= 0.0".to_string());
                 }
             }
+            ConfigCommand::AdjustGaps {
+                scope,
+                delta,
+                display,
+                display_name,
+                persist,
+            } => {
+                let adjustment = GapAdjustment {
+                    scope,
+                    value: delta,
+                    absolute: false,
+                };
+                let gaps = &mut new_config.settings.layout.gaps;
+                gaps.adjust(adjustment, display.as_deref(), display_name.as_deref());
+                config_changed = true;
+                save_after = persist;
+                info!(?adjustment, ?display, "Adjusted gaps");
+            }
+            ConfigCommand::SetGaps {
+                scope,
+                value,
+                display,
+                display_name,
+                persist,
+            } => {
+                let adjustment = GapAdjustment { scope, value, absolute: true };
+                let gaps = &mut new_config.settings.layout.gaps;
+                gaps.adjust(adjustment, display.as_deref(), display_name.as_deref());
+                config_changed = true;
+                save_after = persist;
+                info!(?adjustment, ?display, "Set gaps");
+            }
             ConfigCommand::SetWorkspaceNames(names) => {
                 if names.len() <= MAX_WORKSPACES {
                     new_config.virtual_workspaces.workspace_names = names.clone();
@@ -232,7 +288,7 @@ impl ConfigActor {
             },
 
             ConfigCommand::GetConfig => {
-                let config_json = serde_json::to_string_pretty(&self.config)
+                let config_json = serde_json::to_string_pretty(&self.config.redacted())
                     .unwrap_or_else(|e| format!("Error serializing config: {}", e));
                 info!("Current config:\n{}", config_json);
                 return Ok(());
@@ -252,12 +308,27 @@ impl ConfigActor {
                 }
                 Err(e) => return Err(format!("Failed to reload config: {}", e)),
             },
+            ConfigCommand::SetProfile(profile) => {
+                match Config::read_with_profile(&self.config_path, profile.as_deref()) {
+                    Ok(cfg) => {
+                        info!("Loaded config profile {:?}", profile);
+                        config_changed = true;
+                        new_config = cfg;
+                        switched_profile = Some(profile);
+                    }
+                    Err(e) => return Err(format!("Failed to load profile: {}", e)),
+                }
+            }
         }
 
         if !errors.is_empty() {
             return Err(errors.join("; "));
         }
 
+        if self.config.settings.safe_mode {
+            new_config.apply_safe_mode();
+        }
+
         let validation_issues = new_config.validate();
         if !validation_issues.is_empty() {
             return Err(validation_issues.join("; "));
@@ -271,7 +342,19 @@ impl ConfigActor {
 
             self.config = new_config;
 
-            self.reactor_tx.send(reactor::Event::ConfigUpdated(self.config.clone()));
+            match switched_profile {
+                Some(profile) => {
+                    self.profile = profile;
+                    self.reactor_tx.send(reactor::Event::ProfileSwitched(self.config.clone()));
+                }
+                None => self.reactor_tx.send(reactor::Event::ConfigUpdated(self.config.clone())),
+            }
+        }
+
+        if save_after {
+            self.save_config_to_file()
+                .map_err(|e| format!("Failed to save config: {}", e))?;
+            info!("Config saved successfully");
         }
 
         Ok(())
@@ -279,7 +362,16 @@ impl ConfigActor {
 
     fn save_config_to_file(&self) -> Result<(), Box<dyn std::error::Error>> {
         let config_path = &self.config_path;
-        self.config.save(config_path)?;
+        let config = match &self.profile {
+            // Saving the merged config would bake the profile into the base
+            // file, so only the changes made since it was loaded are kept.
+            Some(profile) => {
+                let loaded = Config::read_with_profile(config_path, Some(profile.as_str()))?;
+                Config::read(config_path)?.with_changes(&loaded, &self.config)?
+            }
+            None => self.config.clone(),
+        };
+        config.save(config_path)?;
         Ok(())
     }
 
@@ -289,7 +381,10 @@ impl ConfigActor {
         let config_path = &self.config_path;
 
         if config_path.exists() {
-            let new_config = crate::common::config::Config::read(config_path)?;
+            let new_config = crate::common::config::Config::read_with_profile(
+                config_path,
+                self.profile.as_deref(),
+            )?;
             Ok(new_config)
         } else {
             Err("Config file not found".into())
@@ -158,6 +158,7 @@ impl ConfigWatcher {
             match msg {
                 ConfigEvent::ApplyConfig { response, .. } => std::mem::forget(response),
                 ConfigEvent::QueryConfig(response) => std::mem::forget(response),
+                ConfigEvent::CheckConfigFile(response) => std::mem::forget(response),
             }
             return Err("Config actor unavailable".to_string());
         }
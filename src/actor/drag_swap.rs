@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::time::{Duration, Instant};
 
 use objc2_core_foundation::{CGPoint, CGRect};
 
@@ -13,6 +14,10 @@ const OVERLAP_WEIGHT: f64 = 0.7;
 const CENTER_WEIGHT: f64 = 1.0 - OVERLAP_WEIGHT;
 // require only a modest improvement before switching to a new candidate.
 const SWITCH_DELTA: f64 = 0.04;
+// how close the cursor must be to a screen edge to count as holding a drag there.
+const EDGE_THRESHOLD: f64 = 3.0;
+// how close to a window border a mouse down must land to start a resize.
+const RESIZE_GRAB_MARGIN: f64 = 6.0;
 
 #[derive(Debug, Clone, Copy)]
 struct CandidateMetrics {
@@ -184,6 +189,165 @@ impl DragManager {
     }
 }
 
+/// A side of the screen a drag can be held against to change workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragEdge {
+    Left,
+    Right,
+}
+
+impl DragEdge {
+    /// The edge of `screen` the cursor is pressed against, if any.
+    pub fn at(cursor: CGPoint, screen: CGRect) -> Option<Self> {
+        if cursor.y < screen.origin.y || cursor.y > screen.origin.y + screen.size.height {
+            return None;
+        }
+        if cursor.x <= screen.origin.x + EDGE_THRESHOLD {
+            Some(DragEdge::Left)
+        } else if cursor.x >= screen.origin.x + screen.size.width - EDGE_THRESHOLD {
+            Some(DragEdge::Right)
+        } else {
+            None
+        }
+    }
+}
+
+/// Tracks how long a drag has been held against one screen edge.
+#[derive(Debug, Clone, Default)]
+pub struct EdgeDwell {
+    pending: Option<(DragEdge, Instant)>,
+}
+
+impl EdgeDwell {
+    /// Records the edge the cursor is at; reaching a different edge restarts
+    /// the wait and leaving the edges cancels it.
+    pub fn update(&mut self, edge: Option<DragEdge>, now: Instant, delay: Duration) {
+        self.pending = match (edge, self.pending) {
+            (Some(edge), Some((pending, deadline))) if edge == pending => Some((edge, deadline)),
+            (Some(edge), _) => Some((edge, now + delay)),
+            (None, _) => None,
+        };
+    }
+
+    pub fn deadline(&self) -> Option<Instant> { self.pending.map(|(_, deadline)| deadline) }
+
+    /// The edge whose wait has elapsed. The wait is re-armed so that holding
+    /// the drag there keeps stepping through workspaces.
+    pub fn take_due(&mut self, now: Instant, delay: Duration) -> Option<DragEdge> {
+        let (edge, deadline) = self.pending?;
+        if deadline > now {
+            return None;
+        }
+        self.pending = Some((edge, now + delay));
+        Some(edge)
+    }
+
+    pub fn reset(&mut self) { self.pending = None; }
+}
+
+/// The borders of a window a mouse resize is moving.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResizeEdges {
+    pub left: bool,
+    pub right: bool,
+    pub top: bool,
+    pub bottom: bool,
+}
+
+impl ResizeEdges {
+    /// The borders of `frame` a mouse down at `point` grabs, if any.
+    pub fn at(frame: CGRect, point: CGPoint) -> Option<Self> {
+        let max = frame.max();
+        let within =
+            |v: f64, lo: f64, hi: f64| v >= lo - RESIZE_GRAB_MARGIN && v <= hi + RESIZE_GRAB_MARGIN;
+        if !within(point.x, frame.origin.x, max.x) || !within(point.y, frame.origin.y, max.y) {
+            return None;
+        }
+        let near = |v: f64, edge: f64| (v - edge).abs() <= RESIZE_GRAB_MARGIN;
+        let edges = Self {
+            left: near(point.x, frame.origin.x),
+            right: near(point.x, max.x),
+            top: near(point.y, frame.origin.y),
+            bottom: near(point.y, max.y),
+        };
+        (edges != Self::default()).then_some(edges)
+    }
+}
+
+/// Where a dropped floating window snaps to: the edges of other floating
+/// windows first, otherwise a grid laid over the display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatingSnap {
+    /// The display area the grid starts from.
+    pub area: CGRect,
+    /// Grid cell size; 0 disables the grid.
+    pub grid: f64,
+    /// Maximum distance an edge moves to meet another window's edge.
+    pub distance: f64,
+}
+
+impl FloatingSnap {
+    /// Moves `frame` without resizing it so that it lines up with `others`
+    /// or the grid, each axis independently.
+    pub fn apply(&self, frame: CGRect, others: &[CGRect]) -> CGRect {
+        let max = frame.max();
+        let x = self.snap_axis(
+            frame.origin.x,
+            frame.size.width,
+            self.area.origin.x,
+            others
+                .iter()
+                .filter(|other| other.origin.y < max.y && other.max().y > frame.origin.y)
+                .flat_map(|other| [other.origin.x, other.max().x]),
+        );
+        let y = self.snap_axis(
+            frame.origin.y,
+            frame.size.height,
+            self.area.origin.y,
+            others
+                .iter()
+                .filter(|other| other.origin.x < max.x && other.max().x > frame.origin.x)
+                .flat_map(|other| [other.origin.y, other.max().y]),
+        );
+        CGRect::new(CGPoint::new(x, y), frame.size)
+    }
+
+    /// The new start of a span `[start, start + length]`, aligning either end
+    /// with the nearest edge within reach, or its start with the grid.
+    fn snap_axis(
+        &self,
+        start: f64,
+        length: f64,
+        grid_origin: f64,
+        edges: impl Iterator<Item = f64>,
+    ) -> f64 {
+        let mut best: Option<f64> = None;
+        for edge in edges {
+            for own in [start, start + length] {
+                let delta = edge - own;
+                if delta.abs() <= self.distance && best.is_none_or(|best| delta.abs() < best.abs())
+                {
+                    best = Some(delta);
+                }
+            }
+        }
+        match best {
+            Some(delta) => start + delta,
+            None if self.grid > 0.0 => {
+                grid_origin + ((start - grid_origin) / self.grid).round() * self.grid
+            }
+            None => start,
+        }
+    }
+}
+
+/// A tiled window being resized by its borders with the mouse.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InteractiveResize {
+    pub window: WindowId,
+    pub edges: ResizeEdges,
+}
+
 #[cfg(test)]
 mod tests {
     use objc2_core_foundation::{CGPoint, CGRect, CGSize};
@@ -200,7 +364,10 @@ mod tests {
 
     #[test]
     fn selects_candidate_based_on_scored_overlap() {
-        let mut dm = DragManager::new(WindowSnappingSettings { drag_swap_fraction: 0.3 });
+        let mut dm = DragManager::new(WindowSnappingSettings {
+            drag_swap_fraction: 0.3,
+            ..Default::default()
+        });
 
         let dragged = rect(0.0, 0.0, 100.0, 100.0);
         let wid = WindowId::new(1, 1);
@@ -214,7 +381,10 @@ mod tests {
 
     #[test]
     fn respects_last_target_to_avoid_repeats() {
-        let mut dm = DragManager::new(WindowSnappingSettings { drag_swap_fraction: 0.25 });
+        let mut dm = DragManager::new(WindowSnappingSettings {
+            drag_swap_fraction: 0.25,
+            ..Default::default()
+        });
         let wid = WindowId::new(1, 10);
         let dragged = rect(0.0, 0.0, 200.0, 100.0);
 
@@ -229,7 +399,10 @@ mod tests {
 
     #[test]
     fn clears_active_target_when_overlap_is_lost() {
-        let mut dm = DragManager::new(WindowSnappingSettings { drag_swap_fraction: 0.2 });
+        let mut dm = DragManager::new(WindowSnappingSettings {
+            drag_swap_fraction: 0.2,
+            ..Default::default()
+        });
         let wid = WindowId::new(1, 42);
         let dragged = rect(0.0, 0.0, 100.0, 100.0);
         let cand = (WindowId::new(1, 99), rect(0.0, 0.0, 60.0, 100.0));
@@ -246,7 +419,10 @@ mod tests {
 
     #[test]
     fn hysteresis_keeps_candidate_when_overlap_drops_slightly() {
-        let mut dm = DragManager::new(WindowSnappingSettings { drag_swap_fraction: 0.4 });
+        let mut dm = DragManager::new(WindowSnappingSettings {
+            drag_swap_fraction: 0.4,
+            ..Default::default()
+        });
         let wid = WindowId::new(5, 1);
         let dragged = rect(0.0, 0.0, 100.0, 100.0);
         let cand = (WindowId::new(5, 2), rect(0.0, 0.0, 50.0, 100.0)); // 50%
@@ -262,7 +438,10 @@ mod tests {
 
     #[test]
     fn switches_only_when_new_candidate_is_meaningfully_better() {
-        let mut dm = DragManager::new(WindowSnappingSettings { drag_swap_fraction: 0.3 });
+        let mut dm = DragManager::new(WindowSnappingSettings {
+            drag_swap_fraction: 0.3,
+            ..Default::default()
+        });
         let wid = WindowId::new(7, 1);
         let dragged = rect(0.0, 0.0, 120.0, 100.0);
 
@@ -284,4 +463,85 @@ mod tests {
         assert_eq!(switched, Some(WindowId::new(7, 2)));
         assert_eq!(dm.last_target(), Some(WindowId::new(7, 2)));
     }
+
+    #[test]
+    fn edge_is_detected_only_at_the_left_and_right_borders() {
+        let screen = rect(0.0, 25.0, 1440.0, 875.0);
+        assert_eq!(
+            DragEdge::at(CGPoint::new(0.0, 400.0), screen),
+            Some(DragEdge::Left)
+        );
+        assert_eq!(
+            DragEdge::at(CGPoint::new(1439.0, 400.0), screen),
+            Some(DragEdge::Right)
+        );
+        assert_eq!(DragEdge::at(CGPoint::new(720.0, 400.0), screen), None);
+        assert_eq!(DragEdge::at(CGPoint::new(0.0, 10.0), screen), None);
+    }
+
+    #[test]
+    fn edge_dwell_fires_after_the_delay_and_rearms() {
+        let delay = Duration::from_millis(300);
+        let start = Instant::now();
+        let mut dwell = EdgeDwell::default();
+
+        dwell.update(Some(DragEdge::Right), start, delay);
+        assert_eq!(dwell.take_due(start + Duration::from_millis(100), delay), None);
+        // Staying on the same edge does not restart the wait.
+        dwell.update(Some(DragEdge::Right), start + Duration::from_millis(200), delay);
+        let fired = start + Duration::from_millis(300);
+        assert_eq!(dwell.take_due(fired, delay), Some(DragEdge::Right));
+        assert_eq!(dwell.deadline(), Some(fired + delay));
+
+        dwell.update(None, fired, delay);
+        assert_eq!(dwell.deadline(), None);
+    }
+
+    #[test]
+    fn resize_edges_are_grabbed_only_near_the_border() {
+        let frame = rect(100.0, 100.0, 400.0, 300.0);
+        assert_eq!(
+            ResizeEdges::at(frame, CGPoint::new(502.0, 250.0)),
+            Some(ResizeEdges {
+                right: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            ResizeEdges::at(frame, CGPoint::new(98.0, 399.0)),
+            Some(ResizeEdges {
+                left: true,
+                bottom: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(ResizeEdges::at(frame, CGPoint::new(300.0, 250.0)), None);
+        assert_eq!(ResizeEdges::at(frame, CGPoint::new(520.0, 250.0)), None);
+    }
+
+    #[test]
+    fn floating_snap_prefers_neighbour_edges_over_the_grid() {
+        let snap = FloatingSnap {
+            area: rect(0.0, 25.0, 1440.0, 875.0),
+            grid: 40.0,
+            distance: 12.0,
+        };
+
+        // Nothing nearby: the origin lands on the grid, offset by the area.
+        let alone = snap.apply(rect(95.0, 130.0, 300.0, 200.0), &[]);
+        assert_eq!(alone.origin, CGPoint::new(80.0, 145.0));
+        assert_eq!(alone.size, CGSize::new(300.0, 200.0));
+
+        // The right edge meets a neighbour's left edge; y still uses the grid.
+        let neighbour = rect(500.0, 100.0, 300.0, 300.0);
+        let beside = snap.apply(rect(195.0, 130.0, 300.0, 200.0), &[neighbour]);
+        assert_eq!(beside.origin, CGPoint::new(200.0, 145.0));
+
+        // Windows that do not share a row do not attract horizontally.
+        let dragged = rect(198.0, 130.0, 300.0, 200.0);
+        let same_row = rect(510.0, 100.0, 300.0, 300.0);
+        let far_below = rect(510.0, 800.0, 300.0, 50.0);
+        assert_eq!(snap.apply(dragged, &[same_row]).origin.x, 210.0);
+        assert_eq!(snap.apply(dragged, &[far_below]).origin.x, 200.0);
+    }
 }
@@ -0,0 +1,104 @@
+use objc2::MainThreadMarker;
+use tracing::instrument;
+
+use crate::actor;
+use crate::common::config::Config;
+use crate::layout_engine::DropZone;
+use crate::sys::window_server::WindowServerId;
+use crate::ui::borders::{BorderStyle, BorderWindow};
+use crate::ui::stack_line::Color;
+
+/// The tile a dragged floating window would be dropped into.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropZonePreview {
+    pub zone: DropZone,
+    /// Window server id of the tile being split, to draw the overlay on it.
+    pub target_server_id: Option<WindowServerId>,
+}
+
+#[derive(Debug)]
+pub enum Event {
+    Update(Option<DropZonePreview>),
+    ConfigUpdated(Config),
+}
+
+pub type Sender = actor::Sender<Event>;
+pub type Receiver = actor::Receiver<Event>;
+
+pub struct DropZoneOverlay {
+    config: Config,
+    rx: Receiver,
+    #[allow(dead_code)]
+    mtm: MainThreadMarker,
+    overlay: Option<BorderWindow>,
+    last_update: Option<DropZonePreview>,
+}
+
+impl DropZoneOverlay {
+    pub fn new(config: Config, rx: Receiver, mtm: MainThreadMarker) -> Self {
+        Self {
+            config,
+            rx,
+            mtm,
+            overlay: None,
+            last_update: None,
+        }
+    }
+
+    pub async fn run(mut self) {
+        while let Some((span, event)) = self.rx.recv().await {
+            let _guard = span.enter();
+            self.handle_event(event);
+        }
+    }
+
+    #[instrument(name = "drop_zone_overlay::handle_event", skip(self))]
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Update(preview) => {
+                if preview == self.last_update {
+                    return;
+                }
+                self.last_update = preview;
+                self.redraw();
+            }
+            Event::ConfigUpdated(config) => {
+                self.config = config;
+                self.redraw();
+            }
+        }
+    }
+
+    fn color(&self) -> Color {
+        Color::from_hex(&self.config.settings.ui.borders.active_color).unwrap_or_else(Color::blue)
+    }
+
+    fn redraw(&mut self) {
+        let Some(preview) = &self.last_update else {
+            if let Some(overlay) = &self.overlay
+                && let Err(err) = overlay.hide()
+            {
+                tracing::warn!(?err, "failed to hide drop zone overlay");
+            }
+            return;
+        };
+        let frame = preview.zone.frame;
+        let above = preview.target_server_id.map(|id| id.as_u32());
+        let style = BorderStyle::insertion(self.color());
+        if self.overlay.is_none() {
+            match BorderWindow::new(frame) {
+                Ok(overlay) => self.overlay = Some(overlay),
+                Err(err) => {
+                    tracing::warn!(?err, "failed to create drop zone overlay");
+                    return;
+                }
+            }
+        }
+        let Some(overlay) = &mut self.overlay else {
+            return;
+        };
+        if let Err(err) = overlay.update(frame, style, above) {
+            tracing::warn!(?err, "failed to update drop zone overlay");
+        }
+    }
+}
@@ -19,6 +19,7 @@ use std::cell::{Cell, RefCell};
 use std::panic::AssertUnwindSafe;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use arc_swap::ArcSwap;
 use objc2_core_foundation::{CGPoint, CGRect};
@@ -41,6 +42,7 @@ use crate::sys::hotkey::{
     modifiers_from_flags_with_keys,
 };
 use crate::sys::screen::{CoordinateConverter, SpaceId};
+use crate::sys::timer::Timer;
 use crate::sys::window_server::WindowServerId;
 use crate::sys::{power, window_server};
 use crate::ui::stack_line::point_hits_indicator_frame;
@@ -54,6 +56,9 @@ pub enum Request {
     EnforceHidden,
     SpaceStateUpdated(ForwardedSpaceState, CoordinateConverter),
     SetEventProcessing(bool),
+    /// Rift is suspended: mouse events are ignored and every key except the
+    /// `toggle_suspended` binding reaches the focused app.
+    SetSuspended(bool),
     SetFocusFollowsMouseEnabled(bool),
     SetHotkeys(Vec<(String, WmCommand)>),
     KeyboardLayoutChanged,
@@ -72,6 +77,13 @@ pub struct EventTap {
     mouse_window: Cell<MouseWindow>,
     tap: RefCell<Option<crate::sys::event_tap::EventTap>>,
     disable_hotkey: RefCell<Option<Hotkey>>,
+    /// Focus only follows the mouse while this is held, when set.
+    focus_modifier: RefCell<Option<Hotkey>>,
+    focus_delay: Cell<Duration>,
+    /// Window the mouse came to rest over, focused once `hover_timer` fires.
+    pending_hover: Cell<Option<WindowServerId>>,
+    /// Created in `run` so that it fires on the input thread's run loop.
+    hover_timer: Option<Timer>,
     hotkey_specs: RefCell<Vec<(String, WmCommand)>>,
     hotkeys: SharedHotkeyTable,
     wm_sender: wm_controller::Sender,
@@ -93,6 +105,7 @@ struct State {
     converter: CoordinateConverter,
     screens: Vec<CGRect>,
     event_processing_enabled: bool,
+    suspended: bool,
     focus_follows_mouse_enabled: bool,
     stack_line_enabled: bool,
     stack_line_hover_mode: StackLineHoverMode,
@@ -122,6 +135,7 @@ impl Default for State {
             converter: CoordinateConverter::default(),
             screens: Vec::new(),
             event_processing_enabled: false,
+            suspended: false,
             focus_follows_mouse_enabled: true,
             stack_line_enabled: false,
             stack_line_hover_mode: StackLineHoverMode::default(),
@@ -158,13 +172,27 @@ impl EventTap {
         state.focus_follows_mouse_config_enabled && state.focus_follows_mouse_enabled
     }
 
+    /// Whether moving the mouse into a window may focus it right now.
+    fn focus_follows_mouse_allowed(&self, state: &State) -> bool {
+        state.focus_follows_mouse_config_enabled
+            && state.focus_follows_mouse_enabled
+            && !state.disable_hotkey_active
+            && self
+                .focus_modifier
+                .borrow()
+                .as_ref()
+                .is_none_or(|modifier| state.is_hotkey_held(modifier))
+    }
+
     fn keyboard_handlers_enabled(&self) -> bool {
-        self.disable_hotkey.borrow().is_some() || !self.hotkeys.load().is_empty()
+        self.disable_hotkey.borrow().is_some()
+            || self.focus_modifier.borrow().is_some()
+            || !self.hotkeys.load().is_empty()
     }
 
     fn mouse_move_handlers_enabled(&self) -> bool {
         let state = self.state.borrow();
-        state.event_processing_enabled
+        state.mouse_events_enabled()
             && (self.stack_line_hover_enabled(&state)
                 || Self::focus_follows_mouse_handler_enabled(&state))
     }
@@ -230,6 +258,11 @@ impl EventTap {
             .focus_follows_mouse_disable_hotkey
             .clone()
             .and_then(|spec| spec.to_hotkey());
+        let focus_modifier = config
+            .settings
+            .focus_follows_mouse_modifier
+            .clone()
+            .and_then(|spec| spec.to_hotkey());
         let mut state = State::default();
         state.mouse_hides_on_focus = config.settings.mouse_hides_on_focus;
         state.focus_follows_mouse_config_enabled = config.settings.focus_follows_mouse;
@@ -238,11 +271,11 @@ impl EventTap {
         state.default_layout_mode = config.settings.layout.mode;
         state.disable_hotkey_active = disable_hotkey
             .as_ref()
-            .map(|target| state.compute_disable_hotkey_active(target))
+            .map(|target| state.is_hotkey_held(target))
             .unwrap_or(false);
         let event_mask = build_event_mask(
-            disable_hotkey.is_some(),
-            state.event_processing_enabled
+            disable_hotkey.is_some() || focus_modifier.is_some(),
+            state.mouse_events_enabled()
                 && (state.stack_line_enabled || Self::focus_follows_mouse_handler_enabled(&state)),
         );
         let mouse_move_min_interval_ns = mouse_move_sampling_profile(state.low_power_mode);
@@ -256,6 +289,12 @@ impl EventTap {
             mouse_window: Cell::new(MouseWindow::default()),
             tap: RefCell::new(None),
             disable_hotkey: RefCell::new(disable_hotkey),
+            focus_modifier: RefCell::new(focus_modifier),
+            focus_delay: Cell::new(Duration::from_millis(
+                config.settings.focus_follows_mouse_delay_ms,
+            )),
+            pending_hover: Cell::new(None),
+            hover_timer: None,
             hotkey_specs: RefCell::new(Vec::new()),
             hotkeys: Arc::new(ArcSwap::from_pointee(HashMap::default())),
             wm_sender,
@@ -266,6 +305,7 @@ impl EventTap {
 
     pub async fn run(mut self) {
         let mut requests_rx = self.requests_rx.take().unwrap();
+        self.hover_timer = Some(Timer::manual());
 
         let this = Arc::new(self);
 
@@ -287,9 +327,16 @@ impl EventTap {
             }
         }
 
-        while let Some((span, request)) = requests_rx.recv().await {
-            let _guard = span.enter();
-            this.on_request(request);
+        let hover_timer = this.hover_timer.as_ref().unwrap();
+        loop {
+            tokio::select! {
+                request = requests_rx.recv() => {
+                    let Some((span, request)) = request else { break };
+                    let _guard = span.enter();
+                    this.on_request(request);
+                }
+                _ = hover_timer.fired() => this.flush_pending_hover(),
+            }
         }
     }
 
@@ -330,6 +377,15 @@ impl EventTap {
                 }
                 should_rebuild_mask = true;
             }
+            Request::SetSuspended(suspended) => {
+                state.suspended = suspended;
+                state.reset(!suspended);
+                if !suspended {
+                    self.reset_mouse_move_sample_gate();
+                    self.reset_mouse_window();
+                }
+                should_rebuild_mask = true;
+            }
             Request::SetFocusFollowsMouseEnabled(enabled) => {
                 debug!(
                     "focus_follows_mouse temporarily {}",
@@ -364,6 +420,15 @@ impl EventTap {
                     .clone()
                     .and_then(|spec| spec.to_hotkey());
                 *self.disable_hotkey.borrow_mut() = disable_hotkey;
+                *self.focus_modifier.borrow_mut() = new_config
+                    .settings
+                    .focus_follows_mouse_modifier
+                    .clone()
+                    .and_then(|spec| spec.to_hotkey());
+                self.focus_delay.set(Duration::from_millis(
+                    new_config.settings.focus_follows_mouse_delay_ms,
+                ));
+                self.pending_hover.set(None);
                 {
                     let prev_mouse_hides_on_focus = state.mouse_hides_on_focus;
                     let prev_focus_follows_mouse_config_enabled =
@@ -380,7 +445,7 @@ impl EventTap {
                         .disable_hotkey
                         .borrow()
                         .as_ref()
-                        .map(|target| state.compute_disable_hotkey_active(target))
+                        .map(|target| state.is_hotkey_held(target))
                         .unwrap_or(false);
                     if prev_active && !state.disable_hotkey_active {
                         state.reset(true);
@@ -438,7 +503,7 @@ impl EventTap {
             return;
         };
         let prev_active = state.disable_hotkey_active;
-        state.disable_hotkey_active = state.compute_disable_hotkey_active(&target);
+        state.disable_hotkey_active = state.is_hotkey_held(&target);
         if state.disable_hotkey_active != prev_active {
             if state.disable_hotkey_active {
                 debug!(?target, "focus_follows_mouse disabled while hotkey held");
@@ -455,7 +520,42 @@ impl EventTap {
     fn reset_mouse_move_sample_gate(&self) { self.mouse_move_last_timestamp.set(None); }
 
     #[inline]
-    fn reset_mouse_window(&self) { self.mouse_window.set(MouseWindow::default()); }
+    fn reset_mouse_window(&self) {
+        self.mouse_window.set(MouseWindow::default());
+        self.pending_hover.set(None);
+    }
+
+    /// Focus `window` now, or once the mouse has rested over it for
+    /// `focus_follows_mouse_delay_ms`.
+    fn hover_window(&self, window: WindowServerId) {
+        let delay = self.focus_delay.get();
+        if delay.is_zero() {
+            self.send_mouse_moved(window);
+            return;
+        }
+        if let Some(timer) = &self.hover_timer {
+            self.pending_hover.set(Some(window));
+            timer.set_next_fire(delay);
+        }
+    }
+
+    fn flush_pending_hover(&self) {
+        let Some(window) = self.pending_hover.take() else {
+            return;
+        };
+        let state = self.state.borrow();
+        if state.mouse_events_enabled()
+            && self.focus_follows_mouse_allowed(&state)
+            && self.mouse_window.get().resolved == Some(window)
+        {
+            self.send_mouse_moved(window);
+        }
+    }
+
+    fn send_mouse_moved(&self, window: WindowServerId) {
+        window_server::note_windowserver_activity(window.as_u32());
+        _ = self.events_tx.send(Event::MouseMoved(window));
+    }
 
     fn reconcile_after_tap_reenabled(&self) {
         let mut state = self.state.borrow_mut();
@@ -527,7 +627,7 @@ impl EventTap {
             return self.handle_keyboard_event(event_type, event, &mut state);
         }
 
-        if !state.event_processing_enabled {
+        if !state.mouse_events_enabled() {
             trace!("Mouse event processing disabled, ignoring {:?}", event_type);
             return true;
         }
@@ -537,6 +637,9 @@ impl EventTap {
             state.show_mouse();
         }
         match event_type {
+            CGEventType::LeftMouseDown => {
+                _ = self.events_tx.send(Event::MouseDown(CGEvent::location(Some(event))));
+            }
             CGEventType::RightMouseUp | CGEventType::LeftMouseUp => {
                 _ = self.events_tx.send(Event::MouseUp);
             }
@@ -554,7 +657,7 @@ impl EventTap {
     /// the sampled move path below is sufficient as a recovery check.
     fn on_mouse_moved(&self, event: &CGEvent) -> bool {
         let mut state = self.state.borrow_mut();
-        if !state.event_processing_enabled {
+        if !state.mouse_events_enabled() {
             return true;
         }
         if state.hide_count > 0 {
@@ -567,7 +670,7 @@ impl EventTap {
         // mouse event. Normal modifier transitions arrive through
         // FlagsChanged; this is only the defensive reconciliation path for
         // events lost while macOS UI interrupts the tap.
-        if self.disable_hotkey.borrow().is_some() {
+        if self.disable_hotkey.borrow().is_some() || self.focus_modifier.borrow().is_some() {
             let flags = CGEvent::flags(Some(event));
             if flags != state.current_flags {
                 state.current_flags = flags;
@@ -600,10 +703,7 @@ impl EventTap {
         // Resolve and deduplicate the window on the input thread. The reactor
         // only needs to see transitions; it must not receive a message for
         // every sampled point while the cursor remains in one window.
-        if state.focus_follows_mouse_config_enabled
-            && state.focus_follows_mouse_enabled
-            && !state.disable_hotkey_active
-        {
+        if self.focus_follows_mouse_allowed(&state) {
             let hint = mouse_window_hint(event);
             let previous = self.mouse_window.get();
             let window = Self::resolve_mouse_window(hint, loc, previous);
@@ -623,10 +723,14 @@ impl EventTap {
                 resolved: window,
                 valid: true,
             });
-            if let Some(window) = window {
-                window_server::note_windowserver_activity(window.as_u32());
-                _ = self.events_tx.send(Event::MouseMoved(window));
+            match window {
+                Some(window) => self.hover_window(window),
+                None => self.pending_hover.set(None),
             }
+        } else if self.mouse_window.get().valid {
+            // Forget the window under the mouse so it is picked up again as
+            // soon as the policy passes, e.g. when the modifier is pressed.
+            self.reset_mouse_window();
         }
 
         true
@@ -697,6 +801,11 @@ impl EventTap {
                 );
                 let bindings = self.hotkeys.load();
                 if let Some(commands) = bindings.get(&hotkey) {
+                    // While suspended, only the binding that resumes rift is
+                    // swallowed; everything else belongs to the focused app.
+                    if state.suspended && !commands.iter().any(is_toggle_suspended) {
+                        return true;
+                    }
                     // A held key generates repeated KeyDown events. Hotkeys
                     // are press-triggered, so dispatching those repeats can
                     // execute a command over and over. This is especially
@@ -710,6 +819,9 @@ impl EventTap {
                         return false;
                     }
                     for cmd in commands {
+                        if state.suspended && !is_toggle_suspended(cmd) {
+                            continue;
+                        }
                         self.wm_sender.send(WmEvent::Command(cmd.clone()));
                     }
                     return false;
@@ -855,7 +967,7 @@ impl State {
         self.current_flags = flags;
     }
 
-    fn compute_disable_hotkey_active(&self, target: &Hotkey) -> bool {
+    fn is_hotkey_held(&self, target: &Hotkey) -> bool {
         let active_mods = modifiers_from_flags_with_keys(self.current_flags, &self.pressed_keys);
 
         let check_modifier = |left: Modifiers, right: Modifiers| -> bool {
@@ -895,6 +1007,8 @@ impl State {
         }
     }
 
+    fn mouse_events_enabled(&self) -> bool { self.event_processing_enabled && !self.suspended }
+
     fn reset(&mut self, enabled: bool) {
         if enabled {
             self.reset_mouse_sampling();
@@ -905,6 +1019,15 @@ impl State {
     fn reset_mouse_sampling(&mut self) { self.last_stack_line_hit = None; }
 }
 
+fn is_toggle_suspended(command: &WmCommand) -> bool {
+    matches!(
+        command,
+        WmCommand::ReactorCommand(reactor::Command::Reactor(
+            reactor::ReactorCommand::ToggleSuspended
+        ))
+    )
+}
+
 #[inline]
 fn mouse_move_sampling_profile(low_power_mode: bool) -> u64 {
     if low_power_mode {
@@ -998,4 +1121,28 @@ mod tests {
         assert!(state.pressed_keys.is_empty());
         assert_eq!(state.current_flags, live_flags);
     }
+
+    #[test]
+    fn only_toggle_suspended_is_a_hotkey_while_suspended() {
+        let toggle = WmCommand::ReactorCommand(reactor::Command::Reactor(
+            reactor::ReactorCommand::ToggleSuspended,
+        ));
+        let other = WmCommand::ReactorCommand(reactor::Command::Reactor(
+            reactor::ReactorCommand::ToggleSpaceActivated,
+        ));
+        assert!(is_toggle_suspended(&toggle));
+        assert!(!is_toggle_suspended(&other));
+    }
+
+    #[test]
+    fn focus_modifier_is_held_only_with_the_modifier_down() {
+        let spec: crate::sys::hotkey::HotkeySpec = serde_json::from_str("\"Alt\"").unwrap();
+        let modifier = spec.to_hotkey().unwrap();
+        let mut state = State::default();
+        assert!(!state.is_hotkey_held(&modifier));
+
+        state.current_flags =
+            CGEventFlags::from_bits_retain(CGEventFlags::MaskAlternate.bits() | 0x20);
+        assert!(state.is_hotkey_held(&modifier));
+    }
 }
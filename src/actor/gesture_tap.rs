@@ -1,7 +1,8 @@
 //! Gesture handling via a dedicated CGEventTap.
 //!
 //! This actor runs on the main thread and handles trackpad swipe/scroll
-//! gestures for workspace switching.
+//! gestures for workspace switching, and two-finger panning of the scrolling
+//! layout while a modifier is held.
 
 use std::cell::RefCell;
 use std::panic::AssertUnwindSafe;
@@ -11,7 +12,7 @@ use objc2::exception;
 use objc2_app_kit::{NSEvent, NSEventPhase, NSEventType, NSTouchPhase, NSTouchType};
 use objc2_core_foundation::{CGPoint, CGRect};
 use objc2_core_graphics::{
-    CGEvent, CGEventField, CGEventMask, CGEventTapLocation as CGTapLoc,
+    CGEvent, CGEventField, CGEventFlags, CGEventMask, CGEventTapLocation as CGTapLoc,
     CGEventTapOptions as CGTapOpt, CGEventTapProxy, CGEventType,
 };
 use tracing::{trace, warn};
@@ -24,6 +25,7 @@ use crate::common::collections::HashMap;
 use crate::common::config::{Config, HapticPattern, LayoutMode};
 use crate::layout_engine::LayoutCommand as LC;
 use crate::sys::haptics;
+use crate::sys::hotkey::{Modifiers, modifiers_from_flags};
 use crate::sys::screen::SpaceId;
 
 const K_CGS_EVENT_TYPE_FIELD: CGEventField = CGEventField(55);
@@ -32,6 +34,18 @@ const K_GESTURE_HID_TYPE_FIELD: CGEventField = CGEventField(110);
 const K_GESTURE_SWIPE_MOTION_FIELD: CGEventField = CGEventField(123);
 const K_IOHID_EVENT_TYPE_DOCK_SWIPE: i64 = 23;
 const K_CG_GESTURE_MOTION_HORIZONTAL: i64 = 1;
+const K_CG_SCROLL_WHEEL_IS_CONTINUOUS: CGEventField = CGEventField(88);
+const K_CG_SCROLL_WHEEL_POINT_DELTA_Y: CGEventField = CGEventField(96);
+const K_CG_SCROLL_WHEEL_POINT_DELTA_X: CGEventField = CGEventField(97);
+const K_CG_SCROLL_WHEEL_SCROLL_PHASE: CGEventField = CGEventField(99);
+const K_CG_SCROLL_WHEEL_MOMENTUM_PHASE: CGEventField = CGEventField(123);
+const K_CG_SCROLL_PHASE_BEGAN: i64 = 1;
+const K_CG_SCROLL_PHASE_CHANGED: i64 = 2;
+const K_CG_SCROLL_PHASE_ENDED: i64 = 4;
+const K_CG_SCROLL_PHASE_CANCELLED: i64 = 8;
+const K_CG_MOMENTUM_PHASE_END: i64 = 3;
+/// Smallest change in swipe preview progress worth a new layout.
+const SWIPE_PREVIEW_STEP: f64 = 0.005;
 
 #[derive(Debug)]
 pub enum GestureRequest {
@@ -48,6 +62,7 @@ pub struct GestureTap {
     wm_sender: wm_controller::Sender,
     swipe: RefCell<Option<SwipeHandler>>,
     scroll: RefCell<Option<ScrollHandler>>,
+    pan: RefCell<Option<PanHandler>>,
     tap: RefCell<Option<crate::sys::event_tap::EventTap>>,
     screen_spaces: RefCell<Vec<(CGRect, SpaceId)>>,
     layout_mode_by_space: RefCell<HashMap<SpaceId, LayoutMode>>,
@@ -66,6 +81,7 @@ struct SwipeConfig {
     distance_pct: f64,
     haptics_enabled: bool,
     haptic_pattern: HapticPattern,
+    preview: bool,
 }
 
 impl SwipeConfig {
@@ -88,6 +104,7 @@ impl SwipeConfig {
             distance_pct: g.distance_pct.clamp(0.01, 1.0),
             haptics_enabled: g.haptics_enabled,
             haptic_pattern: g.haptic_pattern,
+            preview: g.preview,
         }
     }
 }
@@ -97,6 +114,11 @@ struct SwipeState {
     phase: GesturePhase,
     start_x: f64,
     start_y: f64,
+    /// Horizontal travel of a previewed swipe, positive towards the next
+    /// workspace.
+    preview_dx: f64,
+    /// Progress last sent to the reactor, so small moves are not re-sent.
+    preview_sent: f64,
 }
 
 impl SwipeState {
@@ -104,6 +126,8 @@ impl SwipeState {
         self.phase = GesturePhase::Idle;
         self.start_x = 0.0;
         self.start_y = 0.0;
+        self.preview_dx = 0.0;
+        self.preview_sent = 0.0;
     }
 }
 
@@ -177,6 +201,41 @@ struct ScrollHandler {
     state: RefCell<ScrollState>,
 }
 
+#[derive(Debug, Clone)]
+struct PanConfig {
+    modifier: Modifiers,
+    invert_horizontal: bool,
+    column_width_ratio: f64,
+}
+
+impl PanConfig {
+    fn from_config(config: &Config) -> Option<Self> {
+        let scrolling = &config.settings.layout.scrolling;
+        let modifier = scrolling.gestures.pan_modifier.as_ref()?.modifiers();
+        Some(PanConfig {
+            modifier,
+            invert_horizontal: scrolling.gestures.invert_horizontal,
+            column_width_ratio: scrolling.column_width_ratio,
+        })
+    }
+}
+
+#[derive(Default, Debug)]
+struct PanState {
+    /// Whether the current scroll (and its momentum) pans the strip.
+    active: bool,
+    /// Pixels per column, estimated from the screen the scroll started on.
+    step_px: f64,
+    /// Horizontal delta of the last finger movement, to tell whether
+    /// momentum events will follow the fingers lifting.
+    last_dx: f64,
+}
+
+struct PanHandler {
+    cfg: PanConfig,
+    state: RefCell<PanState>,
+}
+
 struct CallbackCtx {
     this: Rc<GestureTap>,
     consumes: bool,
@@ -189,12 +248,13 @@ unsafe fn drop_gesture_ctx(ptr: *mut std::ffi::c_void) {
 impl GestureTap {
     pub fn new(config: Config, wm_sender: wm_controller::Sender, requests_rx: Receiver) -> Self {
         let default_layout_mode = config.settings.layout.mode;
-        let (swipe, scroll) = Self::build_gesture_handlers(&config);
+        let (swipe, scroll, pan) = Self::build_gesture_handlers(&config);
         GestureTap {
             config: RefCell::new(config),
             wm_sender,
             swipe: RefCell::new(swipe),
             scroll: RefCell::new(scroll),
+            pan: RefCell::new(pan),
             tap: RefCell::new(None),
             screen_spaces: RefCell::new(Vec::new()),
             layout_mode_by_space: RefCell::new(HashMap::default()),
@@ -242,7 +302,9 @@ impl GestureTap {
         }
     }
 
-    fn build_gesture_handlers(config: &Config) -> (Option<SwipeHandler>, Option<ScrollHandler>) {
+    fn build_gesture_handlers(
+        config: &Config,
+    ) -> (Option<SwipeHandler>, Option<ScrollHandler>, Option<PanHandler>) {
         let swipe_cfg = SwipeConfig::from_config(config);
         let swipe = if swipe_cfg.enabled {
             Some(SwipeHandler {
@@ -263,18 +325,29 @@ impl GestureTap {
             None
         };
 
-        (swipe, scroll)
+        let pan = PanConfig::from_config(config).map(|cfg| PanHandler {
+            cfg,
+            state: RefCell::new(PanState::default()),
+        });
+
+        (swipe, scroll, pan)
     }
 
     fn update_gesture_handlers(self: &Rc<Self>) {
         let config = self.config.borrow();
-        let (swipe, scroll) = Self::build_gesture_handlers(&config);
+        let (swipe, scroll, pan) = Self::build_gesture_handlers(&config);
         let was_enabled = self.gesture_handlers_enabled();
+        let had_pan = self.pan.borrow().is_some();
         *self.swipe.borrow_mut() = swipe;
         *self.scroll.borrow_mut() = scroll;
+        *self.pan.borrow_mut() = pan;
         let is_enabled = self.gesture_handlers_enabled();
+        let has_pan = self.pan.borrow().is_some();
 
-        if !was_enabled && is_enabled {
+        if is_enabled && (!was_enabled || had_pan != has_pan) {
+            // The tap only listens for scroll wheel events while panning is
+            // configured, so a change there needs a new tap.
+            *self.tap.borrow_mut() = None;
             self.create_and_install_tap();
         } else if was_enabled && !is_enabled {
             *self.tap.borrow_mut() = None;
@@ -282,11 +355,13 @@ impl GestureTap {
     }
 
     fn gesture_handlers_enabled(&self) -> bool {
-        self.swipe.borrow().is_some() || self.scroll.borrow().is_some()
+        self.swipe.borrow().is_some()
+            || self.scroll.borrow().is_some()
+            || self.pan.borrow().is_some()
     }
 
     fn create_and_install_tap(self: &Rc<Self>) {
-        let mask = gesture_event_mask();
+        let mask = gesture_event_mask(self.pan.borrow().is_some());
         let tap_location = CGTapLoc::HIDEventTap;
         let tap = unsafe {
             let ctx_ptr = Box::into_raw(Box::new(CallbackCtx {
@@ -338,6 +413,13 @@ impl GestureTap {
     }
 
     fn on_event(self: &Rc<Self>, event_type: CGEventType, event: &CGEvent) -> bool {
+        if event_type.0 == CGEventType::ScrollWheel.0 {
+            return match self.pan.borrow().as_ref() {
+                Some(handler) => self.handle_pan_scroll_event(handler, event),
+                None => true,
+            };
+        }
+
         let scroll_handler = self.scroll.borrow();
         let swipe_handler = self.swipe.borrow();
         if scroll_handler.is_none() && swipe_handler.is_none() {
@@ -389,10 +471,9 @@ impl GestureTap {
         }
     }
 
-    fn layout_mode_at_point(&self, loc: CGPoint) -> Option<LayoutMode> {
-        let screen_spaces = self.screen_spaces.borrow();
-        let layout_modes = self.layout_mode_by_space.borrow();
-        screen_spaces
+    fn screen_at_point(&self, loc: CGPoint) -> Option<(CGRect, SpaceId)> {
+        self.screen_spaces
+            .borrow()
             .iter()
             .find(|(frame, _)| {
                 loc.x >= frame.origin.x
@@ -400,7 +481,70 @@ impl GestureTap {
                     && loc.y >= frame.origin.y
                     && loc.y < frame.origin.y + frame.size.height
             })
-            .and_then(|(_, space)| layout_modes.get(space).copied())
+            .copied()
+    }
+
+    fn layout_mode_at_point(&self, loc: CGPoint) -> Option<LayoutMode> {
+        let (_, space) = self.screen_at_point(loc)?;
+        self.layout_mode_by_space.borrow().get(&space).copied()
+    }
+
+    /// Pans the scrolling layout with a two-finger trackpad scroll that began
+    /// with the pan modifier held, following its momentum and snapping to a
+    /// column once it comes to rest. Returns false to swallow the event.
+    fn handle_pan_scroll_event(&self, handler: &PanHandler, event: &CGEvent) -> bool {
+        let field = |f| CGEvent::integer_value_field(Some(event), f);
+        if field(K_CG_SCROLL_WHEEL_IS_CONTINUOUS) == 0 {
+            return true;
+        }
+        let phase = field(K_CG_SCROLL_WHEEL_SCROLL_PHASE);
+        let momentum = field(K_CG_SCROLL_WHEEL_MOMENTUM_PHASE);
+        let mut st = handler.state.borrow_mut();
+
+        if phase == K_CG_SCROLL_PHASE_BEGAN {
+            let cursor = CGEvent::location(Some(event));
+            let mode =
+                self.layout_mode_at_point(cursor).unwrap_or(*self.default_layout_mode.borrow());
+            let screen_width =
+                self.screen_at_point(cursor).map_or(0.0, |(frame, _)| frame.size.width);
+            st.active = matches!(mode, LayoutMode::Scrolling)
+                && modifier_held(handler.cfg.modifier, CGEvent::flags(Some(event)));
+            st.step_px = screen_width * handler.cfg.column_width_ratio;
+            st.last_dx = 0.0;
+        }
+        if !st.active {
+            return true;
+        }
+
+        let dx = field(K_CG_SCROLL_WHEEL_POINT_DELTA_X) as f64;
+        let dy = field(K_CG_SCROLL_WHEEL_POINT_DELTA_Y) as f64;
+        if dx.abs() > dy.abs() && st.step_px > 0.0 {
+            let distance = if handler.cfg.invert_horizontal {
+                dx
+            } else {
+                -dx
+            };
+            let delta = distance / st.step_px;
+            self.wm_sender.send(WmEvent::Command(WmCommand::ReactorCommand(
+                reactor::Command::Layout(LC::ScrollStrip { delta }),
+            )));
+        }
+        if phase == K_CG_SCROLL_PHASE_CHANGED {
+            st.last_dx = dx;
+        }
+
+        // Fingers that stop before lifting produce no momentum, so the scroll
+        // is over when they lift; otherwise wait for the momentum to end.
+        let finished = phase == K_CG_SCROLL_PHASE_CANCELLED
+            || momentum == K_CG_MOMENTUM_PHASE_END
+            || (phase == K_CG_SCROLL_PHASE_ENDED && st.last_dx.abs() < 1.0);
+        if finished {
+            st.active = false;
+            self.wm_sender.send(WmEvent::Command(WmCommand::ReactorCommand(
+                reactor::Command::Layout(LC::SnapStrip),
+            )));
+        }
+        false
     }
 
     fn handle_gesture_event(&self, handler: &SwipeHandler, nsevent: &NSEvent) {
@@ -411,10 +555,12 @@ impl GestureTap {
 
         let phase = nsevent.phase();
         if matches!(phase, NSEventPhase::Ended | NSEventPhase::Cancelled) {
+            self.end_swipe_preview(cfg, &mut st, matches!(phase, NSEventPhase::Ended));
             st.reset();
             return;
         }
         if matches!(phase, NSEventPhase::Began) {
+            self.end_swipe_preview(cfg, &mut st, false);
             st.reset();
         }
 
@@ -444,6 +590,9 @@ impl GestureTap {
         }
 
         if too_many_touches || touch_count != cfg.fingers || active_count == 0 {
+            // Lifting the fingers releases a previewed swipe, while an extra
+            // finger cancels it.
+            self.end_swipe_preview(cfg, &mut st, !too_many_touches && active_count == 0);
             st.reset();
             return;
         }
@@ -467,23 +616,25 @@ impl GestureTap {
                 let horizontal = dx.abs();
                 let vertical = dy.abs();
 
-                if horizontal >= cfg.distance_pct && vertical <= cfg.vertical_tolerance {
+                if cfg.preview {
+                    // The workspace follows the fingers and the swipe is
+                    // decided when they lift.
+                    let towards_next = if cfg.invert_horizontal { dx } else { -dx };
+                    st.preview_dx = if vertical <= cfg.vertical_tolerance {
+                        towards_next
+                    } else {
+                        0.0
+                    };
+                    if (st.preview_dx - st.preview_sent).abs() >= SWIPE_PREVIEW_STEP {
+                        st.preview_sent = st.preview_dx;
+                        self.send_swipe_preview(cfg, st.preview_dx);
+                    }
+                } else if horizontal >= cfg.distance_pct && vertical <= cfg.vertical_tolerance {
                     let mut dir_left = dx < 0.0;
                     if cfg.invert_horizontal {
                         dir_left = !dir_left;
                     }
-                    let cmd = if dir_left {
-                        LC::NextWorkspace(cfg.skip_empty_workspaces)
-                    } else {
-                        LC::PrevWorkspace(cfg.skip_empty_workspaces)
-                    };
-
-                    if cfg.haptics_enabled {
-                        let _ = haptics::perform_haptic(cfg.haptic_pattern);
-                    }
-                    self.wm_sender.send(WmEvent::Command(WmCommand::ReactorCommand(
-                        reactor::Command::Layout(cmd),
-                    )));
+                    self.commit_swipe(cfg, dir_left);
                     st.phase = GesturePhase::Committed;
                 }
             }
@@ -495,6 +646,42 @@ impl GestureTap {
         }
     }
 
+    /// Switches to the next (`forward`) or previous workspace.
+    fn commit_swipe(&self, cfg: &SwipeConfig, forward: bool) {
+        let cmd = if forward {
+            LC::NextWorkspace(cfg.skip_empty_workspaces)
+        } else {
+            LC::PrevWorkspace(cfg.skip_empty_workspaces)
+        };
+        if cfg.haptics_enabled {
+            let _ = haptics::perform_haptic(cfg.haptic_pattern);
+        }
+        self.wm_sender.send(WmEvent::Command(WmCommand::ReactorCommand(
+            reactor::Command::Layout(cmd),
+        )));
+    }
+
+    fn send_swipe_preview(&self, cfg: &SwipeConfig, progress: f64) {
+        self.wm_sender.send(WmEvent::WorkspaceSwipePreview {
+            progress,
+            skip_empty: cfg.skip_empty_workspaces,
+        });
+    }
+
+    /// Ends a previewed swipe. Releasing it past `distance_pct` switches
+    /// workspaces, and the slide carries on from where the preview left the
+    /// windows; otherwise the workspace is put back.
+    fn end_swipe_preview(&self, cfg: &SwipeConfig, st: &mut SwipeState, released: bool) {
+        if !cfg.preview || st.phase != GesturePhase::Armed {
+            return;
+        }
+        if released && st.preview_dx.abs() >= cfg.distance_pct {
+            self.commit_swipe(cfg, st.preview_dx > 0.0);
+        } else if st.preview_sent != 0.0 {
+            self.send_swipe_preview(cfg, 0.0);
+        }
+    }
+
     fn handle_scroll_gesture_event(&self, handler: &ScrollHandler, nsevent: &NSEvent) {
         let cfg = &handler.cfg;
         let state = &handler.state;
@@ -639,8 +826,26 @@ impl GestureTap {
     }
 }
 
-fn gesture_event_mask() -> CGEventMask {
-    (1u64 << (NSEventType::Gesture.0 as u64)) | (1u64 << (K_CGS_EVENT_DOCK_CONTROL as u64))
+fn gesture_event_mask(pan: bool) -> CGEventMask {
+    let mut mask =
+        (1u64 << (NSEventType::Gesture.0 as u64)) | (1u64 << (K_CGS_EVENT_DOCK_CONTROL as u64));
+    if pan {
+        mask |= 1u64 << (CGEventType::ScrollWheel.0 as u64);
+    }
+    mask
+}
+
+/// Whether every modifier family in `required` is down in `flags`.
+pub(crate) fn modifier_held(required: Modifiers, flags: CGEventFlags) -> bool {
+    let active = modifiers_from_flags(flags);
+    [
+        Modifiers::SHIFT,
+        Modifiers::CONTROL,
+        Modifiers::ALT,
+        Modifiers::META,
+    ]
+    .into_iter()
+    .all(|family| !required.intersects(family) || active.intersects(family))
 }
 
 fn is_physical_horizontal_dock_swipe(event_type: CGEventType, event: &CGEvent) -> bool {
@@ -688,3 +893,51 @@ unsafe extern "C-unwind" fn gesture_callback(
         Err(_) => event_ref.as_ptr(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::hotkey::HotkeySpec;
+
+    fn pan_config(modifier: &str) -> Option<PanConfig> {
+        let mut config = Config::default();
+        config.settings.layout.scrolling.gestures.pan_modifier =
+            Some(serde_json::from_value::<HotkeySpec>(modifier.into()).unwrap());
+        PanConfig::from_config(&config)
+    }
+
+    fn alt_control() -> Modifiers {
+        let mut modifiers = Modifiers::ALT;
+        modifiers.insert(Modifiers::CONTROL);
+        modifiers
+    }
+
+    #[test]
+    fn pan_modifier_accepts_modifier_combinations_without_a_key() {
+        let cfg = pan_config("Alt + Ctrl").expect("pan enabled");
+        assert_eq!(cfg.modifier, alt_control());
+        assert!(pan_config("Alt").is_some());
+    }
+
+    #[test]
+    fn pan_is_disabled_without_a_modifier() {
+        assert!(PanConfig::from_config(&Config::default()).is_none());
+    }
+
+    #[test]
+    fn modifier_held_requires_every_family() {
+        let alt = CGEventFlags::MaskAlternate;
+        let alt_ctrl = CGEventFlags::MaskAlternate | CGEventFlags::MaskControl;
+
+        assert!(modifier_held(Modifiers::ALT, alt));
+        assert!(modifier_held(Modifiers::ALT, alt_ctrl));
+        assert!(!modifier_held(alt_control(), alt));
+        assert!(modifier_held(alt_control(), alt_ctrl));
+        assert!(!modifier_held(Modifiers::META, alt_ctrl));
+    }
+
+    #[test]
+    fn modifier_held_accepts_either_side_for_a_sided_modifier() {
+        assert!(modifier_held(Modifiers::ALT_RIGHT, CGEventFlags::MaskAlternate));
+    }
+}
@@ -0,0 +1,149 @@
+use objc2::MainThreadMarker;
+use objc2_core_foundation::CGRect;
+use tracing::instrument;
+
+use crate::actor;
+use crate::actor::app::WindowId;
+use crate::common::collections::HashMap;
+use crate::common::collections::hash_map::Entry;
+use crate::common::config::Config;
+use crate::sys::window_server::WindowServerId;
+use crate::ui::hint_overlay::{HintWindow, badge_frame};
+use crate::ui::stack_line::Color;
+
+/// Keys handed out as hints, home row first.
+pub const HINT_KEYS: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+/// A window that can be picked by typing `key`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub key: char,
+    pub window_server_id: Option<WindowServerId>,
+    pub frame: CGRect,
+}
+
+#[derive(Debug)]
+pub enum Event {
+    Update(Vec<Hint>),
+    ConfigUpdated(Config),
+}
+
+pub type Sender = actor::Sender<Event>;
+pub type Receiver = actor::Receiver<Event>;
+
+/// Gives each window a key from [`HINT_KEYS`], left to right and then top to
+/// bottom, so the home row lands on the leftmost windows. Windows beyond the
+/// available keys get no hint.
+pub fn assign_hints(windows: &[(WindowId, CGRect)]) -> Vec<(char, WindowId)> {
+    let mut windows = windows.to_vec();
+    windows.sort_by(|(_, a), (_, b)| {
+        a.origin.x.total_cmp(&b.origin.x).then(a.origin.y.total_cmp(&b.origin.y))
+    });
+    HINT_KEYS.chars().zip(windows.into_iter().map(|(wid, _)| wid)).collect()
+}
+
+pub struct HintOverlay {
+    config: Config,
+    rx: Receiver,
+    #[allow(dead_code)]
+    mtm: MainThreadMarker,
+    overlays: HashMap<char, HintWindow>,
+    last_update: Vec<Hint>,
+}
+
+impl HintOverlay {
+    pub fn new(config: Config, rx: Receiver, mtm: MainThreadMarker) -> Self {
+        Self {
+            config,
+            rx,
+            mtm,
+            overlays: HashMap::default(),
+            last_update: Vec::new(),
+        }
+    }
+
+    pub async fn run(mut self) {
+        while let Some((span, event)) = self.rx.recv().await {
+            let _guard = span.enter();
+            self.handle_event(event);
+        }
+    }
+
+    #[instrument(name = "hint_overlay::handle_event", skip(self))]
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Update(hints) => {
+                if hints == self.last_update {
+                    return;
+                }
+                self.last_update = hints;
+                self.redraw();
+            }
+            Event::ConfigUpdated(config) => {
+                self.config = config;
+                self.redraw();
+            }
+        }
+    }
+
+    fn color(&self) -> Color {
+        Color::from_hex(&self.config.settings.ui.borders.active_color).unwrap_or_else(Color::blue)
+    }
+
+    fn redraw(&mut self) {
+        let color = self.color();
+        let hints = &self.last_update;
+
+        self.overlays.retain(|key, overlay| {
+            let keep = hints.iter().any(|hint| hint.key == *key);
+            if !keep && let Err(err) = overlay.hide() {
+                tracing::warn!(?err, "failed to hide hint overlay");
+            }
+            keep
+        });
+
+        for hint in hints {
+            let frame = badge_frame(hint.frame);
+            let overlay = match self.overlays.entry(hint.key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => match HintWindow::new(frame) {
+                    Ok(overlay) => entry.insert(overlay),
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to create hint overlay");
+                        continue;
+                    }
+                },
+            };
+            let above = hint.window_server_id.map(|id| id.as_u32());
+            if let Err(err) = overlay.update(frame, hint.key, color, above) {
+                tracing::warn!(?err, key = ?hint.key, "failed to update hint overlay");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2_core_foundation::{CGPoint, CGSize};
+
+    use super::*;
+
+    #[test]
+    fn hints_follow_window_positions() {
+        let rect = |x: f64, y: f64| CGRect::new(CGPoint::new(x, y), CGSize::new(100.0, 100.0));
+        let (a, b, c) = (WindowId::new(1, 1), WindowId::new(1, 2), WindowId::new(1, 3));
+
+        assert_eq!(
+            assign_hints(&[
+                (a, rect(500.0, 0.0)),
+                (b, rect(0.0, 300.0)),
+                (c, rect(0.0, 0.0))
+            ]),
+            vec![('a', c), ('s', b), ('d', a)]
+        );
+
+        let many: Vec<_> =
+            (1..=30).map(|idx| (WindowId::new(1, idx), rect(f64::from(idx), 0.0))).collect();
+        assert_eq!(assign_hints(&many).len(), HINT_KEYS.len());
+    }
+}
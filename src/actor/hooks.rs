@@ -0,0 +1,80 @@
+use tracing::{debug, instrument, warn};
+
+use crate::actor;
+use crate::actor::reactor::ReactorQueryHandle;
+use crate::common::config::Config;
+use crate::ipc::cli_exec::{CliExecutor, DefaultCliExecutor};
+use crate::ipc::subscriptions::CliSubscription;
+use crate::model::broadcast::BroadcastEvent;
+
+#[derive(Debug)]
+pub enum Event {
+    Broadcast(BroadcastEvent),
+    ConfigUpdated(Config),
+}
+
+pub type Sender = actor::Sender<Event>;
+pub type Receiver = actor::Receiver<Event>;
+
+/// Runs the `settings.hooks` commands for broadcast events. Hooks reuse the CLI
+/// subscription executor, so they see the same `RIFT_*` environment as a
+/// `rift-cli subscribe cli` command.
+///
+/// A hook with a `match` expression looks the event's window up through a
+/// blocking reactor query, so this actor runs on a thread of its own.
+pub struct Hooks {
+    config: Config,
+    /// IPC settings are read once at startup, so hooks keep the token the
+    /// server checks even if a reload changes it.
+    spawn_env: Vec<(String, String)>,
+    rx: Receiver,
+    queries: ReactorQueryHandle,
+}
+
+impl Hooks {
+    pub fn new(config: Config, rx: Receiver, queries: ReactorQueryHandle) -> Self {
+        Self {
+            spawn_env: config.settings.ipc.spawn_env(),
+            config,
+            rx,
+            queries,
+        }
+    }
+
+    pub async fn run(mut self) {
+        while let Some((span, event)) = self.rx.recv().await {
+            let _guard = span.enter();
+            self.handle_event(event);
+        }
+    }
+
+    #[instrument(name = "hooks::handle_event", skip(self))]
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Broadcast(event) => self.run_hook(&event),
+            Event::ConfigUpdated(config) => self.config = config,
+        }
+    }
+
+    fn run_hook(&self, event: &BroadcastEvent) {
+        let Some(hook) = self.config.settings.hooks.hook_for(event.name()) else {
+            return;
+        };
+        if let Some(matcher) = hook.matcher() {
+            let window = event.window_id().and_then(|wid| self.queries.query_window_info(wid));
+            if !window.is_some_and(|window| matcher.matches(window.rule_context())) {
+                return;
+            }
+        }
+        let command = hook.command();
+        debug!(event = event.name(), command, "running hook");
+        let subscription = CliSubscription {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), command.to_string()],
+        };
+        let executor = DefaultCliExecutor::with_env(self.spawn_env.clone());
+        if let Err(error) = executor.execute(event, &subscription) {
+            warn!(event = event.name(), command, %error, "failed to spawn hook");
+        }
+    }
+}
@@ -7,7 +7,7 @@ use objc2::MainThreadMarker;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::actor::{config, reactor};
-use crate::common::config::{Config, ConfigCommand};
+use crate::common::config::{Config, ConfigCommand, WorkspaceSelector};
 use crate::layout_engine::LayoutCommand;
 use crate::model::VirtualWorkspaceId;
 use crate::model::server::{WindowData, WorkspaceData};
@@ -203,6 +203,22 @@ impl Menu {
             MenuAction::SwitchToWorkspace(workspace) => {
                 self.send_layout_command(LayoutCommand::SwitchToWorkspace(workspace));
             }
+            MenuAction::ToggleWindowFloating => {
+                self.send_layout_command(LayoutCommand::ToggleWindowFloating);
+            }
+            MenuAction::ToggleStack => self.send_layout_command(LayoutCommand::ToggleStack),
+            MenuAction::MoveWindowToWorkspace { window, workspace } => {
+                self.send_layout_command(LayoutCommand::MoveWindowToWorkspace {
+                    workspace: WorkspaceSelector::Index(workspace),
+                    follow: false,
+                    window_id: Some(window.idx.get()),
+                });
+            }
+            MenuAction::CloseWindow(window_server_id) => {
+                self.reactor_tx.send(reactor::Event::Command(reactor::Command::Reactor(
+                    reactor::ReactorCommand::CloseWindow { window_server_id },
+                )));
+            }
             MenuAction::RestoreLayout { path, scope, source } => {
                 self.reactor_tx.send(reactor::Event::Command(reactor::Command::Reactor(
                     reactor::ReactorCommand::RestoreLayout { path, scope, source },
@@ -288,6 +304,7 @@ impl Menu {
             match msg {
                 config::Event::ApplyConfig { response, .. } => std::mem::forget(response),
                 config::Event::QueryConfig(response) => std::mem::forget(response),
+                config::Event::CheckConfigFile(response) => std::mem::forget(response),
             }
         }
     }
@@ -386,11 +403,18 @@ fn workspace_sig(ws: &WorkspaceData) -> u64 {
 
 #[inline(always)]
 fn window_sig(w: &WindowData) -> u64 {
-    (w.id.idx.get() as u64)
+    let mut x = (w.id.idx.get() as u64)
         ^ w.info.frame.origin.x.to_bits().rotate_left(11)
         ^ w.info.frame.origin.y.to_bits().rotate_left(23)
         ^ w.info.frame.size.width.to_bits().rotate_left(37)
-        ^ w.info.frame.size.height.to_bits().rotate_left(51)
+        ^ w.info.frame.size.height.to_bits().rotate_left(51);
+    // The window submenu reflects the focused window's title and float state.
+    if w.is_focused {
+        x ^= 0x2545_F491_4F6C_DD1Du64
+            ^ hash_str(&w.info.title).rotate_left(7)
+            ^ (w.is_floating as u64).rotate_left(31);
+    }
+    x
 }
 
 #[inline(always)]
@@ -417,6 +441,8 @@ mod tests {
             is_active: true,
             window_count: 1,
             windows: Vec::new(),
+            display_uuid: None,
+            display_name: None,
         }
     }
 
@@ -136,7 +136,11 @@ impl MissionControlActor {
             }
             MissionControlAction::FocusWindow { window_id, window_server_id } => {
                 let _ = self.reactor.try_send(reactor::Event::Command(reactor::Command::Reactor(
-                    reactor::ReactorCommand::FocusWindow { window_id, window_server_id },
+                    reactor::ReactorCommand::FocusWindow {
+                        window_id,
+                        window_server_id,
+                        switch_workspace: true,
+                    },
                 )));
                 self.dispose_overlay();
             }
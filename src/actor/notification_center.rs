@@ -22,12 +22,14 @@ use crate::sys::app::NSRunningApplicationExt;
 use crate::sys::dispatch::DispatchExt;
 use crate::sys::power::{init_power_state, set_low_power_mode_state};
 use crate::sys::skylight::{CGDisplayRegisterReconfigurationCallback, DisplayReconfigFlags};
+use crate::sys::stage_manager::is_stage_manager_enabled;
 
 #[repr(C)]
 struct Instance {
     events_tx: wm_controller::Sender,
     spaces_tx: spaces::Sender,
     session_inactive_hint: Cell<bool>,
+    stage_manager_enabled: Cell<bool>,
 }
 
 unsafe impl Encode for Instance {
@@ -66,6 +68,7 @@ define_class! {
         fn recv_wake_event(&self, notif: &NSNotification) {
             trace!("{notif:#?}");
             self.send_space_event(spaces::Event::SystemDidWake);
+            self.check_stage_manager();
         }
 
         #[unsafe(method(recvSleepEvent:))]
@@ -112,6 +115,7 @@ impl NotificationCenterInner {
             events_tx,
             spaces_tx,
             session_inactive_hint: Cell::new(false),
+            stage_manager_enabled: Cell::new(false),
         };
         let handler: Retained<Self> = unsafe { msg_send![Self::alloc(), initWith: instance] };
         unsafe {
@@ -265,6 +269,18 @@ impl NotificationCenterInner {
     fn handle_dock_pref_changed(&self) {
         trace!("Dock preferences changed; scheduling refresh");
         self.send_space_event(spaces::Event::ScreenRefreshRequested);
+        self.check_stage_manager();
+    }
+
+    /// Stage Manager has no notification of its own; toggling it posts the
+    /// Dock's preference change, so the preference is only re-read then (and
+    /// on wake), never on the hot space-change path.
+    fn check_stage_manager(&self) {
+        let enabled = is_stage_manager_enabled();
+        if self.ivars().stage_manager_enabled.replace(enabled) != enabled {
+            debug!(enabled, "Stage Manager state changed");
+            self.send_event(WmEvent::StageManagerChanged(enabled));
+        }
     }
 
     fn handle_menu_bar_pref_changed(&self) {
@@ -444,6 +460,7 @@ impl NotificationCenter {
 
         self.inner.send_space_event(spaces::Event::ScreenRefreshRequested);
         self.inner.send_event(WmEvent::AppEventsRegistered);
+        self.inner.check_stage_manager();
         if let Some(app) = workspace.frontmostApplication() {
             if app.bundle_id().as_deref().map(ToString::to_string).as_deref()
                 == Some("com.apple.loginwindow")
@@ -0,0 +1,186 @@
+use objc2::MainThreadMarker;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use tracing::instrument;
+
+use crate::actor;
+use crate::actor::app::WindowId;
+use crate::common::collections::HashMap;
+use crate::common::collections::hash_map::Entry;
+use crate::common::config::Config;
+use crate::layout_engine::Direction;
+use crate::sys::window_server::WindowServerId;
+use crate::ui::borders::{BorderStyle, BorderWindow};
+use crate::ui::stack_line::Color;
+
+/// A window with a pending preselection on one of the active spaces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preselection {
+    pub window: WindowId,
+    pub window_server_id: Option<WindowServerId>,
+    pub frame: CGRect,
+    pub direction: Direction,
+    /// Share of the split the inserted window will take, if one was given.
+    pub ratio: Option<f64>,
+}
+
+#[derive(Debug)]
+pub enum Event {
+    Update(Vec<Preselection>),
+    ConfigUpdated(Config),
+}
+
+pub type Sender = actor::Sender<Event>;
+pub type Receiver = actor::Receiver<Event>;
+
+/// Share of the split frame the inserted window takes when no ratio was given.
+pub const DEFAULT_INSERTION_FRACTION: f64 = 0.5;
+
+/// The part of `frame` the next window takes when it is inserted on the
+/// `direction` side of it and given `fraction` of the split.
+pub fn insertion_rect(frame: CGRect, direction: Direction, fraction: f64) -> CGRect {
+    let CGSize { width, height } = frame.size;
+    let CGPoint { x, y } = frame.origin;
+    let fraction = fraction.clamp(0.0, 1.0);
+    let (part_width, part_height) = (width * fraction, height * fraction);
+    match direction {
+        Direction::Left => CGRect::new(frame.origin, CGSize::new(part_width, height)),
+        Direction::Right => CGRect::new(
+            CGPoint::new(x + width - part_width, y),
+            CGSize::new(part_width, height),
+        ),
+        Direction::Up => CGRect::new(frame.origin, CGSize::new(width, part_height)),
+        Direction::Down => CGRect::new(
+            CGPoint::new(x, y + height - part_height),
+            CGSize::new(width, part_height),
+        ),
+    }
+}
+
+pub struct PreselectOverlay {
+    config: Config,
+    rx: Receiver,
+    #[allow(dead_code)]
+    mtm: MainThreadMarker,
+    overlays: HashMap<WindowId, BorderWindow>,
+    last_update: Vec<Preselection>,
+}
+
+impl PreselectOverlay {
+    pub fn new(config: Config, rx: Receiver, mtm: MainThreadMarker) -> Self {
+        Self {
+            config,
+            rx,
+            mtm,
+            overlays: HashMap::default(),
+            last_update: Vec::new(),
+        }
+    }
+
+    pub async fn run(mut self) {
+        while let Some((span, event)) = self.rx.recv().await {
+            let _guard = span.enter();
+            self.handle_event(event);
+        }
+    }
+
+    #[instrument(name = "preselect_overlay::handle_event", skip(self))]
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Update(preselections) => {
+                if preselections == self.last_update {
+                    return;
+                }
+                self.last_update = preselections;
+                self.redraw();
+            }
+            Event::ConfigUpdated(config) => {
+                self.config = config;
+                self.redraw();
+            }
+        }
+    }
+
+    fn color(&self) -> Color {
+        Color::from_hex(&self.config.settings.ui.borders.active_color).unwrap_or_else(Color::blue)
+    }
+
+    fn redraw(&mut self) {
+        let style = BorderStyle::insertion(self.color());
+        let preselections = &self.last_update;
+
+        self.overlays.retain(|window, overlay| {
+            let keep = preselections.iter().any(|p| p.window == *window);
+            if !keep && let Err(err) = overlay.hide() {
+                tracing::warn!(?err, "failed to hide preselect overlay");
+            }
+            keep
+        });
+
+        for preselection in preselections {
+            let frame = insertion_rect(
+                preselection.frame,
+                preselection.direction,
+                preselection.ratio.unwrap_or(DEFAULT_INSERTION_FRACTION),
+            );
+            let overlay = match self.overlays.entry(preselection.window) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => match BorderWindow::new(frame) {
+                    Ok(overlay) => entry.insert(overlay),
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to create preselect overlay");
+                        continue;
+                    }
+                },
+            };
+            let above = preselection.window_server_id.map(|id| id.as_u32());
+            if let Err(err) = overlay.update(frame, style, above) {
+                tracing::warn!(?err, window = ?preselection.window, "failed to update preselect overlay");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insertion_rect_takes_the_preselected_half() {
+        let frame = CGRect::new(CGPoint::new(100.0, 50.0), CGSize::new(800.0, 600.0));
+
+        assert_eq!(
+            insertion_rect(frame, Direction::Left, DEFAULT_INSERTION_FRACTION),
+            CGRect::new(CGPoint::new(100.0, 50.0), CGSize::new(400.0, 600.0))
+        );
+        assert_eq!(
+            insertion_rect(frame, Direction::Right, DEFAULT_INSERTION_FRACTION),
+            CGRect::new(CGPoint::new(500.0, 50.0), CGSize::new(400.0, 600.0))
+        );
+        assert_eq!(
+            insertion_rect(frame, Direction::Up, DEFAULT_INSERTION_FRACTION),
+            CGRect::new(CGPoint::new(100.0, 50.0), CGSize::new(800.0, 300.0))
+        );
+        assert_eq!(
+            insertion_rect(frame, Direction::Down, DEFAULT_INSERTION_FRACTION),
+            CGRect::new(CGPoint::new(100.0, 350.0), CGSize::new(800.0, 300.0))
+        );
+    }
+
+    #[test]
+    fn insertion_rect_takes_the_requested_fraction() {
+        let frame = CGRect::new(CGPoint::new(100.0, 50.0), CGSize::new(800.0, 600.0));
+
+        assert_eq!(
+            insertion_rect(frame, Direction::Left, 0.25),
+            CGRect::new(CGPoint::new(100.0, 50.0), CGSize::new(200.0, 600.0))
+        );
+        assert_eq!(
+            insertion_rect(frame, Direction::Right, 0.25),
+            CGRect::new(CGPoint::new(700.0, 50.0), CGSize::new(200.0, 600.0))
+        );
+        assert_eq!(
+            insertion_rect(frame, Direction::Down, 0.75),
+            CGRect::new(CGPoint::new(100.0, 200.0), CGSize::new(800.0, 450.0))
+        );
+    }
+}
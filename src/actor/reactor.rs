@@ -5,13 +5,19 @@
 //! changes by sending requests out to the other actors in the system.
 
 mod animation;
+mod command_result;
+mod event_log;
 mod events;
+mod floating_layer;
+mod journal;
 mod main_window;
 mod managers;
+mod mouse_warp;
 mod query;
 mod replay;
 pub mod transaction_manager;
 mod utils;
+mod window_reconciliation;
 
 #[cfg(test)]
 mod testing;
@@ -65,41 +71,54 @@ mod SpaceEventHandler {
 mod tests;
 
 use std::thread;
+use std::time::Duration;
 
-use animation::Sender as AnimationSender;
+use animation::{AnimationManager, Sender as AnimationSender};
+use event_log::EventLog;
 use events::{
     EventOutcome, app as application_workflow, command as command_workflow,
     drag as interaction_workflow, focus as focus_service, space as topology_workflow,
     system as system_workflow, window as window_workflow,
 };
+pub use journal::Journal;
 use main_window::MainWindowTracker;
 use managers::LayoutManager;
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
-pub use replay::{Record, replay};
+use objc2_core_graphics::{CGEventSource, CGEventSourceStateID};
+pub use replay::{HeadlessReactor, Record, Trace, replay};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use tracing::{debug, instrument, trace, warn};
+use tracing::{debug, info, instrument, trace, warn};
 use transaction_manager::TransactionId;
 
 use super::{event_tap, gesture_tap};
 use crate::actor::app::{AppInfo, AppThreadHandle, Quiet, Request, WindowId, WindowInfo, pid_t};
+use crate::actor::drag_swap::{DragEdge, FloatingSnap, InteractiveResize, ResizeEdges};
 use crate::actor::raise_manager::{self, RaiseManager, RaiseRequest};
 use crate::actor::reactor::events::window_discovery;
 use crate::actor::spaces::{ForwardedSpaceState, TopologyWindowDelta};
-use crate::actor::{self, menu_bar, stack_line};
+use crate::actor::{
+    self, borders, drop_zone_overlay, hint_overlay, hooks, menu_bar, preselect_overlay, stack_line,
+};
 use crate::common::collections::{BTreeMap, HashMap, HashSet};
-use crate::common::config::Config;
+use crate::common::config::{
+    Config, ConfigDiff, FocusFollowsMouseMode, StageManagerMode, WorkspaceSelector,
+};
+use crate::common::metrics;
 use crate::layout_engine::{self as layout, Direction, LayoutEngine, LayoutEvent};
-use crate::model::RiftState;
 use crate::model::broadcast::{BroadcastEvent, BroadcastSender};
 use crate::model::space_activation::{SpaceActivationConfig, SpaceActivationPolicy};
 use crate::model::tx_store::WindowTxStore;
 use crate::model::virtual_workspace::AppRuleResult;
+use crate::model::window_heuristics::heuristic_ignore_reason;
+use crate::model::{RiftState, WindowRuleContext};
+use crate::sys::crash_restore;
 use crate::sys::event::MouseState;
 use crate::sys::executor::Executor;
-use crate::sys::geometry::{CGRectDef, CGRectExt};
+use crate::sys::geometry::{CGPointDef, CGRectDef, CGRectExt};
 pub use crate::sys::screen::ScreenInfo;
 use crate::sys::screen::{SpaceId, order_visible_spaces_by_position};
+use crate::sys::timer::Timer;
 use crate::sys::window_server::{
     self, WindowServerId, WindowServerInfo, window_level, window_sub_level,
 };
@@ -109,11 +128,12 @@ type Receiver = actor::Receiver<Event>;
 use managers::RefreshQuarantineState;
 pub use query::ReactorQueryHandle;
 
+use crate::model::reactor::floating_frame;
 pub(crate) use crate::model::reactor::{AppState, WindowFilter, WindowState};
 pub use crate::model::reactor::{
-    Command, DisplaySelector, DragSession, DragState, MenuState, MissionControlState,
-    ReactorCommand, RefocusState, Requested, StaleCleanupState, WorkspaceSwitchOrigin,
-    WorkspaceSwitchState,
+    Command, DisplaySelector, DragSession, DragState, FloatSize, MenuState, MissionControlState,
+    ReactorCommand, RefocusState, Requested, StageManagerState, StaleCleanupState,
+    WorkspaceSwitchOrigin, WorkspaceSwitchState,
 };
 
 #[derive(Clone)]
@@ -135,6 +155,59 @@ impl ReactorHandle {
     ) -> Result<(), tokio::sync::mpsc::error::SendError<(tracing::Span, Event)>> {
         self.sender.try_send(event)
     }
+
+    /// Runs `command` and waits up to `timeout` for its result.
+    pub fn execute_command(&self, command: Command, timeout: Duration) -> CommandResult {
+        self.command_result(|tx| Event::CommandWithResult(command, tx), timeout)
+    }
+
+    /// Works out the frames a layout command would change without running
+    /// it, waiting up to `timeout` for the answer.
+    pub fn dry_run_command(&self, command: Command, timeout: Duration) -> CommandResult {
+        self.command_result(|tx| Event::DryRunCommand(command, tx), timeout)
+    }
+
+    fn command_result(
+        &self,
+        event: impl FnOnce(std::sync::mpsc::SyncSender<CommandResult>) -> Event,
+        timeout: Duration,
+    ) -> CommandResult {
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+        if self.sender.try_send(event(tx)).is_err() {
+            return CommandResult::error(
+                CommandErrorKind::Unavailable,
+                "The reactor is not accepting commands",
+            );
+        }
+        rx.recv_timeout(timeout).unwrap_or_else(|_| {
+            CommandResult::error(
+                CommandErrorKind::TimedOut,
+                "The command did not finish in time and may still be applied",
+            )
+        })
+    }
+
+    /// Runs `commands` as one batch and waits up to `timeout` for a result
+    /// per command.
+    pub fn execute_batch(
+        &self,
+        commands: Vec<Command>,
+        timeout: Duration,
+    ) -> Result<Vec<CommandResult>, CommandResult> {
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+        if self.sender.try_send(Event::CommandBatch(commands, tx)).is_err() {
+            return Err(CommandResult::error(
+                CommandErrorKind::Unavailable,
+                "The reactor is not accepting commands",
+            ));
+        }
+        rx.recv_timeout(timeout).map_err(|_| {
+            CommandResult::error(
+                CommandErrorKind::TimedOut,
+                "The command batch did not finish in time and may still be applied",
+            )
+        })
+    }
 }
 
 impl std::ops::Deref for ReactorHandle {
@@ -143,7 +216,7 @@ impl std::ops::Deref for ReactorHandle {
     fn deref(&self) -> &Self::Target { &self.queries }
 }
 
-use crate::model::server::WindowData;
+use crate::model::server::{CommandErrorKind, CommandResult, WindowData};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SpaceEventKind {
@@ -152,7 +225,7 @@ pub enum SpaceEventKind {
 }
 
 #[serde_as]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, strum_macros::IntoStaticStr)]
 pub enum Event {
     #[serde(skip)]
     SpaceStateChanged(ForwardedSpaceState),
@@ -181,6 +254,10 @@ pub enum Event {
     },
     ApplicationTerminated(pid_t),
     ApplicationThreadTerminated(pid_t),
+    /// Accessibility requests to the app keep timing out. Its windows are
+    /// left out of layout until [`Event::ApplicationResponsive`].
+    ApplicationUnresponsive(pid_t),
+    ApplicationResponsive(pid_t),
     ApplicationActivated(pid_t, Quiet),
     ApplicationDeactivated(pid_t),
     ApplicationGloballyActivated(pid_t),
@@ -241,6 +318,11 @@ pub enum Event {
     /// FIXME: This can be interleaved incorrectly with the MouseState in app
     /// actor events.
     MouseUp,
+    /// Left mouse button was pressed at this point.
+    ///
+    /// Used to recognise a tiled window being resized by its borders, so the
+    /// layout can follow the resize while the button is held.
+    MouseDown(#[serde(with = "CGPointDef")] CGPoint),
     /// Sent by the event tap only when the cursor enters a different window.
     /// Window resolution and transition deduplication stay on the input
     /// thread; the reactor only applies the model-dependent focus/raise work.
@@ -269,6 +351,19 @@ pub enum Event {
     #[serde(skip)]
     MissionControlNativeExited,
 
+    /// Stage Manager was turned on or off.
+    #[serde(skip)]
+    StageManagerChanged(bool),
+
+    /// Drag the current workspace `progress` screen widths towards the next
+    /// (positive) or previous (negative) workspace while a swipe is in
+    /// progress, showing the neighbour alongside. Zero puts it back.
+    #[serde(skip)]
+    WorkspaceSwipePreview {
+        progress: f64,
+        skip_empty: Option<bool>,
+    },
+
     /// A raise request completed. Used by the raise manager to track when
     /// all raise requests in a sequence have finished.
     RaiseCompleted {
@@ -287,11 +382,38 @@ pub enum Event {
 
     Command(Command),
 
+    /// A command whose [`CommandResult`] is sent back once it has run.
+    #[serde(skip)]
+    CommandWithResult(Command, std::sync::mpsc::SyncSender<CommandResult>),
+
+    /// A layout command tried on a copy of the state, answered with the
+    /// frames it would change.
+    #[serde(skip)]
+    DryRunCommand(Command, std::sync::mpsc::SyncSender<CommandResult>),
+
+    /// Commands applied in order with a single layout update at the end,
+    /// answered with one result per command.
+    #[serde(skip)]
+    CommandBatch(Vec<Command>, std::sync::mpsc::SyncSender<Vec<CommandResult>>),
+
     #[serde(skip)]
     RegisterWmSender(crate::actor::wm_controller::Sender),
 
     #[serde(skip)]
     ConfigUpdated(Config),
+
+    /// The config was replaced with a different profile. Applied like
+    /// `ConfigUpdated`, then every window is matched against the new app
+    /// rules again so it moves to the workspace they assign.
+    #[serde(skip)]
+    ProfileSwitched(Config),
+}
+
+/// What picking a window hint does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HintAction {
+    Swap,
+    Focus,
 }
 
 pub struct Reactor {
@@ -303,6 +425,8 @@ pub struct Reactor {
     space_state: ForwardedSpaceState,
     space_activation_policy: SpaceActivationPolicy,
     main_window_tracker: MainWindowTracker,
+    cursor_history: mouse_warp::CursorHistory,
+    floating_layer: floating_layer::FloatingLayer,
     drag_manager: managers::DragManager,
     workspace_switch_manager: managers::WorkspaceSwitchManager,
     recording_manager: managers::RecordingManager,
@@ -311,10 +435,17 @@ pub struct Reactor {
     transaction_manager: transaction_manager::TransactionManager,
     menu_manager: managers::MenuManager,
     mission_control_manager: managers::MissionControlManager,
+    stage_manager_state: StageManagerState,
     refocus_manager: managers::RefocusManager,
     refresh_quarantine_manager: managers::RefreshQuarantineManager,
     pending_space_change_manager: managers::PendingSpaceChangeManager,
+    launch_manager: managers::LaunchManager,
     active_spaces: HashSet<SpaceId>,
+    /// Windows labelled by `swap_with_hint` or `focus_with_hint`, by the key
+    /// that picks them.
+    hints: Option<(HintAction, Vec<(char, WindowId)>)>,
+    /// Set by `save_and_exit`; the process ends after the current event.
+    exit_requested: bool,
     pub animation_tx: Option<AnimationSender>,
 }
 
@@ -323,10 +454,16 @@ impl Reactor {
         config: Config,
         layout_engine: LayoutEngine,
         record: Record,
+        journal: Journal,
         event_tap_tx: event_tap::Sender,
         broadcast_tx: BroadcastSender,
         menu_tx: menu_bar::Sender,
         stack_line_tx: stack_line::Sender,
+        borders_tx: borders::Sender,
+        preselect_overlay_tx: preselect_overlay::Sender,
+        drop_zone_overlay_tx: drop_zone_overlay::Sender,
+        hint_overlay_tx: hint_overlay::Sender,
+        hooks_tx: hooks::Sender,
         window_notify: Option<(crate::actor::window_notify::Sender, WindowTxStore)>,
         gesture_tap_tx: Option<gesture_tap::Sender>,
         one_space: bool,
@@ -341,9 +478,15 @@ impl Reactor {
             window_notify,
             one_space,
         );
+        reactor.recording_manager.journal = journal;
         reactor.communication_manager.event_tap_tx = Some(event_tap_tx);
         reactor.menu_manager.menu_tx = Some(menu_tx);
         reactor.communication_manager.stack_line_tx = Some(stack_line_tx);
+        reactor.communication_manager.borders_tx = Some(borders_tx);
+        reactor.communication_manager.preselect_overlay_tx = Some(preselect_overlay_tx);
+        reactor.communication_manager.drop_zone_overlay_tx = Some(drop_zone_overlay_tx);
+        reactor.communication_manager.hint_overlay_tx = Some(hint_overlay_tx);
+        reactor.communication_manager.hooks_tx = Some(hooks_tx);
         reactor.communication_manager.gesture_tap_tx = gesture_tap_tx;
         reactor.communication_manager.events_tx = Some(events_tx_clone.clone());
         let query_handle = ReactorQueryHandle::new(events_tx_clone.clone());
@@ -380,12 +523,18 @@ impl Reactor {
             space_state: ForwardedSpaceState::default(),
             space_activation_policy: SpaceActivationPolicy::new(),
             main_window_tracker: MainWindowTracker::default(),
+            cursor_history: mouse_warp::CursorHistory::default(),
+            floating_layer: floating_layer::FloatingLayer::default(),
             drag_manager: managers::DragManager {
                 drag_state: DragState::Inactive,
                 drag_swap_manager: crate::actor::drag_swap::DragManager::new(
-                    config.settings.window_snapping,
+                    config.settings.window_snapping.clone(),
                 ),
                 skip_layout_for_window: None,
+                edge_dwell: Default::default(),
+                interactive_resize: None,
+                floating_drop: None,
+                swap_preview: None,
             },
             workspace_switch_manager: managers::WorkspaceSwitchManager {
                 workspace_switch_state: WorkspaceSwitchState::Inactive,
@@ -393,12 +542,23 @@ impl Reactor {
                 active_workspace_switch: None,
                 pending_workspace_switch_origin: None,
                 pending_workspace_mouse_warp: None,
+                pending_slide: None,
+                previewing_space: None,
+            },
+            recording_manager: managers::RecordingManager {
+                record,
+                journal: Journal::default(),
+                event_log: EventLog::default(),
             },
-            recording_manager: managers::RecordingManager { record },
             communication_manager: managers::CommunicationManager {
                 event_tap_tx: None,
                 gesture_tap_tx: None,
                 stack_line_tx: None,
+                borders_tx: None,
+                preselect_overlay_tx: None,
+                drop_zone_overlay_tx: None,
+                hint_overlay_tx: None,
+                hooks_tx: None,
                 raise_manager_tx,
                 event_broadcaster: broadcast_tx,
                 wm_sender: None,
@@ -407,6 +567,12 @@ impl Reactor {
             notification_manager: managers::NotificationManager {
                 last_sls_notification_ids: Vec::new(),
                 last_layout_modes_by_space: HashMap::default(),
+                last_broadcast_focus: None,
+                last_broadcast_layout_modes: HashMap::default(),
+                pending_frame_broadcasts: HashMap::default(),
+                last_broadcast_frames: HashMap::default(),
+                last_border_update: None,
+                last_preselect_update: None,
                 _window_notify_tx: window_notify_tx,
             },
             transaction_manager: transaction_manager::TransactionManager::new(window_tx_store),
@@ -418,6 +584,7 @@ impl Reactor {
                 mission_control_state: MissionControlState::Inactive,
                 pending_mission_control_refresh: HashSet::default(),
             },
+            stage_manager_state: StageManagerState::Inactive,
             refocus_manager: managers::RefocusManager {
                 stale_cleanup_state: StaleCleanupState::Enabled,
                 refocus_state: RefocusState::None,
@@ -434,7 +601,10 @@ impl Reactor {
             pending_space_change_manager: managers::PendingSpaceChangeManager {
                 pending_space_change: None,
             },
+            launch_manager: managers::LaunchManager { pending_launch: None },
             active_spaces: HashSet::default(),
+            hints: None,
+            exit_requested: false,
             animation_tx: None,
         };
         reactor
@@ -733,6 +903,16 @@ impl Reactor {
         self.reconcile_windows_with_authoritative_spaces();
     }
 
+    fn broadcast_app_responsiveness(&self, pid: pid_t, responsive: bool) {
+        let bundle_id = self.app_manager.apps.get(&pid).and_then(|app| app.info.bundle_id.clone());
+        let event = if responsive {
+            BroadcastEvent::AppRecovered { pid, bundle_id }
+        } else {
+            BroadcastEvent::AppUnresponsive { pid, bundle_id }
+        };
+        let _ = self.communication_manager.event_broadcaster.send(event);
+    }
+
     fn is_login_window_pid(&self, pid: pid_t) -> bool {
         self.app_manager.apps.get(&pid).and_then(|a| a.info.bundle_id.as_deref())
             == Some("com.apple.loginwindow")
@@ -788,6 +968,41 @@ impl Reactor {
         )
     }
 
+    fn set_stage_manager_enabled(&mut self, enabled: bool) {
+        let state = match (enabled, self.config.settings.stage_manager) {
+            (false, _) => StageManagerState::Inactive,
+            (true, StageManagerMode::Ignore) => StageManagerState::Ignored,
+            (true, _) => StageManagerState::Cooperating,
+        };
+        if state == self.stage_manager_state {
+            return;
+        }
+        info!(?state, "Stage Manager state changed");
+        self.stage_manager_state = state;
+        let _ = self.communication_manager.event_broadcaster.send(
+            BroadcastEvent::StageManagerChanged {
+                enabled,
+                cooperating: state == StageManagerState::Cooperating,
+            },
+        );
+    }
+
+    /// Whether the layout may move `wid` while Stage Manager is on. Tiled
+    /// windows are left to Stage Manager; floating ones are still placed
+    /// unless `settings.stage_manager` is `pause`.
+    fn stage_manager_allows_frame(&self, wid: WindowId) -> bool {
+        if self.stage_manager_state != StageManagerState::Cooperating {
+            return true;
+        }
+        match self.config.settings.stage_manager {
+            StageManagerMode::FloatingOnly => {
+                self.layout_manager.layout_engine.is_window_floating(wid)
+            }
+            StageManagerMode::Pause => false,
+            StageManagerMode::Ignore => true,
+        }
+    }
+
     fn get_pending_drag_swap(&self) -> Option<(WindowId, WindowId)> {
         if let DragState::PendingSwap { session, target } = &self.drag_manager.drag_state {
             Some((session.window, *target))
@@ -820,14 +1035,30 @@ impl Reactor {
         let event_tap_tx = reactor.communication_manager.event_tap_tx.clone();
         let reactor_task = Self::run_reactor_loop(reactor, events);
         let raise_manager_task = RaiseManager::run(raise_manager_rx, events_tx, event_tap_tx);
-        let animation_task = animation::AnimationManager::run(animation_rx);
+        let animation_task = AnimationManager::run(animation_rx);
         let _ = tokio::join!(reactor_task, raise_manager_task, animation_task);
     }
 
     async fn run_reactor_loop(mut reactor: Reactor, mut events: Receiver) {
         const MAX_EVENT_BATCH: usize = 64;
 
-        while let Some((span, event)) = events.recv().await {
+        loop {
+            let next = match reactor.next_timer_deadline() {
+                Some(deadline) => {
+                    let wait = deadline.saturating_duration_since(std::time::Instant::now());
+                    tokio::select! {
+                        next = events.recv() => next,
+                        _ = Timer::sleep(wait) => {
+                            reactor.on_timer();
+                            continue;
+                        }
+                    }
+                }
+                None => events.recv().await,
+            };
+            let Some((span, event)) = next else {
+                break;
+            };
             let _guard = span.enter();
             reactor.handle_loop_event(event);
             // Drain a bounded batch to reduce recv/select overhead.
@@ -855,6 +1086,9 @@ impl Reactor {
             return;
         }
         Self::note_windowserver_activity(&event);
+        if let Event::WindowFrameChanged(wid, ..) = &event {
+            self.note_frame_change(*wid);
+        }
         self.handle_event(event);
         #[cfg(any(test, debug_assertions))]
         self.state.windows.debug_assert_invariants();
@@ -879,7 +1113,10 @@ impl Reactor {
 
     fn log_event(&self, event: &Event) {
         match event {
-            Event::WindowFrameChanged(..) | Event::MouseUp | Event::MouseMoved(_) => {
+            Event::WindowFrameChanged(..)
+            | Event::MouseUp
+            | Event::MouseDown(_)
+            | Event::MouseMoved(_) => {
                 trace!(?event, "Event")
             }
             _ => debug!(?event, "Event"),
@@ -970,6 +1207,7 @@ impl Reactor {
         self.refresh_quarantine_manager.awaiting_post_session_snapshot = false;
         if released_wake {
             self.refresh_quarantine_manager.sleeping = false;
+            self.floating_layer.request_restack();
         }
         if released_session {
             self.refresh_quarantine_manager.session_inactive = false;
@@ -979,12 +1217,72 @@ impl Reactor {
 
     #[instrument(name = "reactor::handle_event", skip(self), fields(event=?event))]
     fn handle_event(&mut self, event: Event) {
+        let handled = match &event {
+            Event::CommandBatch(commands, _) => commands.len(),
+            _ => 1,
+        };
+        metrics::EVENTS_PROCESSED.add(handled as u64);
+        // Queries change nothing, and logging them would let `debug events`
+        // fill the log with its own requests.
+        let described = (!matches!(event, Event::Query(_))).then(|| EventLog::describe(&event));
+        let started = std::time::SystemTime::now();
+        let timer = std::time::Instant::now();
+        self.run_event(event);
+        if let Some(described) = described {
+            self.recording_manager.event_log.record(described, started, timer.elapsed());
+        }
+        if self.exit_requested {
+            std::process::exit(0);
+        }
+    }
+
+    fn run_event(&mut self, event: Event) {
+        if let Event::CommandBatch(commands, resp) = event {
+            let _ = resp.send(self.handle_command_batch(commands));
+            return;
+        }
+        if let Event::CommandWithResult(command, resp) = event {
+            let _ = resp.send(self.run_command_with_result(command, None));
+            return;
+        }
+        if let Event::DryRunCommand(command, resp) = event {
+            let _ = resp.send(self.dry_run_command(command));
+            return;
+        }
         match self.dispatch_workflow(event) {
             Ok(outcome) => self.apply_event_outcome(outcome),
             Err(error) => warn!(%error, "reactor workflow failed"),
         }
     }
 
+    /// Runs each command with all of its follow-up work except arranging
+    /// windows, which happens once after the last command. The batch is first
+    /// tried on copies of the state, and nothing runs unless every command
+    /// succeeds there.
+    fn handle_command_batch(&mut self, commands: Vec<Command>) -> Vec<CommandResult> {
+        if let Err((failed, result)) = self.trial_run_batch(&commands) {
+            return (0..commands.len())
+                .map(|index| {
+                    if index == failed {
+                        result.clone()
+                    } else {
+                        CommandResult::error(
+                            CommandErrorKind::BatchAborted,
+                            format!("Not applied because batch command {failed} failed"),
+                        )
+                    }
+                })
+                .collect();
+        }
+        let mut batched = EventOutcome::default();
+        let results = commands
+            .into_iter()
+            .map(|command| self.run_command_with_result(command, Some(&mut batched)))
+            .collect();
+        self.apply_event_outcome(batched);
+        results
+    }
+
     /// Dispatches one event and returns all ordered follow-up work without
     /// applying it. This is the migration boundary used by the individual
     /// workflow modules.
@@ -1031,6 +1329,12 @@ impl Reactor {
         let should_update_notifications = Self::should_update_notifications(&event);
 
         let raised_window = self.main_window_tracker.handle_event(&event);
+        if let &Event::ApplicationLaunched { pid, .. }
+        | &Event::ApplicationMainWindowChanged(pid, ..) = &event
+        {
+            let main_window = self.main_window_tracker.app_main_window(pid);
+            self.layout_manager.layout_engine.set_app_main_window(pid, main_window);
+        }
         match event {
             Event::ApplicationLaunched {
                 pid,
@@ -1065,6 +1369,15 @@ impl Reactor {
                     pid,
                 );
             }
+            Event::ApplicationUnresponsive(pid) => {
+                self.app_manager.unresponsive.insert(pid);
+                self.broadcast_app_responsiveness(pid, false);
+            }
+            Event::ApplicationResponsive(pid) => {
+                self.app_manager.unresponsive.remove(&pid);
+                self.broadcast_app_responsiveness(pid, true);
+                return Ok(EventOutcome::finalized_event(None, false, false, false));
+            }
             Event::ApplicationActivated(pid, quiet) => {
                 self.clear_menu_state_for_non_owner(pid);
                 let mut outcome = application_workflow::handle_application_activated(
@@ -1254,6 +1567,7 @@ impl Reactor {
                 );
             }
             Event::SpaceDestroyed(space) => {
+                self.layout_manager.layout_engine.remove_space(&mut self.state.windows, space);
                 return topology_workflow::handle_space_lifecycle(
                     &mut self.space_activation_policy,
                     topology_workflow::SpaceLifecyclePayload { space, created: false },
@@ -1385,6 +1699,10 @@ impl Reactor {
                 self.space_state.command_space = command_space;
                 return Ok(EventOutcome::default());
             }
+            Event::MouseDown(point) => {
+                self.begin_interactive_resize(point);
+                return Ok(EventOutcome::default());
+            }
             Event::MouseUp => {
                 let pending_swap = self.get_pending_drag_swap();
                 let (visible_spaces, visible_space_centers) = self.visible_spaces_for_layout(true);
@@ -1416,6 +1734,14 @@ impl Reactor {
                 let focused = self.window_id_under_cursor().and_then(|window| {
                     self.best_space_for_window_id(window).map(|space| (space, window))
                 });
+                let floating_snap = self.floating_snap(final_space);
+                if pending_swap.is_none() {
+                    self.revert_drag_swap_preview();
+                }
+                let floating_drop = self.take_floating_drop().and_then(|(space, point)| {
+                    let screen = self.space_state.screen_by_space(space)?.frame;
+                    Some(interaction_workflow::FloatingDrop { space, point, screen })
+                });
                 let mut outcome = interaction_workflow::handle_mouse_up(
                     &mut self.state,
                     &mut self.layout_manager,
@@ -1426,6 +1752,8 @@ impl Reactor {
                         final_space,
                         visible_spaces,
                         visible_space_centers,
+                        floating_snap,
+                        floating_drop,
                     },
                 )?;
                 if let Some((space, window)) = focused {
@@ -1466,6 +1794,9 @@ impl Reactor {
                             self.layout_manager.layout_engine.focused_window() != Some(window)
                         }),
                         active_space,
+                        focus_only: self.config.settings.focus_follows_mouse_mode
+                            == FocusFollowsMouseMode::FocusOnly,
+                        window_server_id: Some(wsid),
                     },
                 );
             }
@@ -1480,6 +1811,10 @@ impl Reactor {
                     &mut self.mission_control_manager,
                 );
             }
+            Event::StageManagerChanged(enabled) => {
+                self.set_stage_manager_enabled(enabled);
+                return Ok(EventOutcome::finalized_event(None, false, false, false));
+            }
             Event::RaiseCompleted { window_id, sequence_id } => {
                 return Ok(system_workflow::handle_raise_completed(
                     system_workflow::RaiseCompletedPayload {
@@ -1492,13 +1827,30 @@ impl Reactor {
                 return Ok(system_workflow::handle_raise_timeout(sequence_id)?);
             }
             Event::ConfigUpdated(new_cfg) => {
-                return command_workflow::handle_config_updated(
+                let outcome = command_workflow::handle_config_updated(
+                    &mut self.config,
+                    &mut self.layout_manager,
+                    &self.state,
+                    &mut self.drag_manager,
+                    new_cfg,
+                );
+                self.set_stage_manager_enabled(
+                    self.stage_manager_state != StageManagerState::Inactive,
+                );
+                return outcome;
+            }
+            Event::ProfileSwitched(new_cfg) => {
+                let outcome = command_workflow::handle_profile_switched(
                     &mut self.config,
                     &mut self.layout_manager,
                     &self.state,
                     &mut self.drag_manager,
                     new_cfg,
                 );
+                self.set_stage_manager_enabled(
+                    self.stage_manager_state != StageManagerState::Inactive,
+                );
+                return outcome;
             }
             Event::Command(Command::Metrics(cmd)) => {
                 return command_workflow::handle_command_metrics(cmd);
@@ -1514,6 +1866,7 @@ impl Reactor {
                 return command_workflow::handle_command_reactor_save_and_exit(
                     &self.state,
                     &mut self.layout_manager,
+                    &self.recording_manager.journal,
                     active_space,
                 );
             }
@@ -1559,6 +1912,10 @@ impl Reactor {
                 let serialized = self.serialize_state();
                 return command_workflow::handle_command_reactor_serialize(serialized);
             }
+            Event::WorkspaceSwipePreview { progress, skip_empty } => {
+                LayoutManager::preview_workspace_swipe(self, progress, skip_empty);
+                return Ok(EventOutcome::default());
+            }
             Event::Command(Command::Reactor(ReactorCommand::SwitchSpace(direction))) => {
                 return command_workflow::handle_switch_native_space(direction);
             }
@@ -1575,6 +1932,50 @@ impl Reactor {
                     command_workflow::ToggleSpacePayload { config, space, display_uuid },
                 );
             }
+            Event::Command(Command::Reactor(ReactorCommand::LaunchAppIntoSelection {
+                bundle_id,
+            })) => {
+                let space = self.workspace_command_space();
+                self.launch_manager.pending_launch = space.map(|space| managers::PendingLaunch {
+                    bundle_id: bundle_id.clone(),
+                    space,
+                    anchor: self.layout_manager.layout_engine.selected_window(space),
+                    deadline: std::time::Instant::now() + managers::PENDING_LAUNCH_TIMEOUT,
+                });
+                crate::sys::app::launch_application(&bundle_id);
+                return Ok(EventOutcome::finalized_event(None, false, false, false));
+            }
+            Event::Command(Command::Reactor(ReactorCommand::ToggleDisplayActivated(selector))) => {
+                let screen = self.screen_for_selector(&selector, None);
+                let display_uuid = screen.and_then(|screen| screen.display_uuid_owned());
+                let space = screen.and_then(|screen| screen.space);
+                let config = self.activation_cfg();
+                return command_workflow::handle_command_reactor_toggle_display_activated(
+                    &mut self.space_activation_policy,
+                    command_workflow::ToggleDisplayPayload { config, display_uuid, space },
+                );
+            }
+            Event::Command(Command::Reactor(ReactorCommand::ToggleSuspended)) => {
+                let suspended = !self.space_activation_policy.is_suspended();
+                info!(suspended, "Toggling window management suspension");
+                self.space_activation_policy.set_suspended(suspended);
+                if let Some(tx) = self.communication_manager.event_tap_tx.as_ref() {
+                    tx.send(event_tap::Request::SetSuspended(suspended));
+                }
+                if suspended
+                    && let Some(tx) = &self.communication_manager.stack_line_tx
+                    && let Err(error) = tx.try_send(stack_line::Event::ClearIndicators)
+                {
+                    warn!(%error, "failed to clear stack line indicators");
+                }
+                return Ok(EventOutcome::finalized_event(None, false, false, false)
+                    .with_active_space_recompute());
+            }
+            Event::Command(Command::Reactor(ReactorCommand::ToggleOverview)) => {
+                return command_workflow::handle_mission_control_command(
+                    crate::actor::wm_controller::WmCmd::ToggleOverview,
+                );
+            }
             Event::Command(Command::Reactor(ReactorCommand::ShowMissionControlAll)) => {
                 return command_workflow::handle_mission_control_command(
                     crate::actor::wm_controller::WmCmd::ShowMissionControlAll,
@@ -1593,15 +1994,171 @@ impl Reactor {
             Event::Command(Command::Reactor(ReactorCommand::CloseWindow { window_server_id })) => {
                 return command_workflow::handle_close_window(window_server_id);
             }
+            Event::Command(Command::Reactor(ReactorCommand::MinimizeSelection)) => {
+                if let Some(wid) =
+                    self.main_window().or(self.layout_manager.layout_engine.focused_window())
+                {
+                    self.request_set_minimized(wid, true);
+                }
+                return Ok(EventOutcome::finalized_event(None, false, false, false));
+            }
+            Event::Command(Command::Reactor(ReactorCommand::ToggleNativeFullscreen)) => {
+                if let Some(wid) =
+                    self.main_window().or(self.layout_manager.layout_engine.focused_window())
+                    && let Some(app) = self.app_manager.apps.get(&wid.pid)
+                    && let Err(err) = app.handle.send(Request::ToggleNativeFullscreen(wid))
+                {
+                    warn!(?wid, "Failed to send native fullscreen request: {}", err);
+                }
+                return Ok(EventOutcome::finalized_event(None, false, false, false));
+            }
+            Event::Command(Command::Reactor(ReactorCommand::ToggleAlwaysOnTop)) => {
+                if let Some(wid) =
+                    self.main_window().or(self.layout_manager.layout_engine.focused_window())
+                {
+                    command_workflow::toggle_always_on_top(
+                        &mut self.state,
+                        &self.layout_manager,
+                        wid,
+                    );
+                }
+                return Ok(EventOutcome::finalized_event(None, false, false, false));
+            }
+            Event::Command(Command::Reactor(ReactorCommand::SwapWithHint)) => {
+                let Some((space, focused)) = self.swap_hint_origin() else {
+                    debug!("swap_with_hint ignored: no focused tiled window");
+                    return Ok(EventOutcome::finalized_event(None, false, false, false));
+                };
+                let engine = &self.layout_manager.layout_engine;
+                let windows: Vec<(WindowId, CGRect)> = engine
+                    .windows_in_active_workspace(&self.state.windows, space)
+                    .into_iter()
+                    .filter(|&wid| wid != focused && !engine.is_window_floating(wid))
+                    .filter_map(|wid| Some((wid, self.state.windows.window(wid)?.frame_monotonic)))
+                    .collect();
+                return Ok(self.show_hints(HintAction::Swap, &windows));
+            }
+            Event::Command(Command::Reactor(ReactorCommand::FocusWithHint)) => {
+                let windows = self.focus_hint_candidates();
+                return Ok(self.show_hints(HintAction::Focus, &windows));
+            }
+            Event::Command(Command::Reactor(ReactorCommand::PickHint(key))) => {
+                let key = key.to_ascii_lowercase();
+                let picked = self.hints.take().and_then(|(action, hints)| {
+                    hints.into_iter().find(|(hint, _)| *hint == key).map(|(_, wid)| (action, wid))
+                });
+                let outcome = self.hide_hints();
+                let mut picked_outcome = match picked {
+                    Some((HintAction::Swap, target)) => {
+                        let Some((space, focused)) = self.swap_hint_origin() else {
+                            return Ok(outcome);
+                        };
+                        let (visible_spaces, visible_space_centers) =
+                            self.visible_spaces_for_layout(false);
+                        command_workflow::handle_command_layout(
+                            &mut self.state,
+                            &mut self.layout_manager,
+                            &mut self.workspace_switch_manager,
+                            command_workflow::LayoutCommandPayload {
+                                command: layout::LayoutCommand::SwapWindows(focused, target),
+                                command_space: Some(space),
+                                visible_spaces,
+                                visible_space_centers,
+                            },
+                        )?
+                    }
+                    Some((HintAction::Focus, target)) => {
+                        let Some(window) = self.state.windows.window(target) else {
+                            return Ok(outcome);
+                        };
+                        let resolved_space = self.best_space_for_window_state(window);
+                        command_workflow::handle_command_reactor_focus_window(
+                            &self.state,
+                            &self.app_manager,
+                            command_workflow::FocusWindowPayload {
+                                window_id: target,
+                                window_server_id: window.info.sys_id,
+                                resolved_space,
+                                space_is_active: resolved_space
+                                    .is_some_and(|space| self.is_space_active(space)),
+                            },
+                        )?
+                    }
+                    None => return Ok(outcome),
+                };
+                picked_outcome.absorb(outcome);
+                return Ok(picked_outcome);
+            }
+            Event::Command(Command::Reactor(ReactorCommand::CancelHints)) => {
+                return Ok(self.hide_hints());
+            }
+            Event::Command(Command::Reactor(
+                command @ (ReactorCommand::CenterFloat | ReactorCommand::ResizeFloat(_)),
+            )) => {
+                let size = match command {
+                    ReactorCommand::ResizeFloat(size) => Some(size),
+                    _ => None,
+                };
+                let Some(window) = self
+                    .main_window()
+                    .or(self.layout_manager.layout_engine.focused_window())
+                    .filter(|&wid| self.layout_manager.layout_engine.is_window_floating(wid))
+                else {
+                    debug!("Floating placement ignored: no focused floating window");
+                    return Ok(EventOutcome::finalized_event(None, false, false, false));
+                };
+                let Some(space) = self.best_space_for_window_id(window) else {
+                    return Ok(EventOutcome::finalized_event(None, false, false, false));
+                };
+                let (Some(screen), Some(window_state)) = (
+                    self.space_state.screen_by_space(space),
+                    self.state.windows.window(window),
+                ) else {
+                    return Ok(EventOutcome::finalized_event(None, false, false, false));
+                };
+                let frame = floating_frame(screen.frame, window_state.frame_monotonic, size);
+                return Ok(command_workflow::handle_command_reactor_place_float(
+                    &mut self.state,
+                    &mut self.layout_manager,
+                    window,
+                    space,
+                    frame,
+                ));
+            }
+            Event::Command(Command::Reactor(ReactorCommand::RescueWindows)) => {
+                return Ok(self.rescue_offscreen_windows());
+            }
+            Event::Command(Command::Reactor(ReactorCommand::RestoreLastMinimized)) => {
+                if let Some(wid) = self.layout_manager.layout_engine.last_minimized_window()
+                    && self.state.windows.window(wid).is_some_and(|window| window.info.is_minimized)
+                {
+                    self.request_set_minimized(wid, false);
+                }
+                return Ok(EventOutcome::finalized_event(None, false, false, false));
+            }
             Event::Command(Command::Reactor(ReactorCommand::FocusWindow {
                 window_id,
                 window_server_id,
+                switch_workspace,
             })) => {
                 let resolved_space = self.best_space_for_window_id(window_id).or_else(|| {
                     self.state.windows.window(window_id).and_then(|window| {
                         self.best_space_for_window(&window.frame_monotonic, window.info.sys_id)
                     })
                 });
+                // Windows picked from the overview bring their hidden workspace
+                // along.
+                if switch_workspace
+                    && let Some(space) = resolved_space
+                    && self.is_space_active(space)
+                    && self.switch_to_window_workspace(
+                        window_id,
+                        space,
+                        WorkspaceSwitchOrigin::Manual,
+                    )
+                {
+                    return Ok(EventOutcome::finalized_event(None, false, false, false));
+                }
                 return command_workflow::handle_command_reactor_focus_window(
                     &self.state,
                     &self.app_manager,
@@ -1614,6 +2171,25 @@ impl Reactor {
                     },
                 );
             }
+            Event::Command(Command::Reactor(ReactorCommand::FocusPreviousGlobal)) => {
+                let Some((window_id, space)) = self.previous_global_focus_target() else {
+                    return Ok(EventOutcome::finalized_event(None, false, false, false));
+                };
+                if self.switch_to_window_workspace(window_id, space, WorkspaceSwitchOrigin::Manual)
+                {
+                    return Ok(EventOutcome::finalized_event(None, false, false, false));
+                }
+                return command_workflow::handle_command_reactor_focus_window(
+                    &self.state,
+                    &self.app_manager,
+                    command_workflow::FocusWindowPayload {
+                        window_id,
+                        window_server_id: None,
+                        resolved_space: Some(space),
+                        space_is_active: true,
+                    },
+                );
+            }
             Event::Command(Command::Reactor(ReactorCommand::MoveMouseToDisplay(selector))) => {
                 let screen = self.screen_for_selector(&selector, None).cloned();
                 let focus_window = screen.as_ref().and_then(|screen| {
@@ -1677,6 +2253,31 @@ impl Reactor {
                     },
                 );
             }
+            Event::Command(Command::Reactor(ReactorCommand::SwitchWorkspaceOnDisplay {
+                workspace,
+                selector,
+            })) => {
+                let Some(space) = self
+                    .screen_for_selector(&selector, None)
+                    .and_then(|screen| screen.space)
+                    .filter(|space| self.is_space_active(*space))
+                else {
+                    warn!(?selector, "No active space on display for workspace switch");
+                    return Ok(EventOutcome::finalized_event(None, false, false, false));
+                };
+                let (visible_spaces, visible_space_centers) = self.visible_spaces_for_layout(false);
+                return command_workflow::handle_command_layout(
+                    &mut self.state,
+                    &mut self.layout_manager,
+                    &mut self.workspace_switch_manager,
+                    command_workflow::LayoutCommandPayload {
+                        command: layout::LayoutCommand::SwitchToWorkspace(workspace),
+                        command_space: Some(space),
+                        visible_spaces,
+                        visible_space_centers,
+                    },
+                );
+            }
             Event::Command(Command::Reactor(ReactorCommand::MoveWindowToDisplay {
                 selector,
                 window_id,
@@ -1828,6 +2429,9 @@ impl Reactor {
         for window in outcome.reapply_app_rules {
             self.maybe_reapply_app_rules_for_window(window);
         }
+        if outcome.remap_workspaces {
+            self.remap_windows_to_workspaces();
+        }
         for window in outcome.finalize_created_windows {
             let active_space = self.state.windows.window(window).and_then(|state| {
                 self.best_space_for_window(&state.frame_monotonic, state.info.sys_id)
@@ -1859,6 +2463,9 @@ impl Reactor {
                     .is_some_and(|state| state.matches_filter(WindowFilter::EffectivelyManageable))
                 {
                     self.send_layout_event(LayoutEvent::WindowAdded(space, window));
+                    self.place_launched_window(&[window]);
+                    self.swallow_launching_windows(&[window]);
+                    self.apply_floating_frame_rule(window, space);
                 }
             }
         }
@@ -1927,11 +2534,17 @@ impl Reactor {
             self.handle_layout_response(response, workspace_switch_space);
         }
         for (window, frame) in outcome.drag_swap_evaluations {
+            self.track_drag_edge();
             self.maybe_swap_on_drag(window, frame);
         }
         if outcome.dispatch_mouse_up {
             self.handle_event(Event::MouseUp);
         }
+        // A drag can end without a mouse up, e.g. when the dragged window is
+        // destroyed or Mission Control opens.
+        if !self.is_in_drag() {
+            self.revert_drag_swap_preview();
+        }
 
         let mut layout_changed = false;
         if outcome.arrange.requested && (!self.is_in_drag() || outcome.arrange.window_was_destroyed)
@@ -1947,6 +2560,9 @@ impl Reactor {
             }
             self.maybe_send_menu_update();
         }
+        if layout_changed {
+            self.recording_manager.journal.mark_dirty(std::time::Instant::now());
+        }
 
         for request in outcome.raise_requests {
             if let Err(error) = self.communication_manager.raise_manager_tx.try_send(request) {
@@ -2004,24 +2620,13 @@ impl Reactor {
             }
         }
 
-        if let Some((config, keys_changed)) = outcome.service_config_update {
-            if let Some(tx) = &self.communication_manager.stack_line_tx
-                && let Err(error) = tx.try_send(stack_line::Event::ConfigUpdated(config.clone()))
-            {
-                warn!(%error, "failed to update stack line config");
-            }
-            if let Some(tx) = &self.menu_manager.menu_tx
-                && let Err(error) = tx.try_send(menu_bar::Event::ConfigUpdated(config.clone()))
-            {
-                warn!(%error, "failed to update menu bar config");
-            }
-            if keys_changed && let Some(wm) = &self.communication_manager.wm_sender {
-                wm.send(crate::actor::wm_controller::WmEvent::ConfigUpdated(config));
-            }
+        if let Some((config, diff)) = outcome.service_config_update {
+            self.send_config_to_services(config, &diff);
         }
         for line in outcome.stdout_lines {
             println!("{line}");
         }
+        self.exit_requested |= outcome.exit_process;
         self.workspace_switch_manager.mark_workspace_switch_inactive();
         if self.workspace_switch_manager.active_workspace_switch.is_some() && !layout_changed {
             self.workspace_switch_manager.active_workspace_switch = None;
@@ -2030,10 +2635,14 @@ impl Reactor {
 
         // Execute deferred mouse warp after workspace switch completes
         if let Some(wid) = self.workspace_switch_manager.pending_workspace_mouse_warp.take() {
-            if let Some(window_center) = self.window_center_on_known_screen(wid) {
-                self.warp_mouse(window_center);
+            if let Some(point) = self.mouse_warp_target(wid) {
+                self.warp_mouse(point);
             }
         }
+        if self.floating_layer.take_restack() {
+            self.restack_floating_windows();
+        }
+        self.raise_always_on_top_windows();
 
         if outcome.refresh_window_notifications {
             let mut ids: Vec<u32> = self
@@ -2072,6 +2681,24 @@ impl Reactor {
                 warn!(pid, %error, "failed to send deferred application request");
             }
         }
+        self.broadcast_focus_and_layout_changes();
+        self.maybe_send_border_update();
+        self.maybe_send_preselect_update();
+    }
+
+    /// The label for a window's stack-line tab: its title, or the app name
+    /// for untitled windows.
+    fn stack_tab_title(&self, window_id: WindowId) -> String {
+        let title = self.state.windows.window(window_id).map(|w| w.info.title.as_str());
+        match title {
+            Some(title) if !title.trim().is_empty() => title.to_owned(),
+            _ => self
+                .app_manager
+                .apps
+                .get(&window_id.pid)
+                .and_then(|app| app.info.localized_name.clone())
+                .unwrap_or_default(),
+        }
     }
 
     fn create_window_data(&self, window_id: WindowId) -> Option<WindowData> {
@@ -2083,12 +2710,34 @@ impl Reactor {
 
         let app_name = app.info.localized_name.clone();
         let bundle_id = app.info.bundle_id.clone();
+        let space = self.best_space_for_window_id(window_id);
+        let layout_engine = &self.layout_manager.layout_engine;
+        let workspace_name = space.and_then(|space| {
+            let workspace = layout_engine.virtual_workspace_manager().workspace_for_window(
+                &self.state.windows,
+                space,
+                window_id,
+            )?;
+            layout_engine.workspace_name(space, workspace)
+        });
+        let display = space.and_then(|space| {
+            self.space_state.screens.iter().find(|screen| screen.space == Some(space))
+        });
 
         Some(WindowData {
             id: window_id,
             is_floating: self.layout_manager.layout_engine.is_window_floating(window_id),
             is_focused: self.main_window() == Some(window_id),
+            is_native_fullscreen: self
+                .state
+                .windows
+                .is_window_native_fullscreen_suspended(window_id),
+            is_zoomed: self.layout_manager.layout_engine.is_window_zoomed(window_id),
+            is_always_on_top: self.state.always_on_top.contains(&window_id),
             app_name,
+            workspace_name,
+            display_uuid: display.and_then(|screen| screen.display_uuid_owned()),
+            display_name: display.and_then(|screen| screen.name.clone()),
             info: WindowInfo {
                 title: window_state.info.title.clone(),
                 frame: window_state.frame_monotonic,
@@ -2356,20 +3005,262 @@ impl Reactor {
         }
     }
 
-    fn maybe_reapply_app_rules_for_window(&mut self, window_id: WindowId) {
-        if !self.config.virtual_workspaces.reapply_app_rules_on_title_change {
-            return;
+    /// Schedules `window_moved`/`window_resized` for a window, restarting the
+    /// wait if it is still moving. Must run before the new frame is stored so
+    /// the first change is compared against the frame it started from.
+    fn note_frame_change(&mut self, window_id: WindowId) {
+        let notifications = &mut self.notification_manager;
+        if let Some(window) = self.state.windows.window(window_id) {
+            notifications
+                .last_broadcast_frames
+                .entry(window_id)
+                .or_insert(window.frame_monotonic);
+        }
+        notifications.pending_frame_broadcasts.insert(
+            window_id,
+            std::time::Instant::now() + managers::FRAME_BROADCAST_DEBOUNCE,
+        );
+    }
+
+    fn next_frame_broadcast_deadline(&self) -> Option<std::time::Instant> {
+        self.notification_manager.pending_frame_broadcasts.values().min().copied()
+    }
+
+    /// Hands a reloaded config to the actors that read the settings in `diff`.
+    fn send_config_to_services(&self, config: Config, diff: &ConfigDiff) {
+        if diff.touches(&["settings.ui.stack_line"])
+            && let Some(tx) = &self.communication_manager.stack_line_tx
+            && let Err(error) = tx.try_send(stack_line::Event::ConfigUpdated(config.clone()))
+        {
+            warn!(%error, "failed to update stack line config");
+        }
+        if diff.touches(&["settings.ui.borders"]) {
+            if let Some(tx) = &self.communication_manager.borders_tx
+                && let Err(error) = tx.try_send(borders::Event::ConfigUpdated(config.clone()))
+            {
+                warn!(%error, "failed to update borders config");
+            }
+            if let Some(tx) = &self.communication_manager.preselect_overlay_tx
+                && let Err(error) =
+                    tx.try_send(preselect_overlay::Event::ConfigUpdated(config.clone()))
+            {
+                warn!(%error, "failed to update preselect overlay config");
+            }
+            if let Some(tx) = &self.communication_manager.drop_zone_overlay_tx
+                && let Err(error) =
+                    tx.try_send(drop_zone_overlay::Event::ConfigUpdated(config.clone()))
+            {
+                warn!(%error, "failed to update drop zone overlay config");
+            }
+            if let Some(tx) = &self.communication_manager.hint_overlay_tx
+                && let Err(error) = tx.try_send(hint_overlay::Event::ConfigUpdated(config.clone()))
+            {
+                warn!(%error, "failed to update hint overlay config");
+            }
+        }
+        if diff.touches(&["settings.hooks"])
+            && let Some(tx) = &self.communication_manager.hooks_tx
+            && let Err(error) = tx.try_send(hooks::Event::ConfigUpdated(config.clone()))
+        {
+            warn!(%error, "failed to update hooks config");
         }
+        if diff.touches(&["settings.ui.menu_bar", "keys"])
+            && let Some(tx) = &self.menu_manager.menu_tx
+            && let Err(error) = tx.try_send(menu_bar::Event::ConfigUpdated(config.clone()))
+        {
+            warn!(%error, "failed to update menu bar config");
+        }
+        // The wm controller owns the hotkeys and forwards the config to the
+        // event and gesture taps.
+        const INPUT_SETTINGS: &[&str] = &[
+            "keys",
+            "key_specs",
+            "modes",
+            "settings.focus_follows_mouse",
+            "settings.focus_follows_mouse_disable_hotkey",
+            "settings.focus_follows_mouse_delay_ms",
+            "settings.focus_follows_mouse_modifier",
+            "settings.mouse_hides_on_focus",
+            "settings.ui.stack_line",
+            "settings.layout.mode",
+            "settings.layout.scrolling",
+            "settings.gestures",
+        ];
+        if diff.touches(INPUT_SETTINGS)
+            && let Some(wm) = &self.communication_manager.wm_sender
+        {
+            wm.send(crate::actor::wm_controller::WmEvent::ConfigUpdated(config));
+        }
+    }
 
-        let Some(space) = self.best_space_for_window_id(window_id) else {
+    /// Earliest moment the run loop has to wake up without an event.
+    fn next_timer_deadline(&self) -> Option<std::time::Instant> {
+        [
+            self.next_frame_broadcast_deadline(),
+            self.drag_manager.edge_dwell.deadline(),
+            self.recording_manager.journal.deadline(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    fn on_timer(&mut self) {
+        self.flush_frame_broadcasts();
+        self.flush_drag_edge_dwell();
+        self.flush_journal();
+    }
+
+    /// Writes the crash-recovery journal once a scheduled write is due.
+    fn flush_journal(&mut self) {
+        let Some(path) = self.recording_manager.journal.take_due(std::time::Instant::now()) else {
             return;
         };
-        if !self.is_space_active(space) {
-            return;
+        let active_space = self.active_display_space();
+        if let Err(error) = self.layout_manager.layout_engine.save_current_layout(
+            path,
+            &self.state.windows,
+            active_space,
+        ) {
+            warn!(%error, "failed to write state journal");
         }
+    }
 
-        let (is_manageable, wsid) = match self.state.windows.window(window_id) {
-            Some(window_state) => (
+    /// Publishes frame changes whose window has stopped moving.
+    fn flush_frame_broadcasts(&mut self) {
+        let now = std::time::Instant::now();
+        let due: Vec<WindowId> = self
+            .notification_manager
+            .pending_frame_broadcasts
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(window_id, _)| *window_id)
+            .collect();
+        for window_id in due {
+            self.notification_manager.pending_frame_broadcasts.remove(&window_id);
+            let Some(frame) = self.state.windows.window(window_id).map(|w| w.frame_monotonic)
+            else {
+                self.notification_manager.last_broadcast_frames.remove(&window_id);
+                continue;
+            };
+            let Some(previous) =
+                self.notification_manager.last_broadcast_frames.insert(window_id, frame)
+            else {
+                continue;
+            };
+            let Some(space) = self.best_space_for_window_id(window_id) else {
+                continue;
+            };
+            if !self.is_space_active(space)
+                || !self.layout_manager.layout_engine.is_window_in_active_workspace(
+                    &self.state.windows,
+                    space,
+                    window_id,
+                )
+            {
+                continue;
+            }
+            let display_uuid = self.display_uuid_for_space(space);
+            if previous.origin != frame.origin {
+                let _ = self.communication_manager.event_broadcaster.send(
+                    BroadcastEvent::WindowMoved {
+                        window_id,
+                        frame,
+                        space_id: space,
+                        display_uuid: display_uuid.clone(),
+                    },
+                );
+            }
+            if previous.size != frame.size {
+                let _ = self.communication_manager.event_broadcaster.send(
+                    BroadcastEvent::WindowResized {
+                        window_id,
+                        frame,
+                        space_id: space,
+                        display_uuid,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Publishes `window_focused` and `layout_changed` for whatever differs
+    /// from the last published state. Spaces that became inactive are
+    /// forgotten so reactivating them republishes their layout.
+    fn broadcast_focus_and_layout_changes(&mut self) {
+        let focus = self.main_window();
+        if focus != self.notification_manager.last_broadcast_focus {
+            self.notification_manager.last_broadcast_focus = focus;
+            if let Some(window_id) = focus
+                && let Some(space) = self.best_space_for_window_id(window_id)
+                && let Some(workspace_id) =
+                    self.layout_manager.layout_engine.active_workspace(space)
+                && let Some(window) = self.state.windows.window(window_id)
+            {
+                let workspace_name = self
+                    .layout_manager
+                    .layout_engine
+                    .workspace_name(space, workspace_id)
+                    .unwrap_or_else(|| format!("Workspace {:?}", workspace_id));
+                let event = BroadcastEvent::WindowFocused {
+                    window_id,
+                    title: window.info.title.clone(),
+                    bundle_id: window.info.bundle_id.clone(),
+                    workspace_id,
+                    workspace_name,
+                    space_id: space,
+                    display_uuid: self.display_uuid_for_space(space),
+                };
+                let _ = self.communication_manager.event_broadcaster.send(event);
+            }
+        }
+
+        let active_spaces: Vec<SpaceId> = self.iter_active_spaces().collect();
+        self.notification_manager
+            .last_broadcast_layout_modes
+            .retain(|space, _| active_spaces.contains(space));
+        for space in active_spaces {
+            let layout_mode = self.layout_manager.layout_engine.active_layout_mode_at(space);
+            if self.notification_manager.last_broadcast_layout_modes.get(&space)
+                == Some(&layout_mode)
+            {
+                continue;
+            }
+            let Some(workspace_id) = self.layout_manager.layout_engine.active_workspace(space)
+            else {
+                continue;
+            };
+            self.notification_manager.last_broadcast_layout_modes.insert(space, layout_mode);
+            let workspace_name = self
+                .layout_manager
+                .layout_engine
+                .workspace_name(space, workspace_id)
+                .unwrap_or_else(|| format!("Workspace {:?}", workspace_id));
+            let event = BroadcastEvent::LayoutChanged {
+                workspace_id,
+                workspace_name,
+                layout_mode,
+                space_id: space,
+                display_uuid: self.display_uuid_for_space(space),
+            };
+            let _ = self.communication_manager.event_broadcaster.send(event);
+        }
+    }
+
+    fn maybe_reapply_app_rules_for_window(&mut self, window_id: WindowId) {
+        if !self.config.virtual_workspaces.reapply_app_rules_on_title_change {
+            return;
+        }
+
+        let Some(space) = self.best_space_for_window_id(window_id) else {
+            return;
+        };
+        if !self.is_space_active(space) {
+            return;
+        }
+
+        let (is_manageable, wsid) = match self.state.windows.window(window_id) {
+            Some(window_state) => (
                 window_state.matches_filter(WindowFilter::Manageable),
                 window_state.info.sys_id,
             ),
@@ -2392,6 +3283,21 @@ impl Reactor {
         self.process_windows_for_app_rules(window_id.pid, vec![window_id], app_info);
     }
 
+    /// Match every window of every known app against the current app rules,
+    /// moving windows whose rules now assign a different workspace.
+    fn remap_windows_to_workspaces(&mut self) {
+        let apps: Vec<(pid_t, AppInfo)> = self
+            .app_manager
+            .apps
+            .iter()
+            .map(|(&pid, app_state)| (pid, app_state.info.clone()))
+            .collect();
+        for (pid, app_info) in apps {
+            let windows: Vec<WindowId> = self.state.windows.window_ids_for_pid(pid).collect();
+            self.process_windows_for_app_rules(pid, windows, app_info);
+        }
+    }
+
     fn handle_authoritative_space_snapshot(
         &mut self,
         space_state: ForwardedSpaceState,
@@ -2451,6 +3357,9 @@ impl Reactor {
             return Ok(outcome);
         }
         if display_set_changed {
+            if !screens.is_empty() {
+                self.migrate_departed_displays(&screens);
+            }
             let active_displays: Vec<String> =
                 screens.iter().map(|screen| screen.display_uuid.clone()).collect();
             self.layout_manager.layout_engine.prune_display_state(&active_displays);
@@ -2493,9 +3402,11 @@ impl Reactor {
             else {
                 continue;
             };
-            self.layout_manager
-                .layout_engine
-                .update_space_display(space, Some(display_uuid.to_string()));
+            self.layout_manager.layout_engine.update_space_display(
+                space,
+                Some(display_uuid.to_string()),
+                screen.name.clone(),
+            );
         }
         let current_screens = self.screens_for_current_spaces();
         self.space_activation_policy
@@ -2516,6 +3427,9 @@ impl Reactor {
         if let Some(delta) = topology_window_delta {
             outcome.absorb(self.apply_topology_window_delta(delta));
         }
+        if display_set_changed {
+            self.restore_returning_displays();
+        }
         let active_windows = self.authoritative_active_space_windows();
         self.finalize_space_change(&spaces, active_windows, releases_lifecycle_refresh_quarantine);
         self.try_apply_pending_space_change();
@@ -2525,6 +3439,53 @@ impl Reactor {
         Ok(outcome)
     }
 
+    /// Remember the windows of displays missing from `screens`, and move them
+    /// to the configured workspace on the main display if there is one.
+    fn migrate_departed_displays(&mut self, screens: &[ScreenInfo]) {
+        let departed: Vec<(String, SpaceId)> = self
+            .space_state
+            .screens
+            .iter()
+            .filter(|screen| {
+                !screen.display_uuid.is_empty()
+                    && !screens.iter().any(|current| current.display_uuid == screen.display_uuid)
+            })
+            .filter_map(|screen| Some((screen.display_uuid.clone(), screen.space?)))
+            .collect();
+        let target = screens.first().and_then(|screen| Some((screen.space?, screen.frame.size)));
+        for (display_uuid, space) in departed {
+            let engine = &mut self.layout_manager.layout_engine;
+            engine.remember_departed_display(&self.state.windows, &display_uuid, space);
+            if let Some((target_space, size)) = target
+                && let Some(workspace) =
+                    &self.config.virtual_workspaces.disconnected_display_workspace
+            {
+                engine.migrate_departed_display(
+                    &mut self.state.windows,
+                    &display_uuid,
+                    target_space,
+                    size,
+                    workspace,
+                );
+            }
+        }
+    }
+
+    /// Put the windows of reconnected displays back where they were.
+    fn restore_returning_displays(&mut self) {
+        for screen in &self.space_state.screens {
+            let Some(space) = screen.space else { continue };
+            if self.layout_manager.layout_engine.restore_departed_display(
+                &mut self.state.windows,
+                &screen.display_uuid,
+                space,
+                screen.frame.size,
+            ) {
+                info!(display_uuid = %screen.display_uuid, "Restored windows of reconnected display");
+            }
+        }
+    }
+
     fn try_apply_pending_space_change(&mut self) {
         if let Some(pending) = self.pending_space_change_manager.pending_space_change.take() {
             if pending.screens.len() == self.space_state.screens.len() {
@@ -2552,8 +3513,10 @@ impl Reactor {
         known_visible: Vec<WindowId>,
         app_info: Option<AppInfo>,
     ) {
+        let new_window_ids: Vec<WindowId> = new.iter().map(|(wid, _)| *wid).collect();
         let app_info =
             app_info.or_else(|| self.app_manager.apps.get(&pid).map(|app| app.info.clone()));
+        let reconcile_outcome = self.reconcile_window_server_identities(pid, &new, &known_visible);
         let inactive_windows = self
             .state
             .windows
@@ -2615,6 +3578,7 @@ impl Reactor {
                 return;
             }
         };
+        outcome.absorb(reconcile_outcome);
         let observed_windows = new
             .into_iter()
             .map(|(wid, info)| {
@@ -2679,9 +3643,126 @@ impl Reactor {
                 authoritative_spaces,
                 active_spaces,
                 focused_window,
+                window_heuristics: &self.config.settings.window_heuristics,
             },
         ));
         self.apply_event_outcome(outcome);
+        self.place_launched_window(&new_window_ids);
+        self.swallow_launching_windows(&new_window_ids);
+    }
+
+    /// Put the first new window of a `LaunchAppIntoSelection` app next to the
+    /// selection recorded when the launch was requested, wherever the default
+    /// rules placed it.
+    fn place_launched_window(&mut self, windows: &[WindowId]) {
+        let Some(pending) = self.launch_manager.pending_launch.as_ref() else {
+            return;
+        };
+        if std::time::Instant::now() > pending.deadline {
+            debug!(bundle_id = %pending.bundle_id, "Launched app opened no window in time");
+            self.launch_manager.pending_launch = None;
+            return;
+        }
+        let Some(&wid) = windows.iter().find(|wid| {
+            self.app_manager.apps.get(&wid.pid).is_some_and(|app| {
+                app.info.bundle_id.as_deref() == Some(pending.bundle_id.as_str())
+            }) && self
+                .state
+                .windows
+                .window(**wid)
+                .is_some_and(|state| state.matches_filter(WindowFilter::EffectivelyManageable))
+        }) else {
+            return;
+        };
+        let Some(pending) = self.launch_manager.pending_launch.take() else {
+            return;
+        };
+        if self.best_space_for_window_id(wid) != Some(pending.space) {
+            debug!(
+                ?wid,
+                "Launched app opened its window on another space; leaving it there"
+            );
+            return;
+        }
+        self.layout_manager.layout_engine.insert_window_at_selection(
+            &mut self.state.windows,
+            pending.space,
+            wid,
+            pending.anchor,
+        );
+    }
+
+    /// Let new windows take over the tile of a configured app they were
+    /// launched from, e.g. a video player started from a terminal.
+    fn swallow_launching_windows(&mut self, windows: &[WindowId]) {
+        if self.config.settings.layout.swallow.apps.is_empty() {
+            return;
+        }
+        for &child in windows {
+            let Some(parent) = self.swallow_parent_for(child.pid) else {
+                continue;
+            };
+            if self
+                .layout_manager
+                .layout_engine
+                .swallow_window(&self.state.windows, parent, child)
+                .is_some()
+            {
+                debug!(
+                    ?parent,
+                    ?child,
+                    "window swallowed the window it was launched from"
+                );
+            }
+        }
+    }
+
+    /// Give a new floating window the frame its app rule asks for. Later
+    /// moves and resizes by the user are kept.
+    fn apply_floating_frame_rule(&mut self, wid: WindowId, space: SpaceId) {
+        let engine = &self.layout_manager.layout_engine;
+        if !engine.is_window_floating(wid) {
+            return;
+        }
+        let (Some(window), Some(screen)) = (
+            self.state.windows.window(wid),
+            self.space_state.screen_by_space(space),
+        ) else {
+            return;
+        };
+        let app_info = self.app_manager.apps.get(&wid.pid).map(|app| &app.info);
+        let Some(rule) = engine.app_rule_floating_frame(WindowRuleContext {
+            app_bundle_id: app_info.and_then(|info| info.bundle_id.as_deref()),
+            app_name: app_info.and_then(|info| info.localized_name.as_deref()),
+            window_title: Some(window.info.title.as_str()),
+            ax_role: window.info.ax_role.as_deref(),
+            ax_subrole: window.info.ax_subrole.as_deref(),
+            app_bundle_path: window.info.path.as_deref().and_then(|path| path.to_str()),
+            ..Default::default()
+        }) else {
+            return;
+        };
+        let frame = rule.resolve(screen.frame);
+        debug!(?wid, ?frame, "applying app rule floating frame");
+        self.layout_manager
+            .layout_engine
+            .store_floating_window_positions(space, &[(wid, frame)]);
+    }
+
+    /// The most recently focused window of the nearest ancestor process that
+    /// belongs to a swallowing app.
+    fn swallow_parent_for(&self, pid: pid_t) -> Option<WindowId> {
+        let swallow = &self.config.settings.layout.swallow;
+        let ancestor = crate::sys::process::ancestors(pid).find(|ancestor| {
+            self.app_manager.apps.get(ancestor).is_some_and(|app| {
+                swallow.swallows(app.info.bundle_id.as_deref(), app.info.localized_name.as_deref())
+            })
+        })?;
+        self.layout_manager
+            .layout_engine
+            .global_focus_history()
+            .map(|(_, wid)| wid)
+            .find(|wid| wid.pid == ancestor)
     }
 
     fn best_space_for_window(
@@ -3138,9 +4219,33 @@ impl Reactor {
         self.state.windows.is_window_server_id_native_fullscreen_suspended(wsid)
     }
 
-    fn window_center_on_known_screen(&self, wid: WindowId) -> Option<CGPoint> {
-        let window_center = self.state.windows.window(wid)?.frame_monotonic.mid();
-        self.screen_for_point(window_center).map(|_| window_center)
+    /// Where `mouse_follows_focus` moves the mouse when `wid` is focused.
+    /// Also remembers where the mouse was in the window it is leaving.
+    fn mouse_warp_target(&mut self, wid: WindowId) -> Option<CGPoint> {
+        let frame = self.state.windows.window(wid)?.frame_monotonic;
+        let cursor = window_server::current_cursor_location().ok();
+        if let Some(cursor) = cursor
+            && let Some(previous) = self.window_id_under_cursor().filter(|&w| w != wid)
+            && let Some(previous_frame) =
+                self.state.windows.window(previous).map(|w| w.frame_monotonic)
+        {
+            let windows = &self.state.windows;
+            self.cursor_history.retain(|window| windows.window(window).is_some());
+            self.cursor_history.remember(previous, previous_frame, cursor);
+        }
+
+        let display_of = |point: CGPoint| self.screen_for_point(point).map(|screen| screen.frame);
+        let same_display =
+            cursor.is_some_and(|cursor| display_of(cursor) == display_of(frame.mid()));
+        let point = mouse_warp::warp_target(
+            &self.config.settings.mouse_warp,
+            &self.cursor_history,
+            wid,
+            frame,
+            cursor,
+            same_display,
+        )?;
+        self.screen_for_point(point).map(|_| point)
     }
 
     pub fn warp_mouse(&mut self, point: CGPoint) {
@@ -3234,9 +4339,18 @@ impl Reactor {
     fn send_layout_event(&mut self, event: LayoutEvent) {
         let focus_desktop = matches!(
             event,
-            LayoutEvent::WindowRemoved(wid)
+            LayoutEvent::WindowRemoved(wid) | LayoutEvent::WindowMinimized(wid)
                 if self.layout_manager.layout_engine.focused_window() == Some(wid)
         );
+        match event {
+            LayoutEvent::WindowRemoved(removed) => {
+                self.notification_manager.forget_windows(|wid| wid == removed);
+            }
+            LayoutEvent::AppClosed(pid) => {
+                self.notification_manager.forget_windows(|wid| wid.pid == pid);
+            }
+            _ => {}
+        }
         let event_clone = event.clone();
         let response =
             self.layout_manager.layout_engine.handle_event(&mut self.state.windows, event);
@@ -3380,7 +4494,13 @@ impl Reactor {
                             .unwrap_or(false),
                     )
                 };
-                let assign_result = {
+                let ignored_by_heuristics = self.state.windows.window(*wid).and_then(|window| {
+                    heuristic_ignore_reason(&window.info, &self.config.settings.window_heuristics)
+                });
+                let assign_result = if let Some(reason) = ignored_by_heuristics {
+                    debug!(?wid, ?reason, "leaving window unmanaged");
+                    Ok(AppRuleResult::Unmanaged)
+                } else {
                     let window_metadata = self.state.windows.window(*wid).map(|window| {
                         (
                             window.info.title.clone(),
@@ -3496,6 +4616,11 @@ impl Reactor {
 
         use crate::sys::app::NSRunningApplicationExt;
 
+        if self.config.settings.safe_mode {
+            trace!("Skipping auto workspace switch for pid {} in safe mode", pid);
+            return;
+        }
+
         if self.workspace_switch_manager.active_workspace_switch.is_some() {
             trace!(
                 "Skipping auto workspace switch for pid {} because a workspace switch is in progress",
@@ -3608,47 +4733,87 @@ impl Reactor {
         app_window_id: WindowId,
         window_space: SpaceId,
     ) {
+        if self.switch_to_window_workspace(app_window_id, window_space, WorkspaceSwitchOrigin::Auto)
+        {
+            debug!("Auto-switched workspace for activated app (pid: {})", pid);
+        }
+    }
+
+    /// Switch `window_space` to the virtual workspace holding `window`, focusing it.
+    /// Returns false when the window is already on the active workspace (or has none).
+    fn switch_to_window_workspace(
+        &mut self,
+        window: WindowId,
+        window_space: SpaceId,
+        origin: WorkspaceSwitchOrigin,
+    ) -> bool {
         let workspace_state = self.layout_manager.layout_engine.virtual_workspace_manager();
         let Some(window_workspace) =
-            workspace_state.workspace_for_window(&self.state.windows, window_space, app_window_id)
+            workspace_state.workspace_for_window(&self.state.windows, window_space, window)
         else {
-            return;
+            return false;
         };
 
         let Some(current_workspace) =
             self.layout_manager.layout_engine.active_workspace(window_space)
         else {
-            return;
+            return false;
         };
 
-        if window_workspace != current_workspace {
-            let workspaces = self
-                .layout_manager
-                .layout_engine
-                .virtual_workspace_manager_mut()
-                .list_workspaces(window_space);
-            if let Some((workspace_index, _)) =
-                workspaces.iter().enumerate().find(|(_, (ws_id, _))| *ws_id == window_workspace)
-            {
-                debug!(
-                    "Auto-switching to workspace {} for activated app (pid: {})",
-                    workspace_index, pid
-                );
+        if window_workspace == current_workspace {
+            return false;
+        }
+        let workspaces = self
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager_mut()
+            .list_workspaces(window_space);
+        let Some((workspace_index, _)) =
+            workspaces.iter().enumerate().find(|(_, (ws_id, _))| *ws_id == window_workspace)
+        else {
+            return false;
+        };
+        debug!(
+            ?window,
+            ?origin,
+            "Switching to workspace {} for window",
+            workspace_index
+        );
 
-                self.store_current_floating_positions(window_space);
-                self.workspace_switch_manager
-                    .start_workspace_switch(WorkspaceSwitchOrigin::Auto);
+        self.store_current_floating_positions(window_space);
+        self.workspace_switch_manager.start_workspace_switch(origin);
 
-                let response = self.layout_manager.layout_engine.switch_to_workspace_with_focus(
-                    &self.state.windows,
-                    window_space,
-                    workspace_index,
-                    app_window_id,
-                );
-                self.handle_layout_response(response, Some(window_space));
-                self.update_event_tap_layout_mode();
-            }
-        }
+        let previous_index = self.layout_manager.layout_engine.active_workspace_idx(window_space);
+        let response = self.layout_manager.layout_engine.switch_to_workspace_with_focus(
+            &self.state.windows,
+            window_space,
+            workspace_index,
+            window,
+        );
+        self.workspace_switch_manager.note_slide(
+            window_space,
+            previous_index,
+            self.layout_manager.layout_engine.active_workspace_idx(window_space),
+        );
+        self.handle_layout_response(response, Some(window_space));
+        self.update_event_tap_layout_mode();
+        true
+    }
+
+    /// The most recently focused window, other than the current one, that still exists
+    /// and sits on an active space, along with that space.
+    fn previous_global_focus_target(&self) -> Option<(WindowId, SpaceId)> {
+        let current = self
+            .main_window()
+            .or_else(|| self.layout_manager.layout_engine.focused_window());
+        self.layout_manager
+            .layout_engine
+            .global_focus_history()
+            .filter(|&(_, wid)| Some(wid) != current && self.state.windows.window(wid).is_some())
+            .find_map(|(recorded_space, wid)| {
+                let space = self.best_space_for_window_id(wid).unwrap_or(recorded_space);
+                self.is_space_active(space).then_some((wid, space))
+            })
     }
 
     fn handle_layout_response(
@@ -3836,6 +5001,7 @@ impl Reactor {
                 space,
                 focus_window,
             );
+            self.floating_layer.request_restack();
         }
         let mut windows_by_app_and_screen = HashMap::default();
         for &wid in &raise_windows {
@@ -3853,7 +5019,7 @@ impl Reactor {
                     self.workspace_switch_manager.pending_workspace_mouse_warp = Some(wid);
                     None
                 } else {
-                    self.window_center_on_known_screen(wid)
+                    self.mouse_warp_target(wid)
                 }
             } else {
                 None
@@ -3873,6 +5039,192 @@ impl Reactor {
         }
     }
 
+    /// Raises the visible floating windows of the active workspaces above the
+    /// tiled ones, then hands focus back to the window that had it.
+    fn restack_floating_windows(&mut self) {
+        if !self.config.settings.floating_on_top || self.is_in_drag() {
+            return;
+        }
+        let focused = self.main_window().filter(|&wid| self.is_window_on_active_space(wid));
+        let floating: Vec<WindowId> = self
+            .state
+            .windows
+            .iter_windows()
+            .filter(|&(wid, state)| {
+                Some(wid) != focused
+                    && self.layout_manager.layout_engine.is_window_floating(wid)
+                    && state
+                        .info
+                        .sys_id
+                        .is_some_and(|wsid| self.state.windows.is_window_visible(wsid))
+                    && self.best_space_for_window_state(state).is_some_and(|space| {
+                        self.is_space_active(space)
+                            && self.layout_manager.layout_engine.is_window_in_active_workspace(
+                                &self.state.windows,
+                                space,
+                                wid,
+                            )
+                    })
+            })
+            .map(|(wid, _)| wid)
+            .collect();
+        if floating.is_empty() {
+            return;
+        }
+        trace!(?floating, "Restacking floating windows");
+
+        let mut app_handles = HashMap::default();
+        for &wid in floating.iter().chain(focused.iter()) {
+            self.insert_app_handle_for_window(&mut app_handles, wid);
+        }
+        let msg = raise_manager::Event::RaiseRequest(RaiseRequest {
+            raise_windows: floating_layer::raise_groups(floating),
+            focus_window: focused.map(|wid| (wid, None)),
+            app_handles,
+            focus_quiet: Quiet::Yes,
+        });
+        if let Err(e) = self.communication_manager.raise_manager_tx.try_send(msg) {
+            warn!("Failed to send floating restack request to raise manager: {}", e);
+        }
+    }
+
+    /// Raises the windows kept on top by `toggle_always_on_top` over a newly
+    /// focused window, without focusing them. Windows that were closed or
+    /// tiled again stop being kept on top.
+    fn raise_always_on_top_windows(&mut self) {
+        let RiftState { windows, always_on_top } = &mut self.state;
+        let engine = &self.layout_manager.layout_engine;
+        always_on_top.retain(|&wid| windows.contains_window(wid) && engine.is_window_floating(wid));
+        if self.state.always_on_top.is_empty() || self.is_in_drag() {
+            return;
+        }
+        let focused = self.main_window();
+        if !self.floating_layer.focus_moved_to(focused)
+            || focused.is_some_and(|wid| self.state.always_on_top.contains(&wid))
+        {
+            return;
+        }
+        let mut on_top: Vec<WindowId> = self
+            .state
+            .always_on_top
+            .iter()
+            .copied()
+            .filter(|&wid| self.is_window_on_active_space(wid))
+            .collect();
+        on_top.sort_unstable();
+        for wid in on_top {
+            if let Some(app) = self.app_manager.apps.get(&wid.pid)
+                && let Err(err) = app.handle.send(Request::RaiseWithoutFocus(wid))
+            {
+                warn!(?wid, "Failed to send raise request: {}", err);
+            }
+        }
+    }
+
+    /// How a floating window moved on `space` should snap, if the snap
+    /// modifier is held right now.
+    fn floating_snap(&self, space: Option<SpaceId>) -> Option<FloatingSnap> {
+        let settings = &self.config.settings.window_snapping;
+        let modifiers = settings.floating_snap_modifier.as_ref()?.modifiers();
+        let screen = self.space_state.screen_by_space(space?)?;
+        let flags = CGEventSource::flags_state(CGEventSourceStateID::HIDSystemState);
+        gesture_tap::modifier_held(modifiers, flags).then_some(FloatingSnap {
+            area: screen.frame,
+            grid: settings.floating_snap_grid,
+            distance: settings.floating_snap_distance,
+        })
+    }
+
+    /// Moves a dragged floating window onto the grid or a neighbouring edge
+    /// while the snap modifier is held, so it lands where it is shown.
+    fn snap_floating_drag(&mut self, wid: WindowId, space: SpaceId, frame: CGRect) {
+        let Some(snap) = self.floating_snap(Some(space)) else {
+            return;
+        };
+        let Some(workspace) = self.layout_manager.layout_engine.active_workspace(space) else {
+            return;
+        };
+        let others: Vec<_> = self
+            .layout_manager
+            .layout_engine
+            .workspace_floating_positions(space, workspace)
+            .into_iter()
+            .filter(|(other, _)| *other != wid)
+            .map(|(_, frame)| frame)
+            .collect();
+        let snapped = snap.apply(frame, &others);
+        if snapped.origin == frame.origin {
+            return;
+        }
+        trace!(?wid, ?snapped, "snapping dragged floating window");
+        if let DragState::Active { session } | DragState::PendingSwap { session, .. } =
+            &mut self.drag_manager.drag_state
+            && session.window == wid
+        {
+            session.last_frame = snapped;
+        }
+        let window_server_id = self.state.windows.window_mut(wid).and_then(|window| {
+            window.frame_monotonic = snapped;
+            window.info.sys_id
+        });
+        let transaction = if let Some(window_server_id) = window_server_id {
+            let transaction = self.transaction_manager.generate_next_txid(window_server_id);
+            self.transaction_manager.store_txid(window_server_id, transaction, snapped);
+            transaction
+        } else {
+            TransactionId::default()
+        };
+        if let Some(app) = self.app_manager.apps.get(&wid.pid)
+            && let Err(error) =
+                app.handle.send(Request::SetWindowPos(wid, snapped.origin, transaction, true))
+        {
+            warn!(window = ?wid, %error, "failed to snap dragged floating window");
+        }
+    }
+
+    /// Tracks the tile under the cursor while a floating window is dragged
+    /// and previews where it would be tiled on drop.
+    fn update_floating_drop_zone(&mut self, wid: WindowId, space: SpaceId) {
+        let Some(point) = window_server::current_cursor_location().ok() else {
+            return;
+        };
+        let Some(screen) = self.space_state.screen_by_space(space).map(|screen| screen.frame)
+        else {
+            return;
+        };
+        let Some(zone) =
+            self.layout_manager.layout_engine.drop_zone_at_position(space, wid, point, screen)
+        else {
+            self.take_floating_drop();
+            return;
+        };
+        trace!(?wid, ?zone, "Floating window over drop zone");
+        self.drag_manager.floating_drop = Some((space, point));
+        let target_server_id =
+            self.state.windows.window(zone.target).and_then(|window| window.info.sys_id);
+        self.send_drop_zone_preview(Some(drop_zone_overlay::DropZonePreview {
+            zone,
+            target_server_id,
+        }));
+    }
+
+    /// Forgets the pending floating drop, hiding its preview.
+    fn take_floating_drop(&mut self) -> Option<(SpaceId, CGPoint)> {
+        let drop = self.drag_manager.floating_drop.take();
+        if drop.is_some() {
+            self.send_drop_zone_preview(None);
+        }
+        drop
+    }
+
+    fn send_drop_zone_preview(&self, preview: Option<drop_zone_overlay::DropZonePreview>) {
+        if let Some(tx) = &self.communication_manager.drop_zone_overlay_tx
+            && let Err(e) = tx.try_send(drop_zone_overlay::Event::Update(preview))
+        {
+            warn!("Failed to send drop zone preview: {}", e);
+        }
+    }
+
     fn collect_drag_swap_candidates(
         &self,
         wid: WindowId,
@@ -3906,6 +5258,10 @@ impl Reactor {
             trace!(?wid, "Skipping swap: not in drag (mouse up received)");
             return;
         }
+        if self.config.settings.safe_mode {
+            trace!(?wid, "Skipping swap: drag-swap is disabled in safe mode");
+            return;
+        }
 
         let server_id = {
             let Some(window) = self.state.windows.window(wid) else {
@@ -3953,6 +5309,8 @@ impl Reactor {
                 "Resetting drag swap tracking after space change"
             );
             self.drag_manager.drag_swap_manager.reset();
+            self.take_floating_drop();
+            self.revert_drag_swap_preview();
             return;
         }
 
@@ -3964,6 +5322,17 @@ impl Reactor {
             return;
         }
 
+        if self.layout_manager.layout_engine.is_window_floating(wid) {
+            self.snap_floating_drag(wid, space, new_frame);
+        }
+
+        if self.config.settings.window_snapping.floating_drop_zones
+            && self.layout_manager.layout_engine.is_window_floating(wid)
+        {
+            self.update_floating_drop_zone(wid, space);
+            return;
+        }
+
         let candidates = self.collect_drag_swap_candidates(wid, space);
 
         let previous_pending = self.get_pending_drag_swap();
@@ -3994,6 +5363,7 @@ impl Reactor {
             }
 
             self.drag_manager.skip_layout_for_window = Some(wid);
+            self.preview_drag_swap(space, target_wid);
             return;
         }
 
@@ -4005,6 +5375,7 @@ impl Reactor {
                 ?pending_target,
                 "Clearing pending drag swap; overlap ended before MouseUp"
             );
+            self.revert_drag_swap_preview();
             if let Some(session) = self.take_active_drag_session() {
                 self.drag_manager.drag_state = DragState::Active { session };
             } else {
@@ -4018,6 +5389,199 @@ impl Reactor {
         // wait for mouse::up before doing *anything*
     }
 
+    /// Moves the pending swap target into the dragged window's tile, without
+    /// touching the layout, to show the arrangement the drop would produce.
+    fn preview_drag_swap(&mut self, space: SpaceId, target: WindowId) {
+        if !self.config.settings.window_snapping.drag_swap_preview
+            || self.drag_manager.swap_preview.is_some_and(|(previewed, _)| previewed == target)
+        {
+            return;
+        }
+        self.revert_drag_swap_preview();
+        let Some(origin) = self.drag_manager.origin_frame() else {
+            return;
+        };
+        let Some(tile) = self.state.windows.window(target).map(|window| window.frame_monotonic)
+        else {
+            return;
+        };
+        trace!(?target, ?origin, "Previewing drag swap");
+        self.drag_manager.swap_preview = Some((target, tile));
+        AnimationManager::animate_layout(self, space, &[(target, origin)], false, None);
+    }
+
+    /// Puts a previewed swap target back in its own tile.
+    fn revert_drag_swap_preview(&mut self) {
+        let Some((target, tile)) = self.drag_manager.swap_preview.take() else {
+            return;
+        };
+        let Some(space) = self.best_space_for_window_id(target) else {
+            return;
+        };
+        trace!(?target, "Reverting drag swap preview");
+        AnimationManager::animate_layout(self, space, &[(target, tile)], false, None);
+    }
+
+    /// The focused window and its space, when it is tiled and can be swapped.
+    fn swap_hint_origin(&self) -> Option<(SpaceId, WindowId)> {
+        let focused = self.main_window().or(self.layout_manager.layout_engine.focused_window())?;
+        if self.layout_manager.layout_engine.is_window_floating(focused) {
+            return None;
+        }
+        Some((self.best_space_for_window_id(focused)?, focused))
+    }
+
+    /// Every window showing on an active space, managed or not.
+    fn focus_hint_candidates(&self) -> Vec<(WindowId, CGRect)> {
+        let engine = &self.layout_manager.layout_engine;
+        self.state
+            .windows
+            .iter_windows()
+            .filter_map(|(wid, window)| {
+                let wsid = window.info.sys_id?;
+                if window.info.is_minimized || !window_server::window_is_ordered_in(wsid) {
+                    return None;
+                }
+                let space = self
+                    .best_space_for_window_state(window)
+                    .filter(|space| self.is_space_active(*space))?;
+                // Windows of hidden workspaces are still ordered in, just parked
+                // out of sight.
+                if window.is_manageable
+                    && !engine.is_window_in_active_workspace(&self.state.windows, space, wid)
+                {
+                    return None;
+                }
+                Some((wid, window.frame_monotonic))
+            })
+            .collect()
+    }
+
+    /// Labels `windows` with hint keys and binds the keys until one is picked
+    /// or the hints are cancelled.
+    fn show_hints(&mut self, action: HintAction, windows: &[(WindowId, CGRect)]) -> EventOutcome {
+        let assigned = hint_overlay::assign_hints(windows);
+        if assigned.is_empty() {
+            debug!(?action, "No windows to hint");
+            return self.hide_hints();
+        }
+        let hints = assigned
+            .iter()
+            .filter_map(|&(key, wid)| {
+                let window = self.state.windows.window(wid)?;
+                Some(hint_overlay::Hint {
+                    key,
+                    window_server_id: window.info.sys_id,
+                    frame: window.frame_monotonic,
+                })
+            })
+            .collect();
+        self.send_hints(hints);
+        let keys = assigned.iter().map(|(key, _)| *key).collect();
+        self.hints = Some((action, assigned));
+        EventOutcome::finalized_event(None, false, false, false)
+            .with_wm_event(crate::actor::wm_controller::WmEvent::SetHintKeys(keys))
+    }
+
+    fn hide_hints(&mut self) -> EventOutcome {
+        self.hints = None;
+        self.send_hints(Vec::new());
+        EventOutcome::finalized_event(None, false, false, false)
+            .with_wm_event(crate::actor::wm_controller::WmEvent::SetHintKeys(Vec::new()))
+    }
+
+    fn send_hints(&self, hints: Vec<hint_overlay::Hint>) {
+        if let Some(tx) = &self.communication_manager.hint_overlay_tx
+            && let Err(e) = tx.try_send(hint_overlay::Event::Update(hints))
+        {
+            warn!("Failed to send window hints: {}", e);
+        }
+    }
+
+    fn edge_workspace_switch_delay(&self) -> Option<Duration> {
+        match self.config.settings.window_snapping.edge_workspace_switch_delay_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    /// Starts, keeps or cancels the wait for a drag held against a screen edge.
+    fn track_drag_edge(&mut self) {
+        let Some(delay) = self.edge_workspace_switch_delay().filter(|_| self.is_in_drag()) else {
+            self.drag_manager.edge_dwell.reset();
+            return;
+        };
+        let edge = window_server::current_cursor_location().ok().and_then(|cursor| {
+            self.screen_for_point(cursor)
+                .and_then(|screen| DragEdge::at(cursor, screen.frame))
+        });
+        self.drag_manager.edge_dwell.update(edge, std::time::Instant::now(), delay);
+    }
+
+    /// Carries the dragged window to the adjacent workspace once it has been
+    /// held against the left or right screen edge long enough.
+    fn flush_drag_edge_dwell(&mut self) {
+        self.track_drag_edge();
+        let Some(delay) = self.edge_workspace_switch_delay() else {
+            return;
+        };
+        let Some(edge) = self.drag_manager.edge_dwell.take_due(std::time::Instant::now(), delay)
+        else {
+            return;
+        };
+        // Swap targets belong to the workspace being left.
+        let Some(session) = self.take_active_drag_session() else {
+            return;
+        };
+        let window = session.window;
+        self.drag_manager.drag_state = DragState::Active { session };
+        self.drag_manager.drag_swap_manager.reset();
+
+        let workspace = match edge {
+            DragEdge::Left => "prev",
+            DragEdge::Right => "next",
+        };
+        debug!(
+            ?window,
+            workspace, "carrying dragged window across the screen edge"
+        );
+        self.handle_event(Event::Command(Command::Layout(
+            layout::LayoutCommand::MoveWindowToWorkspace {
+                workspace: WorkspaceSelector::Name(workspace.to_string()),
+                follow: true,
+                window_id: Some(window.idx.get()),
+            },
+        )));
+    }
+
+    /// Remembers the tiled window whose border the mouse went down on, if any.
+    fn begin_interactive_resize(&mut self, point: CGPoint) {
+        self.drag_manager.interactive_resize = None;
+        let Some(space) = self.screen_for_point(point).and_then(|screen| screen.space) else {
+            return;
+        };
+        let under_cursor = self.window_id_under_cursor();
+        let mut candidates: Vec<InteractiveResize> = self
+            .layout_manager
+            .layout_engine
+            .windows_in_active_workspace(&self.state.windows, space)
+            .into_iter()
+            .filter(|&wid| !self.layout_manager.layout_engine.is_window_floating(wid))
+            .filter_map(|wid| {
+                let frame = self.state.windows.window(wid)?.frame_monotonic;
+                let edges = ResizeEdges::at(frame, point)?;
+                Some(InteractiveResize { window: wid, edges })
+            })
+            .collect();
+        // Borders of neighbouring windows sit a gap apart; prefer the window
+        // the press actually landed on.
+        candidates.sort_by_key(|resize| Some(resize.window) != under_cursor);
+        self.drag_manager.interactive_resize = candidates.first().copied();
+        if let Some(resize) = self.drag_manager.interactive_resize {
+            trace!(?resize, "mouse down on a tiled window border");
+        }
+    }
+
     pub(crate) fn window_id_under_cursor(&self) -> Option<WindowId> {
         self.tracked_window_under_cursor().map(|(_, wid)| wid)
     }
@@ -4299,6 +5863,55 @@ impl Reactor {
     // transient empty AX window list as authoritative removal.
     fn force_refresh_all_windows(&mut self) { self.request_visible_windows_for_apps(true); }
 
+    /// Brings back windows whose frames lie outside every screen, which often
+    /// happens when displays change while the machine sleeps. Windows rift
+    /// parked off-screen for an inactive workspace are left where they are.
+    fn rescue_offscreen_windows(&mut self) -> EventOutcome {
+        let mut outcome = EventOutcome::finalized_event(None, true, false, false);
+        let Some(space) = self.command_context_space() else {
+            debug!("rescue_windows ignored: the current space is not managed");
+            return outcome;
+        };
+        let Some(screen) = self.space_state.screen_by_space(space).map(|screen| screen.frame)
+        else {
+            return outcome;
+        };
+        let screens: Vec<CGRect> =
+            self.space_state.screens.iter().map(|screen| screen.frame).collect();
+        let engine = &self.layout_manager.layout_engine;
+        let stranded: Vec<(WindowId, CGRect)> = self
+            .state
+            .windows
+            .iter_windows()
+            .filter(|(_, window)| {
+                window.matches_filter(WindowFilter::EffectivelyManageable)
+                    && !window.info.is_minimized
+                    && !crash_restore::is_on_screen(window.frame_monotonic, &screens)
+            })
+            .filter(|&(wid, _)| {
+                self.state.windows.workspace_info_for_window(wid).is_none_or(|info| {
+                    engine.active_workspace(info.space) == Some(info.workspace_id)
+                })
+            })
+            .map(|(wid, window)| (wid, window.frame_monotonic))
+            .collect();
+
+        for (wid, frame) in stranded {
+            info!(?wid, ?frame, "Rescuing off-screen window");
+            self.reassign_window_to_authoritative_space(wid, space);
+            if self.layout_manager.layout_engine.is_window_floating(wid) {
+                outcome.absorb(command_workflow::handle_command_reactor_place_float(
+                    &mut self.state,
+                    &mut self.layout_manager,
+                    wid,
+                    space,
+                    floating_frame(screen, frame, None),
+                ));
+            }
+        }
+        outcome
+    }
+
     fn has_user_space_context(&self) -> bool {
         self.raw_command_space().is_some_and(|space| !self.is_fullscreen_space(space))
     }
@@ -4316,6 +5929,14 @@ impl Reactor {
         }
     }
 
+    fn request_set_minimized(&self, wid: WindowId, minimized: bool) {
+        if let Some(app) = self.app_manager.apps.get(&wid.pid)
+            && let Err(err) = app.handle.send(Request::SetMinimized(wid, minimized))
+        {
+            warn!(?wid, minimized, "Failed to send minimize request: {}", err);
+        }
+    }
+
     pub(crate) fn main_window(&self) -> Option<WindowId> { self.main_window_tracker.main_window() }
 
     fn main_window_space(&self) -> Option<SpaceId> {
@@ -4463,9 +6084,14 @@ impl Reactor {
                 self.screen_for_direction_from_point(origin, *direction)
             }
             DisplaySelector::Index(index) => self.screens_in_physical_order().get(*index).copied(),
-            DisplaySelector::Uuid(uuid) => {
-                self.space_state.screens.iter().find(|screen| screen.display_uuid == *uuid)
-            }
+            DisplaySelector::Uuid(key) => self
+                .space_state
+                .screens
+                .iter()
+                .find(|screen| screen.display_uuid == *key)
+                .or_else(|| {
+                    self.space_state.screens.iter().find(|screen| screen.matches_display(key))
+                }),
         }
     }
 
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use tokio::sync::mpsc;
@@ -8,12 +8,24 @@ use super::TransactionId;
 use crate::actor::app::{AppThreadHandle, Request, WindowId, pid_t};
 use crate::actor::reactor::Reactor;
 use crate::common::collections::HashMap;
-use crate::sys::geometry::{Round, SameAs};
+use crate::common::config::AnimationEasing;
+use crate::common::metrics;
+use crate::sys::geometry::{CGRectExt, Round, SameAs};
 use crate::sys::power;
 use crate::sys::screen::SpaceId;
 use crate::sys::timer::Timer;
 use crate::sys::window_server::WindowServerId;
 
+const DEFAULT_FPS: f64 = 100.0;
+/// With `animation_adaptive`, animations moving more windows than this run at
+/// a proportionally lower frame rate, since every frame is one AX write per
+/// window.
+const ADAPTIVE_WINDOW_BUDGET: usize = 8;
+/// Weight of the newest sample in the running average of frame lateness.
+const LATENESS_SMOOTHING: f64 = 0.25;
+
+mod easing;
+
 pub type Sender = mpsc::UnboundedSender<Message>;
 pub type Receiver = mpsc::UnboundedReceiver<Message>;
 
@@ -26,18 +38,46 @@ pub enum Message {
 #[derive(Debug, Default)]
 pub struct AnimationManager {
     active: Option<ActiveAnimation>,
+    lateness: FrameLateness,
 }
 
 #[derive(Debug)]
 struct ActiveAnimation {
     animation: Animation,
     next_frame: u32,
+    due: Instant,
+}
+
+/// Running average of how late frames went out compared to when they were
+/// scheduled. Frames that keep missing their slot mean the system, or the
+/// apps being animated, cannot keep up with the configured frame rate.
+#[derive(Debug, Default)]
+struct FrameLateness {
+    average: Option<Duration>,
+}
+
+impl FrameLateness {
+    fn record(&mut self, late: Duration) {
+        self.average = Some(match self.average {
+            Some(average) => {
+                average.mul_f64(1.0 - LATENESS_SMOOTHING) + late.mul_f64(LATENESS_SMOOTHING)
+            }
+            None => late,
+        });
+    }
+
+    /// Whether frames have recently been later than one `interval` on average.
+    fn is_behind(&self, interval: Duration) -> bool {
+        self.average.is_some_and(|average| average > interval)
+    }
 }
 
 #[derive(Debug)]
 pub struct Animation {
     interval: Duration,
     frames: u32,
+    adaptive: bool,
+    easing: AnimationEasing,
     windows: Vec<AnimatedWindow>,
     handled_windows: Vec<WindowId>,
 }
@@ -48,12 +88,17 @@ struct AnimatedWindow {
     wid: WindowId,
     start: CGRect,
     finish: CGRect,
+    /// Where the window is put once the animation is over, when that differs
+    /// from where the animation leaves it.
+    park: Option<CGRect>,
     is_focus: bool,
     txid: TransactionId,
 }
 
 impl AnimatedWindow {
-    fn frame_after(&self, frame: u32, total_frames: u32) -> CGRect {
+    fn resting_frame(&self) -> CGRect { self.park.unwrap_or(self.finish) }
+
+    fn frame_after(&self, frame: u32, total_frames: u32, easing: AnimationEasing) -> CGRect {
         if frame == 0 {
             return if self.is_focus {
                 CGRect {
@@ -66,7 +111,7 @@ impl AnimatedWindow {
         }
 
         let t = f64::from(frame) / f64::from(total_frames);
-        let mut rect = get_frame(self.start, self.finish, t);
+        let mut rect = get_frame(self.start, self.finish, t, easing);
         if self.is_focus || frame * 2 >= total_frames {
             rect.size = self.finish.size;
         } else {
@@ -105,7 +150,14 @@ impl AnimationManager {
 
     pub fn handle_message(&mut self, message: Message) -> Option<Duration> {
         match message {
-            Message::Replace(animation) => {
+            Message::Replace(mut animation) => {
+                if animation.adaptive && self.lateness.is_behind(animation.interval) {
+                    debug!(
+                        lateness = ?self.lateness.average,
+                        "Halving animation frame rate under load"
+                    );
+                    animation.throttle(0.5);
+                }
                 self.active = match self.active.take() {
                     Some(active) => Some(active.replace_with(animation)),
                     None => ActiveAnimation::start(animation),
@@ -122,6 +174,11 @@ impl AnimationManager {
 
     pub fn tick(&mut self) -> Option<Duration> {
         let active = self.active.as_mut()?;
+        let late = Instant::now().saturating_duration_since(active.due);
+        self.lateness.record(late);
+        if active.animation.adaptive {
+            active.catch_up(late);
+        }
         active.send_next_frame();
         if active.is_complete() {
             let active = self.active.take().expect("animation disappeared while ticking");
@@ -148,7 +205,10 @@ impl AnimationManager {
         let Some(active_ws) = reactor.layout_manager.layout_engine.active_workspace(space) else {
             return false;
         };
-        let mut anim = Animation::new();
+        let settings = &reactor.config.settings;
+        let mut anim = Animation::new(settings.animation_duration, settings.animation_fps);
+        anim.set_adaptive(settings.animation_adaptive);
+        anim.set_easing(settings.animation_easing);
         let mut animated_count = 0;
         let mut any_frame_changed = false;
 
@@ -161,6 +221,11 @@ impl AnimationManager {
                 );
                 continue;
             }
+            if reactor.state.windows.is_window_native_fullscreen_suspended(wid) {
+                anim.mark_handled(wid);
+                trace!(?wid, "Skipping layout update for native fullscreen window");
+                continue;
+            }
 
             let target_frame = target_frame.round();
             let (current_frame, window_server_id, txid) = {
@@ -206,8 +271,12 @@ impl AnimationManager {
                 .virtual_workspace_manager()
                 .workspace_for_window(&reactor.state.windows, space, wid)
                 .is_some_and(|ws| ws == active_ws);
+            let blacklisted = reactor
+                .config
+                .settings
+                .is_animation_blacklisted(app_state.info.bundle_id.as_deref());
 
-            if is_active {
+            if is_active && !blacklisted {
                 trace!(?wid, ?current_frame, ?target_frame, "Animating visible window");
                 anim.add_window(&app_state.handle, wid, current_frame, target_frame, false, txid);
                 animated_count += 1;
@@ -220,7 +289,8 @@ impl AnimationManager {
                     ?wid,
                     ?current_frame,
                     ?target_frame,
-                    "Direct positioning hidden window"
+                    hidden = !is_active,
+                    "Direct positioning window"
                 );
                 if let Some(wsid) = window_server_id {
                     reactor.transaction_manager.update_txid_entries([(wsid, txid, target_frame)]);
@@ -228,7 +298,7 @@ impl AnimationManager {
                 if let Err(e) =
                     app_state.handle.send(Request::SetWindowFrame(wid, target_frame, txid, true))
                 {
-                    debug!(?wid, ?e, "Failed to send frame request for hidden window");
+                    debug!(?wid, ?e, "Failed to send direct frame request");
                     continue;
                 }
             }
@@ -246,6 +316,10 @@ impl AnimationManager {
                 .layout_specific_animate_settings(space)
                 .unwrap_or(reactor.config.settings.animate);
             let skip_anim = is_resize || !layout_animate || low_power;
+            if reactor.config.settings.animation_adaptive && animated_count > ADAPTIVE_WINDOW_BUDGET
+            {
+                anim.throttle(ADAPTIVE_WINDOW_BUDGET as f64 / animated_count as f64);
+            }
 
             if let Some(tx) = &reactor.animation_tx {
                 let message = if skip_anim {
@@ -281,6 +355,10 @@ impl AnimationManager {
                 trace!(?wid, "Skipping layout update for window currently being dragged");
                 continue;
             }
+            if reactor.state.windows.is_window_native_fullscreen_suspended(wid) {
+                trace!(?wid, "Skipping layout update for native fullscreen window");
+                continue;
+            }
 
             let is_hidden = !reactor.layout_manager.layout_engine.is_window_in_active_workspace(
                 &reactor.state.windows,
@@ -369,6 +447,124 @@ impl AnimationManager {
 
         any_frame_changed
     }
+
+    /// Lays out `space` after a workspace switch by sliding the outgoing
+    /// workspace off `screen` and the incoming one in from the opposite side.
+    /// Outgoing windows end up at their hidden positions once the slide is
+    /// over. The transaction targets are the final frames, so layout passes
+    /// made while the slide runs see them as already handled. When
+    /// `previewed`, incoming windows continue from where the swipe preview
+    /// left them.
+    /// Falls back to an instant layout when animations are turned off.
+    pub fn slide_layout(
+        reactor: &mut Reactor,
+        space: SpaceId,
+        screen: CGRect,
+        layout: &[(WindowId, CGRect)],
+        forward: bool,
+        previewed: bool,
+        skip_wid: Option<WindowId>,
+    ) -> bool {
+        let animate = reactor
+            .layout_manager
+            .layout_engine
+            .layout_specific_animate_settings(space)
+            .unwrap_or(reactor.config.settings.animate);
+        if !animate || power::is_low_power_mode_enabled() {
+            return Self::instant_layout(reactor, space, layout, skip_wid);
+        }
+        let settings = &reactor.config.settings;
+        let mut anim = Animation::new(settings.animation_duration, settings.animation_fps);
+        anim.set_adaptive(settings.animation_adaptive);
+        anim.set_easing(settings.animation_easing);
+        let shift = if forward {
+            -screen.size.width
+        } else {
+            screen.size.width
+        };
+        let mut remaining = Vec::new();
+        let mut any_frame_changed = false;
+
+        for &(wid, target_frame) in layout {
+            if skip_wid == Some(wid)
+                || reactor.state.windows.is_window_native_fullscreen_suspended(wid)
+            {
+                remaining.push((wid, target_frame));
+                continue;
+            }
+            let is_incoming = reactor.layout_manager.layout_engine.is_window_in_active_workspace(
+                &reactor.state.windows,
+                space,
+                wid,
+            );
+            let target_frame = target_frame.round();
+            let Some(window) = reactor.state.windows.window_mut(wid) else {
+                continue;
+            };
+            let current_frame = window.frame_monotonic;
+            let on_screen = screen.intersection(&current_frame).area() * 2.0 > current_frame.area();
+            let (start, finish, park) = if is_incoming
+                && previewed
+                && screen.intersection(&current_frame).area() > 0.0
+                && !target_frame.same_as(current_frame)
+            {
+                // A swipe preview has already brought this window partway in.
+                (current_frame, target_frame, None)
+            } else if is_incoming && !on_screen {
+                let mut start = target_frame;
+                start.origin.x -= shift;
+                (start, target_frame, None)
+            } else if !is_incoming && on_screen && !target_frame.same_as(current_frame) {
+                let mut finish = current_frame;
+                finish.origin.x += shift;
+                (current_frame, finish, Some(target_frame))
+            } else {
+                remaining.push((wid, target_frame));
+                continue;
+            };
+            let Some(wsid) = window.info.sys_id else {
+                remaining.push((wid, target_frame));
+                continue;
+            };
+            let Some(app_state) = reactor.app_manager.apps.get(&wid.pid) else {
+                debug!(?wid, "Skipping slide for window - app no longer exists");
+                continue;
+            };
+            if reactor
+                .config
+                .settings
+                .is_animation_blacklisted(app_state.info.bundle_id.as_deref())
+            {
+                remaining.push((wid, target_frame));
+                continue;
+            }
+            window.frame_monotonic = target_frame;
+            let txid = reactor.transaction_manager.generate_next_txid(wsid);
+            reactor.transaction_manager.update_txid_entries([(wsid, txid, target_frame)]);
+            trace!(?wid, ?start, ?finish, incoming = is_incoming, "Sliding window");
+            anim.add_parked_window(&app_state.handle, wid, start, finish, park, txid);
+            any_frame_changed = true;
+        }
+
+        any_frame_changed |= Self::instant_layout(reactor, space, &remaining, skip_wid);
+        if anim.is_empty() {
+            return any_frame_changed;
+        }
+        if reactor.config.settings.animation_adaptive && anim.windows.len() > ADAPTIVE_WINDOW_BUDGET
+        {
+            anim.throttle(ADAPTIVE_WINDOW_BUDGET as f64 / anim.windows.len() as f64);
+        }
+        match &reactor.animation_tx {
+            Some(tx) => {
+                if let Err(err) = tx.send(Message::Replace(anim)) {
+                    let (Message::Replace(animation) | Message::SkipToEnd(animation)) = err.0;
+                    animation.skip_to_end();
+                }
+            }
+            None => anim.skip_to_end(),
+        }
+        any_frame_changed
+    }
 }
 
 impl ActiveAnimation {
@@ -377,7 +573,8 @@ impl ActiveAnimation {
             return None;
         }
         animation.begin();
-        Some(Self { animation, next_frame: 1 })
+        let due = Instant::now() + animation.interval;
+        Some(Self { animation, next_frame: 1, due })
     }
 
     fn replace_with(self, mut next: Animation) -> Self {
@@ -385,12 +582,28 @@ impl ActiveAnimation {
         let continuing = next.patch_starts_from(&current);
         next.begin_windows_not_in(&continuing);
         next.carry_over(self.animation, &current);
-        Self { animation: next, next_frame: 1 }
+        let due = Instant::now() + next.interval;
+        Self {
+            animation: next,
+            next_frame: 1,
+            due,
+        }
+    }
+
+    /// Drop the frames that should already have been shown `late` ago, so a
+    /// slow system shortens the animation instead of stretching it.
+    fn catch_up(&mut self, late: Duration) {
+        if self.animation.interval.is_zero() {
+            return;
+        }
+        let behind = (late.as_secs_f64() / self.animation.interval.as_secs_f64()) as u32;
+        self.next_frame = (self.next_frame + behind).min(self.animation.frames);
     }
 
     fn send_next_frame(&mut self) {
         self.animation.send_frame(self.next_frame);
         self.next_frame += 1;
+        self.due = Instant::now() + self.animation.interval;
     }
 
     fn is_complete(&self) -> bool { self.next_frame > self.animation.frames }
@@ -400,24 +613,43 @@ impl ActiveAnimation {
         self.animation
             .windows
             .iter()
-            .map(|window| (window.wid, window.frame_after(frame, self.animation.frames)))
+            .map(|window| {
+                let frame = window.frame_after(frame, self.animation.frames, self.animation.easing);
+                (window.wid, frame)
+            })
             .collect()
     }
 }
 
 impl Animation {
-    pub fn new() -> Self {
-        const FPS: f64 = 100.0;
-        const DURATION: f64 = 0.30;
-        let interval = Duration::from_secs_f64(1.0 / FPS);
+    /// An animation lasting `duration` seconds at `fps` frames per second. A
+    /// non-positive `fps` falls back to the default rate.
+    pub fn new(duration: f64, fps: f64) -> Self {
+        let fps = if fps > 0.0 { fps } else { DEFAULT_FPS };
+        let duration = duration.max(0.0);
+        let frames = ((duration * fps).round() as u32).max(1);
         Self {
-            interval,
-            frames: (DURATION * FPS).round() as u32,
+            interval: Duration::from_secs_f64(duration / f64::from(frames)),
+            frames,
+            adaptive: false,
+            easing: AnimationEasing::default(),
             windows: vec![],
             handled_windows: vec![],
         }
     }
 
+    /// Let the animation drop frames when the system falls behind.
+    pub fn set_adaptive(&mut self, adaptive: bool) { self.adaptive = adaptive; }
+
+    pub fn set_easing(&mut self, easing: AnimationEasing) { self.easing = easing; }
+
+    /// Scale the number of frames by `factor`, keeping the overall duration.
+    fn throttle(&mut self, factor: f64) {
+        let duration = self.interval * self.frames;
+        self.frames = ((f64::from(self.frames) * factor).round() as u32).max(1);
+        self.interval = duration / self.frames;
+    }
+
     pub fn add_window(
         &mut self,
         handle: &AppThreadHandle,
@@ -432,12 +664,30 @@ impl Animation {
             wid,
             start,
             finish,
+            park: None,
             is_focus,
             txid,
         });
         self.mark_handled(wid);
     }
 
+    /// Like [`Animation::add_window`], but the window is moved to `park`, if
+    /// given, once it has reached `finish`.
+    pub fn add_parked_window(
+        &mut self,
+        handle: &AppThreadHandle,
+        wid: WindowId,
+        start: CGRect,
+        finish: CGRect,
+        park: Option<CGRect>,
+        txid: TransactionId,
+    ) {
+        self.add_window(handle, wid, start, finish, false, txid);
+        if let Some(window) = self.windows.last_mut() {
+            window.park = park;
+        }
+    }
+
     fn mark_handled(&mut self, wid: WindowId) {
         if !self.handled_windows.contains(&wid) {
             self.handled_windows.push(wid);
@@ -448,7 +698,7 @@ impl Animation {
         for window in &self.windows {
             _ = window.handle.send(Request::SetWindowFrame(
                 window.wid,
-                window.finish,
+                window.resting_frame(),
                 window.txid,
                 true,
             ));
@@ -484,7 +734,7 @@ impl Animation {
         for window in &self.windows {
             _ = window.handle.send(Request::AnimationFrame {
                 wid: window.wid,
-                frame: window.finish,
+                frame: window.resting_frame(),
                 set_size: true,
                 txid: window.txid,
             });
@@ -493,9 +743,10 @@ impl Animation {
     }
 
     fn send_frame(&self, frame: u32) {
+        metrics::ANIMATION_FRAMES.inc();
         let t = f64::from(frame) / f64::from(self.frames);
         for window in &self.windows {
-            let mut rect = get_frame(window.start, window.finish, t);
+            let mut rect = get_frame(window.start, window.finish, t, self.easing);
             let set_size = frame * 2 == self.frames || frame == self.frames;
             if set_size {
                 rect.size = window.finish.size;
@@ -511,6 +762,14 @@ impl Animation {
 
     fn end(&self) {
         for window in &self.windows {
+            if let Some(park) = window.park {
+                _ = window.handle.send(Request::AnimationFrame {
+                    wid: window.wid,
+                    frame: park,
+                    set_size: true,
+                    txid: window.txid,
+                });
+            }
             _ = window.handle.send(Request::EndWindowAnimation(window.wid));
         }
     }
@@ -547,8 +806,8 @@ impl Animation {
     fn skip_to_end_and_end(self) { self.finish_all(); }
 }
 
-fn get_frame(a: CGRect, b: CGRect, t: f64) -> CGRect {
-    let s = ease(t);
+fn get_frame(a: CGRect, b: CGRect, t: f64, easing: AnimationEasing) -> CGRect {
+    let s = easing::ease(easing, t);
     CGRect {
         origin: CGPoint {
             x: blend(a.origin.x, b.origin.x, s),
@@ -561,14 +820,6 @@ fn get_frame(a: CGRect, b: CGRect, t: f64) -> CGRect {
     }
 }
 
-fn ease(t: f64) -> f64 {
-    if t < 0.5 {
-        (1.0 - f64::sqrt(1.0 - f64::powi(2.0 * t, 2))) / 2.0
-    } else {
-        (f64::sqrt(1.0 - f64::powi(-2.0 * t + 2.0, 2)) + 1.0) / 2.0
-    }
-}
-
 fn blend(a: f64, b: f64, s: f64) -> f64 { (1.0 - s) * a + s * b }
 
 #[cfg(test)]
@@ -577,12 +828,14 @@ mod tests {
 
     use super::*;
 
+    const DEFAULT_DURATION: f64 = 0.30;
+
     fn rect(origin_x: f64, origin_y: f64, width: f64, height: f64) -> CGRect {
         CGRect::new(CGPoint::new(origin_x, origin_y), CGSize::new(width, height))
     }
 
     fn animation(handle: &AppThreadHandle, wid: WindowId, from: CGRect, to: CGRect) -> Animation {
-        let mut animation = Animation::new();
+        let mut animation = Animation::new(DEFAULT_DURATION, DEFAULT_FPS);
         animation.add_window(handle, wid, from, to, false, TransactionId::default());
         animation
     }
@@ -677,10 +930,46 @@ mod tests {
         assert_eq!(resumed_start, continuing_frame);
 
         manager.tick();
-        let expected_next = get_frame(resumed_start, rect(80.0, 90.0, 10.0, 10.0), 1.0 / 30.0);
+        let expected_next = get_frame(
+            resumed_start,
+            rect(80.0, 90.0, 10.0, 10.0),
+            1.0 / 30.0,
+            AnimationEasing::default(),
+        );
         assert_animation_pos(&collect_requests(&mut rx)[0], wid, expected_next.origin);
     }
 
+    #[test]
+    fn throttling_keeps_the_duration() {
+        let mut animation = Animation::new(DEFAULT_DURATION, DEFAULT_FPS);
+        assert_eq!(animation.frames, 30);
+        animation.throttle(0.5);
+        assert_eq!(animation.frames, 15);
+        assert_eq!(animation.interval, Duration::from_millis(20));
+    }
+
+    #[test]
+    fn adaptive_animation_drops_frames_it_is_late_for() {
+        let (tx, _rx) = crate::actor::channel();
+        let handle = AppThreadHandle::new_for_test(tx);
+        let wid = WindowId::new(1, 1);
+        let mut adaptive = animation(
+            &handle,
+            wid,
+            rect(0.0, 0.0, 10.0, 10.0),
+            rect(50.0, 60.0, 10.0, 10.0),
+        );
+        adaptive.set_adaptive(true);
+
+        let mut manager = AnimationManager::new();
+        manager.handle_message(Message::Replace(adaptive));
+        let active = manager.active.as_mut().unwrap();
+        active.due = Instant::now() - active.animation.interval * 10;
+        manager.tick();
+        assert!(manager.active.as_ref().unwrap().next_frame >= 12);
+        assert!(manager.lateness.is_behind(Duration::from_millis(10)));
+    }
+
     fn animation_contains(manager: &AnimationManager, wid: WindowId) -> bool {
         manager
             .active
@@ -695,7 +984,7 @@ mod tests {
         let wid1 = WindowId::new(1, 1);
         let wid2 = WindowId::new(1, 2);
         let wid3 = WindowId::new(1, 3);
-        let mut first = Animation::new();
+        let mut first = Animation::new(DEFAULT_DURATION, DEFAULT_FPS);
         first.add_window(
             &handle,
             wid1,
@@ -712,7 +1001,7 @@ mod tests {
             false,
             TransactionId::default(),
         );
-        let mut second = Animation::new();
+        let mut second = Animation::new(DEFAULT_DURATION, DEFAULT_FPS);
         second.add_window(
             &handle,
             wid1,
@@ -758,7 +1047,7 @@ mod tests {
         let handle = AppThreadHandle::new_for_test(tx);
         let wid1 = WindowId::new(1, 1);
         let wid2 = WindowId::new(1, 2);
-        let mut first = Animation::new();
+        let mut first = Animation::new(DEFAULT_DURATION, DEFAULT_FPS);
         first.add_window(
             &handle,
             wid1,
@@ -775,7 +1064,7 @@ mod tests {
             false,
             TransactionId::default(),
         );
-        let mut second = Animation::new();
+        let mut second = Animation::new(DEFAULT_DURATION, DEFAULT_FPS);
         second.add_window(
             &handle,
             wid1,
@@ -823,4 +1112,43 @@ mod tests {
         assert!(matches!(requests[2], Request::EndWindowAnimation(req_wid) if req_wid == wid));
         assert_set_window_frame(&requests[3], wid, rect(80.0, 90.0, 10.0, 10.0));
     }
+
+    #[test]
+    fn parked_window_moves_to_its_park_frame_after_sliding() {
+        let (tx, mut rx) = crate::actor::channel();
+        let handle = AppThreadHandle::new_for_test(tx);
+        let wid = WindowId::new(1, 1);
+        let mut slide = Animation::new(DEFAULT_DURATION, DEFAULT_FPS);
+        slide.add_parked_window(
+            &handle,
+            wid,
+            rect(0.0, 0.0, 10.0, 10.0),
+            rect(-100.0, 0.0, 10.0, 10.0),
+            Some(rect(500.0, 500.0, 10.0, 10.0)),
+            TransactionId::default(),
+        );
+
+        let mut manager = AnimationManager::new();
+        manager.handle_message(Message::Replace(slide));
+        while manager.tick().is_some() {}
+
+        let requests = collect_requests(&mut rx);
+        let n = requests.len();
+        assert_animation_frame(&requests[n - 3], wid, rect(-100.0, 0.0, 10.0, 10.0));
+        assert_animation_frame(&requests[n - 2], wid, rect(500.0, 500.0, 10.0, 10.0));
+        assert!(matches!(requests[n - 1], Request::EndWindowAnimation(req_wid) if req_wid == wid));
+
+        let mut slide = Animation::new(DEFAULT_DURATION, DEFAULT_FPS);
+        slide.add_parked_window(
+            &handle,
+            wid,
+            rect(0.0, 0.0, 10.0, 10.0),
+            rect(-100.0, 0.0, 10.0, 10.0),
+            Some(rect(500.0, 500.0, 10.0, 10.0)),
+            TransactionId::default(),
+        );
+        slide.skip_to_end();
+        let requests = collect_requests(&mut rx);
+        assert_set_window_frame(&requests[0], wid, rect(500.0, 500.0, 10.0, 10.0));
+    }
 }
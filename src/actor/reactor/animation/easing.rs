@@ -0,0 +1,204 @@
+//! Easing curves for window animations.
+//!
+//! Every curve maps animation progress `t` in `[0, 1]` to how far the window
+//! has travelled, starting at 0 and ending exactly at 1. Springs may overshoot
+//! in between.
+
+use std::f64::consts::PI;
+
+use crate::common::config::AnimationEasing;
+
+/// A spring is considered settled once it is this close to its target.
+const SPRING_SETTLE_THRESHOLD: f64 = 1e-3;
+
+pub fn ease(easing: AnimationEasing, t: f64) -> f64 {
+    if t <= 0.0 {
+        return 0.0;
+    }
+    if t >= 1.0 {
+        return 1.0;
+    }
+    use AnimationEasing::*;
+    match easing {
+        EaseInOut | EaseInOutCirc => {
+            if t < 0.5 {
+                (1.0 - (1.0 - (2.0 * t).powi(2)).sqrt()) / 2.0
+            } else {
+                ((1.0 - (-2.0 * t + 2.0).powi(2)).sqrt() + 1.0) / 2.0
+            }
+        }
+        Linear => t,
+        EaseInSine => 1.0 - (t * PI / 2.0).cos(),
+        EaseOutSine => (t * PI / 2.0).sin(),
+        EaseInOutSine => -((PI * t).cos() - 1.0) / 2.0,
+        EaseInQuad => ease_in_pow(t, 2),
+        EaseOutQuad => ease_out_pow(t, 2),
+        EaseInOutQuad => ease_in_out_pow(t, 2),
+        EaseInCubic => ease_in_pow(t, 3),
+        EaseOutCubic => ease_out_pow(t, 3),
+        EaseInOutCubic => ease_in_out_pow(t, 3),
+        EaseInQuart => ease_in_pow(t, 4),
+        EaseOutQuart => ease_out_pow(t, 4),
+        EaseInOutQuart => ease_in_out_pow(t, 4),
+        EaseInQuint => ease_in_pow(t, 5),
+        EaseOutQuint => ease_out_pow(t, 5),
+        EaseInOutQuint => ease_in_out_pow(t, 5),
+        EaseInExpo => 2f64.powf(10.0 * t - 10.0),
+        EaseOutExpo => 1.0 - 2f64.powf(-10.0 * t),
+        EaseInOutExpo => {
+            if t < 0.5 {
+                2f64.powf(20.0 * t - 10.0) / 2.0
+            } else {
+                (2.0 - 2f64.powf(-20.0 * t + 10.0)) / 2.0
+            }
+        }
+        EaseInCirc => 1.0 - (1.0 - t * t).sqrt(),
+        EaseOutCirc => (1.0 - (t - 1.0).powi(2)).sqrt(),
+        Spring { mass, stiffness, damping } => spring(mass, stiffness, damping, t),
+        CubicBezier([x1, y1, x2, y2]) => cubic_bezier(x1, y1, x2, y2, t),
+    }
+}
+
+fn ease_in_pow(t: f64, n: i32) -> f64 { t.powi(n) }
+
+fn ease_out_pow(t: f64, n: i32) -> f64 { 1.0 - (1.0 - t).powi(n) }
+
+fn ease_in_out_pow(t: f64, n: i32) -> f64 {
+    if t < 0.5 {
+        2f64.powi(n - 1) * t.powi(n)
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(n) / 2.0
+    }
+}
+
+/// A damped spring released at rest from 0 towards 1. The animation's
+/// duration is stretched over the time the spring takes to settle, so the
+/// parameters shape the motion and `animation_duration` still sets its length.
+fn spring(mass: f64, stiffness: f64, damping: f64, t: f64) -> f64 {
+    if mass <= 0.0 || stiffness <= 0.0 || damping < 0.0 {
+        return t;
+    }
+    let omega = (stiffness / mass).sqrt();
+    let zeta = damping / (2.0 * (stiffness * mass).sqrt());
+
+    let decay = if zeta < 1.0 {
+        zeta * omega
+    } else {
+        omega * (zeta - (zeta * zeta - 1.0).sqrt())
+    };
+    if decay <= 0.0 {
+        // Undamped springs never settle; fall back to a plain ramp.
+        return t;
+    }
+    let time = t * (1.0 / SPRING_SETTLE_THRESHOLD).ln() / decay;
+
+    if zeta < 1.0 {
+        let damped = omega * (1.0 - zeta * zeta).sqrt();
+        1.0 - (-zeta * omega * time).exp()
+            * ((damped * time).cos() + (zeta * omega / damped) * (damped * time).sin())
+    } else if (zeta - 1.0).abs() < f64::EPSILON {
+        1.0 - (-omega * time).exp() * (1.0 + omega * time)
+    } else {
+        let root = (zeta * zeta - 1.0).sqrt();
+        let r1 = -omega * (zeta - root);
+        let r2 = -omega * (zeta + root);
+        1.0 - (r2 * (r1 * time).exp() - r1 * (r2 * time).exp()) / (r2 - r1)
+    }
+}
+
+/// A CSS-style `cubic-bezier(x1, y1, x2, y2)` curve from (0, 0) to (1, 1).
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, t: f64) -> f64 {
+    let bezier = |p1: f64, p2: f64, u: f64| {
+        let v = 1.0 - u;
+        3.0 * v * v * u * p1 + 3.0 * v * u * u * p2 + u * u * u
+    };
+    let slope = |p1: f64, p2: f64, u: f64| {
+        let v = 1.0 - u;
+        3.0 * v * v * p1 + 6.0 * v * u * (p2 - p1) + 3.0 * u * u * (1.0 - p2)
+    };
+
+    // Find the curve parameter whose x is `t`: Newton's method first, then
+    // bisection when the slope is too flat for it to converge.
+    let mut u = t;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, u) - t;
+        if error.abs() < 1e-7 {
+            return bezier(y1, y2, u);
+        }
+        let d = slope(x1, x2, u);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        u -= error / d;
+    }
+    let (mut lo, mut hi) = (0.0, 1.0);
+    u = t;
+    for _ in 0..50 {
+        let x = bezier(x1, x2, u);
+        if (x - t).abs() < 1e-7 {
+            break;
+        }
+        if x < t {
+            lo = u;
+        } else {
+            hi = u;
+        }
+        u = (lo + hi) / 2.0;
+    }
+    bezier(y1, y2, u)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-3, "{a} != {b}");
+    }
+
+    #[test]
+    fn every_curve_starts_at_zero_and_ends_at_one() {
+        let curves = [
+            AnimationEasing::EaseInOut,
+            AnimationEasing::EaseOutExpo,
+            AnimationEasing::Spring {
+                mass: 1.0,
+                stiffness: 180.0,
+                damping: 12.0,
+            },
+            AnimationEasing::Spring {
+                mass: 1.0,
+                stiffness: 100.0,
+                damping: 40.0,
+            },
+            AnimationEasing::CubicBezier([0.25, 0.1, 0.25, 1.0]),
+        ];
+        for easing in curves {
+            assert_eq!(ease(easing, 0.0), 0.0);
+            assert_eq!(ease(easing, 1.0), 1.0);
+            assert!((ease(easing, 0.999) - 1.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn underdamped_spring_overshoots() {
+        let bouncy = AnimationEasing::Spring {
+            mass: 1.0,
+            stiffness: 200.0,
+            damping: 5.0,
+        };
+        let peak = (1..100).map(|i| ease(bouncy, f64::from(i) / 100.0)).fold(0.0, f64::max);
+        assert!(peak > 1.0);
+    }
+
+    #[test]
+    fn cubic_bezier_matches_known_curves() {
+        let linear = AnimationEasing::CubicBezier([0.0, 0.0, 1.0, 1.0]);
+        assert_close(ease(linear, 0.3), 0.3);
+
+        // CSS `ease-in-out` is symmetric around the midpoint.
+        let ease_in_out = AnimationEasing::CubicBezier([0.42, 0.0, 0.58, 1.0]);
+        assert_close(ease(ease_in_out, 0.5), 0.5);
+        assert_close(ease(ease_in_out, 0.2) + ease(ease_in_out, 0.8), 1.0);
+    }
+}
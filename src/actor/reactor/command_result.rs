@@ -0,0 +1,296 @@
+use super::events::command as command_workflow;
+use super::managers::{LayoutManager, LayoutResult};
+use super::{Command, Event, EventOutcome, Reactor, ReactorCommand};
+use crate::actor::app::WindowId;
+use crate::common::collections::HashMap;
+use crate::common::config::WorkspaceSelector;
+use crate::layout_engine::LayoutCommand;
+use crate::model::server::{CommandErrorKind, CommandResult, FrameChange};
+
+impl Reactor {
+    /// Runs `command` like `Event::Command` and reports what happened. With
+    /// `batched`, arranging windows is merged into it instead of done now.
+    pub(super) fn run_command_with_result(
+        &mut self,
+        command: Command,
+        batched: Option<&mut EventOutcome>,
+    ) -> CommandResult {
+        if let Err((kind, message)) = self.check_command(&command) {
+            return CommandResult::error(kind, message);
+        }
+
+        let mut window_ids = command_windows(&command);
+        window_ids.extend(self.result_focused_window());
+
+        match self.dispatch_workflow(Event::Command(command)) {
+            Ok(mut outcome) => {
+                if let Some(batched) = batched {
+                    let arrange = std::mem::take(&mut outcome.arrange);
+                    if arrange.requested {
+                        batched.arrange.requested = true;
+                        batched.arrange.passes = batched.arrange.passes.max(arrange.passes);
+                        batched.arrange.is_resize |= arrange.is_resize;
+                        batched.arrange.window_was_destroyed |= arrange.window_was_destroyed;
+                    }
+                }
+                self.apply_event_outcome(outcome);
+            }
+            Err(error) => {
+                return CommandResult::error(CommandErrorKind::Failed, error.to_string());
+            }
+        }
+
+        window_ids.extend(self.result_focused_window());
+        let mut affected: Vec<WindowId> = Vec::with_capacity(window_ids.len());
+        for wid in window_ids {
+            if !affected.contains(&wid) {
+                affected.push(wid);
+            }
+        }
+
+        let space = self.command_context_space();
+        CommandResult {
+            window_ids: affected,
+            workspace_id: space
+                .and_then(|space| self.layout_manager.layout_engine.active_workspace(space))
+                .map(|workspace| format!("{:?}", workspace)),
+            space_id: space.map(|space| space.get()),
+            ..CommandResult::success("Command executed successfully")
+        }
+    }
+
+    /// Runs a layout command on copies of the window and layout state and
+    /// reports the frames it would change. The real state is put back
+    /// afterwards and nothing is sent to apps.
+    pub(super) fn dry_run_command(&mut self, command: Command) -> CommandResult {
+        if let Err((kind, message)) = self.check_command(&command) {
+            return CommandResult::error(kind, message);
+        }
+        let Command::Layout(command) = command else {
+            return CommandResult::error(
+                CommandErrorKind::InvalidCommand,
+                "Only layout commands can be dry-run",
+            );
+        };
+        let ran = self.with_state_copies(|reactor| {
+            // Both layouts come from the copy, so state it could not carry over
+            // shows up on both sides instead of as a change.
+            let before = LayoutManager::calculate_layout(reactor);
+            let after = reactor
+                .run_layout_command_on_copies(command)
+                .map(|_| LayoutManager::calculate_layout(reactor));
+            (before, after, reactor.command_context_space())
+        });
+        let (before, after, space) = match ran {
+            Ok(ran) => ran,
+            Err(result) => return result,
+        };
+        let after = match after {
+            Ok(after) => after,
+            Err(error) => return CommandResult::error(CommandErrorKind::Failed, error.to_string()),
+        };
+        let frame_changes = frame_changes(before, after);
+        CommandResult {
+            window_ids: frame_changes.iter().map(|change| change.window_id).collect(),
+            space_id: space.map(|space| space.get()),
+            frame_changes: Some(frame_changes),
+            ..CommandResult::success("Dry run; nothing was changed")
+        }
+    }
+
+    /// Runs a batch on copies of the window and layout state and returns the
+    /// index and result of the first command that fails. Reactor commands act
+    /// on apps directly, so they are only checked, not run.
+    pub(super) fn trial_run_batch(
+        &mut self,
+        commands: &[Command],
+    ) -> Result<(), (usize, CommandResult)> {
+        let ran = self.with_state_copies(|reactor| {
+            for (index, command) in commands.iter().enumerate() {
+                if let Err((kind, message)) = reactor.check_command(command) {
+                    return Err((index, CommandResult::error(kind, message)));
+                }
+                if let Command::Layout(command) = command
+                    && let Err(error) = reactor.run_layout_command_on_copies(command.clone())
+                {
+                    return Err((
+                        index,
+                        CommandResult::error(CommandErrorKind::Failed, error.to_string()),
+                    ));
+                }
+            }
+            Ok(())
+        });
+        match ran {
+            Ok(ran) => ran,
+            Err(result) => Err((0, result)),
+        }
+    }
+
+    /// Runs `f` with copies of the window and layout state swapped in, then
+    /// puts the real state back.
+    fn with_state_copies<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, CommandResult> {
+        let engine =
+            match self.layout_manager.layout_engine.dry_run_copy(&self.config.virtual_workspaces) {
+                Ok(engine) => engine,
+                Err(error) => {
+                    return Err(CommandResult::error(
+                        CommandErrorKind::Failed,
+                        format!("Could not copy the layout: {error}"),
+                    ));
+                }
+            };
+
+        let state = self.state.clone();
+        let layout_engine = std::mem::replace(&mut self.layout_manager.layout_engine, engine);
+        let workspace_switch = self.workspace_switch_manager.clone();
+
+        let result = f(self);
+
+        self.state = state;
+        self.layout_manager.layout_engine = layout_engine;
+        self.workspace_switch_manager = workspace_switch;
+        Ok(result)
+    }
+
+    /// Applies a layout command to the state without sending anything to apps.
+    /// Only meant for state swapped in by `with_state_copies`.
+    fn run_layout_command_on_copies(
+        &mut self,
+        command: LayoutCommand,
+    ) -> anyhow::Result<EventOutcome> {
+        let command_space = self.command_context_space();
+        let (visible_spaces, visible_space_centers) = self.visible_spaces_for_layout(false);
+        command_workflow::handle_command_layout(
+            &mut self.state,
+            &mut self.layout_manager,
+            &mut self.workspace_switch_manager,
+            command_workflow::LayoutCommandPayload {
+                command,
+                command_space,
+                visible_spaces,
+                visible_space_centers,
+            },
+        )
+    }
+
+    fn result_focused_window(&self) -> Option<WindowId> {
+        self.main_window().or(self.layout_manager.layout_engine.focused_window())
+    }
+
+    /// Catches the failures scripts most often need to tell apart before the
+    /// command runs, since most workflows ignore a missing target silently.
+    fn check_command(&self, command: &Command) -> Result<(), (CommandErrorKind, String)> {
+        for wid in command_windows(command) {
+            if self.state.windows.window(wid).is_none() {
+                return Err((CommandErrorKind::WindowNotFound, format!("No window {:?}", wid)));
+            }
+        }
+
+        let Command::Layout(layout_command) = command else {
+            return Ok(());
+        };
+        let Some(space) = self.command_context_space() else {
+            return Err((
+                CommandErrorKind::SpaceNotManaged,
+                "The current space is not managed by rift".to_string(),
+            ));
+        };
+
+        let workspace = match layout_command {
+            LayoutCommand::SwitchToWorkspace(index) => Some(WorkspaceSelector::Index(*index)),
+            LayoutCommand::SwitchToNamedWorkspace(name) => {
+                Some(WorkspaceSelector::Name(name.clone()))
+            }
+            LayoutCommand::MoveWindowToWorkspace { workspace, .. } => Some(workspace.clone()),
+            _ => None,
+        };
+        if let Some(selector) = workspace {
+            let workspaces = self.layout_manager.layout_engine.virtual_workspace_manager();
+            // Workspaces are created the first time a space is used, so an
+            // empty list does not mean the selector is wrong.
+            if !workspaces.existing_workspaces(space).is_empty()
+                && workspaces.find_workspace(space, &selector).is_none()
+            {
+                return Err((
+                    CommandErrorKind::WorkspaceNotFound,
+                    format!("No workspace {:?} on the current space", selector),
+                ));
+            }
+        }
+
+        if needs_focused_window(layout_command)
+            && self.layout_manager.layout_engine.focused_window().is_none()
+        {
+            return Err((
+                CommandErrorKind::NoFocusedWindow,
+                "There is no focused window to act on".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Frames that differ between two layouts, in the order of `after`, then
+/// windows only `before` laid out.
+fn frame_changes(before: LayoutResult, after: LayoutResult) -> Vec<FrameChange> {
+    let mut before: HashMap<WindowId, _> = before
+        .into_iter()
+        .flat_map(|(space, frames)| {
+            frames.into_iter().map(move |(wid, frame)| (wid, (space, frame)))
+        })
+        .collect();
+    let mut changes = Vec::new();
+    for (space, frames) in after {
+        for (wid, to) in frames {
+            let from = before.remove(&wid).map(|(_, frame)| frame);
+            if from != Some(to) {
+                changes.push(FrameChange {
+                    window_id: wid,
+                    space_id: space.get(),
+                    from,
+                    to: Some(to),
+                });
+            }
+        }
+    }
+    let mut removed: Vec<_> = before.into_iter().collect();
+    removed.sort_by_key(|(wid, _)| *wid);
+    changes.extend(removed.into_iter().map(|(wid, (space, from))| FrameChange {
+        window_id: wid,
+        space_id: space.get(),
+        from: Some(from),
+        to: None,
+    }));
+    changes
+}
+
+/// Windows named explicitly by a command.
+fn command_windows(command: &Command) -> Vec<WindowId> {
+    match command {
+        Command::Layout(LayoutCommand::SwapWindows(a, b)) => vec![*a, *b],
+        Command::Reactor(ReactorCommand::FocusWindow { window_id, .. }) => vec![*window_id],
+        _ => Vec::new(),
+    }
+}
+
+fn needs_focused_window(command: &LayoutCommand) -> bool {
+    matches!(
+        command,
+        LayoutCommand::MoveNode(_)
+            | LayoutCommand::JoinWindow(_)
+            | LayoutCommand::ConsumeOrExpelWindow(_)
+            | LayoutCommand::GroupWindow(_)
+            | LayoutCommand::UngroupWindow
+            | LayoutCommand::ToggleWindowFloating
+            | LayoutCommand::ToggleSticky
+            | LayoutCommand::ToggleFullscreen
+            | LayoutCommand::ToggleFullscreenWithinGaps
+            | LayoutCommand::ToggleZoom
+            | LayoutCommand::PromoteToMaster
+            | LayoutCommand::MoveWindowToWorkspace { window_id: None, .. }
+    )
+}
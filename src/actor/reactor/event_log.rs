@@ -0,0 +1,131 @@
+//! The last events the reactor handled, kept in memory for
+//! `rift-cli debug events` so recent activity can be inspected without
+//! turning on trace logging.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::Event;
+use crate::model::server::EventLogEntry;
+
+/// How many events are kept.
+const EVENT_LOG_CAPACITY: usize = 1000;
+
+/// Longest `detail` kept per event; longer descriptions are cut off.
+const DETAIL_LIMIT: usize = 240;
+
+#[derive(Debug)]
+pub struct EventLog {
+    entries: VecDeque<EventLogEntry>,
+    capacity: usize,
+    next_sequence: u64,
+}
+
+impl Default for EventLog {
+    fn default() -> Self { Self::with_capacity(EVENT_LOG_CAPACITY) }
+}
+
+impl EventLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            next_sequence: 0,
+        }
+    }
+
+    /// The variant name and a shortened debug description of `event`, taken
+    /// before the event is handled and consumed.
+    pub fn describe(event: &Event) -> (&'static str, String) {
+        let mut detail = Truncated(String::new());
+        // Formatting stops with an error once the limit is reached, so large
+        // events are not formatted in full.
+        if write!(detail, "{event:?}").is_err() {
+            detail.0.push('…');
+        }
+        (event.into(), detail.0)
+    }
+
+    pub fn record(
+        &mut self,
+        (event, detail): (&'static str, String),
+        started: SystemTime,
+        duration: Duration,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(EventLogEntry {
+            sequence: self.next_sequence,
+            timestamp_ms: started
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_millis() as u64),
+            event,
+            duration_us: duration.as_micros() as u64,
+            detail,
+        });
+        self.next_sequence += 1;
+    }
+
+    /// The last `count` events, oldest first.
+    pub fn tail(&self, count: usize) -> Vec<EventLogEntry> {
+        let skip = self.entries.len().saturating_sub(count);
+        self.entries.iter().skip(skip).cloned().collect()
+    }
+}
+
+struct Truncated(String);
+
+impl Write for Truncated {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let room = DETAIL_LIMIT.saturating_sub(self.0.len());
+        if s.len() <= room {
+            self.0.push_str(s);
+            return Ok(());
+        }
+        let mut end = room;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.0.push_str(&s[..end]);
+        Err(std::fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &'static str) -> (&'static str, String) { (name, String::new()) }
+
+    #[test]
+    fn keeps_only_the_newest_events() {
+        let mut log = EventLog::with_capacity(2);
+        for name in ["first", "second", "third"] {
+            log.record(entry(name), UNIX_EPOCH, Duration::from_micros(5));
+        }
+
+        let tail = log.tail(10);
+        assert_eq!(tail.iter().map(|e| e.event).collect::<Vec<_>>(), [
+            "second", "third"
+        ]);
+        assert_eq!(tail[1].sequence, 2);
+        assert_eq!(tail[1].duration_us, 5);
+        assert_eq!(log.tail(1)[0].event, "third");
+    }
+
+    #[test]
+    fn long_details_are_cut_off() {
+        let (name, detail) = EventLog::describe(&Event::MouseUp);
+        assert_eq!(name, "MouseUp");
+        assert_eq!(detail, "MouseUp");
+
+        let mut long = Truncated(String::new());
+        assert!(write!(long, "{}", "é".repeat(DETAIL_LIMIT)).is_err());
+        assert!(long.0.len() <= DETAIL_LIMIT);
+    }
+}
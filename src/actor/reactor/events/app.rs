@@ -6,6 +6,7 @@ use crate::actor::reactor::events::{EventOutcome, WindowDiscoveryRequest};
 use crate::actor::reactor::managers::AppManager;
 use crate::layout_engine::LayoutEvent;
 use crate::sys::app::WindowInfo;
+use crate::sys::crash_restore;
 use crate::sys::window_server::WindowServerInfo;
 
 #[derive(Debug)]
@@ -49,6 +50,8 @@ pub fn handle_application_thread_terminated(
     pid: i32,
 ) -> anyhow::Result<EventOutcome> {
     apps.apps.remove(&pid);
+    apps.unresponsive.remove(&pid);
+    crash_restore::forget_app(pid);
     Ok(EventOutcome::finalized_event(None, false, false, true)
         .with_layout_event(LayoutEvent::AppClosed(pid)))
 }
@@ -1,16 +1,17 @@
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 use super::super::ScreenInfo;
 use crate::actor::app::{AppThreadHandle, Quiet, WindowId};
 use crate::actor::raise_manager;
 use crate::actor::reactor::WorkspaceSwitchOrigin;
 use crate::actor::reactor::events::EventOutcome;
+use crate::actor::reactor::journal::Journal;
 use crate::actor::reactor::managers::{
     AppManager, DragManager, LayoutManager, WorkspaceSwitchManager,
 };
 use crate::actor::spaces::ForwardedSpaceState;
 use crate::common::collections::HashMap;
-use crate::common::config::{self as config, Config};
+use crate::common::config::{self as config, Config, ConfigDiff};
 use crate::common::log::{MetricsCommand, handle_command as handle_metrics_command};
 use crate::layout_engine::{EventResponse, LayoutCommand, LayoutEvent};
 use crate::model::RiftState;
@@ -48,6 +49,7 @@ pub fn handle_command_layout(
             | LayoutCommand::SwitchToWorkspace(_)
             | LayoutCommand::MoveWindowToWorkspace { follow: true, .. }
             | LayoutCommand::SwitchToLastWorkspace
+            | LayoutCommand::SwitchToNamedWorkspace(_)
     );
     let requires_workspace_space = matches!(
         cmd,
@@ -58,6 +60,8 @@ pub fn handle_command_layout(
             | LayoutCommand::SetWorkspaceLayout { .. }
             | LayoutCommand::CreateWorkspace
             | LayoutCommand::SwitchToLastWorkspace
+            | LayoutCommand::SwitchToNamedWorkspace(_)
+            | LayoutCommand::RenameWorkspace { .. }
     );
     let workspace_space = if requires_workspace_space {
         if let Some(space) = command_space {
@@ -72,6 +76,9 @@ pub fn handle_command_layout(
     } else {
         workspace_switch.mark_workspace_switch_inactive();
     }
+    let previous_index = workspace_space
+        .filter(|_| is_workspace_switch)
+        .and_then(|space| layout.layout_engine.active_workspace_idx(space));
 
     let response = match &cmd {
         LayoutCommand::NextWorkspace(_)
@@ -79,7 +86,9 @@ pub fn handle_command_layout(
         | LayoutCommand::SwitchToWorkspace(_)
         | LayoutCommand::SetWorkspaceLayout { .. }
         | LayoutCommand::CreateWorkspace
-        | LayoutCommand::SwitchToLastWorkspace => {
+        | LayoutCommand::SwitchToLastWorkspace
+        | LayoutCommand::SwitchToNamedWorkspace(_)
+        | LayoutCommand::RenameWorkspace { .. } => {
             if let Some(space) = workspace_space {
                 layout.layout_engine.handle_virtual_workspace_command(
                     &mut state.windows,
@@ -115,11 +124,55 @@ pub fn handle_command_layout(
             )
         }
     };
+    if let Some(space) = workspace_space.filter(|_| is_workspace_switch) {
+        workspace_switch.note_slide(
+            space,
+            previous_index,
+            layout.layout_engine.active_workspace_idx(space),
+        );
+    }
 
     Ok(EventOutcome::finalized_event(None, false, false, false)
         .with_layout_response(response, workspace_space))
 }
 
+/// Moves a floating window to `frame` and remembers it as the window's
+/// floating position, so later layouts keep it there.
+pub fn handle_command_reactor_place_float(
+    state: &mut RiftState,
+    layout: &mut LayoutManager,
+    window: WindowId,
+    space: SpaceId,
+    frame: objc2_core_foundation::CGRect,
+) -> EventOutcome {
+    if let Some(workspace) = layout
+        .layout_engine
+        .virtual_workspace_manager()
+        .workspace_for_window(&state.windows, space, window)
+        .or_else(|| layout.layout_engine.active_workspace(space))
+    {
+        layout.layout_engine.store_floating_position(space, workspace, window, frame);
+    }
+    if let Some(window_state) = state.windows.window_mut(window) {
+        window_state.frame_monotonic = frame;
+    }
+    EventOutcome::finalized_event(None, false, false, false)
+        .with_pre_layout_window_frame_write(window, frame, true)
+}
+
+/// Keeps `wid` above other windows, or stops doing so. Only floating windows
+/// can be kept on top; the reactor raises them again whenever focus moves.
+pub fn toggle_always_on_top(state: &mut RiftState, layout: &LayoutManager, wid: WindowId) {
+    if state.always_on_top.remove(&wid) {
+        return;
+    }
+    if !layout.layout_engine.is_window_floating(wid) {
+        debug!(?wid, "Only floating windows can be kept on top");
+        return;
+    }
+    state.always_on_top.insert(wid);
+}
+
 fn current_floating_positions(
     state: &RiftState,
     layout: &LayoutManager,
@@ -182,20 +235,59 @@ pub fn handle_config_updated(
     drag: &mut DragManager,
     new_config: Config,
 ) -> anyhow::Result<EventOutcome> {
-    let keys_changed = config.keys != new_config.keys;
+    let diff = ConfigDiff::between(config, &new_config);
+    if diff.is_empty() {
+        debug!("config reloaded without changes");
+        return Ok(EventOutcome::default());
+    }
+    info!(changed = ?diff.changed, "applying config changes");
     *config = new_config;
-    layout.layout_engine.set_layout_settings(&config.settings.layout);
 
-    layout
-        .layout_engine
-        .update_virtual_workspace_settings(&state.windows, &config.virtual_workspaces);
+    if diff.touches(&["settings.layout"]) {
+        layout.layout_engine.set_layout_settings(&config.settings.layout);
+    }
+    if diff.touches(&["virtual_workspaces"]) {
+        layout
+            .layout_engine
+            .update_virtual_workspace_settings(&state.windows, &config.virtual_workspaces);
+    }
+    if diff.touches(&["settings.window_snapping"]) {
+        drag.update_config(config.settings.window_snapping.clone());
+    }
 
-    drag.update_config(config.settings.window_snapping);
+    // Only settings that feed into frames need a new arrangement; animation
+    // settings are read from the config when the next one runs.
+    let outcome = if diff.touches(CONFIG_PATHS_AFFECTING_LAYOUT) {
+        EventOutcome::finalized_event(None, false, false, false)
+    } else {
+        EventOutcome::default()
+    };
+    Ok(outcome.with_service_config_update(config.clone(), diff))
+}
 
-    Ok(EventOutcome::finalized_event(None, false, false, false)
-        .with_service_config_update(config.clone(), keys_changed))
+/// A profile switch replaces the config like a reload, then re-runs the app
+/// rules for every window since the profile may send apps elsewhere.
+pub fn handle_profile_switched(
+    config: &mut Config,
+    layout: &mut LayoutManager,
+    state: &RiftState,
+    drag: &mut DragManager,
+    new_config: Config,
+) -> anyhow::Result<EventOutcome> {
+    let mut outcome = EventOutcome::finalized_event(None, false, false, false);
+    outcome.absorb(handle_config_updated(config, layout, state, drag, new_config)?);
+    outcome.remap_workspaces = true;
+    Ok(outcome)
 }
 
+/// Config settings that change where windows are placed.
+const CONFIG_PATHS_AFFECTING_LAYOUT: &[&str] = &[
+    "settings.layout",
+    "settings.stage_manager",
+    "settings.ui.stack_line",
+    "virtual_workspaces",
+];
+
 pub fn handle_command_reactor_debug(
     layout: &LayoutManager,
     topology: &ForwardedSpaceState,
@@ -217,6 +309,7 @@ pub fn handle_command_reactor_serialize(
 pub fn handle_command_reactor_save_and_exit(
     state: &RiftState,
     layout: &mut LayoutManager,
+    journal: &Journal,
     active_space: Option<SpaceId>,
 ) -> anyhow::Result<EventOutcome> {
     if let Err(e) = save_layout(state, layout, config::restore_file(), active_space) {
@@ -227,7 +320,10 @@ pub fn handle_command_reactor_save_and_exit(
         return Ok(EventOutcome::finalized_event(None, false, false, false)
             .with_stdout_line(format!("Could not save master file; Rift is still running: {e}")));
     }
-    std::process::exit(0);
+    // The master file is now newer than the journal; a leftover journal would
+    // make the next start look like crash recovery.
+    journal.discard();
+    Ok(EventOutcome::default().with_exit())
 }
 
 fn save_layout(
@@ -272,6 +368,24 @@ pub fn handle_command_reactor_toggle_space_activated(
     Ok(EventOutcome::finalized_event(None, false, false, false).with_active_space_recompute())
 }
 
+#[derive(Debug, Clone)]
+pub struct ToggleDisplayPayload {
+    pub config: SpaceActivationConfig,
+    pub display_uuid: Option<String>,
+    pub space: Option<SpaceId>,
+}
+
+pub fn handle_command_reactor_toggle_display_activated(
+    policy: &mut SpaceActivationPolicy,
+    payload: ToggleDisplayPayload,
+) -> anyhow::Result<EventOutcome> {
+    let Some(display_uuid) = payload.display_uuid else {
+        return Ok(EventOutcome::finalized_event(None, false, false, false));
+    };
+    policy.toggle_display_activated(payload.config, &display_uuid, payload.space);
+    Ok(EventOutcome::finalized_event(None, false, false, false).with_active_space_recompute())
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct FocusWindowPayload {
     pub window_id: WindowId,
@@ -1,7 +1,8 @@
-use objc2_core_foundation::CGPoint;
+use objc2_core_foundation::{CGPoint, CGRect};
 use tracing::{trace, warn};
 
 use crate::actor::app::WindowId;
+use crate::actor::drag_swap::FloatingSnap;
 use crate::actor::reactor::events::EventOutcome;
 use crate::actor::reactor::managers::{DragManager, LayoutManager};
 use crate::actor::reactor::{DragState, LayoutEvent};
@@ -17,6 +18,19 @@ pub struct MouseUpPayload {
     pub final_space: Option<SpaceId>,
     pub visible_spaces: Vec<SpaceId>,
     pub visible_space_centers: HashMap<SpaceId, CGPoint>,
+    /// Set when a dropped floating window should snap into place.
+    pub floating_snap: Option<FloatingSnap>,
+    /// Set when a floating window was dropped over a drop zone.
+    pub floating_drop: Option<FloatingDrop>,
+}
+
+/// A floating window dropped over a tile, to be tiled next to it.
+#[derive(Debug, Clone)]
+pub struct FloatingDrop {
+    pub space: SpaceId,
+    pub point: CGPoint,
+    /// Frame of the screen showing the space.
+    pub screen: CGRect,
 }
 
 pub fn handle_mouse_up(
@@ -32,6 +46,8 @@ pub fn handle_mouse_up(
         trace!(?dragged, ?target, "performing deferred drag swap");
         drag.skip_layout_for_window = Some(dragged);
         if state.windows.contains_window(dragged) && state.windows.contains_window(target) {
+            // The swap leaves the target where the preview already put it.
+            drag.swap_preview = None;
             let response = layout.layout_engine.handle_command(
                 &mut state.windows,
                 payload.swap_space,
@@ -50,6 +66,25 @@ pub fn handle_mouse_up(
     };
     if let Some(session) = session {
         let window = session.window;
+        let dropped_into_tiling = match &payload.floating_drop {
+            Some(drop)
+                if Some(drop.space) == payload.final_space
+                    && layout.layout_engine.is_window_floating(window) =>
+            {
+                layout.layout_engine.insert_window_at_position(
+                    &mut state.windows,
+                    drop.space,
+                    window,
+                    drop.point,
+                    drop.screen,
+                )
+            }
+            _ => false,
+        };
+        if dropped_into_tiling {
+            trace!(?window, "tiled floating window at its drop zone");
+            needs_layout = true;
+        }
         if session.origin_space != payload.final_space {
             if session.origin_space.is_some() {
                 outcome = outcome.with_layout_event(LayoutEvent::WindowRemoved(window));
@@ -79,6 +114,7 @@ pub fn handle_mouse_up(
         }
 
         if let Some(space) = payload.final_space
+            && !dropped_into_tiling
             && layout.layout_engine.is_window_floating(window)
         {
             if session.origin_space != payload.final_space {
@@ -90,12 +126,25 @@ pub fn handle_mouse_up(
                 .workspace_for_window(&state.windows, space, window)
                 .or_else(|| layout.layout_engine.active_workspace(space))
             {
-                layout.layout_engine.store_floating_position(
-                    space,
-                    workspace,
-                    window,
-                    session.last_frame,
-                );
+                let mut frame = session.last_frame;
+                if let Some(snap) = payload.floating_snap {
+                    let others: Vec<_> = layout
+                        .layout_engine
+                        .workspace_floating_positions(space, workspace)
+                        .into_iter()
+                        .filter(|(other, _)| *other != window)
+                        .map(|(_, frame)| frame)
+                        .collect();
+                    frame = snap.apply(frame, &others);
+                    if frame.origin != session.last_frame.origin {
+                        trace!(?window, ?frame, "snapping dropped floating window");
+                        if let Some(window_state) = state.windows.window_mut(window) {
+                            window_state.frame_monotonic = frame;
+                        }
+                        outcome = outcome.with_pre_layout_window_frame_write(window, frame, true);
+                    }
+                }
+                layout.layout_engine.store_floating_position(space, workspace, window, frame);
             }
         }
     }
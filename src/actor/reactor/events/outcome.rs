@@ -3,7 +3,7 @@ use objc2_core_foundation::{CGPoint, CGRect};
 use crate::actor::app::{AppInfo, Request, WindowId, WindowInfo, pid_t};
 use crate::actor::raise_manager;
 use crate::actor::wm_controller::{WmCmd, WmEvent};
-use crate::common::config::Config;
+use crate::common::config::{Config, ConfigDiff};
 use crate::layout_engine::{Direction, EventResponse, LayoutEvent};
 use crate::sys::screen::SpaceId;
 use crate::sys::window_server::{WindowServerId, WindowServerInfo};
@@ -66,9 +66,13 @@ pub(crate) struct EventOutcome {
     pub(crate) drag_swap_evaluations: Vec<(WindowId, CGRect)>,
     pub(crate) dispatch_mouse_up: bool,
     pub(crate) close_window: Option<Option<WindowServerId>>,
-    pub(crate) service_config_update: Option<(Config, bool)>,
+    pub(crate) service_config_update: Option<(Config, ConfigDiff)>,
     pub(crate) stdout_lines: Vec<String>,
+    /// End the process once the event is handled and any caller waiting
+    /// for its result has been answered.
+    pub(crate) exit_process: bool,
     pub(crate) reapply_app_rules: Vec<WindowId>,
+    pub(crate) remap_workspaces: bool,
     pub(crate) finalize_created_windows: Vec<WindowId>,
     pub(crate) window_title_broadcasts: Vec<WindowTitleBroadcast>,
     pub(crate) layout_events: Vec<LayoutEvent>,
@@ -117,7 +121,9 @@ impl EventOutcome {
         self.service_config_update =
             other.service_config_update.or(self.service_config_update.take());
         self.stdout_lines.append(&mut other.stdout_lines);
+        self.exit_process |= other.exit_process;
         self.reapply_app_rules.append(&mut other.reapply_app_rules);
+        self.remap_workspaces |= other.remap_workspaces;
         self.finalize_created_windows.append(&mut other.finalize_created_windows);
         self.window_title_broadcasts.append(&mut other.window_title_broadcasts);
         self.layout_events.append(&mut other.layout_events);
@@ -164,7 +170,9 @@ impl EventOutcome {
             close_window: None,
             service_config_update: None,
             stdout_lines: Vec::new(),
+            exit_process: false,
             reapply_app_rules: Vec::new(),
+            remap_workspaces: false,
             finalize_created_windows: Vec::new(),
             window_title_broadcasts: Vec::new(),
             layout_events: Vec::new(),
@@ -342,8 +350,8 @@ impl EventOutcome {
         self
     }
 
-    pub(crate) fn with_service_config_update(mut self, config: Config, keys_changed: bool) -> Self {
-        self.service_config_update = Some((config, keys_changed));
+    pub(crate) fn with_service_config_update(mut self, config: Config, diff: ConfigDiff) -> Self {
+        self.service_config_update = Some((config, diff));
         self
     }
 
@@ -352,6 +360,11 @@ impl EventOutcome {
         self
     }
 
+    pub(crate) fn with_exit(mut self) -> Self {
+        self.exit_process = true;
+        self
+    }
+
     pub(crate) fn with_app_rule_reapply(mut self, window: WindowId) -> Self {
         self.reapply_app_rules.push(window);
         self
@@ -9,10 +9,11 @@ use crate::actor::reactor::{DragState, Quiet, Requested, TransactionId, WindowSt
 use crate::layout_engine::LayoutEvent;
 use crate::model::WindowVisibility;
 use crate::sys::app::WindowInfo as Window;
+use crate::sys::crash_restore;
 use crate::sys::event::MouseState;
 use crate::sys::geometry::SameAs;
 use crate::sys::screen::SpaceId;
-use crate::sys::window_server::WindowServerInfo;
+use crate::sys::window_server::{WindowServerId, WindowServerInfo};
 
 #[derive(Debug)]
 pub struct WindowCreatedPayload {
@@ -21,6 +22,19 @@ pub struct WindowCreatedPayload {
     pub window_server_info: Option<WindowServerInfo>,
 }
 
+/// Records the frame the panic hook puts the window back to, once the window
+/// is manageable and has a window server id. Only the first frame recorded
+/// for a window counts, so later calls do not overwrite it with a frame rift
+/// chose.
+pub(crate) fn remember_original_frame(state: &crate::model::RiftState, wid: WindowId) {
+    if let Some(window) = state.windows.window(wid)
+        && window.is_manageable
+        && let Some(wsid) = window.info.sys_id
+    {
+        crash_restore::remember(wid.pid, wsid, window.frame_monotonic);
+    }
+}
+
 pub fn handle_window_created(
     state: &mut crate::model::RiftState,
     transactions: &TransactionManager,
@@ -58,6 +72,7 @@ pub fn handle_window_created(
     }
 
     state.windows.insert_window(wid, window_state);
+    remember_original_frame(state, wid);
 
     let outcome = EventOutcome::finalized_event(None, false, false, true);
     Ok(if is_manageable {
@@ -135,6 +150,7 @@ pub fn handle_window_destroyed(
     };
 
     if let Some(ws_id) = window_server_id {
+        crash_restore::forget(wid.pid, ws_id);
         transactions.remove_for_window(ws_id);
         state.windows.remove_window_server_state(ws_id);
     } else {
@@ -193,7 +209,7 @@ pub fn handle_window_minimized(
     state.windows.set_visibility(wid, WindowVisibility::Minimized);
     Ok(
         crate::actor::reactor::events::EventOutcome::finalized_event(None, false, false, false)
-            .with_layout_event(LayoutEvent::WindowRemoved(wid)),
+            .with_layout_event(LayoutEvent::WindowMinimized(wid)),
     )
 }
 
@@ -349,12 +365,27 @@ pub fn handle_window_frame_changed(
         window.frame_monotonic = new_frame;
     }
 
+    // A border resize of a tiled window is not a drag: no session is opened,
+    // so the rest of the layout keeps following the window while it resizes.
+    let resizing = drag.interactive_resize.is_some_and(|resize| resize.window == wid)
+        && !old_frame.size.same_as(new_frame.size);
     let dragging = mouse_state == Some(MouseState::Down)
         || matches!(
             drag.drag_state,
             DragState::Active { .. } | DragState::PendingSwap { .. }
         );
-    if dragging {
+    if resizing {
+        drag.skip_layout_for_window = Some(wid);
+        if active_resize_space.is_some() {
+            outcome.arrange.is_resize = true;
+            outcome = outcome.with_layout_event(LayoutEvent::WindowResized {
+                wid,
+                old_frame,
+                new_frame,
+                screens,
+            });
+        }
+    } else if dragging {
         let needs_session = !matches!(
             &drag.drag_state,
             DragState::Active { session } | DragState::PendingSwap { session, .. }
@@ -476,6 +507,9 @@ pub struct MouseMovedPayload {
     pub is_main: bool,
     pub needs_layout_sync: bool,
     pub active_space: Option<SpaceId>,
+    /// Make the window key without raising it (`focus_only` mode).
+    pub focus_only: bool,
+    pub window_server_id: Option<WindowServerId>,
 }
 
 pub fn handle_mouse_moved_over_window(
@@ -490,7 +524,12 @@ pub fn handle_mouse_moved_over_window(
     }
 
     let mut outcome = crate::actor::reactor::events::EventOutcome::default();
-    if !payload.is_main {
+    if !payload.is_main
+        && payload.focus_only
+        && let Some(wsid) = payload.window_server_id
+    {
+        outcome = outcome.with_make_key_window(window.pid, wsid);
+    } else if !payload.is_main {
         let mut app_handles = crate::common::collections::HashMap::default();
         if let Some(app) = apps.apps.get(&window.pid) {
             app_handles.insert(window.pid, app.handle.clone());
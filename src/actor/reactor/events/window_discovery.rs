@@ -4,7 +4,10 @@ use super::window;
 use crate::actor::app::{AppInfo, WindowId, WindowInfo, pid_t};
 use crate::actor::reactor::{LayoutEvent, WindowFilter, WindowState, utils};
 use crate::common::collections::{BTreeMap, HashMap, HashSet};
+use crate::common::config::WindowHeuristicsSettings;
+use crate::model::WindowRuleContext;
 use crate::model::virtual_workspace::{AppRuleResult, WorkspaceError};
+use crate::model::window_heuristics::heuristic_ignore_reason;
 use crate::sys::screen::SpaceId;
 use crate::sys::window_server::WindowServerId;
 
@@ -69,6 +72,8 @@ fn sync_existing_window_state(
             }
         }
     };
+    // A window server id or manageability can show up after the window did.
+    window::remember_original_frame(state, wid);
 
     if was_minimized != info.is_minimized {
         debug!(
@@ -348,6 +353,7 @@ pub(crate) fn process_window_list(
                 );
                 window_state.is_manageable = manageable;
                 state.windows.insert_window(wid, window_state);
+                window::remember_original_frame(state, wid);
             }
             outcome.absorb(sync_window_server_id_mapping(
                 state,
@@ -417,6 +423,7 @@ pub(crate) fn update_window_states(
         );
         state.is_manageable = manageable;
         rift_state.windows.insert_window(wid, state);
+        window::remember_original_frame(rift_state, wid);
     }
 }
 
@@ -427,15 +434,22 @@ fn assign_discovered_window_to_space(
     wid: WindowId,
     space: SpaceId,
     app_info: &Option<AppInfo>,
+    window_heuristics: &WindowHeuristicsSettings,
 ) -> Result<AppRuleResult, WorkspaceError> {
     let Some(window) = state.windows.window(wid) else {
         return Err(WorkspaceError::AssignmentFailed);
     };
+    if let Some(reason) = heuristic_ignore_reason(&window.info, window_heuristics) {
+        debug!(?wid, ?reason, "leaving window unmanaged");
+        return Ok(AppRuleResult::Unmanaged);
+    }
     let title = window.info.title.clone();
     let ax_role = window.info.ax_role.clone();
     let ax_subrole = window.info.ax_subrole.clone();
+    let bundle_path = window.info.path.clone();
+    let window_server_id = window.info.sys_id;
 
-    layout.layout_engine.assign_window_with_app_info(
+    let result = layout.layout_engine.assign_window_with_app_info(
         &mut state.windows,
         wid,
         space,
@@ -444,7 +458,25 @@ fn assign_discovered_window_to_space(
         Some(title.as_str()),
         ax_role.as_deref(),
         ax_subrole.as_deref(),
-    )
+    );
+
+    let opacity = layout.layout_engine.app_rule_opacity(WindowRuleContext {
+        app_bundle_id: app_info.as_ref().and_then(|a| a.bundle_id.as_deref()),
+        app_name: app_info.as_ref().and_then(|a| a.localized_name.as_deref()),
+        window_title: Some(title.as_str()),
+        ax_role: ax_role.as_deref(),
+        ax_subrole: ax_subrole.as_deref(),
+        app_bundle_path: bundle_path.as_deref().and_then(|path| path.to_str()),
+        ..Default::default()
+    });
+    if let (Some(opacity), Some(window_server_id)) = (opacity, window_server_id)
+        && let Err(error) =
+            crate::sys::window_server::set_window_alpha(window_server_id, opacity as f32)
+    {
+        warn!(?wid, ?error, "could not apply app rule opacity");
+    }
+
+    result
 }
 
 fn apply_assignment_result(
@@ -490,6 +522,7 @@ pub(crate) struct EmitLayoutPayload<'a> {
     pub(crate) authoritative_spaces: HashMap<WindowId, SpaceId>,
     pub(crate) active_spaces: Vec<SpaceId>,
     pub(crate) focused_window: Option<(SpaceId, WindowId)>,
+    pub(crate) window_heuristics: &'a WindowHeuristicsSettings,
 }
 
 pub(crate) fn emit_layout_events(
@@ -505,6 +538,7 @@ pub(crate) fn emit_layout_events(
         authoritative_spaces,
         active_spaces,
         focused_window,
+        window_heuristics,
     } = payload;
     let mut outcome = crate::actor::reactor::events::EventOutcome::default();
     if !state.windows.iter_windows().any(|(wid, _)| wid.pid == pid) {
@@ -592,7 +626,14 @@ pub(crate) fn emit_layout_events(
         for &wid in windows_for_space {
             assignment_results.insert(
                 (space, wid),
-                assign_discovered_window_to_space(state, layout, wid, space, app_info),
+                assign_discovered_window_to_space(
+                    state,
+                    layout,
+                    wid,
+                    space,
+                    app_info,
+                    window_heuristics,
+                ),
             );
         }
     }
@@ -604,7 +645,14 @@ pub(crate) fn emit_layout_events(
         if !windows_for_space.is_empty() {
             for &wid in &windows_for_space {
                 let assign_result = assignment_results.remove(&(space, wid)).unwrap_or_else(|| {
-                    assign_discovered_window_to_space(state, layout, wid, space, app_info)
+                    assign_discovered_window_to_space(
+                        state,
+                        layout,
+                        wid,
+                        space,
+                        app_info,
+                        window_heuristics,
+                    )
                 });
                 let apply_outcome =
                     apply_assignment_result(state, layout, wid, space, assign_result);
@@ -0,0 +1,81 @@
+//! Keeps floating windows of the active workspaces above tiled ones.
+//!
+//! Window levels of other apps cannot be changed without SIP disabled, so the
+//! floating layer is maintained by raising floating windows again whenever
+//! something may have buried them: a workspace switch or waking from sleep.
+//! Windows kept on top by `toggle_always_on_top` are raised again whenever
+//! focus moves to another window.
+
+use crate::actor::app::WindowId;
+
+#[derive(Debug, Default)]
+pub struct FloatingLayer {
+    restack_pending: bool,
+    /// The focused window the always-on-top windows were last raised over.
+    on_top_raised_over: Option<WindowId>,
+}
+
+impl FloatingLayer {
+    /// Note that floating windows may have been buried and should be raised
+    /// once the current event has been handled.
+    pub fn request_restack(&mut self) { self.restack_pending = true; }
+
+    pub fn take_restack(&mut self) -> bool { std::mem::take(&mut self.restack_pending) }
+
+    /// Whether focus moved to `focused` since the always-on-top windows were
+    /// last raised, which may have covered them.
+    pub fn focus_moved_to(&mut self, focused: Option<WindowId>) -> bool {
+        focused.is_some() && std::mem::replace(&mut self.on_top_raised_over, focused) != focused
+    }
+}
+
+/// Batches floating windows into one raise group per app, in a stable order.
+pub fn raise_groups(mut floating: Vec<WindowId>) -> Vec<Vec<WindowId>> {
+    floating.sort_unstable();
+    floating.dedup();
+    let mut groups: Vec<Vec<WindowId>> = Vec::new();
+    for wid in floating {
+        match groups.last_mut() {
+            Some(group) if group[0].pid == wid.pid => group.push(wid),
+            _ => groups.push(vec![wid]),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floating_windows_are_grouped_per_app() {
+        let groups = raise_groups(vec![
+            WindowId::new(2, 1),
+            WindowId::new(1, 3),
+            WindowId::new(1, 1),
+            WindowId::new(2, 1),
+        ]);
+        assert_eq!(groups, vec![
+            vec![WindowId::new(1, 1), WindowId::new(1, 3)],
+            vec![WindowId::new(2, 1)],
+        ]);
+    }
+
+    #[test]
+    fn always_on_top_windows_are_raised_once_per_focus_change() {
+        let mut layer = FloatingLayer::default();
+        assert!(!layer.focus_moved_to(None));
+        assert!(layer.focus_moved_to(Some(WindowId::new(1, 1))));
+        assert!(!layer.focus_moved_to(Some(WindowId::new(1, 1))));
+        assert!(layer.focus_moved_to(Some(WindowId::new(2, 1))));
+    }
+
+    #[test]
+    fn restack_request_is_taken_once() {
+        let mut layer = FloatingLayer::default();
+        assert!(!layer.take_restack());
+        layer.request_restack();
+        assert!(layer.take_restack());
+        assert!(!layer.take_restack());
+    }
+}
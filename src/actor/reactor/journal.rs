@@ -0,0 +1,82 @@
+//! Periodic snapshots of layout state for recovering from a crash.
+//!
+//! The master file is only written by `SaveAndExit`, so a panic loses every
+//! workspace assignment made since startup. While running, the reactor
+//! rewrites the journal a short while after the layout changes; a clean exit
+//! removes it again, so a journal left on disk means the last session died.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+/// How long the layout may stay unsaved after a change.
+const JOURNAL_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Default)]
+pub struct Journal {
+    path: Option<PathBuf>,
+    due: Option<Instant>,
+}
+
+impl Journal {
+    pub fn new(path: Option<PathBuf>) -> Self { Self { path, due: None } }
+
+    /// Schedules a write unless one is already pending, so a steady stream of
+    /// changes still reaches disk every `JOURNAL_DELAY`.
+    pub fn mark_dirty(&mut self, now: Instant) {
+        if self.path.is_some() && self.due.is_none() {
+            self.due = Some(now + JOURNAL_DELAY);
+        }
+    }
+
+    pub fn deadline(&self) -> Option<Instant> { self.due }
+
+    /// The journal path once a scheduled write is due.
+    pub fn take_due(&mut self, now: Instant) -> Option<PathBuf> {
+        if self.due.is_some_and(|due| due <= now) {
+            self.due = None;
+            return self.path.clone();
+        }
+        None
+    }
+
+    /// Removes the journal after the master file has been written.
+    pub fn discard(&self) {
+        let Some(path) = &self.path else { return };
+        if let Err(error) = std::fs::remove_file(path)
+            && error.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!(path = %path.display(), %error, "failed to remove state journal");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_are_batched_until_the_delay_passes() {
+        let start = Instant::now();
+        let mut journal = Journal::new(Some(PathBuf::from("/tmp/journal.ron")));
+        assert_eq!(journal.take_due(start), None);
+
+        journal.mark_dirty(start);
+        journal.mark_dirty(start + Duration::from_millis(500));
+        assert_eq!(journal.deadline(), Some(start + JOURNAL_DELAY));
+        assert_eq!(journal.take_due(start + Duration::from_secs(1)), None);
+        assert_eq!(
+            journal.take_due(start + JOURNAL_DELAY),
+            Some(PathBuf::from("/tmp/journal.ron"))
+        );
+        assert_eq!(journal.deadline(), None);
+    }
+
+    #[test]
+    fn disabled_journal_never_schedules() {
+        let mut journal = Journal::default();
+        journal.mark_dirty(Instant::now());
+        assert_eq!(journal.deadline(), None);
+    }
+}
@@ -115,6 +115,11 @@ impl MainWindowTracker {
         }
     }
 
+    /// The main window `pid` last reported, whether or not it is frontmost.
+    pub fn app_main_window(&self, pid: pid_t) -> Option<WindowId> {
+        self.apps.get(&pid).and_then(|app| app.main_window)
+    }
+
     /// Consume the quiet marker associated with the current global activation.
     ///
     /// A workspace switch can raise an app and cause both the app-local
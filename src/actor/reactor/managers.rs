@@ -1,19 +1,23 @@
 use objc2_core_foundation::{CGPoint, CGRect};
 use tracing::trace;
 
+use super::event_log::EventLog;
+use super::journal::Journal;
 use super::replay::Record;
 use super::{AppState, Event, WorkspaceSwitchOrigin, WorkspaceSwitchState};
 use crate::actor;
 use crate::actor::app::{WindowId, pid_t};
-use crate::actor::drag_swap::DragManager as DragSwapManager;
+use crate::actor::drag_swap::{DragManager as DragSwapManager, EdgeDwell, InteractiveResize};
 use crate::actor::reactor::Reactor;
 use crate::actor::reactor::animation::AnimationManager;
 use crate::actor::spaces::ForwardedSpaceState;
 use crate::actor::{
-    event_tap, gesture_tap, menu_bar, raise_manager, stack_line, window_notify, wm_controller,
+    borders, drop_zone_overlay, event_tap, gesture_tap, hint_overlay, hooks, menu_bar,
+    preselect_overlay, raise_manager, stack_line, window_notify, wm_controller,
 };
 use crate::common::collections::{HashMap, HashSet};
-use crate::common::config::{LayoutMode, WindowSnappingSettings};
+use crate::common::config::{LayoutMode, WindowSnappingSettings, WorkspaceTransition};
+use crate::common::metrics;
 use crate::layout_engine::LayoutEngine;
 use crate::model::broadcast::{BroadcastEvent, BroadcastSender, StackInfo};
 use crate::sys::screen::SpaceId;
@@ -21,10 +25,17 @@ use crate::sys::screen::SpaceId;
 /// Manages application state and rules
 pub struct AppManager {
     pub apps: HashMap<pid_t, AppState>,
+    /// Apps whose app thread reported that requests keep timing out.
+    pub unresponsive: HashSet<pid_t>,
 }
 
 impl AppManager {
-    pub fn new() -> Self { AppManager { apps: HashMap::default() } }
+    pub fn new() -> Self {
+        AppManager {
+            apps: HashMap::default(),
+            unresponsive: HashSet::default(),
+        }
+    }
 }
 
 /// Manages drag operations and window swapping
@@ -32,10 +43,25 @@ pub struct DragManager {
     pub drag_state: super::DragState,
     pub drag_swap_manager: DragSwapManager,
     pub skip_layout_for_window: Option<WindowId>,
+    pub edge_dwell: EdgeDwell,
+    /// Tiled window whose border was grabbed by the last mouse down.
+    pub interactive_resize: Option<InteractiveResize>,
+    /// Where a dragged floating window is over a drop zone: the space and the
+    /// cursor position.
+    pub floating_drop: Option<(SpaceId, CGPoint)>,
+    /// Swap target moved into the dragged window's tile as a preview, and the
+    /// frame of its own tile to put it back in.
+    pub swap_preview: Option<(WindowId, CGRect)>,
 }
 
 impl DragManager {
-    pub fn reset(&mut self) { self.drag_swap_manager.reset(); }
+    /// Forgets the drag tracking. A swap preview is kept so the reactor can
+    /// move its target back once the drag has ended.
+    pub fn reset(&mut self) {
+        self.drag_swap_manager.reset();
+        self.edge_dwell.reset();
+        self.interactive_resize = None;
+    }
 
     pub fn last_target(&self) -> Option<WindowId> { self.drag_swap_manager.last_target() }
 
@@ -52,9 +78,28 @@ impl DragManager {
 pub struct NotificationManager {
     pub last_sls_notification_ids: Vec<u32>,
     pub last_layout_modes_by_space: HashMap<SpaceId, crate::common::config::LayoutMode>,
+    /// Focus and per-space layout modes last published to IPC subscribers.
+    pub last_broadcast_focus: Option<WindowId>,
+    pub last_broadcast_layout_modes: HashMap<SpaceId, LayoutMode>,
+    /// Windows whose frame changed recently, and when to publish the change.
+    pub pending_frame_broadcasts: HashMap<WindowId, std::time::Instant>,
+    /// Frames last published to IPC subscribers (or seen before the first change).
+    pub last_broadcast_frames: HashMap<WindowId, CGRect>,
+    /// Border snapshot last sent to the borders actor.
+    pub last_border_update: Option<borders::Update>,
+    /// Preselections last sent to the preselect overlay actor.
+    pub last_preselect_update: Option<Vec<preselect_overlay::Preselection>>,
     pub _window_notify_tx: Option<window_notify::Sender>,
 }
 
+impl NotificationManager {
+    /// Drop the frame broadcast state of windows that no longer exist.
+    pub fn forget_windows(&mut self, mut gone: impl FnMut(WindowId) -> bool) {
+        self.pending_frame_broadcasts.retain(|&wid, _| !gone(wid));
+        self.last_broadcast_frames.retain(|&wid, _| !gone(wid));
+    }
+}
+
 /// Manages menu state and interactions
 pub struct MenuManager {
     pub menu_state: super::MenuState,
@@ -68,15 +113,54 @@ pub struct MissionControlManager {
 }
 
 /// Manages workspace switching state
+#[derive(Clone)]
 pub struct WorkspaceSwitchManager {
     pub workspace_switch_state: super::WorkspaceSwitchState,
     pub workspace_switch_generation: u64,
     pub active_workspace_switch: Option<u64>,
     pub pending_workspace_switch_origin: Option<WorkspaceSwitchOrigin>,
     pub pending_workspace_mouse_warp: Option<WindowId>,
+    /// Direction of the switch waiting for its layout pass, when
+    /// `workspace_transition = "slide"` should animate it.
+    pub pending_slide: Option<WorkspaceSlide>,
+    /// Space whose workspaces a swipe preview has moved, so the slide that
+    /// follows can start from where the preview left them.
+    pub previewing_space: Option<SpaceId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkspaceSlide {
+    pub space: SpaceId,
+    /// Whether the switch went to a later workspace, which slides the
+    /// content to the left.
+    pub forward: bool,
 }
 
 impl WorkspaceSwitchManager {
+    /// Remember which way `space` switched, given its workspace index before
+    /// and after the switch.
+    pub fn note_slide(&mut self, space: SpaceId, previous: Option<u64>, current: Option<u64>) {
+        if let (Some(previous), Some(current)) = (previous, current)
+            && previous != current
+        {
+            self.pending_slide = Some(WorkspaceSlide {
+                space,
+                forward: current > previous,
+            });
+        }
+    }
+
+    pub fn take_slide(&mut self, space: SpaceId) -> Option<WorkspaceSlide> {
+        self.pending_slide.take_if(|slide| slide.space == space)
+    }
+
+    /// Ends the swipe preview of `space`, returning whether one was shown.
+    /// Any layout pass of the space ends it: either the swipe committed and
+    /// the slide carries on from the preview, or the real frames replace it.
+    pub fn take_preview(&mut self, space: SpaceId) -> bool {
+        self.previewing_space.take_if(|previewing| *previewing == space).is_some()
+    }
+
     pub fn start_workspace_switch(&mut self, origin: WorkspaceSwitchOrigin) {
         self.workspace_switch_generation = self.workspace_switch_generation.wrapping_add(1);
         self.active_workspace_switch = Some(self.workspace_switch_generation);
@@ -92,6 +176,7 @@ impl WorkspaceSwitchManager {
     pub fn mark_workspace_switch_inactive(&mut self) {
         self.workspace_switch_state = WorkspaceSwitchState::Inactive;
         self.pending_workspace_switch_origin = None;
+        self.pending_slide = None;
     }
 }
 
@@ -140,6 +225,11 @@ pub struct CommunicationManager {
     pub event_tap_tx: Option<event_tap::Sender>,
     pub gesture_tap_tx: Option<gesture_tap::Sender>,
     pub stack_line_tx: Option<stack_line::Sender>,
+    pub borders_tx: Option<borders::Sender>,
+    pub preselect_overlay_tx: Option<preselect_overlay::Sender>,
+    pub drop_zone_overlay_tx: Option<drop_zone_overlay::Sender>,
+    pub hint_overlay_tx: Option<hint_overlay::Sender>,
+    pub hooks_tx: Option<hooks::Sender>,
     pub raise_manager_tx: raise_manager::Sender,
     pub event_broadcaster: BroadcastSender,
     pub wm_sender: Option<wm_controller::Sender>,
@@ -149,6 +239,8 @@ pub struct CommunicationManager {
 /// Manages recording state
 pub struct RecordingManager {
     pub record: Record,
+    pub journal: Journal,
+    pub event_log: EventLog,
 }
 
 /// Manages layout engine state
@@ -202,11 +294,86 @@ impl LayoutManager {
         is_resize: bool,
         is_workspace_switch: bool,
     ) -> Result<bool, crate::model::reactor::ReactorError> {
+        metrics::LAYOUT_UPDATES.inc();
         let layout_result = Self::calculate_layout(reactor);
         Self::apply_layout(reactor, layout_result, is_resize, is_workspace_switch)
     }
 
-    fn calculate_layout(reactor: &mut Reactor) -> LayoutResult {
+    /// Shows a swipe that is `progress` of the way from the current workspace
+    /// of the command space to its neighbour, without switching. Frames are
+    /// written instantly so the windows track the fingers.
+    pub fn preview_workspace_swipe(reactor: &mut Reactor, progress: f64, skip_empty: Option<bool>) {
+        let Some(space) = reactor.workspace_command_space() else {
+            return;
+        };
+        if reactor.config.settings.workspace_transition != WorkspaceTransition::Slide {
+            return;
+        }
+        let Some(screen) = reactor.space_state.screen_by_space(space).cloned() else {
+            return;
+        };
+        let Some((_, layout)) =
+            Self::calculate_layout(reactor).into_iter().find(|(s, _)| *s == space)
+        else {
+            return;
+        };
+        let progress = progress.clamp(-1.0, 1.0);
+        let mut frames: HashMap<WindowId, CGRect> = layout.iter().copied().collect();
+        if progress != 0.0 {
+            let engine = &reactor.layout_manager.layout_engine;
+            let windows = &reactor.state.windows;
+            let active: HashSet<WindowId> =
+                engine.windows_in_active_workspace(windows, space).into_iter().collect();
+            let outgoing: Vec<(WindowId, CGRect)> =
+                layout.into_iter().filter(|(wid, _)| active.contains(wid)).collect();
+            let workspaces = engine.virtual_workspace_manager();
+            let neighbour = engine.active_workspace(space).and_then(|current| {
+                if progress > 0.0 {
+                    workspaces.next_workspace(windows, space, current, skip_empty)
+                } else {
+                    workspaces.prev_workspace(windows, space, current, skip_empty)
+                }
+            });
+            let gaps = reactor
+                .config
+                .settings
+                .layout
+                .gaps
+                .effective_for_display(screen.display_uuid_opt(), screen.name.as_deref());
+            let stack_line = &reactor.config.settings.ui.stack_line;
+            let incoming = neighbour
+                .map(|workspace| {
+                    engine.calculate_layout_for_workspace(
+                        windows,
+                        space,
+                        workspace,
+                        screen.frame,
+                        &gaps,
+                        stack_line.thickness(),
+                        stack_line.horiz_placement,
+                        stack_line.vert_placement,
+                    )
+                })
+                .unwrap_or_default();
+            frames.extend(swipe_preview_frames(
+                &outgoing,
+                &incoming,
+                screen.frame.size.width,
+                progress,
+            ));
+        }
+        let frames: Vec<(WindowId, CGRect)> = frames
+            .into_iter()
+            .filter(|(wid, _)| {
+                !reactor.app_manager.unresponsive.contains(&wid.pid)
+                    && reactor.stage_manager_allows_frame(*wid)
+            })
+            .collect();
+        reactor.workspace_switch_manager.previewing_space = (progress != 0.0).then_some(space);
+        AnimationManager::instant_layout(reactor, space, &frames, None);
+    }
+
+    pub(super) fn calculate_layout(reactor: &mut Reactor) -> LayoutResult {
         if reactor.state.windows.tracked_window_count() == 0 {
             return LayoutResult::new();
         }
@@ -232,11 +399,12 @@ impl LayoutManager {
                 .settings
                 .layout
                 .gaps
-                .effective_for_display(display_uuid_opt.as_deref());
-            reactor
-                .layout_manager
-                .layout_engine
-                .update_space_display(space, display_uuid_opt.clone());
+                .effective_for_display(display_uuid_opt.as_deref(), screen.name.as_deref());
+            reactor.layout_manager.layout_engine.update_space_display(
+                space,
+                display_uuid_opt.clone(),
+                screen.name.clone(),
+            );
             let mut layout =
                 reactor.layout_manager.layout_engine.calculate_layout_with_virtual_workspaces(
                     &reactor.state.windows,
@@ -289,6 +457,8 @@ impl LayoutManager {
 
         let active_space = reactor.workspace_command_space();
         for (space, layout) in layout_result {
+            let slide = reactor.workspace_switch_manager.take_slide(space);
+            let previewed = reactor.workspace_switch_manager.take_preview(space);
             if let Some(screen) = reactor.space_state.screen_by_space(space) {
                 let screen_frame = screen.frame;
                 let display_uuid = screen.display_uuid_owned();
@@ -297,7 +467,7 @@ impl LayoutManager {
                     .settings
                     .layout
                     .gaps
-                    .effective_for_display(display_uuid.as_deref());
+                    .effective_for_display(display_uuid.as_deref(), screen.name.as_deref());
                 let active_workspace_for_space_has_fullscreen = active_space == Some(space)
                     && reactor
                         .layout_manager
@@ -326,6 +496,11 @@ impl LayoutManager {
                             total_count: g.total_count,
                             selected_index: g.selected_index,
                             window_ids: g.window_ids.clone(),
+                            titles: g
+                                .window_ids
+                                .iter()
+                                .map(|wid| reactor.stack_tab_title(*wid))
+                                .collect(),
                         })
                         .collect();
                     let active_space_ids: Vec<crate::sys::screen::SpaceId> =
@@ -378,9 +553,35 @@ impl LayoutManager {
                 }
             }
 
+            // Frames sent to an unresponsive app would only queue up behind
+            // the stuck request, so its windows stay put until it recovers.
+            // Windows Stage Manager is arranging are left alone as well.
+            let layout: Vec<(WindowId, CGRect)> = layout
+                .into_iter()
+                .filter(|(wid, _)| {
+                    !reactor.app_manager.unresponsive.contains(&wid.pid)
+                        && reactor.stage_manager_allows_frame(*wid)
+                })
+                .collect();
+
             let suppress_animation = is_workspace_switch
                 || reactor.workspace_switch_manager.active_workspace_switch.is_some();
-            if suppress_animation {
+            let slide_screen = slide
+                .filter(|_| {
+                    reactor.config.settings.workspace_transition == WorkspaceTransition::Slide
+                })
+                .zip(reactor.space_state.screen_by_space(space).map(|screen| screen.frame));
+            if let Some((slide, screen)) = slide_screen {
+                any_frame_changed |= AnimationManager::slide_layout(
+                    reactor,
+                    space,
+                    screen,
+                    &layout,
+                    slide.forward,
+                    previewed,
+                    skip_wid,
+                );
+            } else if suppress_animation {
                 any_frame_changed |=
                     AnimationManager::instant_layout(reactor, space, &layout, skip_wid);
             } else {
@@ -394,16 +595,62 @@ impl LayoutManager {
     }
 }
 
+/// Moves the current workspace's frames `progress` screen widths to the
+/// left (towards the next workspace) or right, and brings the neighbour's
+/// frames in from the side the swipe is heading to.
+fn swipe_preview_frames(
+    outgoing: &[(WindowId, CGRect)],
+    incoming: &[(WindowId, CGRect)],
+    width: f64,
+    progress: f64,
+) -> Vec<(WindowId, CGRect)> {
+    let shift = -progress * width;
+    let incoming_shift = shift + progress.signum() * width;
+    let outgoing = outgoing.iter().map(|&(wid, frame)| (wid, frame, shift));
+    let incoming = incoming.iter().map(|&(wid, frame)| (wid, frame, incoming_shift));
+    outgoing
+        .chain(incoming)
+        .map(|(wid, mut frame, dx)| {
+            frame.origin.x += dx;
+            (wid, frame)
+        })
+        .collect()
+}
+
 /// Manages pending space changes
 pub struct PendingSpaceChangeManager {
     pub pending_space_change: Option<ForwardedSpaceState>,
 }
 
+/// How long a window's frame must stay unchanged before `window_moved` or
+/// `window_resized` is published for it.
+pub const FRAME_BROADCAST_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// How long a `LaunchAppIntoSelection` waits for the app's next window.
+pub const PENDING_LAUNCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// An app started by `LaunchAppIntoSelection` whose next window has not
+/// appeared yet.
+#[derive(Debug, Clone)]
+pub struct PendingLaunch {
+    pub bundle_id: String,
+    pub space: SpaceId,
+    /// The window selected when the launch was requested.
+    pub anchor: Option<WindowId>,
+    pub deadline: std::time::Instant,
+}
+
+/// Manages launches waiting to place their first window
+pub struct LaunchManager {
+    pub pending_launch: Option<PendingLaunch>,
+}
+
 #[cfg(test)]
 mod tests {
     use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 
-    use super::bound_frame_to_screen;
+    use super::{bound_frame_to_screen, swipe_preview_frames};
+    use crate::actor::app::WindowId;
 
     fn rect(x: f64, y: f64, w: f64, h: f64) -> CGRect {
         CGRect::new(CGPoint::new(x, y), CGSize::new(w, h))
@@ -444,4 +691,17 @@ mod tests {
         assert_eq!(bounded.origin.x, 2998.0);
         assert_eq!(bounded.size.width, 600.0);
     }
+
+    #[test]
+    fn swipe_preview_brings_the_neighbour_in_from_the_swipe_direction() {
+        let current = [(WindowId::new(1, 1), rect(0.0, 0.0, 500.0, 800.0))];
+        let next = [(WindowId::new(1, 2), rect(500.0, 0.0, 500.0, 800.0))];
+        let frames = swipe_preview_frames(&current, &next, 1000.0, 0.25);
+        assert_eq!(frames[0].1.origin.x, -250.0);
+        assert_eq!(frames[1].1.origin.x, 1250.0);
+
+        let frames = swipe_preview_frames(&current, &next, 1000.0, -0.25);
+        assert_eq!(frames[0].1.origin.x, 250.0);
+        assert_eq!(frames[1].1.origin.x, -250.0);
+    }
 }
@@ -0,0 +1,142 @@
+//! Where `mouse_follows_focus` puts the mouse.
+
+use objc2_core_foundation::{CGPoint, CGRect};
+
+use crate::actor::app::WindowId;
+use crate::common::collections::HashMap;
+use crate::common::config::{MouseWarpSettings, MouseWarpTarget};
+use crate::sys::geometry::CGRectExt;
+
+/// Where the mouse last was inside each window. Positions are kept relative
+/// to the window origin so they survive the window being moved.
+#[derive(Debug, Default)]
+pub struct CursorHistory {
+    offsets: HashMap<WindowId, CGPoint>,
+}
+
+impl CursorHistory {
+    /// Record `cursor` for `window` if it lies inside `frame`.
+    pub fn remember(&mut self, window: WindowId, frame: CGRect, cursor: CGPoint) {
+        if frame.contains(cursor) {
+            let offset = CGPoint::new(cursor.x - frame.origin.x, cursor.y - frame.origin.y);
+            self.offsets.insert(window, offset);
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(WindowId) -> bool) {
+        self.offsets.retain(|window, _| keep(*window));
+    }
+
+    /// The remembered position of the mouse in `window`, if it still falls
+    /// inside the window's current `frame`.
+    pub fn position_in(&self, window: WindowId, frame: CGRect) -> Option<CGPoint> {
+        let offset = self.offsets.get(&window)?;
+        let point = CGPoint::new(frame.origin.x + offset.x, frame.origin.y + offset.y);
+        frame.contains(point).then_some(point)
+    }
+}
+
+/// The point to move the mouse to when `window` is focused, or `None` when
+/// the policy leaves it where it is. `same_display` is whether the mouse is
+/// already on the display showing the window.
+pub fn warp_target(
+    settings: &MouseWarpSettings,
+    history: &CursorHistory,
+    window: WindowId,
+    frame: CGRect,
+    cursor: Option<CGPoint>,
+    same_display: bool,
+) -> Option<CGPoint> {
+    if let Some(cursor) = cursor {
+        if settings.skip_if_inside && frame.contains(cursor) {
+            return None;
+        }
+        if settings.only_across_displays && same_display {
+            return None;
+        }
+    }
+    Some(match settings.target {
+        MouseWarpTarget::Center => frame.mid(),
+        MouseWarpTarget::LastPosition => {
+            history.position_in(window, frame).unwrap_or_else(|| frame.mid())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2_core_foundation::CGSize;
+
+    use super::*;
+
+    fn frame(x: f64, y: f64) -> CGRect {
+        CGRect::new(CGPoint::new(x, y), CGSize::new(400.0, 300.0))
+    }
+
+    #[test]
+    fn last_position_follows_the_window_and_falls_back_to_center() {
+        let window = WindowId::new(1, 1);
+        let settings = MouseWarpSettings {
+            target: MouseWarpTarget::LastPosition,
+            ..Default::default()
+        };
+        let mut history = CursorHistory::default();
+        assert_eq!(
+            warp_target(&settings, &history, window, frame(0.0, 0.0), None, false),
+            Some(CGPoint::new(200.0, 150.0))
+        );
+
+        history.remember(window, frame(0.0, 0.0), CGPoint::new(30.0, 40.0));
+        assert_eq!(
+            warp_target(&settings, &history, window, frame(100.0, 100.0), None, false),
+            Some(CGPoint::new(130.0, 140.0))
+        );
+    }
+
+    #[test]
+    fn conditions_leave_the_mouse_in_place() {
+        let window = WindowId::new(1, 1);
+        let inside = Some(CGPoint::new(10.0, 10.0));
+        let outside = Some(CGPoint::new(900.0, 10.0));
+
+        let skip_inside = MouseWarpSettings {
+            skip_if_inside: true,
+            ..Default::default()
+        };
+        let history = CursorHistory::default();
+        assert_eq!(
+            warp_target(&skip_inside, &history, window, frame(0.0, 0.0), inside, true),
+            None
+        );
+        assert!(
+            warp_target(&skip_inside, &history, window, frame(0.0, 0.0), outside, true).is_some()
+        );
+
+        let across_displays = MouseWarpSettings {
+            only_across_displays: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            warp_target(
+                &across_displays,
+                &history,
+                window,
+                frame(0.0, 0.0),
+                outside,
+                true
+            ),
+            None
+        );
+        assert!(
+            warp_target(
+                &across_displays,
+                &history,
+                window,
+                frame(0.0, 0.0),
+                outside,
+                false
+            )
+            .is_some()
+        );
+    }
+}
@@ -1,16 +1,22 @@
 use std::sync::mpsc::{RecvError, SyncSender, sync_channel};
+use std::time::Duration;
 
 use objc2_core_foundation::CGRect;
 
 use crate::actor::app::WindowId;
-use crate::actor::menu_bar;
-use crate::actor::reactor::{Event, Reactor, Sender};
+use crate::actor::reactor::{DisplaySelector, Event, Reactor, Sender, utils};
+use crate::actor::{borders, menu_bar, preselect_overlay};
 use crate::common::collections::HashSet;
+use crate::model::WindowRuleContext;
 use crate::model::server::{
-    ApplicationData, DisplayData, LayoutStateData, WindowData, WorkspaceData, WorkspaceLayoutData,
+    ActivationData, ActiveWorkspaceData, ApplicationData, DisplayActivationData, DisplayData,
+    EventLogEntry, FocusHistoryEntry, FocusedWindowData, IgnoredWindowData, LayoutStateData,
+    LayoutTreeData, ReactorHealthData, WindowData, WindowQuery, WorkspaceData, WorkspaceLayoutData,
 };
 use crate::model::virtual_workspace::VirtualWorkspaceId;
+use crate::model::window_heuristics::{IgnoreReason, heuristic_ignore_reason};
 use crate::sys::screen::{ScreenInfo, SpaceId};
+use crate::ui::stack_line::Color;
 
 #[derive(Clone)]
 pub struct ReactorQueryHandle {
@@ -37,7 +43,15 @@ impl ReactorQueryHandle {
     }
 
     pub fn query_windows(&self, space_id: Option<SpaceId>) -> Vec<WindowData> {
-        self.send_query(|resp| QueryRequest::Windows { space_id, resp })
+        self.query_windows_matching(space_id, WindowQuery::default())
+    }
+
+    pub fn query_windows_matching(
+        &self,
+        space_id: Option<SpaceId>,
+        query: WindowQuery,
+    ) -> Vec<WindowData> {
+        self.send_query(|resp| QueryRequest::Windows { space_id, query, resp })
             .unwrap_or_default()
     }
 
@@ -47,10 +61,27 @@ impl ReactorQueryHandle {
             .flatten()
     }
 
+    pub fn query_focused_window(&self) -> Option<FocusedWindowData> {
+        self.send_query(QueryRequest::FocusedWindow).ok().flatten()
+    }
+
+    pub fn query_active_workspace_data(
+        &self,
+        display: Option<DisplaySelector>,
+    ) -> Option<ActiveWorkspaceData> {
+        self.send_query(|resp| QueryRequest::ActiveWorkspaceData { display, resp })
+            .ok()
+            .flatten()
+    }
+
     pub fn query_displays(&self) -> Vec<DisplayData> {
         self.send_query(QueryRequest::Displays).unwrap_or_default()
     }
 
+    pub fn query_activation(&self) -> ActivationData {
+        self.send_query(QueryRequest::Activation).unwrap_or_default()
+    }
+
     pub fn query_workspace_layouts(
         &self,
         space_id: Option<SpaceId>,
@@ -76,6 +107,33 @@ impl ReactorQueryHandle {
             .flatten()
     }
 
+    pub fn query_layout_tree(&self, space_id: Option<SpaceId>) -> Option<LayoutTreeData> {
+        self.send_query(|resp| QueryRequest::LayoutTree { space_id, resp })
+            .ok()
+            .flatten()
+    }
+
+    pub fn query_focus_history(&self) -> Vec<FocusHistoryEntry> {
+        self.send_query(QueryRequest::FocusHistory).unwrap_or_default()
+    }
+
+    pub fn query_ignored_windows(&self) -> Vec<IgnoredWindowData> {
+        self.send_query(QueryRequest::IgnoredWindows).unwrap_or_default()
+    }
+
+    pub fn query_event_log(&self, tail: usize) -> Vec<EventLogEntry> {
+        self.send_query(|resp| QueryRequest::EventLog { tail, resp })
+            .unwrap_or_default()
+    }
+
+    /// Unlike the other queries this gives up after `timeout`, since it is
+    /// how `rift-cli doctor` finds out whether the reactor is stuck.
+    pub fn query_health(&self, timeout: Duration) -> Option<ReactorHealthData> {
+        let (tx, rx) = sync_channel(1);
+        self.tx.try_send(Event::Query(QueryRequest::Health(tx))).ok()?;
+        rx.recv_timeout(timeout).ok()
+    }
+
     pub fn query_metrics(&self) -> serde_json::Value {
         self.send_query(QueryRequest::Metrics).unwrap_or_else(|_| serde_json::json!({}))
     }
@@ -89,13 +147,20 @@ pub enum QueryRequest {
     },
     Windows {
         space_id: Option<SpaceId>,
+        query: WindowQuery,
         resp: SyncSender<Vec<WindowData>>,
     },
     ActiveWorkspace {
         space_id: Option<SpaceId>,
         resp: SyncSender<Option<VirtualWorkspaceId>>,
     },
+    FocusedWindow(SyncSender<Option<FocusedWindowData>>),
+    ActiveWorkspaceData {
+        display: Option<DisplaySelector>,
+        resp: SyncSender<Option<ActiveWorkspaceData>>,
+    },
     Displays(SyncSender<Vec<DisplayData>>),
+    Activation(SyncSender<ActivationData>),
     WorkspaceLayouts {
         space_id: Option<SpaceId>,
         workspace_id: Option<usize>,
@@ -110,6 +175,17 @@ pub enum QueryRequest {
         space_id: u64,
         resp: SyncSender<Option<LayoutStateData>>,
     },
+    LayoutTree {
+        space_id: Option<SpaceId>,
+        resp: SyncSender<Option<LayoutTreeData>>,
+    },
+    FocusHistory(SyncSender<Vec<FocusHistoryEntry>>),
+    IgnoredWindows(SyncSender<Vec<IgnoredWindowData>>),
+    EventLog {
+        tail: usize,
+        resp: SyncSender<Vec<EventLogEntry>>,
+    },
+    Health(SyncSender<ReactorHealthData>),
     Metrics(SyncSender<serde_json::Value>),
 }
 
@@ -119,15 +195,24 @@ impl Reactor {
             QueryRequest::Workspaces { space_id, resp } => {
                 let _ = resp.send(self.query_workspaces(space_id));
             }
-            QueryRequest::Windows { space_id, resp } => {
-                let _ = resp.send(self.query_windows(space_id));
+            QueryRequest::Windows { space_id, query, resp } => {
+                let _ = resp.send(self.query_windows_matching(space_id, &query));
             }
             QueryRequest::ActiveWorkspace { space_id, resp } => {
                 let _ = resp.send(self.query_active_workspace(space_id));
             }
+            QueryRequest::FocusedWindow(resp) => {
+                let _ = resp.send(self.query_focused_window());
+            }
+            QueryRequest::ActiveWorkspaceData { display, resp } => {
+                let _ = resp.send(self.query_active_workspace_data(display.as_ref()));
+            }
             QueryRequest::Displays(resp) => {
                 let _ = resp.send(self.query_displays());
             }
+            QueryRequest::Activation(resp) => {
+                let _ = resp.send(self.query_activation());
+            }
             QueryRequest::WorkspaceLayouts { space_id, workspace_id, resp } => {
                 let _ = resp.send(self.query_workspace_layouts(space_id, workspace_id));
             }
@@ -140,6 +225,21 @@ impl Reactor {
             QueryRequest::LayoutState { space_id, resp } => {
                 let _ = resp.send(self.query_layout_state(space_id));
             }
+            QueryRequest::LayoutTree { space_id, resp } => {
+                let _ = resp.send(self.query_layout_tree(space_id));
+            }
+            QueryRequest::FocusHistory(resp) => {
+                let _ = resp.send(self.query_focus_history());
+            }
+            QueryRequest::IgnoredWindows(resp) => {
+                let _ = resp.send(self.query_ignored_windows());
+            }
+            QueryRequest::EventLog { tail, resp } => {
+                let _ = resp.send(self.query_event_log(tail));
+            }
+            QueryRequest::Health(resp) => {
+                let _ = resp.send(self.handle_health_query());
+            }
             QueryRequest::Metrics(resp) => {
                 let _ = resp.send(self.query_metrics());
             }
@@ -160,15 +260,36 @@ impl Reactor {
     }
 
     pub fn query_windows(&self, space_id: Option<SpaceId>) -> Vec<WindowData> {
-        self.handle_windows_query(space_id)
+        self.handle_windows_query(space_id, &WindowQuery::default())
+    }
+
+    pub fn query_windows_matching(
+        &self,
+        space_id: Option<SpaceId>,
+        query: &WindowQuery,
+    ) -> Vec<WindowData> {
+        self.handle_windows_query(space_id, query)
     }
 
     pub fn query_active_workspace(&self, space_id: Option<SpaceId>) -> Option<VirtualWorkspaceId> {
         self.handle_active_workspace_query(space_id)
     }
 
+    pub fn query_focused_window(&self) -> Option<FocusedWindowData> {
+        self.handle_focused_window_query()
+    }
+
+    pub fn query_active_workspace_data(
+        &self,
+        display: Option<&DisplaySelector>,
+    ) -> Option<ActiveWorkspaceData> {
+        self.handle_active_workspace_data_query(display)
+    }
+
     pub fn query_displays(&self) -> Vec<DisplayData> { self.handle_displays_query() }
 
+    pub fn query_activation(&self) -> ActivationData { self.handle_activation_query() }
+
     pub fn query_workspace_layouts(
         &mut self,
         space_id: Option<SpaceId>,
@@ -187,6 +308,23 @@ impl Reactor {
         self.handle_layout_state_query(space_id)
     }
 
+    pub fn query_layout_tree(&self, space_id: Option<SpaceId>) -> Option<LayoutTreeData> {
+        self.handle_layout_tree_query(space_id)
+    }
+
+    pub fn query_focus_history(&self) -> Vec<FocusHistoryEntry> {
+        self.handle_focus_history_query()
+    }
+
+    pub fn query_ignored_windows(&self) -> Vec<IgnoredWindowData> {
+        self.handle_ignored_windows_query()
+    }
+
+    /// The last `tail` events handled, oldest first.
+    pub fn query_event_log(&self, tail: usize) -> Vec<EventLogEntry> {
+        self.recording_manager.event_log.tail(tail)
+    }
+
     pub fn query_metrics(&self) -> serde_json::Value { self.handle_metrics_query() }
 
     pub(super) fn maybe_send_menu_update(&mut self) {
@@ -205,7 +343,7 @@ impl Reactor {
         let active_workspace = self.layout_manager.layout_engine.active_workspace(active_space);
         let active_workspace_idx =
             self.layout_manager.layout_engine.active_workspace_idx(active_space);
-        let windows = self.handle_windows_query(Some(active_space));
+        let windows = self.handle_windows_query(Some(active_space), &WindowQuery::default());
 
         menu_tx.send(menu_bar::Event::Update(menu_bar::Update {
             active_space,
@@ -217,6 +355,136 @@ impl Reactor {
         }));
     }
 
+    pub(super) fn maybe_send_border_update(&mut self) {
+        if !self.config.settings.ui.borders.enabled {
+            return;
+        }
+        let Some(tx) = self.communication_manager.borders_tx.clone() else {
+            return;
+        };
+
+        let mut windows = Vec::new();
+        let mut grouped = Vec::new();
+        let active_spaces: Vec<SpaceId> = self.iter_active_spaces().collect();
+        for space in active_spaces {
+            if self
+                .layout_manager
+                .layout_engine
+                .active_workspace_for_space_has_fullscreen(space)
+            {
+                continue;
+            }
+            for window in self
+                .layout_manager
+                .layout_engine
+                .windows_in_active_workspace(&self.state.windows, space)
+            {
+                if let Some(state) = self.state.windows.window(window) {
+                    windows.push(borders::BorderTarget {
+                        window,
+                        window_server_id: state.info.sys_id,
+                        frame: state.frame_monotonic,
+                    });
+                }
+            }
+            if let Some(screen) = self.space_state.screen_by_space(space) {
+                let screen_frame = screen.frame;
+                let gaps = self
+                    .config
+                    .settings
+                    .layout
+                    .gaps
+                    .effective_for_display(screen.display_uuid_opt(), screen.name.as_deref());
+                let stack_line = &self.config.settings.ui.stack_line;
+                let groups = self.layout_manager.layout_engine.collect_group_containers(
+                    space,
+                    screen_frame,
+                    &gaps,
+                    stack_line.thickness(),
+                    stack_line.horiz_placement,
+                    stack_line.vert_placement,
+                );
+                grouped.extend(groups.into_iter().flat_map(|group| group.window_ids));
+            }
+        }
+
+        let settings = &self.config.settings.ui.borders;
+        let color = |color: &Option<String>| color.as_deref().and_then(Color::from_hex);
+        let colored = |kind, color: Option<Color>, windows: Vec<WindowId>| {
+            windows.into_iter().filter_map(move |window| {
+                Some(borders::Decoration { window, kind, color: Some(color?) })
+            })
+        };
+        let urgent: Vec<WindowId> = windows
+            .iter()
+            .map(|target| target.window)
+            .filter(|window| self.app_manager.unresponsive.contains(&window.pid))
+            .collect();
+        let decorations = self
+            .get_pending_drag_swap()
+            .map(|(_, target)| borders::Decoration {
+                window: target,
+                kind: borders::DecorationKind::DragTarget,
+                color: None,
+            })
+            .into_iter()
+            .chain(colored(
+                borders::DecorationKind::AlwaysOnTop,
+                color(&settings.always_on_top_color),
+                self.state.always_on_top.iter().copied().collect(),
+            ))
+            .chain(colored(
+                borders::DecorationKind::Group,
+                color(&settings.group_color),
+                grouped,
+            ))
+            .chain(colored(
+                borders::DecorationKind::Urgent,
+                color(&settings.urgent_color),
+                urgent,
+            ))
+            .collect();
+
+        let update = borders::Update {
+            focused: self.main_window(),
+            windows,
+            decorations,
+        };
+        // Most events change nothing a border draws; skip the round trip.
+        if self.notification_manager.last_border_update.as_ref() == Some(&update) {
+            return;
+        }
+        self.notification_manager.last_border_update = Some(update.clone());
+        tx.send(borders::Event::Update(update));
+    }
+
+    pub(super) fn maybe_send_preselect_update(&mut self) {
+        let Some(tx) = self.communication_manager.preselect_overlay_tx.clone() else {
+            return;
+        };
+
+        let active_spaces: Vec<SpaceId> = self.iter_active_spaces().collect();
+        let preselections = active_spaces
+            .into_iter()
+            .filter_map(|space| self.layout_manager.layout_engine.preselection_for_space(space))
+            .filter_map(|(window, direction, ratio)| {
+                let state = self.state.windows.window(window)?;
+                Some(preselect_overlay::Preselection {
+                    window,
+                    window_server_id: state.info.sys_id,
+                    frame: state.frame_monotonic,
+                    direction,
+                    ratio,
+                })
+            })
+            .collect::<Vec<_>>();
+        if self.notification_manager.last_preselect_update.as_ref() == Some(&preselections) {
+            return;
+        }
+        self.notification_manager.last_preselect_update = Some(preselections.clone());
+        tx.send(preselect_overlay::Event::Update(preselections));
+    }
+
     fn menu_bar_space(&self) -> Option<SpaceId> {
         self.resolve_menu_bar_space_with_preferred(self.space_state.menu_bar_space)
     }
@@ -254,6 +522,12 @@ impl Reactor {
                 Vec::new()
             };
 
+        let display = space_id.and_then(|space| {
+            self.space_state.screens.iter().find(|screen| screen.space == Some(space))
+        });
+        let display_uuid = display.and_then(|screen| screen.display_uuid_owned());
+        let display_name = display.and_then(|screen| screen.name.clone());
+
         for (index, (workspace_id, workspace_name)) in workspace_list.iter().enumerate() {
             let is_active = if let Some(space) = space_id {
                 self.layout_manager.layout_engine.active_workspace(space) == Some(*workspace_id)
@@ -283,9 +557,10 @@ impl Reactor {
                         .or_else(|| self.space_state.screens.first().cloned());
 
                     if let Some(screen) = screen_info {
-                        let display_uuid = screen.display_uuid_opt();
-                        let gaps =
-                            self.config.settings.layout.gaps.effective_for_display(display_uuid);
+                        let gaps = self.config.settings.layout.gaps.effective_for_display(
+                            screen.display_uuid_opt(),
+                            screen.name.as_deref(),
+                        );
                         self.layout_manager.layout_engine.calculate_layout_for_workspace(
                             &self.state.windows,
                             space,
@@ -339,6 +614,8 @@ impl Reactor {
                 window_count: windows.len(),
                 windows,
                 index,
+                display_uuid: display_uuid.clone(),
+                display_name: display_name.clone(),
             });
         }
 
@@ -392,6 +669,68 @@ impl Reactor {
         self.layout_manager.layout_engine.active_workspace(space_id)
     }
 
+    fn handle_focused_window_query(&self) -> Option<FocusedWindowData> {
+        let wid = self.main_window()?;
+        let window = self.state.windows.window(wid)?;
+        let app = self.app_manager.apps.get(&wid.pid);
+        let layout_engine = &self.layout_manager.layout_engine;
+        let space = self.best_space_for_window_id(wid);
+        let workspace_name = space.and_then(|space| {
+            let workspace = layout_engine.virtual_workspace_manager().workspace_for_window(
+                &self.state.windows,
+                space,
+                wid,
+            )?;
+            layout_engine.workspace_name(space, workspace)
+        });
+        Some(FocusedWindowData {
+            id: wid,
+            window_server_id: window.info.sys_id.map(|id| id.as_u32()),
+            title: window.info.title.clone(),
+            app_name: app.and_then(|app| app.info.localized_name.clone()),
+            bundle_id: app.and_then(|app| app.info.bundle_id.clone()),
+            is_floating: layout_engine.is_window_floating(wid),
+            space_id: space.map(|space| space.get()),
+            workspace_name,
+        })
+    }
+
+    fn handle_active_workspace_data_query(
+        &self,
+        display: Option<&DisplaySelector>,
+    ) -> Option<ActiveWorkspaceData> {
+        let screen = match display {
+            Some(selector) => Some(self.screen_for_selector(selector, None)?),
+            None => None,
+        };
+        let space = match screen {
+            Some(screen) => screen.space?,
+            None => self.default_query_space()?,
+        };
+        let screen = screen
+            .or_else(|| self.space_state.screens.iter().find(|screen| screen.space == Some(space)));
+
+        let workspaces = self.layout_manager.layout_engine.virtual_workspace_manager();
+        let workspace_id = workspaces.active_workspace(space)?;
+        let (index, name) = workspaces
+            .existing_workspaces(space)
+            .into_iter()
+            .enumerate()
+            .find_map(|(index, (id, name))| (id == workspace_id).then_some((index, name)))?;
+        Some(ActiveWorkspaceData {
+            id: format!("{:?}", workspace_id),
+            index,
+            name,
+            layout_mode: self.layout_manager.layout_engine.layout_mode_at(space).to_string(),
+            window_count: workspaces
+                .workspace_windows(&self.state.windows, space, workspace_id)
+                .len(),
+            space_id: space.get(),
+            display_uuid: screen.and_then(|screen| screen.display_uuid_owned()),
+            display_name: screen.and_then(|screen| screen.name.clone()),
+        })
+    }
+
     fn handle_displays_query(&self) -> Vec<DisplayData> {
         let active_context_space = self.active_display_space();
         let active_space_ids = self.active_space_ids();
@@ -431,32 +770,94 @@ impl Reactor {
                     },
                     active_space_ids: per_display_active_space_ids,
                     inactive_space_ids: per_display_inactive_space_ids,
+                    gaps: self
+                        .config
+                        .settings
+                        .layout
+                        .gaps
+                        .effective_for_display(screen.display_uuid_opt(), screen.name.as_deref()),
                 }
             })
             .collect()
     }
 
-    fn handle_windows_query(&self, space_id: Option<SpaceId>) -> Vec<WindowData> {
+    fn handle_activation_query(&self) -> ActivationData {
+        let policy = &self.space_activation_policy;
+        ActivationData {
+            suspended: policy.is_suspended(),
+            displays: self
+                .space_state
+                .screens
+                .iter()
+                .map(|screen| DisplayActivationData {
+                    display_uuid: screen.display_uuid.clone(),
+                    name: screen.name.clone(),
+                    space_id: screen.space.map(|space| space.get()),
+                    activated: screen.space.is_some_and(|space| self.is_space_active(space)),
+                    display_activated: policy.display_override(&screen.display_uuid),
+                })
+                .collect(),
+        }
+    }
+
+    fn handle_windows_query(
+        &self,
+        space_id: Option<SpaceId>,
+        query: &WindowQuery,
+    ) -> Vec<WindowData> {
         let target_space = space_id.or_else(|| self.default_query_space());
 
-        if let Some(space) = target_space {
-            let active_windows = self
-                .layout_manager
-                .layout_engine
-                .windows_in_active_workspace(&self.state.windows, space);
-
-            active_windows
-                .into_iter()
-                .filter_map(|wid| self.create_window_data(wid))
-                .collect()
-        } else {
-            self.state
-                .windows
-                .iter_windows()
-                .map(|(wid, _)| wid)
-                .filter_map(|wid| self.create_window_data(wid))
-                .collect()
-        }
+        // Candidates carry their workspace index, which only `workspace:`
+        // predicates need.
+        let candidates: Vec<(WindowId, Option<usize>)> =
+            if query.filter.as_ref().is_some_and(|filter| filter.mentions_workspace()) {
+                // A workspace name or index only means something within a space,
+                // so without one every known space is searched.
+                let spaces: Vec<SpaceId> = match target_space {
+                    Some(space) => vec![space],
+                    None => self.space_state.iter_known_spaces().collect(),
+                };
+                let workspaces = self.layout_manager.layout_engine.virtual_workspace_manager();
+                let mut seen = HashSet::default();
+                spaces
+                    .into_iter()
+                    .flat_map(|space| {
+                        workspaces.existing_workspaces(space).into_iter().enumerate().flat_map(
+                            move |(index, (workspace, _))| {
+                                workspaces
+                                    .workspace_windows(&self.state.windows, space, workspace)
+                                    .into_iter()
+                                    .map(move |wid| (wid, Some(index)))
+                            },
+                        )
+                    })
+                    .filter(|(wid, _)| seen.insert(*wid))
+                    .collect()
+            } else if let Some(space) = target_space {
+                self.layout_manager
+                    .layout_engine
+                    .windows_in_active_workspace(&self.state.windows, space)
+                    .into_iter()
+                    .map(|wid| (wid, None))
+                    .collect()
+            } else {
+                self.state.windows.iter_windows().map(|(wid, _)| (wid, None)).collect()
+            };
+
+        let mut windows: Vec<WindowData> = candidates
+            .into_iter()
+            .filter_map(|(wid, workspace_index)| {
+                let window = self.create_window_data(wid)?;
+                query
+                    .matches(WindowRuleContext {
+                        workspace_index,
+                        ..window.rule_context()
+                    })
+                    .then_some(window)
+            })
+            .collect();
+        query.sort(&mut windows);
+        windows
     }
 
     fn handle_window_info_query(&self, window_id: WindowId) -> Option<WindowData> {
@@ -525,6 +926,114 @@ impl Reactor {
         })
     }
 
+    fn handle_layout_tree_query(&self, space_id: Option<SpaceId>) -> Option<LayoutTreeData> {
+        let space = space_id.or_else(|| self.default_query_space())?;
+        let layout_engine = &self.layout_manager.layout_engine;
+        let workspace_id = layout_engine.active_workspace(space)?;
+        let root = layout_engine.layout_tree(space)?;
+        Some(LayoutTreeData {
+            space_id: space.get(),
+            workspace_id: format!("{:?}", workspace_id),
+            workspace_name: layout_engine.workspace_name(space, workspace_id).unwrap_or_default(),
+            layout_mode: layout_engine.layout_mode_at(space).to_string(),
+            root,
+        })
+    }
+
+    fn handle_focus_history_query(&self) -> Vec<FocusHistoryEntry> {
+        let layout_engine = &self.layout_manager.layout_engine;
+        layout_engine
+            .global_focus_history()
+            .filter_map(|(recorded_space, window_id)| {
+                let window = self.create_window_data(window_id)?;
+                let space = self.best_space_for_window_id(window_id).unwrap_or(recorded_space);
+                let workspace_id = layout_engine.virtual_workspace_manager().workspace_for_window(
+                    &self.state.windows,
+                    space,
+                    window_id,
+                );
+                Some(FocusHistoryEntry {
+                    space_id: space.get(),
+                    workspace_id: workspace_id.map(|id| format!("{:?}", id)),
+                    workspace_name: workspace_id
+                        .and_then(|id| layout_engine.workspace_name(space, id)),
+                    window,
+                })
+            })
+            .collect()
+    }
+
+    fn handle_ignored_windows_query(&self) -> Vec<IgnoredWindowData> {
+        let heuristics = &self.config.settings.window_heuristics;
+        let mut ignored: Vec<IgnoredWindowData> = self
+            .state
+            .windows
+            .iter_windows()
+            .filter_map(|(wid, window)| {
+                let heuristic = heuristic_ignore_reason(&window.info, heuristics);
+                let reason = if window.is_manageable {
+                    if !window.ignore_app_rule {
+                        return None;
+                    }
+                    heuristic.unwrap_or(IgnoreReason::AppRule)
+                } else {
+                    let reason = utils::unmanageable_reason(
+                        window.info.sys_id,
+                        window.info.is_minimized,
+                        window.info.is_standard,
+                        window.info.is_root,
+                        |wsid| self.state.windows.get_window_server_info(wsid),
+                    )?;
+                    // PiP players and panels usually fail the level or subrole
+                    // checks too; name them for what they are.
+                    match reason {
+                        IgnoreReason::Minimized => reason,
+                        _ => heuristic.unwrap_or(reason),
+                    }
+                };
+                let app = self.app_manager.apps.get(&wid.pid);
+                Some(IgnoredWindowData {
+                    id: wid,
+                    app_name: app.and_then(|app| app.info.localized_name.clone()),
+                    bundle_id: app.and_then(|app| app.info.bundle_id.clone()),
+                    title: window.info.title.clone(),
+                    ax_subrole: window.info.ax_subrole.clone(),
+                    frame: window.frame_monotonic,
+                    reason,
+                })
+            })
+            .collect();
+        ignored.sort_by_key(|window| window.id);
+        ignored
+    }
+
+    fn handle_health_query(&self) -> ReactorHealthData {
+        let mut unresponsive_apps: Vec<String> = self
+            .app_manager
+            .unresponsive
+            .iter()
+            .map(|pid| {
+                self.app_manager
+                    .apps
+                    .get(pid)
+                    .and_then(|app| app.info.localized_name.clone())
+                    .unwrap_or_else(|| format!("pid {pid}"))
+            })
+            .collect();
+        unresponsive_apps.sort();
+        ReactorHealthData {
+            managed_windows: self
+                .state
+                .windows
+                .iter_windows()
+                .filter(|(_, window)| window.is_effectively_manageable())
+                .count(),
+            unresponsive_apps,
+            stage_manager_cooperating: self.stage_manager_state
+                == crate::model::reactor::StageManagerState::Cooperating,
+        }
+    }
+
     fn handle_metrics_query(&self) -> serde_json::Value {
         let stats = self
             .layout_manager
@@ -544,6 +1053,7 @@ impl Reactor {
             "applications": self.app_manager.apps.len(),
             "screens": self.space_state.screens.len(),
             "workspace_stats": workspace_stats,
+            "runtime": crate::common::metrics::snapshot(),
         })
     }
 
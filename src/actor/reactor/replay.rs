@@ -3,6 +3,7 @@ use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
 
+use anyhow::{Context, bail};
 #[cfg(test)]
 use tempfile::NamedTempFile;
 use tracing::Span;
@@ -67,27 +68,130 @@ impl Record {
     }
 }
 
+/// A trace written by [`Record`]: the config and layout the reactor started
+/// with, followed by every event it handled.
+pub struct Trace {
+    config: Config,
+    layout: String,
+    events: Vec<String>,
+}
+
+impl Trace {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut lines = reader.lines();
+        let Some(config) = lines.next() else {
+            bail!("empty trace");
+        };
+        let config = ron::de::from_str(&config?)?;
+        let Some(layout) = lines.next() else {
+            bail!("trace has no layout line");
+        };
+        let events = lines.collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            config,
+            layout: layout?,
+            events,
+        })
+    }
+
+    pub fn config(&self) -> &Config { &self.config }
+
+    /// Replays the trace under `config` instead of the recorded one.
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn len(&self) -> usize { self.events.len() }
+
+    pub fn is_empty(&self) -> bool { self.events.is_empty() }
+
+    /// Runs every event through a reactor whose app requests go to
+    /// `requests` instead of the accessibility API.
+    fn run(self, requests: actor::Sender<Request>) -> anyhow::Result<Reactor> {
+        DESERIALIZE_THREAD_HANDLE
+            .with(|h| h.borrow_mut().replace(AppThreadHandle::new_for_test(requests)));
+        let layout = LayoutEngine::deserialize_from_str(&self.layout)?;
+        let (broadcast_tx, _) = actor::channel();
+        let mut reactor =
+            Reactor::new(self.config, layout, Record::new(None), broadcast_tx, None, false);
+        for (line, event) in self.events.iter().enumerate() {
+            let event: Event = ron::de::from_str(event)
+                .with_context(|| format!("trace event {} is malformed", line + 1))?;
+            reactor.handle_event(event);
+        }
+        Ok(reactor)
+    }
+
+    /// Replays the trace without touching any real application, keeping the
+    /// requests the reactor would have sent them.
+    pub fn run_headless(self) -> anyhow::Result<HeadlessReactor> {
+        let (tx, requests) = actor::channel();
+        let reactor = self.run(tx)?;
+        Ok(HeadlessReactor { reactor, requests })
+    }
+}
+
+/// A reactor left in the state a replayed trace put it in.
+pub struct HeadlessReactor {
+    reactor: Reactor,
+    requests: actor::Receiver<Request>,
+}
+
+impl HeadlessReactor {
+    pub fn reactor(&self) -> &Reactor { &self.reactor }
+
+    /// Requests sent to applications since the last call.
+    pub fn take_requests(&mut self) -> Vec<Request> {
+        std::iter::from_fn(|| self.requests.try_recv().ok())
+            .map(|(_, request)| request)
+            .collect()
+    }
+
+    /// Layout trees of the active workspace on every screen, in screen order.
+    pub fn layout_json(&self) -> serde_json::Value {
+        let trees: Vec<_> = self
+            .reactor
+            .space_state
+            .screens
+            .iter()
+            .filter_map(|screen| screen.space)
+            .filter_map(|space| self.reactor.query_layout_tree(Some(space)))
+            .collect();
+        serde_json::to_value(trees).unwrap_or_default()
+    }
+
+    /// Every tracked window on the screens' spaces, as returned by the
+    /// `windows` query.
+    pub fn windows_json(&self) -> serde_json::Value {
+        let windows: Vec<_> = self
+            .reactor
+            .space_state
+            .screens
+            .iter()
+            .filter_map(|screen| screen.space)
+            .flat_map(|space| self.reactor.query_windows(Some(space)))
+            .collect();
+        serde_json::to_value(windows).unwrap_or_default()
+    }
+}
+
 pub fn replay(
     path: &Path,
     mut on_event: impl FnMut(Span, Request) + Send + 'static,
 ) -> anyhow::Result<()> {
-    let file = BufReader::new(File::open(path)?);
+    let trace = Trace::load(path)?;
     let (tx, mut rx) = actor::channel();
-    let handle = AppThreadHandle::new_for_test(tx);
-    DESERIALIZE_THREAD_HANDLE.with(|h| h.borrow_mut().replace(handle));
-    let mut lines = file.lines();
-    let config = ron::de::from_str(&lines.next().expect("Empty restore file")?)?;
-    let layout = LayoutEngine::deserialize_from_str(&lines.next().expect("Expected layout line")?)?;
-    let (broadcast_tx, _) = actor::channel();
-    let mut reactor = Reactor::new(config, layout, Record::new(None), broadcast_tx, None, false);
     std::thread::spawn(move || {
         while let Some((span, request)) = rx.blocking_recv() {
             let _ = span.enter();
             on_event(span, request);
         }
     });
-    for line in lines {
-        reactor.handle_event(ron::de::from_str(&line?)?);
-    }
+    trace.run(tx)?;
     Ok(())
 }
@@ -1,7 +1,7 @@
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use tracing::debug;
 
-use super::{Event, Reactor, Record, Requested, ScreenInfo, TransactionId};
+use super::{Event, Reactor, Record, Requested, ScreenInfo, SpaceEventKind, TransactionId};
 use crate::actor;
 use crate::actor::app::{AppThreadHandle, Request, WindowId};
 use crate::actor::spaces::ForwardedSpaceState;
@@ -125,6 +125,8 @@ pub struct Apps {
     tx: actor::Sender<Request>,
     rx: actor::Receiver<Request>,
     pub windows: BTreeMap<WindowId, TestWindowState>,
+    /// The user space windows return to when they leave native fullscreen.
+    pub space: SpaceId,
 }
 
 #[derive(Default, PartialEq, Debug, Clone)]
@@ -133,6 +135,8 @@ pub struct TestWindowState {
     pub last_sent_txid: TransactionId,
     pub animating: bool,
     pub frame: CGRect,
+    pub sys_id: Option<WindowServerId>,
+    pub native_fullscreen: bool,
 }
 
 impl Apps {
@@ -142,6 +146,7 @@ impl Apps {
             tx,
             rx,
             windows: BTreeMap::new(),
+            space: SpaceId::new(1),
         }
     }
 
@@ -174,6 +179,7 @@ impl Apps {
         for (id, info) in (1..).map(|idx| WindowId::new(pid, idx)).zip(&windows) {
             self.windows.insert(id, TestWindowState {
                 frame: info.frame,
+                sys_id: info.sys_id,
                 ..Default::default()
             });
         }
@@ -335,7 +341,28 @@ impl Apps {
                     ));
                 }
                 Request::Raise(..) => todo!(),
+                Request::RaiseWithoutFocus(_) => {}
                 Request::CloseWindow(..) => todo!(),
+                Request::SetMinimized(wid, true) => events.push(Event::WindowMinimized(wid)),
+                Request::SetMinimized(wid, false) => {
+                    events.push(Event::WindowDeminiaturized(wid));
+                }
+                Request::ToggleNativeFullscreen(wid) => {
+                    let window = self.windows.entry(wid).or_default();
+                    let Some(wsid) = window.sys_id else { continue };
+                    window.native_fullscreen = !window.native_fullscreen;
+                    // The window server moves the window to its own space (or
+                    // back), which is all rift gets to see of the transition.
+                    events.push(if window.native_fullscreen {
+                        Event::WindowServerAppeared(
+                            wsid,
+                            SpaceId::new(0x400000000 + self.space.get()),
+                            SpaceEventKind::Fullscreen,
+                        )
+                    } else {
+                        Event::WindowServerAppeared(wsid, self.space, SpaceEventKind::User)
+                    });
+                }
             }
         }
         debug!(?events);
@@ -6,6 +6,7 @@ use super::*;
 use crate::actor::app::{AppThreadHandle, Request, pid_t};
 use crate::common::config::{OuterGaps, WorkspaceSelector};
 use crate::layout_engine::{Direction, LayoutCommand, LayoutEngine, LayoutEvent};
+use crate::model::server::CommandErrorKind;
 use crate::model::window_store::NativeFullscreenTransition;
 use crate::sys::app::{AppInfo, WindowInfo};
 use crate::sys::geometry::SameAs;
@@ -316,6 +317,48 @@ fn forwarded_space_snapshot_respects_toggled_space_activation_policy() {
     );
 }
 
+#[test]
+fn toggle_display_activated_only_affects_the_selected_display() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let left = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1440., 900.));
+    let right = CGRect::new(CGPoint::new(1440., 0.), CGSize::new(1440., 900.));
+    let (left_space, right_space) = (SpaceId::new(1), SpaceId::new(2));
+    reactor.handle_event(space_state_event(vec![left, right], vec![
+        Some(left_space),
+        Some(right_space),
+    ]));
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::ToggleDisplayActivated(DisplaySelector::Uuid("test-display-1".into())),
+    )));
+    assert!(reactor.is_space_active(left_space));
+    assert!(!reactor.is_space_active(right_space));
+
+    let activation = reactor.query_activation();
+    assert_eq!(activation.displays.len(), 2);
+    assert!(activation.displays[0].activated);
+    assert_eq!(activation.displays[0].display_activated, None);
+    assert!(!activation.displays[1].activated);
+    assert_eq!(activation.displays[1].display_activated, Some(false));
+
+    // A different space showing up on the display stays unmanaged.
+    let other_space = SpaceId::new(3);
+    reactor.handle_event(space_state_event(vec![left, right], vec![
+        Some(left_space),
+        Some(other_space),
+    ]));
+    assert!(!reactor.is_space_active(other_space));
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::ToggleDisplayActivated(DisplaySelector::Uuid("test-display-1".into())),
+    )));
+    assert!(reactor.is_space_active(other_space));
+}
+
 #[test]
 fn layout_commands_follow_active_display_space_across_active_displays() {
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
@@ -899,6 +942,187 @@ fn queries_prefer_authoritative_active_space_over_stale_command_space() {
     );
 }
 
+#[test]
+fn focused_window_and_active_workspace_queries_are_compact() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(2),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let focused = reactor.query_focused_window().expect("focused window");
+    assert_eq!(focused.id, WindowId::new(1, 1));
+    assert_eq!(focused.space_id, Some(space.get()));
+    assert!(!focused.is_floating);
+
+    let workspace = reactor.query_active_workspace_data(None).expect("active workspace");
+    assert_eq!(workspace.space_id, space.get());
+    assert_eq!(workspace.index, 0);
+    assert_eq!(workspace.window_count, 2);
+    assert_eq!(
+        reactor
+            .query_active_workspace_data(Some(&DisplaySelector::Index(0)))
+            .map(|workspace| workspace.space_id),
+        Some(space.get())
+    );
+    assert!(reactor.query_active_workspace_data(Some(&DisplaySelector::Index(3))).is_none());
+}
+
+#[test]
+fn command_results_classify_failures() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let missing = reactor.run_command_with_result(
+        Command::Layout(LayoutCommand::SwapWindows(
+            WindowId::new(1, 1),
+            WindowId::new(9, 9),
+        )),
+        None,
+    );
+    assert!(!missing.ok);
+    assert_eq!(missing.error, Some(CommandErrorKind::WindowNotFound));
+
+    let no_workspace = reactor
+        .run_command_with_result(Command::Layout(LayoutCommand::SwitchToWorkspace(99)), None);
+    assert_eq!(no_workspace.error, Some(CommandErrorKind::WorkspaceNotFound));
+
+    let swapped = reactor.run_command_with_result(
+        Command::Layout(LayoutCommand::SwapWindows(
+            WindowId::new(1, 1),
+            WindowId::new(1, 2),
+        )),
+        None,
+    );
+    assert!(swapped.ok);
+    assert!(swapped.window_ids.contains(&WindowId::new(1, 1)));
+    assert!(swapped.window_ids.contains(&WindowId::new(1, 2)));
+    assert_eq!(swapped.space_id, Some(space.get()));
+    assert!(swapped.workspace_id.is_some());
+}
+
+#[test]
+fn dry_run_reports_frame_changes_without_applying_them() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let (a, b) = (WindowId::new(1, 1), WindowId::new(1, 2));
+    let frames = |reactor: &Reactor| {
+        [a, b].map(|wid| reactor.state.windows.window(wid).unwrap().frame_monotonic)
+    };
+    let before = frames(&reactor);
+
+    let result = reactor.dry_run_command(Command::Layout(LayoutCommand::SwapWindows(a, b)));
+    assert!(result.ok);
+    let changes = result.frame_changes.unwrap();
+    assert_eq!(changes.len(), 2);
+    let change = changes.iter().find(|change| change.window_id == a).unwrap();
+    assert_eq!(change.from, Some(before[0]));
+    assert_eq!(change.to, Some(before[1]));
+
+    assert!(apps.requests().is_empty());
+    assert_eq!(frames(&reactor), before);
+    let swapped =
+        reactor.run_command_with_result(Command::Layout(LayoutCommand::SwapWindows(a, b)), None);
+    assert!(swapped.ok);
+    assert!(swapped.frame_changes.is_none());
+
+    let not_layout = reactor.dry_run_command(Command::Reactor(ReactorCommand::Debug));
+    assert_eq!(not_layout.error, Some(CommandErrorKind::InvalidCommand));
+}
+
+#[test]
+fn rescue_brings_offscreen_windows_back_into_the_layout() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let stranded = WindowId::new(1, 2);
+    let tiled = reactor.state.windows.window(stranded).unwrap().frame_monotonic;
+    reactor.state.windows.window_mut(stranded).unwrap().frame_monotonic =
+        CGRect::new(CGPoint::new(5000., 3000.), tiled.size);
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::RescueWindows)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert_eq!(
+        reactor.state.windows.window(stranded).unwrap().frame_monotonic,
+        tiled
+    );
+}
+
+#[test]
+fn layout_pass_ends_the_workspace_swipe_preview() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.workspace_transition =
+        crate::common::config::WorkspaceTransition::Slide;
+    let space = SpaceId::new(1);
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::WorkspaceSwipePreview {
+        progress: 0.5,
+        skip_empty: None,
+    });
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(reactor.workspace_switch_manager.previewing_space, Some(space));
+    let window = WindowId::new(1, 1);
+    assert!(reactor.state.windows.window(window).unwrap().frame_monotonic.origin.x < 0.0);
+
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert_eq!(reactor.workspace_switch_manager.previewing_space, None);
+    assert!(reactor.state.windows.window(window).unwrap().frame_monotonic.origin.x >= 0.0);
+}
+
 #[test]
 fn menu_bar_space_prefers_active_menu_bar_display_space() {
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
@@ -1614,6 +1838,8 @@ fn cross_display_drag_clears_source_floating_position() {
             final_space: Some(space2),
             visible_spaces,
             visible_space_centers,
+            floating_snap: None,
+            floating_drop: None,
         },
     )
     .unwrap();
@@ -2063,6 +2289,120 @@ fn known_window_server_appearance_restores_same_workspace_after_fullscreen() {
     );
 }
 
+#[test]
+fn native_fullscreen_windows_get_no_frame_requests() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    let wid = WindowId::new(1, 1);
+
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app_with_opts(1, make_windows(2), Some(wid), true, true));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::ToggleNativeFullscreen,
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(apps.windows[&wid].native_fullscreen);
+    assert!(reactor.state.windows.is_window_native_fullscreen_suspended(wid));
+
+    let frame_before = apps.windows[&wid].frame;
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+    let targets_window = |request: &Request| match request {
+        Request::SetWindowFrame(target, ..)
+        | Request::SetWindowPos(target, ..)
+        | Request::AnimationFrame { wid: target, .. } => *target == wid,
+        Request::SetBatchWindowFrame(frames, ..) => frames.iter().any(|(target, _)| *target == wid),
+        _ => false,
+    };
+    let requests = apps.requests();
+    assert!(
+        !requests.iter().any(targets_window),
+        "relayout must leave the native fullscreen window alone: {requests:?}"
+    );
+    for event in apps.simulate_events_for_requests(requests) {
+        reactor.handle_event(event);
+    }
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(apps.windows[&wid].frame, frame_before);
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::ToggleNativeFullscreen,
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(!apps.windows[&wid].native_fullscreen);
+    assert!(!reactor.state.windows.is_window_native_fullscreen_suspended(wid));
+}
+
+#[test]
+fn always_on_top_windows_are_raised_over_newly_focused_windows() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    let pinned = WindowId::new(1, 1);
+    let other = WindowId::new(1, 2);
+    let raised = |requests: Vec<Request>| -> Vec<WindowId> {
+        requests
+            .into_iter()
+            .filter_map(|request| match request {
+                Request::RaiseWithoutFocus(wid) => Some(wid),
+                _ => None,
+            })
+            .collect()
+    };
+
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app_with_opts(1, make_windows(2), Some(pinned), true, true));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let toggle_on_top = || Event::Command(Command::Reactor(ReactorCommand::ToggleAlwaysOnTop));
+    reactor.handle_event(toggle_on_top());
+    assert!(
+        reactor.state.always_on_top.is_empty(),
+        "tiled windows cannot be kept on top"
+    );
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::ToggleWindowFloating,
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor.handle_event(toggle_on_top());
+    assert!(reactor.state.always_on_top.contains(&pinned));
+    assert!(raised(apps.requests()).is_empty());
+
+    reactor.handle_event(Event::WindowServerFocusChanged(other, space));
+    assert_eq!(raised(apps.requests()), vec![pinned]);
+    reactor.handle_event(Event::WindowServerFocusChanged(other, space));
+    assert!(
+        raised(apps.requests()).is_empty(),
+        "raised once per focus change"
+    );
+
+    reactor.handle_event(Event::WindowServerFocusChanged(pinned, space));
+    assert!(raised(apps.requests()).is_empty());
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::ToggleWindowFloating,
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(
+        reactor.state.always_on_top.is_empty(),
+        "tiling the window again releases it"
+    );
+}
+
 #[test]
 fn fullscreen_tracking_survives_until_ax_window_id_arrives() {
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
@@ -2752,6 +3092,82 @@ fn mission_control_enter_clears_active_drag_state() {
     assert!(reactor.drag_manager.skip_layout_for_window.is_none());
 }
 
+/// Two tiled windows with the first one dragged and the second previewed in
+/// its tile. Returns the previewed window and the frame of its own tile.
+fn reactor_with_swap_preview(apps: &mut Apps, reactor: &mut Reactor) -> (WindowId, CGRect) {
+    let space = SpaceId::new(1);
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(space)],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(reactor);
+
+    let (dragged, target) = (WindowId::new(1, 1), WindowId::new(1, 2));
+    let origin = reactor.state.windows.window(dragged).unwrap().frame_monotonic;
+    let tile = reactor.state.windows.window(target).unwrap().frame_monotonic;
+    reactor.ensure_active_drag(dragged, &origin);
+    let _ = reactor.drag_manager.drag_swap_manager.on_frame_change(dragged, origin, &[]);
+    assert!(super::animation::AnimationManager::animate_layout(
+        reactor,
+        space,
+        &[(target, origin)],
+        false,
+        None,
+    ));
+    reactor.drag_manager.swap_preview = Some((target, tile));
+    apps.requests();
+    (target, tile)
+}
+
+fn requests_frame(requests: &[Request], wid: WindowId, frame: CGRect) -> bool {
+    requests.iter().any(|request| match request {
+        Request::SetWindowFrame(target, target_frame, ..) => {
+            *target == wid && *target_frame == frame
+        }
+        Request::SetBatchWindowFrame(frames, ..) => frames.contains(&(wid, frame)),
+        _ => false,
+    })
+}
+
+#[test]
+fn mission_control_enter_reverts_the_drag_swap_preview() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let (target, tile) = reactor_with_swap_preview(&mut apps, &mut reactor);
+
+    reactor.handle_event(Event::MissionControlNativeEntered);
+
+    assert!(reactor.drag_manager.swap_preview.is_none());
+    assert_eq!(
+        reactor.state.windows.window(target).unwrap().frame_monotonic,
+        tile
+    );
+    let requests = apps.requests();
+    assert!(requests_frame(&requests, target, tile), "{requests:?}");
+}
+
+#[test]
+fn destroying_the_dragged_window_reverts_the_drag_swap_preview() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let (target, tile) = reactor_with_swap_preview(&mut apps, &mut reactor);
+
+    reactor.handle_event(Event::WindowDestroyed(WindowId::new(1, 1)));
+
+    assert!(reactor.drag_manager.swap_preview.is_none());
+    let requests = apps.requests();
+    assert!(requests_frame(&requests, target, tile), "{requests:?}");
+}
+
 #[test]
 fn it_ignores_windows_on_disabled_spaces() {
     let mut apps = Apps::new();
@@ -3159,7 +3575,7 @@ fn auto_workspace_switch_focuses_activated_window_not_stale_workspace_focus() {
 }
 
 #[test]
-fn windows_discovered_does_not_reintroduce_inactive_workspace_window() {
+fn only_overview_focus_requests_switch_to_a_hidden_workspace() {
     let mut apps = Apps::new();
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
         &crate::common::config::VirtualWorkspaceSettings::default(),
@@ -3168,26 +3584,76 @@ fn windows_discovered_does_not_reintroduce_inactive_workspace_window() {
     ));
     let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
     let space = SpaceId::new(1);
+    let hidden = WindowId::new(1, 2);
 
     reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
     reactor.handle_events(apps.make_app(1, make_windows(2)));
     apps.simulate_until_quiet(&mut reactor);
 
+    reactor.send_layout_event(LayoutEvent::WindowFocused(space, hidden));
     reactor.handle_event(Event::Command(Command::Layout(
         LayoutCommand::MoveWindowToWorkspace {
             workspace: WorkspaceSelector::Index(1),
             follow: false,
-            window_id: Some(2),
+            window_id: None,
         },
     )));
     apps.simulate_until_quiet(&mut reactor);
+    let first = reactor.layout_manager.layout_engine.active_workspace(space);
 
-    reactor.handle_event(Event::Command(Command::Layout(
-        LayoutCommand::SwitchToWorkspace(1),
-    )));
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::FocusWindow {
+        window_id: hidden,
+        window_server_id: None,
+        switch_workspace: false,
+    })));
     apps.simulate_until_quiet(&mut reactor);
-
-    reactor.handle_event(Event::WindowsDiscovered {
+    assert_eq!(
+        reactor.layout_manager.layout_engine.active_workspace(space),
+        first
+    );
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::FocusWindow {
+        window_id: hidden,
+        window_server_id: None,
+        switch_workspace: true,
+    })));
+    apps.simulate_until_quiet(&mut reactor);
+    assert_ne!(
+        reactor.layout_manager.layout_engine.active_workspace(space),
+        first
+    );
+}
+
+#[test]
+fn windows_discovered_does_not_reintroduce_inactive_workspace_window() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::MoveWindowToWorkspace {
+            workspace: WorkspaceSelector::Index(1),
+            follow: false,
+            window_id: Some(2),
+        },
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SwitchToWorkspace(1),
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::WindowsDiscovered {
         pid: 1,
         new: vec![],
         known_visible: vec![WindowId::new(1, 1), WindowId::new(1, 2)],
@@ -3202,6 +3668,52 @@ fn windows_discovered_does_not_reintroduce_inactive_workspace_window() {
     );
 }
 
+#[test]
+fn window_queries_evaluate_matcher_predicates_across_workspaces() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    let hidden = WindowId::new(1, 2);
+
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::MoveWindowToWorkspace {
+            workspace: WorkspaceSelector::Index(1),
+            follow: false,
+            window_id: Some(hidden.idx.get()),
+        },
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let query = |filter: &str| crate::model::server::WindowQuery {
+        filter: Some(crate::model::WindowMatcher::parse(filter).unwrap()),
+        sort: None,
+    };
+    let ids = |windows: Vec<WindowData>| windows.into_iter().map(|w| w.id).collect::<Vec<_>>();
+
+    assert_eq!(
+        ids(reactor.query_windows_matching(Some(space), &query("workspace:1"))),
+        vec![hidden]
+    );
+    assert_eq!(
+        ids(reactor.query_windows_matching(Some(space), &query("floating:false"))),
+        vec![WindowId::new(1, 1)],
+        "without a workspace predicate only the active workspace is searched"
+    );
+    assert!(
+        reactor
+            .query_windows_matching(Some(space), &query("workspace:1 && floating:true"))
+            .is_empty()
+    );
+}
+
 #[test]
 fn workspace_query_uses_authoritative_assignment_after_move() {
     let mut apps = Apps::new();
@@ -3606,6 +4118,31 @@ fn menu_open_state_is_cleared_when_owner_deactivates() {
     assert_eq!(reactor.menu_manager.menu_state, MenuState::Closed);
 }
 
+#[test]
+fn suspending_hands_the_keyboard_back_to_apps_until_resumed() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let (event_tap_tx, mut event_tap_rx) = actor::channel();
+    reactor.communication_manager.event_tap_tx = Some(event_tap_tx);
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::ToggleSuspended)));
+    let suspend = event_tap_rx.try_recv().expect("suspending should update event tap").1;
+    assert!(matches!(
+        suspend,
+        crate::actor::event_tap::Request::SetSuspended(true)
+    ));
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::ToggleSuspended)));
+    let resume = event_tap_rx.try_recv().expect("resuming should update event tap").1;
+    assert!(matches!(
+        resume,
+        crate::actor::event_tap::Request::SetSuspended(false)
+    ));
+}
+
 #[test]
 fn stale_menu_open_state_is_cleared_when_other_app_activates() {
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
@@ -3755,6 +4292,34 @@ fn animated_layout_handles_windows_without_server_ids() {
     );
 }
 
+#[test]
+fn the_reactor_records_original_frames_for_the_panic_hook() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+    ));
+
+    // The recorded frames are process-wide, so use a pid no other test does.
+    let pid = 4596;
+    let wid = WindowId::new(pid, 1);
+    reactor.handle_events(apps.make_app(pid, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let window = reactor.state.windows.window(wid).unwrap();
+    let wsid = window.info.sys_id.unwrap();
+    assert_ne!(window.frame_monotonic, make_window(1).frame);
+    assert_eq!(crash_restore::original_frame(pid, wsid), Some(make_window(1).frame));
+
+    reactor.handle_event(Event::WindowDestroyed(wid));
+    assert_eq!(crash_restore::original_frame(pid, wsid), None);
+}
+
 #[test]
 fn display_index_selector_uses_physical_left_to_right_order() {
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
@@ -3828,6 +4393,51 @@ fn moving_tiled_window_to_display_applies_destination_layout_after_transfer_fram
     );
 }
 
+#[test]
+fn moving_window_to_display_by_direction_reassigns_it_to_the_neighbor_space() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let left = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let right = CGRect::new(CGPoint::new(1000., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(space_state_event(vec![left, right], vec![
+        Some(SpaceId::new(1)),
+        Some(SpaceId::new(2)),
+    ]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let moved = WindowId::new(1, 1);
+    assert_eq!(
+        reactor.assigned_space_for_window_id(moved),
+        Some(SpaceId::new(1))
+    );
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::MoveWindowToDisplay {
+            selector: DisplaySelector::Direction(Direction::Right),
+            window_id: Some(1),
+        },
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert_eq!(
+        reactor.assigned_space_for_window_id(moved),
+        Some(SpaceId::new(2))
+    );
+    assert!(
+        reactor
+            .layout_manager
+            .layout_engine
+            .windows_in_active_workspace(&reactor.state.windows, SpaceId::new(2))
+            .contains(&moved),
+        "the window should join the target display's active workspace"
+    );
+}
+
 #[test]
 fn authoritative_active_window_snapshot_reassigns_window_across_active_displays() {
     let (mut reactor, wid, wsid, space1, space2, _initial_frame, _screen2) =
@@ -4592,6 +5202,10 @@ fn fullscreen_startup_applies_app_rules_to_hidden_user_space_windows() {
         title_substring: None,
         ax_role: None,
         ax_subrole: None,
+        matcher: None,
+        bundle_path: None,
+        opacity: None,
+        floating_frame: None,
     }];
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
         &workspace_cfg,
@@ -6107,3 +6721,443 @@ fn floating_window_toggles_to_fullscreen_within_gaps() {
         "expected {expected:?}, got {laid_out:?}"
     );
 }
+
+#[test]
+fn small_windows_start_floating_when_auto_float_is_configured() {
+    let mut apps = Apps::new();
+    let mut layout_settings = crate::common::config::LayoutSettings::default();
+    layout_settings.auto_float.width = 200.0;
+    layout_settings.auto_float.height = 200.0;
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &layout_settings,
+        None,
+    ));
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+    ));
+
+    // The largest window is not the app's main window; the popup must be
+    // centered over the main window regardless.
+    let mut largest = make_window(1);
+    largest.frame = CGRect::new(CGPoint::new(0., 0.), CGSize::new(900., 900.));
+    let mut main = make_window(2);
+    main.frame = CGRect::new(CGPoint::new(200., 150.), CGSize::new(400., 300.));
+    let popup = make_window(3);
+    let popup_id = WindowId::new(1, 3);
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        vec![largest, main, popup],
+        Some(WindowId::new(1, 2)),
+        true,
+        true,
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let engine = &reactor.layout_manager.layout_engine;
+    assert!(!engine.is_window_floating(WindowId::new(1, 1)));
+    assert!(!engine.is_window_floating(WindowId::new(1, 2)));
+    assert!(
+        engine.is_window_floating(popup_id),
+        "a window smaller than the threshold should open floating"
+    );
+
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let expected = CGRect::new(CGPoint::new(375., 275.), CGSize::new(50., 50.));
+    let laid_out =
+        laid_out_frame(&mut reactor, SpaceId::new(1), screen, popup_id).expect("popup laid out");
+    assert!(
+        laid_out.same_as(expected),
+        "expected popup centered over the main window at {expected:?}, got {laid_out:?}"
+    );
+}
+
+#[test]
+fn recorded_trace_replays_headless_to_the_same_layout() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(3)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let path = reactor.recording_manager.record.temp().unwrap().path().to_path_buf();
+    let trace = Trace::load(&path).unwrap();
+    assert!(!trace.is_empty());
+    let mut replayed = trace.run_headless().unwrap();
+
+    let recorded = serde_json::to_value(reactor.query_layout_tree(Some(SpaceId::new(1)))).unwrap();
+    assert_eq!(replayed.layout_json(), serde_json::json!([recorded]));
+    assert!(
+        !replayed.take_requests().is_empty(),
+        "replay should capture the frame writes instead of sending them"
+    );
+}
+
+#[test]
+fn unresponsive_apps_are_left_out_of_layout_until_they_recover() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let frame_write_pids = |requests: Vec<Request>| -> Vec<pid_t> {
+        requests
+            .iter()
+            .flat_map(|request| match request {
+                Request::SetWindowFrame(wid, ..) => vec![wid.pid],
+                Request::SetBatchWindowFrame(frames, ..) => {
+                    frames.iter().map(|(wid, _)| wid.pid).collect()
+                }
+                _ => vec![],
+            })
+            .collect()
+    };
+
+    reactor.handle_event(Event::ApplicationUnresponsive(2));
+    reactor.handle_events(apps.make_app(3, make_windows(1)));
+    let pids = frame_write_pids(apps.requests());
+    assert!(pids.contains(&1));
+    assert!(!pids.contains(&2), "unresponsive app got frame writes: {pids:?}");
+
+    reactor.handle_event(Event::ApplicationResponsive(2));
+    assert!(frame_write_pids(apps.requests()).contains(&2));
+}
+
+#[test]
+fn tiled_windows_are_left_to_stage_manager_while_it_is_on() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let frame_writes = |requests: Vec<Request>| {
+        requests
+            .iter()
+            .filter(|request| {
+                matches!(
+                    request,
+                    Request::SetWindowFrame(..) | Request::SetBatchWindowFrame(..)
+                )
+            })
+            .count()
+    };
+
+    reactor.handle_event(Event::StageManagerChanged(true));
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+    assert_eq!(frame_writes(apps.requests()), 0);
+
+    reactor.handle_event(Event::StageManagerChanged(false));
+    assert!(frame_writes(apps.requests()) > 0);
+}
+
+#[test]
+fn picture_in_picture_windows_are_reported_as_ignored() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+    ));
+    let mut windows = make_windows(2);
+    windows[1].title = "Picture in Picture".to_string();
+    reactor.handle_events(apps.make_app(1, windows));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let ignored = reactor.query_ignored_windows();
+    assert_eq!(ignored.len(), 1);
+    assert_eq!(ignored[0].id, WindowId::new(1, 2));
+    assert_eq!(
+        ignored[0].reason,
+        crate::model::window_heuristics::IgnoreReason::PictureInPicture
+    );
+    let layout = reactor.layout_manager.layout_engine.calculate_layout(
+        SpaceId::new(1),
+        CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.)),
+        &reactor.config.settings.layout.gaps,
+        0.0,
+        crate::common::config::HorizontalPlacement::Top,
+        crate::common::config::VerticalPlacement::Right,
+    );
+    assert!(layout.iter().any(|(wid, _)| *wid == WindowId::new(1, 1)));
+    assert!(layout.iter().all(|(wid, _)| *wid != WindowId::new(1, 2)));
+}
+
+#[test]
+fn borders_are_only_sent_when_their_snapshot_changes() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.ui.borders.enabled = true;
+    let (borders_tx, mut borders_rx) = actor::channel();
+    reactor.communication_manager.borders_tx = Some(borders_tx);
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(borders_rx.try_recv().is_ok());
+    while borders_rx.try_recv().is_ok() {}
+
+    reactor.handle_event(Event::MenuOpened(1));
+    reactor.handle_event(Event::MenuClosed(1));
+    assert!(borders_rx.try_recv().is_err());
+}
+
+#[test]
+fn windows_of_unresponsive_apps_get_an_urgent_border() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.ui.borders.enabled = true;
+    reactor.config.settings.ui.borders.urgent_color = Some("#d64545".to_string());
+    let (borders_tx, mut borders_rx) = actor::channel();
+    reactor.communication_manager.borders_tx = Some(borders_tx);
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    while borders_rx.try_recv().is_ok() {}
+
+    reactor.app_manager.unresponsive.insert(1);
+    reactor.handle_event(Event::MenuOpened(1));
+    let (_, crate::actor::borders::Event::Update(update)) =
+        borders_rx.try_recv().expect("an urgent app should update the borders")
+    else {
+        panic!("expected a border update");
+    };
+    let mut urgent: Vec<WindowId> = update
+        .decorations
+        .iter()
+        .filter(|decoration| decoration.kind == crate::actor::borders::DecorationKind::Urgent)
+        .map(|decoration| decoration.window)
+        .collect();
+    urgent.sort();
+    assert_eq!(urgent, vec![WindowId::new(1, 1), WindowId::new(1, 2)]);
+}
+
+#[test]
+fn swallowed_window_is_hidden_until_the_child_closes() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let terminal = WindowId::new(1, 1);
+    let child = WindowId::new(2, 1);
+    assert_eq!(
+        reactor.layout_manager.layout_engine.swallow_window(
+            &reactor.state.windows,
+            terminal,
+            child
+        ),
+        Some(space)
+    );
+    let tile = laid_out_frame(&mut reactor, space, screen, child).expect("child laid out");
+    let hidden = laid_out_frame(&mut reactor, space, screen, terminal).expect("terminal laid out");
+    assert!(
+        reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager()
+            .is_hidden_position(&screen, &hidden, None),
+        "the swallowed window should be moved out of sight, got {hidden:?}"
+    );
+
+    reactor.handle_event(Event::WindowDestroyed(child));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert!(!reactor.layout_manager.layout_engine.is_swallowed(terminal));
+    let restored =
+        laid_out_frame(&mut reactor, space, screen, terminal).expect("terminal laid out");
+    assert!(
+        restored.same_as(tile),
+        "expected the terminal back in the child's tile {tile:?}, got {restored:?}"
+    );
+}
+
+#[test]
+fn launched_window_is_moved_next_to_the_recorded_anchor() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1200., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(space_state_event(vec![screen], vec![Some(space)]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let anchor = WindowId::new(1, 1);
+    let selected = WindowId::new(1, 2);
+    reactor.send_layout_event(LayoutEvent::WindowFocused(space, selected));
+    // The selection moved on after the launch was requested; the new window
+    // must still land next to the window selected at that time.
+    reactor.launch_manager.pending_launch = Some(managers::PendingLaunch {
+        bundle_id: "com.testapp2".to_string(),
+        space,
+        anchor: Some(anchor),
+        deadline: std::time::Instant::now() + managers::PENDING_LAUNCH_TIMEOUT,
+    });
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let launched = WindowId::new(2, 1);
+    assert!(reactor.launch_manager.pending_launch.is_none());
+    let x = |reactor: &mut Reactor, wid| {
+        laid_out_frame(reactor, space, screen, wid).expect("window laid out").origin.x
+    };
+    let (anchor_x, launched_x, selected_x) =
+        (x(&mut reactor, anchor), x(&mut reactor, launched), x(&mut reactor, selected));
+    assert!(
+        anchor_x < launched_x && launched_x < selected_x,
+        "expected the launched window right after the anchor, got x = {anchor_x}, {launched_x}, {selected_x}"
+    );
+}
+
+fn reactor_for_batch() -> (Reactor, Apps, SpaceId) {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(space)],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor.send_layout_event(LayoutEvent::WindowFocused(space, WindowId::new(1, 1)));
+    (reactor, apps, space)
+}
+
+#[test]
+fn command_batch_applies_every_command() {
+    let (mut reactor, _apps, _space) = reactor_for_batch();
+    let (w1, w2) = (WindowId::new(1, 1), WindowId::new(1, 2));
+
+    let results = reactor.handle_command_batch(vec![
+        Command::Layout(LayoutCommand::SwapWindows(w1, w2)),
+        Command::Layout(LayoutCommand::ToggleWindowFloating),
+    ]);
+
+    assert!(results.iter().all(|result| result.ok), "{results:?}");
+    assert!(reactor.layout_manager.layout_engine.is_window_floating(w1));
+}
+
+#[test]
+fn command_batch_applies_nothing_when_a_command_fails() {
+    let (mut reactor, _apps, _space) = reactor_for_batch();
+    let w1 = WindowId::new(1, 1);
+
+    let results = reactor.handle_command_batch(vec![
+        Command::Layout(LayoutCommand::ToggleWindowFloating),
+        Command::Layout(LayoutCommand::SwapWindows(w1, WindowId::new(9, 9))),
+    ]);
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].error, Some(CommandErrorKind::BatchAborted));
+    assert_eq!(results[1].error, Some(CommandErrorKind::WindowNotFound));
+    assert!(
+        !reactor.layout_manager.layout_engine.is_window_floating(w1),
+        "the first command must be rolled back with the batch"
+    );
+}
+
+#[test]
+fn commands_that_time_out_may_still_apply() {
+    let (events_tx, events_rx) = actor::channel();
+    let handle = ReactorHandle::new(events_tx.clone(), ReactorQueryHandle::new(events_tx));
+    let timeout = std::time::Duration::from_millis(1);
+
+    let result = handle.execute_command(Command::Reactor(ReactorCommand::Debug), timeout);
+    assert_eq!(result.error, Some(CommandErrorKind::TimedOut));
+    let batch = handle.execute_batch(vec![Command::Reactor(ReactorCommand::Debug)], timeout);
+    assert_eq!(batch.unwrap_err().error, Some(CommandErrorKind::TimedOut));
+
+    drop(events_rx);
+    let result = handle.execute_command(Command::Reactor(ReactorCommand::Debug), timeout);
+    assert_eq!(result.error, Some(CommandErrorKind::Unavailable));
+}
+
+#[test]
+fn blacklisted_apps_are_moved_without_animating() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.animate = true;
+    reactor.config.settings.animation_blacklist = vec!["com.testapp2".into()];
+    let (animation_tx, mut animation_rx) = tokio::sync::mpsc::unbounded_channel();
+    reactor.animation_tx = Some(animation_tx);
+    reactor.handle_event(space_state_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+    ));
+
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+
+    let frame_writes: Vec<WindowId> = apps
+        .requests()
+        .into_iter()
+        .filter_map(|request| match request {
+            Request::SetWindowFrame(wid, ..) => Some(wid),
+            _ => None,
+        })
+        .collect();
+    assert!(frame_writes.contains(&WindowId::new(2, 1)));
+    assert!(!frame_writes.contains(&WindowId::new(1, 1)));
+    assert!(animation_rx.try_recv().is_ok());
+}
@@ -1,5 +1,6 @@
 use objc2_app_kit::NSNormalWindowLevel;
 
+use crate::model::window_heuristics::IgnoreReason;
 use crate::sys::window_server::{WindowServerId, WindowServerInfo, window_is_sticky, window_level};
 
 /// Computes whether a window is manageable based on its properties and window server information.
@@ -15,27 +16,51 @@ pub fn compute_window_manageability(
     is_minimized: bool,
     is_ax_standard: bool,
     is_ax_root: bool,
-    mut window_server_info: impl FnMut(WindowServerId) -> Option<WindowServerInfo>,
+    window_server_info: impl FnMut(WindowServerId) -> Option<WindowServerInfo>,
 ) -> bool {
+    unmanageable_reason(
+        window_server_id,
+        is_minimized,
+        is_ax_standard,
+        is_ax_root,
+        window_server_info,
+    )
+    .is_none()
+}
+
+/// The first check of [`compute_window_manageability`] that the window fails.
+pub fn unmanageable_reason(
+    window_server_id: Option<WindowServerId>,
+    is_minimized: bool,
+    is_ax_standard: bool,
+    is_ax_root: bool,
+    mut window_server_info: impl FnMut(WindowServerId) -> Option<WindowServerInfo>,
+) -> Option<IgnoreReason> {
     if is_minimized {
-        return false;
+        return Some(IgnoreReason::Minimized);
     }
 
     if let Some(wsid) = window_server_id {
         if let Some(info) = window_server_info(wsid) {
             if info.layer != 0 {
-                return false;
+                return Some(IgnoreReason::WindowLayer);
             }
         }
         if window_is_sticky(wsid) {
-            return false;
+            return Some(IgnoreReason::Sticky);
         }
 
         if let Some(level) = window_level(wsid.0) {
             if level != NSNormalWindowLevel {
-                return false;
+                return Some(IgnoreReason::WindowLevel);
             }
         }
     }
-    is_ax_standard && is_ax_root
+    if !is_ax_standard {
+        Some(IgnoreReason::NotStandard)
+    } else if !is_ax_root {
+        Some(IgnoreReason::NotRoot)
+    } else {
+        None
+    }
 }
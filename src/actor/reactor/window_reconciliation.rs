@@ -0,0 +1,194 @@
+//! Re-identifies windows whose WindowServer id changed underneath them.
+//!
+//! After sleep/wake or a display hot-plug some apps come back with new window
+//! server ids for windows that never closed. The app thread then reports them
+//! as new windows, while the old ones linger with dead ids as ghosts. Pairing
+//! the two by app, title and frame lets the new window take over the ghost's
+//! place in the layout instead of being tiled as a stranger.
+
+use objc2_core_foundation::CGRect;
+use tracing::debug;
+
+use super::Reactor;
+use super::events::EventOutcome;
+use crate::actor::app::{WindowId, WindowInfo, pid_t};
+use crate::common::collections::HashSet;
+use crate::layout_engine::LayoutEvent;
+use crate::sys::crash_restore;
+use crate::sys::geometry::CGRectExt;
+use crate::sys::window_server;
+
+/// Frames must overlap at least this much (intersection over union) to be
+/// considered the same window.
+const MIN_OVERLAP: f64 = 0.8;
+/// Without a matching title, frames have to agree almost exactly.
+const UNTITLED_MIN_OVERLAP: f64 = 0.98;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowFingerprint {
+    pub wid: WindowId,
+    pub title: String,
+    pub frame: CGRect,
+}
+
+/// Pairs each ghost with at most one newly reported window of the same app,
+/// best matches first. Returns `(ghost, replacement)` pairs.
+pub fn match_replacements(
+    ghosts: &[WindowFingerprint],
+    arrivals: &[WindowFingerprint],
+) -> Vec<(WindowId, WindowId)> {
+    let mut candidates = Vec::new();
+    for ghost in ghosts {
+        for arrival in arrivals {
+            if ghost.wid.pid != arrival.wid.pid {
+                continue;
+            }
+            let overlap = overlap(ghost.frame, arrival.frame);
+            let same_title = !ghost.title.is_empty() && ghost.title == arrival.title;
+            let required = if same_title {
+                MIN_OVERLAP
+            } else {
+                UNTITLED_MIN_OVERLAP
+            };
+            if overlap >= required {
+                let score = overlap + if same_title { 1.0 } else { 0.0 };
+                candidates.push((score, ghost.wid, arrival.wid));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut used = HashSet::default();
+    let mut pairs = Vec::new();
+    for (_, ghost, arrival) in candidates {
+        if used.contains(&ghost) || used.contains(&arrival) {
+            continue;
+        }
+        used.insert(ghost);
+        used.insert(arrival);
+        pairs.push((ghost, arrival));
+    }
+    pairs
+}
+
+fn overlap(a: CGRect, b: CGRect) -> f64 {
+    let intersection = a.intersection(&b).area();
+    let union = a.area() + b.area() - intersection;
+    if union <= 0.0 {
+        0.0
+    } else {
+        intersection / union
+    }
+}
+
+impl Reactor {
+    /// Hands the layout position of ghost windows of `pid` to the matching
+    /// windows in `new`, before the new windows are added and the ghosts are
+    /// cleaned up as stale.
+    pub(super) fn reconcile_window_server_identities(
+        &mut self,
+        pid: pid_t,
+        new: &[(WindowId, WindowInfo)],
+        known_visible: &[WindowId],
+    ) -> EventOutcome {
+        let mut outcome = EventOutcome::default();
+        let arrivals: Vec<WindowFingerprint> = new
+            .iter()
+            .filter(|(wid, info)| {
+                !self.state.windows.contains_window(*wid)
+                    && info.sys_id.is_some()
+                    && !info.is_minimized
+            })
+            .map(|(wid, info)| WindowFingerprint {
+                wid: *wid,
+                title: info.title.clone(),
+                frame: info.frame,
+            })
+            .collect();
+        if arrivals.is_empty() {
+            return outcome;
+        }
+
+        let reported: HashSet<WindowId> =
+            new.iter().map(|(wid, _)| *wid).chain(known_visible.iter().copied()).collect();
+        let ghosts: Vec<WindowFingerprint> = self
+            .state
+            .windows
+            .iter_windows()
+            .filter(|(wid, window)| {
+                wid.pid == pid
+                    && !reported.contains(wid)
+                    && !window.info.is_minimized
+                    && window
+                        .info
+                        .sys_id
+                        .is_some_and(|wsid| window_server::get_window(wsid).is_none())
+            })
+            .map(|(wid, window)| WindowFingerprint {
+                wid,
+                title: window.info.title.clone(),
+                frame: window.frame_monotonic,
+            })
+            .collect();
+
+        for (ghost, replacement) in match_replacements(&ghosts, &arrivals) {
+            debug!(
+                ?ghost,
+                ?replacement,
+                "Window came back under a new window server id"
+            );
+            self.layout_manager.layout_engine.rekey_window_identity(
+                &mut self.state.windows,
+                ghost,
+                replacement,
+            );
+            if let Some(wsid) = self.state.windows.window(ghost).and_then(|w| w.info.sys_id) {
+                crash_restore::forget(ghost.pid, wsid);
+            }
+            self.state.windows.remove_window(ghost);
+            outcome = outcome.with_layout_event(LayoutEvent::WindowRemovedPreserveFloating(ghost));
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2_core_foundation::{CGPoint, CGSize};
+
+    use super::*;
+
+    fn window(pid: pid_t, idx: u32, title: &str, x: f64) -> WindowFingerprint {
+        WindowFingerprint {
+            wid: WindowId::new(pid, idx),
+            title: title.to_string(),
+            frame: CGRect::new(CGPoint::new(x, 0.0), CGSize::new(400.0, 300.0)),
+        }
+    }
+
+    #[test]
+    fn ghosts_are_matched_by_title_and_frame() {
+        let ghosts = [window(1, 1, "Inbox", 0.0), window(1, 2, "Drafts", 500.0)];
+        let arrivals = [
+            window(1, 3, "Drafts", 505.0),
+            window(1, 4, "Inbox", 10.0),
+            window(2, 5, "Inbox", 0.0),
+        ];
+        let mut pairs = match_replacements(&ghosts, &arrivals);
+        pairs.sort();
+        assert_eq!(pairs, vec![
+            (WindowId::new(1, 1), WindowId::new(1, 4)),
+            (WindowId::new(1, 2), WindowId::new(1, 3)),
+        ]);
+    }
+
+    #[test]
+    fn distant_or_retitled_windows_are_not_matched() {
+        let ghosts = [window(1, 1, "Inbox", 0.0)];
+        assert!(match_replacements(&ghosts, &[window(1, 2, "Inbox", 300.0)]).is_empty());
+        assert!(match_replacements(&ghosts, &[window(1, 2, "Sent", 10.0)]).is_empty());
+        assert_eq!(match_replacements(&ghosts, &[window(1, 2, "Sent", 0.0)]), vec![
+            (WindowId::new(1, 1), WindowId::new(1, 2))
+        ]);
+    }
+}
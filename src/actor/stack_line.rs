@@ -19,6 +19,7 @@ use crate::model::tree::NodeId;
 use crate::sys::screen::{CoordinateConverter, SpaceId};
 use crate::ui::stack_line::{
     GroupDisplayData, GroupIndicatorWindow, GroupKind, IndicatorConfig, point_hits_indicator_frame,
+    tab_label,
 };
 
 /// Shared indicator hit-rect state readable from the event tap callback.
@@ -36,6 +37,8 @@ pub struct GroupInfo {
     pub total_count: usize,
     pub selected_index: usize,
     pub window_ids: Vec<WindowId>,
+    /// Title of each window in `window_ids`, for the tabs style.
+    pub titles: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -58,6 +61,9 @@ pub enum Event {
         point: CGPoint,
         hits_indicator: bool,
     },
+    /// Remove every indicator, e.g. while management is suspended. They are
+    /// redrawn from the next `GroupsUpdated`.
+    ClearIndicators,
 }
 
 pub struct StackLine {
@@ -167,6 +173,10 @@ impl StackLine {
             Event::MouseMoved { point, hits_indicator } => {
                 self.handle_mouse_moved(point, hits_indicator);
             }
+            Event::ClearIndicators => {
+                self.clear_indicators();
+                self.sync_shared_hit_rects();
+            }
         }
     }
 
@@ -238,6 +248,16 @@ impl StackLine {
         tracing::debug!("Updated coordinate converter for group indicators");
     }
 
+    fn clear_indicators(&mut self) {
+        for indicator in self.indicators.values() {
+            if let Err(err) = indicator.clear() {
+                tracing::warn!(?err, "failed to clear stack line indicator");
+            }
+        }
+        self.indicators.clear();
+        self.group_sigs_by_space.clear();
+    }
+
     fn handle_config_updated(&mut self, config: Config) {
         let old_enabled = self.is_enabled();
         self.config = config;
@@ -245,16 +265,7 @@ impl StackLine {
         let new_enabled = self.is_enabled();
 
         if old_enabled && !new_enabled {
-            for indicator in self.indicators.values() {
-                if let Err(err) = indicator.clear() {
-                    tracing::warn!(
-                        ?err,
-                        "failed to clear stack line indicator during config update"
-                    );
-                }
-            }
-            self.indicators.clear();
-            self.group_sigs_by_space.clear();
+            self.clear_indicators();
         } else if new_enabled {
             let new_config = self.indicator_config();
             for (node_id, indicator) in &self.indicators {
@@ -370,6 +381,7 @@ impl StackLine {
                     ReactorCommand::FocusWindow {
                         window_id: *window_id,
                         window_server_id: None,
+                        switch_workspace: false,
                     },
                 )));
             } else {
@@ -404,6 +416,7 @@ impl StackLine {
             total_count: group.total_count,
             selected_index: group.selected_index,
             window_ids: group.window_ids,
+            titles: group.titles,
         };
 
         let indicator_frame = Self::calculate_indicator_frame(
@@ -511,6 +524,7 @@ struct GroupSig {
     total: usize,
     selected_index: usize,
     window_ids: Vec<WindowId>,
+    titles: Vec<String>,
 }
 
 impl GroupSig {
@@ -526,6 +540,7 @@ impl GroupSig {
             total: g.total_count,
             selected_index: g.selected_index,
             window_ids: g.window_ids.clone(),
+            titles: g.titles.clone(),
         }
     }
 }
@@ -541,6 +556,16 @@ mod tests {
         assert_eq!(LayoutKind::Horizontal.is_group(), false);
     }
 
+    #[test]
+    fn test_tab_label() {
+        assert_eq!(
+            tab_label("  Inbox - Mail ", GroupKind::Horizontal),
+            "Inbox - Mail"
+        );
+        assert_eq!(tab_label("zsh", GroupKind::Vertical), "Z");
+        assert_eq!(tab_label("", GroupKind::Vertical), "");
+    }
+
     #[test]
     fn test_calculate_indicator_frame() {
         let group_frame = CGRect::new(CGPoint::new(100.0, 200.0), CGSize::new(400.0, 300.0));
@@ -4,6 +4,7 @@
 
 use std::borrow::Cow;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use dispatchr::queue;
 use dispatchr::time::Time;
@@ -15,7 +16,7 @@ use strum::VariantNames;
 use tracing::{debug, error, info, instrument, warn};
 
 use crate::actor::gesture_tap;
-use crate::common::config::WorkspaceSelector;
+use crate::common::config::{ConfigCommand, WorkspaceSelector};
 use crate::sys::app::{NSRunningApplicationExt, pid_t};
 
 pub type Sender = actor::Sender<WmEvent>;
@@ -28,12 +29,15 @@ use crate::actor::spaces::ForwardedSpaceState;
 use crate::actor::{self, config, event_tap, mission_control, reactor};
 use crate::model::tx_store::WindowTxStore;
 use crate::sys::dispatch::DispatchExt;
+use crate::sys::hotkey::{Hotkey, KeyCode, Modifiers};
 use crate::sys::screen::CoordinateConverter;
 use crate::{layout_engine as layout, sys};
 
 #[derive(Debug)]
 pub enum WmEvent {
     DiscoverRunningApps,
+    /// Startup discovery of lower-priority apps, spawned a batch at a time.
+    DiscoverAppBatch(Vec<(pid_t, AppInfo)>),
     AppEventsRegistered,
     AppLaunch(pid_t, AppInfo),
     AppGloballyActivated(pid_t),
@@ -41,7 +45,15 @@ pub enum WmEvent {
     AppTerminated(pid_t),
     SpaceStateUpdated(ForwardedSpaceState, CoordinateConverter),
     PowerStateChanged(bool),
+    StageManagerChanged(bool),
+    WorkspaceSwipePreview {
+        progress: f64,
+        skip_empty: Option<bool>,
+    },
     KeyboardLayoutChanged,
+    /// Bind the given hint keys (see `swap_with_hint` and `focus_with_hint`)
+    /// in place of the current hotkeys; an empty list restores them.
+    SetHintKeys(Vec<char>),
     ConfigUpdated(crate::common::config::Config),
     Command(WmCommand),
 }
@@ -51,6 +63,8 @@ pub enum WmEvent {
 pub enum WmCommand {
     Wm(WmCmd),
     ReactorCommand(reactor::Command),
+    /// A config change bound to a key, such as `adjust_gaps`.
+    Config(ConfigCommand),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, strum_macros::VariantNames)]
@@ -67,10 +81,18 @@ pub enum WmCmd {
     CreateWorkspace,
     SwitchToLastWorkspace,
 
+    /// Show or hide every workspace of the current space as a grid; clicking
+    /// a window switches to its workspace and focuses it.
+    ToggleOverview,
     ShowMissionControlAll,
     ShowMissionControlCurrent,
     DismissMissionControl,
     CloseWindow,
+
+    /// Swap the active hotkeys for the named `[modes.<name>]` keymap.
+    EnterMode(String),
+    /// Return to the top-level `[keys]` bindings.
+    ExitMode,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -110,6 +132,59 @@ impl WmCommand {
     pub fn builtin_candidates() -> &'static [String] { WmCmd::snake_case_variants() }
 }
 
+/// Background apps spawned per startup discovery batch once the frontmost and
+/// visible apps have been handed to the reactor.
+const STARTUP_DISCOVERY_BATCH_SIZE: usize = 4;
+const STARTUP_DISCOVERY_BATCH_INTERVAL_MS: i64 = 50;
+
+/// Split the running apps into those discovered immediately (frontmost first,
+/// then apps with on-screen windows) and the background rest.
+fn prioritize_startup_apps(
+    mut apps: Vec<(pid_t, AppInfo)>,
+    frontmost: Option<pid_t>,
+    onscreen: &crate::common::collections::HashSet<pid_t>,
+) -> (Vec<(pid_t, AppInfo)>, Vec<(pid_t, AppInfo)>) {
+    apps.sort_by_key(|(pid, _)| (Some(*pid) != frontmost, !onscreen.contains(pid)));
+    let split = apps
+        .iter()
+        .position(|(pid, _)| Some(*pid) != frontmost && !onscreen.contains(pid))
+        .unwrap_or(apps.len());
+    let background = apps.split_off(split);
+    (apps, background)
+}
+
+/// Bindings for a modal keymap. A bare Escape leaves the mode unless the mode
+/// binds Escape itself, so a mode can never trap the keyboard.
+fn mode_bindings(bindings: &[(String, WmCommand)]) -> Vec<(String, WmCommand)> {
+    let escape = Hotkey::new(Modifiers::empty(), KeyCode::Escape);
+    let mut out = bindings.to_vec();
+    if !bindings
+        .iter()
+        .any(|(spec, _)| Hotkey::from_str(spec).is_ok_and(|hotkey| hotkey == escape))
+    {
+        out.push((escape.to_string(), WmCommand::Wm(WmCmd::ExitMode)));
+    }
+    out
+}
+
+/// Bindings while hints are shown: each hint key picks its window, and a bare
+/// Escape hides the hints.
+fn hint_bindings(keys: &[char]) -> Vec<(String, WmCommand)> {
+    let picks = keys.iter().map(|key| {
+        (
+            key.to_string(),
+            WmCommand::ReactorCommand(reactor::Command::Reactor(
+                reactor::ReactorCommand::PickHint(*key),
+            )),
+        )
+    });
+    let cancel = (
+        Hotkey::new(Modifiers::empty(), KeyCode::Escape).to_string(),
+        WmCommand::ReactorCommand(reactor::Command::Reactor(reactor::ReactorCommand::CancelHints)),
+    );
+    picks.chain(std::iter::once(cancel)).collect()
+}
+
 pub struct Config {
     pub restore_file: PathBuf,
     pub config: crate::common::config::Config,
@@ -124,9 +199,15 @@ pub struct WmController {
     stack_line_tx: Option<crate::actor::stack_line::Sender>,
     mission_control_tx: Option<mission_control::Sender>,
     window_tx_store: Option<WindowTxStore>,
+    /// Added to `exec` commands. IPC settings are read once at startup, so
+    /// this keeps the token the server checks across config reloads.
+    spawn_env: Vec<(String, String)>,
     receiver: Receiver,
     sender: Sender,
     hotkeys_installed: bool,
+    active_mode: Option<String>,
+    /// Keys bound while window hints are shown, overriding the active mode.
+    hint_keys: Vec<char>,
 }
 
 impl WmController {
@@ -146,6 +227,7 @@ impl WmController {
             move |pid, info| sender.send(WmEvent::AppLaunch(pid, info))
         });
         let this = Self {
+            spawn_env: config.config.settings.ipc.spawn_env(),
             config,
             config_tx,
             events_tx,
@@ -157,6 +239,8 @@ impl WmController {
             receiver,
             sender: sender.clone(),
             hotkeys_installed: false,
+            active_mode: None,
+            hint_keys: Vec::new(),
         };
         (this, sender)
     }
@@ -230,9 +314,27 @@ impl WmController {
                 );
             }
             DiscoverRunningApps => {
-                for (pid, info) in sys::app::running_apps(None) {
+                let (priority, background) = prioritize_startup_apps(
+                    sys::app::running_apps(None).collect(),
+                    sys::app::frontmost_app_pid(),
+                    &sys::window_server::pids_with_onscreen_windows(),
+                );
+                debug!(
+                    priority = priority.len(),
+                    background = background.len(),
+                    "Discovering running apps"
+                );
+                for (pid, info) in priority {
                     self.new_app(pid, info);
                 }
+                self.schedule_app_batch(background);
+            }
+            DiscoverAppBatch(mut apps) => {
+                let rest = apps.split_off(apps.len().min(STARTUP_DISCOVERY_BATCH_SIZE));
+                for (pid, info) in apps {
+                    self.new_app(pid, info);
+                }
+                self.schedule_app_batch(rest);
             }
             AppLaunch(pid, info) => {
                 self.new_app(pid, info);
@@ -249,9 +351,22 @@ impl WmController {
                 self.events_tx.send(Event::ApplicationTerminated(pid));
             }
             ConfigUpdated(new_cfg) => {
-                let old_keys_ser = serde_json::to_string(&self.config.config.keys).ok();
+                let old_keys_ser =
+                    serde_json::to_string(&(&self.config.config.keys, &self.config.config.modes))
+                        .ok();
 
                 self.config.config = new_cfg;
+                let mut mode_removed = false;
+                if let Some(mode) = &self.active_mode
+                    && !self.config.config.modes.contains_key(mode)
+                {
+                    info!(
+                        mode,
+                        "Active hotkey mode was removed from the config; leaving it"
+                    );
+                    self.active_mode = None;
+                    mode_removed = true;
+                }
 
                 _ = self
                     .event_tap_tx
@@ -269,8 +384,13 @@ impl WmController {
                     return;
                 }
 
-                if let Some(old_ser) = old_keys_ser {
-                    if serde_json::to_string(&self.config.config.keys).ok().as_deref()
+                if mode_removed {
+                    debug!("active hotkey mode removed; reloading hotkeys");
+                    self.register_hotkeys();
+                } else if let Some(old_ser) = old_keys_ser {
+                    if serde_json::to_string(&(&self.config.config.keys, &self.config.config.modes))
+                        .ok()
+                        .as_deref()
                         != Some(&old_ser)
                     {
                         debug!("hotkey bindings changed; reloading hotkeys");
@@ -287,9 +407,26 @@ impl WmController {
                 info!("Power state changed: low power mode = {}", is_low_power_mode);
                 _ = self.event_tap_tx.send(event_tap::Request::SetLowPowerMode(is_low_power_mode));
             }
+            StageManagerChanged(enabled) => {
+                info!("Stage Manager enabled = {}", enabled);
+                self.events_tx.send(Event::StageManagerChanged(enabled));
+            }
+            WorkspaceSwipePreview { progress, skip_empty } => {
+                self.events_tx.send(Event::WorkspaceSwipePreview { progress, skip_empty });
+            }
             KeyboardLayoutChanged => {
                 _ = self.event_tap_tx.send(event_tap::Request::KeyboardLayoutChanged);
             }
+            SetHintKeys(keys) => {
+                if keys == self.hint_keys {
+                    return;
+                }
+                debug!(?keys, "Hint keys changed");
+                self.hint_keys = keys;
+                if self.hotkeys_installed {
+                    self.register_hotkeys();
+                }
+            }
             Command(Wm(ReloadConfig)) => self.reload_config(),
             Command(Wm(crate::actor::wm_controller::WmCmd::ToggleSpaceActivated)) => {
                 self.events_tx.send(reactor::Event::Command(reactor::Command::Reactor(
@@ -307,27 +444,15 @@ impl WmController {
                 )));
             }
             Command(Wm(SwitchToWorkspace(ws_sel))) => {
-                let maybe_index: Option<usize> = match &ws_sel {
-                    WorkspaceSelector::Index(i) => Some(*i),
-                    WorkspaceSelector::Name(name) => self
-                        .config
-                        .config
-                        .virtual_workspaces
-                        .workspace_names
-                        .iter()
-                        .position(|n| n == name),
+                // Names are resolved by the layout engine so that workspaces
+                // renamed at runtime can be addressed by their current name.
+                let command = match ws_sel {
+                    WorkspaceSelector::Index(i) => layout::LayoutCommand::SwitchToWorkspace(i),
+                    WorkspaceSelector::Name(name) => {
+                        layout::LayoutCommand::SwitchToNamedWorkspace(name)
+                    }
                 };
-
-                if let Some(workspace_index) = maybe_index {
-                    self.events_tx.send(reactor::Event::Command(reactor::Command::Layout(
-                        layout::LayoutCommand::SwitchToWorkspace(workspace_index),
-                    )));
-                } else {
-                    tracing::warn!(
-                        "Hotkey requested switch to workspace {:?} but it could not be resolved; ignoring",
-                        ws_sel
-                    );
-                }
+                self.events_tx.send(reactor::Event::Command(reactor::Command::Layout(command)));
             }
             Command(Wm(MoveWindowToWorkspace(workspace))) => {
                 self.events_tx.send(reactor::Event::Command(reactor::Command::Layout(
@@ -348,7 +473,7 @@ impl WmController {
                     layout::LayoutCommand::SwitchToLastWorkspace,
                 )));
             }
-            Command(Wm(ShowMissionControlAll)) => {
+            Command(Wm(ToggleOverview | ShowMissionControlAll)) => {
                 if let Some(tx) = &self.mission_control_tx {
                     let _ = tx.try_send(mission_control::Event::ShowAll);
                 }
@@ -368,15 +493,42 @@ impl WmController {
                     reactor::ReactorCommand::CloseWindow { window_server_id: None },
                 )));
             }
+            Command(Wm(EnterMode(mode))) => {
+                if !self.config.config.modes.contains_key(&mode) {
+                    warn!(mode, "Ignoring enter_mode for a mode that is not configured");
+                    return;
+                }
+                info!(mode, "Entering hotkey mode");
+                self.active_mode = Some(mode);
+                self.register_hotkeys();
+            }
+            Command(Wm(ExitMode)) => {
+                if let Some(mode) = self.active_mode.take() {
+                    info!(mode, "Leaving hotkey mode");
+                    self.register_hotkeys();
+                }
+            }
             Command(Wm(Exec(cmd))) => {
                 self.exec_cmd(cmd);
             }
             Command(ReactorCommand(cmd)) => {
                 self.events_tx.send(reactor::Event::Command(cmd));
             }
+            Command(WmCommand::Config(cmd)) => self.apply_config(cmd),
         }
     }
 
+    fn schedule_app_batch(&self, apps: Vec<(pid_t, AppInfo)>) {
+        if apps.is_empty() {
+            return;
+        }
+        queue::main().after_f_s(
+            Time::new_after(Time::NOW, STARTUP_DISCOVERY_BATCH_INTERVAL_MS * 1000000),
+            (self.sender.clone(), WmEvent::DiscoverAppBatch(apps)),
+            |(sender, event)| sender.send(event),
+        );
+    }
+
     fn new_app(&mut self, pid: pid_t, info: AppInfo) {
         let Some(running_app) = NSRunningApplication::with_process_id(pid) else {
             debug!(pid = ?pid, "Failed to resolve NSRunningApplication for new app");
@@ -424,37 +576,45 @@ impl WmController {
     }
 
     fn register_hotkeys(&mut self) {
-        debug!("register_hotkeys");
-        let bindings: Vec<(String, WmCommand)> =
-            self.config.config.key_specs.iter().cloned().collect();
+        debug!(mode = ?self.active_mode, "register_hotkeys");
+        let bindings = match self.active_mode.as_ref() {
+            _ if !self.hint_keys.is_empty() => hint_bindings(&self.hint_keys),
+            Some(mode) => {
+                mode_bindings(self.config.config.modes.get(mode).map_or(&[], Vec::as_slice))
+            }
+            None => self.config.config.key_specs.clone(),
+        };
         _ = self.event_tap_tx.send(event_tap::Request::SetHotkeys(bindings));
     }
 
     fn reload_config(&self) {
+        self.apply_config(ConfigCommand::ReloadConfig);
+    }
+
+    fn apply_config(&self, cmd: ConfigCommand) {
         let (response, _fut) = r#continue::continuation();
-        let msg = config::Event::ApplyConfig {
-            cmd: crate::common::config::ConfigCommand::ReloadConfig,
-            response,
-        };
+        let msg = config::Event::ApplyConfig { cmd, response };
         if let Err(e) = self.config_tx.try_send(msg) {
             let error_message = e.to_string();
             let tokio::sync::mpsc::error::SendError((_span, msg)) = e;
             match msg {
                 config::Event::ApplyConfig { response, .. } => std::mem::forget(response),
                 config::Event::QueryConfig(response) => std::mem::forget(response),
+                config::Event::CheckConfigFile(response) => std::mem::forget(response),
             }
-            error!("Failed to request config reload: {error_message}");
+            error!("Failed to send config command: {error_message}");
         }
     }
 
     fn exec_cmd(&self, cmd_args: ExecCmd) {
+        let env = self.spawn_env.clone();
         std::thread::spawn(move || {
             let cmd_args = cmd_args.as_array();
             let [cmd, args @ ..] = &*cmd_args else {
                 error!("Empty argument list passed to exec");
                 return;
             };
-            let output = std::process::Command::new(cmd).args(args).output();
+            let output = std::process::Command::new(cmd).args(args).envs(env).output();
             let output = match output {
                 Ok(o) => o,
                 Err(e) => {
@@ -482,3 +642,58 @@ impl ExecCmd {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::collections::HashSet;
+
+    fn app(pid: pid_t) -> (pid_t, AppInfo) {
+        (pid, AppInfo {
+            bundle_id: None,
+            localized_name: None,
+        })
+    }
+
+    #[test]
+    fn startup_discovery_puts_frontmost_then_visible_apps_first() {
+        let onscreen: HashSet<pid_t> = [3, 5].into_iter().collect();
+        let (priority, background) = prioritize_startup_apps(
+            vec![app(1), app(2), app(3), app(4), app(5)],
+            Some(4),
+            &onscreen,
+        );
+
+        let pids = |apps: &[(pid_t, AppInfo)]| apps.iter().map(|(pid, _)| *pid).collect::<Vec<_>>();
+        assert_eq!(pids(&priority), vec![4, 3, 5]);
+        assert_eq!(pids(&background), vec![1, 2]);
+    }
+
+    #[test]
+    fn modes_get_an_escape_exit_unless_they_bind_escape() {
+        let exit = WmCommand::Wm(WmCmd::ExitMode);
+        let grow = WmCommand::Wm(WmCmd::NextWorkspace);
+
+        let bindings = mode_bindings(&[("Alt + Tab".to_string(), grow.clone())]);
+        assert_eq!(bindings, vec![
+            ("Alt + Tab".to_string(), grow.clone()),
+            ("Escape".to_string(), exit),
+        ]);
+
+        let bindings = mode_bindings(&[("Escape".to_string(), grow.clone())]);
+        assert_eq!(bindings, vec![("Escape".to_string(), grow)]);
+    }
+
+    #[test]
+    fn hint_keys_pick_windows_and_escape_cancels() {
+        let command = |command| WmCommand::ReactorCommand(reactor::Command::Reactor(command));
+        assert_eq!(hint_bindings(&['a', 's']), vec![
+            ("a".to_string(), command(reactor::ReactorCommand::PickHint('a'))),
+            ("s".to_string(), command(reactor::ReactorCommand::PickHint('s'))),
+            (
+                "Escape".to_string(),
+                command(reactor::ReactorCommand::CancelHints)
+            ),
+        ]);
+    }
+}
@@ -1,14 +1,20 @@
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self};
 
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use rift_wm::actor::app::WindowId;
 use rift_wm::actor::reactor::{self, DisplaySelector};
-use rift_wm::common::config::{LayoutMode, WorkspaceSelector};
+use rift_wm::actor::wm_controller::WmCmd;
+use rift_wm::common::config::import::{self, ImportSource};
+use rift_wm::common::config::{ConfigCommand, GapAdjustment, LayoutMode, WorkspaceSelector};
+use rift_wm::common::doctor::{CheckStatus, DoctorReport};
 use rift_wm::ipc::{RiftCommand, RiftMachClient, RiftRequest, RiftResponse};
-use rift_wm::layout_engine as layout;
+use rift_wm::layout_engine::{self as layout, LayoutCommand};
+use rift_wm::model::WindowMatcher;
+use rift_wm::model::server::WindowSort;
 use rift_wm::sys::window_server::WindowServerId;
+use serde::de::{DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor};
 use serde_json::Value;
 
 #[derive(Parser)]
@@ -23,24 +29,100 @@ struct Cli {
 enum Commands {
     /// Query information from rift
     Query {
+        /// Output format: `json`, `plain` (one tab-separated line per item),
+        /// or `template:"{title} [{workspace}]"`
+        #[arg(long, global = true, default_value = "json", value_parser = parse_output_format)]
+        format: OutputFormat,
         #[command(subcommand)]
         query: QueryCommands,
     },
     /// Execute commands in rift
     Execute {
+        /// Print the window frames a layout command would change, as JSON,
+        /// without applying it
+        #[arg(long, global = true)]
+        dry_run: bool,
         #[command(subcommand)]
         command: ExecuteCommands,
     },
+    /// Run several `execute` commands with a single layout update, e.g.
+    /// `rift-cli batch 'workspace switch 2; window focus right; layout rebalance'`
+    Batch {
+        /// `execute` commands separated by `;`
+        commands: String,
+    },
+    /// Switch to a named config profile, e.g. `rift-cli profile presentation`
+    Profile {
+        /// Profile to apply on top of the config file; omit to use the file alone
+        name: Option<String>,
+    },
+    /// Move windows left outside every display (e.g. after sleep) back into
+    /// the current workspace and lay it out again
+    Rescue,
     /// Event subscription commands
     Subscribe {
         #[command(subcommand)]
         subscribe: SubscribeCommands,
     },
+    /// Check permissions, the config file and other window managers, and say
+    /// how to fix what is wrong
+    Doctor {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Translate a yabai or AeroSpace config into a rift config, printed to
+    /// stdout. What cannot be translated is listed on stderr.
+    Import {
+        /// Window manager the config belongs to
+        #[arg(long, value_enum)]
+        from: CliImportSource,
+        /// yabairc/skhdrc or aerospace.toml to read
+        path: PathBuf,
+    },
+    /// Change which log messages rift emits, e.g.
+    /// `rift-cli log-level rift_wm::actor::reactor=trace`. Without arguments,
+    /// print the active filter.
+    LogLevel {
+        /// Comma-separated `RUST_LOG`-style directives to add
+        directives: Option<String>,
+        /// Go back to the filter given by `RUST_LOG` at startup
+        #[arg(long, conflicts_with = "directives")]
+        reset: bool,
+    },
+    /// Inspect what rift has been doing
+    Debug {
+        #[command(subcommand)]
+        debug: DebugCommands,
+    },
     /// Manage the launchd service for rift
     Service {
         #[command(subcommand)]
         service: ServiceCommands,
     },
+    /// Print a shell completion script, e.g. `rift-cli completions zsh > _rift-cli`
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// List every `execute` command with its parameters, and the command
+    /// names usable in key bindings
+    Commands {
+        /// Print the list as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DebugCommands {
+    /// Show the events the reactor handled most recently, with their
+    /// durations, without turning on trace logging
+    Events {
+        /// Number of events to show, oldest first
+        #[arg(long, default_value_t = 50)]
+        tail: usize,
+    },
 }
 
 #[derive(Subcommand)]
@@ -68,15 +150,46 @@ enum QueryCommands {
     Windows {
         #[arg(long)]
         space_id: Option<u64>,
+        /// Only list windows matching an expression, e.g. `app:Safari && title~"GitHub"` or
+        /// `workspace:2 && floating:true`
+        #[arg(long = "match")]
+        filter: Option<String>,
+        /// Only windows of this app (bundle id or name)
+        #[arg(long)]
+        app: Option<String>,
+        /// Only windows whose title contains this text (case-insensitive)
+        #[arg(long)]
+        title: Option<String>,
+        /// Only windows whose title matches this regex (case-insensitive)
+        #[arg(long)]
+        title_regex: Option<String>,
+        /// Only windows of this workspace (0-based index or name) instead of the active one
+        #[arg(long)]
+        workspace: Option<String>,
+        /// Only floating (true) or tiled (false) windows
+        #[arg(long)]
+        floating: Option<bool>,
+        /// Only minimized (true) or unminimized (false) windows
+        #[arg(long)]
+        minimized: Option<bool>,
+        #[arg(long, value_enum)]
+        sort: Option<WindowSort>,
     },
     /// List connected displays
     Displays,
+    /// Show which displays and spaces rift currently manages
+    Activation,
     /// Get information about a specific window
     Window { window_id: String },
     /// List running applications
     Applications,
     /// Get layout state for a space
     Layout { space_id: u64 },
+    /// Get the active layout tree (containers, ratios, windows, selection) as JSON
+    Tree {
+        #[arg(long)]
+        space_id: Option<u64>,
+    },
     /// Get workspace layout-engine mode(s)
     WorkspaceLayout {
         #[arg(long)]
@@ -84,8 +197,24 @@ enum QueryCommands {
         #[arg(long)]
         workspace_id: Option<usize>,
     },
+    /// Show the focused window (null if none), cheap enough for status bars
+    FocusedWindow,
+    /// Show the active workspace of a display, without its windows
+    ActiveWorkspace {
+        /// Display index (0-based), UUID or name; defaults to the focused display
+        #[arg(long)]
+        display: Option<String>,
+    },
+    /// List recently focused windows across all displays, most recent first
+    FocusHistory,
+    /// List windows rift leaves alone (PiP players, palettes, dialogs, ...) and why
+    Ignored,
     /// Get performance metrics
-    Metrics,
+    Metrics {
+        /// Print in the Prometheus text format instead of JSON
+        #[arg(long)]
+        prometheus: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -151,6 +280,15 @@ enum ExecuteCommands {
     /// this command is deprecated, use `rift-cli execute space toggle-activated`
     #[deprecated]
     ToggleSpaceActivated,
+    /// Suspend all window management and overlays, or resume it with layouts intact
+    ToggleSuspended,
+    /// Move windows outside every display back into the current workspace
+    RescueWindows,
+    /// Launch or activate an app and insert its next window at the current selection
+    LaunchApp {
+        /// Bundle identifier, e.g. com.apple.Terminal
+        bundle_id: String,
+    },
     /// Show timing metrics
     ShowTiming,
 }
@@ -161,6 +299,14 @@ enum WindowCommands {
     Next,
     /// Focus the previous window
     Prev,
+    /// Focus the previously focused window on any display or workspace
+    LastGlobal,
+    /// Focus the previously focused window on the current space
+    Last {
+        /// How far back in the focus history to go (1 = the previous window)
+        #[arg(default_value_t = 1)]
+        n: usize,
+    },
     /// Focus a window by direction or by a specific window ID
     Focus {
         /// Direction to focus (left, right, up, down)
@@ -174,10 +320,14 @@ enum WindowCommands {
     },
     /// Toggle window floating state
     ToggleFloat,
+    /// Toggle showing the window on every workspace of its space
+    ToggleSticky,
     /// Toggle fullscreen mode (fills the whole screen, ignores outer gaps)
     ToggleFullscreen,
     /// Toggle fullscreen within configured outer gaps (respects outer gaps / fills tiling area)
     ToggleFullscreenWithinGaps,
+    /// Temporarily expand the window over the tiling area; run again to put it back
+    ToggleZoom,
     /// Grow the current window size (increments by ~5%).
     ResizeGrow {
         /// Axis to resize; smart chooses the nearest applicable split.
@@ -197,12 +347,60 @@ enum WindowCommands {
     ///   rift-cli execute window resize-by --amount 0.05    # grow by 5%
     ///   rift-cli execute window resize-by --amount -0.10   # shrink by 10%
     ResizeBy { amount: f64 },
+    /// Give the selected window an exact share of the split containing it
+    /// (traditional and bsp layouts).
+    ///
+    /// Example:
+    ///   rift-cli execute window set-split-ratio 0.618
+    SetSplitRatio { ratio: f64 },
+    /// Move one edge of the current window by a number of points.
+    ///
+    /// Examples:
+    ///   rift-cli execute window resize-edge right 40     # grow the right edge
+    ///   rift-cli execute window resize-edge left -- -40  # pull the left edge in
+    ResizeEdge { direction: String, amount_px: f64 },
     /// Close a window as if Command-W was pressed
     Close {
         /// Optional window server ID; defaults to the focused window
         #[arg(long, visible_alias = "window-server-id")]
         window_id: Option<String>,
     },
+    /// Minimize the focused window, remembering its place in the layout
+    Minimize,
+    /// Restore the most recently minimized window to its previous place
+    RestoreMinimized,
+    /// Move the window into (or out of) its own macOS fullscreen space
+    ToggleNativeFullscreen,
+    /// Keep the floating window above other windows; run again to release it
+    ToggleAlwaysOnTop,
+    /// Center the floating window on its display
+    CenterFloat,
+    /// Resize the floating window to a share of its display
+    ResizeFloat {
+        /// small, medium, large (golden-ratio fractions), or WxH percentages such as 60x70
+        size: reactor::FloatSize,
+    },
+    /// Label the other tiled windows with letters; typing one swaps the
+    /// focused window with it (Escape cancels)
+    SwapWithHint,
+    /// Label every visible window with a letter; typing one focuses it
+    /// (Escape cancels)
+    FocusWithHint,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliImportSource {
+    Yabai,
+    Aerospace,
+}
+
+impl From<CliImportSource> for ImportSource {
+    fn from(value: CliImportSource) -> Self {
+        match value {
+            CliImportSource::Yabai => Self::Yabai,
+            CliImportSource::Aerospace => Self::Aerospace,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -256,11 +454,11 @@ enum WorkspaceCommands {
     Next { skip_empty: Option<bool> },
     /// Switch to previous workspace
     Prev { skip_empty: Option<bool> },
-    /// Switch to specific workspace
-    Switch { workspace_id: usize },
-    /// Move current window to workspace
+    /// Switch to specific workspace, by index or name
+    Switch { workspace: String },
+    /// Move current window to workspace, by index or name
     MoveWindow {
-        workspace_id: usize,
+        workspace: String,
         /// Switch to the destination workspace after moving the window.
         #[arg(long)]
         follow: bool,
@@ -269,7 +467,10 @@ enum WorkspaceCommands {
     /// Create a new workspace
     Create,
     /// Switch to the last workspace
+    #[command(visible_alias = "back-and-forth")]
     Last,
+    /// Rename a workspace, given by index or current name
+    Rename { workspace: String, name: String },
     /// Set layout mode for a workspace (or active workspace when omitted)
     SetLayout {
         /// Workspace index (0-based). Defaults to active workspace if omitted.
@@ -288,6 +489,10 @@ enum LayoutCommands {
     Descend,
     /// Move the selected node in a direction
     MoveNode { direction: String },
+    /// Move the selected node to a 0-based slot of its parent container
+    MoveToIndex { index: usize },
+    /// Swap the selected node with the node at a 0-based slot of its parent container
+    SwapWithIndex { index: usize },
     /// Join the selected window with neighbor in a direction
     JoinWindow { direction: String },
     /// Join with a neighbor, or unjoin when the selected window is already joined
@@ -296,6 +501,33 @@ enum LayoutCommands {
     ToggleStack,
     /// Global orientation toggle that works consistently across layout modes (and between splits/stacks)
     ToggleOrientation,
+    /// Choose the side of the selection the next window opens on (bsp layout only);
+    /// repeating the same preselection cancels it
+    Preselect {
+        direction: String,
+        /// Share of the selection's tile the next window takes, e.g. 0.3
+        #[arg(long)]
+        ratio: Option<f64>,
+    },
+    /// Move the selected window into a tab group with its neighbor (bsp layout only)
+    Group { direction: String },
+    /// Take the selected window out of its tab group (bsp layout only)
+    Ungroup,
+    /// Show the next window of the selected tab group
+    NextInGroup,
+    /// Show the previous window of the selected tab group
+    PrevInGroup,
+    /// Reset split sizes so every tiled window gets an equal share
+    Rebalance,
+    /// Save the current layout as a named snapshot (~/.rift/layouts/<NAME>.ron)
+    Save { name: String },
+    /// Restore a named snapshot, re-matching windows by app and title
+    Restore {
+        name: String,
+        /// Restore one workspace or all saved workspaces for the current macOS Space.
+        #[arg(long, value_enum, default_value_t = CliRestoreScope::Workspace)]
+        scope: CliRestoreScope,
+    },
     /// Unjoin previously joined windows
     Unjoin,
     /// Toggle floating on the focused selection (tree focus)
@@ -317,6 +549,12 @@ enum LayoutCommands {
     /// Toggle centering of the selected column in scrolling layout.
     /// If invoked again on the same selection, centering is removed.
     CenterSelection,
+    /// Resize the selected column to the next width preset (scrolling layout only)
+    CycleColumnWidth {
+        /// Step to the previous preset instead
+        #[arg(long)]
+        backward: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -331,6 +569,8 @@ enum ConfigCommands {
     SetAnimationFps {
         value: f64,
     },
+    /// A named easing, `spring(mass, stiffness, damping)` or
+    /// `cubic_bezier(x1, y1, x2, y2)`.
     SetAnimationEasing {
         value: String,
     },
@@ -365,6 +605,16 @@ enum ConfigCommands {
         horizontal: f64,
         vertical: f64,
     },
+    /// Adjust gaps at runtime, e.g. `gaps outer+10`, `gaps inner-4` or `gaps inner=0`
+    Gaps {
+        adjustment: GapAdjustment,
+        /// Only change the gaps on this display (UUID or name)
+        #[arg(long)]
+        display: Option<String>,
+        /// Also write the new gaps to the config file
+        #[arg(long)]
+        persist: bool,
+    },
 
     /// Update workspace settings
     SetWorkspaceNames {
@@ -389,6 +639,11 @@ enum ConfigCommands {
 
     /// Reload config from file
     Reload,
+
+    /// Reload config from file with a named profile applied, or without one
+    Profile {
+        name: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -399,10 +654,24 @@ enum MissionControlCommands {
     ShowCurrent,
     /// Dismiss mission control
     Dismiss,
+    /// Toggle the workspace overview; click a window to switch to it
+    Overview,
 }
 
 #[derive(Subcommand)]
 enum DisplayCommands {
+    /// Toggle whether rift manages a display (all of its spaces) by direction, index, or UUID.
+    ToggleActivated {
+        /// Direction relative to the current display (left, right, up, down).
+        #[arg(long)]
+        direction: Option<String>,
+        /// Display index (0-based).
+        #[arg(long)]
+        index: Option<usize>,
+        /// Display UUID or name (e.g. "LG HDR 4K").
+        #[arg(long, visible_alias = "name")]
+        uuid: Option<String>,
+    },
     /// Focus a display by direction, index, or UUID.
     Focus {
         /// Direction relative to the current display (left, right, up, down).
@@ -411,8 +680,8 @@ enum DisplayCommands {
         /// Display index (0-based).
         #[arg(long)]
         index: Option<usize>,
-        /// Display UUID.
-        #[arg(long)]
+        /// Display UUID or name (e.g. "LG HDR 4K").
+        #[arg(long, visible_alias = "name")]
         uuid: Option<String>,
     },
     /// Move mouse cursor to a display by index (0-based)
@@ -420,9 +689,9 @@ enum DisplayCommands {
         /// Display index (0-based)
         index: usize,
     },
-    /// Move mouse cursor to a display by UUID
+    /// Move mouse cursor to a display by UUID or name
     MoveMouseToUuid {
-        /// Display UUID
+        /// Display UUID or name
         uuid: String,
     },
     /// Move a window to a display by direction, index, or UUID.
@@ -433,25 +702,39 @@ enum DisplayCommands {
         /// Display index (0-based).
         #[arg(long)]
         index: Option<usize>,
-        /// Display UUID.
-        #[arg(long)]
+        /// Display UUID or name (e.g. "LG HDR 4K").
+        #[arg(long, visible_alias = "name")]
         uuid: Option<String>,
         /// Optional window id (window idx); defaults to the focused window if omitted.
         #[arg(long)]
         window_id: Option<u32>,
     },
+    /// Switch the workspace shown on a display by direction, index, or UUID.
+    SwitchWorkspace {
+        /// Workspace index (0-based) within the display's workspace set.
+        workspace_id: usize,
+        /// Direction relative to the current display (left, right, up, down).
+        #[arg(long)]
+        direction: Option<String>,
+        /// Display index (0-based).
+        #[arg(long)]
+        index: Option<usize>,
+        /// Display UUID or name (e.g. "LG HDR 4K").
+        #[arg(long, visible_alias = "name")]
+        uuid: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
 enum SubscribeCommands {
     /// Subscribe to Mach IPC events
     Mach {
-        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, stacks_changed, *)
+        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, stacks_changed, window_focused, layout_changed, *)
         event: String,
     },
     /// Subscribe to events via CLI command execution
     Cli {
-        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, stacks_changed, *)
+        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, stacks_changed, window_focused, layout_changed, *)
         #[arg(long)]
         event: String,
         /// Command to execute when event occurs
@@ -479,6 +762,14 @@ fn main() {
     sigpipe::reset();
     let cli = Cli::parse();
 
+    let prometheus = matches!(cli.command, Commands::Query {
+        query: QueryCommands::Metrics { prometheus: true },
+        ..
+    });
+    let format = match &cli.command {
+        Commands::Query { format, .. } => format.clone(),
+        _ => OutputFormat::Json,
+    };
     let request = match cli.command {
         Commands::Service { .. } => {
             println!(
@@ -486,6 +777,13 @@ fn main() {
             );
             process::exit(0);
         }
+        Commands::Doctor { json } => process::exit(run_doctor(json)),
+        Commands::Import { from, path } => process::exit(run_import(from.into(), &path)),
+        Commands::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "rift-cli", &mut io::stdout());
+            process::exit(0);
+        }
+        Commands::Commands { json } => process::exit(run_commands(json)),
         Commands::Subscribe {
             subscribe: SubscribeCommands::Mach { event },
         } => {
@@ -505,7 +803,7 @@ fn main() {
         },
     };
 
-    let client = match RiftMachClient::connect() {
+    let client = match connect() {
         Ok(c) => c,
         Err(e) => {
             eprintln!("Failed to connect to rift: {}", e);
@@ -516,11 +814,21 @@ fn main() {
     // Send request and handle response.
     match client.send_request(&request) {
         Ok(resp) => match resp {
+            RiftResponse::Success { data } if prometheus => {
+                print!("{}", rift_wm::common::metrics::render_prometheus(&data));
+            }
             RiftResponse::Success { data } => {
-                if let Err(e) = write_json(
-                    &data,
-                    std::env::var("RIFT_CLI_PRETTY").map(|v| v != "0").unwrap_or(false),
-                ) {
+                let written = match &format {
+                    OutputFormat::Json => write_json(
+                        &data,
+                        std::env::var("RIFT_CLI_PRETTY").map(|v| v != "0").unwrap_or(false),
+                    ),
+                    OutputFormat::Plain => write_lines(&data, plain_line),
+                    OutputFormat::Template(template) => {
+                        write_lines(&data, |item| render_template(template, item))
+                    }
+                };
+                if let Err(e) = written {
                     eprintln!("Failed to handle response: {}", e);
                     process::exit(1);
                 }
@@ -547,28 +855,90 @@ fn main() {
 
 fn build_request(command: Commands) -> Result<RiftRequest, String> {
     match command {
-        Commands::Query { query } => build_query_request(query),
-        Commands::Execute { command } => build_execute_request(command),
+        Commands::Query { query, .. } => build_query_request(query),
+        Commands::Execute { command, dry_run } => build_execute_request(command, dry_run),
+        Commands::Batch { commands } => build_batch_request(&commands),
+        Commands::Profile { name } => build_execute_request(
+            ExecuteCommands::Config {
+                config_cmd: ConfigCommands::Profile { name },
+            },
+            false,
+        ),
+        Commands::Rescue => build_execute_request(ExecuteCommands::RescueWindows, false),
         Commands::Subscribe { subscribe } => build_subscribe_request(subscribe),
+        Commands::Doctor { .. } => Ok(RiftRequest::Doctor),
+        Commands::Debug {
+            debug: DebugCommands::Events { tail },
+        } => Ok(RiftRequest::GetEventLog { tail }),
+        Commands::LogLevel { directives, reset } => Ok(RiftRequest::LogLevel { directives, reset }),
+        Commands::Import { .. } => Err(
+            "Imports are handled locally and should not be sent to the rift server.".to_string(),
+        ),
         Commands::Service { .. } => Err(
             "Service commands are handled locally and should not be sent to the rift server."
                 .to_string(),
         ),
+        Commands::Completions { .. } | Commands::Commands { .. } => Err(
+            "Completions and command listings are handled locally and should not be sent to the \
+             rift server."
+                .to_string(),
+        ),
     }
 }
 
 fn build_query_request(query: QueryCommands) -> Result<RiftRequest, String> {
     match query {
         QueryCommands::Workspaces { space_id } => Ok(RiftRequest::GetWorkspaces { space_id }),
-        QueryCommands::Windows { space_id } => Ok(RiftRequest::GetWindows { space_id }),
+        QueryCommands::Windows {
+            space_id,
+            filter,
+            app,
+            title,
+            title_regex,
+            workspace,
+            floating,
+            minimized,
+            sort,
+        } => {
+            // The flags are shorthands for matcher predicates, ANDed with `--match`.
+            let quote = WindowMatcher::quote;
+            let predicates: Vec<String> = [
+                filter.map(|filter| format!("({filter})")),
+                app.map(|app| format!("app:{}", quote(&app))),
+                title.map(|title| format!("title~{}", quote(&regex::escape(&title)))),
+                title_regex.map(|pattern| format!("title~{}", quote(&pattern))),
+                workspace.map(|workspace| format!("workspace:{}", quote(workspace.trim()))),
+                floating.map(|floating| format!("floating:{floating}")),
+                minimized.map(|minimized| format!("minimized:{minimized}")),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            Ok(RiftRequest::GetWindows {
+                space_id,
+                filter: (!predicates.is_empty()).then(|| predicates.join(" && ")),
+                sort,
+            })
+        }
         QueryCommands::Displays => Ok(RiftRequest::GetDisplays),
+        QueryCommands::Activation => Ok(RiftRequest::GetActivation),
         QueryCommands::Window { window_id } => Ok(RiftRequest::GetWindowInfo { window_id }),
         QueryCommands::Applications => Ok(RiftRequest::GetApplications),
         QueryCommands::Layout { space_id } => Ok(RiftRequest::GetLayoutState { space_id }),
+        QueryCommands::Tree { space_id } => Ok(RiftRequest::GetLayoutTree { space_id }),
         QueryCommands::WorkspaceLayout { space_id, workspace_id } => {
             Ok(RiftRequest::GetWorkspaceLayouts { space_id, workspace_id })
         }
-        QueryCommands::Metrics => Ok(RiftRequest::GetMetrics),
+        QueryCommands::FocusedWindow => Ok(RiftRequest::GetFocusedWindow),
+        QueryCommands::ActiveWorkspace { display } => Ok(RiftRequest::GetActiveWorkspace {
+            display: display.as_deref().map(|display| match display.trim().parse() {
+                Ok(index) => DisplaySelector::Index(index),
+                Err(_) => DisplaySelector::Uuid(display.to_string()),
+            }),
+        }),
+        QueryCommands::FocusHistory => Ok(RiftRequest::GetFocusHistory),
+        QueryCommands::Ignored => Ok(RiftRequest::GetIgnoredWindows),
+        QueryCommands::Metrics { .. } => Ok(RiftRequest::GetMetrics),
     }
 }
 
@@ -584,8 +954,76 @@ fn build_subscribe_request(sub: SubscribeCommands) -> Result<RiftRequest, String
     }
 }
 
-fn build_execute_request(execute: ExecuteCommands) -> Result<RiftRequest, String> {
-    let rift_command = match execute {
+/// One `;`-separated step of a `batch` command, parsed like `rift-cli execute ...`.
+#[derive(Parser)]
+#[command(name = "batch", no_binary_name = true)]
+struct BatchStep {
+    #[command(subcommand)]
+    command: ExecuteCommands,
+}
+
+fn build_batch_request(commands: &str) -> Result<RiftRequest, String> {
+    let commands = commands
+        .split(';')
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+        .map(|step| {
+            let parsed = BatchStep::try_parse_from(step.split_whitespace())
+                .map_err(|e| format!("invalid batch step `{step}`: {e}"))?;
+            match map_execute_command(parsed.command)? {
+                RiftCommand::Config(_) => Err(format!(
+                    "batch step `{step}` is a config command and cannot be batched"
+                )),
+                command => serde_json::to_string(&command)
+                    .map_err(|e| format!("Failed to serialize command: {}", e)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if commands.is_empty() {
+        return Err("batch needs at least one command".to_string());
+    }
+    Ok(RiftRequest::ExecuteBatch { commands })
+}
+
+fn build_execute_request(execute: ExecuteCommands, dry_run: bool) -> Result<RiftRequest, String> {
+    let rift_command = map_execute_command(execute)?;
+
+    if dry_run && !matches!(rift_command, RiftCommand::Reactor(reactor::Command::Layout(_))) {
+        return Err("--dry-run only works with layout commands".to_string());
+    }
+
+    if let RiftCommand::Config(rift_wm::common::config::ConfigCommand::GetConfig) = &rift_command {
+        return Ok(RiftRequest::GetConfig);
+    }
+
+    let maybe_config_json = match &rift_command {
+        RiftCommand::Config(cfg_cmd) => match serde_json::to_string(cfg_cmd) {
+            Ok(s) => Some(s),
+            Err(_) => None,
+        },
+        _ => None,
+    };
+
+    let command_str = serde_json::to_string(&rift_command)
+        .map_err(|e| format!("Failed to serialize command: {}", e))?;
+
+    if let Some(cfg_json) = maybe_config_json {
+        Ok(RiftRequest::ExecuteCommand {
+            command: command_str,
+            args: vec!["__apply_config__".to_string(), cfg_json],
+            dry_run: false,
+        })
+    } else {
+        Ok(RiftRequest::ExecuteCommand {
+            command: command_str,
+            args: vec![],
+            dry_run,
+        })
+    }
+}
+
+fn map_execute_command(execute: ExecuteCommands) -> Result<RiftCommand, String> {
+    Ok(match execute {
         ExecuteCommands::Window { window_cmd } => map_window_command(window_cmd)?,
         ExecuteCommands::Workspace { workspace_cmd } => map_workspace_command(workspace_cmd)?,
         ExecuteCommands::Layout { layout_cmd } => map_layout_command(layout_cmd)?,
@@ -646,37 +1084,21 @@ fn build_execute_request(execute: ExecuteCommands) -> Result<RiftRequest, String
                 reactor::ReactorCommand::ToggleSpaceActivated,
             ))
         }
+        ExecuteCommands::ToggleSuspended => RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::ToggleSuspended,
+        )),
+        ExecuteCommands::RescueWindows => {
+            RiftCommand::Reactor(reactor::Command::Reactor(reactor::ReactorCommand::RescueWindows))
+        }
+        ExecuteCommands::LaunchApp { bundle_id } => {
+            RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::LaunchAppIntoSelection { bundle_id },
+            ))
+        }
         ExecuteCommands::ShowTiming => RiftCommand::Reactor(reactor::Command::Metrics(
             rift_wm::common::log::MetricsCommand::ShowTiming,
         )),
-    };
-
-    if let RiftCommand::Config(rift_wm::common::config::ConfigCommand::GetConfig) = &rift_command {
-        return Ok(RiftRequest::GetConfig);
-    }
-
-    let maybe_config_json = match &rift_command {
-        RiftCommand::Config(cfg_cmd) => match serde_json::to_string(cfg_cmd) {
-            Ok(s) => Some(s),
-            Err(_) => None,
-        },
-        _ => None,
-    };
-
-    let command_str = serde_json::to_string(&rift_command)
-        .map_err(|e| format!("Failed to serialize command: {}", e))?;
-
-    if let Some(cfg_json) = maybe_config_json {
-        Ok(RiftRequest::ExecuteCommand {
-            command: command_str,
-            args: vec!["__apply_config__".to_string(), cfg_json],
-        })
-    } else {
-        Ok(RiftRequest::ExecuteCommand {
-            command: command_str,
-            args: vec![],
-        })
-    }
+    })
 }
 
 fn absolute_layout_path(path: PathBuf) -> Result<PathBuf, String> {
@@ -689,11 +1111,25 @@ fn absolute_layout_path(path: PathBuf) -> Result<PathBuf, String> {
     }
 }
 
+fn named_layout_path(name: &str) -> Result<PathBuf, String> {
+    rift_wm::common::config::named_layout_file(name)
+        .ok_or_else(|| format!("invalid layout name `{name}`: use a plain file name"))
+}
+
 fn map_window_command(cmd: WindowCommands) -> Result<RiftCommand, String> {
     use layout::LayoutCommand as LC;
     match cmd {
         WindowCommands::Next => Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::NextWindow))),
         WindowCommands::Prev => Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::PrevWindow))),
+        WindowCommands::LastGlobal => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::FocusPreviousGlobal,
+        ))),
+        WindowCommands::Last { n: 1 } => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::FocusLast)))
+        }
+        WindowCommands::Last { n } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::FocusNthPrevious { n },
+        ))),
         WindowCommands::Focus {
             direction,
             window_id,
@@ -709,6 +1145,7 @@ fn map_window_command(cmd: WindowCommands) -> Result<RiftCommand, String> {
                         .as_deref()
                         .map(parse_window_server_id)
                         .transpose()?,
+                    switch_workspace: false,
                 },
             ))),
             (None, None) => Err("window focus requires a direction or --window-id".to_string()),
@@ -719,12 +1156,18 @@ fn map_window_command(cmd: WindowCommands) -> Result<RiftCommand, String> {
         WindowCommands::ToggleFloat => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ToggleWindowFloating,
         ))),
+        WindowCommands::ToggleSticky => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::ToggleSticky)))
+        }
         WindowCommands::ToggleFullscreen => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ToggleFullscreen,
         ))),
         WindowCommands::ToggleFullscreenWithinGaps => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::ToggleFullscreenWithinGaps),
         )),
+        WindowCommands::ToggleZoom => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::ToggleZoom)))
+        }
         WindowCommands::ResizeGrow { orientation } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::ResizeWindowGrow(orientation.into())),
         )),
@@ -734,12 +1177,45 @@ fn map_window_command(cmd: WindowCommands) -> Result<RiftCommand, String> {
         WindowCommands::ResizeBy { amount } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ResizeWindowBy { amount },
         ))),
+        WindowCommands::SetSplitRatio { ratio } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::SetSplitRatio(ratio)),
+        )),
+        WindowCommands::ResizeEdge { direction, amount_px } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::ResizeSelection {
+                direction: direction.into(),
+                amount_px,
+            }),
+        )),
         WindowCommands::Close { window_id } => {
             let window_server_id = window_id.as_deref().map(parse_window_server_id).transpose()?;
             Ok(RiftCommand::Reactor(reactor::Command::Reactor(
                 reactor::ReactorCommand::CloseWindow { window_server_id },
             )))
         }
+        WindowCommands::Minimize => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::MinimizeSelection,
+        ))),
+        WindowCommands::RestoreMinimized => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::RestoreLastMinimized,
+        ))),
+        WindowCommands::ToggleNativeFullscreen => Ok(RiftCommand::Reactor(
+            reactor::Command::Reactor(reactor::ReactorCommand::ToggleNativeFullscreen),
+        )),
+        WindowCommands::ToggleAlwaysOnTop => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::ToggleAlwaysOnTop,
+        ))),
+        WindowCommands::CenterFloat => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::CenterFloat,
+        ))),
+        WindowCommands::ResizeFloat { size } => Ok(RiftCommand::Reactor(
+            reactor::Command::Reactor(reactor::ReactorCommand::ResizeFloat(size)),
+        )),
+        WindowCommands::SwapWithHint => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::SwapWithHint,
+        ))),
+        WindowCommands::FocusWithHint => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::FocusWithHint,
+        ))),
     }
 }
 
@@ -773,6 +1249,15 @@ fn parse_window_id(input: &str) -> Result<WindowId, String> {
     ))
 }
 
+/// A workspace given on the command line: a number is an index, anything else
+/// a name.
+fn parse_workspace_selector(value: &str) -> WorkspaceSelector {
+    match value.trim().parse() {
+        Ok(index) => WorkspaceSelector::Index(index),
+        Err(_) => WorkspaceSelector::Name(value.to_string()),
+    }
+}
+
 fn parse_layout_mode(value: &str) -> Result<LayoutMode, String> {
     match value.trim().to_ascii_lowercase().as_str() {
         "traditional" => Ok(LayoutMode::Traditional),
@@ -796,26 +1281,32 @@ fn map_workspace_command(cmd: WorkspaceCommands) -> Result<RiftCommand, String>
         WorkspaceCommands::Prev { skip_empty } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::PrevWorkspace(skip_empty)),
         )),
-        WorkspaceCommands::Switch { workspace_id } => Ok(RiftCommand::Reactor(
-            reactor::Command::Layout(LC::SwitchToWorkspace(workspace_id)),
-        )),
-        WorkspaceCommands::MoveWindow {
-            workspace_id,
-            follow,
-            window_id,
-        } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
-            LC::MoveWindowToWorkspace {
-                workspace: WorkspaceSelector::Index(workspace_id),
+        WorkspaceCommands::Switch { workspace } => {
+            let command = match parse_workspace_selector(&workspace) {
+                WorkspaceSelector::Index(index) => LC::SwitchToWorkspace(index),
+                WorkspaceSelector::Name(name) => LC::SwitchToNamedWorkspace(name),
+            };
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(command)))
+        }
+        WorkspaceCommands::MoveWindow { workspace, follow, window_id } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::MoveWindowToWorkspace {
+                workspace: parse_workspace_selector(&workspace),
                 follow,
                 window_id,
-            },
-        ))),
+            }),
+        )),
         WorkspaceCommands::Create => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::CreateWorkspace,
         ))),
         WorkspaceCommands::Last => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::SwitchToLastWorkspace,
         ))),
+        WorkspaceCommands::Rename { workspace, name } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::RenameWorkspace {
+                workspace: Some(parse_workspace_selector(&workspace)),
+                name,
+            }),
+        )),
         WorkspaceCommands::SetLayout { workspace_id, mode } => {
             let mode = parse_layout_mode(&mode)?;
             Ok(RiftCommand::Reactor(reactor::Command::Layout(
@@ -833,6 +1324,14 @@ fn map_layout_command(cmd: LayoutCommands) -> Result<RiftCommand, String> {
         LayoutCommands::MoveNode { direction } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::MoveNode(direction.into())),
         )),
+        LayoutCommands::MoveToIndex { index } => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::MoveToIndex {
+                index,
+            })))
+        }
+        LayoutCommands::SwapWithIndex { index } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::SwapWithIndex { index }),
+        )),
         LayoutCommands::JoinWindow { direction } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::JoinWindow(direction.into())),
         )),
@@ -845,6 +1344,48 @@ fn map_layout_command(cmd: LayoutCommands) -> Result<RiftCommand, String> {
         LayoutCommands::ToggleOrientation => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ToggleOrientation,
         ))),
+        LayoutCommands::Preselect { direction, ratio } => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::Preselect {
+                direction: direction.into(),
+                ratio,
+            })))
+        }
+        LayoutCommands::Group { direction } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::GroupWindow(direction.into()),
+        ))),
+        LayoutCommands::Ungroup => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::UngroupWindow)))
+        }
+        LayoutCommands::NextInGroup => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::NextInGroup)))
+        }
+        LayoutCommands::PrevInGroup => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::PrevInGroup)))
+        }
+        LayoutCommands::Rebalance => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::Rebalance)))
+        }
+        LayoutCommands::Save { name } => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::SaveLayout {
+                path: named_layout_path(&name)?,
+            },
+        ))),
+        LayoutCommands::Restore { name, scope } => {
+            let path = named_layout_path(&name)?;
+            layout::LayoutEngine::load(path.clone())
+                .map_err(|error| format!("could not load layout `{name}`: {error}"))?;
+            let scope = match scope {
+                CliRestoreScope::Workspace => layout::RestoreScope::Workspace,
+                CliRestoreScope::Space => layout::RestoreScope::Space,
+            };
+            Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::RestoreLayout {
+                    path,
+                    scope,
+                    source: layout::RestoreSource::SavedActiveSpace,
+                },
+            )))
+        }
         LayoutCommands::Unjoin => {
             Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::UnjoinWindows)))
         }
@@ -877,11 +1418,19 @@ fn map_layout_command(cmd: LayoutCommands) -> Result<RiftCommand, String> {
         LayoutCommands::CenterSelection => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::CenterSelection,
         ))),
+        LayoutCommands::CycleColumnWidth { backward } => {
+            let command = if backward {
+                LC::CycleColumnWidthBackward
+            } else {
+                LC::CycleColumnWidth
+            };
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(command)))
+        }
     }
 }
 
 fn map_config_command(cmd: ConfigCommands) -> Result<RiftCommand, String> {
-    use rift_wm::common::config::{AnimationEasing, ConfigCommand};
+    use rift_wm::common::config::AnimationEasing;
 
     let cfg_cmd = match cmd {
         ConfigCommands::SetAnimate { value } => {
@@ -921,7 +1470,8 @@ fn map_config_command(cmd: ConfigCommands) -> Result<RiftCommand, String> {
                 "ease_in_circ" => AnimationEasing::EaseInCirc,
                 "ease_out_circ" => AnimationEasing::EaseOutCirc,
                 "ease_in_out_circ" => AnimationEasing::EaseInOutCirc,
-                _ => return Err(format!("Invalid animation easing: {}", value)),
+                _ => parse_parametric_easing(&value)
+                    .ok_or_else(|| format!("Invalid animation easing: {}", value))?,
             };
             ConfigCommand::SetAnimationEasing(easing)
         }
@@ -948,6 +1498,26 @@ fn map_config_command(cmd: ConfigCommands) -> Result<RiftCommand, String> {
         ConfigCommands::SetInnerGaps { horizontal, vertical } => {
             ConfigCommand::SetInnerGaps { horizontal, vertical }
         }
+        ConfigCommands::Gaps { adjustment, display, persist } => {
+            let GapAdjustment { scope, value, absolute } = adjustment;
+            if absolute {
+                ConfigCommand::SetGaps {
+                    scope,
+                    value,
+                    display,
+                    display_name: None,
+                    persist,
+                }
+            } else {
+                ConfigCommand::AdjustGaps {
+                    scope,
+                    delta: value,
+                    display,
+                    display_name: None,
+                    persist,
+                }
+            }
+        }
         ConfigCommands::SetWorkspaceNames { names } => ConfigCommand::SetWorkspaceNames(names),
         ConfigCommands::Set { key, value } => {
             let parsed_value: Value = match serde_json::from_str(&value) {
@@ -959,11 +1529,31 @@ fn map_config_command(cmd: ConfigCommands) -> Result<RiftCommand, String> {
         ConfigCommands::Get => ConfigCommand::GetConfig,
         ConfigCommands::Save => ConfigCommand::SaveConfig,
         ConfigCommands::Reload => ConfigCommand::ReloadConfig,
+        ConfigCommands::Profile { name } => ConfigCommand::SetProfile(name),
     };
 
     Ok(RiftCommand::Config(cfg_cmd))
 }
 
+/// Parses `spring(mass, stiffness, damping)` and
+/// `cubic_bezier(x1, y1, x2, y2)` easing definitions.
+fn parse_parametric_easing(value: &str) -> Option<rift_wm::common::config::AnimationEasing> {
+    use rift_wm::common::config::AnimationEasing;
+
+    let (name, args) = value.trim().strip_suffix(')')?.split_once('(')?;
+    let args: Vec<f64> =
+        args.split(',').map(|arg| arg.trim().parse().ok()).collect::<Option<_>>()?;
+    match (name.trim(), args.as_slice()) {
+        ("spring", &[mass, stiffness, damping]) => {
+            Some(AnimationEasing::Spring { mass, stiffness, damping })
+        }
+        ("cubic_bezier" | "cubic-bezier", &[x1, y1, x2, y2]) => {
+            Some(AnimationEasing::CubicBezier([x1, y1, x2, y2]))
+        }
+        _ => None,
+    }
+}
+
 fn map_mission_control_command(cmd: MissionControlCommands) -> Result<RiftCommand, String> {
     match cmd {
         MissionControlCommands::ShowAll => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
@@ -975,6 +1565,9 @@ fn map_mission_control_command(cmd: MissionControlCommands) -> Result<RiftComman
         MissionControlCommands::Dismiss => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
             reactor::ReactorCommand::DismissMissionControl,
         ))),
+        MissionControlCommands::Overview => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::ToggleOverview,
+        ))),
     }
 }
 
@@ -991,6 +1584,11 @@ fn map_space_command(cmd: SpaceCommands) -> Result<RiftCommand, String> {
 
 fn map_display_command(cmd: DisplayCommands) -> Result<RiftCommand, String> {
     match cmd {
+        DisplayCommands::ToggleActivated { direction, index, uuid } => Ok(RiftCommand::Reactor(
+            reactor::Command::Reactor(reactor::ReactorCommand::ToggleDisplayActivated(
+                build_display_selector(direction, index, uuid)?,
+            )),
+        )),
         DisplayCommands::Focus { direction, index, uuid } => {
             let selector = build_display_selector(direction, index, uuid)?;
             Ok(RiftCommand::Reactor(reactor::Command::Reactor(
@@ -1018,6 +1616,17 @@ fn map_display_command(cmd: DisplayCommands) -> Result<RiftCommand, String> {
                 window_id,
             },
         ))),
+        DisplayCommands::SwitchWorkspace {
+            workspace_id,
+            direction,
+            index,
+            uuid,
+        } => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::SwitchWorkspaceOnDisplay {
+                workspace: workspace_id,
+                selector: build_display_selector(direction, index, uuid)?,
+            },
+        ))),
     }
 }
 
@@ -1059,6 +1668,101 @@ fn parse_focus_direction(value: &str) -> Result<layout::Direction, String> {
     }
 }
 
+#[derive(Clone)]
+enum OutputFormat {
+    Json,
+    Plain,
+    Template(String),
+}
+
+fn parse_output_format(value: &str) -> Result<OutputFormat, String> {
+    match value {
+        "json" => Ok(OutputFormat::Json),
+        "plain" => Ok(OutputFormat::Plain),
+        _ => match value.strip_prefix("template:") {
+            Some(template) => Ok(OutputFormat::Template(template.to_string())),
+            None => Err(format!(
+                "Invalid format '{}'; must be json, plain, or template:<template>",
+                value
+            )),
+        },
+    }
+}
+
+/// Writes one line per element of an array response, or a single line for
+/// anything else. `null` (e.g. no focused window) writes nothing.
+fn write_lines(value: &Value, line: impl Fn(&Value) -> String) -> Result<(), String> {
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    let mut writer = io::BufWriter::new(&mut handle);
+
+    let items: &[Value] = match value {
+        Value::Array(items) => items.as_slice(),
+        Value::Null => &[],
+        other => std::slice::from_ref(other),
+    };
+    for item in items {
+        writeln!(writer, "{}", line(item)).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+fn plain_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The scalar fields of an object, tab-separated and sorted by field name.
+fn plain_line(value: &Value) -> String {
+    match value {
+        Value::Object(fields) => fields
+            .values()
+            .filter(|field| !field.is_object() && !field.is_array())
+            .map(plain_value)
+            .collect::<Vec<_>>()
+            .join("\t"),
+        other => plain_value(other),
+    }
+}
+
+/// Expands `{field}` placeholders from `item`. Nested fields use dots
+/// (`{frame.origin.x}`), and `{workspace}`, `{display}` or `{app}` fall back
+/// to the `_name` field. `{{` and `}}` are literal braces; unknown fields
+/// expand to nothing.
+fn render_template(template: &str, item: &Value) -> String {
+    let lookup = |key: &str| {
+        let pointer = format!("/{}", key.replace('.', "/"));
+        item.pointer(&pointer)
+            .or_else(|| item.get(format!("{key}_name")))
+            .map(plain_value)
+            .unwrap_or_default()
+    };
+
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let key: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                out.push_str(&lookup(key.trim()));
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 fn write_json(value: &Value, pretty: bool) -> Result<(), String> {
     let stdout = io::stdout();
     let mut handle = stdout.lock();
@@ -1073,9 +1777,402 @@ fn write_json(value: &Value, pretty: bool) -> Result<(), String> {
     writer.flush().map_err(|e| e.to_string())
 }
 
+/// One leaf of the `execute` subcommand tree, read from the clap definitions
+/// so the listing cannot drift from what the CLI accepts.
+struct CommandEntry {
+    path: String,
+    about: String,
+    args: Vec<Value>,
+    usage: String,
+}
+
+fn collect_commands(command: &clap::Command, path: &str, out: &mut Vec<CommandEntry>) {
+    let path = if path.is_empty() {
+        command.get_name().to_string()
+    } else {
+        format!("{path} {}", command.get_name())
+    };
+    if command.has_subcommands() {
+        for sub in command.get_subcommands() {
+            collect_commands(sub, &path, out);
+        }
+        return;
+    }
+
+    let mut args = Vec::new();
+    let mut usage = path.clone();
+    for arg in command.get_arguments().filter(|arg| arg.get_id() != "help") {
+        let name = match arg.get_long() {
+            Some(long) => format!("--{long}"),
+            None => arg.get_id().to_string(),
+        };
+        let takes_value = arg.get_num_args().is_none_or(|n| n.takes_values());
+        let shown = match (arg.is_positional(), takes_value) {
+            (true, _) => format!("<{name}>"),
+            (false, true) => format!("{name} <{}>", arg.get_id()),
+            (false, false) => name.clone(),
+        };
+        usage.push(' ');
+        if arg.is_required_set() {
+            usage.push_str(&shown);
+        } else {
+            usage.push_str(&format!("[{shown}]"));
+        }
+        args.push(serde_json::json!({
+            "name": name,
+            "required": arg.is_required_set(),
+            "takes_value": takes_value,
+            "help": arg.get_help().map(|help| help.to_string()),
+            "possible_values": arg
+                .get_possible_values()
+                .iter()
+                .map(|value| value.get_name().to_string())
+                .collect::<Vec<_>>(),
+        }));
+    }
+    out.push(CommandEntry {
+        path,
+        about: command.get_about().map(|about| about.to_string()).unwrap_or_default(),
+        args,
+        usage,
+    });
+}
+
+fn run_commands(json: bool) -> i32 {
+    let mut cli = Cli::command();
+    cli.build();
+    let mut commands = Vec::new();
+    if let Some(execute) = cli.find_subcommand("execute") {
+        for sub in execute.get_subcommands() {
+            collect_commands(sub, "", &mut commands);
+        }
+    }
+    let bindings = [
+        ("layout", binding_signatures::<LayoutCommand>()),
+        ("reactor", binding_signatures::<reactor::ReactorCommand>()),
+        ("wm", binding_signatures::<WmCmd>()),
+        ("config", binding_signatures::<ConfigCommand>()),
+    ];
+
+    if json {
+        let value = serde_json::json!({
+            "execute": commands
+                .iter()
+                .map(|entry| serde_json::json!({
+                    "command": entry.path,
+                    "about": entry.about,
+                    "args": entry.args,
+                }))
+                .collect::<Vec<_>>(),
+            "bindings": bindings
+                .iter()
+                .map(|(kind, signatures)| {
+                    let signatures = signatures
+                        .iter()
+                        .map(|(name, params)| serde_json::json!({
+                            "command": name,
+                            "params": (!params.is_empty()).then_some(params),
+                        }))
+                        .collect();
+                    (kind.to_string(), Value::Array(signatures))
+                })
+                .collect::<serde_json::Map<_, _>>(),
+        });
+        return match write_json(&value, true) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("Failed to write command list: {e}");
+                1
+            }
+        };
+    }
+
+    let width = commands.iter().map(|entry| entry.usage.len()).max().unwrap_or(0);
+    println!("execute commands (rift-cli execute ...):");
+    for entry in &commands {
+        println!("  {:width$}  {}", entry.usage, entry.about);
+    }
+    for (kind, signatures) in &bindings {
+        println!("\n{kind} commands (key bindings):");
+        for (name, params) in signatures {
+            if params.is_empty() {
+                println!("  {name}");
+            } else {
+                println!("  {name} {params}");
+            }
+        }
+    }
+    0
+}
+
+/// Commands the hint overlay and debugging tools send on their own; binding
+/// them to a key does nothing useful.
+const INTERNAL_COMMANDS: &[&str] = &["debug", "serialize", "pick_hint", "cancel_hints"];
+
+/// Every command of `T` a key binding can name, with a sketch of its
+/// parameters, e.g. `move_focus <left|right|up|down>` or
+/// `move_to_index { index }`. Read from `T`'s `Deserialize` impl, so it
+/// lists exactly what the config parser accepts.
+fn binding_signatures<T: serde::de::DeserializeOwned>() -> Vec<(String, String)> {
+    let mut names = String::new();
+    let _ = T::deserialize(SignatureTracer::new(Trace::Variants, &mut names));
+    names
+        .split_whitespace()
+        .zip(0u32..)
+        .filter(|(name, _)| !INTERNAL_COMMANDS.contains(name))
+        .map(|(name, index)| {
+            let mut params = String::new();
+            let _ = T::deserialize(SignatureTracer::new(Trace::Variant(index), &mut params));
+            (name.to_string(), params)
+        })
+        .collect()
+}
+
+enum Trace {
+    /// Record the names of the enum's variants.
+    Variants,
+    /// Pick this variant and record what it takes.
+    Variant(u32),
+    /// Record the shape of a parameter.
+    Value,
+}
+
+/// A deserializer that reads no input. Each `deserialize_*` call records
+/// what was asked for and then fails, which is enough to describe a command
+/// without constructing one.
+struct SignatureTracer<'a> {
+    trace: Trace,
+    out: &'a mut String,
+}
+
+type TraceError = serde::de::value::Error;
+
+impl<'a> SignatureTracer<'a> {
+    fn new(trace: Trace, out: &'a mut String) -> Self { SignatureTracer { trace, out } }
+
+    fn record<T>(self, shape: &str) -> Result<T, TraceError> {
+        self.out.push_str(shape);
+        Err(serde::de::Error::custom("traced"))
+    }
+}
+
+macro_rules! trace_shapes {
+    ($($method:ident => $shape:literal),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, _: V) -> Result<V::Value, TraceError> {
+                self.record($shape)
+            }
+        )*
+    };
+}
+
+impl<'de> serde::Deserializer<'de> for SignatureTracer<'_> {
+    type Error = TraceError;
+
+    trace_shapes! {
+        deserialize_any => "<value>",
+        deserialize_bool => "<bool>",
+        deserialize_i8 => "<integer>",
+        deserialize_i16 => "<integer>",
+        deserialize_i32 => "<integer>",
+        deserialize_i64 => "<integer>",
+        deserialize_u8 => "<integer>",
+        deserialize_u16 => "<integer>",
+        deserialize_u32 => "<integer>",
+        deserialize_u64 => "<integer>",
+        deserialize_f32 => "<number>",
+        deserialize_f64 => "<number>",
+        deserialize_char => "<char>",
+        deserialize_str => "<string>",
+        deserialize_string => "<string>",
+        deserialize_bytes => "<bytes>",
+        deserialize_byte_buf => "<bytes>",
+        deserialize_unit => "",
+        deserialize_seq => "<list>",
+        deserialize_map => "<table>",
+        deserialize_identifier => "<string>",
+        deserialize_ignored_any => "",
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        let mut inner = String::new();
+        let result = visitor.visit_some(SignatureTracer::new(Trace::Value, &mut inner));
+        self.out.push_str(&format!("[{inner}]"));
+        result
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: V,
+    ) -> Result<V::Value, TraceError> {
+        self.record("")
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, _: V) -> Result<V::Value, TraceError> {
+        self.record(&format!("<{len} values>"))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        _: V,
+    ) -> Result<V::Value, TraceError> {
+        self.record(&format!("<{len} values>"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, TraceError> {
+        self.record(&format!("{{ {} }}", fields.join(", ")))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        match self.trace {
+            Trace::Variants => self.record(&variants.join(" ")),
+            Trace::Variant(index) => visitor.visit_enum(VariantTracer { index, out: self.out }),
+            Trace::Value => self.record(&format!("<{}>", variants.join("|"))),
+        }
+    }
+}
+
+struct VariantTracer<'a> {
+    index: u32,
+    out: &'a mut String,
+}
+
+impl<'de> EnumAccess<'de> for VariantTracer<'_> {
+    type Error = TraceError;
+    type Variant = Self;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, Self), TraceError> {
+        let index = IntoDeserializer::<TraceError>::into_deserializer(self.index);
+        let variant = seed.deserialize(index)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantTracer<'_> {
+    type Error = TraceError;
+
+    fn unit_variant(self) -> Result<(), TraceError> { Ok(()) }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<S::Value, TraceError> {
+        seed.deserialize(SignatureTracer::new(Trace::Value, self.out))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, _: V) -> Result<V::Value, TraceError> {
+        SignatureTracer::new(Trace::Value, self.out).record(&format!("<{len} values>"))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, TraceError> {
+        SignatureTracer::new(Trace::Value, self.out).record(&format!("{{ {} }}", fields.join(", ")))
+    }
+}
+
+/// Connects with the credentials from `RIFT_IPC_TOKEN` and
+/// `RIFT_IPC_READ_ONLY`, for servers with `settings.ipc` restrictions.
+fn connect() -> Result<RiftMachClient, String> {
+    let mut client = RiftMachClient::connect()?;
+    if let Ok(token) = std::env::var("RIFT_IPC_TOKEN")
+        && !token.is_empty()
+    {
+        client = client.with_token(token);
+    }
+    if std::env::var("RIFT_IPC_READ_ONLY").is_ok_and(|v| v != "0") {
+        client = client.read_only();
+    }
+    Ok(client)
+}
+
+/// Prints the doctor report and returns the exit code: 1 if any check failed
+/// with an error.
+fn run_doctor(json: bool) -> i32 {
+    let response = connect().and_then(|client| client.send_request(&RiftRequest::Doctor));
+    let data = match response {
+        Ok(RiftResponse::Success { data }) => data,
+        Ok(RiftResponse::Error { error }) => {
+            eprintln!("Error: {}", error);
+            return 1;
+        }
+        Err(e) => {
+            println!("[error] rift: not reachable ({e})");
+            println!("        fix: start it with `rift service start`");
+            return 1;
+        }
+    };
+    if json {
+        if let Err(e) = write_json(&data, true) {
+            eprintln!("Failed to handle response: {}", e);
+            return 1;
+        }
+    }
+    match serde_json::from_value::<DoctorReport>(data) {
+        Ok(report) => {
+            if !json {
+                print!("{}", report.render());
+            }
+            i32::from(report.status() == CheckStatus::Error)
+        }
+        Err(e) => {
+            eprintln!("Failed to parse doctor report: {}", e);
+            1
+        }
+    }
+}
+
+fn run_import(source: ImportSource, path: &Path) -> i32 {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path.display(), e);
+            return 1;
+        }
+    };
+    let imported = match import::import(source, &text) {
+        Ok(imported) => imported,
+        Err(e) => {
+            eprintln!("Failed to parse {}: {}", path.display(), e);
+            return 1;
+        }
+    };
+    for warning in &imported.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    print!("{}", imported.to_toml());
+    0
+}
+
 fn run_mach_subscription(event: String) -> Result<(), String> {
     let pretty = std::env::var("RIFT_CLI_PRETTY").map(|v| v != "0").unwrap_or(false);
-    let client = RiftMachClient::connect()?;
+    let client = connect()?;
     let subscription = client.subscribe(event)?;
 
     loop {
@@ -1089,3 +2186,69 @@ fn run_mach_subscription(event: String) -> Result<(), String> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn output_formats_parse() {
+        assert!(matches!(parse_output_format("json"), Ok(OutputFormat::Json)));
+        assert!(matches!(parse_output_format("plain"), Ok(OutputFormat::Plain)));
+        assert!(matches!(
+            parse_output_format("template:{id} {title}"),
+            Ok(OutputFormat::Template(template)) if template == "{id} {title}"
+        ));
+        assert!(parse_output_format("yaml").is_err());
+        assert!(parse_output_format("template").is_err());
+    }
+
+    #[test]
+    fn plain_lines_skip_nested_fields_and_sort_by_name() {
+        let window = json!({
+            "title": "Inbox",
+            "id": 7,
+            "frame": { "origin": { "x": 0.0 } },
+            "app_name": null,
+            "floating": false,
+            "tags": ["a"],
+        });
+        assert_eq!(plain_line(&window), "\tfalse\t7\tInbox");
+        assert_eq!(plain_line(&json!("main")), "main");
+        assert_eq!(plain_line(&Value::Null), "");
+    }
+
+    #[test]
+    fn binding_signatures_show_parameters() {
+        let layout = binding_signatures::<LayoutCommand>();
+        let params = |name: &str| {
+            let (_, params) = layout.iter().find(|(command, _)| command == name)?;
+            Some(params.as_str())
+        };
+        assert_eq!(params("next_window"), Some(""));
+        assert_eq!(params("move_focus"), Some("<left|right|up|down>"));
+        assert_eq!(params("move_to_index"), Some("{ index }"));
+        assert_eq!(params("set_split_ratio"), Some("<number>"));
+
+        let reactor = binding_signatures::<reactor::ReactorCommand>();
+        assert!(reactor.iter().any(|(command, _)| command == "toggle_overview"));
+        assert!(!reactor.iter().any(|(command, _)| INTERNAL_COMMANDS.contains(&command.as_str())));
+    }
+
+    #[test]
+    fn templates_expand_fields() {
+        let window = json!({
+            "id": 7,
+            "title": "Inbox",
+            "frame": { "origin": { "x": 10.5 } },
+            "workspace_name": "mail",
+        });
+        assert_eq!(render_template("{id}: {title}", &window), "7: Inbox");
+        assert_eq!(render_template("{frame.origin.x}", &window), "10.5");
+        assert_eq!(render_template("{workspace}", &window), "mail");
+        assert_eq!(render_template("{{{ id }}}", &window), "{7}");
+        assert_eq!(render_template("[{missing}]", &window), "[]");
+    }
+}
@@ -1,31 +1,38 @@
 use std::future::Future;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 
 use clap::{Parser, Subcommand};
+use nix::sys::signal::{SigSet, Signal};
 use objc2::MainThreadMarker;
 use objc2_application_services::AXUIElement;
+use rift_wm::actor::borders::Borders;
 use rift_wm::actor::config::ConfigActor;
 use rift_wm::actor::config_watcher::ConfigWatcher;
+use rift_wm::actor::drop_zone_overlay::DropZoneOverlay;
 use rift_wm::actor::event_tap::EventTap;
 use rift_wm::actor::gesture_tap::GestureTap;
+use rift_wm::actor::hint_overlay::HintOverlay;
+use rift_wm::actor::hooks::{self, Hooks};
 use rift_wm::actor::menu_bar::Menu;
 use rift_wm::actor::mission_control::MissionControlActor;
 use rift_wm::actor::mission_control_observer::NativeMissionControl;
 use rift_wm::actor::notification_center::NotificationCenter;
+use rift_wm::actor::preselect_overlay::PreselectOverlay;
 use rift_wm::actor::process::ProcessActor;
 use rift_wm::actor::reactor::{self, Reactor};
 use rift_wm::actor::spaces::SpacesActor;
 use rift_wm::actor::stack_line::StackLine;
 use rift_wm::actor::window_notify as window_notify_actor;
 use rift_wm::actor::wm_controller::{self, WmController};
-use rift_wm::common::config::{Config, config_file, restore_file};
+use rift_wm::common::config::{Config, config_file, journal_file, restore_file};
 use rift_wm::common::log;
 use rift_wm::common::util::execute_startup_commands;
 use rift_wm::ipc;
 use rift_wm::layout_engine::LayoutEngine;
 use rift_wm::model::tx_store::WindowTxStore;
 use rift_wm::sys::accessibility::ensure_accessibility_permission;
+use rift_wm::sys::crash_restore;
 use rift_wm::sys::executor::Executor;
 use rift_wm::sys::mach::init_window_sub_level_server_port;
 use rift_wm::sys::screen::{CoordinateConverter, displays_have_separate_spaces};
@@ -62,6 +69,12 @@ struct Cli {
     #[arg(long)]
     restore: bool,
 
+    /// Restore the state journal left behind by a session that crashed. Takes
+    /// precedence over --restore. The crashed session's copy is only kept
+    /// until the next start.
+    #[arg(long)]
+    recover: bool,
+
     /// Record reactor events to the specified file path. Overwrites the file if
     /// exists.
     #[arg(long)]
@@ -71,6 +84,13 @@ struct Cli {
     #[arg(long, value_name = "PATH")]
     config: Option<PathBuf>,
 
+    /// Start without animations, focus-follows-mouse, automatic workspace
+    /// switching or drag-swap, with every window floating and without startup
+    /// commands or hooks. The saved layout is not restored. Queries and commands
+    /// keep working so the config can be fixed and reloaded.
+    #[arg(long)]
+    safe_mode: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -82,6 +102,55 @@ enum Commands {
         #[command(subcommand)]
         service: ServiceCommands,
     },
+    /// Inspect or upgrade the saved layout state
+    State {
+        #[command(subcommand)]
+        state: StateCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum StateCommands {
+    /// Show the schema version and contents of a saved layout file
+    Inspect {
+        /// Layout file to read (defaults to the master file)
+        #[arg(long, value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+    /// Rewrite a saved layout file in the current schema, keeping a backup of the original
+    Migrate {
+        /// Layout file to rewrite (defaults to the master file)
+        #[arg(long, value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+}
+
+fn handle_state_command(command: &StateCommands) -> Result<String, String> {
+    match command {
+        StateCommands::Inspect { path } => {
+            let path = path.clone().unwrap_or_else(restore_file);
+            LayoutEngine::inspect_state(&path)
+                .map(|inspection| format!("{}: {}", path.display(), inspection.summary()))
+                .map_err(|error| {
+                    format!("Could not read layout file at {}: {error}", path.display())
+                })
+        }
+        StateCommands::Migrate { path } => {
+            let path = path.clone().unwrap_or_else(restore_file);
+            match LayoutEngine::migrate_state(&path) {
+                Ok(Some(backup)) => Ok(format!(
+                    "Migrated {} to the current schema; original saved to {}",
+                    path.display(),
+                    backup.display()
+                )),
+                Ok(None) => Ok(format!("{} is already up to date", path.display())),
+                Err(error) => Err(format!(
+                    "Could not migrate layout file at {}: {error}",
+                    path.display()
+                )),
+            }
+        }
+    }
 }
 
 /// this is okay because there is no recovery mechanism for actors
@@ -92,6 +161,46 @@ async fn supervise(name: &'static str, fut: impl Future<Output = ()>) {
     panic!("{name} exited");
 }
 
+fn termination_signals() -> SigSet {
+    let mut signals = SigSet::empty();
+    signals.add(Signal::SIGTERM);
+    signals.add(Signal::SIGINT);
+    signals
+}
+
+fn remove_journal(path: &Path) {
+    if let Err(error) = std::fs::remove_file(path)
+        && error.kind() != std::io::ErrorKind::NotFound
+    {
+        eprintln!("Could not remove state journal at {}: {error}", path.display());
+    }
+}
+
+/// Waits for SIGTERM or SIGINT (launchd stopping the service, Ctrl-C) and
+/// exits after removing the journal, so a clean stop is not reported as a
+/// crash on the next start.
+fn spawn_shutdown_listener(signals: SigSet, journal: PathBuf) {
+    std::thread::Builder::new()
+        .name("shutdown".into())
+        .spawn(move || match signals.wait() {
+            Ok(signal) => {
+                tracing::info!(?signal, "shutting down");
+                remove_journal(&journal);
+                process::exit(0);
+            }
+            Err(error) => {
+                // Let the signals take their default action on this thread
+                // instead of staying blocked everywhere.
+                tracing::warn!(%error, "failed to wait for termination signals");
+                let _ = signals.thread_unblock();
+                loop {
+                    std::thread::park();
+                }
+            }
+        })
+        .expect("failed to spawn shutdown thread");
+}
+
 fn main() {
     sigpipe::reset();
     let opt = Cli::parse();
@@ -109,10 +218,29 @@ fn main() {
         }
     }
 
+    if let Some(Commands::State { state }) = &opt.command {
+        match handle_state_command(state) {
+            Ok(msg) => {
+                println!("{}", msg);
+                process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+    }
+
     if std::env::var_os("RUST_BACKTRACE").is_none() {
         // SAFETY: We are single threaded at this point.
         unsafe { std::env::set_var("RUST_BACKTRACE", "1") };
     }
+    // Block the termination signals before any other thread exists so they
+    // are only delivered to the shutdown listener.
+    let termination_signals = termination_signals();
+    if let Err(error) = termination_signals.thread_block() {
+        eprintln!("Could not block termination signals: {error}");
+    }
     log::init_logging();
     install_panic_hook();
 
@@ -147,6 +275,10 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
     };
     config.settings.animate &= !opt.no_animate;
     config.settings.default_disable |= opt.default_disable;
+    if opt.safe_mode {
+        config.apply_safe_mode();
+        eprintln!("Starting in safe mode: windows float and automatic behaviour is disabled.");
+    }
 
     if opt.validate {
         let path = restore_file();
@@ -160,11 +292,60 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
         process::exit(0);
     }
 
-    execute_startup_commands(&config.settings.run_on_start);
+    if let Err(error) = log::configure_log_file(&config.settings.log_file) {
+        eprintln!("Could not set up the log file: {error}");
+    }
+
+    execute_startup_commands(&config.settings.run_on_start, &config.settings.ipc.spawn_env());
 
     let (broadcast_tx, broadcast_rx) = rift_wm::actor::channel();
 
-    let mut layout = if opt.restore {
+    let journal = journal_file();
+    // This session will overwrite the journal, so keep a crashed session's
+    // copy aside for a later --recover.
+    let crashed_journal = journal.with_extension("crashed.ron");
+    if journal.exists() && !opt.recover {
+        match std::fs::rename(&journal, &crashed_journal) {
+            Ok(()) => eprintln!(
+                "The previous session did not exit cleanly; its state was kept at {}. Restart with --recover to restore it.",
+                crashed_journal.display()
+            ),
+            Err(error) => eprintln!(
+                "The previous session did not exit cleanly, but its state journal could not be kept: {error}"
+            ),
+        }
+    } else if !opt.recover {
+        // A crashed copy from before the last start was already reported then.
+        remove_journal(&crashed_journal);
+    }
+
+    let recovered = if opt.recover && !opt.safe_mode {
+        let source = if journal.exists() {
+            journal.clone()
+        } else {
+            crashed_journal.clone()
+        };
+        match LayoutEngine::load_for_startup_restore(source.clone()) {
+            Ok(layout) => {
+                remove_journal(&crashed_journal);
+                Some(layout)
+            }
+            Err(error) => {
+                eprintln!(
+                    "Could not recover state journal at {}; falling back: {error}",
+                    source.display()
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    spawn_shutdown_listener(termination_signals, journal.clone());
+
+    let mut layout = if let Some(layout) = recovered {
+        layout
+    } else if opt.restore && !opt.safe_mode {
         let path = restore_file();
         match LayoutEngine::load_for_startup_restore(path.clone()) {
             Ok(layout) => layout,
@@ -195,6 +376,11 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
     let (event_tap_tx, event_tap_rx) = rift_wm::actor::channel();
     let (menu_tx, menu_rx) = rift_wm::actor::channel();
     let (stack_line_tx, stack_line_rx) = rift_wm::actor::channel();
+    let (borders_tx, borders_rx) = rift_wm::actor::channel();
+    let (preselect_tx, preselect_rx) = rift_wm::actor::channel();
+    let (drop_zone_tx, drop_zone_rx) = rift_wm::actor::channel();
+    let (hint_tx, hint_rx) = rift_wm::actor::channel();
+    let (hooks_tx, hooks_rx) = rift_wm::actor::channel();
     let (wnd_tx, wnd_rx) = rift_wm::actor::channel();
     let window_tx_store = WindowTxStore::new();
     let (gesture_tap_tx, gesture_tap_rx) = rift_wm::actor::channel();
@@ -202,10 +388,18 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
         config.clone(),
         layout,
         reactor::Record::new(opt.record.as_deref()),
+        // Safe mode floats every window; journaling that would overwrite the
+        // state a later --recover needs.
+        reactor::Journal::new((!opt.safe_mode).then_some(journal)),
         event_tap_tx.clone(),
         broadcast_tx.clone(),
         menu_tx.clone(),
         stack_line_tx.clone(),
+        borders_tx,
+        preselect_tx,
+        drop_zone_tx,
+        hint_tx,
+        hooks_tx.clone(),
         Some((wnd_tx.clone(), window_tx_store.clone())),
         Some(gesture_tap_tx.clone()),
         opt.one,
@@ -217,13 +411,28 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
 
     ConfigWatcher::spawn(config_tx.clone(), config.clone(), config_path.clone());
 
-    let server_state = match ipc::run_mach_server(reactor.clone(), config_tx.clone()) {
-        Ok(state) => state,
-        Err(err) => {
-            eprintln!("{}", err);
-            process::exit(1);
-        }
-    };
+    let server_state =
+        match ipc::run_mach_server(reactor.clone(), config_tx.clone(), config.settings.ipc.clone())
+        {
+            Ok(state) => state,
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        };
+
+    let websocket = &config.settings.websocket;
+    if websocket.enabled
+        && let Err(err) = ipc::websocket::run_websocket_server(
+            websocket,
+            config.settings.ipc.clone(),
+            reactor.clone(),
+            config_tx.clone(),
+            server_state.clone(),
+        )
+    {
+        eprintln!("{}", err);
+    }
 
     let mach_bridge_rx = broadcast_rx;
 
@@ -234,6 +443,7 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
         loop {
             match rx.blocking_recv() {
                 Some((_span, event)) => {
+                    hooks_tx.send(hooks::Event::Broadcast(event.clone()));
                     let state = server_state.read();
                     state.publish(event);
                 }
@@ -323,6 +533,12 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
         stack_line_hit_rects,
     );
 
+    let borders = Borders::new(config.clone(), borders_rx, mtm);
+    let preselect_overlay = PreselectOverlay::new(config.clone(), preselect_rx, mtm);
+    let drop_zone_overlay = DropZoneOverlay::new(config.clone(), drop_zone_rx, mtm);
+    let hint_overlay = HintOverlay::new(config.clone(), hint_rx, mtm);
+    let hooks = Hooks::new(config.clone(), hooks_rx, (*reactor).clone());
+
     let mission_control = MissionControlActor::new(config.clone(), mc_rx, reactor.clone(), mtm);
     let mission_control_native = NativeMissionControl::new(events_tx.clone(), mc_native_rx);
 
@@ -349,6 +565,13 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
         })
         .expect("failed to spawn input thread");
 
+    // Hooks with a `match` expression wait on reactor queries, which must not
+    // stall the main thread.
+    std::thread::Builder::new()
+        .name("hooks".into())
+        .spawn(move || rift_wm::sys::executor::Executor::run(supervise("hooks", hooks.run())))
+        .expect("failed to spawn hooks thread");
+
     Executor::run_main(mtm, async move {
         join!(
             supervise("wm_controller", wm_controller.run()),
@@ -360,6 +583,10 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
             supervise("gesture_tap", gesture_tap.run()),
             supervise("menu", menu.run()),
             supervise("stack_line", stack_line.run()),
+            supervise("borders", borders.run()),
+            supervise("preselect_overlay", preselect_overlay.run()),
+            supervise("drop_zone_overlay", drop_zone_overlay.run()),
+            supervise("hint_overlay", hint_overlay.run()),
             supervise("window_notify", wn_actor.run()),
             supervise("mc_native", mission_control_native.run()),
             supervise("mission_control", mission_control.run()),
@@ -368,16 +595,16 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
     });
 }
 
-#[cfg(panic = "unwind")]
 fn install_panic_hook() {
     // Abort on panic instead of propagating panics to the main thread.
     // See Cargo.toml for why we don't use panic=abort everywhere.
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |info| {
         original_hook(info);
+        // Windows on hidden workspaces are parked off-screen; put everything
+        // back before going away.
+        crash_restore::restore_windows();
+        #[cfg(panic = "unwind")]
         std::process::abort();
     }));
 }
-
-#[cfg(not(panic = "unwind"))]
-fn install_panic_hook() {}
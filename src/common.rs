@@ -1,4 +1,6 @@
 pub mod collections;
 pub mod config;
+pub mod doctor;
 pub mod log;
+pub mod metrics;
 pub mod util;
@@ -1,14 +1,20 @@
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::bail;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::collections::HashMap;
 use crate::actor::wm_controller::WmCommand;
+use crate::model::broadcast::BroadcastEvent;
+use crate::model::matcher::WindowMatcher;
 use crate::sys::hotkey::{Hotkey, HotkeySpec};
 
+pub mod import;
+
 pub const MAX_WORKSPACES: usize = 128;
 
 // TODO: when to remove these?
@@ -18,8 +24,9 @@ const DEPRECATED_MAP: &[(&str, &str)] = &[
     ("toggle_tile_orientation", "toggle_orientation"),
 ];
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, strum_macros::VariantNames)]
 #[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
 pub enum ConfigCommand {
     SetAnimate(bool),
     SetAnimationDuration(f64),
@@ -41,6 +48,33 @@ pub enum ConfigCommand {
         horizontal: f64,
         vertical: f64,
     },
+    /// Grow (or, when negative, shrink) every inner or outer gap by `delta`,
+    /// on one display when `display` is given, otherwise everywhere. The
+    /// server resolves a display given by name to its UUID and fills in
+    /// `display_name` before the config is changed.
+    AdjustGaps {
+        scope: GapScope,
+        delta: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        display: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        display_name: Option<String>,
+        /// Also write the new gaps to the config file.
+        #[serde(default)]
+        persist: bool,
+    },
+    /// Set every inner or outer gap to `value`; `display` works as for
+    /// `AdjustGaps`.
+    SetGaps {
+        scope: GapScope,
+        value: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        display: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        display_name: Option<String>,
+        #[serde(default)]
+        persist: bool,
+    },
 
     SetWorkspaceNames(Vec<String>),
 
@@ -54,13 +88,33 @@ pub enum ConfigCommand {
     GetConfig,
     SaveConfig,
     ReloadConfig,
+    /// Reload the config file with the named profile applied on top of it,
+    /// or with no profile when `None`.
+    SetProfile(Option<String>),
 }
 
 pub fn data_dir() -> PathBuf { dirs::home_dir().unwrap().join(".rift") }
 pub fn restore_file() -> PathBuf { data_dir().join("layout.ron") }
+/// Layout state written periodically while rift runs, for `--recover`.
+pub fn journal_file() -> PathBuf { data_dir().join("journal.ron") }
+/// Where a layout snapshot saved under `name` lives, or `None` if the name
+/// would escape the snapshot directory.
+pub fn named_layout_file(name: &str) -> Option<PathBuf> {
+    let valid = !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\']);
+    valid.then(|| data_dir().join("layouts").join(format!("{name}.ron")))
+}
 pub fn config_file() -> PathBuf {
     dirs::home_dir().unwrap().join(".config").join("rift").join("config.toml")
 }
+/// Where a profile that is not defined inline in `config_path` is looked up:
+/// `profiles/<name>.toml` next to the config file.
+pub fn profile_file(config_path: &Path, name: &str) -> Option<PathBuf> {
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.starts_with('.');
+    let dir = config_path.parent()?;
+    valid.then(|| dir.join("profiles").join(format!("{name}.toml")))
+}
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
@@ -87,6 +141,64 @@ pub struct VirtualWorkspaceSettings {
     pub app_rules: Vec<AppWorkspaceRule>,
     #[serde(default)]
     pub workspace_rules: Vec<WorkspaceLayoutRule>,
+    /// Display-specific workspace sets keyed by display UUID or name
+    #[serde(default)]
+    pub per_display: HashMap<String, DisplayWorkspaceSettings>,
+    /// Workspace on the main display that collects the windows of a
+    /// disconnected display. Unset keeps each window on the workspace with
+    /// the same index.
+    #[serde(default)]
+    pub disconnected_display_workspace: Option<WorkspaceSelector>,
+    /// Script consulted when a window first appears, for placements app
+    /// rules cannot express.
+    #[serde(default)]
+    pub script: Option<RuleScriptSettings>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RuleScriptSettings {
+    /// Rhai script defining `fn on_window_created(window)`
+    pub path: PathBuf,
+    /// Time a single call may run before it is aborted
+    #[serde(default = "default_rule_script_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl RuleScriptSettings {
+    pub fn resolved_path(&self) -> PathBuf {
+        let Ok(relative) = self.path.strip_prefix("~") else {
+            return self.path.clone();
+        };
+        dirs::home_dir()
+            .map(|home| home.join(relative))
+            .unwrap_or_else(|| self.path.clone())
+    }
+}
+
+fn default_rule_script_timeout_ms() -> u64 { 10 }
+
+/// Independent workspace set for one display. Unset fields fall back to the
+/// global `virtual_workspaces` values.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayWorkspaceSettings {
+    #[serde(default)]
+    pub workspace_names: Option<Vec<String>>,
+    #[serde(default)]
+    pub default_workspace_count: Option<usize>,
+    #[serde(default)]
+    pub default_workspace: Option<usize>,
+}
+
+impl DisplayWorkspaceSettings {
+    /// Number of workspaces for this display. An entry that only lists names
+    /// gets exactly that many workspaces.
+    pub fn workspace_count(&self, fallback: usize) -> usize {
+        self.default_workspace_count
+            .or_else(|| self.workspace_names.as_ref().map(Vec::len))
+            .unwrap_or(fallback)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -96,8 +208,36 @@ pub struct WorkspaceLayoutRule {
     pub workspace: WorkspaceSelector,
     /// Layout mode to use for this workspace
     pub layout: LayoutMode,
+    /// Structure pre-built for the first windows of a traditional workspace
+    #[serde(default)]
+    pub template: Option<LayoutTemplate>,
+}
+
+/// Initial container structure for a traditional layout. Each of the first
+/// windows opened on an empty layout claims the next slot; once every slot is
+/// filled, windows are inserted next to the selection as usual.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutTemplate {
+    /// Direction the slots are laid out in (`horizontal` for columns, `vertical` for rows)
+    #[serde(default)]
+    pub orientation: crate::layout_engine::Orientation,
+    pub slots: Vec<TemplateSlot>,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateSlot {
+    /// Relative share of the screen, compared against the other slots
+    #[serde(default = "default_template_ratio")]
+    pub ratio: f64,
+    /// Stack the windows in this slot instead of splitting them
+    #[serde(default)]
+    pub stacked: bool,
+}
+
+fn default_template_ratio() -> f64 { 1.0 }
+
 // Allow specifying a workspace by numeric index or by name in the config.
 // This supports both `workspace = 2` and `workspace = "coding"` in app rules.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Eq)]
@@ -107,6 +247,12 @@ pub enum WorkspaceSelector {
     Name(String),
 }
 
+impl WorkspaceSelector {
+    /// Whether `name` would be read as an index wherever a workspace can be
+    /// given either way, which rules it out as a workspace name.
+    pub fn is_index_like(name: &str) -> bool { name.trim().parse::<usize>().is_ok() }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct AppWorkspaceRule {
@@ -144,6 +290,53 @@ pub struct AppWorkspaceRule {
     /// non-empty string and will be compared against the accessibility subrole
     /// reported by the AX APIs for a window (exact string match).
     pub ax_subrole: Option<String>,
+
+    /// Optional: Matcher expression combining window properties, e.g.
+    /// `app:"Safari" && title~"GitHub"`. Evaluated in addition to the fields above.
+    #[serde(default)]
+    pub matcher: Option<WindowMatcher>,
+
+    /// Optional: Glob matched against the application bundle path, e.g.
+    /// `/Applications/*.app` or `**/Electron Framework*`. `*` and `?` stop at `/`;
+    /// `**` crosses directories.
+    #[serde(default)]
+    pub bundle_path: Option<String>,
+
+    /// Optional: Window opacity between 0.0 and 1.0 applied to matching windows.
+    /// macOS may refuse to change another application's window alpha, in which
+    /// case the setting is skipped with a warning.
+    #[serde(default)]
+    pub opacity: Option<f64>,
+
+    /// Optional: Size and position applied once when a matching window first
+    /// appears floating, e.g. `{ width = 0.6, height = 0.7 }` for a centered
+    /// window covering 60% x 70% of the screen.
+    #[serde(default)]
+    pub floating_frame: Option<FloatingFrame>,
+}
+
+/// A frame expressed as fractions of the screen a window opens on.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct FloatingFrame {
+    pub width: f64,
+    pub height: f64,
+    /// Left edge as a fraction of the screen width. Centered horizontally when unset.
+    #[serde(default)]
+    pub x: Option<f64>,
+    /// Top edge as a fraction of the screen height. Centered vertically when unset.
+    #[serde(default)]
+    pub y: Option<f64>,
+}
+
+impl FloatingFrame {
+    pub fn resolve(&self, screen: CGRect) -> CGRect {
+        let CGSize { width, height } = screen.size;
+        let size = CGSize::new(width * self.width, height * self.height);
+        let x = self.x.map_or((width - size.width) / 2.0, |x| width * x);
+        let y = self.y.map_or((height - size.height) / 2.0, |y| height * y);
+        CGRect::new(CGPoint::new(screen.origin.x + x, screen.origin.y + y), size)
+    }
 }
 
 impl Default for VirtualWorkspaceSettings {
@@ -160,6 +353,9 @@ impl Default for VirtualWorkspaceSettings {
             reapply_app_rules_on_title_change: false,
             app_rules: Vec::new(),
             workspace_rules: Vec::new(),
+            per_display: HashMap::default(),
+            disconnected_display_workspace: None,
+            script: None,
         }
     }
 }
@@ -181,6 +377,11 @@ impl VirtualWorkspaceSettings {
         if self.workspace_names.len() > self.default_workspace_count {
             issues.push("More workspace names provided than default_workspace_count".to_string());
         }
+        for name in
+            self.workspace_names.iter().filter(|name| WorkspaceSelector::is_index_like(name))
+        {
+            issues.push(format!("Workspace name {name:?} must not be a number"));
+        }
 
         if self.default_workspace >= self.default_workspace_count {
             issues.push(format!(
@@ -205,13 +406,47 @@ impl VirtualWorkspaceSettings {
                 && rule.title_substring.is_none()
                 && rule.ax_role.is_none()
                 && rule.ax_subrole.is_none()
+                && rule.matcher.is_none()
+                && rule.bundle_path.is_none()
             {
                 issues.push(format!(
-                    "App rule {} has no app_id, app_name, title_regex, or title_substring specified",
+                    "App rule {} has no app_id, app_name, title_regex, title_substring, bundle_path, or matcher specified",
+                    index
+                ));
+            }
+
+            if rule.bundle_path.as_deref() == Some("") {
+                issues.push(format!("App rule {} has empty bundle_path", index));
+            }
+
+            if rule.matcher.as_ref().is_some_and(WindowMatcher::mentions_window_state) {
+                issues.push(format!(
+                    "App rule {} matcher uses floating, minimized or workspace, which app rules cannot match",
                     index
                 ));
             }
 
+            if let Some(opacity) = rule.opacity
+                && !(0.0..=1.0).contains(&opacity)
+            {
+                issues.push(format!(
+                    "App rule {} has opacity {} outside 0.0..=1.0",
+                    index, opacity
+                ));
+            }
+
+            if let Some(frame) = rule.floating_frame {
+                let fractions = [Some(frame.width), Some(frame.height), frame.x, frame.y];
+                if !(frame.width > 0.0 && frame.height > 0.0)
+                    || fractions.into_iter().flatten().any(|f| !(0.0..=1.0).contains(&f))
+                {
+                    issues.push(format!(
+                        "App rule {} has a floating_frame with fractions outside 0.0..=1.0",
+                        index
+                    ));
+                }
+            }
+
             if let Some(ref workspace) = rule.workspace {
                 if let WorkspaceSelector::Index(idx) = workspace {
                     if *idx >= self.default_workspace_count {
@@ -235,7 +470,9 @@ impl VirtualWorkspaceSettings {
                     || rule.title_regex.is_some()
                     || rule.title_substring.is_some()
                     || rule.ax_role.is_some()
-                    || rule.ax_subrole.is_some();
+                    || rule.ax_subrole.is_some()
+                    || rule.matcher.is_some()
+                    || rule.bundle_path.is_some();
                 if !app_id.is_empty() && !has_specific_match && !seen_app_ids.insert(app_id) {
                     issues.push(format!("Duplicate app_id '{}' in rule {}", app_id, index));
                 }
@@ -283,6 +520,73 @@ impl VirtualWorkspaceSettings {
             }
         }
 
+        for (uuid, display) in &self.per_display {
+            let count = display.workspace_count(self.default_workspace_count);
+            if count == 0 {
+                issues.push(format!(
+                    "per_display[{uuid}] default_workspace_count must be at least 1"
+                ));
+            }
+            if count > MAX_WORKSPACES {
+                issues.push(format!(
+                    "per_display[{uuid}] default_workspace_count should not exceed {}",
+                    MAX_WORKSPACES
+                ));
+            }
+            if display.workspace_names.as_ref().is_some_and(|names| names.len() > count) {
+                issues.push(format!(
+                    "per_display[{uuid}] has more workspace names than default_workspace_count"
+                ));
+            }
+            for name in display
+                .workspace_names
+                .iter()
+                .flatten()
+                .filter(|name| WorkspaceSelector::is_index_like(name))
+            {
+                issues.push(format!(
+                    "per_display[{uuid}] workspace name {name:?} must not be a number"
+                ));
+            }
+            if let Some(default) = display.default_workspace
+                && default >= count
+            {
+                issues.push(format!(
+                    "per_display[{uuid}] default_workspace ({default}) must be less than default_workspace_count ({count})"
+                ));
+            }
+        }
+
+        for (index, rule) in self.workspace_rules.iter().enumerate() {
+            let Some(template) = &rule.template else { continue };
+            if rule.layout != LayoutMode::Traditional {
+                issues.push(format!(
+                    "Workspace rule {} has a template but templates only apply to the traditional layout",
+                    index
+                ));
+            }
+            if template.slots.is_empty() {
+                issues.push(format!("Workspace rule {} has a template with no slots", index));
+            }
+            for (slot_index, slot) in template.slots.iter().enumerate() {
+                if !slot.ratio.is_finite() || slot.ratio <= 0.0 {
+                    issues.push(format!(
+                        "Workspace rule {} template slot {} ratio must be a positive number",
+                        index, slot_index
+                    ));
+                }
+            }
+        }
+
+        if let Some(script) = &self.script
+            && !script.resolved_path().is_file()
+        {
+            issues.push(format!(
+                "rule script {} does not exist",
+                script.resolved_path().display()
+            ));
+        }
+
         issues
     }
 }
@@ -298,6 +602,13 @@ struct ConfigFile {
     /// e.g., "comb1" = "Alt + Shift" allows using "comb1 + C" in keys
     #[serde(default)]
     modifier_combinations: HashMap<String, String>,
+    /// Modal keymaps entered with `enter_mode = "<name>"`. While a mode is
+    /// active only its bindings are live.
+    #[serde(default)]
+    modes: HashMap<String, HashMap<String, WmCommand>>,
+    /// Named overlays selected at runtime with `rift-cli profile <name>`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    profiles: HashMap<String, toml::Table>,
 }
 
 fn migrate_legacy_resize_bindings(document: &mut toml::Value) -> bool {
@@ -321,6 +632,21 @@ fn migrate_legacy_resize_bindings(document: &mut toml::Value) -> bool {
     migrated
 }
 
+/// Merge `overlay` into `base`: tables are merged key by key, every other
+/// value (including arrays) is replaced.
+fn merge_toml(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_toml(base, overlay)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 fn parse_config_file(buf: &str) -> Result<ConfigFile, toml::de::Error> {
     toml::from_str(buf).or_else(|original_error| {
         let Ok(mut document) = toml::from_str::<toml::Value>(buf) else {
@@ -339,7 +665,14 @@ pub struct Config {
     pub keys: Vec<(Hotkey, WmCommand)>,
     #[serde(default)]
     pub key_specs: Vec<(String, WmCommand)>,
+    /// Normalized key bindings for each modal keymap, by mode name.
+    #[serde(default)]
+    pub modes: HashMap<String, Vec<(String, WmCommand)>>,
     pub virtual_workspaces: VirtualWorkspaceSettings,
+    /// Profiles defined inline in the config file, kept so saving does not
+    /// drop them.
+    #[serde(skip)]
+    pub profiles: HashMap<String, toml::Table>,
 }
 
 impl<'de> Deserialize<'de> for Config {
@@ -351,6 +684,8 @@ impl<'de> Deserialize<'de> for Config {
             keys: Vec<(Hotkey, WmCommand)>,
             #[serde(default)]
             key_specs: Vec<(String, WmCommand)>,
+            #[serde(default)]
+            modes: HashMap<String, Vec<(String, WmCommand)>>,
             virtual_workspaces: VirtualWorkspaceSettings,
         }
 
@@ -369,7 +704,9 @@ impl<'de> Deserialize<'de> for Config {
             settings: config.settings,
             keys: config.keys,
             key_specs,
+            modes: config.modes,
             virtual_workspaces: config.virtual_workspaces,
+            profiles: HashMap::default(),
         })
     }
 }
@@ -388,10 +725,25 @@ pub struct Settings {
     pub animation_fps: f64,
     #[serde(default)]
     pub animation_easing: AnimationEasing,
+    /// Drop animation frames when the system falls behind or many windows
+    /// move at once, instead of letting the animation stutter. When on,
+    /// `animation_fps` is an upper bound.
+    #[serde(default = "no")]
+    pub animation_adaptive: bool,
+    /// How windows move when switching virtual workspaces.
+    #[serde(default)]
+    pub workspace_transition: WorkspaceTransition,
+    /// Bundle identifiers of apps whose windows are moved in one step instead
+    /// of animated, for apps that redraw badly mid-animation.
+    #[serde(default)]
+    pub animation_blacklist: Vec<String>,
     #[serde(default = "yes")]
     pub default_disable: bool,
     #[serde(default = "yes")]
     pub mouse_follows_focus: bool,
+    /// Where and when `mouse_follows_focus` moves the mouse.
+    #[serde(default)]
+    pub mouse_warp: MouseWarpSettings,
     #[serde(default = "yes")]
     pub mouse_hides_on_focus: bool,
     #[serde(default = "yes")]
@@ -400,11 +752,31 @@ pub struct Settings {
     /// Accepts either a full hotkey (e.g. "Ctrl + A") or a modifier-only spec (e.g. "Ctrl")
     #[serde(default)]
     pub focus_follows_mouse_disable_hotkey: Option<HotkeySpec>,
+    /// How long the mouse must rest over a window before it is focused.
+    #[serde(default)]
+    pub focus_follows_mouse_delay_ms: u64,
+    /// When set, focus only follows the mouse while this is held, e.g. "Alt".
+    #[serde(default)]
+    pub focus_follows_mouse_modifier: Option<HotkeySpec>,
+    /// Whether a window focused by the mouse is also raised.
+    #[serde(default)]
+    pub focus_follows_mouse_mode: FocusFollowsMouseMode,
     /// Apps that should not trigger automatic workspace switching when activated.
     /// List of bundle identifiers (e.g., "com.apple.Spotlight") that often
     /// inappropriately steal focus and shouldn't cause workspace switches.
     #[serde(default)]
     pub auto_focus_blacklist: Vec<String>,
+    /// Raise floating windows above tiled ones again after workspace switches
+    /// and wake, since macOS does not keep them on top by itself.
+    #[serde(default = "yes")]
+    pub floating_on_top: bool,
+    /// What rift does while macOS Stage Manager is turned on.
+    #[serde(default)]
+    pub stage_manager: StageManagerMode,
+    /// Built-in rules that keep picture-in-picture players and tool palettes
+    /// out of the layout.
+    #[serde(default)]
+    pub window_heuristics: WindowHeuristicsSettings,
     #[serde(default)]
     pub layout: LayoutSettings,
     #[serde(default)]
@@ -420,10 +792,30 @@ pub struct Settings {
     #[serde(default)]
     pub run_on_start: Vec<String>,
 
+    /// Shell commands run when rift publishes the matching event
+    #[serde(default)]
+    pub hooks: HookSettings,
+
+    /// Optional WebSocket bridge for the IPC protocol
+    #[serde(default)]
+    pub websocket: WebSocketSettings,
+
+    /// Who may send requests over IPC, and what they may do
+    #[serde(default)]
+    pub ipc: IpcSettings,
+
+    /// Structured (JSON) trace output to a rotating file
+    #[serde(default)]
+    pub log_file: LogFileSettings,
+
     /// Whether to reapply app rules when a window title changes.
     /// Enable hot-reloading of the config file when it changes
     #[serde(default = "yes")]
     pub hot_reload: bool,
+
+    /// Set by `rift --safe-mode`; never read from or written to the config file.
+    #[serde(skip)]
+    pub safe_mode: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default, Copy)]
@@ -453,6 +845,49 @@ pub enum AnimationEasing {
     EaseInCirc,
     EaseOutCirc,
     EaseInOutCirc,
+    /// A damped spring, stretched over `animation_duration`. Low damping
+    /// relative to stiffness makes windows overshoot and settle back.
+    Spring {
+        mass: f64,
+        stiffness: f64,
+        damping: f64,
+    },
+    /// A CSS-style `cubic-bezier(x1, y1, x2, y2)` curve.
+    CubicBezier([f64; 4]),
+}
+
+/// Transition played when switching virtual workspaces.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceTransition {
+    /// Windows jump straight to their new positions.
+    #[default]
+    None,
+    /// The outgoing workspace slides off screen while the incoming one slides
+    /// in from the other side, using the animation settings.
+    Slide,
+}
+
+impl AnimationEasing {
+    fn validate(&self) -> Option<String> {
+        match *self {
+            AnimationEasing::Spring { mass, stiffness, damping }
+                if mass <= 0.0 || stiffness <= 0.0 || damping <= 0.0 =>
+            {
+                Some(format!(
+                    "animation_easing spring needs positive mass, stiffness and damping, got {mass}, {stiffness}, {damping}"
+                ))
+            }
+            AnimationEasing::CubicBezier([x1, _, x2, _])
+                if !(0.0..=1.0).contains(&x1) || !(0.0..=1.0).contains(&x2) =>
+            {
+                Some(format!(
+                    "animation_easing cubic_bezier x values must be within 0..=1, got {x1} and {x2}"
+                ))
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
@@ -464,6 +899,8 @@ pub struct UiSettings {
     pub stack_line: StackLineSettings,
     #[serde(default)]
     pub mission_control: MissionControlSettings,
+    #[serde(default)]
+    pub borders: BorderSettings,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -496,6 +933,10 @@ pub struct GestureSettings {
     /// Haptic feedback pattern (generic | alignment | level_change)
     #[serde(default)]
     pub haptic_pattern: HapticPattern,
+    /// Move the workspace with the fingers and switch when they lift, past
+    /// `distance_pct`. Needs `workspace_transition = "slide"`.
+    #[serde(default)]
+    pub preview: bool,
 }
 
 impl Default for GestureSettings {
@@ -510,15 +951,43 @@ impl Default for GestureSettings {
             distance_pct: default_distance_pct(),
             haptics_enabled: true,
             haptic_pattern: HapticPattern::LevelChange,
+            preview: false,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default, Copy)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct WindowSnappingSettings {
     #[serde(default = "default_drag_swap_fraction")]
     pub drag_swap_fraction: f64,
+    /// Milliseconds a dragged window must be held against the left or right
+    /// screen edge before it is carried to the previous or next workspace.
+    /// 0 disables edge switching.
+    #[serde(default)]
+    pub edge_workspace_switch_delay_ms: u64,
+    /// Holding this (e.g. "Alt") while dragging a floating window snaps it to
+    /// the grid and to the edges of other floating windows. Unset disables
+    /// floating snapping.
+    #[serde(default)]
+    pub floating_snap_modifier: Option<HotkeySpec>,
+    /// Size of the invisible snapping grid cells, in points. 0 disables the
+    /// grid, leaving only snapping to other windows.
+    #[serde(default = "default_floating_snap_grid")]
+    pub floating_snap_grid: f64,
+    /// How close, in points, an edge must be to another floating window's edge
+    /// to snap to it.
+    #[serde(default = "default_floating_snap_distance")]
+    pub floating_snap_distance: f64,
+    /// Dragging a floating window over a tile highlights where it would be
+    /// tiled (left half, right half, or the tile's container) and tiles it
+    /// there on drop.
+    #[serde(default)]
+    pub floating_drop_zones: bool,
+    /// While a dragged tiled window is over a swap target, animate the target
+    /// into the dragged window's tile to preview the swap.
+    #[serde(default)]
+    pub drag_swap_preview: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
@@ -560,6 +1029,16 @@ pub struct MenuBarSettings {
     pub display_style: WorkspaceDisplayStyle,
     #[serde(default = "default_layout_folder")]
     pub layout_folder: PathBuf,
+    /// Text drawn for each workspace in the "label" style. Supports `{label}`,
+    /// `{index}`, `{name}`, `{count}` and `{apps}`.
+    #[serde(default = "default_menu_bar_label_format")]
+    pub label_format: String,
+    /// Glyphs used by `{apps}`, keyed by bundle id or app name.
+    #[serde(default)]
+    pub app_glyphs: HashMap<String, String>,
+    /// Glyph used by `{apps}` for apps missing from `app_glyphs`.
+    #[serde(default = "default_menu_bar_app_glyph")]
+    pub default_app_glyph: String,
 }
 
 impl MenuBarSettings {
@@ -582,6 +1061,9 @@ impl Default for MenuBarSettings {
             active_label: ActiveWorkspaceLabel::default(),
             display_style: WorkspaceDisplayStyle::default(),
             layout_folder: default_layout_folder(),
+            label_format: default_menu_bar_label_format(),
+            app_glyphs: HashMap::default(),
+            default_app_glyph: default_menu_bar_app_glyph(),
         }
     }
 }
@@ -593,6 +1075,8 @@ pub struct StackLineSettings {
     pub enabled: bool,
     #[serde(default)]
     pub hover: StackLineHoverMode,
+    #[serde(default)]
+    pub style: StackLineStyle,
     #[serde(default = "default_stack_line_thickness")]
     pub thickness: f64,
     #[serde(default)]
@@ -605,6 +1089,53 @@ pub struct StackLineSettings {
     pub spacing: f64,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MouseWarpSettings {
+    #[serde(default)]
+    pub target: MouseWarpTarget,
+    /// Only warp when the focused window is on a different display than the
+    /// mouse.
+    #[serde(default)]
+    pub only_across_displays: bool,
+    /// Leave the mouse alone when it is already inside the focused window.
+    #[serde(default)]
+    pub skip_if_inside: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseWarpTarget {
+    /// The middle of the window.
+    #[default]
+    Center,
+    /// Where the mouse last was inside the window, or its middle when it has
+    /// not been there yet.
+    LastPosition,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusFollowsMouseMode {
+    /// Focus the window and bring it to the front.
+    #[default]
+    Autoraise,
+    /// Make the window key without changing the stacking order.
+    FocusOnly,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StageManagerMode {
+    /// Leave tiled windows to Stage Manager and only place floating ones.
+    #[default]
+    FloatingOnly,
+    /// Stop moving windows altogether until Stage Manager is turned off.
+    Pause,
+    /// Keep tiling as if Stage Manager were off.
+    Ignore,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum StackLineHoverMode {
@@ -613,6 +1144,17 @@ pub enum StackLineHoverMode {
     Hover,
 }
 
+/// How each member of a stacked container is drawn in the stack line.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StackLineStyle {
+    /// Plain segments, one per window.
+    #[default]
+    Line,
+    /// Segments labelled with the window title, like a tab bar.
+    Tabs,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct MissionControlSettings {
@@ -626,8 +1168,328 @@ pub struct MissionControlSettings {
 
 fn default_mission_control_fade_duration_ms() -> f64 { 180.0 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BorderSettings {
+    #[serde(default = "no")]
+    pub enabled: bool,
+    /// Border thickness (in points), drawn outside the window frame
+    #[serde(default = "default_border_width")]
+    pub width: f64,
+    /// Corner radius of the window the border wraps (in points)
+    #[serde(default = "default_border_radius")]
+    pub radius: f64,
+    /// Color of the focused window's border as `#RRGGBB` or `#RRGGBBAA`
+    #[serde(default = "default_border_active_color")]
+    pub active_color: String,
+    /// Color for unfocused windows; when unset only the focused window gets a border
+    #[serde(default)]
+    pub inactive_color: Option<String>,
+    /// Color marking floating windows kept on top by `toggle_always_on_top`
+    #[serde(default)]
+    pub always_on_top_color: Option<String>,
+    /// Color marking windows that share a stack or tab group with others
+    #[serde(default)]
+    pub group_color: Option<String>,
+    /// Color marking windows of apps that stopped answering accessibility
+    /// requests, which rift leaves alone until they recover
+    #[serde(default)]
+    pub urgent_color: Option<String>,
+}
+
+impl Default for BorderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            width: default_border_width(),
+            radius: default_border_radius(),
+            active_color: default_border_active_color(),
+            inactive_color: None,
+            always_on_top_color: None,
+            group_color: None,
+            urgent_color: None,
+        }
+    }
+}
+
+impl BorderSettings {
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if !self.width.is_finite() || self.width <= 0.0 {
+            issues.push(format!("ui.borders.width must be positive, got {}", self.width));
+        }
+        if !self.radius.is_finite() || self.radius < 0.0 {
+            issues.push(format!(
+                "ui.borders.radius must be non-negative, got {}",
+                self.radius
+            ));
+        }
+        if parse_hex_color(&self.active_color).is_none() {
+            issues.push(format!(
+                "ui.borders.active_color '{}' is not a #RRGGBB or #RRGGBBAA color",
+                self.active_color
+            ));
+        }
+        for (name, color) in [
+            ("inactive_color", &self.inactive_color),
+            ("always_on_top_color", &self.always_on_top_color),
+            ("group_color", &self.group_color),
+            ("urgent_color", &self.urgent_color),
+        ] {
+            if let Some(color) = color
+                && parse_hex_color(color).is_none()
+            {
+                issues.push(format!(
+                    "ui.borders.{name} '{color}' is not a #RRGGBB or #RRGGBBAA color"
+                ));
+            }
+        }
+        issues
+    }
+}
+
+fn default_border_width() -> f64 { 4.0 }
+
+fn default_border_radius() -> f64 { 10.0 }
+
+fn default_border_active_color() -> String { "#3d8fd6".to_string() }
+
+/// Parses `#RRGGBB` / `#RRGGBBAA` into normalized RGBA components.
+pub fn parse_hex_color(value: &str) -> Option<[f64; 4]> {
+    let hex = value.strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some([channel(0)?, channel(2)?, channel(4)?, alpha].map(|c| c as f64 / 255.0))
+}
+
+/// Commands executed with `sh -c` for published events, keyed by event name (any of
+/// `BroadcastEvent::NAMES`, e.g. `workspace_changed`). The command receives the same
+/// `RIFT_*` environment variables as `rift-cli subscribe cli` subscriptions.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(transparent)]
+pub struct HookSettings(HashMap<String, Hook>);
+
+/// A hook command, optionally limited to events about windows a matcher
+/// expression selects, e.g. `{ command = "...", match = 'app:"Safari"' }`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum Hook {
+    Command(String),
+    Matching {
+        command: String,
+        #[serde(rename = "match")]
+        matcher: WindowMatcher,
+    },
+}
+
+impl Hook {
+    pub fn command(&self) -> &str {
+        match self {
+            Hook::Command(command) | Hook::Matching { command, .. } => command,
+        }
+    }
+
+    pub fn matcher(&self) -> Option<&WindowMatcher> {
+        match self {
+            Hook::Command(_) => None,
+            Hook::Matching { matcher, .. } => Some(matcher),
+        }
+    }
+}
+
+impl HookSettings {
+    /// The hook configured for a broadcast event name.
+    pub fn hook_for(&self, event_name: &str) -> Option<&Hook> {
+        self.0.get(event_name).filter(|hook| !hook.command().trim().is_empty())
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort_by_key(|(name, _)| name.as_str());
+        entries
+            .into_iter()
+            .filter_map(|(name, hook)| {
+                if !BroadcastEvent::NAMES.contains(&name.as_str()) {
+                    Some(format!(
+                        "hooks.{name} is not an event; expected one of {}",
+                        BroadcastEvent::NAMES.join(", ")
+                    ))
+                } else if hook.matcher().is_some()
+                    && !BroadcastEvent::WINDOW_EVENT_NAMES.contains(&name.as_str())
+                {
+                    Some(format!(
+                        "hooks.{name} has a match expression, but only {} concern a window",
+                        BroadcastEvent::WINDOW_EVENT_NAMES.join(", ")
+                    ))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Picture-in-picture players, inspectors and tool palettes are never managed.
+/// Run `rift-cli query ignored` to see which windows are left alone and why.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WindowHeuristicsSettings {
+    #[serde(default = "yes")]
+    pub enabled: bool,
+    /// Windows that cannot be resized and are narrower or shorter than this
+    /// are treated as tool palettes.
+    #[serde(default = "default_palette_max_width")]
+    pub palette_max_width: f64,
+    #[serde(default = "default_palette_max_height")]
+    pub palette_max_height: f64,
+    /// Bundle identifiers whose windows are only subject to app rules.
+    #[serde(default)]
+    pub exempt_apps: Vec<String>,
+}
+
+impl Default for WindowHeuristicsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            palette_max_width: default_palette_max_width(),
+            palette_max_height: default_palette_max_height(),
+            exempt_apps: Vec::new(),
+        }
+    }
+}
+
+fn default_palette_max_width() -> f64 { 320.0 }
+
+fn default_palette_max_height() -> f64 { 240.0 }
+
+/// A localhost WebSocket server speaking the same JSON requests and responses
+/// as `rift-cli`, for browser dashboards and widgets. Read once at startup.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketSettings {
+    #[serde(default = "no")]
+    pub enabled: bool,
+    #[serde(default = "default_websocket_port")]
+    pub port: u16,
+    /// Only answer queries and subscriptions, never run commands.
+    #[serde(default = "no")]
+    pub read_only: bool,
+    /// Browser origins (e.g. `http://localhost:3000`) allowed to connect.
+    /// Browsers let any page open a connection to localhost, so handshakes
+    /// carrying an `Origin` header not listed here are refused. Clients that
+    /// send no `Origin`, such as scripts, are unaffected.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for WebSocketSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_websocket_port(),
+            read_only: false,
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+/// Access control for the Mach and WebSocket IPC servers. Everything is
+/// allowed by default. Read once at startup.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct IpcSettings {
+    /// Reject Mach clients running as another user, unless listed in
+    /// `allowed_processes`. The WebSocket bridge is not started while this is
+    /// set.
+    #[serde(default = "no")]
+    pub same_user_only: bool,
+    /// Absolute executable paths accepted despite `same_user_only`. Bare
+    /// names are not accepted, since any binary can be given one.
+    #[serde(default)]
+    pub allowed_processes: Vec<String>,
+    /// When set, every request must carry this token (see `RIFT_IPC_TOKEN`
+    /// for `rift-cli`).
+    #[serde(default)]
+    pub token: Option<String>,
+    /// A second token that only allows queries and subscriptions. Only
+    /// meaningful together with `token`.
+    #[serde(default)]
+    pub read_only_token: Option<String>,
+}
+
+impl IpcSettings {
+    /// A copy safe to show to IPC clients. Set tokens are replaced by a
+    /// placeholder, otherwise a read-only client could read the full-access
+    /// token from the config.
+    pub fn redacted(&self) -> Self {
+        let redact = |token: &Option<String>| token.as_ref().map(|_| "<redacted>".to_string());
+        Self {
+            token: redact(&self.token),
+            read_only_token: redact(&self.read_only_token),
+            ..self.clone()
+        }
+    }
+
+    /// Environment for the commands rift spawns itself (`run_on_start`,
+    /// hooks and `exec` bindings), so they can reach a server that requires
+    /// a token.
+    pub fn spawn_env(&self) -> Vec<(String, String)> {
+        self.token.iter().map(|token| ("RIFT_IPC_TOKEN".to_string(), token.clone())).collect()
+    }
+}
+
+/// Where JSON trace output goes besides stderr. Which events are written
+/// follows `RUST_LOG`, `filter` and `rift-cli log-level`. Read once at
+/// startup.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LogFileSettings {
+    /// File to write, e.g. `~/.rift/logs/rift.log`. Rotated files get the
+    /// period appended. Nothing is written when unset.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    #[serde(default)]
+    pub rotation: LogRotation,
+    /// Rotated files to keep; older ones are deleted. 0 keeps them all.
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
+    /// `RUST_LOG`-style directives added at startup, e.g.
+    /// `"rift_wm::actor::reactor=debug"`.
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+impl Default for LogFileSettings {
+    fn default() -> Self {
+        Self {
+            path: None,
+            rotation: LogRotation::default(),
+            max_files: default_log_max_files(),
+            filter: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRotation {
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+fn default_log_max_files() -> usize { 7 }
+
 fn default_drag_swap_fraction() -> f64 { 0.3 }
 
+fn default_floating_snap_grid() -> f64 { 40.0 }
+
+fn default_floating_snap_distance() -> f64 { 12.0 }
+
 fn default_master_stack_ratio() -> f64 { 0.6 }
 
 fn default_master_stack_count() -> usize { 1 }
@@ -638,6 +1500,8 @@ fn default_scrolling_min_column_width_ratio() -> f64 { 0.3 }
 
 fn default_scrolling_max_column_width_ratio() -> f64 { 0.9 }
 
+fn default_scrolling_column_width_presets() -> Vec<f64> { vec![1.0 / 3.0, 0.5, 2.0 / 3.0] }
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum HorizontalPlacement {
@@ -654,28 +1518,185 @@ pub enum VerticalPlacement {
     Right,
 }
 
-impl StackLineSettings {
-    pub fn thickness(&self) -> f64 { if self.enabled { self.thickness } else { 0.0 } }
+impl StackLineSettings {
+    pub fn thickness(&self) -> f64 { if self.enabled { self.thickness } else { 0.0 } }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutSettings {
+    /// Layout mode: "traditional", "bsp", "stack", "master_stack", or "scrolling"
+    #[serde(default)]
+    pub mode: LayoutMode,
+    /// Traditional layout configuration
+    #[serde(default)]
+    pub traditional: TraditionalSettings,
+    /// Stack system configuration
+    #[serde(default)]
+    pub stack: StackSettings,
+    /// Master/stack layout configuration
+    #[serde(default)]
+    pub master_stack: MasterStackSettings,
+    /// Gap configuration for window spacing
+    #[serde(default)]
+    pub gaps: GapSettings,
+    /// Scrolling layout configuration (niri-style columns)
+    #[serde(default)]
+    pub scrolling: ScrollingLayoutSettings,
+    /// Float small or transient windows when they first appear
+    #[serde(default)]
+    pub auto_float: AutoFloatSettings,
+    /// Let windows launched from a terminal take over the terminal's tile
+    #[serde(default)]
+    pub swallow: SwallowSettings,
+    /// How the windows next to a closed one take over its space
+    #[serde(default)]
+    pub close_resize: CloseResize,
+    /// Float every managed window instead of tiling it. Only set by safe mode.
+    #[serde(skip)]
+    pub float_all_windows: bool,
+}
+
+/// Decides which newly appearing windows start out floating, centered over
+/// their app's main window, instead of being tiled. App rules take precedence.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AutoFloatSettings {
+    /// Float windows that open narrower than `width` and shorter than `height`
+    /// (in points). Either set to 0 disables the size check.
+    #[serde(default)]
+    pub width: f64,
+    #[serde(default)]
+    pub height: f64,
+    /// Float windows whose accessibility role marks them as dialogs, floating
+    /// panels, or popovers.
+    #[serde(default)]
+    pub transient_roles: bool,
+}
+
+impl AutoFloatSettings {
+    pub fn should_float(
+        &self,
+        width: f64,
+        height: f64,
+        ax_role: Option<&str>,
+        ax_subrole: Option<&str>,
+    ) -> bool {
+        let transient = matches!(ax_role, Some("AXPopover"))
+            || matches!(
+                ax_subrole,
+                Some("AXDialog" | "AXSystemDialog" | "AXFloatingWindow")
+            );
+        if self.transient_roles && transient {
+            return true;
+        }
+        self.width > 0.0 && self.height > 0.0 && width < self.width && height < self.height
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.width < 0.0 || self.height < 0.0 {
+            issues.push(format!(
+                "layout.auto_float width and height must be non-negative, got {}x{}",
+                self.width, self.height
+            ));
+        }
+        issues
+    }
+}
+
+/// Window swallowing: when a listed app (typically a terminal) is an ancestor
+/// process of a newly opened window, that window replaces the app's window in
+/// the layout until it closes. Programs started through `open` or Launch
+/// Services are children of launchd rather than the terminal, so their
+/// windows are never swallowed.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SwallowSettings {
+    /// Bundle identifiers or app names whose windows can be swallowed.
+    #[serde(default)]
+    pub apps: Vec<String>,
+}
+
+impl SwallowSettings {
+    pub fn swallows(&self, bundle_id: Option<&str>, app_name: Option<&str>) -> bool {
+        self.apps.iter().any(|app| {
+            bundle_id.is_some_and(|id| id.eq_ignore_ascii_case(app)) || app_name == Some(app)
+        })
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.apps.iter().any(|app| app.trim().is_empty()) {
+            issues.push("layout.swallow.apps must not contain empty entries".to_string());
+        }
+        issues
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
 #[serde(deny_unknown_fields)]
-pub struct LayoutSettings {
-    /// Layout mode: "traditional", "bsp", "stack", "master_stack", or "scrolling"
-    #[serde(default)]
-    pub mode: LayoutMode,
-    /// Stack system configuration
-    #[serde(default)]
-    pub stack: StackSettings,
-    /// Master/stack layout configuration
-    #[serde(default)]
-    pub master_stack: MasterStackSettings,
-    /// Gap configuration for window spacing
+pub struct TraditionalSettings {
+    /// Where a newly opened window is placed in the tree
     #[serde(default)]
-    pub gaps: GapSettings,
-    /// Scrolling layout configuration (niri-style columns)
+    pub insertion_policy: InsertionPolicy,
+    /// Most windows a container holds before new windows overflow into a
+    /// nested container (0 = no limit)
+    #[serde(default = "default_max_windows_per_container")]
+    pub max_windows_per_container: usize,
+    /// What a full container overflows into
     #[serde(default)]
-    pub scrolling: ScrollingLayoutSettings,
+    pub container_overflow: ContainerOverflow,
+}
+
+/// What a window opened next to the selection goes into once the
+/// selection's container is full.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerOverflow {
+    /// A new container, tiled the same way, holding the selection and the
+    /// new window.
+    #[default]
+    Nest,
+    /// A new stack holding the selection and the new window.
+    Stack,
+}
+
+/// Where the traditional layout puts a newly opened window.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InsertionPolicy {
+    /// Next to the selection, overflowing once its container holds
+    /// `max_windows_per_container` windows.
+    #[default]
+    AfterSelection,
+    /// At the end of the selection's container.
+    EndOfParent,
+    /// Split the selection's tile across its container, so windows spiral
+    /// inwards.
+    Spiral,
+    /// Split the largest tile, wherever the selection is.
+    LargestArea,
+}
+
+/// How a closed window's space is shared out in the traditional and bsp
+/// layouts.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseResize {
+    /// The layout's own behavior: the traditional layout grows the rest of
+    /// the container in proportion to their sizes, and bsp gives the closed
+    /// window's split to its sibling.
+    #[default]
+    Keep,
+    /// The window next to the closed one takes its whole tile and the other
+    /// windows keep their sizes.
+    Neighbor,
+    /// The windows of the container it was closed in get equal sizes.
+    Equalize,
+    /// The windows of the container it was closed in grow in proportion to
+    /// their sizes.
+    Proportional,
 }
 
 /// Layout mode enum
@@ -722,6 +1743,10 @@ pub struct ScrollingLayoutSettings {
     /// Maximum column width ratio allowed by resize commands.
     #[serde(default = "default_scrolling_max_column_width_ratio")]
     pub max_column_width_ratio: f64,
+    /// Column widths, as fractions of the screen width, that
+    /// `cycle_column_width` steps through in order.
+    #[serde(default = "default_scrolling_column_width_presets")]
+    pub column_width_presets: Vec<f64>,
     /// Alignment for the focused column (left, center, right).
     #[serde(default)]
     pub alignment: ScrollingAlignment,
@@ -742,6 +1767,7 @@ impl Default for ScrollingLayoutSettings {
             column_width_ratio: default_scrolling_column_width_ratio(),
             min_column_width_ratio: default_scrolling_min_column_width_ratio(),
             max_column_width_ratio: default_scrolling_max_column_width_ratio(),
+            column_width_presets: default_scrolling_column_width_presets(),
             alignment: ScrollingAlignment::default(),
             focus_navigation_style: ScrollingFocusNavigationStyle::default(),
             gestures: ScrollingGestureSettings::default(),
@@ -807,7 +1833,7 @@ pub enum MasterStackNewWindowPlacement {
     Focused,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct ScrollingGestureSettings {
     /// Enable horizontal scroll gestures to switch columns
@@ -831,6 +1857,11 @@ pub struct ScrollingGestureSettings {
     /// Amount of overscroll (in steps) required to trigger a workspace switch
     #[serde(default = "default_overscroll_threshold")]
     pub workspace_switch_threshold: f64,
+    /// While this modifier is held (e.g. "Alt"), ordinary two-finger
+    /// trackpad scrolling pans the strip, with momentum, instead of
+    /// scrolling the window under the cursor.
+    #[serde(default)]
+    pub pan_modifier: Option<HotkeySpec>,
 }
 
 impl Default for ScrollingGestureSettings {
@@ -843,6 +1874,7 @@ impl Default for ScrollingGestureSettings {
             distance_pct: default_distance_pct(),
             propagate_to_workspace_swipe: false,
             workspace_switch_threshold: default_overscroll_threshold(),
+            pan_modifier: None,
         }
     }
 }
@@ -885,9 +1917,12 @@ pub struct GapSettings {
     /// Inner gaps (space between windows)
     #[serde(default)]
     pub inner: InnerGaps,
-    /// Display-specific gap overrides keyed by display UUID
-    #[serde(default)]
+    /// Display-specific gap overrides keyed by display UUID or name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub per_display: HashMap<String, GapOverride>,
+    /// Drop inner and outer gaps while a workspace has a single tiled window
+    #[serde(default)]
+    pub smart_gaps: bool,
 }
 
 /// Outer gap configuration (space between windows and screen edges)
@@ -932,6 +1967,64 @@ pub struct GapOverride {
     pub inner: Option<InnerGaps>,
 }
 
+/// Which gaps a [`GapAdjustment`] changes.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum GapScope {
+    Inner,
+    Outer,
+}
+
+/// A runtime gap change, written `outer+10`, `inner-5` or `inner=0`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub struct GapAdjustment {
+    pub scope: GapScope,
+    /// Amount added to every gap in the scope, or the value every gap is set
+    /// to when `absolute` is set.
+    pub value: f64,
+    #[serde(default)]
+    pub absolute: bool,
+}
+
+impl FromStr for GapAdjustment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(['+', '-', '='])
+            .ok_or_else(|| format!("expected e.g. `outer+10` or `inner=0`, got `{s}`"))?;
+        let scope = match s[..split].trim() {
+            "inner" => GapScope::Inner,
+            "outer" => GapScope::Outer,
+            other => return Err(format!("unknown gap scope `{other}`, expected inner or outer")),
+        };
+        let amount: f64 = s[split + 1..]
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid gap amount in `{s}`"))?;
+        if !amount.is_finite() {
+            return Err(format!("invalid gap amount in `{s}`"));
+        }
+        let (value, absolute) = match &s[split..split + 1] {
+            "+" => (amount, false),
+            "-" => (-amount, false),
+            _ => (amount, true),
+        };
+        Ok(Self { scope, value, absolute })
+    }
+}
+
+impl Default for TraditionalSettings {
+    fn default() -> Self {
+        Self {
+            insertion_policy: InsertionPolicy::default(),
+            max_windows_per_container: default_max_windows_per_container(),
+            container_overflow: ContainerOverflow::default(),
+        }
+    }
+}
+
 impl Default for StackSettings {
     fn default() -> Self {
         Self {
@@ -955,6 +2048,10 @@ impl Default for MasterStackSettings {
 }
 
 impl Settings {
+    pub fn is_animation_blacklisted(&self, bundle_id: Option<&str>) -> bool {
+        bundle_id.is_some_and(|id| self.animation_blacklist.iter().any(|app| app == id))
+    }
+
     pub fn validate(&self) -> Vec<String> {
         let mut issues = Vec::new();
 
@@ -972,7 +2069,11 @@ impl Settings {
             ));
         }
 
+        issues.extend(self.animation_easing.validate());
+
         issues.extend(self.layout.validate());
+        issues.extend(self.ui.borders.validate());
+        issues.extend(self.hooks.validate());
 
         if self.gestures.swipe_vertical_tolerance < 0.0 {
             issues.push(format!(
@@ -981,6 +2082,22 @@ impl Settings {
             ));
         }
 
+        if self.ipc.read_only_token.is_some() && self.ipc.token.is_none() {
+            issues.push(
+                "ipc.read_only_token has no effect without ipc.token; every client still gets \
+                 full access"
+                    .to_string(),
+            );
+        }
+
+        for process in &self.ipc.allowed_processes {
+            if !Path::new(process).is_absolute() {
+                issues.push(format!(
+                    "ipc.allowed_processes entry '{process}' must be an absolute executable path"
+                ));
+            }
+        }
+
         issues
     }
 }
@@ -997,6 +2114,10 @@ impl LayoutSettings {
 
         issues.extend(self.scrolling.validate());
 
+        issues.extend(self.auto_float.validate());
+
+        issues.extend(self.swallow.validate());
+
         issues
     }
 }
@@ -1042,6 +2163,14 @@ impl ScrollingLayoutSettings {
             ));
         }
 
+        for preset in &self.column_width_presets {
+            if !(*preset > 0.0 && *preset <= 1.0) {
+                issues.push(format!(
+                    "layout.scrolling.column_width_presets entries must be in (0.0, 1.0], got {preset}"
+                ));
+            }
+        }
+
         if self.gestures.vertical_tolerance < 0.0 {
             issues.push(format!(
                 "layout.scrolling.gestures.vertical_tolerance must be non-negative, got {}",
@@ -1113,14 +2242,23 @@ impl GapSettings {
         issues
     }
 
-    pub fn effective_for_display(&self, display_uuid: Option<&str>) -> GapSettings {
+    /// The gaps for the display with `display_uuid`, whose localized name is
+    /// `display_name`, with its `per_display` override applied.
+    pub fn effective_for_display(
+        &self,
+        display_uuid: Option<&str>,
+        display_name: Option<&str>,
+    ) -> GapSettings {
         let mut resolved = GapSettings {
             outer: self.outer.clone(),
             inner: self.inner.clone(),
             per_display: HashMap::default(),
+            smart_gaps: self.smart_gaps,
         };
         if let Some(uuid) = display_uuid {
-            if let Some(overrides) = self.per_display.get(uuid) {
+            if let Some(overrides) =
+                crate::sys::screen::lookup_display(&self.per_display, uuid, display_name)
+            {
                 if let Some(outer_override) = &overrides.outer {
                     resolved.outer = outer_override.clone();
                 }
@@ -1131,9 +2269,71 @@ impl GapSettings {
         }
         resolved
     }
+
+    /// Applies `adjustment` to the global gaps, or to the override for the
+    /// display with `display_uuid`, which starts from the global gaps when the
+    /// display has none yet. An override written under the display's name is
+    /// moved to its UUID, so the display never ends up with two entries. Gaps
+    /// never go below zero.
+    pub fn adjust(
+        &mut self,
+        adjustment: GapAdjustment,
+        display_uuid: Option<&str>,
+        display_name: Option<&str>,
+    ) {
+        let Some(uuid) = display_uuid else {
+            match adjustment.scope {
+                GapScope::Outer => self.outer.adjust(adjustment),
+                GapScope::Inner => self.inner.adjust(adjustment),
+            }
+            return;
+        };
+        let key = self.per_display.keys().find(|key| key.eq_ignore_ascii_case(uuid)).cloned();
+        let overrides = match key {
+            Some(key) => self.per_display.entry(key).or_default(),
+            None => {
+                let by_name = display_name
+                    .and_then(|name| self.per_display.remove(name))
+                    .unwrap_or_default();
+                self.per_display.entry(uuid.to_string()).or_insert(by_name)
+            }
+        };
+        match adjustment.scope {
+            GapScope::Outer => {
+                overrides.outer.get_or_insert_with(|| self.outer.clone()).adjust(adjustment)
+            }
+            GapScope::Inner => {
+                overrides.inner.get_or_insert_with(|| self.inner.clone()).adjust(adjustment)
+            }
+        }
+    }
+
+    /// The gaps to lay out `tiled_windows` windows with: none at all for a lone
+    /// window when `smart_gaps` is set, otherwise these gaps unchanged.
+    pub fn for_tiled_window_count(&self, tiled_windows: usize) -> Cow<'_, GapSettings> {
+        if self.smart_gaps && tiled_windows == 1 {
+            Cow::Owned(GapSettings {
+                smart_gaps: true,
+                ..GapSettings::default()
+            })
+        } else {
+            Cow::Borrowed(self)
+        }
+    }
 }
 
 impl OuterGaps {
+    fn adjust(&mut self, adjustment: GapAdjustment) {
+        for gap in [
+            &mut self.top,
+            &mut self.left,
+            &mut self.bottom,
+            &mut self.right,
+        ] {
+            *gap = adjusted_gap(*gap, adjustment);
+        }
+    }
+
     /// Validates outer gap configuration values and returns a list of issues found.
     pub fn validate(&self) -> Vec<String> {
         let mut issues = Vec::new();
@@ -1165,6 +2365,12 @@ impl OuterGaps {
 }
 
 impl InnerGaps {
+    fn adjust(&mut self, adjustment: GapAdjustment) {
+        for gap in [&mut self.horizontal, &mut self.vertical] {
+            *gap = adjusted_gap(*gap, adjustment);
+        }
+    }
+
     /// Validates inner gap configuration values and returns a list of issues found.
     pub fn validate(&self) -> Vec<String> {
         let mut issues = Vec::new();
@@ -1187,10 +2393,21 @@ impl InnerGaps {
     }
 }
 
+fn adjusted_gap(gap: f64, adjustment: GapAdjustment) -> f64 {
+    let gap = if adjustment.absolute {
+        adjustment.value
+    } else {
+        gap + adjustment.value
+    };
+    gap.max(0.0)
+}
+
 fn yes() -> bool { true }
 
 fn default_stack_offset() -> f64 { 40.0 }
 
+fn default_max_windows_per_container() -> usize { 4 }
+
 pub fn default_stack_orientation() -> StackDefaultOrientation {
     StackDefaultOrientation::Perpendicular
 }
@@ -1203,11 +2420,17 @@ fn default_animation_duration() -> f64 { 0.3 }
 
 fn default_animation_fps() -> f64 { 100.0 }
 
+fn default_websocket_port() -> u16 { 8418 }
+
 #[allow(dead_code)]
 pub fn no() -> bool { false }
 
 fn default_layout_folder() -> PathBuf { PathBuf::from("~/.config/rift/layouts") }
 
+fn default_menu_bar_label_format() -> String { "{label}".to_string() }
+
+fn default_menu_bar_app_glyph() -> String { "•".to_string() }
+
 fn default_workspace_count() -> usize { 4 }
 
 fn default_workspace_names() -> Vec<String> {
@@ -1239,13 +2462,70 @@ pub enum HapticPattern {
 }
 
 impl Config {
+    /// The config as returned by `GetConfig`, without IPC tokens.
+    pub fn redacted(&self) -> Config {
+        let mut config = self.clone();
+        config.settings.ipc = config.settings.ipc.redacted();
+        config
+    }
+
     pub fn read(path: &Path) -> anyhow::Result<Config> {
         let buf = std::fs::read_to_string(path)?;
         Self::parse(&buf)
     }
 
+    /// Read the config at `path` with `profile` applied on top of it. A
+    /// profile is a partial config: its tables are merged into the base file
+    /// and any other value replaces the base one. It is looked up under
+    /// `[profiles.<name>]` first and then in [`profile_file`].
+    pub fn read_with_profile(path: &Path, profile: Option<&str>) -> anyhow::Result<Config> {
+        let buf = std::fs::read_to_string(path)?;
+        let Some(name) = profile else {
+            return Self::parse(&buf);
+        };
+
+        let mut document: toml::Table = toml::from_str(&buf)?;
+        let inline = document.get("profiles").and_then(|profiles| profiles.get(name)).cloned();
+        let mut overlay = match inline {
+            Some(toml::Value::Table(overlay)) => overlay,
+            Some(_) => bail!("profile `{name}` must be a table"),
+            None => match profile_file(path, name) {
+                Some(file) if file.exists() => toml::from_str(&std::fs::read_to_string(file)?)?,
+                _ => bail!("unknown profile `{name}`"),
+            },
+        };
+        overlay.remove("profiles");
+        merge_toml(&mut document, overlay);
+
+        Self::parse(&toml::to_string(&document)?)
+    }
+
+    /// This config with every setting that differs between `old` and `new`
+    /// taken from `new`. Used to carry runtime changes made on top of a
+    /// profile over to the base config without the profile itself.
+    pub fn with_changes(&self, old: &Config, new: &Config) -> anyhow::Result<Config> {
+        let mut merged = serde_json::to_value(self)?;
+        apply_value_changes(&mut merged, &serde_json::to_value(old)?, &serde_json::to_value(new)?);
+        let mut config: Config = serde_json::from_value(merged)?;
+        config.profiles = self.profiles.clone();
+        Ok(config)
+    }
+
     pub fn default() -> Config { Self::parse(include_str!("../../rift.default.toml")).unwrap() }
 
+    /// Override everything that moves windows on its own so a bad config can be
+    /// fixed from a running instance: animations, focus-follows-mouse, automatic
+    /// workspace switching and drag-swap are turned off, windows float, and
+    /// startup commands and hooks are dropped. Reloads keep these overrides.
+    pub fn apply_safe_mode(&mut self) {
+        self.settings.safe_mode = true;
+        self.settings.animate = false;
+        self.settings.focus_follows_mouse = false;
+        self.settings.layout.float_all_windows = true;
+        self.settings.run_on_start.clear();
+        self.settings.hooks = HookSettings::default();
+    }
+
     /// Save the current config to a file
     pub fn save(&self, path: &Path) -> anyhow::Result<()> {
         let config_file = ConfigFile {
@@ -1257,6 +2537,12 @@ impl Config {
                 .collect(),
             virtual_workspaces: self.virtual_workspaces.clone(),
             modifier_combinations: HashMap::default(),
+            modes: self
+                .modes
+                .iter()
+                .map(|(mode, bindings)| (mode.clone(), bindings.iter().cloned().collect()))
+                .collect(),
+            profiles: self.profiles.clone(),
         };
 
         let toml_string = toml::to_string_pretty(&config_file)?;
@@ -1280,6 +2566,19 @@ impl Config {
         // Validate virtual workspace settings
         issues.extend(self.virtual_workspaces.validate());
 
+        let bindings = self.keys.iter().map(|(hotkey, command)| (hotkey.to_string(), command));
+        let mode_bindings = self.modes.iter().flat_map(|(mode, keys)| {
+            keys.iter().map(move |(spec, command)| (format!("{spec} in mode `{mode}`"), command))
+        });
+        for (binding, command) in bindings.chain(mode_bindings) {
+            if let WmCommand::ReactorCommand(crate::actor::reactor::Command::Layout(command)) =
+                command
+                && command.split_ratio().is_some_and(|ratio| !ratio.is_finite())
+            {
+                issues.push(format!("Key binding {binding} has a ratio that is not a number"));
+            }
+        }
+
         issues
     }
 
@@ -1495,28 +2794,43 @@ impl Config {
         None
     }
 
+    fn parse_key_table(
+        table: HashMap<String, WmCommand>,
+        modifier_combinations: &HashMap<String, String>,
+    ) -> anyhow::Result<(Vec<(Hotkey, WmCommand)>, Vec<(String, WmCommand)>)> {
+        let mut keys = Vec::new();
+        let mut key_specs = Vec::new();
+        for (key, cmd) in table {
+            let expanded_key = Self::expand_modifier_combinations(&key, modifier_combinations);
+            let normalized_key = Self::normalize_hotkey_string(&expanded_key);
+            let Ok(hotkey) = Hotkey::from_str(&normalized_key) else {
+                bail!("Could not parse hotkey: {key}");
+            };
+            keys.push((hotkey, cmd.clone()));
+            key_specs.push((normalized_key, cmd));
+        }
+        Ok((keys, key_specs))
+    }
+
     fn parse(buf: &str) -> anyhow::Result<Config> {
         // Attempt to deserialize. If it fails, and the error indicates an unknown enum
         // variant, attempt to provide a helpful suggestion.
         match parse_config_file(buf) {
             Ok(c) => {
-                let mut keys = Vec::new();
-                let mut key_specs = Vec::new();
-                for (key, cmd) in c.keys {
-                    let expanded_key =
-                        Self::expand_modifier_combinations(&key, &c.modifier_combinations);
-                    let normalized_key = Self::normalize_hotkey_string(&expanded_key);
-                    let Ok(hotkey) = Hotkey::from_str(&normalized_key) else {
-                        bail!("Could not parse hotkey: {key}");
-                    };
-                    keys.push((hotkey, cmd.clone()));
-                    key_specs.push((normalized_key, cmd));
+                let (keys, key_specs) = Self::parse_key_table(c.keys, &c.modifier_combinations)?;
+                let mut modes = HashMap::default();
+                for (mode, bindings) in c.modes {
+                    let (_, specs) = Self::parse_key_table(bindings, &c.modifier_combinations)
+                        .map_err(|err| anyhow::anyhow!("{err} (in mode `{mode}`)"))?;
+                    modes.insert(mode, specs);
                 }
                 Ok(Config {
                     settings: c.settings,
                     keys,
                     key_specs,
+                    modes,
                     virtual_workspaces: c.virtual_workspaces,
+                    profiles: c.profiles,
                 })
             }
             Err(e) => {
@@ -1546,12 +2860,247 @@ impl Config {
     }
 }
 
+/// Settings that differ between two configs, as dotted paths such as
+/// `settings.layout.gaps.inner.horizontal`. Lists are compared as a whole.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    pub changed: Vec<String>,
+}
+
+impl ConfigDiff {
+    pub fn between(old: &Config, new: &Config) -> Self {
+        let mut changed = Vec::new();
+        match (serde_json::to_value(old), serde_json::to_value(new)) {
+            (Ok(old), Ok(new)) => diff_values(&old, &new, String::new(), &mut changed),
+            _ => changed.push(String::new()),
+        }
+        Self { changed }
+    }
+
+    pub fn is_empty(&self) -> bool { self.changed.is_empty() }
+
+    /// Whether a setting at or under any of `prefixes` changed.
+    pub fn touches(&self, prefixes: &[&str]) -> bool {
+        self.changed.iter().any(|path| {
+            prefixes.iter().any(|prefix| {
+                path.is_empty()
+                    || path == prefix
+                    || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.'))
+                    || prefix.strip_prefix(path.as_str()).is_some_and(|rest| rest.starts_with('.'))
+            })
+        })
+    }
+}
+
+fn diff_values(old: &Value, new: &Value, path: String, changed: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff_values(old, new, child, changed),
+                    _ => changed.push(child),
+                }
+            }
+        }
+        _ if old != new => changed.push(path),
+        _ => {}
+    }
+}
+
+fn apply_value_changes(base: &mut Value, old: &Value, new: &Value) {
+    match (base, old, new) {
+        (Value::Object(base), Value::Object(old), Value::Object(new)) => {
+            for key in old.keys().filter(|key| !new.contains_key(*key)) {
+                base.remove(key);
+            }
+            for (key, new) in new {
+                match (base.get_mut(key), old.get(key)) {
+                    (Some(base), Some(old)) => apply_value_changes(base, old, new),
+                    (_, old) if old != Some(new) => {
+                        base.insert(key.clone(), new.clone());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (base, old, new) if old != new => *base = new.clone(),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::actor::reactor;
     use crate::layout_engine::{LayoutCommand, ResizeOrientation};
 
+    #[test]
+    fn gap_adjustments_apply_globally_or_per_display() {
+        let adjustment = |s: &str| s.parse::<GapAdjustment>().unwrap();
+        assert_eq!(adjustment("outer+10"), GapAdjustment {
+            scope: GapScope::Outer,
+            value: 10.0,
+            absolute: false,
+        });
+        assert!("middle+1".parse::<GapAdjustment>().is_err());
+        assert!("inner=wide".parse::<GapAdjustment>().is_err());
+
+        let mut gaps = GapSettings::default();
+        gaps.inner.horizontal = 8.0;
+        gaps.adjust(adjustment("inner-10"), None, None);
+        assert_eq!(gaps.inner.horizontal, 0.0);
+        gaps.adjust(adjustment("outer=4"), None, None);
+        gaps.adjust(adjustment("outer+6"), Some("UUID-1"), Some("DELL U2720Q"));
+
+        assert_eq!(gaps.outer.top, 4.0);
+        let display = gaps.effective_for_display(Some("UUID-1"), Some("DELL U2720Q"));
+        assert_eq!(display.outer.top, 10.0);
+        assert_eq!(display.inner, gaps.inner);
+    }
+
+    #[test]
+    fn adjusting_a_display_moves_its_name_override_to_the_uuid() {
+        let mut gaps = GapSettings::default();
+        gaps.per_display.insert(
+            "DELL U2720Q".into(),
+            GapOverride {
+                outer: Some(OuterGaps {
+                    top: 20.0,
+                    ..OuterGaps::default()
+                }),
+                inner: None,
+            },
+        );
+
+        gaps.adjust("outer+5".parse().unwrap(), Some("UUID-1"), Some("DELL U2720Q"));
+
+        assert_eq!(gaps.per_display.len(), 1);
+        assert_eq!(gaps.per_display["UUID-1"].outer.as_ref().unwrap().top, 25.0);
+    }
+
+    #[test]
+    fn ipc_tokens_are_redacted_and_read_only_token_needs_token() {
+        let mut config = Config::default();
+        config.settings.ipc.token = Some("full-secret".into());
+        config.settings.ipc.read_only_token = Some("status-secret".into());
+        assert!(config.settings.validate().is_empty());
+
+        let json = serde_json::to_string(&config.redacted()).unwrap();
+        assert!(!json.contains("full-secret"));
+        assert!(!json.contains("status-secret"));
+        assert_eq!(
+            config.redacted().settings.ipc.token.as_deref(),
+            Some("<redacted>")
+        );
+        assert_eq!(config.settings.ipc.token.as_deref(), Some("full-secret"));
+
+        config.settings.ipc.token = None;
+        assert_eq!(config.settings.validate().len(), 1);
+    }
+
+    #[test]
+    fn ipc_allowed_processes_need_absolute_paths_and_spawns_get_the_token() {
+        let mut config = Config::default();
+        config.settings.ipc.allowed_processes =
+            vec!["/opt/homebrew/bin/sketchybar".into(), "sketchybar".into()];
+        let issues = config.settings.validate();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("'sketchybar'"));
+
+        assert!(config.settings.ipc.spawn_env().is_empty());
+        config.settings.ipc.token = Some("secret".into());
+        assert_eq!(config.settings.ipc.spawn_env(), vec![(
+            "RIFT_IPC_TOKEN".to_string(),
+            "secret".to_string()
+        )]);
+    }
+
+    #[test]
+    fn border_colors_parse_with_optional_alpha() {
+        assert_eq!(parse_hex_color("#ff0000"), Some([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(parse_hex_color("#00000000"), Some([0.0, 0.0, 0.0, 0.0]));
+        assert_eq!(parse_hex_color("ff0000"), None);
+        assert_eq!(parse_hex_color("#ff00"), None);
+
+        let settings: BorderSettings =
+            toml::from_str("enabled = true\ninactive_color = \"blue\"").unwrap();
+        assert_eq!(settings.validate().len(), 1);
+        let settings: BorderSettings = toml::from_str("always_on_top_color = \"#ff000\"").unwrap();
+        assert_eq!(settings.validate().len(), 1);
+    }
+
+    #[test]
+    fn hooks_resolve_by_event_name_and_skip_blank_commands() {
+        let hooks: HookSettings = toml::from_str(
+            "workspace_changed = \"sketchybar --trigger ws\"\nwindow_focused = \"  \"\n\
+             window_moved = \"moved.sh\"",
+        )
+        .unwrap();
+        let command = |name| hooks.hook_for(name).map(Hook::command);
+        assert_eq!(command("workspace_changed"), Some("sketchybar --trigger ws"));
+        assert_eq!(command("window_moved"), Some("moved.sh"));
+        assert_eq!(command("window_focused"), None);
+        assert_eq!(command("layout_changed"), None);
+        assert!(hooks.validate().is_empty());
+
+        let hooks: HookSettings = toml::from_str("on_window_move = \"x\"").unwrap();
+        assert_eq!(hooks.validate().len(), 1);
+    }
+
+    #[test]
+    fn hooks_take_a_matcher_only_for_window_events() {
+        let hooks: HookSettings = toml::from_str(
+            "window_focused = { command = \"safari.sh\", match = 'app:\"Safari\"' }",
+        )
+        .unwrap();
+        let hook = hooks.hook_for("window_focused").unwrap();
+        assert_eq!(hook.command(), "safari.sh");
+        assert_eq!(hook.matcher().unwrap().as_str(), "app:\"Safari\"");
+        assert!(hooks.validate().is_empty());
+
+        let hooks: HookSettings = toml::from_str(
+            "workspace_changed = { command = \"ws.sh\", match = 'app:\"Safari\"' }",
+        )
+        .unwrap();
+        assert_eq!(hooks.validate().len(), 1);
+    }
+
+    #[test]
+    fn floating_frame_centers_unset_axes_on_the_screen() {
+        let frame: FloatingFrame = toml::from_str("width = 0.6\nheight = 0.5\ny = 0.1").unwrap();
+        let screen = CGRect::new(CGPoint::new(100.0, 0.0), CGSize::new(1000.0, 800.0));
+        assert_eq!(
+            frame.resolve(screen),
+            CGRect::new(CGPoint::new(300.0, 80.0), CGSize::new(600.0, 400.0))
+        );
+    }
+
+    #[test]
+    fn safe_mode_overrides_are_runtime_only() {
+        let mut config = Config::default();
+        config.settings.animate = true;
+        config.settings.run_on_start = vec!["sketchybar".into()];
+        config.apply_safe_mode();
+
+        assert!(config.settings.safe_mode);
+        assert!(!config.settings.animate);
+        assert!(!config.settings.focus_follows_mouse);
+        assert!(config.settings.layout.float_all_windows);
+        assert!(config.settings.run_on_start.is_empty());
+
+        let serialized = toml::to_string(&config.settings).unwrap();
+        assert!(!serialized.contains("safe_mode"));
+        assert!(!serialized.contains("float_all_windows"));
+    }
+
     #[test]
     fn virtual_workspace_prevent_wrapping_defaults_to_false_and_accepts_suggested_alias() {
         let defaults: VirtualWorkspaceSettings = toml::from_str("").unwrap();
@@ -1601,6 +3150,100 @@ mod tests {
         );
     }
 
+    #[test]
+    fn config_diff_reports_changed_setting_paths() {
+        let old = Config::default();
+        let mut new = old.clone();
+        new.settings.layout.gaps.inner.horizontal += 4.0;
+        new.settings.focus_follows_mouse = !old.settings.focus_follows_mouse;
+
+        let diff = ConfigDiff::between(&old, &new);
+        assert_eq!(diff.changed, vec![
+            "settings.focus_follows_mouse",
+            "settings.layout.gaps.inner.horizontal"
+        ]);
+        assert!(diff.touches(&["settings.layout"]));
+        assert!(diff.touches(&["settings.layout.gaps.inner"]));
+        assert!(!diff.touches(&["settings.ui", "keys"]));
+        assert!(ConfigDiff::between(&old, &old.clone()).is_empty());
+    }
+
+    #[test]
+    fn profiles_overlay_the_base_config() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("config.toml");
+        let base = include_str!("../../rift.default.toml");
+        let inline = r#"
+[profiles.presentation.settings.layout.gaps.outer]
+top = 40
+left = 40
+bottom = 40
+right = 40
+
+[profiles.presentation.virtual_workspaces]
+workspace_names = ["slides", "notes"]
+"#;
+        std::fs::write(&path, format!("{base}\n{inline}")).unwrap();
+        std::fs::create_dir(directory.path().join("profiles")).unwrap();
+        std::fs::write(
+            directory.path().join("profiles/home.toml"),
+            "[settings]\nfocus_follows_mouse = false\n",
+        )
+        .unwrap();
+
+        let plain = Config::read_with_profile(&path, None).unwrap();
+        assert!(plain.profiles.contains_key("presentation"));
+
+        let presentation = Config::read_with_profile(&path, Some("presentation")).unwrap();
+        assert_eq!(presentation.settings.layout.gaps.outer.top, 40.0);
+        assert_eq!(presentation.virtual_workspaces.workspace_names, vec![
+            "slides", "notes"
+        ]);
+        assert_eq!(
+            presentation.settings.layout.gaps.inner,
+            plain.settings.layout.gaps.inner
+        );
+        assert_eq!(presentation.key_specs.len(), plain.key_specs.len());
+
+        let home = Config::read_with_profile(&path, Some("home")).unwrap();
+        assert!(!home.settings.focus_follows_mouse);
+        assert_eq!(home.settings.layout.gaps.outer, plain.settings.layout.gaps.outer);
+
+        assert!(Config::read_with_profile(&path, Some("missing")).is_err());
+        assert!(Config::read_with_profile(&path, Some("../config")).is_err());
+    }
+
+    #[test]
+    fn runtime_changes_under_a_profile_are_saved_without_the_profile() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("config.toml");
+        let base = include_str!("../../rift.default.toml");
+        let inline = "\n[profiles.presentation.settings.layout.gaps.outer]\ntop = 40\n";
+        std::fs::write(&path, format!("{base}{inline}")).unwrap();
+
+        let loaded = Config::read_with_profile(&path, Some("presentation")).unwrap();
+        let mut current = loaded.clone();
+        current.settings.animate = !loaded.settings.animate;
+
+        let saved = Config::read(&path).unwrap().with_changes(&loaded, &current).unwrap();
+        saved.save(&path).unwrap();
+
+        let reread = Config::read(&path).unwrap();
+        assert_eq!(reread.settings.animate, current.settings.animate);
+        assert_ne!(reread.settings.layout.gaps.outer.top, 40.0);
+        assert!(reread.profiles.contains_key("presentation"));
+        assert_eq!(
+            Config::read_with_profile(&path, Some("presentation"))
+                .unwrap()
+                .settings
+                .layout
+                .gaps
+                .outer
+                .top,
+            40.0
+        );
+    }
+
     #[test]
     fn menu_bar_layout_folder_defaults_and_expands_home() {
         let settings: MenuBarSettings = toml::from_str("").unwrap();
@@ -1661,6 +3304,117 @@ mod tests {
         assert!(!cfg.keys.is_empty());
     }
 
+    #[test]
+    fn modes_are_parsed_with_modifier_combinations() {
+        let toml = r#"
+            [modifier_combinations]
+            comb1 = "Alt + Shift"
+
+            [keys]
+            "comb1 + R" = { enter_mode = "resize" }
+
+            [modes.resize]
+            "H" = { resize_window_shrink = "horizontal" }
+            "comb1 + L" = { resize_window_grow = "horizontal" }
+        "#;
+
+        let cfg = Config::parse(toml).unwrap();
+        let resize = cfg.modes.get("resize").expect("resize mode");
+        assert_eq!(resize.len(), 2);
+        assert!(resize.iter().any(|(spec, _)| spec == "Alt + Shift + L"));
+
+        let bad = r#"
+            [modes.resize]
+            "Hyperr + H" = "exit_mode"
+        "#;
+        let err = Config::parse(bad).unwrap_err().to_string();
+        assert!(err.contains("mode `resize`"), "{err}");
+    }
+
+    #[test]
+    fn non_finite_split_ratios_are_rejected() {
+        let toml = r#"
+            [keys]
+            "Alt + R" = { set_split_ratio = nan }
+            "Alt + P" = { preselect = { direction = "right", ratio = inf } }
+
+            [modes.resize]
+            "H" = { set_split_ratio = 0.5 }
+            "L" = { set_split_ratio = -inf }
+        "#;
+
+        let issues = Config::parse(toml).unwrap().validate();
+        assert_eq!(issues.len(), 3, "{issues:?}");
+        assert!(issues.iter().any(|issue| issue.contains("mode `resize`")));
+    }
+
+    #[test]
+    fn app_rule_matchers_cannot_use_window_state() {
+        let toml = r#"
+            [[virtual_workspaces.app_rules]]
+            matcher = 'app:Safari && floating:true'
+
+            [[virtual_workspaces.app_rules]]
+            matcher = 'app:Safari && title~workspace'
+        "#;
+
+        let issues = Config::parse(toml).unwrap().validate();
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(issues[0].starts_with("App rule 0 matcher"));
+    }
+
+    #[test]
+    fn gap_bindings_are_config_commands() {
+        let toml = r#"
+            [keys]
+            "Alt + G" = { adjust_gaps = { scope = "inner", delta = 4 } }
+            "Alt + Shift + G" = { set_gaps = { scope = "outer", value = 0, persist = true } }
+        "#;
+
+        let config = Config::parse(toml).unwrap();
+        let commands: Vec<_> = config.keys.iter().map(|(_, command)| command.clone()).collect();
+        assert!(commands.contains(&WmCommand::Config(ConfigCommand::AdjustGaps {
+            scope: GapScope::Inner,
+            delta: 4.0,
+            display: None,
+            display_name: None,
+            persist: false,
+        })));
+        assert!(commands.contains(&WmCommand::Config(ConfigCommand::SetGaps {
+            scope: GapScope::Outer,
+            value: 0.0,
+            display: None,
+            display_name: None,
+            persist: true,
+        })));
+    }
+
+    #[test]
+    fn named_layout_files_stay_in_the_layouts_directory() {
+        let coding = named_layout_file("coding").unwrap();
+        assert_eq!(coding, data_dir().join("layouts").join("coding.ron"));
+        assert!(named_layout_file("").is_none());
+        assert!(named_layout_file("../layout").is_none());
+        assert!(named_layout_file(".hidden").is_none());
+    }
+
+    #[test]
+    fn auto_float_matches_small_or_transient_windows() {
+        let disabled = AutoFloatSettings::default();
+        assert!(!disabled.should_float(10.0, 10.0, None, Some("AXDialog")));
+
+        let settings = AutoFloatSettings {
+            width: 400.0,
+            height: 300.0,
+            transient_roles: true,
+        };
+        assert!(settings.should_float(399.0, 299.0, None, None));
+        assert!(!settings.should_float(399.0, 300.0, None, None));
+        assert!(settings.should_float(1200.0, 900.0, None, Some("AXDialog")));
+        assert!(settings.should_float(1200.0, 900.0, Some("AXPopover"), None));
+        assert!(!settings.should_float(1200.0, 900.0, None, Some("AXStandardWindow")));
+    }
+
     #[test]
     fn serde_round_trip_preserves_key_specs() {
         let cfg = Config::default();
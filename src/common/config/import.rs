@@ -0,0 +1,917 @@
+//! Translates yabai and AeroSpace configs into rift's config format.
+//!
+//! Only what has a rift equivalent is carried over: gaps, the layout mode,
+//! window rules and key bindings whose commands map onto rift commands.
+//! Everything else is reported as a warning so it can be redone by hand.
+//!
+//! AeroSpace configs are TOML and are read through the serde models below.
+//! yabai is configured with shell scripts, so `yabai -m config` and
+//! `yabai -m rule` lines are picked out of the script, and skhd lines
+//! (`alt - h : yabai -m window --focus west`) in the same file are read as
+//! key bindings.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    Yabai,
+    Aerospace,
+}
+
+#[derive(Debug, Default)]
+pub struct Imported {
+    /// Everything except key bindings, laid out like `rift.toml`.
+    pub config: toml::Table,
+    pub keys: toml::Table,
+    pub modes: BTreeMap<String, toml::Table>,
+    /// What could not be translated, one line each.
+    pub warnings: Vec<String>,
+}
+
+impl Imported {
+    /// Renders the result as a config file, with one key binding per line
+    /// like the default config.
+    pub fn to_toml(&self) -> String {
+        let mut out = toml::to_string(&self.config).expect("config tables always serialize");
+        out.push_str("\n[keys]\n");
+        write_bindings(&mut out, &self.keys);
+        for (name, bindings) in &self.modes {
+            let _ = writeln!(out, "\n[modes.{}]", table_key(name));
+            write_bindings(&mut out, bindings);
+        }
+        out
+    }
+}
+
+fn write_bindings(out: &mut String, bindings: &toml::Table) {
+    for (hotkey, command) in bindings {
+        let _ = writeln!(out, "{} = {}", toml::Value::from(hotkey.as_str()), command);
+    }
+}
+
+fn table_key(name: &str) -> String {
+    let bare =
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        name.to_string()
+    } else {
+        toml::Value::from(name).to_string()
+    }
+}
+
+pub fn import(source: ImportSource, text: &str) -> anyhow::Result<Imported> {
+    match source {
+        ImportSource::Yabai => Ok(import_yabai(text)),
+        ImportSource::Aerospace => import_aerospace(text),
+    }
+}
+
+#[derive(Default)]
+struct Builder {
+    imported: Imported,
+    app_rules: Vec<toml::Value>,
+    /// Workspace names in index order. Written to the config when any
+    /// binding or rule refers to a workspace.
+    workspaces: Vec<String>,
+}
+
+impl Builder {
+    fn set(&mut self, path: &[&str], value: impl Into<toml::Value>) {
+        let (last, parents) = path.split_last().expect("config paths are not empty");
+        let mut table = &mut self.imported.config;
+        for name in parents {
+            table = table
+                .entry(name.to_string())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .expect("config paths only nest tables");
+        }
+        table.insert(last.to_string(), value.into());
+    }
+
+    fn warn(&mut self, message: impl Into<String>) { self.imported.warnings.push(message.into()); }
+
+    fn bind(&mut self, mode: Option<&str>, hotkey: String, command: toml::Value) {
+        let bindings = match mode {
+            Some(mode) => self.imported.modes.entry(mode.to_string()).or_default(),
+            None => &mut self.imported.keys,
+        };
+        bindings.insert(hotkey, command);
+    }
+
+    fn workspace_index(&self, name: &str) -> Option<i64> {
+        self.workspaces
+            .iter()
+            .position(|workspace| workspace == name)
+            .map(|index| index as i64)
+    }
+
+    fn finish(mut self) -> Imported {
+        if !self.workspaces.is_empty() {
+            let names = std::mem::take(&mut self.workspaces);
+            self.set(
+                &["virtual_workspaces", "default_workspace_count"],
+                names.len() as i64,
+            );
+            self.set(&["virtual_workspaces", "workspace_names"], names);
+        }
+        if !self.app_rules.is_empty() {
+            let rules = std::mem::take(&mut self.app_rules);
+            self.set(&["virtual_workspaces", "app_rules"], rules);
+        }
+        self.imported
+            .config
+            .entry("settings")
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        self.imported
+    }
+}
+
+fn command(name: &str) -> toml::Value { toml::Value::from(name) }
+
+fn command_with(name: &str, argument: impl Into<toml::Value>) -> toml::Value {
+    let mut table = toml::Table::new();
+    table.insert(name.to_string(), argument.into());
+    toml::Value::Table(table)
+}
+
+/// A case-insensitive regex predicate for a rule `matcher`, escaped for the
+/// matcher's string syntax.
+fn regex_predicate(field: &str, regex: &str) -> String {
+    format!(
+        "{field}~\"{}\"",
+        regex.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+fn modifier_name(modifier: &str) -> Option<&'static [&'static str]> {
+    Some(match modifier.trim() {
+        "alt" | "lalt" | "ralt" | "option" => &["Alt"],
+        "ctrl" | "lctrl" | "rctrl" | "control" => &["Ctrl"],
+        "shift" | "lshift" | "rshift" => &["Shift"],
+        "cmd" | "lcmd" | "rcmd" => &["Meta"],
+        "meh" => &["Alt", "Shift", "Ctrl"],
+        "hyper" => &["Alt", "Shift", "Ctrl", "Meta"],
+        _ => return None,
+    })
+}
+
+fn key_name(key: &str) -> String {
+    let key = key.trim();
+    match key.to_ascii_lowercase().as_str() {
+        "return" | "enter" => "Enter".to_string(),
+        "esc" | "escape" => "Escape".to_string(),
+        "backspace" => "Delete".to_string(),
+        "backtick" | "grave" => "Backquote".to_string(),
+        _ => {
+            let mut chars = key.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        }
+    }
+}
+
+fn hotkey(modifiers: &[&str], key: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    for modifier in modifiers {
+        parts.extend_from_slice(modifier_name(modifier)?);
+    }
+    let key = key_name(key);
+    if key.is_empty() || key.starts_with("0x") || key.starts_with("0X") {
+        return None;
+    }
+    parts.push(key.as_str());
+    Some(parts.join(" + "))
+}
+
+// AeroSpace
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+struct AerospaceConfig {
+    #[serde(default)]
+    gaps: AerospaceGaps,
+    #[serde(default)]
+    mode: BTreeMap<String, AerospaceMode>,
+    #[serde(default)]
+    on_window_detected: Vec<AerospaceWindowRule>,
+    #[serde(default)]
+    default_root_container_layout: Option<String>,
+    #[serde(default)]
+    after_startup_command: Vec<String>,
+    #[serde(flatten)]
+    other: toml::Table,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct AerospaceGaps {
+    #[serde(default)]
+    inner: BTreeMap<String, AerospaceGap>,
+    #[serde(default)]
+    outer: BTreeMap<String, AerospaceGap>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum AerospaceGap {
+    Fixed(f64),
+    /// `[{ monitor.main = 16 }, 24]`: per-monitor values, then the default.
+    PerMonitor(Vec<toml::Value>),
+}
+
+impl AerospaceGap {
+    fn default_value(&self) -> Option<f64> {
+        match self {
+            AerospaceGap::Fixed(value) => Some(*value),
+            AerospaceGap::PerMonitor(values) => values.last().and_then(|value| {
+                value.as_float().or_else(|| value.as_integer().map(|v| v as f64))
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct AerospaceMode {
+    #[serde(default)]
+    binding: BTreeMap<String, AerospaceCommands>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum AerospaceCommands {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl AerospaceCommands {
+    fn as_slice(&self) -> &[String] {
+        match self {
+            AerospaceCommands::One(command) => std::slice::from_ref(command),
+            AerospaceCommands::Many(commands) => commands,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct AerospaceWindowRule {
+    #[serde(default, rename = "if")]
+    condition: AerospaceCondition,
+    #[serde(default)]
+    run: Option<AerospaceCommands>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+struct AerospaceCondition {
+    app_id: Option<String>,
+    app_name_regex_substring: Option<String>,
+    window_title_regex_substring: Option<String>,
+    #[serde(flatten)]
+    other: toml::Table,
+}
+
+/// Splits an AeroSpace command into its name and positional arguments,
+/// dropping `--flags`.
+fn aerospace_words(command: &str) -> Option<(&str, Vec<&str>)> {
+    let mut words = command.split_whitespace();
+    let name = words.next()?;
+    Some((name, words.filter(|word| !word.starts_with("--")).collect()))
+}
+
+/// AeroSpace workspaces are named. They become rift workspaces in the order
+/// AeroSpace shows them: numbers first, then the rest alphabetically.
+fn aerospace_workspaces(config: &AerospaceConfig) -> Vec<String> {
+    let bindings = config.mode.values().flat_map(|mode| mode.binding.values());
+    let rules = config.on_window_detected.iter().filter_map(|rule| rule.run.as_ref());
+    let mut names: Vec<String> = bindings
+        .chain(rules)
+        .flat_map(AerospaceCommands::as_slice)
+        .filter_map(|command| match aerospace_words(command)? {
+            ("workspace" | "move-node-to-workspace", args) => match args.as_slice() {
+                ["next" | "prev"] => None,
+                [name] => Some(name.to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    names.sort_by_key(|name| {
+        let number = name.parse::<u64>().ok();
+        (number.is_none(), number, name.clone())
+    });
+    names.dedup();
+    names
+}
+
+fn import_aerospace(text: &str) -> anyhow::Result<Imported> {
+    let config: AerospaceConfig = toml::from_str(text)?;
+    let mut builder = Builder {
+        workspaces: aerospace_workspaces(&config),
+        ..Default::default()
+    };
+
+    for key in config.other.keys() {
+        builder.warn(format!("`{key}` has no rift equivalent"));
+    }
+
+    match config.default_root_container_layout.as_deref() {
+        None => {}
+        Some("tiles") => builder.set(&["settings", "layout", "mode"], "traditional"),
+        Some("accordion") => builder.set(&["settings", "layout", "mode"], "stack"),
+        Some(other) => builder.warn(format!("unknown default-root-container-layout `{other}`")),
+    }
+
+    for (kind, gaps) in [("inner", &config.gaps.inner), ("outer", &config.gaps.outer)] {
+        for (side, gap) in gaps {
+            if matches!(gap, AerospaceGap::PerMonitor(_)) {
+                builder.warn(format!(
+                    "gaps.{kind}.{side} has per-monitor values; only the default was imported"
+                ));
+            }
+            match gap.default_value() {
+                Some(value) => {
+                    builder.set(&["settings", "layout", "gaps", kind, side.as_str()], value)
+                }
+                None => builder.warn(format!("gaps.{kind}.{side} has no default value")),
+            }
+        }
+    }
+
+    let mut run_on_start = Vec::new();
+    for command in &config.after_startup_command {
+        match command.trim().strip_prefix("exec-and-forget") {
+            Some(shell) => run_on_start.push(shell.trim().to_string()),
+            None => builder.warn(format!("after-startup-command `{command}` was not imported")),
+        }
+    }
+    if !run_on_start.is_empty() {
+        builder.set(&["settings", "run_on_start"], run_on_start);
+    }
+
+    for (mode, AerospaceMode { binding }) in &config.mode {
+        let mode = (mode != "main").then_some(mode.as_str());
+        for (key, commands) in binding {
+            let mut parts: Vec<&str> = key.split('-').collect();
+            let Some(hotkey) = parts.pop().and_then(|key| hotkey(&parts, key)) else {
+                builder.warn(format!("key `{key}` could not be translated"));
+                continue;
+            };
+            let mut translated = Vec::new();
+            for command in commands.as_slice() {
+                match aerospace_command(&builder, command) {
+                    Some(value) => translated.push(value),
+                    None => builder.warn(format!("`{key}`: `{command}` has no rift equivalent")),
+                }
+            }
+            let mut translated = translated.into_iter();
+            let Some(first) = translated.next() else { continue };
+            if translated.next().is_some() {
+                builder.warn(format!(
+                    "`{key}` runs several commands; only the first one was imported"
+                ));
+            }
+            builder.bind(mode, hotkey, first);
+        }
+    }
+
+    for rule in &config.on_window_detected {
+        aerospace_rule(&mut builder, rule);
+    }
+
+    Ok(builder.finish())
+}
+
+fn aerospace_direction(direction: &str) -> Option<&'static str> {
+    Some(match direction {
+        "left" => "left",
+        "right" => "right",
+        "up" => "up",
+        "down" => "down",
+        _ => return None,
+    })
+}
+
+fn aerospace_command(builder: &Builder, text: &str) -> Option<toml::Value> {
+    let (name, args) = aerospace_words(text)?;
+    Some(match (name, args.as_slice()) {
+        ("focus", [direction]) => command_with("move_focus", aerospace_direction(direction)?),
+        ("move", [direction]) => command_with("move_node", aerospace_direction(direction)?),
+        ("join-with", [direction]) => command_with("join_window", aerospace_direction(direction)?),
+        ("focus-back-and-forth", []) => command("focus_last"),
+        ("workspace", ["next"]) => command("next_workspace"),
+        ("workspace", ["prev"]) => command("prev_workspace"),
+        ("workspace", [name]) => {
+            command_with("switch_to_workspace", builder.workspace_index(name)?)
+        }
+        ("move-node-to-workspace", [target @ ("next" | "prev")]) => {
+            command_with("move_window_to_workspace", *target)
+        }
+        ("move-node-to-workspace", [name]) => {
+            command_with("move_window_to_workspace", builder.workspace_index(name)?)
+        }
+        ("workspace-back-and-forth", []) => command("switch_to_last_workspace"),
+        ("fullscreen", []) => command("toggle_fullscreen"),
+        ("macos-native-fullscreen", []) => command("toggle_native_fullscreen"),
+        ("layout", ["floating", "tiling"] | ["tiling", "floating"]) => {
+            command("toggle_window_floating")
+        }
+        ("layout", ["accordion", ..] | ["tiles", "accordion"]) => command("toggle_stack"),
+        ("layout", ["tiles", ..] | ["horizontal", "vertical"] | ["vertical", "horizontal"]) => {
+            command("toggle_orientation")
+        }
+        ("balance-sizes", []) => command("rebalance"),
+        ("reload-config", []) => command("reload_config"),
+        ("close", []) => command("close_window"),
+        ("mode", ["main"]) => command("exit_mode"),
+        ("mode", [mode]) => command_with("enter_mode", *mode),
+        ("resize", [dimension, amount]) => {
+            let orientation = match *dimension {
+                "smart" | "smart-opposite" => "smart",
+                "width" => "horizontal",
+                "height" => "vertical",
+                _ => return None,
+            };
+            match amount.chars().next()? {
+                '+' => command_with("resize_window_grow", orientation),
+                '-' => command_with("resize_window_shrink", orientation),
+                _ => return None,
+            }
+        }
+        ("exec-and-forget", _) => {
+            command_with("exec", text.trim().strip_prefix("exec-and-forget")?.trim())
+        }
+        _ => return None,
+    })
+}
+
+fn aerospace_rule(builder: &mut Builder, rule: &AerospaceWindowRule) {
+    let condition = &rule.condition;
+    let mut table = toml::Table::new();
+    if let Some(app_id) = &condition.app_id {
+        table.insert("app_id".into(), app_id.as_str().into());
+    }
+    let mut predicates = Vec::new();
+    if let Some(name) = &condition.app_name_regex_substring {
+        predicates.push(regex_predicate("app", name));
+    }
+    if let Some(title) = &condition.window_title_regex_substring {
+        predicates.push(regex_predicate("title", title));
+    }
+    if !predicates.is_empty() {
+        table.insert("matcher".into(), predicates.join(" && ").into());
+    }
+    let described = condition.app_id.clone().unwrap_or_else(|| predicates.join(" && "));
+    for key in condition.other.keys() {
+        builder.warn(format!(
+            "window rule for `{described}`: condition `if.{key}` is not supported and was dropped"
+        ));
+    }
+    if table.is_empty() {
+        builder.warn("window rule without an app or title condition was skipped");
+        return;
+    }
+
+    let mut effective = false;
+    for command in rule.run.iter().flat_map(AerospaceCommands::as_slice) {
+        let (name, args) = aerospace_words(command).unwrap_or_default();
+        let workspace = match (name, args.as_slice()) {
+            ("move-node-to-workspace", [workspace]) => builder.workspace_index(workspace),
+            _ => None,
+        };
+        match (name, args.as_slice(), workspace) {
+            ("layout", ["floating"], _) => {
+                table.insert("floating".into(), true.into());
+                effective = true;
+            }
+            ("layout", ["tiling"], _) => {}
+            (_, _, Some(index)) => {
+                table.insert("workspace".into(), index.into());
+                effective = true;
+            }
+            _ => builder.warn(format!(
+                "window rule for `{described}`: `{command}` has no rift equivalent"
+            )),
+        }
+    }
+    if effective {
+        builder.app_rules.push(toml::Value::Table(table));
+    }
+}
+
+// yabai
+
+fn import_yabai(text: &str) -> Imported {
+    let mut builder = Builder::default();
+    for line in logical_lines(text) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let words = shell_words(line);
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        match words.as_slice() {
+            ["yabai", "-m", "config", setting @ ..] => yabai_setting(&mut builder, line, setting),
+            ["yabai", "-m", "rule", "--add", args @ ..] => yabai_rule(&mut builder, line, args),
+            _ if !line.starts_with("yabai") && !line.starts_with("sudo") && line.contains(':') => {
+                skhd_binding(&mut builder, line)
+            }
+            _ => builder.warn(format!("`{line}` has no rift equivalent")),
+        }
+    }
+    builder.finish()
+}
+
+/// Joins lines continued with a trailing backslash.
+fn logical_lines(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        if let Some(continued) = line.strip_suffix('\\') {
+            current.push_str(continued);
+            current.push(' ');
+            continue;
+        }
+        current.push_str(line);
+        lines.push(std::mem::take(&mut current));
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Splits a shell command line into words, honouring quotes and stopping at
+/// a comment.
+fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some('"'), '\\') => {
+                match chars.next_if(|next| matches!(*next, '"' | '\\' | '$' | '`')) {
+                    Some(escaped) => word.push(escaped),
+                    None => word.push('\\'),
+                }
+            }
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '\\') => {
+                word.extend(chars.next());
+                in_word = true;
+            }
+            (None, '#') if !in_word => break,
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+fn yabai_setting(builder: &mut Builder, line: &str, setting: &[&str]) {
+    let [name, value] = setting else {
+        builder.warn(format!("`{line}` has no rift equivalent"));
+        return;
+    };
+    let gap = value.parse::<f64>().ok();
+    match (*name, *value, gap) {
+        ("layout", "bsp" | "stack", _) => builder.set(&["settings", "layout", "mode"], *value),
+        ("layout", "float", _) => builder.set(&["settings", "layout", "float_all_windows"], true),
+        ("window_gap", _, Some(gap)) => {
+            builder.set(&["settings", "layout", "gaps", "inner", "horizontal"], gap);
+            builder.set(&["settings", "layout", "gaps", "inner", "vertical"], gap);
+        }
+        ("top_padding" | "bottom_padding" | "left_padding" | "right_padding", _, Some(gap)) => {
+            let side = name.trim_end_matches("_padding");
+            builder.set(&["settings", "layout", "gaps", "outer", side], gap);
+        }
+        ("focus_follows_mouse", "off", _) => {
+            builder.set(&["settings", "focus_follows_mouse"], false)
+        }
+        ("focus_follows_mouse", mode @ ("autofocus" | "autoraise"), _) => {
+            builder.set(&["settings", "focus_follows_mouse"], true);
+            let mode = if mode == "autofocus" {
+                "focus_only"
+            } else {
+                "autoraise"
+            };
+            builder.set(&["settings", "focus_follows_mouse_mode"], mode);
+        }
+        ("mouse_follows_focus", "on" | "off", _) => {
+            builder.set(&["settings", "mouse_follows_focus"], *value == "on");
+        }
+        _ => builder.warn(format!("yabai setting `{name}` has no rift equivalent")),
+    }
+}
+
+impl Builder {
+    /// Maps a 1-based yabai space number to a rift workspace index, naming
+    /// workspaces after the space numbers they replace.
+    fn yabai_space(&mut self, space: &str) -> Option<i64> {
+        let number = space.parse::<usize>().ok().filter(|&number| number > 0)?;
+        while self.workspaces.len() < number {
+            self.workspaces.push((self.workspaces.len() + 1).to_string());
+        }
+        Some(number as i64 - 1)
+    }
+}
+
+fn yabai_rule(builder: &mut Builder, line: &str, args: &[&str]) {
+    let mut table = toml::Table::new();
+    let mut predicates = Vec::new();
+    let mut dropped = Vec::new();
+    for arg in args {
+        let Some((key, value)) = arg.split_once('=') else {
+            dropped.push(*arg);
+            continue;
+        };
+        match key {
+            "app" | "title" => predicates.push(regex_predicate(key, value)),
+            "manage" if value == "off" => {
+                table.insert("floating".into(), true.into());
+            }
+            "manage" | "label" => {}
+            "space" => match builder.yabai_space(value) {
+                Some(index) => {
+                    table.insert("workspace".into(), index.into());
+                }
+                None => dropped.push(*arg),
+            },
+            _ => dropped.push(*arg),
+        }
+    }
+    if !dropped.is_empty() {
+        builder.warn(format!("`{line}`: {} not imported", dropped.join(", ")));
+    }
+    if predicates.is_empty() || table.is_empty() {
+        builder.warn(format!("`{line}` was skipped"));
+        return;
+    }
+    table.insert("matcher".into(), predicates.join(" && ").into());
+    builder.app_rules.push(toml::Value::Table(table));
+}
+
+fn skhd_binding(builder: &mut Builder, line: &str) {
+    if line.starts_with("::") || line.starts_with('.') {
+        builder.warn(format!("skhd `{line}` has no rift equivalent"));
+        return;
+    }
+    let Some((spec, action)) = line.split_once(':') else {
+        return;
+    };
+    if spec.contains('<') || spec.contains('[') {
+        builder.warn(format!("skhd mode or app-specific binding `{line}` was skipped"));
+        return;
+    }
+    let (modifiers, key) = spec.rsplit_once('-').unwrap_or(("", spec));
+    let modifiers: Vec<&str> = modifiers.split('+').filter(|m| !m.trim().is_empty()).collect();
+    let Some(hotkey) = hotkey(&modifiers, key) else {
+        builder.warn(format!("key `{}` could not be translated", spec.trim()));
+        return;
+    };
+
+    let action = action.trim();
+    let first = action.split(['|', '&', ';']).next().unwrap_or_default().trim();
+    if first.len() != action.len() {
+        builder.warn(format!(
+            "`{hotkey}` runs several commands; only `{first}` was imported"
+        ));
+    }
+    let command = match first.strip_prefix("yabai -m ") {
+        Some(args) => {
+            let words = shell_words(args);
+            let words: Vec<&str> = words.iter().map(String::as_str).collect();
+            yabai_command(builder, &words)
+        }
+        None => Some(command_with("exec", first)),
+    };
+    match command {
+        Some(command) => builder.bind(None, hotkey, command),
+        None => builder.warn(format!("`{hotkey}`: `{first}` has no rift equivalent")),
+    }
+}
+
+fn yabai_direction(direction: &str) -> Option<&'static str> {
+    Some(match direction {
+        "west" => "left",
+        "east" => "right",
+        "north" => "up",
+        "south" => "down",
+        _ => return None,
+    })
+}
+
+fn yabai_command(builder: &mut Builder, words: &[&str]) -> Option<toml::Value> {
+    Some(match words {
+        ["window", "--focus", "recent"] => command("focus_last"),
+        ["window", "--focus", "next"] => command("next_window"),
+        ["window", "--focus", "prev"] => command("prev_window"),
+        ["window", "--focus", direction] => command_with("move_focus", yabai_direction(direction)?),
+        ["window", "--swap" | "--warp", direction] => {
+            command_with("move_node", yabai_direction(direction)?)
+        }
+        ["window", "--stack", direction] => {
+            command_with("join_window", yabai_direction(direction)?)
+        }
+        ["window", "--space", target @ ("next" | "prev")] => {
+            command_with("move_window_to_workspace", *target)
+        }
+        ["window", "--space", space] => {
+            command_with("move_window_to_workspace", builder.yabai_space(space)?)
+        }
+        ["window", "--toggle", "float"] => command("toggle_window_floating"),
+        ["window", "--toggle", "zoom-fullscreen"] => command("toggle_fullscreen"),
+        ["window", "--toggle", "native-fullscreen"] => command("toggle_native_fullscreen"),
+        ["window", "--toggle", "split"] => command("toggle_orientation"),
+        ["window", "--toggle", "sticky"] => command("toggle_sticky"),
+        ["window", "--close"] => command("close_window"),
+        ["window", "--minimize"] => command("minimize_selection"),
+        ["space", "--focus", "recent"] => command("switch_to_last_workspace"),
+        ["space", "--focus", "next"] => command("next_workspace"),
+        ["space", "--focus", "prev"] => command("prev_workspace"),
+        ["space", "--focus", space] => {
+            command_with("switch_to_workspace", builder.yabai_space(space)?)
+        }
+        ["space", "--balance"] => command("rebalance"),
+        ["space", "--layout", mode @ ("bsp" | "stack")] => {
+            command_with("set_workspace_layout", command_with("mode", *mode))
+        }
+        ["space", "--create"] => command("create_workspace"),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::config::Config;
+
+    fn warned(imported: &Imported, needle: &str) -> bool {
+        imported.warnings.iter().any(|warning| warning.contains(needle))
+    }
+
+    #[test]
+    fn aerospace_gaps_bindings_and_rules_are_translated() {
+        let imported = import(
+            ImportSource::Aerospace,
+            r#"
+            start-at-login = true
+            [gaps]
+            inner.horizontal = 8
+            outer.top = [{ monitor.main = 16 }, 24]
+
+            [mode.main.binding]
+            alt-h = 'focus left'
+            alt-shift-1 = 'move-node-to-workspace 1'
+            alt-b = 'workspace B'
+            alt-r = 'mode resize'
+            alt-p = 'flatten-workspace-tree'
+
+            [mode.resize.binding]
+            minus = 'resize smart -50'
+            esc = ['reload-config', 'mode main']
+
+            [[on-window-detected]]
+            if.app-id = 'com.apple.finder'
+            run = 'layout floating'
+            "#,
+        )
+        .unwrap();
+
+        let config = &imported.config;
+        assert_eq!(
+            config["settings"]["layout"]["gaps"]["inner"]["horizontal"],
+            toml::Value::Float(8.0)
+        );
+        assert_eq!(
+            config["settings"]["layout"]["gaps"]["outer"]["top"],
+            toml::Value::Float(24.0)
+        );
+        assert_eq!(
+            config["virtual_workspaces"]["workspace_names"],
+            toml::Value::from(vec!["1", "B"])
+        );
+        assert_eq!(
+            config["virtual_workspaces"]["app_rules"][0]["floating"],
+            toml::Value::Boolean(true)
+        );
+        assert_eq!(imported.keys["Alt + H"], command_with("move_focus", "left"));
+        assert_eq!(
+            imported.keys["Alt + Shift + 1"],
+            command_with("move_window_to_workspace", 0i64)
+        );
+        assert_eq!(
+            imported.keys["Alt + B"],
+            command_with("switch_to_workspace", 1i64)
+        );
+        assert_eq!(
+            imported.modes["resize"]["Minus"],
+            command_with("resize_window_shrink", "smart")
+        );
+        assert_eq!(imported.modes["resize"]["Escape"], command("reload_config"));
+
+        assert!(warned(&imported, "start-at-login"));
+        assert!(warned(&imported, "flatten-workspace-tree"));
+        assert!(warned(&imported, "per-monitor"));
+        assert!(warned(&imported, "several commands"));
+        Config::parse(&imported.to_toml()).unwrap();
+    }
+
+    #[test]
+    fn yabai_config_rules_and_skhd_bindings_are_translated() {
+        let imported = import(
+            ImportSource::Yabai,
+            r#"
+            #!/usr/bin/env sh
+            yabai -m config layout bsp
+            yabai -m config window_gap 06
+            yabai -m config top_padding 12
+            yabai -m config window_shadow off
+            yabai -m rule --add app="^System Settings$" manage=off
+            yabai -m rule --add app="^Mail$" space=3 sticky=on
+
+            alt - h : yabai -m window --focus west
+            shift + alt - 2 : yabai -m window --space 2
+            cmd - return : open -a Terminal
+            alt - r : yabai -m space --rotate 90
+            "#,
+        )
+        .unwrap();
+
+        let config = &imported.config;
+        assert_eq!(config["settings"]["layout"]["mode"], toml::Value::from("bsp"));
+        assert_eq!(
+            config["settings"]["layout"]["gaps"]["inner"]["vertical"],
+            toml::Value::Float(6.0)
+        );
+        assert_eq!(
+            config["settings"]["layout"]["gaps"]["outer"]["top"],
+            toml::Value::Float(12.0)
+        );
+        let rules = &config["virtual_workspaces"]["app_rules"];
+        assert_eq!(
+            rules[0]["matcher"],
+            toml::Value::from("app~\"^System Settings$\"")
+        );
+        assert_eq!(rules[0]["floating"], toml::Value::Boolean(true));
+        assert_eq!(rules[1]["workspace"], toml::Value::Integer(2));
+        assert_eq!(
+            config["virtual_workspaces"]["workspace_names"],
+            toml::Value::from(vec!["1", "2", "3"])
+        );
+
+        assert_eq!(imported.keys["Alt + H"], command_with("move_focus", "left"));
+        assert_eq!(
+            imported.keys["Shift + Alt + 2"],
+            command_with("move_window_to_workspace", 1i64)
+        );
+        assert_eq!(
+            imported.keys["Meta + Enter"],
+            command_with("exec", "open -a Terminal")
+        );
+        assert!(!imported.keys.contains_key("Alt + R"));
+
+        assert!(warned(&imported, "window_shadow"));
+        assert!(warned(&imported, "sticky=on"));
+        assert!(warned(&imported, "--rotate"));
+        Config::parse(&imported.to_toml()).unwrap();
+    }
+
+    #[test]
+    fn shell_words_handle_quotes_and_comments() {
+        assert_eq!(
+            shell_words(r#"yabai -m rule --add title='a "b"' app="^A\"B$" # note"#),
+            [
+                "yabai",
+                "-m",
+                "rule",
+                "--add",
+                "title=a \"b\"",
+                "app=^A\"B$"
+            ]
+        );
+    }
+}
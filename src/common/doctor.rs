@@ -0,0 +1,264 @@
+//! Health checks behind `rift-cli doctor`.
+//!
+//! Most checks are about the system rather than rift's state, so they run on
+//! the IPC thread: permissions, the WindowServer connection and other window
+//! managers. The config actor checks the config file and the reactor only
+//! reports what it alone knows. Every failed check says how to fix it.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::server::ReactorHealthData;
+
+/// Tiling window managers that move the same windows rift does.
+const WINDOW_MANAGERS: &[(&str, &str)] = &[
+    ("com.amethyst.Amethyst", "Amethyst"),
+    ("bobko.aerospace", "AeroSpace"),
+];
+/// Window managers that run as a plain process without an app bundle.
+const WINDOW_MANAGER_PROCESSES: &[&str] = &["yabai"];
+/// Snapping tools that only move windows when asked, but can still be bound to
+/// the same shortcuts.
+const SNAPPING_TOOLS: &[(&str, &str)] = &[
+    ("com.knollsoft.Rectangle", "Rectangle"),
+    ("com.crowdcafe.windowmagnet", "Magnet"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    /// What to do about it, for checks that did not pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &str, message: impl Into<String>) -> Check {
+        Check {
+            name: name.to_string(),
+            status: CheckStatus::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn failed(
+        name: &str,
+        status: CheckStatus,
+        message: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Check {
+        Check {
+            name: name.to_string(),
+            status,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DoctorReport {
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    /// The worst status of any check.
+    pub fn status(&self) -> CheckStatus {
+        self.checks.iter().map(|check| check.status).max().unwrap_or(CheckStatus::Ok)
+    }
+
+    /// Renders the report as one line per check, followed by the fix.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for check in &self.checks {
+            let label = match check.status {
+                CheckStatus::Ok => "ok",
+                CheckStatus::Warning => "warning",
+                CheckStatus::Error => "error",
+            };
+            let _ = writeln!(out, "[{label}] {}: {}", check.name, check.message);
+            if let Some(fix) = &check.fix {
+                let _ = writeln!(out, "        fix: {fix}");
+            }
+        }
+        out
+    }
+}
+
+pub fn accessibility(trusted: bool) -> Check {
+    if trusted {
+        Check::ok("accessibility", "rift is allowed to control windows")
+    } else {
+        Check::failed(
+            "accessibility",
+            CheckStatus::Error,
+            "rift is not trusted for accessibility, so it cannot move windows",
+            "enable rift in System Settings > Privacy & Security > Accessibility, then restart it",
+        )
+    }
+}
+
+pub fn screen_recording(granted: bool) -> Check {
+    if granted {
+        Check::ok("screen recording", "window titles of other apps are readable")
+    } else {
+        Check::failed(
+            "screen recording",
+            CheckStatus::Warning,
+            "without screen recording permission some window titles are empty, so title-based \
+             app rules and previews may not match",
+            "enable rift in System Settings > Privacy & Security > Screen & System Audio \
+             Recording",
+        )
+    }
+}
+
+pub fn window_server(connected: bool) -> Check {
+    if connected {
+        Check::ok("window server", "connected to the WindowServer")
+    } else {
+        Check::failed(
+            "window server",
+            CheckStatus::Error,
+            "the WindowServer connection is not usable",
+            "restart rift; if that does not help, log out and back in",
+        )
+    }
+}
+
+/// `result` is the list of validation issues of the config file, or the error
+/// it failed to load with.
+pub fn config(result: Result<Vec<String>, String>) -> Check {
+    match result {
+        Ok(issues) if issues.is_empty() => Check::ok("config", "the config file is valid"),
+        Ok(issues) => Check::failed(
+            "config",
+            CheckStatus::Warning,
+            format!("the config file has problems: {}", issues.join("; ")),
+            "correct the listed settings; rift falls back to defaults for them",
+        ),
+        Err(error) => Check::failed(
+            "config",
+            CheckStatus::Error,
+            format!("the config file cannot be loaded: {error}"),
+            "fix the file; rift keeps running with the last config that loaded",
+        ),
+    }
+}
+
+/// Looks for other window managers among the running apps and processes.
+pub fn conflicting_apps(bundle_ids: &[String], process_names: &[String]) -> Check {
+    let running = |list: &[(&str, &'static str)]| -> Vec<&'static str> {
+        list.iter()
+            .filter(|(bundle_id, _)| bundle_ids.iter().any(|running| running == bundle_id))
+            .map(|(_, name)| *name)
+            .collect()
+    };
+    let mut managers = running(WINDOW_MANAGERS);
+    managers.extend(
+        WINDOW_MANAGER_PROCESSES
+            .iter()
+            .filter(|process| process_names.iter().any(|running| running == **process))
+            .copied(),
+    );
+    let snapping = running(SNAPPING_TOOLS);
+
+    if !managers.is_empty() {
+        Check::failed(
+            "other window managers",
+            CheckStatus::Error,
+            format!("{} will fight rift over window positions", managers.join(", ")),
+            "quit the other window manager or keep it from starting at login",
+        )
+    } else if !snapping.is_empty() {
+        Check::failed(
+            "other window managers",
+            CheckStatus::Warning,
+            format!("{} is running", snapping.join(", ")),
+            "make sure its shortcuts do not overlap with rift's",
+        )
+    } else {
+        Check::ok("other window managers", "none running")
+    }
+}
+
+/// `health` is `None` when the reactor did not answer in time.
+pub fn reactor(health: Option<&ReactorHealthData>) -> Vec<Check> {
+    let Some(health) = health else {
+        return vec![Check::failed(
+            "reactor",
+            CheckStatus::Error,
+            "the reactor did not respond",
+            "restart rift with `rift service restart` and include its log in a bug report",
+        )];
+    };
+    let mut checks = vec![Check::ok(
+        "reactor",
+        format!("managing {} windows", health.managed_windows),
+    )];
+    if !health.unresponsive_apps.is_empty() {
+        checks.push(Check::failed(
+            "apps",
+            CheckStatus::Warning,
+            format!(
+                "not responding to accessibility requests: {}",
+                health.unresponsive_apps.join(", ")
+            ),
+            "their windows are left alone until they recover; restart them if they do not",
+        ));
+    }
+    if health.stage_manager_cooperating {
+        checks.push(Check::failed(
+            "stage manager",
+            CheckStatus::Warning,
+            "Stage Manager is on, so tiled windows are left to it",
+            "turn Stage Manager off in Control Center, or set settings.stage_manager",
+        ));
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn other_window_managers_are_reported_by_severity() {
+        let none = conflicting_apps(&["com.apple.Safari".to_string()], &[]);
+        assert_eq!(none.status, CheckStatus::Ok);
+
+        let snapping = conflicting_apps(&["com.knollsoft.Rectangle".to_string()], &[]);
+        assert_eq!(snapping.status, CheckStatus::Warning);
+
+        let yabai =
+            conflicting_apps(&["com.knollsoft.Rectangle".to_string()], &["yabai".to_string()]);
+        assert_eq!(yabai.status, CheckStatus::Error);
+        assert!(yabai.message.contains("yabai"));
+    }
+
+    #[test]
+    fn report_status_is_the_worst_check() {
+        let mut report = DoctorReport {
+            checks: vec![accessibility(true), screen_recording(false)],
+        };
+        assert_eq!(report.status(), CheckStatus::Warning);
+        report.checks.push(config(Err("expected `=`".to_string())));
+        assert_eq!(report.status(), CheckStatus::Error);
+
+        let text = report.render();
+        assert!(text.starts_with("[ok] accessibility: "));
+        assert!(text.contains("[error] config: the config file cannot be loaded: expected `=`\n"));
+    }
+}
@@ -1,21 +1,140 @@
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
-use tracing_subscriber::layer::SubscriberExt;
+use tracing::Subscriber;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::Directive;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::{Layered, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{EnvFilter, Layer, Registry};
+use tracing_subscriber::{EnvFilter, Layer, Registry, reload};
 use tracing_timing::{Histogram, group};
 use tracing_tree::time::UtcDateTime;
 
+use crate::common::config::{LogFileSettings, LogRotation};
+
+type Filtered = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+type FileLayer = Box<dyn Layer<Filtered> + Send + Sync>;
+
+/// Handles for changing logging after `init_logging`.
+struct LogControl {
+    filter: reload::Handle<EnvFilter, Registry>,
+    file: reload::Handle<Option<FileLayer>, Filtered>,
+    /// Keeps the file writer's thread running.
+    file_guard: Mutex<Option<WorkerGuard>>,
+}
+
+static LOG_CONTROL: OnceLock<LogControl> = OnceLock::new();
+
 pub fn init_logging() {
+    let (filter, filter_handle) = reload::Layer::new(EnvFilter::from_default_env());
+    let (file, file_handle) = reload::Layer::new(None::<FileLayer>);
     tracing_subscriber::registry()
+        .with(filter)
+        .with(file)
         .with(tree_layer())
         .with(timing_layer())
-        .with(EnvFilter::from_default_env())
         .init();
+    let _ = LOG_CONTROL.set(LogControl {
+        filter: filter_handle,
+        file: file_handle,
+        file_guard: Mutex::new(None),
+    });
+}
+
+/// Starts writing JSON traces to `settings.path`, if set, and adds
+/// `settings.filter` to the active filter.
+pub fn configure_log_file(settings: &LogFileSettings) -> Result<(), String> {
+    let control = LOG_CONTROL.get().ok_or("logging is not initialized")?;
+    if let Some(filter) = &settings.filter {
+        add_directives(filter)?;
+    }
+    let Some(path) = &settings.path else {
+        return Ok(());
+    };
+
+    let path = expand_home(path);
+    let (dir, prefix) = match (path.parent(), path.file_name()) {
+        (Some(dir), Some(name)) => (dir.to_path_buf(), name.to_string_lossy().into_owned()),
+        _ => return Err(format!("invalid log file path {}", path.display())),
+    };
+    let rotation = match settings.rotation {
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+    };
+    let mut appender = RollingFileAppender::builder().rotation(rotation).filename_prefix(prefix);
+    if settings.max_files > 0 {
+        appender = appender.max_log_files(settings.max_files);
+    }
+    let appender = appender.build(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let layer: FileLayer = Box::new(
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_ansi(false)
+            .with_writer(writer),
+    );
+    control.file.reload(Some(layer)).map_err(|e| e.to_string())?;
+    *control.file_guard.lock().unwrap() = Some(guard);
+    Ok(())
+}
+
+/// Adds comma-separated `RUST_LOG`-style directives, such as
+/// `rift_wm::actor::reactor=trace`, to the active filter. A directive for a
+/// target that already has one replaces it.
+pub fn add_directives(directives: &str) -> Result<(), String> {
+    let control = LOG_CONTROL.get().ok_or("logging is not initialized")?;
+    let parsed = parse_directives(directives)?;
+    control
+        .filter
+        .modify(|filter| {
+            *filter = parsed.into_iter().fold(std::mem::take(filter), EnvFilter::add_directive);
+        })
+        .map_err(|e| e.to_string())
+}
+
+fn parse_directives(directives: &str) -> Result<Vec<Directive>, String> {
+    directives
+        .split(',')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(|directive| {
+            directive
+                .parse::<Directive>()
+                .map_err(|e| format!("invalid directive `{directive}`: {e}"))
+        })
+        .collect()
 }
 
-pub fn tree_layer() -> impl Layer<Registry> {
+/// Goes back to the filter given by `RUST_LOG` at startup.
+pub fn reset_filter() -> Result<(), String> {
+    let control = LOG_CONTROL.get().ok_or("logging is not initialized")?;
+    control.filter.reload(EnvFilter::from_default_env()).map_err(|e| e.to_string())
+}
+
+/// The active filter, in `RUST_LOG` syntax.
+pub fn current_filter() -> Option<String> {
+    LOG_CONTROL.get()?.filter.with_current(|filter| filter.to_string()).ok()
+}
+
+fn expand_home(path: &std::path::Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => dirs::home_dir().map_or_else(|| path.to_path_buf(), |home| home.join(rest)),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+pub fn tree_layer<S>() -> impl Layer<S>
+where S: Subscriber + for<'a> LookupSpan<'a> {
     tracing_tree::HierarchicalLayer::default()
         .with_indent_amount(2)
         .with_indent_lines(true)
@@ -79,3 +198,44 @@ pub fn trace_misc<T>(desc: &str, f: impl FnOnce() -> T) -> T {
     tracing::trace!(time = ?(end - start), "{desc}");
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directives_are_split_on_commas() {
+        let parsed = parse_directives(" rift_wm::actor::reactor=trace, ,warn").unwrap();
+        let parsed: Vec<String> = parsed.iter().map(ToString::to_string).collect();
+        assert_eq!(parsed, ["rift_wm::actor::reactor=trace", "warn"]);
+
+        assert!(parse_directives("").unwrap().is_empty());
+        let error = parse_directives("rift_wm=loud").unwrap_err();
+        assert!(error.contains("`rift_wm=loud`"), "{error}");
+    }
+
+    #[test]
+    fn log_file_settings_deserialize_with_defaults() {
+        let settings: LogFileSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, LogFileSettings::default());
+        assert_eq!(settings.rotation, LogRotation::Daily);
+        assert_eq!(settings.max_files, 7);
+
+        let settings: LogFileSettings = toml::from_str(
+            r#"
+            path = "~/.rift/logs/rift.log"
+            rotation = "hourly"
+            max_files = 0
+            filter = "rift_wm=debug"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.path, Some(PathBuf::from("~/.rift/logs/rift.log")));
+        assert_eq!(settings.rotation, LogRotation::Hourly);
+        assert_eq!(settings.max_files, 0);
+        assert_eq!(settings.filter.as_deref(), Some("rift_wm=debug"));
+
+        assert!(toml::from_str::<LogFileSettings>("rotation = \"weekly\"").is_err());
+        assert!(toml::from_str::<LogFileSettings>("level = \"debug\"").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! Runtime counters reported by `rift-cli query metrics`.
+//!
+//! The reactor, the animation actor and every app thread record into the same
+//! process-wide statics, so recording is a relaxed atomic add and never
+//! blocks the thread doing the work.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Upper bounds, in seconds, of the AX request latency buckets.
+const LATENCY_BUCKETS: [f64; 10] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 1.0,
+];
+
+/// Reactor events handled, including every command of a batch.
+pub static EVENTS_PROCESSED: Counter = Counter::new();
+/// Layout passes run by the reactor.
+pub static LAYOUT_UPDATES: Counter = Counter::new();
+/// Animation frames sent to app threads.
+pub static ANIMATION_FRAMES: Counter = Counter::new();
+/// Time app threads spend handling each request, which is mostly AX calls.
+pub static AX_REQUEST_LATENCY: Histogram = Histogram::new();
+
+#[derive(Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub const fn new() -> Self { Self(AtomicU64::new(0)) }
+
+    pub fn inc(&self) { self.add(1); }
+
+    pub fn add(&self, count: u64) { self.0.fetch_add(count, Ordering::Relaxed); }
+
+    pub fn get(&self) -> u64 { self.0.load(Ordering::Relaxed) }
+}
+
+#[derive(Default)]
+pub struct Histogram {
+    /// One count per bucket in [`LATENCY_BUCKETS`], plus one for anything
+    /// slower than the last bound.
+    buckets: [AtomicU64; LATENCY_BUCKETS.len() + 1],
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    pub const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicU64::new(0) }; LATENCY_BUCKETS.len() + 1],
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|&bound| secs <= bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let mut cumulative = 0;
+        let buckets = LATENCY_BUCKETS
+            .iter()
+            .zip(&self.buckets)
+            .map(|(&le, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                Bucket { le, count: cumulative }
+            })
+            .collect();
+        let count = cumulative + self.buckets[LATENCY_BUCKETS.len()].load(Ordering::Relaxed);
+        HistogramSnapshot {
+            buckets,
+            count,
+            sum_seconds: self.sum_micros.load(Ordering::Relaxed) as f64 / 1e6,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    pub events_processed: u64,
+    pub layout_updates: u64,
+    pub animation_frames: u64,
+    pub ax_request_latency: HistogramSnapshot,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct HistogramSnapshot {
+    /// Cumulative counts, as in Prometheus: each bucket includes the faster
+    /// ones before it.
+    pub buckets: Vec<Bucket>,
+    pub count: u64,
+    pub sum_seconds: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub le: f64,
+    pub count: u64,
+}
+
+pub fn snapshot() -> MetricsSnapshot {
+    MetricsSnapshot {
+        events_processed: EVENTS_PROCESSED.get(),
+        layout_updates: LAYOUT_UPDATES.get(),
+        animation_frames: ANIMATION_FRAMES.get(),
+        ax_request_latency: AX_REQUEST_LATENCY.snapshot(),
+    }
+}
+
+/// Renders a `query metrics` response in the Prometheus text exposition
+/// format.
+pub fn render_prometheus(metrics: &serde_json::Value) -> String {
+    let mut out = String::new();
+    let gauges = [
+        ("windows_managed", "Windows managed by rift."),
+        ("workspaces", "Virtual workspaces across all spaces."),
+        ("applications", "Applications rift is connected to."),
+        ("screens", "Connected screens."),
+    ];
+    for (name, help) in gauges {
+        if let Some(value) = metrics.get(name).and_then(serde_json::Value::as_u64) {
+            metric(&mut out, &format!("rift_{name}"), "gauge", help);
+            let _ = writeln!(out, "rift_{name} {value}");
+        }
+    }
+
+    let Some(runtime) = metrics
+        .get("runtime")
+        .and_then(|runtime| serde_json::from_value::<MetricsSnapshot>(runtime.clone()).ok())
+    else {
+        return out;
+    };
+    let counters = [
+        (
+            "events_processed",
+            "Reactor events handled.",
+            runtime.events_processed,
+        ),
+        ("layout_updates", "Layout passes run.", runtime.layout_updates),
+        (
+            "animation_frames",
+            "Animation frames sent.",
+            runtime.animation_frames,
+        ),
+    ];
+    for (name, help, value) in counters {
+        metric(&mut out, &format!("rift_{name}_total"), "counter", help);
+        let _ = writeln!(out, "rift_{name}_total {value}");
+    }
+
+    let latency = &runtime.ax_request_latency;
+    let name = "rift_ax_request_duration_seconds";
+    metric(
+        &mut out,
+        name,
+        "histogram",
+        "Time spent handling app thread requests.",
+    );
+    for bucket in &latency.buckets {
+        let _ = writeln!(out, "{name}_bucket{{le=\"{}\"}} {}", bucket.le, bucket.count);
+    }
+    let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", latency.count);
+    let _ = writeln!(out, "{name}_sum {}", latency.sum_seconds);
+    let _ = writeln!(out, "{name}_count {}", latency.count);
+    out
+}
+
+fn metric(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let histogram = Histogram::new();
+        histogram.record(Duration::from_micros(300));
+        histogram.record(Duration::from_millis(3));
+        histogram.record(Duration::from_secs(2));
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 3);
+        assert_eq!(snapshot.buckets[0], Bucket { le: 0.0005, count: 1 });
+        assert_eq!(snapshot.buckets[3], Bucket { le: 0.005, count: 2 });
+        assert_eq!(snapshot.buckets.last().unwrap().count, 2);
+    }
+
+    #[test]
+    fn prometheus_output_has_gauges_counters_and_histogram() {
+        let runtime = MetricsSnapshot {
+            layout_updates: 7,
+            ax_request_latency: HistogramSnapshot {
+                buckets: vec![Bucket { le: 0.001, count: 2 }],
+                count: 3,
+                sum_seconds: 0.5,
+            },
+            ..Default::default()
+        };
+        let metrics = serde_json::json!({
+            "windows_managed": 4,
+            "runtime": runtime,
+        });
+
+        let text = render_prometheus(&metrics);
+        assert!(text.contains("# TYPE rift_windows_managed gauge\nrift_windows_managed 4\n"));
+        assert!(text.contains("rift_layout_updates_total 7\n"));
+        assert!(text.contains("rift_ax_request_duration_seconds_bucket{le=\"0.001\"} 2\n"));
+        assert!(text.contains("rift_ax_request_duration_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("rift_ax_request_duration_seconds_count 3\n"));
+    }
+}
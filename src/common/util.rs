@@ -52,7 +52,9 @@ pub fn parse_command(command: &str) -> Vec<String> {
     parts
 }
 
-pub fn execute_startup_commands(commands: &[String]) {
+/// Runs `settings.run_on_start`, each command with `env` added to rift's own
+/// environment.
+pub fn execute_startup_commands(commands: &[String], env: &[(String, String)]) {
     if commands.is_empty() {
         return;
     }
@@ -73,9 +75,11 @@ pub fn execute_startup_commands(commands: &[String]) {
         let cmd_owned = cmd.to_string();
         let args_owned: Vec<String> = args.iter().map(|s| s.to_string()).collect();
         let command_str = command.clone();
+        let env = env.to_vec();
 
         std::thread::spawn(move || {
-            let output = std::process::Command::new(&cmd_owned).args(&args_owned).output();
+            let output =
+                std::process::Command::new(&cmd_owned).args(&args_owned).envs(env).output();
 
             match output {
                 Ok(output) => {
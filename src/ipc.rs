@@ -4,27 +4,38 @@ use std::time::Duration;
 use r#continue::continuation;
 use tracing::{error, info, trace};
 
+pub mod auth;
 pub mod cli_exec;
 pub mod protocol;
 pub mod subscriptions;
+pub mod websocket;
 
 pub use protocol::{RiftCommand, RiftRequest, RiftResponse};
 
-use crate::actor::config as config_actor;
-use crate::actor::reactor::{self, Event};
+use crate::actor::{config as config_actor, reactor};
+use crate::common::config::{ConfigCommand, IpcSettings};
+use crate::common::{doctor, log};
+use crate::ipc::auth::IpcAuth;
 use crate::ipc::subscriptions::SharedServerState;
+use crate::model::WindowMatcher;
+use crate::model::broadcast::BroadcastEvent;
+use crate::model::server::{CommandErrorKind, CommandResult, WindowQuery};
 use crate::sys::dispatch::block_on;
 use crate::sys::mach::{
     is_mach_server_registered, mach_allocate_reply_port, mach_deallocate_reply_port,
-    mach_msg_header_t, mach_receive_message_on_port, mach_send_request,
+    mach_msg_header_t, mach_peer, mach_receive_message_on_port, mach_send_request,
     mach_send_request_with_reply_port, mach_server_run, send_mach_reply,
 };
 
 type ClientPort = u32;
 
+/// How long a command may take before the client is told it is unavailable.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub fn run_mach_server(
     reactor: reactor::ReactorHandle,
     config_tx: config_actor::Sender,
+    ipc_settings: IpcSettings,
 ) -> Result<SharedServerState, String> {
     if is_mach_server_registered() {
         return Err(
@@ -39,7 +50,13 @@ pub fn run_mach_server(
 
     let thread_state = shared_state.clone();
     std::thread::spawn(move || {
-        let handler = MachHandler::new(reactor, config_tx, thread_state.clone());
+        let handler = MachHandler::new(
+            reactor,
+            config_tx,
+            thread_state.clone(),
+            IpcAuth::new(ipc_settings),
+            false,
+        );
         unsafe {
             mach_server_run(Box::into_raw(Box::new(handler)) as *mut _, handle_mach_request_c);
         }
@@ -50,6 +67,8 @@ pub fn run_mach_server(
 
 pub struct RiftMachClient {
     connected: bool,
+    token: Option<String>,
+    read_only: bool,
 }
 
 pub struct RiftMachSubscription {
@@ -81,7 +100,41 @@ impl Drop for RiftMachSubscription {
 }
 
 impl RiftMachClient {
-    pub fn connect() -> Result<Self, String> { Ok(RiftMachClient { connected: true }) }
+    pub fn connect() -> Result<Self, String> {
+        Ok(RiftMachClient {
+            connected: true,
+            token: None,
+            read_only: false,
+        })
+    }
+
+    /// Sends `token` with every request, for servers with `settings.ipc.token`.
+    pub fn with_token(mut self, token: String) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Asks the server to refuse anything but queries and subscriptions from
+    /// this client.
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    fn serialize_request(&self, request: &RiftRequest) -> Result<Vec<u8>, String> {
+        let result = if self.token.is_none() && !self.read_only {
+            serde_json::to_vec(request)
+        } else {
+            serde_json::to_vec(&serde_json::json!({
+                "authenticated": {
+                    "token": self.token,
+                    "read_only": self.read_only,
+                    "request": request,
+                }
+            }))
+        };
+        result.map_err(|e| format!("Failed to serialize request: {}", e))
+    }
 
     fn parse_response_buffer(response_buf: &[u8]) -> Result<RiftResponse, String> {
         let json_bytes = CStr::from_bytes_until_nul(response_buf)
@@ -97,8 +150,7 @@ impl RiftMachClient {
             return Err("Not connected".to_string());
         }
 
-        let request_json = serde_json::to_vec(request)
-            .map_err(|e| format!("Failed to serialize request: {}", e))?;
+        let request_json = self.serialize_request(request)?;
 
         let mut response_buf = Vec::with_capacity(256);
         let ok = unsafe {
@@ -126,8 +178,7 @@ impl RiftMachClient {
         };
 
         let request = RiftRequest::Subscribe { event: event.clone() };
-        let request_json = serde_json::to_vec(&request)
-            .map_err(|e| format!("Failed to serialize request: {}", e))?;
+        let request_json = self.serialize_request(&request)?;
 
         let mut response_buf = Vec::with_capacity(256);
         let ok = unsafe {
@@ -171,6 +222,9 @@ struct MachHandler {
     reactor: reactor::ReactorHandle,
     config_tx: config_actor::Sender,
     server_state: SharedServerState,
+    auth: IpcAuth,
+    /// Every client of this transport may only query and subscribe.
+    read_only: bool,
 }
 
 impl MachHandler {
@@ -178,17 +232,30 @@ impl MachHandler {
         reactor: reactor::ReactorHandle,
         config_tx: config_actor::Sender,
         server_state: SharedServerState,
+        auth: IpcAuth,
+        read_only: bool,
     ) -> Self {
         Self {
             reactor,
             config_tx,
             server_state,
+            auth,
+            read_only,
         }
     }
 
+    fn authorize(&self, request: RiftRequest) -> Result<RiftRequest, RiftResponse> {
+        self.auth
+            .authorize(request, self.read_only)
+            .map_err(|message| RiftResponse::Error {
+                error: serde_json::json!({ "message": message }),
+            })
+    }
+
     fn forget_config_query_sender(event: config_actor::Event) {
         match event {
             config_actor::Event::QueryConfig(response) => std::mem::forget(response),
+            config_actor::Event::CheckConfigFile(response) => std::mem::forget(response),
             config_actor::Event::ApplyConfig { response, .. } => std::mem::forget(response),
         }
     }
@@ -216,10 +283,84 @@ impl MachHandler {
         }
     }
 
+    /// Points a gap adjustment for one display at the display's UUID, so it
+    /// changes the same `per_display` entry whether the display was given by
+    /// UUID or by name.
+    fn resolve_gap_display(&self, mut cmd: ConfigCommand) -> Result<ConfigCommand, CommandResult> {
+        let (key, display_name) = match &mut cmd {
+            ConfigCommand::AdjustGaps {
+                display: Some(key),
+                display_name,
+                ..
+            }
+            | ConfigCommand::SetGaps {
+                display: Some(key),
+                display_name,
+                ..
+            } => (key, display_name),
+            _ => return Ok(cmd),
+        };
+        let displays = self.reactor.query_displays();
+        let Some(display) = displays.iter().find(|display| display.info.matches_display(key))
+        else {
+            return Err(CommandResult::error(
+                CommandErrorKind::InvalidCommand,
+                format!("No connected display matches {key:?}"),
+            ));
+        };
+        *key = display.info.display_uuid.clone();
+        *display_name = display.info.name.clone();
+        Ok(cmd)
+    }
+
+    fn apply_config_command(&self, cmd: ConfigCommand) -> CommandResult {
+        let cmd = match self.resolve_gap_display(cmd) {
+            Ok(cmd) => cmd,
+            Err(result) => return result,
+        };
+        match self.perform_config_query(|tx| config_actor::Event::ApplyConfig { cmd, response: tx })
+        {
+            Ok(Ok(())) => CommandResult::success("Config applied successfully"),
+            Ok(Err(msg)) => CommandResult::error(CommandErrorKind::Failed, msg),
+            Err(e) => {
+                error!("{}", e);
+                CommandResult::error(
+                    CommandErrorKind::Unavailable,
+                    format!("Failed to apply config: {}", e),
+                )
+            }
+        }
+    }
+
+    fn run_doctor(&self) -> doctor::DoctorReport {
+        use crate::sys::{accessibility, app, process, window_server};
+
+        let mut checks = vec![
+            doctor::accessibility(accessibility::has_accessibility_permission()),
+            doctor::screen_recording(window_server::has_screen_recording_permission()),
+            doctor::window_server(window_server::connection_is_usable()),
+            doctor::config(
+                self.perform_config_query(config_actor::Event::CheckConfigFile)
+                    .and_then(|result| result),
+            ),
+            doctor::conflicting_apps(&app::running_bundle_ids(), &process::process_names()),
+        ];
+        checks.extend(doctor::reactor(
+            self.reactor.query_health(Duration::from_secs(2)).as_ref(),
+        ));
+        doctor::DoctorReport { checks }
+    }
+
     fn handle_request(&self, request: RiftRequest, client_port: ClientPort) -> RiftResponse {
         trace!("Handling request: {:?} from client {}", request, client_port);
 
         match request {
+            RiftRequest::Subscribe { event } if !BroadcastEvent::is_subscribable(&event) => {
+                unknown_event_response(&event)
+            }
+            RiftRequest::SubscribeCli { event, .. } if !BroadcastEvent::is_subscribable(&event) => {
+                unknown_event_response(&event)
+            }
             RiftRequest::Subscribe { event } => {
                 let state = self.server_state.read();
                 state.subscribe_client(client_port, event.clone());
@@ -257,6 +398,9 @@ impl MachHandler {
                 let data = state.list_cli_subscriptions();
                 RiftResponse::Success { data }
             }
+            RiftRequest::Authenticated { .. } => RiftResponse::Error {
+                error: serde_json::json!({ "message": "Authenticated requests cannot be nested" }),
+            },
 
             RiftRequest::GetWorkspaces { space_id } => {
                 let workspaces =
@@ -273,10 +417,27 @@ impl MachHandler {
                 }
             }
 
-            RiftRequest::GetWindows { space_id } => {
+            RiftRequest::GetActivation => {
+                let activation = self.reactor.query_activation();
+                RiftResponse::Success {
+                    data: serde_json::to_value(activation).unwrap(),
+                }
+            }
+
+            RiftRequest::GetWindows { space_id, filter, sort } => {
                 let space_id = space_id.map(|id| crate::sys::screen::SpaceId::new(id));
+                let matcher = match filter.as_deref().map(WindowMatcher::parse).transpose() {
+                    Ok(matcher) => matcher,
+                    Err(e) => {
+                        return RiftResponse::Error {
+                            error: serde_json::json!({ "message": format!("Invalid window filter: {}", e) }),
+                        };
+                    }
+                };
 
-                let windows = self.reactor.query_windows(space_id);
+                let windows = self
+                    .reactor
+                    .query_windows_matching(space_id, WindowQuery { filter: matcher, sort });
                 RiftResponse::Success {
                     data: serde_json::to_value(windows).unwrap(),
                 }
@@ -313,6 +474,17 @@ impl MachHandler {
                     },
                 }
             }
+            RiftRequest::GetLayoutTree { space_id } => {
+                match self.reactor.query_layout_tree(space_id.map(crate::sys::screen::SpaceId::new))
+                {
+                    Some(tree) => RiftResponse::Success {
+                        data: serde_json::to_value(tree).unwrap(),
+                    },
+                    None => RiftResponse::Error {
+                        error: serde_json::json!({ "message": "No active layout for space" }),
+                    },
+                }
+            }
             RiftRequest::GetWorkspaceLayouts { space_id, workspace_id } => {
                 let workspace_layouts = self.reactor.query_workspace_layouts(
                     space_id.map(crate::sys::screen::SpaceId::new),
@@ -330,6 +502,66 @@ impl MachHandler {
                 }
             }
 
+            RiftRequest::GetFocusedWindow => {
+                let window = self.reactor.query_focused_window();
+                RiftResponse::Success {
+                    data: serde_json::to_value(window).unwrap(),
+                }
+            }
+
+            RiftRequest::GetActiveWorkspace { display } => {
+                let workspace = self.reactor.query_active_workspace_data(display);
+                RiftResponse::Success {
+                    data: serde_json::to_value(workspace).unwrap(),
+                }
+            }
+
+            RiftRequest::GetFocusHistory => {
+                let history = self.reactor.query_focus_history();
+                RiftResponse::Success {
+                    data: serde_json::to_value(history).unwrap(),
+                }
+            }
+
+            RiftRequest::GetIgnoredWindows => {
+                let ignored = self.reactor.query_ignored_windows();
+                RiftResponse::Success {
+                    data: serde_json::to_value(ignored).unwrap(),
+                }
+            }
+
+            RiftRequest::GetEventLog { tail } => {
+                let events = self.reactor.query_event_log(tail);
+                RiftResponse::Success {
+                    data: serde_json::to_value(events).unwrap(),
+                }
+            }
+
+            RiftRequest::Doctor => {
+                let report = self.run_doctor();
+                RiftResponse::Success {
+                    data: serde_json::to_value(report).unwrap(),
+                }
+            }
+
+            RiftRequest::LogLevel { directives, reset } => {
+                let changed = if reset {
+                    log::reset_filter()
+                } else if let Some(directives) = directives {
+                    log::add_directives(&directives)
+                } else {
+                    Ok(())
+                };
+                match changed {
+                    Ok(()) => RiftResponse::Success {
+                        data: serde_json::json!({ "filter": log::current_filter() }),
+                    },
+                    Err(message) => RiftResponse::Error {
+                        error: serde_json::json!({ "message": message }),
+                    },
+                }
+            }
+
             RiftRequest::GetMetrics => {
                 let metrics = self.reactor.query_metrics();
                 RiftResponse::Success { data: metrics }
@@ -337,7 +569,7 @@ impl MachHandler {
 
             RiftRequest::GetConfig => {
                 match self.perform_config_query(|tx| config_actor::Event::QueryConfig(tx)) {
-                    Ok(config) => match serde_json::to_value(&config) {
+                    Ok(config) => match serde_json::to_value(config.redacted()) {
                         Ok(value) => RiftResponse::Success { data: value },
                         Err(e) => {
                             error!("Failed to serialize config: {}", e);
@@ -355,65 +587,107 @@ impl MachHandler {
                 }
             }
 
-            RiftRequest::ExecuteCommand { command, args } => {
-                match serde_json::from_str::<RiftCommand>(&command) {
+            RiftRequest::ExecuteBatch { commands } => {
+                let mut batch = Vec::with_capacity(commands.len());
+                for (index, command) in commands.iter().enumerate() {
+                    let invalid = match serde_json::from_str::<RiftCommand>(command) {
+                        Ok(RiftCommand::Reactor(reactor_command)) => {
+                            batch.push(reactor_command);
+                            continue;
+                        }
+                        Ok(RiftCommand::Config(_)) => format!(
+                            "Batch command {} is a config command; config commands cannot be batched",
+                            index
+                        ),
+                        Err(e) => format!("Invalid batch command {}: {}", index, e),
+                    };
+                    return command_response(CommandResult::error(
+                        CommandErrorKind::InvalidCommand,
+                        invalid,
+                    ));
+                }
+
+                let count = batch.len();
+                let results = match self.reactor.execute_batch(batch, COMMAND_TIMEOUT) {
+                    Ok(results) => results,
+                    Err(result) => {
+                        error!("Failed to execute command batch: {}", result.message);
+                        return command_response(result);
+                    }
+                };
+                let data = serde_json::json!({ "executed": count, "results": results });
+                // Commands rolled back with the batch are not the cause.
+                let failed = results.iter().position(|result| {
+                    !result.ok && result.error != Some(CommandErrorKind::BatchAborted)
+                });
+                match failed.or_else(|| results.iter().position(|result| !result.ok)) {
+                    None => RiftResponse::Success { data },
+                    Some(index) => {
+                        let mut error = data;
+                        error["message"] = serde_json::json!(format!(
+                            "Batch command {} failed: {}",
+                            index, results[index].message
+                        ));
+                        error["error"] = serde_json::json!(results[index].error);
+                        RiftResponse::Error { error }
+                    }
+                }
+            }
+
+            RiftRequest::ExecuteCommand { command, dry_run: true, .. } => {
+                let result = match serde_json::from_str::<RiftCommand>(&command) {
+                    Ok(RiftCommand::Reactor(reactor::Command::Layout(cmd))) => {
+                        self.reactor.dry_run_command(reactor::Command::Layout(cmd), COMMAND_TIMEOUT)
+                    }
+                    Ok(_) => CommandResult::error(
+                        CommandErrorKind::InvalidCommand,
+                        "Only layout commands can be dry-run",
+                    ),
+                    Err(e) => CommandResult::error(
+                        CommandErrorKind::InvalidCommand,
+                        format!("Invalid command format: {}", e),
+                    ),
+                };
+                command_response(result)
+            }
+
+            RiftRequest::ExecuteCommand { command, args, dry_run: false } => {
+                let result = match serde_json::from_str::<RiftCommand>(&command) {
                     Ok(RiftCommand::Config(_)) => {
                         if args.len() >= 2 && args[0] == "__apply_config__" {
-                            match serde_json::from_str::<crate::common::config::ConfigCommand>(
-                                &args[1],
-                            ) {
-                                Ok(cfg_cmd) => match self.perform_config_query(|tx| {
-                                    config_actor::Event::ApplyConfig { cmd: cfg_cmd, response: tx }
-                                }) {
-                                    Ok(apply_result) => match apply_result {
-                                        Ok(()) => RiftResponse::Success {
-                                            data: serde_json::json!("Config applied successfully"),
-                                        },
-                                        Err(msg) => RiftResponse::Error {
-                                            error: serde_json::json!({ "message": msg }),
-                                        },
-                                    },
-                                    Err(e) => {
-                                        error!("{}", e);
-                                        RiftResponse::Error {
-                                            error: serde_json::json!({ "message": format!("Failed to apply config: {}", e) }),
-                                        }
-                                    }
-                                },
+                            match serde_json::from_str::<ConfigCommand>(&args[1]) {
+                                Ok(cfg_cmd) => self.apply_config_command(cfg_cmd),
                                 Err(e) => {
                                     error!("Failed to parse config command from args: {}", e);
-                                    RiftResponse::Error {
-                                        error: serde_json::json!({ "message": format!("Invalid config command in args: {}", e) }),
-                                    }
+                                    CommandResult::error(
+                                        CommandErrorKind::InvalidCommand,
+                                        format!("Invalid config command in args: {}", e),
+                                    )
                                 }
                             }
                         } else {
-                            RiftResponse::Success {
-                                data: serde_json::json!("No-op config command"),
-                            }
+                            CommandResult::success("No-op config command")
                         }
                     }
                     Ok(RiftCommand::Reactor(reactor_command)) => {
-                        let event = Event::Command(reactor_command);
-
-                        if let Err(e) = self.reactor.try_send(event) {
-                            error!("Failed to send command to reactor: {}", e);
-                            return RiftResponse::Error {
-                                error: serde_json::json!({ "message": "Failed to execute command", "details": format!("{}", e) }),
-                            };
-                        }
-
-                        RiftResponse::Success {
-                            data: serde_json::json!("Command executed successfully"),
+                        let result = self.reactor.execute_command(reactor_command, COMMAND_TIMEOUT);
+                        if matches!(
+                            result.error,
+                            Some(CommandErrorKind::Unavailable | CommandErrorKind::TimedOut)
+                        ) {
+                            error!("Failed to execute command: {}", result.message);
                         }
+                        result
                     }
                     Err(e) => {
                         error!("Failed to parse command: {}", e);
-                        RiftResponse::Error {
-                            error: serde_json::json!({ "message": format!("Invalid command format: {}", e) }),
-                        }
+                        CommandResult::error(
+                            CommandErrorKind::InvalidCommand,
+                            format!("Invalid command format: {}", e),
+                        )
                     }
-                }
+                };
+                command_response(result)
             }
         }
     }
@@ -424,6 +698,7 @@ unsafe extern "C" fn handle_mach_request_c(
     message: *mut c_char,
     len: u32,
     original_msg: *mut mach_msg_header_t,
+    peer: *const mach_peer,
 ) {
     if context.is_null() {
         error!("Invalid context pointer");
@@ -456,6 +731,15 @@ unsafe extern "C" fn handle_mach_request_c(
 
     let client_port = unsafe { (*original_msg).msgh_remote_port };
 
+    let peer = unsafe { peer.as_ref() }.copied();
+    if let Err(message) = handler.auth.check_peer(peer) {
+        info!("Rejected IPC request: {}", message);
+        send_response(original_msg, &RiftResponse::Error {
+            error: serde_json::json!({ "message": message }),
+        });
+        return;
+    }
+
     let request: RiftRequest = match serde_json::from_str(message_str) {
         Ok(req) => req,
         Err(e) => {
@@ -468,10 +752,33 @@ unsafe extern "C" fn handle_mach_request_c(
         }
     };
 
-    let response = handler.handle_request(request, client_port);
+    let response = match handler.authorize(request) {
+        Ok(request) => handler.handle_request(request, client_port),
+        Err(response) => response,
+    };
     send_response(original_msg, &response);
 }
 
+/// Successful results go in `data` and failures in `error`, both with the
+/// full [`CommandResult`] so scripts can branch on `error`.
+fn command_response(result: CommandResult) -> RiftResponse {
+    let value = serde_json::to_value(&result).unwrap();
+    if result.ok {
+        RiftResponse::Success { data: value }
+    } else {
+        RiftResponse::Error { error: value }
+    }
+}
+
+fn unknown_event_response(event: &str) -> RiftResponse {
+    RiftResponse::Error {
+        error: serde_json::json!({
+            "message": format!("Unknown event '{}'", event),
+            "valid_events": BroadcastEvent::NAMES,
+        }),
+    }
+}
+
 fn send_response(original_msg: *mut mach_msg_header_t, response: &RiftResponse) {
     let mut response_json = serde_json::to_vec(response).unwrap();
 
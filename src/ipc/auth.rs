@@ -0,0 +1,186 @@
+//! Access control for IPC requests.
+//!
+//! Mach clients can be restricted to the user running rift (plus an
+//! allowlist of executable paths); the WebSocket bridge does not start in that
+//! case, since its peers cannot be identified. Any client can be required to
+//! present a token, and WebSocket clients need one to run commands. The read-only token, the WebSocket `read_only` setting and a
+//! client's own `read_only` flag all limit a request to queries and
+//! subscriptions.
+
+use super::RiftRequest;
+use crate::common::config::IpcSettings;
+use crate::sys::mach::mach_peer;
+use crate::sys::process;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Full,
+    ReadOnly,
+}
+
+pub struct IpcAuth {
+    settings: IpcSettings,
+    uid: u32,
+}
+
+impl IpcAuth {
+    pub fn new(settings: IpcSettings) -> Self {
+        Self {
+            settings,
+            uid: nix::unistd::geteuid().as_raw(),
+        }
+    }
+
+    /// Checks who sent a Mach request. `None` means the message carried no
+    /// audit trailer, which is only accepted while `same_user_only` is off.
+    pub fn check_peer(&self, peer: Option<mach_peer>) -> Result<(), String> {
+        if !self.settings.same_user_only {
+            return Ok(());
+        }
+        let Some(peer) = peer else {
+            return Err("Permission denied: could not identify the client".into());
+        };
+        if peer.uid == self.uid || self.is_allowed_process(peer.pid) {
+            return Ok(());
+        }
+        Err(format!(
+            "Permission denied: client pid {} runs as uid {}",
+            peer.pid, peer.uid
+        ))
+    }
+
+    fn is_allowed_process(&self, pid: i32) -> bool {
+        if self.settings.allowed_processes.is_empty() {
+            return false;
+        }
+        let Some(path) = process::process_path(pid) else {
+            return false;
+        };
+        self.settings.allowed_processes.iter().any(|allowed| *allowed == path)
+    }
+
+    /// Unwraps an [`RiftRequest::Authenticated`] envelope and checks its
+    /// token, returning the request to run. `read_only_transport` is set for
+    /// connections that may never run commands.
+    pub fn authorize(
+        &self,
+        request: RiftRequest,
+        read_only_transport: bool,
+    ) -> Result<RiftRequest, String> {
+        let (token, client_read_only, request) = match request {
+            RiftRequest::Authenticated { token, read_only, request } => {
+                (token, read_only, *request)
+            }
+            request => (None, false, request),
+        };
+        if matches!(request, RiftRequest::Authenticated { .. }) {
+            return Err("Nested authenticated requests are not allowed".into());
+        }
+
+        let mut access = self.access_for_token(token.as_deref())?;
+        if read_only_transport || client_read_only {
+            access = Access::ReadOnly;
+        }
+        if access == Access::ReadOnly && !request.is_read_only() {
+            return Err("Permission denied: this client may only query and subscribe".into());
+        }
+        Ok(request)
+    }
+
+    fn access_for_token(&self, token: Option<&str>) -> Result<Access, String> {
+        let Some(required) = self.settings.token.as_deref() else {
+            return Ok(Access::Full);
+        };
+        match token {
+            Some(token) if tokens_match(token, required) => Ok(Access::Full),
+            Some(token)
+                if self
+                    .settings
+                    .read_only_token
+                    .as_deref()
+                    .is_some_and(|read_only| tokens_match(token, read_only)) =>
+            {
+                Ok(Access::ReadOnly)
+            }
+            Some(_) => Err("Permission denied: invalid token".into()),
+            None => Err("Permission denied: this server requires a token".into()),
+        }
+    }
+}
+
+/// Compares without returning early, so response timing does not reveal how
+/// much of a guessed token was right.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth(settings: IpcSettings) -> IpcAuth { IpcAuth { settings, uid: 501 } }
+
+    fn wrapped(token: Option<&str>, read_only: bool, request: RiftRequest) -> RiftRequest {
+        RiftRequest::Authenticated {
+            token: token.map(str::to_string),
+            read_only,
+            request: Box::new(request),
+        }
+    }
+
+    fn execute() -> RiftRequest {
+        RiftRequest::ExecuteCommand {
+            command: "{}".into(),
+            args: Vec::new(),
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn tokens_grant_full_or_read_only_access() {
+        let auth = auth(IpcSettings {
+            token: Some("secret".into()),
+            read_only_token: Some("status".into()),
+            ..Default::default()
+        });
+
+        assert!(auth.authorize(execute(), false).is_err());
+        assert!(auth.authorize(wrapped(Some("wrong"), false, execute()), false).is_err());
+        assert!(auth.authorize(wrapped(Some("secret"), false, execute()), false).is_ok());
+        assert!(auth.authorize(wrapped(Some("status"), false, execute()), false).is_err());
+        assert!(
+            auth.authorize(wrapped(Some("status"), false, RiftRequest::GetDisplays), false)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn read_only_clients_and_transports_cannot_run_commands() {
+        let auth = auth(IpcSettings::default());
+
+        assert!(auth.authorize(execute(), false).is_ok());
+        assert!(auth.authorize(execute(), true).is_err());
+        assert!(auth.authorize(wrapped(None, true, execute()), false).is_err());
+        assert!(auth.authorize(RiftRequest::GetDisplays, true).is_ok());
+        assert!(
+            auth.authorize(
+                wrapped(None, false, wrapped(None, false, RiftRequest::GetDisplays)),
+                false
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn same_user_only_rejects_other_users() {
+        let auth = auth(IpcSettings {
+            same_user_only: true,
+            ..Default::default()
+        });
+
+        assert!(auth.check_peer(Some(mach_peer { uid: 501, pid: 10 })).is_ok());
+        assert!(auth.check_peer(Some(mach_peer { uid: 0, pid: 10 })).is_err());
+        assert!(auth.check_peer(None).is_err());
+    }
+}
@@ -23,10 +23,15 @@ pub trait CliExecutor: Send + Sync + 'static {
     ) -> Result<i32, std::io::Error>;
 }
 
-pub struct DefaultCliExecutor;
+pub struct DefaultCliExecutor {
+    env: Vec<(String, String)>,
+}
 
 impl DefaultCliExecutor {
-    pub fn new() -> Self { Self {} }
+    pub fn new() -> Self { Self { env: Vec::new() } }
+
+    /// Adds `env` to every spawned command, after the event's variables.
+    pub fn with_env(env: Vec<(String, String)>) -> Self { Self { env } }
 }
 
 impl CliExecutor for DefaultCliExecutor {
@@ -51,6 +56,26 @@ impl CliExecutor for DefaultCliExecutor {
                     env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
                 }
             }
+            BroadcastEvent::WorkspaceRenamed {
+                workspace_id,
+                workspace_index,
+                previous_name,
+                workspace_name,
+                space_id,
+                display_uuid,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "workspace_renamed".into());
+                env_vars.insert("RIFT_WORKSPACE_ID".into(), workspace_id.to_string());
+                if let Some(workspace_index) = workspace_index {
+                    env_vars.insert("RIFT_WORKSPACE_INDEX".into(), workspace_index.to_string());
+                }
+                env_vars.insert("RIFT_PREVIOUS_WORKSPACE_NAME".into(), previous_name.clone());
+                env_vars.insert("RIFT_WORKSPACE_NAME".into(), workspace_name.clone());
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
             BroadcastEvent::WindowsChanged {
                 workspace_id,
                 workspace_name,
@@ -117,6 +142,80 @@ impl CliExecutor for DefaultCliExecutor {
                     env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
                 }
             }
+            BroadcastEvent::WindowFocused {
+                window_id,
+                title,
+                bundle_id,
+                workspace_id,
+                workspace_name,
+                space_id,
+                display_uuid,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "window_focused".into());
+                env_vars.insert("RIFT_WINDOW_ID".into(), window_id.to_debug_string());
+                env_vars.insert("RIFT_WINDOW_TITLE".into(), title.clone());
+                if let Some(bundle_id) = bundle_id.as_ref() {
+                    env_vars.insert("RIFT_BUNDLE_ID".into(), bundle_id.clone());
+                }
+                env_vars.insert("RIFT_WORKSPACE_ID".into(), workspace_id.to_string());
+                env_vars.insert("RIFT_WORKSPACE_NAME".into(), workspace_name.clone());
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
+            BroadcastEvent::LayoutChanged {
+                workspace_id,
+                workspace_name,
+                layout_mode,
+                space_id,
+                display_uuid,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "layout_changed".into());
+                env_vars.insert("RIFT_WORKSPACE_ID".into(), workspace_id.to_string());
+                env_vars.insert("RIFT_WORKSPACE_NAME".into(), workspace_name.clone());
+                env_vars.insert("RIFT_LAYOUT_MODE".into(), layout_mode.to_string());
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
+            BroadcastEvent::WindowMoved {
+                window_id,
+                frame,
+                space_id,
+                display_uuid,
+            }
+            | BroadcastEvent::WindowResized {
+                window_id,
+                frame,
+                space_id,
+                display_uuid,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), event.name().into());
+                env_vars.insert("RIFT_WINDOW_ID".into(), window_id.to_debug_string());
+                env_vars.insert("RIFT_WINDOW_X".into(), frame.origin.x.to_string());
+                env_vars.insert("RIFT_WINDOW_Y".into(), frame.origin.y.to_string());
+                env_vars.insert("RIFT_WINDOW_WIDTH".into(), frame.size.width.to_string());
+                env_vars.insert("RIFT_WINDOW_HEIGHT".into(), frame.size.height.to_string());
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
+            BroadcastEvent::AppUnresponsive { pid, bundle_id }
+            | BroadcastEvent::AppRecovered { pid, bundle_id } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), event.name().into());
+                env_vars.insert("RIFT_PID".into(), pid.to_string());
+                if let Some(bundle_id) = bundle_id.as_ref() {
+                    env_vars.insert("RIFT_BUNDLE_ID".into(), bundle_id.clone());
+                }
+            }
+            BroadcastEvent::StageManagerChanged { enabled, cooperating } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), event.name().into());
+                env_vars.insert("RIFT_STAGE_MANAGER".into(), enabled.to_string());
+                env_vars.insert("RIFT_COOPERATING".into(), cooperating.to_string());
+            }
         }
 
         let event_json = match serde_json::to_string(event) {
@@ -130,6 +229,7 @@ impl CliExecutor for DefaultCliExecutor {
             }
         };
         env_vars.insert("RIFT_EVENT_JSON".to_string(), event_json.clone());
+        env_vars.extend(self.env.iter().cloned());
 
         let command = subscription.command.clone();
         let mut args = subscription.args.clone();
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::actor::reactor::DisplaySelector;
+use crate::model::server::WindowSort;
+
 #[non_exhaustive]
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "snake_case")]
@@ -9,25 +12,72 @@ pub enum RiftRequest {
         space_id: Option<u64>,
     },
     GetDisplays,
+    /// Which displays and spaces Rift currently manages.
+    GetActivation,
     GetWindows {
         space_id: Option<u64>,
+        /// Matcher expression (see `model::matcher`) restricting the returned windows.
+        #[serde(default)]
+        filter: Option<String>,
+        #[serde(default)]
+        sort: Option<WindowSort>,
     },
     GetWindowInfo {
         window_id: String,
     },
+    /// Just the focused window, cheap enough to poll from a status bar.
+    GetFocusedWindow,
+    /// The active workspace of a display (the focused one by default),
+    /// without its windows.
+    GetActiveWorkspace {
+        #[serde(default)]
+        display: Option<DisplaySelector>,
+    },
     GetLayoutState {
         space_id: u64,
     },
+    GetLayoutTree {
+        space_id: Option<u64>,
+    },
     GetWorkspaceLayouts {
         space_id: Option<u64>,
         workspace_id: Option<usize>,
     },
     GetApplications,
+    /// Recently focused windows across all displays and workspaces, most recent first.
+    GetFocusHistory,
+    /// Windows Rift knows about but does not manage, with the reason for each.
+    GetIgnoredWindows,
+    /// The last `tail` events the reactor handled, oldest first.
+    GetEventLog {
+        #[serde(default = "default_event_log_tail")]
+        tail: usize,
+    },
     GetMetrics,
     GetConfig,
+    /// Adds `RUST_LOG`-style directives to the log filter, or goes back to
+    /// `RUST_LOG` with `reset`, and answers with the active filter. With
+    /// neither it only reports the filter.
+    LogLevel {
+        #[serde(default)]
+        directives: Option<String>,
+        #[serde(default)]
+        reset: bool,
+    },
+    /// Permission, config and conflict checks for `rift-cli doctor`.
+    Doctor,
     ExecuteCommand {
         command: String,
         args: Vec<String>,
+        /// Report the frames a layout command would change instead of
+        /// running it.
+        #[serde(default)]
+        dry_run: bool,
+    },
+    /// Serialized [`RiftCommand`]s run in order with one layout update at the
+    /// end. Nothing runs unless every command parses and succeeds.
+    ExecuteBatch {
+        commands: Vec<String>,
     },
     Subscribe {
         event: String,
@@ -44,6 +94,55 @@ pub enum RiftRequest {
         event: String,
     },
     ListCliSubscriptions,
+    /// Another request with credentials. `read_only` lets a client give up
+    /// the right to run commands, e.g. a status bar that only polls.
+    Authenticated {
+        #[serde(default)]
+        token: Option<String>,
+        #[serde(default)]
+        read_only: bool,
+        request: Box<RiftRequest>,
+    },
+}
+
+fn default_event_log_tail() -> usize { 50 }
+
+impl RiftRequest {
+    /// Whether the request only reads state or manages event subscriptions
+    /// for the calling client. CLI subscriptions run commands, so they are
+    /// not read-only; dry runs change nothing, so they are.
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            RiftRequest::GetWorkspaces { .. }
+            | RiftRequest::GetDisplays
+            | RiftRequest::GetActivation
+            | RiftRequest::GetWindows { .. }
+            | RiftRequest::GetWindowInfo { .. }
+            | RiftRequest::GetFocusedWindow
+            | RiftRequest::GetActiveWorkspace { .. }
+            | RiftRequest::GetLayoutState { .. }
+            | RiftRequest::GetLayoutTree { .. }
+            | RiftRequest::GetWorkspaceLayouts { .. }
+            | RiftRequest::GetApplications
+            | RiftRequest::GetFocusHistory
+            | RiftRequest::GetIgnoredWindows
+            | RiftRequest::GetEventLog { .. }
+            | RiftRequest::GetMetrics
+            | RiftRequest::GetConfig
+            | RiftRequest::Doctor
+            | RiftRequest::Subscribe { .. }
+            | RiftRequest::Unsubscribe { .. }
+            | RiftRequest::ListCliSubscriptions
+            | RiftRequest::ExecuteCommand { dry_run: true, .. }
+            | RiftRequest::LogLevel { directives: None, reset: false } => true,
+            RiftRequest::ExecuteCommand { .. }
+            | RiftRequest::ExecuteBatch { .. }
+            | RiftRequest::SubscribeCli { .. }
+            | RiftRequest::UnsubscribeCli { .. }
+            | RiftRequest::LogLevel { .. }
+            | RiftRequest::Authenticated { .. } => false,
+        }
+    }
 }
 
 #[non_exhaustive]
@@ -3,7 +3,7 @@ use std::os::raw::c_char;
 use std::sync::Arc;
 use std::thread;
 
-use crossbeam_channel::{Sender, TrySendError, bounded};
+use crossbeam_channel::{Receiver, Sender, TrySendError, bounded};
 use dashmap::DashMap;
 use dashmap::mapref::entry::Entry;
 use parking_lot::{Mutex, RwLock};
@@ -26,12 +26,14 @@ pub struct ServerState {
     subscriptions_by_client: Arc<DashMap<ClientPort, Vec<String>>>,
     subscriptions_by_event: Arc<DashMap<String, Vec<ClientPort>>>,
     cli_subscriptions: Arc<Mutex<HashMap<String, Vec<CliSubscription>>>>,
+    event_listeners: Mutex<Vec<Sender<BroadcastEvent>>>,
     event_dispatch_tx: Sender<DispatchBatch>,
 }
 
 pub type SharedServerState = Arc<RwLock<ServerState>>;
 
 const EVENT_DISPATCH_QUEUE_CAPACITY: usize = 4096;
+const EVENT_LISTENER_QUEUE_CAPACITY: usize = 1024;
 
 struct DispatchBatch {
     event_json: String,
@@ -59,6 +61,7 @@ impl ServerState {
             subscriptions_by_client,
             subscriptions_by_event,
             cli_subscriptions,
+            event_listeners: Mutex::new(Vec::new()),
             event_dispatch_tx,
         }
     }
@@ -176,18 +179,34 @@ impl ServerState {
         })
     }
 
+    /// Receive every published event in-process. The listener is dropped
+    /// once its receiver goes away.
+    pub fn add_event_listener(&self) -> Receiver<BroadcastEvent> {
+        let (tx, rx) = bounded(EVENT_LISTENER_QUEUE_CAPACITY);
+        self.event_listeners.lock().push(tx);
+        rx
+    }
+
     pub fn publish(&self, event: BroadcastEvent) {
+        self.forward_event_to_listeners(&event);
         self.forward_event_to_cli_subscribers(event.clone());
         self.forward_event_to_subscribers(event);
     }
 
+    fn forward_event_to_listeners(&self, event: &BroadcastEvent) {
+        let mut listeners = self.event_listeners.lock();
+        listeners.retain(|tx| match tx.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                warn!("Dropping event for in-process listener: queue full");
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+    }
+
     fn forward_event_to_subscribers(&self, event: BroadcastEvent) {
-        let event_name = match &event {
-            BroadcastEvent::WorkspaceChanged { .. } => "workspace_changed",
-            BroadcastEvent::WindowsChanged { .. } => "windows_changed",
-            BroadcastEvent::WindowTitleChanged { .. } => "window_title_changed",
-            BroadcastEvent::StacksChanged { .. } => "stacks_changed",
-        };
+        let event_name = event.name();
 
         let mut targets: HashSet<ClientPort> = HashSet::default();
         if let Some(clients) = self.subscriptions_by_event.get(event_name) {
@@ -230,12 +249,7 @@ impl ServerState {
     }
 
     fn forward_event_to_cli_subscribers(&self, event: BroadcastEvent) {
-        let event_name = match &event {
-            BroadcastEvent::WorkspaceChanged { .. } => "workspace_changed",
-            BroadcastEvent::WindowsChanged { .. } => "windows_changed",
-            BroadcastEvent::WindowTitleChanged { .. } => "window_title_changed",
-            BroadcastEvent::StacksChanged { .. } => "stacks_changed",
-        };
+        let event_name = event.name();
 
         // Collect relevant subscriptions without full HashMap clone
         let mut relevant: Vec<CliSubscription> = Vec::new();
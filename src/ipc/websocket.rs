@@ -0,0 +1,345 @@
+//! WebSocket bridge for the IPC protocol.
+//!
+//! Each text frame carries one JSON [`RiftRequest`] and is answered with one
+//! JSON [`RiftResponse`], exactly as over Mach. Subscriptions are tracked per
+//! connection and matching events are pushed as JSON text frames.
+//!
+//! Any web page can open a connection to localhost, so handshakes from
+//! browser origins outside `allowed_origins` are refused, and CLI
+//! subscriptions, which run commands, are never offered here. Any local
+//! process can connect as well, so commands are only run when `ipc.token` is
+//! set, and at most [`MAX_CONNECTIONS`] clients are served at once.
+
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tracing::{debug, error, info, warn};
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tungstenite::http::StatusCode;
+use tungstenite::{Message, WebSocket};
+
+use super::{MachHandler, RiftRequest, RiftResponse, unknown_event_response};
+use crate::actor::{config as config_actor, reactor};
+use crate::common::config::{IpcSettings, WebSocketSettings};
+use crate::ipc::auth::IpcAuth;
+use crate::ipc::subscriptions::SharedServerState;
+use crate::model::broadcast::BroadcastEvent;
+
+/// How long a connection blocks on reads before flushing pending events.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Clients served at once. Each one gets its own thread; further connections
+/// are closed until one of them leaves.
+const MAX_CONNECTIONS: usize = 16;
+
+/// Start listening on `127.0.0.1:port`. Binding happens before returning so
+/// that a port conflict is reported to the caller. Refuses to start while
+/// `ipc.same_user_only` is set, since a TCP peer's user cannot be checked.
+pub fn run_websocket_server(
+    settings: &WebSocketSettings,
+    ipc_settings: IpcSettings,
+    reactor: reactor::ReactorHandle,
+    config_tx: config_actor::Sender,
+    server_state: SharedServerState,
+) -> Result<(), String> {
+    if ipc_settings.same_user_only {
+        return Err(
+            "Not starting the WebSocket server: ipc.same_user_only is set, and the user behind a WebSocket connection cannot be checked"
+                .into(),
+        );
+    }
+    let port = settings.port;
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .map_err(|e| format!("Failed to bind WebSocket server on port {port}: {e}"))?;
+    info!("WebSocket server listening on ws://127.0.0.1:{}", port);
+
+    let read_only = transport_read_only(settings, &ipc_settings);
+    if read_only && !settings.read_only {
+        warn!("ipc.token is not set, so WebSocket clients may only query and subscribe");
+    }
+    let handler = Arc::new(MachHandler::new(
+        reactor,
+        config_tx,
+        server_state.clone(),
+        IpcAuth::new(ipc_settings),
+        read_only,
+    ));
+    let allowed_origins: Arc<[String]> = settings.allowed_origins.clone().into();
+    let active = Arc::new(AtomicUsize::new(0));
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Failed to accept WebSocket connection: {}", e);
+                    continue;
+                }
+            };
+            let Some(slot) = ConnectionSlot::acquire(&active) else {
+                warn!(
+                    "Refusing WebSocket connection from {:?}: {} clients already connected",
+                    stream.peer_addr().ok(),
+                    MAX_CONNECTIONS
+                );
+                continue;
+            };
+            let handler = handler.clone();
+            let server_state = server_state.clone();
+            let allowed_origins = allowed_origins.clone();
+            std::thread::spawn(move || {
+                serve_connection(stream, &handler, &server_state, &allowed_origins);
+                drop(slot);
+            });
+        }
+    });
+
+    Ok(())
+}
+
+/// Nothing identifies the process behind a TCP connection, so without a
+/// token any local process could run commands.
+fn transport_read_only(settings: &WebSocketSettings, ipc_settings: &IpcSettings) -> bool {
+    settings.read_only || ipc_settings.token.is_none()
+}
+
+/// One of the [`MAX_CONNECTIONS`] places, given back when dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn acquire(active: &Arc<AtomicUsize>) -> Option<Self> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < MAX_CONNECTIONS).then_some(n + 1)
+            })
+            .ok()?;
+        Some(Self(active.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) { self.0.fetch_sub(1, Ordering::AcqRel); }
+}
+
+fn serve_connection(
+    stream: TcpStream,
+    handler: &MachHandler,
+    server_state: &SharedServerState,
+    allowed_origins: &[String],
+) {
+    let peer = stream.peer_addr().ok();
+    let check_origin = |request: &Request, response: Response| {
+        let origin = request.headers().get("origin").map(|origin| origin.to_str().unwrap_or(""));
+        if origin_allowed(origin, allowed_origins) {
+            return Ok(response);
+        }
+        warn!(
+            ?peer,
+            ?origin,
+            "Refusing WebSocket connection from a browser origin"
+        );
+        let mut refusal = ErrorResponse::new(Some("Origin not allowed".to_string()));
+        *refusal.status_mut() = StatusCode::FORBIDDEN;
+        Err(refusal)
+    };
+    let mut socket = match tungstenite::accept_hdr(stream, check_origin) {
+        Ok(socket) => socket,
+        Err(e) => {
+            debug!("WebSocket handshake with {:?} failed: {}", peer, e);
+            return;
+        }
+    };
+    if let Err(e) = socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)) {
+        error!("Failed to set WebSocket read timeout: {}", e);
+        return;
+    }
+    debug!("WebSocket client {:?} connected", peer);
+
+    let mut connection = Connection::default();
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                let response = connection.handle_text(&text, handler, server_state);
+                if !send_json(&mut socket, &response) {
+                    break;
+                }
+            }
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => break,
+            Err(e) => {
+                debug!("WebSocket client {:?} read failed: {}", peer, e);
+                break;
+            }
+        }
+
+        if !connection.flush_events(&mut socket) {
+            break;
+        }
+    }
+    debug!("WebSocket client {:?} disconnected", peer);
+}
+
+#[derive(Default)]
+struct Connection {
+    subscriptions: Vec<String>,
+    events: Option<crossbeam_channel::Receiver<BroadcastEvent>>,
+}
+
+impl Connection {
+    fn handle_text(
+        &mut self,
+        text: &str,
+        handler: &MachHandler,
+        server_state: &SharedServerState,
+    ) -> RiftResponse {
+        let request: RiftRequest = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(e) => {
+                return RiftResponse::Error {
+                    error: serde_json::json!({ "message": format!("Invalid request format: {}", e) }),
+                };
+            }
+        };
+        let request = match handler.authorize(request) {
+            Ok(request) => request,
+            Err(response) => return response,
+        };
+
+        match request {
+            RiftRequest::Subscribe { event } if !BroadcastEvent::is_subscribable(&event) => {
+                unknown_event_response(&event)
+            }
+            RiftRequest::Subscribe { event } => {
+                if self.events.is_none() {
+                    self.events = Some(server_state.read().add_event_listener());
+                }
+                if !self.subscriptions.contains(&event) {
+                    self.subscriptions.push(event.clone());
+                }
+                RiftResponse::Success {
+                    data: serde_json::json!({ "subscribed": event }),
+                }
+            }
+            RiftRequest::Unsubscribe { event } => {
+                self.subscriptions.retain(|e| e != &event);
+                if self.subscriptions.is_empty() {
+                    self.events = None;
+                }
+                RiftResponse::Success {
+                    data: serde_json::json!({ "unsubscribed": event }),
+                }
+            }
+            // These make rift run a command on every event; any page could
+            // reach them if they were allowed here.
+            RiftRequest::SubscribeCli { .. } | RiftRequest::UnsubscribeCli { .. } => {
+                RiftResponse::Error {
+                    error: serde_json::json!({
+                        "message": "CLI subscriptions are not available over WebSocket"
+                    }),
+                }
+            }
+            // There is no Mach port behind a WebSocket client, so the port
+            // argument is never used for the remaining requests.
+            request => handler.handle_request(request, 0),
+        }
+    }
+
+    /// Push every queued event this connection is subscribed to. Returns
+    /// false once the socket can no longer be written to.
+    fn flush_events(&mut self, socket: &mut WebSocket<TcpStream>) -> bool {
+        let Some(events) = &self.events else {
+            return true;
+        };
+        while let Ok(event) = events.try_recv() {
+            if is_subscribed(&self.subscriptions, event.name()) && !send_json(socket, &event) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Browsers always send `Origin`; clients that send none are not web pages.
+fn origin_allowed(origin: Option<&str>, allowed_origins: &[String]) -> bool {
+    origin.is_none_or(|origin| {
+        allowed_origins.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin))
+    })
+}
+
+fn is_subscribed(subscriptions: &[String], event_name: &str) -> bool {
+    subscriptions.iter().any(|s| s == "*" || s == event_name)
+}
+
+fn send_json(socket: &mut WebSocket<TcpStream>, value: &impl serde::Serialize) -> bool {
+    let json = match serde_json::to_string(value) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("Failed to serialize WebSocket message: {}", e);
+            return true;
+        }
+    };
+    match socket.send(Message::text(json)) {
+        Ok(()) => true,
+        Err(e) => {
+            debug!("Failed to send WebSocket message: {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_subscription_matches_every_event() {
+        let subscriptions = vec!["window_focused".to_string()];
+        assert!(is_subscribed(&subscriptions, "window_focused"));
+        assert!(!is_subscribed(&subscriptions, "workspace_changed"));
+
+        let subscriptions = vec!["*".to_string()];
+        assert!(is_subscribed(&subscriptions, "workspace_changed"));
+    }
+
+    #[test]
+    fn only_listed_browser_origins_may_connect() {
+        let allowed = vec!["http://localhost:3000".to_string()];
+        assert!(origin_allowed(None, &allowed));
+        assert!(origin_allowed(Some("http://localhost:3000"), &allowed));
+        assert!(!origin_allowed(Some("https://example.com"), &allowed));
+        assert!(!origin_allowed(Some("null"), &allowed));
+        assert!(!origin_allowed(Some("http://localhost:3000"), &[]));
+    }
+
+    #[test]
+    fn commands_need_a_token() {
+        let websocket = WebSocketSettings::default();
+        assert!(transport_read_only(&websocket, &IpcSettings::default()));
+
+        let ipc = IpcSettings {
+            token: Some("secret".into()),
+            ..Default::default()
+        };
+        assert!(!transport_read_only(&websocket, &ipc));
+        let websocket = WebSocketSettings { read_only: true, ..websocket };
+        assert!(transport_read_only(&websocket, &ipc));
+    }
+
+    #[test]
+    fn connections_are_capped() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let slots: Vec<_> = (0..MAX_CONNECTIONS)
+            .map(|_| ConnectionSlot::acquire(&active).unwrap())
+            .collect();
+        assert!(ConnectionSlot::acquire(&active).is_none());
+
+        drop(slots);
+        assert_eq!(active.load(Ordering::Acquire), 0);
+        assert!(ConnectionSlot::acquire(&active).is_some());
+    }
+}
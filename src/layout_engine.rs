@@ -6,14 +6,14 @@ pub mod utils;
 mod workspaces;
 
 pub use engine::{
-    EventResponse, LayoutCommand, LayoutEngine, LayoutEvent, RestoreReport, RestoreRequest,
-    RestoreScope, RestoreSource, RestoreWarning,
+    DropZone, DropZoneKind, EventResponse, LayoutCommand, LayoutEngine, LayoutEvent, RestoreReport,
+    RestoreRequest, RestoreScope, RestoreSource, RestoreWarning, StateInspection,
 };
 pub(crate) use floating::FloatingManager;
 pub use graph::{Direction, LayoutKind, Orientation, ResizeOrientation};
 pub(crate) use systems::LayoutId;
 pub use systems::{
-    BspLayoutSystem, LayoutSystem, LayoutSystemKind, MasterStackLayoutSystem,
+    BspLayoutSystem, LayoutSystem, LayoutSystemKind, LayoutTreeNode, MasterStackLayoutSystem,
     ScrollingLayoutSystem, StackLayoutSystem, TraditionalLayoutSystem,
 };
 pub(crate) use workspaces::WorkspaceLayouts;
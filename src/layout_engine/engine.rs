@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::cmp::Ordering;
 
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
@@ -5,25 +6,32 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
 use super::{
-    Direction, FloatingManager, LayoutId, LayoutSystemKind, ResizeOrientation, WorkspaceLayouts,
+    Direction, FloatingManager, LayoutId, LayoutSystemKind, Orientation, ResizeOrientation,
+    WorkspaceLayouts,
 };
 use crate::actor::app::{AppInfo, WindowId, pid_t};
 use crate::common::collections::{HashMap, HashSet};
-use crate::common::config::{LayoutMode, LayoutSettings, WorkspaceSelector};
+use crate::common::config::{CloseResize, LayoutMode, LayoutSettings, WorkspaceSelector};
 use crate::layout_engine::LayoutSystem;
 use crate::layout_engine::floating::FloatingFullscreenKind;
-use crate::layout_engine::systems::WindowLayoutConstraints;
+use crate::layout_engine::systems::{LayoutTreeNode, TilingBookmark, WindowLayoutConstraints};
 use crate::model::broadcast::{BroadcastEvent, BroadcastSender};
+use crate::model::rule_script::ScriptFacts;
 use crate::model::virtual_workspace::{
     AppRuleAssignment, AppRuleResult, VirtualWorkspace, VirtualWorkspaceId, WorkspaceStore,
 };
-use crate::model::{AppRuleEngine, FloatingPositionStore, WindowRuleContext, WindowStore};
+use crate::model::{
+    AppRuleDecision, AppRuleEngine, FloatingPositionStore, FocusHistory, WindowRuleContext,
+    WindowStore,
+};
 use crate::sys::screen::SpaceId;
 
 mod persistence;
 
 use persistence::PersistenceState;
-pub use persistence::{RestoreReport, RestoreRequest, RestoreScope, RestoreSource, RestoreWarning};
+pub use persistence::{
+    RestoreReport, RestoreRequest, RestoreScope, RestoreSource, RestoreWarning, StateInspection,
+};
 
 #[derive(Debug, Clone)]
 pub struct GroupContainerInfo {
@@ -41,8 +49,9 @@ struct WindowRemovalImpact {
 }
 
 #[non_exhaustive]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, strum_macros::VariantNames)]
 #[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
 pub enum LayoutCommand {
     NextWindow,
     PrevWindow,
@@ -50,22 +59,66 @@ pub enum LayoutCommand {
     Ascend,
     Descend,
     MoveNode(Direction),
+    /// Move the selection to the given 0-based slot of its parent container.
+    MoveToIndex {
+        index: usize,
+    },
+    /// Swap the selection with whatever occupies the given 0-based slot of its parent.
+    SwapWithIndex {
+        index: usize,
+    },
 
     JoinWindow(Direction),
     ConsumeOrExpelWindow(Direction),
     ToggleStack,
     ToggleOrientation,
+    /// Choose which side of the selection the next window opens on (bsp only),
+    /// and optionally the share of the selection's tile it takes. Repeating
+    /// the same preselection cancels it. `preselect = "left"` is accepted as
+    /// a preselection without a ratio.
+    #[serde(deserialize_with = "deserialize_preselect")]
+    Preselect {
+        direction: Direction,
+        ratio: Option<f64>,
+    },
+    /// Move the selected window into a tab group with its neighbor in that
+    /// direction (bsp only). Only one window of a group is shown at a time.
+    GroupWindow(Direction),
+    /// Take the selected window out of its tab group (bsp only).
+    UngroupWindow,
+    /// Show the next window of the selected tab group (bsp only).
+    NextInGroup,
+    /// Show the previous window of the selected tab group (bsp only).
+    PrevInGroup,
+    /// Reset split sizes so every tiled window gets an equal share of the screen.
+    Rebalance,
     UnjoinWindows,
     ToggleFocusFloating,
     ToggleWindowFloating,
+    /// Show the focused window on every workspace of its space, floating it
+    /// first if it is tiled. Repeating the command pins it to the active
+    /// workspace again.
+    ToggleSticky,
     ToggleFullscreen,
     ToggleFullscreenWithinGaps,
+    /// Show the focused window across the whole tiling area, or put it back
+    /// in its tile. The tree is not touched.
+    ToggleZoom,
 
     ResizeWindowGrow(ResizeOrientation),
     ResizeWindowShrink(ResizeOrientation),
     ResizeWindowBy {
         amount: f64,
     },
+    /// Give the selection exactly this share (0..1) of the split containing
+    /// it (traditional and bsp only).
+    SetSplitRatio(f64),
+    /// Move the selection's `direction` edge outward by `amount_px` points;
+    /// negative amounts move it inward.
+    ResizeSelection {
+        direction: Direction,
+        amount_px: f64,
+    },
 
     /// Scroll the strip by a normalized delta (scaled by column step width)
     ScrollStrip {
@@ -76,6 +129,17 @@ pub enum LayoutCommand {
     /// Toggle centering for the selected column without changing alignment settings.
     /// The center override is cleared when focus moves to a different window.
     CenterSelection,
+    /// Resize the selected column to the next of the scrolling layout's
+    /// `column_width_presets`.
+    CycleColumnWidth,
+    /// Resize the selected column to the previous width preset.
+    CycleColumnWidthBackward,
+    /// Focus the previously focused window on the current space.
+    FocusLast,
+    /// Focus the `n`th most recently focused window on the current space (1 = previous).
+    FocusNthPrevious {
+        n: usize,
+    },
 
     NextWorkspace(Option<bool>),
     PrevWorkspace(Option<bool>),
@@ -91,7 +155,14 @@ pub enum LayoutCommand {
         mode: LayoutMode,
     },
     CreateWorkspace,
+    #[serde(alias = "workspace_back_and_forth")]
     SwitchToLastWorkspace,
+    SwitchToNamedWorkspace(String),
+    /// Rename a workspace (the active one when `workspace` is omitted).
+    RenameWorkspace {
+        workspace: Option<WorkspaceSelector>,
+        name: String,
+    },
 
     SwapWindows(crate::actor::app::WindowId, crate::actor::app::WindowId),
 
@@ -103,6 +174,36 @@ pub enum LayoutCommand {
     SwapMasterStack,
 }
 
+impl LayoutCommand {
+    /// Share of a split the command asks for, if it takes one.
+    pub fn split_ratio(&self) -> Option<f64> {
+        match self {
+            LayoutCommand::SetSplitRatio(ratio) => Some(*ratio),
+            LayoutCommand::Preselect { ratio, .. } => *ratio,
+            _ => None,
+        }
+    }
+}
+
+fn deserialize_preselect<'de, D>(deserializer: D) -> Result<(Direction, Option<f64>), D::Error>
+where D: serde::Deserializer<'de> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Preselect {
+        Direction(Direction),
+        WithRatio {
+            direction: Direction,
+            #[serde(default)]
+            ratio: Option<f64>,
+        },
+    }
+
+    Ok(match Preselect::deserialize(deserializer)? {
+        Preselect::Direction(direction) => (direction, None),
+        Preselect::WithRatio { direction, ratio } => (direction, ratio),
+    })
+}
+
 #[non_exhaustive]
 #[derive(Debug, Clone)]
 pub enum LayoutEvent {
@@ -127,6 +228,9 @@ pub enum LayoutEvent {
     WindowAdded(SpaceId, WindowId),
     WindowRemoved(WindowId),
     WindowRemovedPreserveFloating(WindowId),
+    /// The window was minimized. It leaves the layout but keeps its workspace
+    /// and remembers its tile, so deminiaturizing puts it back in place.
+    WindowMinimized(WindowId),
     WindowFocused(SpaceId, WindowId),
     WindowResized {
         wid: WindowId,
@@ -145,19 +249,89 @@ pub struct EventResponse {
     pub boundary_hit: Option<Direction>,
 }
 
+/// Which part of a tile a dragged floating window is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropZoneKind {
+    /// The dropped window takes the left half of the tile.
+    Left,
+    /// The dropped window takes the right half of the tile.
+    Right,
+    /// The dropped window joins the container holding the tile.
+    Container,
+}
+
+/// Where a floating window dropped at a point would be tiled, and the area
+/// to highlight while it is dragged there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DropZone {
+    pub target: WindowId,
+    pub kind: DropZoneKind,
+    pub frame: CGRect,
+}
+
+/// Resolves `point` against the frames of tiled windows: the outer thirds of
+/// a tile drop the window beside it, the middle third into its container.
+pub fn drop_zone_at_point(point: CGPoint, tiles: &[(WindowId, CGRect)]) -> Option<DropZone> {
+    let &(target, frame) = tiles.iter().find(|(_, frame)| {
+        let max = frame.max();
+        (frame.origin.x..=max.x).contains(&point.x) && (frame.origin.y..=max.y).contains(&point.y)
+    })?;
+    let third = frame.size.width / 3.0;
+    let half = CGSize::new(frame.size.width / 2.0, frame.size.height);
+    let (kind, frame) = if point.x < frame.origin.x + third {
+        (DropZoneKind::Left, CGRect::new(frame.origin, half))
+    } else if point.x > frame.max().x - third {
+        let origin = CGPoint::new(frame.origin.x + half.width, frame.origin.y);
+        (DropZoneKind::Right, CGRect::new(origin, half))
+    } else {
+        (DropZoneKind::Container, frame)
+    };
+    Some(DropZone { target, kind, frame })
+}
+
+/// A window of a disconnected display and where it sat there. Saved with the
+/// layout so a display that is reconnected after a restart still gets its
+/// windows back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DepartedWindow {
+    wid: WindowId,
+    workspace_index: usize,
+    tile: Option<TilingBookmark>,
+}
+
 pub struct LayoutEngine {
     workspace_layouts: WorkspaceLayouts,
     floating: FloatingManager,
     floating_positions: FloatingPositionStore,
     app_rules: AppRuleEngine,
     focused_window: Option<WindowId>,
+    focus_history: FocusHistory,
     window_layout_constraints: HashMap<WindowId, WindowLayoutConstraints>,
     virtual_workspace_manager: WorkspaceStore,
     layout_settings: LayoutSettings,
     broadcast_tx: Option<BroadcastSender>,
     space_display_map: HashMap<SpaceId, Option<String>>,
     display_last_space: HashMap<String, SpaceId>,
+    /// Localized display names by UUID, for `per_display` gaps keyed by name.
+    /// Refreshed with the space's display on every layout pass.
+    display_names: HashMap<String, String>,
+    /// Windows of disconnected displays, keyed by display UUID, so they can go
+    /// back to their workspaces when the display is plugged in again.
+    departed_displays: HashMap<String, Vec<DepartedWindow>>,
     persistence: PersistenceState,
+    /// Swallowed windows, keyed by the window that took over their tile.
+    swallowed: HashMap<WindowId, WindowId>,
+    /// Where floated windows sat in their tiling tree, so un-floating them puts
+    /// them back in the same place.
+    tiling_bookmarks: HashMap<WindowId, (VirtualWorkspaceId, LayoutId, TilingBookmark)>,
+    /// Minimized windows, most recently minimized last.
+    minimized: Vec<WindowId>,
+    /// Windows laid out over the whole tiling area instead of their tile or
+    /// floating frame, until zoomed out again.
+    zoomed: HashSet<WindowId>,
+    /// Each app's main window as last reported by the app, used to place the
+    /// windows it opens.
+    app_main_windows: HashMap<pid_t, WindowId>,
     /// Set only while a master-file startup restore is waiting for the first display snapshot.
     startup_restore_pending: bool,
 }
@@ -165,6 +339,11 @@ pub struct LayoutEngine {
 impl LayoutEngine {
     pub fn focused_window(&self) -> Option<WindowId> { self.focused_window }
 
+    /// Recently focused windows across all spaces, most recent first.
+    pub fn global_focus_history(&self) -> impl Iterator<Item = (SpaceId, WindowId)> + '_ {
+        self.focus_history.recent_global()
+    }
+
     /// Get the active workspace ID for a space, ensuring initialization.
     fn active_workspace_id(&self, space: SpaceId) -> Option<VirtualWorkspaceId> {
         self.virtual_workspace_manager.active_workspace(space)
@@ -312,6 +491,21 @@ impl LayoutEngine {
         Self::response_for_raised_windows(visible_windows)
     }
 
+    /// `gaps` adjusted for `smart_gaps` by the number of windows tiled in
+    /// `layout`.
+    fn smart_gaps<'a>(
+        &self,
+        ws_id: VirtualWorkspaceId,
+        layout: LayoutId,
+        gaps: &'a crate::common::config::GapSettings,
+    ) -> Cow<'a, crate::common::config::GapSettings> {
+        if !gaps.smart_gaps {
+            return Cow::Borrowed(gaps);
+        }
+        let tiled = self.workspace_tree(ws_id).all_windows_in_layout(layout).len();
+        gaps.for_tiled_window_count(tiled)
+    }
+
     fn collect_group_containers_for_space(
         &self,
         space: SpaceId,
@@ -326,6 +520,8 @@ impl LayoutEngine {
             return Vec::new();
         };
         let stack_offset = self.layout_settings.stack.stack_offset;
+        let gaps = self.smart_gaps(ws_id, layout_id, gaps);
+        let gaps = &*gaps;
         match self.workspace_tree(ws_id) {
             LayoutSystemKind::Traditional(s) => {
                 if selection_path_only {
@@ -407,6 +603,9 @@ impl LayoutEngine {
 
         for (_, ws) in self.virtual_workspace_manager.workspaces.iter_mut() {
             match &mut ws.layout_system {
+                LayoutSystemKind::Traditional(system) => {
+                    system.update_settings(&settings.traditional);
+                }
                 LayoutSystemKind::Stack(system) => {
                     system.update_settings(settings.stack.default_orientation);
                 }
@@ -426,7 +625,8 @@ impl LayoutEngine {
         window_store: &WindowStore,
         settings: &crate::common::config::VirtualWorkspaceSettings,
     ) {
-        self.app_rules = AppRuleEngine::new(&settings.app_rules);
+        self.app_rules =
+            AppRuleEngine::new(&settings.app_rules).with_script(settings.script.as_ref());
         self.virtual_workspace_manager.update_settings(settings, &self.layout_settings);
 
         // Re-apply workspace layout rules to already-existing workspaces on hot reload.
@@ -702,6 +902,40 @@ impl LayoutEngine {
         }
     }
 
+    /// Focus the `n`th most recent window in the space's focus history that is still
+    /// tiled or floating in the active workspace, skipping the focused window.
+    fn focus_nth_previous(
+        &mut self,
+        window_store: &mut WindowStore,
+        space: SpaceId,
+        ws_id: VirtualWorkspaceId,
+        layout: LayoutId,
+        n: usize,
+    ) -> EventResponse {
+        let Some(index) = n.checked_sub(1) else {
+            return EventResponse::default();
+        };
+        let floating = self.active_floating_windows_in_workspace(window_store, space);
+        let Some(target) = self
+            .focus_history
+            .recent(space)
+            .filter(|&wid| Some(wid) != self.focused_window)
+            .filter(|wid| {
+                floating.contains(wid) || self.workspace_tree(ws_id).contains_window(layout, *wid)
+            })
+            .nth(index)
+        else {
+            return EventResponse::default();
+        };
+        let response = EventResponse {
+            focus_window: Some(target),
+            raise_windows: vec![target],
+            boundary_hit: None,
+        };
+        self.apply_focus_response(window_store, space, ws_id, layout, &response);
+        response
+    }
+
     fn move_focus_internal(
         &mut self,
         window_store: &mut WindowStore,
@@ -976,7 +1210,13 @@ impl LayoutEngine {
         wid: WindowId,
         preserve_floating: bool,
     ) {
-        let removal = self.remove_window_layout_membership(window_store, wid);
+        let restored_space = if preserve_floating {
+            None
+        } else {
+            self.release_swallowed_window(wid)
+        };
+        let close_resize = (!preserve_floating).then_some(self.layout_settings.close_resize);
+        let removal = self.remove_window_layout_membership(window_store, wid, close_resize);
 
         if preserve_floating {
             self.floating.remove_active_for_window(wid);
@@ -988,22 +1228,218 @@ impl LayoutEngine {
             self.virtual_workspace_manager.remove_window(window_store, wid);
             self.floating_positions.remove_window(wid);
             self.forget_persisted_window(wid);
+            self.focus_history.forget_window(wid);
+            self.tiling_bookmarks.remove(&wid);
+            self.minimized.retain(|&minimized| minimized != wid);
         }
 
         if self.focused_window == Some(wid) {
             self.focused_window = None;
         }
         self.window_layout_constraints.remove(&wid);
+        self.zoomed.remove(&wid);
 
-        if let Some(space) = removal.active_space {
+        if let Some(space) = removal.active_space.or(restored_space) {
             self.broadcast_windows_changed(window_store, space);
         }
     }
 
+    /// Give `parent`'s tile to `child` and keep `parent` out of the layout
+    /// until `child` is removed. Both windows must be tiled in the same active
+    /// workspace. Returns the space whose layout changed.
+    pub fn swallow_window(
+        &mut self,
+        window_store: &WindowStore,
+        parent: WindowId,
+        child: WindowId,
+    ) -> Option<SpaceId> {
+        if parent == child
+            || self.floating.is_floating(parent)
+            || self.floating.is_floating(child)
+            || self.swallowed.contains_key(&child)
+            || self.swallowed.contains_key(&parent)
+            || self.is_swallowed(parent)
+        {
+            return None;
+        }
+        let space = self.space_with_window(parent)?;
+        let ws_id = self.active_workspace_id(space)?;
+        let layout = self.workspace_layouts.active(space, ws_id)?;
+        if !self.workspace_tree(ws_id).contains_window(layout, parent)
+            || self.virtual_workspace_manager.workspace_for_window(window_store, space, child)
+                != Some(ws_id)
+        {
+            return None;
+        }
+
+        self.remove_window_from_all_tiling_trees(child);
+        self.workspace_tree_mut(ws_id).replace_window(parent, child);
+        let _ = self.workspace_tree_mut(ws_id).select_window(layout, child);
+        self.swallowed.insert(child, parent);
+        self.broadcast_windows_changed(window_store, space);
+        Some(space)
+    }
+
+    /// Place a newly opened tiled window in the active workspace of `space`
+    /// right after `anchor`. A window that its insertion already tiled in that
+    /// workspace is moved next to `anchor`; without an anchor it stays put.
+    pub fn insert_window_at_selection(
+        &mut self,
+        window_store: &mut WindowStore,
+        space: SpaceId,
+        wid: WindowId,
+        anchor: Option<WindowId>,
+    ) -> bool {
+        let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
+            return false;
+        };
+        if self.floating.is_floating(wid) || anchor == Some(wid) {
+            return false;
+        }
+        let tree = self.workspace_tree(ws_id);
+        if tree.contains_window(layout, wid) {
+            if !anchor.is_some_and(|anchor| tree.contains_window(layout, anchor)) {
+                return false;
+            }
+        } else if !self.virtual_workspace_manager.assign_window_to_workspace(
+            window_store,
+            space,
+            wid,
+            ws_id,
+        ) {
+            return false;
+        }
+        self.remove_window_from_all_tiling_trees(wid);
+        if let Some(anchor) = anchor
+            && self.workspace_tree(ws_id).contains_window(layout, anchor)
+        {
+            let _ = self.workspace_tree_mut(ws_id).select_window(layout, anchor);
+        }
+        self.workspace_tree_mut(ws_id).add_window_after_selection(layout, wid);
+        self.broadcast_windows_changed(window_store, space);
+        true
+    }
+
+    /// Tile `wid`, dropped at `point` on `space`, next to the tiled window
+    /// under the point. The tiles are hit-tested against the layout of the
+    /// active workspace on `screen`. Returns whether the window was inserted.
+    pub fn insert_window_at_position(
+        &mut self,
+        window_store: &mut WindowStore,
+        space: SpaceId,
+        wid: WindowId,
+        point: CGPoint,
+        screen: CGRect,
+    ) -> bool {
+        let tiles = self.drop_targets(space, screen);
+        self.insert_window_at_point(window_store, space, wid, point, &tiles)
+    }
+
+    /// Where `wid` would be tiled if dropped at `point` on `space`. Uses the
+    /// same frames as [`Self::insert_window_at_position`], so a preview of
+    /// this zone matches the drop.
+    pub fn drop_zone_at_position(
+        &mut self,
+        space: SpaceId,
+        wid: WindowId,
+        point: CGPoint,
+        screen: CGRect,
+    ) -> Option<DropZone> {
+        let tiles: Vec<_> = self
+            .drop_targets(space, screen)
+            .into_iter()
+            .filter(|(tile, _)| *tile != wid)
+            .collect();
+        drop_zone_at_point(point, &tiles)
+    }
+
+    /// The frames of the tiled windows in the active workspace of `space`.
+    fn drop_targets(&mut self, space: SpaceId, screen: CGRect) -> Vec<(WindowId, CGRect)> {
+        let display_uuid = self.space_display_map.get(&space).cloned().flatten();
+        let gaps = self.gaps_for_display(display_uuid.as_deref());
+        self.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    /// Tile `wid`, dropped at `point` on `space`, next to the tiled window
+    /// under the point (see [`drop_zone_at_point`]). `tiles` are the frames of
+    /// the tiled windows in the active workspace. A floating window stops
+    /// floating; a tiled one is taken out of its current tile. Returns whether
+    /// the window was inserted.
+    pub fn insert_window_at_point(
+        &mut self,
+        window_store: &mut WindowStore,
+        space: SpaceId,
+        wid: WindowId,
+        point: CGPoint,
+        tiles: &[(WindowId, CGRect)],
+    ) -> bool {
+        let tiles: Vec<_> = tiles.iter().copied().filter(|(tile, _)| *tile != wid).collect();
+        let Some(zone) = drop_zone_at_point(point, &tiles) else {
+            return false;
+        };
+        let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
+            return false;
+        };
+        if !self.workspace_tree(ws_id).contains_window(layout, zone.target)
+            || !self.virtual_workspace_manager.assign_window_to_workspace(
+                window_store,
+                space,
+                wid,
+                ws_id,
+            )
+        {
+            return false;
+        }
+
+        if self.floating.is_floating(wid) {
+            self.floating.remove_active(space, wid.pid, wid);
+            self.floating.remove_floating(wid);
+            self.floating.set_last_focus(None);
+            self.virtual_workspace_manager.set_sticky(wid, false);
+        } else {
+            self.remove_window_from_all_tiling_trees(wid);
+        }
+        self.tiling_bookmarks.remove(&wid);
+
+        self.workspace_tree_mut(ws_id)
+            .insert_window_beside(layout, zone.target, wid, zone.kind);
+        self.broadcast_windows_changed(window_store, space);
+        true
+    }
+
+    pub fn is_window_zoomed(&self, wid: WindowId) -> bool { self.zoomed.contains(&wid) }
+
+    /// Whether `wid` is currently hidden behind a window that swallowed it.
+    pub fn is_swallowed(&self, wid: WindowId) -> bool {
+        self.swallowed.values().any(|parent| *parent == wid)
+    }
+
+    /// Put the window swallowed by `child` back into `child`'s tile.
+    fn release_swallowed_window(&mut self, child: WindowId) -> Option<SpaceId> {
+        // A swallowed window that goes away on its own has nothing to return to.
+        self.swallowed.retain(|_, parent| *parent != child);
+        let parent = self.swallowed.remove(&child)?;
+        let space = self.space_with_window(child);
+        for (_, workspace) in self.virtual_workspace_manager.workspaces.iter_mut() {
+            workspace.layout_system.replace_window(child, parent);
+        }
+        space
+    }
+
+    /// `close_resize` is set when the window was closed rather than moved
+    /// elsewhere, so its neighbors should be resized as configured.
     fn remove_window_layout_membership(
         &mut self,
         window_store: &WindowStore,
         wid: WindowId,
+        close_resize: Option<CloseResize>,
     ) -> WindowRemovalImpact {
         let active_space = self.space_with_window(wid);
         let tiled_workspaces =
@@ -1011,7 +1447,11 @@ impl LayoutEngine {
 
         if !tiled_workspaces.is_empty() {
             for ws_id in &tiled_workspaces {
-                self.workspace_tree_mut(*ws_id).remove_window(wid);
+                let tree = self.workspace_tree_mut(*ws_id);
+                match close_resize {
+                    Some(resize) => tree.remove_closed_window(wid, resize),
+                    None => tree.remove_window(wid),
+                }
             }
             return WindowRemovalImpact { active_space };
         }
@@ -1056,10 +1496,11 @@ impl LayoutEngine {
 
         if should_be_floating {
             self.floating.add_active(space, wid.pid, wid);
+        } else if self.is_swallowed(wid) {
+            // Stays out of the layout until the window that swallowed it closes.
         } else if let Some(layout) = self.workspace_layouts.active(space, assigned_workspace) {
             if !self.workspace_tree(assigned_workspace).contains_window(layout, wid) {
-                self.workspace_tree_mut(assigned_workspace)
-                    .add_window_after_selection(layout, wid);
+                self.insert_tiled_window(assigned_workspace, layout, wid);
             }
         } else {
             warn!(
@@ -1071,6 +1512,44 @@ impl LayoutEngine {
         self.space_with_window(wid) != active_space_before
     }
 
+    /// Put `wid` back where it was bookmarked when it left the tree, or after
+    /// the selection if the bookmark is gone or belongs to another layout.
+    fn insert_tiled_window(
+        &mut self,
+        workspace: VirtualWorkspaceId,
+        layout: LayoutId,
+        wid: WindowId,
+    ) {
+        let restored = self.tiling_bookmarks.remove(&wid).is_some_and(
+            |(ws_id, bookmarked_layout, bookmark)| {
+                ws_id == workspace
+                    && bookmarked_layout == layout
+                    && self.workspace_tree_mut(workspace).restore_window(layout, wid, bookmark)
+            },
+        );
+        if !restored {
+            self.workspace_tree_mut(workspace).add_window_after_selection(layout, wid);
+        }
+    }
+
+    /// Take `wid` out of the layout because it was minimized, remembering its
+    /// tile so [`LayoutEvent::WindowAdded`] can restore it.
+    fn minimize_window(&mut self, window_store: &mut WindowStore, wid: WindowId) {
+        if !self.floating.is_floating(wid)
+            && let Some(space) = self.space_with_window(wid)
+            && let Some((ws_id, layout)) = self.workspace_and_layout(space)
+            && let Some(bookmark) = self.workspace_tree(ws_id).tiling_bookmark(layout, wid)
+        {
+            self.tiling_bookmarks.insert(wid, (ws_id, layout, bookmark));
+        }
+        self.minimized.retain(|&minimized| minimized != wid);
+        self.minimized.push(wid);
+        self.remove_window_internal(window_store, wid, true);
+    }
+
+    /// The most recently minimized window that has not been restored yet.
+    pub fn last_minimized_window(&self) -> Option<WindowId> { self.minimized.last().copied() }
+
     fn remove_window_from_all_tiling_trees(&mut self, wid: WindowId) {
         let ws_ids: Vec<_> = self.virtual_workspace_manager.workspaces.keys().collect();
         for ws_id in ws_ids {
@@ -1139,6 +1618,7 @@ impl LayoutEngine {
                 // (it was moved to another space during this discovery cycle).
                 if wid.pid != pid
                     || self.floating.is_floating(wid)
+                    || self.is_swallowed(wid)
                     || desired.contains(&wid)
                     || authoritative_native_space.is_some_and(|native_space| native_space != space)
                     || self.window_no_longer_assigned_to_space(window_store, space, wid)
@@ -1203,8 +1683,22 @@ impl LayoutEngine {
         changed_layouts
     }
 
-    pub fn update_space_display(&mut self, space: SpaceId, display_uuid: Option<String>) {
+    pub fn update_space_display(
+        &mut self,
+        space: SpaceId,
+        display_uuid: Option<String>,
+        display_name: Option<String>,
+    ) {
+        self.virtual_workspace_manager.set_space_display(
+            space,
+            display_uuid.as_deref(),
+            display_name.as_deref(),
+        );
         if let Some(uuid) = display_uuid {
+            match display_name {
+                Some(name) => self.display_names.insert(uuid.clone(), name),
+                None => self.display_names.remove(&uuid),
+            };
             self.space_display_map.insert(space, Some(uuid.clone()));
             self.display_last_space.insert(uuid, space);
         } else {
@@ -1212,6 +1706,11 @@ impl LayoutEngine {
         }
     }
 
+    fn gaps_for_display(&self, display_uuid: Option<&str>) -> crate::common::config::GapSettings {
+        let name = display_uuid.and_then(|uuid| self.display_names.get(uuid));
+        self.layout_settings.gaps.effective_for_display(display_uuid, name.map(String::as_str))
+    }
+
     pub fn last_space_for_display_uuid(&self, display_uuid: &str) -> Option<SpaceId> {
         self.display_last_space.get(display_uuid).copied()
     }
@@ -1261,6 +1760,16 @@ impl LayoutEngine {
         }
     }
 
+    /// Forget all per-space layout state for a space that was destroyed.
+    pub fn remove_space(&mut self, window_store: &mut WindowStore, space: SpaceId) {
+        self.workspace_layouts.remove_space(space);
+        self.floating.remove_space(space);
+        self.floating_positions.remove_space(space);
+        self.virtual_workspace_manager.remove_space(window_store, space);
+        self.space_display_map.remove(&space);
+        self.display_last_space.retain(|_, last| *last != space);
+    }
+
     pub fn prune_display_state(&mut self, active_display_uuids: &[String]) {
         let active: HashSet<&str> = active_display_uuids.iter().map(|s| s.as_str()).collect();
 
@@ -1271,6 +1780,152 @@ impl LayoutEngine {
         });
     }
 
+    /// Remember the workspace and tile of every window on `space`, which
+    /// belonged to the display `display_uuid` that was just disconnected.
+    pub fn remember_departed_display(
+        &mut self,
+        window_store: &WindowStore,
+        display_uuid: &str,
+        space: SpaceId,
+    ) {
+        let mut departed = Vec::new();
+        let mut seen = HashSet::default();
+        let workspaces = self.virtual_workspace_manager.list_workspaces(space);
+        for (workspace_index, (ws_id, _)) in workspaces.into_iter().enumerate() {
+            let mut windows =
+                self.virtual_workspace_manager.workspace_windows(window_store, space, ws_id);
+            let layout = self.workspace_layouts.active(space, ws_id);
+            if let Some(layout) = layout {
+                let order = self.workspace_tree(ws_id).all_windows_in_layout(layout);
+                windows
+                    .sort_by_key(|wid| order.iter().position(|w| w == wid).unwrap_or(usize::MAX));
+            }
+            for wid in windows {
+                // Sticky windows show up on every workspace; keep the first.
+                if !seen.insert(wid) {
+                    continue;
+                }
+                let tile = layout
+                    .and_then(|layout| self.workspace_tree(ws_id).tiling_bookmark(layout, wid));
+                departed.push(DepartedWindow { wid, workspace_index, tile });
+            }
+        }
+        if !departed.is_empty() {
+            debug!(
+                display_uuid,
+                count = departed.len(),
+                "Remembering windows of disconnected display"
+            );
+            self.departed_displays.insert(display_uuid.to_string(), departed);
+        }
+    }
+
+    /// Move the remembered windows of a disconnected display to `workspace`
+    /// on `space`, one of the remaining displays.
+    pub fn migrate_departed_display(
+        &mut self,
+        window_store: &mut WindowStore,
+        display_uuid: &str,
+        space: SpaceId,
+        screen_size: CGSize,
+        workspace: &WorkspaceSelector,
+    ) {
+        let Some(departed) = self.departed_displays.get(display_uuid) else {
+            return;
+        };
+        let windows: Vec<WindowId> = departed.iter().map(|window| window.wid).collect();
+        let _ = self.virtual_workspace_manager.list_workspaces(space);
+        let Some(target) = self.virtual_workspace_manager.find_workspace(space, workspace) else {
+            warn!(
+                ?workspace,
+                "Workspace for windows of a disconnected display not found"
+            );
+            return;
+        };
+        for wid in windows {
+            if window_store.workspace_info_for_window(wid).is_some() {
+                self.relocate_window(window_store, wid, space, screen_size, target, None);
+            }
+        }
+        self.broadcast_windows_changed(window_store, space);
+    }
+
+    /// Put the windows of a reconnected display back on the workspaces and
+    /// tiles they had before it was disconnected. Returns whether the display
+    /// had any windows remembered.
+    pub fn restore_departed_display(
+        &mut self,
+        window_store: &mut WindowStore,
+        display_uuid: &str,
+        space: SpaceId,
+        screen_size: CGSize,
+    ) -> bool {
+        let Some(departed) = self.departed_displays.remove(display_uuid) else {
+            return false;
+        };
+        let workspaces = self.virtual_workspace_manager.list_workspaces(space);
+        for window in departed {
+            let Some(&(target, _)) = workspaces.get(window.workspace_index) else {
+                continue;
+            };
+            if window_store.workspace_info_for_window(window.wid).is_some() {
+                self.relocate_window(
+                    window_store,
+                    window.wid,
+                    space,
+                    screen_size,
+                    target,
+                    window.tile,
+                );
+            }
+        }
+        self.broadcast_windows_changed(window_store, space);
+        true
+    }
+
+    /// Reassign `wid` to `workspace` on `space`, putting it back on `tile`
+    /// when that still exists.
+    fn relocate_window(
+        &mut self,
+        window_store: &mut WindowStore,
+        wid: WindowId,
+        space: SpaceId,
+        screen_size: CGSize,
+        workspace: VirtualWorkspaceId,
+        tile: Option<TilingBookmark>,
+    ) {
+        let is_floating = self.floating.is_floating(wid);
+        if is_floating {
+            self.floating.remove_active_for_window(wid);
+        } else {
+            self.remove_window_from_all_tiling_trees(wid);
+        }
+        if !self.virtual_workspace_manager.assign_window_to_workspace(
+            window_store,
+            space,
+            wid,
+            workspace,
+        ) {
+            return;
+        }
+        let tree = &mut self.virtual_workspace_manager.workspaces[workspace].layout_system;
+        self.workspace_layouts
+            .ensure_active_for_workspace(space, screen_size, workspace, tree);
+
+        if is_floating {
+            if self.virtual_workspace_manager.active_workspace(space) == Some(workspace) {
+                self.floating.add_active(space, wid.pid, wid);
+            }
+        } else if let Some(layout) = self.workspace_layouts.active(space, workspace) {
+            let restored = tile.is_some_and(|tile| {
+                self.workspace_tree_mut(workspace).restore_window(layout, wid, tile)
+            });
+            if !restored {
+                self.workspace_tree_mut(workspace).add_window_after_selection(layout, wid);
+            }
+        }
+    }
+
     pub fn new(
         virtual_workspace_config: &crate::common::config::VirtualWorkspaceSettings,
         layout_settings: &LayoutSettings,
@@ -1285,17 +1940,31 @@ impl LayoutEngine {
             floating_positions: FloatingPositionStore::default(),
             app_rules: AppRuleEngine::new(&virtual_workspace_config.app_rules),
             focused_window: None,
+            focus_history: FocusHistory::default(),
             window_layout_constraints: HashMap::default(),
             virtual_workspace_manager,
             layout_settings: layout_settings.clone(),
             broadcast_tx,
             space_display_map: HashMap::default(),
             display_last_space: HashMap::default(),
+            display_names: HashMap::default(),
+            departed_displays: HashMap::default(),
             persistence: PersistenceState::default(),
+            swallowed: HashMap::default(),
+            tiling_bookmarks: HashMap::default(),
+            minimized: Vec::new(),
+            zoomed: HashSet::default(),
+            app_main_windows: HashMap::default(),
             startup_restore_pending: false,
         }
     }
 
+    /// Structured tree of the active workspace's layout on `space`.
+    pub fn layout_tree(&self, space: SpaceId) -> Option<LayoutTreeNode> {
+        let (workspace_id, layout) = self.workspace_and_layout(space)?;
+        Some(self.workspace_tree(workspace_id).layout_tree(layout))
+    }
+
     pub fn debug_tree(&self, space: SpaceId) { self.debug_tree_desc(space, "", false); }
 
     pub fn debug_tree_desc(&self, space: SpaceId, desc: &'static str, print: bool) {
@@ -1442,7 +2111,7 @@ impl LayoutEngine {
                         self.floating.remove_floating(wid);
                     }
 
-                    if !self.floating.is_floating(wid) {
+                    if !self.floating.is_floating(wid) && !self.is_swallowed(wid) {
                         windows_by_workspace.entry(assigned_workspace).or_default().push(wid);
                     }
 
@@ -1478,18 +2147,27 @@ impl LayoutEngine {
                 }
             }
             LayoutEvent::AppClosed(pid) => {
+                self.app_main_windows.remove(&pid);
+                let children: Vec<_> =
+                    self.swallowed.keys().copied().filter(|child| child.pid == pid).collect();
+                for child in children {
+                    self.release_swallowed_window(child);
+                }
+                self.swallowed.retain(|_, parent| parent.pid != pid);
                 for (_, ws) in self.virtual_workspace_manager.workspaces.iter_mut() {
                     ws.layout_system.remove_windows_for_app(pid);
                 }
                 self.floating.remove_all_for_pid(pid);
                 self.window_layout_constraints.retain(|wid, _| wid.pid != pid);
                 self.forget_persisted_app(pid);
+                self.focus_history.forget_app(pid);
 
                 self.virtual_workspace_manager.remove_windows_for_app(window_store, pid);
                 self.floating_positions.remove_app(pid);
             }
             LayoutEvent::WindowAdded(space, wid) => {
                 self.debug_tree(space);
+                self.minimized.retain(|&minimized| minimized != wid);
                 if self.add_window_to_layout(window_store, space, wid) {
                     self.broadcast_windows_changed(window_store, space);
                 }
@@ -1500,10 +2178,14 @@ impl LayoutEngine {
             LayoutEvent::WindowRemovedPreserveFloating(wid) => {
                 self.remove_window_internal(window_store, wid, true);
             }
+            LayoutEvent::WindowMinimized(wid) => {
+                self.minimize_window(window_store, wid);
+            }
             LayoutEvent::WindowFocused(space, wid) => {
                 if self.floating.is_floating(wid) {
                     self.focused_window = Some(wid);
                     self.floating.set_last_focus(Some(wid));
+                    self.focus_history.record(space, wid);
                 } else if let Some((ws_id, layout)) = self.workspace_and_layout(space) {
                     if !self.workspace_tree(ws_id).contains_window(layout, wid) {
                         warn!(
@@ -1513,6 +2195,7 @@ impl LayoutEngine {
                         return EventResponse::default();
                     }
                     self.focused_window = Some(wid);
+                    self.focus_history.record(space, wid);
                     let _ = self.workspace_tree_mut(ws_id).select_window(layout, wid);
                     self.virtual_workspace_manager.set_last_focused_window(space, ws_id, Some(wid));
                 } else {
@@ -1528,6 +2211,10 @@ impl LayoutEngine {
                 new_frame,
                 screens,
             } => {
+                // A zoomed window's frame says nothing about the size of its tile.
+                if self.zoomed.contains(&wid) {
+                    return EventResponse::default();
+                }
                 for (space, screen_frame, display_uuid) in screens {
                     let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
                         debug!(
@@ -1536,8 +2223,8 @@ impl LayoutEngine {
                         );
                         continue;
                     };
-                    let gaps =
-                        self.layout_settings.gaps.effective_for_display(display_uuid.as_deref());
+                    let gaps = self.gaps_for_display(display_uuid.as_deref());
+                    let gaps = self.smart_gaps(ws_id, layout, &gaps).into_owned();
                     self.workspace_tree_mut(ws_id).on_window_resized(
                         layout,
                         wid,
@@ -1597,8 +2284,7 @@ impl LayoutEngine {
                         });
 
                     if let Some(layout) = self.workspace_layouts.active(space, assigned_workspace) {
-                        self.workspace_tree_mut(assigned_workspace)
-                            .add_window_after_selection(layout, wid);
+                        self.insert_tiled_window(assigned_workspace, layout, wid);
                         debug!(
                             "Re-added floating window {:?} to tiling tree in workspace {:?}",
                             wid, assigned_workspace
@@ -1607,12 +2293,18 @@ impl LayoutEngine {
 
                     self.floating.remove_active(space, wid.pid, wid);
                 }
+                self.virtual_workspace_manager.set_sticky(wid, false);
                 self.floating.remove_floating(wid);
                 self.floating.set_last_focus(None);
             } else {
                 if let Some(space) = space {
                     self.floating.add_active(space, wid.pid, wid);
-                    if let Some((ws_id, _)) = self.workspace_and_layout(space) {
+                    if let Some((ws_id, layout)) = self.workspace_and_layout(space) {
+                        if let Some(bookmark) =
+                            self.workspace_tree(ws_id).tiling_bookmark(layout, wid)
+                        {
+                            self.tiling_bookmarks.insert(wid, (ws_id, layout, bookmark));
+                        }
                         self.workspace_tree_mut(ws_id).remove_window(wid);
                     } else {
                         debug!(
@@ -1723,6 +2415,57 @@ impl LayoutEngine {
             LayoutCommand::ToggleWindowFloating => unreachable!(),
             LayoutCommand::ToggleFocusFloating => unreachable!(),
 
+            LayoutCommand::ToggleSticky => {
+                let Some(wid) = self.focused_window else {
+                    return EventResponse::default();
+                };
+                if self.virtual_workspace_manager.is_sticky(wid) {
+                    self.virtual_workspace_manager.set_sticky(wid, false);
+                    // Keep the window where the user can see it.
+                    self.virtual_workspace_manager.assign_window_to_workspace(
+                        window_store,
+                        space,
+                        wid,
+                        workspace_id,
+                    );
+                } else {
+                    if !is_floating {
+                        self.floating.add_active(space, wid.pid, wid);
+                        self.workspace_tree_mut(workspace_id).remove_window(wid);
+                        self.floating.add_floating(wid);
+                        self.floating.set_last_focus(Some(wid));
+                    }
+                    self.virtual_workspace_manager.set_sticky(wid, true);
+                }
+                self.update_active_floating_windows(window_store, space);
+                EventResponse::default()
+            }
+
+            LayoutCommand::Rebalance => {
+                self.workspace_tree_mut(workspace_id).rebalance(layout);
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                EventResponse::default()
+            }
+
+            LayoutCommand::ToggleZoom => {
+                let wid = if is_floating {
+                    self.focused_window
+                } else {
+                    self.workspace_tree(workspace_id).selected_window(layout)
+                };
+                let Some(wid) = wid else {
+                    return EventResponse::default();
+                };
+                if !self.zoomed.remove(&wid) {
+                    self.zoomed.insert(wid);
+                }
+                EventResponse {
+                    raise_windows: vec![wid],
+                    focus_window: Some(wid),
+                    boundary_hit: None,
+                }
+            }
+
             LayoutCommand::SwapWindows(a, b) => {
                 let _ = self.workspace_tree_mut(workspace_id).swap_windows(layout, a, b);
 
@@ -1768,6 +2511,12 @@ impl LayoutEngine {
                     return response;
                 }
             }
+            LayoutCommand::FocusLast => {
+                return self.focus_nth_previous(window_store, space, workspace_id, layout, 1);
+            }
+            LayoutCommand::FocusNthPrevious { n } => {
+                return self.focus_nth_previous(window_store, space, workspace_id, layout, n);
+            }
             LayoutCommand::MoveFocus(direction) => {
                 debug!(
                     "MoveFocus command received, direction: {:?}, is_floating: {}",
@@ -1855,6 +2604,18 @@ impl LayoutEngine {
                     }
                 }
             }
+            LayoutCommand::MoveToIndex { index } => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                let _ =
+                    self.workspace_tree_mut(workspace_id).move_selection_to_index(layout, index);
+                EventResponse::default()
+            }
+            LayoutCommand::SwapWithIndex { index } => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                let _ =
+                    self.workspace_tree_mut(workspace_id).swap_selection_with_index(layout, index);
+                EventResponse::default()
+            }
             // handled by upper reactor
             LayoutCommand::NextWorkspace(_)
             | LayoutCommand::PrevWorkspace(_)
@@ -1862,7 +2623,9 @@ impl LayoutEngine {
             | LayoutCommand::MoveWindowToWorkspace { .. }
             | LayoutCommand::SetWorkspaceLayout { .. }
             | LayoutCommand::CreateWorkspace
-            | LayoutCommand::SwitchToLastWorkspace => EventResponse::default(),
+            | LayoutCommand::SwitchToLastWorkspace
+            | LayoutCommand::SwitchToNamedWorkspace(_)
+            | LayoutCommand::RenameWorkspace { .. } => EventResponse::default(),
             LayoutCommand::JoinWindow(direction) => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 self.workspace_tree_mut(workspace_id)
@@ -1881,6 +2644,46 @@ impl LayoutEngine {
                     self.layout_settings.stack.default_orientation;
                 self.toggle_stack_for_workspace(workspace_id, layout, default_orientation)
             }
+            LayoutCommand::Preselect { direction, ratio } => {
+                if ratio.is_some_and(|ratio| !ratio.is_finite()) {
+                    return EventResponse::default();
+                }
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                if let LayoutSystemKind::Bsp(s) = self.workspace_tree_mut(workspace_id) {
+                    s.set_preselection(layout, direction, ratio);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::GroupWindow(direction) => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                if let LayoutSystemKind::Bsp(s) = self.workspace_tree_mut(workspace_id) {
+                    s.group_selection_with_direction(layout, direction);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::UngroupWindow => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                if let LayoutSystemKind::Bsp(s) = self.workspace_tree_mut(workspace_id) {
+                    s.ungroup_selection(layout);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::NextInGroup | LayoutCommand::PrevInGroup => {
+                let forward = matches!(command, LayoutCommand::NextInGroup);
+                let LayoutSystemKind::Bsp(s) = self.workspace_tree_mut(workspace_id) else {
+                    return EventResponse::default();
+                };
+                let Some(wid) = s.cycle_group_of_selection(layout, forward) else {
+                    return EventResponse::default();
+                };
+                let response = EventResponse {
+                    focus_window: Some(wid),
+                    raise_windows: vec![wid],
+                    boundary_hit: None,
+                };
+                self.apply_focus_response(window_store, space, workspace_id, layout, &response);
+                response
+            }
             LayoutCommand::UnjoinWindows => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 self.workspace_tree_mut(workspace_id).unjoin_selection(layout);
@@ -1937,6 +2740,15 @@ impl LayoutEngine {
                 );
                 EventResponse::default()
             }
+            LayoutCommand::SetSplitRatio(ratio) => {
+                if is_floating || !ratio.is_finite() {
+                    return EventResponse::default();
+                }
+
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                self.workspace_tree_mut(workspace_id).set_selection_split_ratio(layout, ratio);
+                EventResponse::default()
+            }
             LayoutCommand::ResizeWindowBy { amount } => {
                 if is_floating {
                     return EventResponse::default();
@@ -1950,6 +2762,29 @@ impl LayoutEngine {
                 );
                 EventResponse::default()
             }
+            LayoutCommand::ResizeSelection { direction, amount_px } => {
+                if is_floating || amount_px == 0.0 {
+                    return EventResponse::default();
+                }
+                let Some(size) = self.workspace_layouts.active_size(space, workspace_id) else {
+                    return EventResponse::default();
+                };
+                let extent = match direction.orientation() {
+                    Orientation::Horizontal => size.width,
+                    Orientation::Vertical => size.height,
+                };
+                if extent <= 0.0 {
+                    return EventResponse::default();
+                }
+
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                self.workspace_tree_mut(workspace_id).resize_selection_edge(
+                    layout,
+                    direction,
+                    amount_px / extent,
+                );
+                EventResponse::default()
+            }
             LayoutCommand::AdjustMasterRatio(delta) => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 if let LayoutSystemKind::MasterStack(s) = self.workspace_tree_mut(workspace_id) {
@@ -1997,6 +2832,13 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
+            LayoutCommand::CycleColumnWidth | LayoutCommand::CycleColumnWidthBackward => {
+                let forward = matches!(command, LayoutCommand::CycleColumnWidth);
+                if let LayoutSystemKind::Scrolling(system) = self.workspace_tree_mut(workspace_id) {
+                    system.cycle_column_width(layout, forward);
+                }
+                EventResponse::default()
+            }
         }
     }
 
@@ -2012,12 +2854,13 @@ impl LayoutEngine {
         let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
             return Vec::new();
         };
+        let gaps = self.smart_gaps(ws_id, layout, gaps);
         self.workspace_tree(ws_id).calculate_layout(
             layout,
             screen,
             self.layout_settings.stack.stack_offset,
             &self.window_layout_constraints,
-            gaps,
+            &gaps,
             stack_line_thickness,
             stack_line_horiz,
             stack_line_vert,
@@ -2087,12 +2930,13 @@ impl LayoutEngine {
 
         if let Some(active_workspace_id) = self.virtual_workspace_manager.active_workspace(space) {
             if let Some(layout) = self.workspace_layouts.active(space, active_workspace_id) {
+                let gaps = self.smart_gaps(active_workspace_id, layout, gaps);
                 let tiled_positions = self.workspace_tree(active_workspace_id).calculate_layout(
                     layout,
                     screen,
                     self.layout_settings.stack.stack_offset,
                     &self.window_layout_constraints,
-                    gaps,
+                    &gaps,
                     stack_line_thickness,
                     stack_line_horiz,
                     stack_line_vert,
@@ -2131,13 +2975,19 @@ impl LayoutEngine {
 
             let floating_windows = self.active_floating_windows_in_workspace(window_store, space);
             for wid in floating_windows {
+                // Sticky windows stay where they are across workspace switches.
+                let candidate = if self.virtual_workspace_manager.is_sticky(wid) {
+                    get_window_frame(wid)
+                } else {
+                    None
+                };
                 ensure_visible_floating(
                     self,
                     &mut positions,
                     space,
                     active_workspace_id,
                     wid,
-                    None,
+                    candidate,
                     false,
                     &screen,
                     all_screens,
@@ -2146,6 +2996,14 @@ impl LayoutEngine {
                 );
             }
 
+            let o = &gaps.outer;
+            let within_gaps = CGRect::new(
+                CGPoint::new(screen.origin.x + o.left, screen.origin.y + o.top),
+                CGSize::new(
+                    screen.size.width - o.left - o.right,
+                    screen.size.height - o.top - o.bottom,
+                ),
+            );
             let fullscreen: Vec<(WindowId, FloatingFullscreenKind)> = positions
                 .keys()
                 .copied()
@@ -2154,24 +3012,41 @@ impl LayoutEngine {
             for (w, kind) in fullscreen {
                 let rect = match kind {
                     FloatingFullscreenKind::Full => screen,
-                    FloatingFullscreenKind::WithinGaps => {
-                        let o = &gaps.outer;
-                        CGRect::new(
-                            CGPoint::new(screen.origin.x + o.left, screen.origin.y + o.top),
-                            CGSize::new(
-                                screen.size.width - o.left - o.right,
-                                screen.size.height - o.top - o.bottom,
-                            ),
-                        )
-                    }
+                    FloatingFullscreenKind::WithinGaps => within_gaps,
                 };
                 positions.insert(w, rect);
             }
+
+            for w in &self.zoomed {
+                if let Some(rect) = positions.get_mut(w)
+                    && self.floating.fullscreen_kind(*w).is_none()
+                {
+                    *rect = within_gaps;
+                }
+            }
         }
 
+        // Swallowed windows are parked like windows of inactive workspaces
+        // until the window that swallowed them closes.
+        let active_workspace = self.virtual_workspace_manager.active_workspace(space);
+        let swallowed_windows: Vec<WindowId> = self
+            .swallowed
+            .values()
+            .copied()
+            .filter(|&parent| {
+                active_workspace.is_some()
+                    && self.virtual_workspace_manager.workspace_for_window(
+                        window_store,
+                        space,
+                        parent,
+                    ) == active_workspace
+            })
+            .collect();
         let hidden_windows = self
             .virtual_workspace_manager
-            .windows_in_inactive_workspaces(window_store, space);
+            .windows_in_inactive_workspaces(window_store, space)
+            .into_iter()
+            .chain(swallowed_windows);
         for wid in hidden_windows {
             let original_frame = get_window_frame(wid);
 
@@ -2241,6 +3116,19 @@ impl LayoutEngine {
         self.workspace_tree(ws_id).has_any_fullscreen_node(layout_id)
     }
 
+    /// The selected window on the space's active workspace, the side of it
+    /// preselected for the next insertion and the requested share of the split.
+    pub fn preselection_for_space(
+        &self,
+        space: SpaceId,
+    ) -> Option<(WindowId, Direction, Option<f64>)> {
+        let (ws_id, layout_id) = self.workspace_and_layout(space)?;
+        match self.workspace_tree(ws_id) {
+            LayoutSystemKind::Bsp(s) => s.preselection(layout_id),
+            _ => None,
+        }
+    }
+
     pub fn collect_group_containers(
         &mut self,
         space: SpaceId,
@@ -2275,12 +3163,13 @@ impl LayoutEngine {
         let mut positions = HashMap::default();
 
         if let Some(layout) = self.workspace_layouts.active(space, workspace_id) {
+            let gaps = self.smart_gaps(workspace_id, layout, gaps);
             let tiled_positions = self.workspace_tree(workspace_id).calculate_layout(
                 layout,
                 screen,
                 self.layout_settings.stack.stack_offset,
                 &self.window_layout_constraints,
-                gaps,
+                &gaps,
                 stack_line_thickness,
                 stack_line_horiz,
                 stack_line_vert,
@@ -2339,8 +3228,8 @@ impl LayoutEngine {
         }
     }
 
-    #[cfg(test)]
-    pub(crate) fn selected_window(&mut self, space: SpaceId) -> Option<WindowId> {
+    /// The selected window in the active workspace of `space`.
+    pub fn selected_window(&self, space: SpaceId) -> Option<WindowId> {
         let (ws_id, layout) = self.workspace_and_layout(space)?;
         self.workspace_tree(ws_id).selected_window(layout)
     }
@@ -2543,6 +3432,46 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
+            LayoutCommand::SwitchToNamedWorkspace(name) => {
+                let workspaces = self.virtual_workspace_manager_mut().list_workspaces(space);
+                match workspaces.iter().position(|(_, n)| n == name) {
+                    Some(index) => self.switch_to_workspace(window_store, space, index, None),
+                    None => {
+                        warn!("No workspace named {:?} on space {:?}", name, space);
+                        EventResponse::default()
+                    }
+                }
+            }
+            LayoutCommand::RenameWorkspace { workspace, name } => {
+                let workspace_id = match workspace {
+                    Some(selector) => {
+                        self.virtual_workspace_manager.find_workspace(space, selector)
+                    }
+                    None => self.virtual_workspace_manager.active_workspace(space),
+                };
+                let Some(workspace_id) = workspace_id else {
+                    warn!("Rename target {:?} not found on space {:?}", workspace, space);
+                    return EventResponse::default();
+                };
+                let previous_name = self
+                    .virtual_workspace_manager
+                    .workspace_info(space, workspace_id)
+                    .map(|ws| ws.name.clone())
+                    .unwrap_or_default();
+                if !self.virtual_workspace_manager.rename_workspace(
+                    space,
+                    workspace_id,
+                    name.clone(),
+                ) {
+                    warn!("Cannot rename workspace {:?} to {:?}", previous_name, name);
+                    return EventResponse::default();
+                }
+                self.broadcast_workspace_renamed(space, workspace_id, previous_name);
+                if self.virtual_workspace_manager.active_workspace(space) == Some(workspace_id) {
+                    self.broadcast_workspace_changed(space);
+                }
+                EventResponse::default()
+            }
             LayoutCommand::SetWorkspaceLayout { workspace, mode } => {
                 let Some(workspace_id) = self.workspace_id_for_index(space, *workspace) else {
                     return EventResponse::default();
@@ -2607,19 +3536,133 @@ impl LayoutEngine {
         ax_role: Option<&str>,
         ax_subrole: Option<&str>,
     ) -> Result<AppRuleResult, crate::model::virtual_workspace::WorkspaceError> {
-        let decision = self.app_rules.evaluate(WindowRuleContext {
+        let app_bundle_path = window_store
+            .window(window_id)
+            .and_then(|window| window.info.path.as_deref())
+            .and_then(|path| path.to_str());
+        let first_sight = !self.floating.is_floating(window_id)
+            && self
+                .virtual_workspace_manager
+                .workspace_for_window(window_store, space, window_id)
+                .is_none();
+        let context = WindowRuleContext {
             app_bundle_id,
             app_name,
             window_title,
             ax_role,
             ax_subrole,
-        });
-        self.virtual_workspace_manager.apply_app_rule_decision(
+            app_bundle_path,
+            ..Default::default()
+        };
+        let mut decision = self.app_rules.evaluate(context);
+        // The script only places windows it has not seen yet, so a window the
+        // user moved is not sent back on the next rule pass.
+        if first_sight
+            && let Some(scripted) = self.app_rules.evaluate_script(context, || ScriptFacts {
+                app_window_count: window_store
+                    .window_ids_for_pid(window_id.pid)
+                    .filter(|&wid| {
+                        wid != window_id
+                            && self
+                                .virtual_workspace_manager
+                                .workspace_for_window(window_store, space, wid)
+                                .is_some()
+                    })
+                    .count(),
+            })
+        {
+            decision = scripted;
+        }
+        let auto_float = first_sight
+            && decision == AppRuleDecision::NoMatch
+            && window_store.window(window_id).is_some_and(|window| {
+                let size = window.frame_monotonic.size;
+                self.layout_settings.auto_float.should_float(
+                    size.width,
+                    size.height,
+                    ax_role,
+                    ax_subrole,
+                )
+            });
+        if self.layout_settings.float_all_windows {
+            decision = match decision {
+                AppRuleDecision::NoMatch => AppRuleDecision::Managed {
+                    workspace: None,
+                    floating: true,
+                },
+                AppRuleDecision::Managed { workspace, .. } => {
+                    AppRuleDecision::Managed { workspace, floating: true }
+                }
+                AppRuleDecision::Unmanaged => AppRuleDecision::Unmanaged,
+            };
+        }
+        let result = self.virtual_workspace_manager.apply_app_rule_decision(
             window_store,
             window_id,
             space,
             decision,
-        )
+        );
+        // Auto-floating is not a rule decision: it behaves as if the user had
+        // floated the window, so later resizes do not tile it again.
+        if auto_float && let Ok(AppRuleResult::Managed(assignment)) = &result {
+            self.floating.add_floating(window_id);
+            self.floating.add_active(space, window_id.pid, window_id);
+            if let Some(frame) = self.auto_float_frame(window_store, space, window_id) {
+                self.floating_positions.store(space, assignment.workspace_id, window_id, frame);
+            }
+        }
+        result
+    }
+
+    pub fn set_app_main_window(&mut self, pid: pid_t, main_window: Option<WindowId>) {
+        match main_window {
+            Some(wid) => self.app_main_windows.insert(pid, wid),
+            None => self.app_main_windows.remove(&pid),
+        };
+    }
+
+    /// Frame centering `window_id` over its app's main window on `space`, or
+    /// the app's most recently focused window there when the main window is
+    /// unknown or elsewhere.
+    fn auto_float_frame(
+        &self,
+        window_store: &WindowStore,
+        space: SpaceId,
+        window_id: WindowId,
+    ) -> Option<CGRect> {
+        let size = window_store.window(window_id)?.frame_monotonic.size;
+        let on_space = |wid: WindowId| {
+            wid != window_id
+                && wid.pid == window_id.pid
+                && self
+                    .virtual_workspace_manager
+                    .workspace_for_window(window_store, space, wid)
+                    .is_some()
+        };
+        let parent = self
+            .app_main_windows
+            .get(&window_id.pid)
+            .copied()
+            .filter(|&wid| on_space(wid))
+            .or_else(|| self.focus_history.recent(space).find(|&wid| on_space(wid)))?;
+        let center = window_store.window(parent)?.frame_monotonic.mid();
+        Some(CGRect::new(
+            CGPoint::new(center.x - size.width / 2.0, center.y - size.height / 2.0),
+            size,
+        ))
+    }
+
+    /// Opacity requested by the app rule that wins for this window, if any.
+    pub fn app_rule_opacity(&self, context: WindowRuleContext<'_>) -> Option<f64> {
+        self.app_rules.opacity(context)
+    }
+
+    /// Floating frame requested by the app rule that wins for this window, if any.
+    pub fn app_rule_floating_frame(
+        &self,
+        context: WindowRuleContext<'_>,
+    ) -> Option<crate::common::config::FloatingFrame> {
+        self.app_rules.floating_frame(context)
     }
 
     pub fn ensure_active_workspace_info(
@@ -2867,9 +3910,23 @@ impl LayoutEngine {
         self.floating_positions.transfer_window_identity(from, to);
         self.floating.transfer_window_identity(from, to);
         self.transfer_persisted_window_identity(from, to);
+        self.focus_history.transfer_window_identity(from, to);
         if let Some(constraints) = self.window_layout_constraints.remove(&from) {
             self.window_layout_constraints.insert(to, constraints);
         }
+        for window in self.departed_displays.values_mut().flatten() {
+            if window.wid == from {
+                window.wid = to;
+            }
+        }
+        if let Some(parent) = self.swallowed.remove(&from) {
+            self.swallowed.insert(to, parent);
+        }
+        for parent in self.swallowed.values_mut() {
+            if *parent == from {
+                *parent = to;
+            }
+        }
         if self.focused_window == Some(from) {
             self.focused_window = Some(to);
         }
@@ -2888,6 +3945,10 @@ impl LayoutEngine {
     ) {
         if let Some(workspace) = self.active_workspace(space) {
             for &(window, frame) in floating_positions {
+                // Keep the frame a zoomed window goes back to.
+                if self.zoomed.contains(&window) {
+                    continue;
+                }
                 self.floating_positions.store(space, workspace, window, frame);
             }
         }
@@ -2909,6 +3970,35 @@ impl LayoutEngine {
         }
     }
 
+    fn broadcast_workspace_renamed(
+        &self,
+        space_id: SpaceId,
+        workspace_id: VirtualWorkspaceId,
+        previous_name: String,
+    ) {
+        let Some(ref broadcast_tx) = self.broadcast_tx else {
+            return;
+        };
+        let Some(workspace) = self.virtual_workspace_manager.workspace_info(space_id, workspace_id)
+        else {
+            return;
+        };
+        let workspace_index = self
+            .virtual_workspace_manager
+            .existing_workspaces(space_id)
+            .iter()
+            .position(|(id, _)| *id == workspace_id)
+            .map(|index| index as u64);
+        let _ = broadcast_tx.send(BroadcastEvent::WorkspaceRenamed {
+            workspace_id,
+            workspace_index,
+            previous_name,
+            workspace_name: workspace.name.clone(),
+            space_id,
+            display_uuid: self.display_uuid_for_space(space_id),
+        });
+    }
+
     fn broadcast_windows_changed(&self, window_store: &WindowStore, space_id: SpaceId) {
         if let Some(ref broadcast_tx) = self.broadcast_tx {
             if let Some((workspace_id, workspace_name)) =
@@ -3136,6 +4226,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unfloating_a_window_puts_it_back_in_its_tile() {
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let space = SpaceId::new(306);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let pid: pid_t = 44;
+        let windows: Vec<WindowId> = (1..=3).map(|idx| WindowId::new(pid, idx)).collect();
+        let window_info = |wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None);
+
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::WindowsOnScreenUpdated(
+                space,
+                pid,
+                windows.iter().copied().map(window_info).collect(),
+                None,
+            ),
+        );
+        let (ws_id, layout) = engine.workspace_and_layout(space).expect("active layout");
+        let order =
+            |engine: &LayoutEngine| engine.workspace_tree(ws_id).all_windows_in_layout(layout);
+        assert_eq!(order(&engine), windows);
+
+        let toggle_float = |engine: &mut LayoutEngine, window_store: &mut WindowStore| {
+            let _ = engine.handle_command(
+                window_store,
+                Some(space),
+                &[space],
+                &HashMap::default(),
+                LayoutCommand::ToggleWindowFloating,
+            );
+        };
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::WindowFocused(space, windows[0]));
+        toggle_float(&mut engine, &mut window_store);
+        assert_eq!(order(&engine), windows[1..]);
+
+        // Selecting another tile would normally decide where the window lands.
+        engine.workspace_tree_mut(ws_id).select_window(layout, windows[2]);
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::WindowFocused(space, windows[0]));
+        toggle_float(&mut engine, &mut window_store);
+        assert_eq!(order(&engine), windows);
+    }
+
+    #[test]
+    fn deminiaturized_window_returns_to_its_tile() {
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let space = SpaceId::new(307);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let pid: pid_t = 45;
+        let windows: Vec<WindowId> = (1..=3).map(|idx| WindowId::new(pid, idx)).collect();
+        let window_info = |wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None);
+
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::WindowsOnScreenUpdated(
+                space,
+                pid,
+                windows.iter().copied().map(window_info).collect(),
+                None,
+            ),
+        );
+        let (ws_id, layout) = engine.workspace_and_layout(space).expect("active layout");
+
+        let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowMinimized(windows[1]));
+        assert_eq!(engine.workspace_tree(ws_id).all_windows_in_layout(layout), vec![
+            windows[0], windows[2]
+        ]);
+        assert_eq!(engine.last_minimized_window(), Some(windows[1]));
+        assert_eq!(
+            engine.virtual_workspace_manager().workspace_for_window(
+                &window_store,
+                space,
+                windows[1]
+            ),
+            Some(ws_id),
+            "minimized windows keep their workspace"
+        );
+
+        engine.workspace_tree_mut(ws_id).select_window(layout, windows[2]);
+        let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowAdded(space, windows[1]));
+        assert_eq!(
+            engine.workspace_tree(ws_id).all_windows_in_layout(layout),
+            windows
+        );
+        assert_eq!(engine.last_minimized_window(), None);
+    }
+
     #[test]
     fn window_removed_preserve_floating_keeps_workspace_assignment() {
         let mut window_store = WindowStore::default();
@@ -3314,6 +4499,7 @@ mod tests {
         settings.workspace_rules = vec![WorkspaceLayoutRule {
             workspace: WorkspaceSelector::Name(workspace_name),
             layout: LayoutMode::Scrolling,
+            template: None,
         }];
 
         engine.update_virtual_workspace_settings(&window_store, &settings);
@@ -3411,7 +4597,7 @@ mod tests {
             ),
         );
 
-        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+        let gaps = engine.layout_settings.gaps.effective_for_display(None, None);
         let positions = engine.calculate_layout_with_virtual_workspaces(
             &window_store,
             space,
@@ -3536,6 +4722,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn focus_last_walks_back_through_space_focus_history() {
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let space = SpaceId::new(95);
+        let pid: pid_t = 5154;
+        let (w1, w2, w3) = (
+            WindowId::new(pid, 1),
+            WindowId::new(pid, 2),
+            WindowId::new(pid, 3),
+        );
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::SpaceExposed(space, CGSize::new(1000.0, 1000.0)),
+        );
+        for wid in [w1, w2, w3] {
+            let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowAdded(space, wid));
+            let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowFocused(space, wid));
+        }
+        let mut command = |engine: &mut LayoutEngine, command: LayoutCommand| {
+            engine
+                .handle_command(
+                    &mut window_store,
+                    Some(space),
+                    &[space],
+                    &HashMap::default(),
+                    command,
+                )
+                .focus_window
+        };
+
+        assert_eq!(command(&mut engine, LayoutCommand::FocusLast), Some(w2));
+        assert_eq!(
+            command(&mut engine, LayoutCommand::FocusNthPrevious { n: 2 }),
+            Some(w1)
+        );
+        assert_eq!(
+            command(&mut engine, LayoutCommand::FocusNthPrevious { n: 0 }),
+            None
+        );
+
+        let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowRemoved(w2));
+        assert_eq!(command(&mut engine, LayoutCommand::FocusLast), Some(w3));
+    }
+
     #[test]
     fn partial_windows_on_screen_update_preserves_assigned_tiled_windows() {
         let mut window_store = WindowStore::default();
@@ -3678,6 +4909,142 @@ mod tests {
         );
     }
 
+    #[test]
+    fn smart_gaps_drop_gaps_around_a_lone_tiled_window() {
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let space = SpaceId::new(96);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let info = |wid| {
+            (
+                wid,
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            )
+        };
+        let mut gaps = engine.layout_settings.gaps.clone();
+        gaps.outer = crate::common::config::OuterGaps {
+            top: 20.0,
+            left: 20.0,
+            bottom: 20.0,
+            right: 20.0,
+        };
+        gaps.inner.horizontal = 10.0;
+        gaps.smart_gaps = true;
+        let first = WindowId::new(5170, 1);
+        let second = WindowId::new(5171, 1);
+
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::WindowsOnScreenUpdated(space, first.pid, vec![info(first)], None),
+        );
+        let alone = engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+        assert_eq!(alone, vec![(first, screen)]);
+
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::WindowsOnScreenUpdated(space, second.pid, vec![info(second)], None),
+        );
+        let shared = engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+        assert_eq!(shared.len(), 2);
+        assert!(shared.iter().all(|(_, frame)| frame.origin.y == 20.0));
+    }
+
+    #[test]
+    fn swallowed_window_gives_up_its_tile_until_the_child_closes() {
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let space = SpaceId::new(97);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let info = |wid| {
+            (
+                wid,
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            )
+        };
+        let terminal = WindowId::new(5160, 1);
+        let other = WindowId::new(5162, 1);
+        let child = WindowId::new(5161, 1);
+        let gaps = engine.layout_settings.gaps.clone();
+        let layout = |engine: &mut LayoutEngine| {
+            engine.calculate_layout(
+                space,
+                screen,
+                &gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            )
+        };
+
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        for wid in [terminal, other, child] {
+            let _ = engine.handle_event(
+                &mut window_store,
+                LayoutEvent::WindowsOnScreenUpdated(space, wid.pid, vec![info(wid)], None),
+            );
+        }
+
+        assert_eq!(
+            engine.swallow_window(&window_store, terminal, child),
+            Some(space)
+        );
+        assert!(engine.is_swallowed(terminal));
+        let swallowed = layout(&mut engine);
+        assert!(swallowed.iter().all(|(wid, _)| *wid != terminal));
+        let child_frame = swallowed
+            .iter()
+            .find(|(wid, _)| *wid == child)
+            .map(|(_, frame)| *frame)
+            .unwrap();
+
+        // Rediscovering the terminal's windows must not put it back.
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::WindowsOnScreenUpdated(space, terminal.pid, vec![info(terminal)], None),
+        );
+        assert!(layout(&mut engine).iter().all(|(wid, _)| *wid != terminal));
+
+        let _ = window_store.remove_window_assignment(child);
+        let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowRemoved(child));
+
+        assert!(!engine.is_swallowed(terminal));
+        let restored = layout(&mut engine);
+        assert_eq!(
+            restored.iter().find(|(wid, _)| *wid == terminal).map(|(_, frame)| *frame),
+            Some(child_frame)
+        );
+        assert!(restored.iter().all(|(wid, _)| *wid != child));
+    }
+
     #[test]
     fn removing_unassigned_window_rebalances_only_its_immediate_split() {
         let mut window_store = WindowStore::default();
@@ -3966,6 +5333,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn switching_to_the_active_workspace_goes_back_and_forth() {
+        let mut window_store = WindowStore::default();
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings {
+                workspace_auto_back_and_forth: true,
+                ..VirtualWorkspaceSettings::default()
+            },
+            &LayoutSettings::default(),
+            None,
+        );
+        let space = SpaceId::new(96);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        let workspaces = engine.virtual_workspace_manager_mut().list_workspaces(space).to_vec();
+        let (first, third) = (workspaces[0].0, workspaces[2].0);
+        assert_eq!(engine.active_workspace(space), Some(first));
+
+        let mut switch_to = |engine: &mut LayoutEngine, index| {
+            let _ = engine.handle_virtual_workspace_command(
+                &mut window_store,
+                space,
+                &LayoutCommand::SwitchToWorkspace(index),
+            );
+        };
+        switch_to(&mut engine, 2);
+        assert_eq!(engine.active_workspace(space), Some(third));
+        switch_to(&mut engine, 2);
+        assert_eq!(engine.active_workspace(space), Some(first));
+        switch_to(&mut engine, 0);
+        assert_eq!(engine.active_workspace(space), Some(third));
+    }
+
     #[test]
     fn move_window_to_workspace_updates_authoritative_workspace_membership() {
         let mut window_store = WindowStore::default();
@@ -4048,6 +5449,128 @@ mod tests {
         }
     }
 
+    #[test]
+    fn windows_of_a_reconnected_display_return_to_their_workspace() {
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let (external, main, reconnected) =
+            (SpaceId::new(201), SpaceId::new(202), SpaceId::new(203));
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let windows = [
+            WindowId::new(6020, 1),
+            WindowId::new(6020, 2),
+            WindowId::new(6021, 1),
+        ];
+
+        for space in [external, main] {
+            let _ = engine
+                .handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        }
+        for wid in windows {
+            let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowAdded(external, wid));
+        }
+
+        engine.remember_departed_display(&window_store, "external", external);
+        engine.migrate_departed_display(
+            &mut window_store,
+            "external",
+            main,
+            screen.size,
+            &WorkspaceSelector::Index(1),
+        );
+        let parked = engine.virtual_workspace_manager_mut().list_workspaces(main)[1].0;
+        for wid in windows {
+            assert_eq!(
+                engine.virtual_workspace_manager.workspace_for_window(&window_store, main, wid),
+                Some(parked)
+            );
+        }
+
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::SpaceExposed(reconnected, screen.size),
+        );
+        assert!(engine.restore_departed_display(
+            &mut window_store,
+            "external",
+            reconnected,
+            screen.size
+        ));
+        let gaps = engine.layout_settings.gaps.clone();
+        let order: Vec<_> = engine
+            .calculate_layout(
+                reconnected,
+                screen,
+                &gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            )
+            .into_iter()
+            .map(|(wid, _)| wid)
+            .collect();
+        assert_eq!(order, windows);
+        assert!(!engine.restore_departed_display(
+            &mut window_store,
+            "external",
+            reconnected,
+            screen.size
+        ));
+    }
+
+    #[test]
+    fn launched_window_is_inserted_after_the_recorded_selection() {
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let space = SpaceId::new(97);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let (w1, w2) = (WindowId::new(6010, 1), WindowId::new(6010, 2));
+        let launched = WindowId::new(6011, 1);
+
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        for wid in [w1, w2, launched] {
+            let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowAdded(space, wid));
+        }
+        let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowFocused(space, w1));
+        let _ = engine.handle_virtual_workspace_command(
+            &mut window_store,
+            space,
+            &LayoutCommand::CreateWorkspace,
+        );
+        let active = engine.virtual_workspace_manager.active_workspace(space);
+        // Simulate a rule sending the new window to another workspace.
+        let _ = engine.handle_virtual_workspace_command(
+            &mut window_store,
+            space,
+            &LayoutCommand::MoveWindowToWorkspace {
+                workspace: WorkspaceSelector::Index(1),
+                follow: false,
+                window_id: Some(launched.idx.get()),
+            },
+        );
+
+        assert!(engine.insert_window_at_selection(&mut window_store, space, launched, Some(w1)));
+        assert_eq!(
+            engine
+                .virtual_workspace_manager
+                .workspace_for_window(&window_store, space, launched),
+            active
+        );
+        let gaps = engine.layout_settings.gaps.clone();
+        let order: Vec<_> = engine
+            .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+            .into_iter()
+            .map(|(wid, _)| wid)
+            .collect();
+        assert_eq!(order, vec![w1, launched, w2]);
+
+        assert!(
+            !engine.insert_window_at_selection(&mut window_store, space, launched, Some(w2)),
+            "windows already in the workspace keep their place"
+        );
+    }
+
     #[test]
     fn move_window_to_workspace_can_follow_the_window() {
         let mut window_store = WindowStore::default();
@@ -4101,4 +5624,228 @@ mod tests {
             Some(target_workspace)
         );
     }
+
+    #[test]
+    fn preselect_accepts_a_bare_direction_or_a_ratio() {
+        let bare: LayoutCommand = serde_json::from_str(r#"{"preselect": "left"}"#).unwrap();
+        assert_eq!(bare, LayoutCommand::Preselect {
+            direction: Direction::Left,
+            ratio: None,
+        });
+
+        let with_ratio = LayoutCommand::Preselect {
+            direction: Direction::Right,
+            ratio: Some(0.3),
+        };
+        let json = serde_json::to_string(&with_ratio).unwrap();
+        assert_eq!(serde_json::from_str::<LayoutCommand>(&json).unwrap(), with_ratio);
+    }
+
+    #[test]
+    fn zoom_covers_the_tiling_area_without_touching_the_tree() {
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let space = SpaceId::new(97);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let pid: pid_t = 5170;
+        let (w1, w2) = (WindowId::new(pid, 1), WindowId::new(pid, 2));
+        let window = |wid| {
+            (
+                wid,
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            )
+        };
+
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::WindowsOnScreenUpdated(space, pid, vec![window(w1), window(w2)], None),
+        );
+        let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowFocused(space, w1));
+        let mut gaps = engine.layout_settings.gaps.effective_for_display(None, None);
+        gaps.outer.top = 10.0;
+        let layout = |engine: &mut LayoutEngine, window_store: &WindowStore| {
+            engine
+                .calculate_layout_with_virtual_workspaces(
+                    window_store,
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    |_| None,
+                    &[screen],
+                )
+                .into_iter()
+                .collect::<HashMap<WindowId, CGRect>>()
+        };
+        let tiled = layout(&mut engine, &window_store);
+
+        let zoom = |engine: &mut LayoutEngine, window_store: &mut WindowStore| {
+            engine.handle_command(
+                window_store,
+                Some(space),
+                &[space],
+                &HashMap::default(),
+                LayoutCommand::ToggleZoom,
+            )
+        };
+        let response = zoom(&mut engine, &mut window_store);
+        assert_eq!(response.raise_windows, vec![w1]);
+        assert!(engine.is_window_zoomed(w1));
+        let zoomed = layout(&mut engine, &window_store);
+        assert_eq!(
+            zoomed[&w1],
+            CGRect::new(CGPoint::new(0.0, 10.0), CGSize::new(1000.0, 790.0))
+        );
+        assert_eq!(zoomed[&w2], tiled[&w2]);
+
+        let _ = zoom(&mut engine, &mut window_store);
+        assert!(!engine.is_window_zoomed(w1));
+        assert_eq!(layout(&mut engine, &window_store), tiled);
+    }
+
+    #[test]
+    fn dropped_floating_windows_are_tiled_beside_the_tile_under_the_cursor() {
+        let tile = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(900.0, 600.0));
+        let target = WindowId::new(1, 1);
+        let zone = |x| drop_zone_at_point(CGPoint::new(x, 300.0), &[(target, tile)]);
+        assert_eq!(zone(100.0).map(|zone| zone.kind), Some(DropZoneKind::Left));
+        assert_eq!(zone(450.0).map(|zone| zone.kind), Some(DropZoneKind::Container));
+        assert_eq!(
+            zone(800.0).map(|zone| zone.frame),
+            Some(CGRect::new(CGPoint::new(450.0, 0.0), CGSize::new(450.0, 600.0)))
+        );
+        assert_eq!(zone(950.0), None);
+
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let space = SpaceId::new(98);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let pid: pid_t = 5180;
+        let (w1, w2) = (WindowId::new(pid, 1), WindowId::new(pid, 2));
+        let window = |wid| {
+            (
+                wid,
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            )
+        };
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::WindowsOnScreenUpdated(space, pid, vec![window(w1), window(w2)], None),
+        );
+        let _ = engine.handle_event(&mut window_store, LayoutEvent::WindowFocused(space, w2));
+        let _ = engine.handle_command(
+            &mut window_store,
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::ToggleWindowFloating,
+        );
+        assert!(engine.is_window_floating(w2));
+
+        let tiles = [(w1, screen)];
+        assert!(!engine.insert_window_at_point(
+            &mut window_store,
+            space,
+            w2,
+            CGPoint::new(2000.0, 300.0),
+            &tiles
+        ));
+        assert!(engine.insert_window_at_point(
+            &mut window_store,
+            space,
+            w2,
+            CGPoint::new(100.0, 300.0),
+            &tiles
+        ));
+        assert!(!engine.is_window_floating(w2));
+        let gaps = engine.layout_settings.gaps.effective_for_display(None, None);
+        let frames: HashMap<WindowId, CGRect> = engine
+            .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+            .into_iter()
+            .collect();
+        assert!(frames[&w2].max().x <= frames[&w1].origin.x);
+    }
+
+    #[test]
+    fn tiled_windows_are_moved_next_to_the_tile_at_a_position() {
+        let mut window_store = WindowStore::default();
+        let mut engine = test_engine();
+        let space = SpaceId::new(99);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(900.0, 600.0));
+        let pid: pid_t = 5181;
+        let (w1, w2, w3) = (
+            WindowId::new(pid, 1),
+            WindowId::new(pid, 2),
+            WindowId::new(pid, 3),
+        );
+        let window = |wid| {
+            (
+                wid,
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(300.0, 600.0),
+                None,
+                None,
+            )
+        };
+        let _ =
+            engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(
+            &mut window_store,
+            LayoutEvent::WindowsOnScreenUpdated(
+                space,
+                pid,
+                vec![window(w1), window(w2), window(w3)],
+                None,
+            ),
+        );
+
+        // The window's own tile is not a drop target.
+        assert_eq!(
+            engine.drop_zone_at_position(space, w1, CGPoint::new(150.0, 300.0), screen),
+            None
+        );
+        assert!(!engine.insert_window_at_position(
+            &mut window_store,
+            space,
+            w1,
+            CGPoint::new(150.0, 300.0),
+            screen
+        ));
+        assert!(engine.insert_window_at_position(
+            &mut window_store,
+            space,
+            w1,
+            CGPoint::new(880.0, 300.0),
+            screen
+        ));
+        let gaps = engine.layout_settings.gaps.effective_for_display(None, None);
+        let frames: HashMap<WindowId, CGRect> = engine
+            .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+            .into_iter()
+            .collect();
+        assert_eq!(frames.len(), 3);
+        assert!(frames[&w2].max().x <= frames[&w3].origin.x);
+        assert!(frames[&w3].max().x <= frames[&w1].origin.x);
+    }
 }
@@ -132,13 +132,18 @@ fn compare_fallback(
         let app = (saved.app_id.is_some() && saved.app_id == live.app_id) as u8;
         let title = (saved.title.is_some() && saved.title == live.title) as u8;
         let size_delta = (saved.width - live.width).abs() + (saved.height - live.height).abs();
-        (app, title, size_delta)
+        let position_delta =
+            saved.position.zip(live.position).map_or(f64::INFINITY, |((sx, sy), (lx, ly))| {
+                (sx - lx).abs() + (sy - ly).abs()
+            });
+        (app, title, size_delta, position_delta)
     };
-    let (app_a, title_a, size_a) = score(a.fingerprint);
-    let (app_b, title_b, size_b) = score(b.fingerprint);
+    let (app_a, title_a, size_a, position_a) = score(a.fingerprint);
+    let (app_b, title_b, size_b, position_b) = score(b.fingerprint);
     app_a
         .cmp(&app_b)
         .then_with(|| title_a.cmp(&title_b))
         .then_with(|| size_b.partial_cmp(&size_a).unwrap_or(Ordering::Equal))
+        .then_with(|| position_b.partial_cmp(&position_a).unwrap_or(Ordering::Equal))
         .then_with(|| b.window.cmp(&a.window))
 }
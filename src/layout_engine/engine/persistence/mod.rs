@@ -7,14 +7,15 @@ use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use objc2_core_foundation::CGSize;
 use serde::{Deserialize, Serialize};
 
-use super::{FloatingManager, LayoutEngine, WorkspaceLayouts};
+use super::{DepartedWindow, FloatingManager, LayoutEngine, WorkspaceLayouts};
 use crate::actor::app::{WindowId, pid_t};
 use crate::common::collections::{HashMap, HashSet};
 use crate::common::config::{LayoutSettings, VirtualWorkspaceSettings};
 use crate::layout_engine::LayoutSystem;
 use crate::model::broadcast::BroadcastSender;
 use crate::model::{
-    AppRuleEngine, FloatingPositionStore, VirtualWorkspaceId, WindowStore, WorkspaceStore,
+    AppRuleEngine, FloatingPositionStore, FocusHistory, VirtualWorkspaceId, WindowStore,
+    WindowVisibility, WorkspaceStore,
 };
 use crate::sys::screen::SpaceId;
 
@@ -108,6 +109,10 @@ struct WindowFingerprint {
     width: f64,
     height: f64,
     app_id: Option<String>,
+    /// Last observed AX origin. Only used to tell apart same-app windows whose titles and
+    /// sizes are otherwise indistinguishable, e.g. two terminals after a WindowServer re-id.
+    #[serde(default)]
+    position: Option<(f64, f64)>,
 }
 
 impl WindowFingerprint {
@@ -147,6 +152,11 @@ impl PersistenceState {
                     "window {window:?} has an invalid persisted size"
                 ));
             }
+            if fingerprint.position.is_some_and(|(x, y)| !x.is_finite() || !y.is_finite()) {
+                return Err(anyhow::anyhow!(
+                    "window {window:?} has an invalid persisted position"
+                ));
+            }
         }
         Ok(())
     }
@@ -196,6 +206,24 @@ impl PersistenceState {
     }
 }
 
+/// Position evidence is only meaningful while a window is on screen. Windows parked by an
+/// inactive workspace keep whatever position was recorded while they were last visible.
+fn observed_position(
+    window_store: &WindowStore,
+    window: WindowId,
+    previous: Option<&WindowFingerprint>,
+) -> Option<(f64, f64)> {
+    let visible = window_store
+        .record(window)
+        .is_some_and(|record| record.visibility() == WindowVisibility::Visible);
+    window_store
+        .window(window)
+        .filter(|_| visible)
+        .map(|window| (window.frame_monotonic.origin.x, window.frame_monotonic.origin.y))
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .or_else(|| previous.and_then(|fingerprint| fingerprint.position))
+}
+
 impl LayoutEngine {
     pub(super) fn observe_window_for_persistence(
         &mut self,
@@ -215,6 +243,7 @@ impl LayoutEngine {
             width: size.width,
             height: size.height,
             app_id: app_id.filter(|app_id| !app_id.trim().is_empty()).map(str::to_owned),
+            position: observed_position(window_store, window, self.persistence.fingerprint(window)),
         };
         self.reconcile_restored_window(window_store, space, window, &fingerprint);
         self.persistence.record(window, fingerprint);
@@ -237,5 +266,7 @@ mod restore;
 mod snapshot;
 mod storage;
 
+pub use storage::StateInspection;
+
 #[cfg(test)]
 mod tests;
@@ -18,6 +18,9 @@ impl LayoutEngine {
             self.floating.remove_floating(*window);
             self.virtual_workspace_manager.forget_window_identity(*window);
             self.window_layout_constraints.remove(window);
+            for departed in self.departed_displays.values_mut() {
+                departed.retain(|departed| departed.wid != *window);
+            }
             if self.focused_window == Some(*window) {
                 self.focused_window = None;
             }
@@ -123,6 +126,11 @@ impl LayoutEngine {
                 width: window.frame_monotonic.size.width,
                 height: window.frame_monotonic.size.height,
                 app_id,
+                position: observed_position(
+                    window_store,
+                    window_id,
+                    self.persistence.fingerprint(window_id),
+                ),
             });
         }
     }
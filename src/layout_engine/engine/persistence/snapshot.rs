@@ -4,6 +4,37 @@ pub(super) const CURRENT_SCHEMA_VERSION: u32 = 2;
 
 fn legacy_schema_version() -> u32 { 0 }
 
+/// A textual rewrite that lets a snapshot written before schema version `to` parse as the
+/// current schema. `apply` returns `None` when the file contains nothing it needs to change.
+pub(super) struct Migration {
+    pub(super) to: u32,
+    pub(super) description: &'static str,
+    pub(super) apply: fn(&str) -> Option<String>,
+}
+
+/// Every known upgrade step, oldest first. An incompatible change to a persisted type must
+/// bump `CURRENT_SCHEMA_VERSION` and add its step here; relying on `serde(default)` alone is
+/// only safe for purely additive fields.
+pub(super) const MIGRATIONS: &[Migration] = &[Migration {
+    to: 2,
+    description: "layout systems are tagged by variant name instead of a `kind` field",
+    apply: super::storage::migrate_legacy_layout_system_tags,
+}];
+
+/// Run every migration over `buf`, returning the rewritten text and the steps that changed
+/// it, or `None` when no step applied.
+pub(super) fn apply_migrations(buf: &str) -> Option<(String, Vec<&'static Migration>)> {
+    let mut current = std::borrow::Cow::Borrowed(buf);
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS {
+        if let Some(next) = (migration.apply)(&current) {
+            current = std::borrow::Cow::Owned(next);
+            applied.push(migration);
+        }
+    }
+    (!applied.is_empty()).then(|| (current.into_owned(), applied))
+}
+
 /// Owned, versioned representation of the layout file.
 ///
 /// Keep this type independent from runtime-only `LayoutEngine` fields. Adding an engine cache,
@@ -20,6 +51,8 @@ pub(super) struct PersistedLayout {
     pub(super) space_display_map: HashMap<SpaceId, Option<String>>,
     #[serde(default)]
     pub(super) display_last_space: HashMap<String, SpaceId>,
+    #[serde(default)]
+    pub(super) departed_displays: HashMap<String, Vec<DepartedWindow>>,
     #[serde(flatten)]
     pub(super) persistence: PersistenceState,
 }
@@ -34,6 +67,7 @@ struct PersistedLayoutRef<'a> {
     virtual_workspace_manager: &'a WorkspaceStore,
     space_display_map: &'a HashMap<SpaceId, Option<String>>,
     display_last_space: &'a HashMap<String, SpaceId>,
+    departed_displays: &'a HashMap<String, Vec<DepartedWindow>>,
     #[serde(flatten)]
     persistence: &'a PersistenceState,
 }
@@ -52,6 +86,7 @@ impl PersistedLayout {
             virtual_workspace_manager: &engine.virtual_workspace_manager,
             space_display_map: &engine.space_display_map,
             display_last_space: &engine.display_last_space,
+            departed_displays: &engine.departed_displays,
             persistence: &engine.persistence,
         })
         .expect("persisted layout serialization must support all engine layout state")
@@ -64,13 +99,21 @@ impl PersistedLayout {
             floating_positions: self.floating_positions,
             app_rules: AppRuleEngine::default(),
             focused_window: None,
+            focus_history: FocusHistory::default(),
             window_layout_constraints: HashMap::default(),
             virtual_workspace_manager: self.virtual_workspace_manager,
             layout_settings: LayoutSettings::default(),
             broadcast_tx: None,
             space_display_map: self.space_display_map,
             display_last_space: self.display_last_space,
+            display_names: HashMap::default(),
+            departed_displays: self.departed_displays,
             persistence: self.persistence,
+            swallowed: HashMap::default(),
+            tiling_bookmarks: HashMap::default(),
+            minimized: Vec::new(),
+            zoomed: HashSet::default(),
+            app_main_windows: HashMap::default(),
             startup_restore_pending: false,
         }
     }
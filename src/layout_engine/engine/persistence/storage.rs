@@ -1,6 +1,41 @@
-use super::snapshot::{CURRENT_SCHEMA_VERSION, PersistedLayout};
+use super::snapshot::{CURRENT_SCHEMA_VERSION, Migration, PersistedLayout, apply_migrations};
 use super::*;
 
+/// How a snapshot on disk relates to the schema this build writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateInspection {
+    pub schema_version: u32,
+    pub current_schema_version: u32,
+    /// Descriptions of the migrations needed to read the file.
+    pub migrations: Vec<&'static str>,
+    pub native_spaces: usize,
+    pub virtual_workspaces: usize,
+    pub saved_windows: usize,
+    pub restore_candidates: usize,
+}
+
+impl StateInspection {
+    pub fn needs_migration(&self) -> bool {
+        self.schema_version < self.current_schema_version || !self.migrations.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "schema version {} (current {}); {} native space(s), {} workspace(s), {} saved window(s), {} restore candidate(s)",
+            self.schema_version,
+            self.current_schema_version,
+            self.native_spaces,
+            self.virtual_workspaces,
+            self.saved_windows,
+            self.restore_candidates,
+        );
+        for migration in &self.migrations {
+            summary.push_str(&format!("\nneeds migration: {migration}"));
+        }
+        summary
+    }
+}
+
 impl LayoutEngine {
     pub fn load(path: PathBuf) -> anyhow::Result<Self> {
         Self::load_with_schema_version(&path).map(|(engine, _)| engine)
@@ -34,22 +69,59 @@ impl LayoutEngine {
     }
 
     pub(super) fn load_with_schema_version(path: &Path) -> anyhow::Result<(Self, u32)> {
+        Self::load_with_migrations(path).map(|(engine, version, _)| (engine, version))
+    }
+
+    fn load_with_migrations(path: &Path) -> anyhow::Result<(Self, u32, Vec<&'static Migration>)> {
         let mut buf = String::new();
         File::open(path)?.read_to_string(&mut buf)?;
         Self::deserialize_from_str_with_schema_version(&buf)
     }
 
     pub(crate) fn deserialize_from_str(buf: &str) -> anyhow::Result<Self> {
-        Self::deserialize_from_str_with_schema_version(buf).map(|(engine, _)| engine)
+        Self::deserialize_from_str_with_schema_version(buf).map(|(engine, ..)| engine)
+    }
+
+    /// Read a snapshot file without installing it, reporting its schema version and contents.
+    pub fn inspect_state(path: &Path) -> anyhow::Result<StateInspection> {
+        let (engine, schema_version, migrations) = Self::load_with_migrations(path)?;
+        Ok(StateInspection {
+            schema_version,
+            current_schema_version: CURRENT_SCHEMA_VERSION,
+            migrations: migrations.iter().map(|migration| migration.description).collect(),
+            native_spaces: engine.workspace_layouts.spaces().len(),
+            virtual_workspaces: engine.virtual_workspace_manager.workspaces.len(),
+            saved_windows: engine.persistence.windows.len(),
+            restore_candidates: engine.persistence.pending_len(),
+        })
+    }
+
+    /// Rewrite an older snapshot in the current schema. The original file is kept next to it
+    /// as `<name>.v<version>.bak`, whose path is returned; `None` means the file was current.
+    pub fn migrate_state(path: &Path) -> anyhow::Result<Option<PathBuf>> {
+        let (engine, schema_version, migrations) = Self::load_with_migrations(path)?;
+        if schema_version >= CURRENT_SCHEMA_VERSION && migrations.is_empty() {
+            return Ok(None);
+        }
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(format!(".v{schema_version}.bak"));
+        let backup = PathBuf::from(backup);
+        fs::copy(path, &backup)?;
+        engine.save(path.to_path_buf())?;
+        Ok(Some(backup))
     }
 
-    fn deserialize_from_str_with_schema_version(buf: &str) -> anyhow::Result<(Self, u32)> {
+    fn deserialize_from_str_with_schema_version(
+        buf: &str,
+    ) -> anyhow::Result<(Self, u32, Vec<&'static Migration>)> {
+        let mut migrations = Vec::new();
         let persisted = match PersistedLayout::deserialize(buf) {
             Ok(persisted) => persisted,
             Err(original_error) => {
-                let Some(migrated) = migrate_legacy_layout_system_tags(buf) else {
+                let Some((migrated, applied)) = apply_migrations(buf) else {
                     return Err(original_error.into());
                 };
+                migrations = applied;
                 PersistedLayout::deserialize(&migrated).map_err(|migration_error| {
                     anyhow::anyhow!(
                         "could not parse layout file ({original_error}); compatibility migration also failed ({migration_error})"
@@ -57,6 +129,9 @@ impl LayoutEngine {
                 })?
             }
         };
+        // A file can only need a step introduced after the version it claims. Anything else was
+        // rewritten by an older build that already understood the change; keep the report honest.
+        migrations.retain(|migration| migration.to > persisted.schema_version);
         if persisted.schema_version > CURRENT_SCHEMA_VERSION {
             return Err(anyhow::anyhow!(
                 "layout schema version {} is newer than supported version {}",
@@ -116,7 +191,7 @@ impl LayoutEngine {
             .filter(|window| engine.restored_location_for_window(*window).is_some())
             .collect::<Vec<_>>();
         engine.persistence.replace_pending(pending);
-        Ok((engine, schema_version))
+        Ok((engine, schema_version, migrations))
     }
 
     pub fn save(&self, path: PathBuf) -> std::io::Result<()> {
@@ -334,6 +409,34 @@ impl LayoutEngine {
 
     pub fn serialize_to_string(&self) -> String { PersistedLayout::serialize_engine(self) }
 
+    /// A copy to try commands on, made by reloading the persisted state and
+    /// carrying over what only lives at runtime. It broadcasts nothing.
+    /// Scroll offsets are not persisted, so scrolling layouts start aligned.
+    pub fn dry_run_copy(
+        &self,
+        virtual_workspace_config: &VirtualWorkspaceSettings,
+    ) -> anyhow::Result<LayoutEngine> {
+        let mut copy = Self::deserialize_from_str(&self.serialize_to_string())?;
+        copy.set_layout_settings(&self.layout_settings);
+        copy.app_rules = self.app_rules.clone();
+        copy.virtual_workspace_manager
+            .update_settings(virtual_workspace_config, &self.layout_settings);
+        copy.floating = self.floating.clone();
+        copy.focused_window = self.focused_window;
+        copy.focus_history = self.focus_history.clone();
+        copy.window_layout_constraints = self.window_layout_constraints.clone();
+        copy.display_names = self.display_names.clone();
+        copy.departed_displays = self.departed_displays.clone();
+        copy.swallowed = self.swallowed.clone();
+        copy.tiling_bookmarks = self.tiling_bookmarks.clone();
+        copy.minimized = self.minimized.clone();
+        copy.zoomed = self.zoomed.clone();
+        copy.app_main_windows = self.app_main_windows.clone();
+        Ok(copy)
+    }
+
+    /// Applies the current config to an engine made by `new` or loaded from
+    /// disk. This is where the rule script is loaded, once per startup.
     pub fn finish_loading(
         &mut self,
         virtual_workspace_config: &VirtualWorkspaceSettings,
@@ -342,7 +445,8 @@ impl LayoutEngine {
     ) {
         self.broadcast_tx = broadcast_tx;
         self.set_layout_settings(layout_settings);
-        self.app_rules = AppRuleEngine::new(&virtual_workspace_config.app_rules);
+        self.app_rules = AppRuleEngine::new(&virtual_workspace_config.app_rules)
+            .with_script(virtual_workspace_config.script.as_ref());
         self.virtual_workspace_manager
             .update_settings(virtual_workspace_config, layout_settings);
     }
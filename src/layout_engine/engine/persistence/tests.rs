@@ -37,6 +37,7 @@ fn identity_transfer_preserves_window_tree_position_and_fingerprint() {
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.editor".into()),
+        position: None,
     });
     engine.persistence.pending_windows.insert(old);
     let workspace = engine.active_workspace(space).unwrap();
@@ -97,6 +98,7 @@ fn save_and_load_arms_fingerprint_reconciliation() {
         width: 900.0,
         height: 700.0,
         app_id: Some("com.example.editor".into()),
+        position: None,
     });
     let path = std::env::temp_dir().join(format!(
         "rift-layout-restore-test-{}-{}.ron",
@@ -113,6 +115,32 @@ fn save_and_load_arms_fingerprint_reconciliation() {
     assert!(loaded.restored_location_for_window(window).is_some());
 }
 
+#[test]
+fn departed_displays_survive_save_and_load() {
+    let mut engine = test_engine();
+    let window = WindowId::new(43, 2);
+    let tile = crate::layout_engine::systems::TilingBookmark { container: None, index: 1 };
+    engine.departed_displays.insert("external-display".into(), vec![DepartedWindow {
+        wid: window,
+        workspace_index: 2,
+        tile: Some(tile),
+    }]);
+    let path = std::env::temp_dir().join(format!(
+        "rift-layout-departed-test-{}.ron",
+        std::process::id()
+    ));
+
+    engine.save(path.clone()).unwrap();
+    let loaded = LayoutEngine::load(path.clone()).unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let departed = &loaded.departed_displays["external-display"];
+    assert_eq!(departed.len(), 1);
+    assert_eq!(departed[0].wid, window);
+    assert_eq!(departed[0].workspace_index, 2);
+    assert_eq!(departed[0].tile, Some(tile));
+}
+
 #[test]
 fn full_save_records_floating_window_in_its_inactive_workspace() {
     let mut engine = test_engine();
@@ -258,6 +286,7 @@ fn load_does_not_arm_locationless_fingerprints() {
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.orphan".into()),
+        position: None,
     });
     engine
         .virtual_workspace_manager
@@ -332,6 +361,7 @@ fn startup_validation_preserves_stale_ids_when_the_app_can_still_fuzzy_match() {
             width: 600.0,
             height: 800.0,
             app_id: Some(format!("com.example.{}", window.pid)),
+            position: None,
         });
         engine.persistence.pending_windows.insert(window);
     }
@@ -388,6 +418,7 @@ fn workspace_restore_discards_unmatched_scoped_windows_and_floating_state() {
             width: 500.0,
             height: 400.0,
             app_id: Some("com.example.restore".into()),
+            position: None,
         });
     }
     let path = std::env::temp_dir().join(format!(
@@ -451,6 +482,7 @@ fn workspace_restore_keeps_current_windows_absent_from_snapshot() {
         width: 700.0,
         height: 500.0,
         app_id: Some("com.example.saved".into()),
+        position: None,
     });
     let path = std::env::temp_dir().join(format!(
         "rift-live-window-restore-test-{}-{}.ron",
@@ -560,6 +592,7 @@ fn scoped_restore_does_not_consume_same_id_live_window_on_another_space() {
         width: 700.0,
         height: 500.0,
         app_id: Some("com.example.old".into()),
+        position: None,
     });
     let path = std::env::temp_dir().join(format!(
         "rift-cross-space-id-collision-test-{}-{}.ron",
@@ -658,6 +691,7 @@ fn completed_app_discovery_discards_unmatched_startup_ghosts() {
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.closed-window".into()),
+        position: None,
     });
     engine.persistence.pending_windows.insert(ghost);
     let _ = engine.handle_event(
@@ -674,6 +708,7 @@ fn completed_app_discovery_discards_unmatched_startup_ghosts() {
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.closed-window".into()),
+        position: None,
     });
     engine.persistence.pending_windows.insert(inactive_ghost);
     let workspace = engine.active_workspace(space).unwrap();
@@ -722,6 +757,31 @@ fn persisted_layout_schema_is_versioned_and_legacy_files_still_load() {
     assert!(error.to_string().contains("newer than supported"));
 }
 
+#[test]
+fn legacy_state_file_is_inspected_and_migrated_with_backup() {
+    let engine = test_engine();
+    let legacy = engine.serialize_to_string().replacen("\"schema_version\":2,", "", 1);
+    let path =
+        std::env::temp_dir().join(format!("rift-layout-migrate-test-{}.ron", std::process::id()));
+    std::fs::write(&path, &legacy).unwrap();
+
+    let inspection = LayoutEngine::inspect_state(&path).unwrap();
+    assert_eq!(inspection.schema_version, 0);
+    assert!(inspection.needs_migration());
+
+    let backup = LayoutEngine::migrate_state(&path).unwrap().expect("legacy file should migrate");
+    let migrated = LayoutEngine::inspect_state(&path).unwrap();
+    let original = std::fs::read_to_string(&backup).unwrap();
+    let rerun = LayoutEngine::migrate_state(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&backup);
+
+    assert_eq!(original, legacy);
+    assert_eq!(migrated.schema_version, migrated.current_schema_version);
+    assert!(!migrated.needs_migration());
+    assert_eq!(rerun, None);
+}
+
 #[test]
 fn malformed_active_layout_configuration_is_rejected_at_load_boundary() {
     let mut engine = test_engine();
@@ -762,6 +822,7 @@ fn invalid_persisted_window_geometry_is_rejected() {
         width: -1.0,
         height: 500.0,
         app_id: Some("com.example.invalid".into()),
+        position: None,
     });
 
     let error = match LayoutEngine::deserialize_from_str(&engine.serialize_to_string()) {
@@ -1022,7 +1083,7 @@ fn master_restore_resolves_old_space_id_by_display_identity() {
         (saved_b, "display-b", LayoutMode::Scrolling),
     ] {
         let _ = snapshot.handle_event(&mut snapshot_store, LayoutEvent::SpaceExposed(space, size));
-        snapshot.update_space_display(space, Some(display.into()));
+        snapshot.update_space_display(space, Some(display.into()), None);
         let workspace = snapshot.active_workspace(space).unwrap();
         assert!(snapshot.switch_workspace_layout_mode(&snapshot_store, space, workspace, mode,));
     }
@@ -1037,7 +1098,7 @@ fn master_restore_resolves_old_space_id_by_display_identity() {
     let mut engine = test_engine();
     let mut window_store = WindowStore::default();
     let _ = engine.handle_event(&mut window_store, LayoutEvent::SpaceExposed(current_a, size));
-    engine.update_space_display(current_a, Some("display-a".into()));
+    engine.update_space_display(current_a, Some("display-a".into()), None);
     engine
         .restore_layout(
             path.clone(),
@@ -1091,7 +1152,7 @@ fn startup_restore_remaps_saved_space_by_display_identity_once() {
     let mut snapshot_store = WindowStore::default();
     let _ =
         snapshot.handle_event(&mut snapshot_store, LayoutEvent::SpaceExposed(saved_space, size));
-    snapshot.update_space_display(saved_space, Some(display.clone()));
+    snapshot.update_space_display(saved_space, Some(display.clone()), None);
     let path = std::env::temp_dir().join(format!(
         "rift-startup-space-remap-test-{}-{}.ron",
         std::process::id(),
@@ -1124,7 +1185,7 @@ fn startup_restore_handles_space_id_swaps_between_displays() {
     let mut snapshot_store = WindowStore::default();
     for (space, display) in [(space_a, "display-a"), (space_b, "display-b")] {
         let _ = snapshot.handle_event(&mut snapshot_store, LayoutEvent::SpaceExposed(space, size));
-        snapshot.update_space_display(space, Some(display.into()));
+        snapshot.update_space_display(space, Some(display.into()), None);
         let workspace = snapshot.active_workspace(space).unwrap();
         assert!(snapshot.virtual_workspace_manager.rename_workspace(
             space,
@@ -1183,6 +1244,7 @@ fn pure_matcher_reports_duplicate_identities_without_mutating_candidates() {
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.editor".into()),
+        position: None,
     };
     let candidates = vec![
         RestoreCandidate {
@@ -1225,6 +1287,7 @@ fn direct_window_identity_never_consumes_another_candidate() {
         width: 600.0,
         height: 800.0,
         app_id: Some("com.example.editor".into()),
+        position: None,
     };
     let other_fingerprint = WindowFingerprint {
         window_server_id: Some(20),
@@ -1232,6 +1295,7 @@ fn direct_window_identity_never_consumes_another_candidate() {
         width: 600.0,
         height: 800.0,
         app_id: Some("com.example.editor".into()),
+        position: None,
     };
     let live_fingerprint = WindowFingerprint {
         window_server_id: Some(20),
@@ -1239,6 +1303,7 @@ fn direct_window_identity_never_consumes_another_candidate() {
         width: 600.0,
         height: 800.0,
         app_id: Some("com.example.editor".into()),
+        position: None,
     };
     let candidates = [
         RestoreCandidate {
@@ -1274,6 +1339,7 @@ fn reused_direct_window_identity_cannot_cross_known_application_identity() {
         width: 600.0,
         height: 800.0,
         app_id: Some("com.example.old".into()),
+        position: None,
     };
     let right_app = WindowFingerprint {
         window_server_id: Some(20),
@@ -1281,6 +1347,7 @@ fn reused_direct_window_identity_cannot_cross_known_application_identity() {
         width: 600.0,
         height: 800.0,
         app_id: Some("com.example.current".into()),
+        position: None,
     };
     let candidates = [
         RestoreCandidate {
@@ -1314,6 +1381,7 @@ fn fuzzy_match_requires_window_specific_evidence() {
         width: 500.0,
         height: 500.0,
         app_id: Some("com.example.app".into()),
+        position: None,
     };
     let unrelated_live = WindowFingerprint {
         window_server_id: None,
@@ -1321,6 +1389,7 @@ fn fuzzy_match_requires_window_specific_evidence() {
         width: 900.0,
         height: 700.0,
         app_id: Some("com.example.app".into()),
+        position: None,
     };
     let candidate = [RestoreCandidate {
         window: saved,
@@ -1349,6 +1418,7 @@ fn fuzzy_match_requires_window_specific_evidence() {
         width: 1200.0,
         height: 900.0,
         app_id: Some("com.example.other".into()),
+        position: None,
     };
     let unknown_candidate = [RestoreCandidate {
         window: saved,
@@ -1367,6 +1437,47 @@ fn fuzzy_match_requires_window_specific_evidence() {
     );
 }
 
+#[test]
+fn fuzzy_match_uses_position_to_separate_identical_app_windows() {
+    use super::matcher::{RestoreCandidate, choose_match};
+
+    let left = WindowId::new(42, 1);
+    let right = WindowId::new(42, 2);
+    let live = WindowId::new(99, 1);
+    let space = SpaceId::new(503);
+    let workspace = crate::model::VirtualWorkspaceId::default();
+    let saved = |x: f64| WindowFingerprint {
+        window_server_id: None,
+        title: Some("zsh".into()),
+        width: 600.0,
+        height: 800.0,
+        app_id: Some("com.example.terminal".into()),
+        position: Some((x, 25.0)),
+    };
+    let (left_fingerprint, right_fingerprint) = (saved(0.0), saved(600.0));
+    let candidates = [
+        RestoreCandidate {
+            window: left,
+            fingerprint: &left_fingerprint,
+            location: Some((space, workspace)),
+        },
+        RestoreCandidate {
+            window: right,
+            fingerprint: &right_fingerprint,
+            location: Some((space, workspace)),
+        },
+    ];
+    let live_fingerprint = WindowFingerprint {
+        title: Some("vim".into()),
+        position: Some((598.0, 25.0)),
+        ..saved(0.0)
+    };
+
+    let decision = choose_match(live, space, &live_fingerprint, None, &candidates).unwrap();
+    assert_eq!(decision.selected, right);
+    assert!(!decision.exact_identity);
+}
+
 #[test]
 fn rejected_fuzzy_candidate_is_removed_when_discovery_finishes() {
     let mut engine = test_engine();
@@ -1387,6 +1498,7 @@ fn rejected_fuzzy_candidate_is_removed_when_discovery_finishes() {
         width: 500.0,
         height: 500.0,
         app_id: Some("com.example.app".into()),
+        position: None,
     });
     engine.persistence.pending_windows.insert(ghost);
 
@@ -1397,6 +1509,7 @@ fn rejected_fuzzy_candidate_is_removed_when_discovery_finishes() {
             width: 900.0,
             height: 700.0,
             app_id: Some("com.example.app".into()),
+            position: None,
         });
     assert!(!outcome.matched);
     assert!(engine.persistence.pending_windows.contains(&ghost));
@@ -1490,6 +1603,7 @@ fn runtime_restore_cleans_unmatched_windows_from_inactive_size_configurations()
         width: 1512.0,
         height: 944.0,
         app_id: Some("com.apple.Music".into()),
+        position: None,
     });
     let path = std::env::temp_dir().join(format!(
         "rift-runtime-inactive-size-restore-test-{}-{}.ron",
@@ -1589,6 +1703,7 @@ fn restored_window_server_id_cannot_cross_known_application_identity() {
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.one".into()),
+        position: None,
     });
     engine.persistence.windows.insert(id_match, WindowFingerprint {
         window_server_id: Some(20),
@@ -1596,6 +1711,7 @@ fn restored_window_server_id_cannot_cross_known_application_identity() {
         width: 500.0,
         height: 400.0,
         app_id: Some("com.example.two".into()),
+        position: None,
     });
     engine.persistence.pending_windows.extend([titled_match, id_match]);
 
@@ -1605,6 +1721,7 @@ fn restored_window_server_id_cannot_cross_known_application_identity() {
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.one".into()),
+        position: None,
     });
 
     let workspace = engine.active_workspace(space).unwrap();
@@ -1651,6 +1768,7 @@ fn duplicate_window_server_fingerprints_choose_live_assignment_and_are_healed()
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.editor".into()),
+        position: None,
     };
     engine.persistence.windows.insert(stale, fingerprint("stale"));
     engine.persistence.windows.insert(preferred, fingerprint("preferred"));
@@ -1706,6 +1824,7 @@ fn duplicate_restored_identity_prefers_live_workspace_assignment() {
         width: 800.0,
         height: 600.0,
         app_id: Some("dev.zed.Zed".into()),
+        position: None,
     };
     engine.persistence.windows.insert(live, fingerprint.clone());
     engine.persistence.pending_windows.insert(live);
@@ -1747,6 +1866,7 @@ fn restore_fallback_never_crosses_known_app_identity() {
         width: 400.0,
         height: 300.0,
         app_id: Some("com.example.other".into()),
+        position: None,
     });
     engine.persistence.windows.insert(size_and_app_match, WindowFingerprint {
         window_server_id: None,
@@ -1754,6 +1874,7 @@ fn restore_fallback_never_crosses_known_app_identity() {
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.editor".into()),
+        position: None,
     });
     engine.persistence.pending_windows.extend([title_match, size_and_app_match]);
 
@@ -1763,6 +1884,7 @@ fn restore_fallback_never_crosses_known_app_identity() {
         width: 800.0,
         height: 600.0,
         app_id: Some("com.example.editor".into()),
+        position: None,
     });
 
     let workspace = engine.active_workspace(space).unwrap();
@@ -1791,6 +1913,7 @@ fn load_heals_disagreeing_tiled_and_floating_ownership() {
             width: 600.0,
             height: 500.0,
             app_id: Some("com.example.editor".into()),
+            position: None,
         });
     }
     let workspaces = engine.virtual_workspace_manager.existing_workspaces(space);
@@ -1842,6 +1965,7 @@ fn app_close_removes_saved_fingerprints() {
         width: 400.0,
         height: 300.0,
         app_id: Some("com.example.closed".into()),
+        position: None,
     });
     engine.persistence.pending_windows.insert(window);
 
@@ -10,7 +10,7 @@ pub(crate) enum FloatingFullscreenKind {
     WithinGaps,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub(crate) struct FloatingManager {
     floating_windows: BTreeSet<WindowId>,
     #[serde(skip)]
@@ -202,6 +202,10 @@ impl FloatingManager {
         }
     }
 
+    pub(crate) fn remove_space(&mut self, space: SpaceId) {
+        self.active_floating_windows.remove(&space);
+    }
+
     fn remove_active_entries(&mut self, window_id: WindowId) {
         for space_map in self.active_floating_windows.values_mut() {
             if let Some(app_set) = space_map.get_mut(&window_id.pid) {
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::actor::app::{WindowId, pid_t};
 use crate::common::collections::HashMap;
-use crate::layout_engine::{Direction, LayoutKind, ResizeOrientation};
+use crate::common::config::CloseResize;
+use crate::layout_engine::{Direction, DropZoneKind, LayoutKind, Orientation, ResizeOrientation};
+use crate::model::tree::NodeId;
 
 slotmap::new_key_type! { pub struct LayoutId; }
 
@@ -87,6 +89,37 @@ impl WindowLayoutConstraints {
     }
 }
 
+/// Structured snapshot of a layout for external consumers (the `tree` query).
+///
+/// `ratio` is the node's share of its parent along the parent's axis, and
+/// `selected` marks the nodes on the path from the root to the selection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LayoutTreeNode {
+    Container {
+        kind: LayoutKind,
+        ratio: f64,
+        selected: bool,
+        children: Vec<LayoutTreeNode>,
+    },
+    Window {
+        window_id: WindowId,
+        ratio: f64,
+        selected: bool,
+    },
+}
+
+/// Where a window sat in a layout before it was taken out of tiling, so it can
+/// be put back in the same place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TilingBookmark {
+    /// The container that held the window, for systems that have them.
+    pub container: Option<NodeId>,
+    /// Position among the container's children, or among all windows of the
+    /// layout when there is no container.
+    pub index: usize,
+}
+
 #[enum_dispatch]
 pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
     fn create_layout(&mut self) -> LayoutId;
@@ -96,6 +129,27 @@ pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
 
     fn draw_tree(&self, layout: LayoutId) -> String;
 
+    /// The layout as a [`LayoutTreeNode`]. Systems without containers report a
+    /// single root holding every window with an even share.
+    fn layout_tree(&self, layout: LayoutId) -> LayoutTreeNode {
+        let selected = self.selected_window(layout);
+        let windows = self.all_windows_in_layout(layout);
+        let ratio = 1.0 / windows.len().max(1) as f64;
+        LayoutTreeNode::Container {
+            kind: LayoutKind::Horizontal,
+            ratio: 1.0,
+            selected: true,
+            children: windows
+                .into_iter()
+                .map(|window_id| LayoutTreeNode::Window {
+                    window_id,
+                    ratio,
+                    selected: Some(window_id) == selected,
+                })
+                .collect(),
+        }
+    }
+
     fn calculate_layout(
         &self,
         layout: LayoutId,
@@ -124,10 +178,36 @@ pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
     ) -> (Option<WindowId>, Vec<WindowId>);
     fn window_in_direction(&self, layout: LayoutId, direction: Direction) -> Option<WindowId>;
     fn add_window_after_selection(&mut self, layout: LayoutId, wid: WindowId);
+    /// Where `wid` sits in `layout`, for [`LayoutSystem::restore_window`].
+    fn tiling_bookmark(&self, layout: LayoutId, wid: WindowId) -> Option<TilingBookmark> {
+        let index = self.all_windows_in_layout(layout).iter().position(|&w| w == wid)?;
+        Some(TilingBookmark { container: None, index })
+    }
+    /// Put `wid` back where `bookmark` says it was and select it. Returns false,
+    /// leaving the layout untouched, when that place no longer exists.
+    fn restore_window(
+        &mut self,
+        layout: LayoutId,
+        wid: WindowId,
+        bookmark: TilingBookmark,
+    ) -> bool {
+        if bookmark.container.is_some() || self.contains_window(layout, wid) {
+            return false;
+        }
+        self.add_window_after_selection(layout, wid);
+        self.select_window(layout, wid);
+        self.move_selection_to_index(layout, bookmark.index);
+        true
+    }
     /// Replace a window identity in-place without changing its layout position.
     fn replace_window(&mut self, from: WindowId, to: WindowId);
     fn remove_window(&mut self, wid: WindowId);
     fn remove_window_and_rebalance_parent(&mut self, wid: WindowId) { self.remove_window(wid) }
+    /// Remove a window that was closed, resizing the windows that shared its
+    /// container as `resize` asks. Systems without split sizes just remove it.
+    fn remove_closed_window(&mut self, wid: WindowId, _resize: CloseResize) {
+        self.remove_window(wid)
+    }
     fn remove_windows_for_app(&mut self, pid: pid_t);
     fn windows_for_app(&self, layout: LayoutId, pid: pid_t) -> Vec<WindowId>;
     fn set_windows_for_app(&mut self, layout: LayoutId, pid: pid_t, desired: Vec<WindowId>);
@@ -147,12 +227,62 @@ pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
     fn swap_windows(&mut self, layout: LayoutId, a: WindowId, b: WindowId) -> bool;
 
     fn move_selection(&mut self, layout: LayoutId, direction: Direction) -> bool;
+    /// Reposition the selection to the `index`th slot (0-based, clamped) of its parent.
+    ///
+    /// The default treats the layout's window order as a single flat parent, which is
+    /// the closest analogue for systems without n-ary containers.
+    fn move_selection_to_index(&mut self, layout: LayoutId, index: usize) -> bool {
+        let Some(selected) = self.selected_window(layout) else {
+            return false;
+        };
+        let windows = self.all_windows_in_layout(layout);
+        let Some(current) = windows.iter().position(|&w| w == selected) else {
+            return false;
+        };
+        let target = index.min(windows.len() - 1);
+        if current == target {
+            return false;
+        }
+        if current < target {
+            for pair in windows[current..=target].windows(2) {
+                self.swap_windows(layout, selected, pair[1]);
+            }
+        } else {
+            for pair in windows[target..=current].windows(2).rev() {
+                self.swap_windows(layout, selected, pair[0]);
+            }
+        }
+        self.select_window(layout, selected);
+        true
+    }
+    /// Exchange the selection with whatever occupies the `index`th slot of its parent.
+    fn swap_selection_with_index(&mut self, layout: LayoutId, index: usize) -> bool {
+        let Some(selected) = self.selected_window(layout) else {
+            return false;
+        };
+        let Some(&other) = self.all_windows_in_layout(layout).get(index) else {
+            return false;
+        };
+        if other == selected || !self.swap_windows(layout, selected, other) {
+            return false;
+        }
+        self.select_window(layout, selected);
+        true
+    }
     fn move_selection_to_layout_after_selection(
         &mut self,
         from_layout: LayoutId,
         to_layout: LayoutId,
     );
     fn split_selection(&mut self, layout: LayoutId, kind: LayoutKind);
+    /// Adds `wid` next to `target` as described by `zone`, and selects it.
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        target: WindowId,
+        wid: WindowId,
+        zone: DropZoneKind,
+    );
 
     fn toggle_fullscreen_of_selection(&mut self, layout: LayoutId) -> Vec<WindowId>;
     fn toggle_fullscreen_within_gaps_of_selection(&mut self, layout: LayoutId) -> Vec<WindowId>;
@@ -180,6 +310,19 @@ pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
         amount: f64,
         orientation: ResizeOrientation,
     );
+    /// Move the `direction` edge of the selection outward by `screen_ratio`
+    /// of the screen along that axis (inward when negative). Systems without
+    /// per-edge control resize along the edge's axis instead.
+    fn resize_selection_edge(&mut self, layout: LayoutId, direction: Direction, screen_ratio: f64) {
+        let orientation = match direction.orientation() {
+            Orientation::Horizontal => ResizeOrientation::Horizontal,
+            Orientation::Vertical => ResizeOrientation::Vertical,
+        };
+        self.resize_selection_by(layout, screen_ratio, orientation);
+    }
+    /// Give the selection exactly `ratio` of the split containing it, its
+    /// siblings sharing the rest. Returns false for systems without splits.
+    fn set_selection_split_ratio(&mut self, _layout: LayoutId, _ratio: f64) -> bool { false }
     fn rebalance(&mut self, layout: LayoutId);
     fn toggle_tile_orientation(&mut self, layout: LayoutId);
 }
@@ -3,10 +3,13 @@ use serde::{Deserialize, Serialize};
 
 use crate::actor::app::{WindowId, pid_t};
 use crate::common::collections::{HashMap, HashSet};
+use crate::common::config::CloseResize;
 use crate::layout_engine::systems::constraints::{AxisConstraints, solve_axis_lengths};
-use crate::layout_engine::systems::{LayoutSystem, WindowLayoutConstraints};
+use crate::layout_engine::systems::{LayoutSystem, LayoutTreeNode, WindowLayoutConstraints};
 use crate::layout_engine::utils::compute_tiling_area;
-use crate::layout_engine::{Direction, LayoutId, LayoutKind, Orientation, ResizeOrientation};
+use crate::layout_engine::{
+    Direction, DropZoneKind, LayoutId, LayoutKind, Orientation, ResizeOrientation,
+};
 use crate::model::selection::*;
 use crate::model::tree::{NodeId, NodeMap, Tree};
 
@@ -21,6 +24,14 @@ enum NodeKind {
         fullscreen: bool,
         fullscreen_within_gaps: bool,
         preselected: Option<Direction>,
+        /// Share of the leaf the preselected window will take, instead of
+        /// half.
+        #[serde(default)]
+        preselected_ratio: Option<f32>,
+        /// All windows of a tabbed group in tab order, `window` being the one
+        /// shown. Empty unless the leaf holds a group.
+        #[serde(default)]
+        tabs: Vec<WindowId>,
     },
 }
 
@@ -130,10 +141,20 @@ impl BspLayoutSystem {
             let leaf = self.descend_to_leaf(sel);
 
             if let Some(NodeKind::Leaf {
-                preselected: Some(direction), ..
+                preselected: Some(direction),
+                preselected_ratio,
+                ..
             }) = self.kind.get(leaf).cloned()
             {
                 self.split_leaf_in_direction(leaf, direction, window);
+                if let Some(share) = preselected_ratio
+                    && let Some(NodeKind::Split { ratio, .. }) = self.kind.get_mut(leaf)
+                {
+                    *ratio = match direction {
+                        Direction::Left | Direction::Up => share,
+                        Direction::Right | Direction::Down => 1.0 - share,
+                    };
+                }
 
                 if let Some(NodeKind::Leaf { preselected, .. }) = self.kind.get_mut(leaf) {
                     *preselected = None;
@@ -144,23 +165,150 @@ impl BspLayoutSystem {
         false
     }
 
+    /// Mark the side of the selected window the next inserted window will take,
+    /// and optionally the share of the selection's tile it gets (half by
+    /// default). Preselecting the same direction and ratio again cancels it.
+    pub fn set_preselection(
+        &mut self,
+        layout: LayoutId,
+        direction: Direction,
+        ratio: Option<f64>,
+    ) -> bool {
+        let Some(sel) = self.selection_of_layout(layout) else {
+            return false;
+        };
+        let leaf = self.descend_to_leaf(sel);
+        let Some(NodeKind::Leaf {
+            window: Some(_),
+            preselected,
+            preselected_ratio,
+            ..
+        }) = self.kind.get_mut(leaf)
+        else {
+            return false;
+        };
+        let ratio = ratio.map(|ratio| ratio.clamp(0.05, 0.95) as f32);
+        if *preselected == Some(direction) && *preselected_ratio == ratio {
+            *preselected = None;
+            *preselected_ratio = None;
+        } else {
+            *preselected = Some(direction);
+            *preselected_ratio = ratio;
+        }
+        true
+    }
+
+    /// The selected window, its preselected insertion side and the share of
+    /// the split the next window will get, if one was requested.
+    pub fn preselection(&self, layout: LayoutId) -> Option<(WindowId, Direction, Option<f64>)> {
+        let leaf = self.descend_to_leaf(self.selection_of_layout(layout)?);
+        match self.kind.get(leaf)? {
+            NodeKind::Leaf {
+                window: Some(window),
+                preselected: Some(direction),
+                preselected_ratio,
+                ..
+            } => Some((*window, *direction, preselected_ratio.map(f64::from))),
+            _ => None,
+        }
+    }
+
+    /// Move the selected window into the tab group of its neighbor in
+    /// `direction`, turning the neighbor into a group if it is a lone window.
+    pub fn group_selection_with_direction(
+        &mut self,
+        layout: LayoutId,
+        direction: Direction,
+    ) -> bool {
+        let Some(sel) = self.selection_of_layout(layout) else {
+            return false;
+        };
+        let leaf = self.descend_to_leaf(sel);
+        let Some(NodeKind::Leaf { window: Some(wid), .. }) = self.kind.get(leaf) else {
+            return false;
+        };
+        let wid = *wid;
+        let Some(neighbor) = self.find_neighbor_leaf(leaf, direction) else {
+            return false;
+        };
+        let Some(NodeKind::Leaf { window: Some(shown), .. }) = self.kind.get(neighbor) else {
+            return false;
+        };
+        let shown = *shown;
+
+        self.remove_window_internal(layout, wid);
+        // Removing the window may have collapsed the neighbor into its parent.
+        let Some(target) = self.node_for_window(shown) else {
+            return false;
+        };
+        if let Some(NodeKind::Leaf { window, tabs, .. }) = self.kind.get_mut(target) {
+            if tabs.is_empty() {
+                tabs.push(shown);
+            }
+            tabs.push(wid);
+            *window = Some(wid);
+        }
+        self.index_window(wid, target);
+        self.tree.data.selection.select(&self.tree.map, target);
+        true
+    }
+
+    /// Take the shown window out of the selected tab group and tile it next
+    /// to the group.
+    pub fn ungroup_selection(&mut self, layout: LayoutId) -> bool {
+        let Some(sel) = self.selection_of_layout(layout) else {
+            return false;
+        };
+        let leaf = self.descend_to_leaf(sel);
+        let Some(NodeKind::Leaf { window: Some(wid), tabs, .. }) = self.kind.get(leaf) else {
+            return false;
+        };
+        if tabs.is_empty() {
+            return false;
+        }
+        let wid = *wid;
+        self.remove_from_group(leaf, wid);
+        let direction = match self.orientation_for_depth(self.node_depth(leaf)) {
+            Orientation::Horizontal => Direction::Right,
+            Orientation::Vertical => Direction::Down,
+        };
+        self.split_leaf_in_direction(leaf, direction, wid);
+        true
+    }
+
+    /// Show the next (or previous) window of the selected tab group and
+    /// return it.
+    pub fn cycle_group_of_selection(
+        &mut self,
+        layout: LayoutId,
+        forward: bool,
+    ) -> Option<WindowId> {
+        let leaf = self.descend_to_leaf(self.selection_of_layout(layout)?);
+        let Some(NodeKind::Leaf { window, tabs, .. }) = self.kind.get_mut(leaf) else {
+            return None;
+        };
+        let index = tabs.iter().position(|w| Some(*w) == *window)?;
+        let next = if forward {
+            (index + 1) % tabs.len()
+        } else {
+            (index + tabs.len() - 1) % tabs.len()
+        };
+        *window = Some(tabs[next]);
+        *window
+    }
+
     fn split_leaf_in_direction(
         &mut self,
         leaf: NodeId,
         direction: Direction,
         new_window: WindowId,
     ) {
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get(leaf).cloned() {
+        if matches!(self.kind.get(leaf), Some(NodeKind::Leaf { .. })) {
             let orientation = direction.orientation();
 
-            let existing_node = self.make_leaf(window);
+            let existing_node = self.copy_leaf(leaf);
             let new_node = self.make_leaf(Some(new_window));
 
-            if let Some(w) = window {
-                self.index_window(w, existing_node);
-            }
-            self.index_window(new_window, new_node);
-
             self.kind.insert(leaf, NodeKind::Split { orientation, ratio: 0.5 });
 
             let (first_child, second_child) = match direction {
@@ -219,6 +367,8 @@ impl BspLayoutSystem {
             fullscreen: false,
             fullscreen_within_gaps: false,
             preselected: None,
+            preselected_ratio: None,
+            tabs: Vec::new(),
         });
         if let Some(w) = window {
             self.index_window(w, id);
@@ -226,6 +376,70 @@ impl BspLayoutSystem {
         id
     }
 
+    /// The shown window of a leaf and the members of its tab group.
+    fn leaf_contents(&self, leaf: NodeId) -> (Option<WindowId>, Vec<WindowId>) {
+        match self.kind.get(leaf) {
+            Some(NodeKind::Leaf { window, tabs, .. }) => (*window, tabs.clone()),
+            _ => (None, Vec::new()),
+        }
+    }
+
+    fn set_leaf_contents(&mut self, leaf: NodeId, contents: (Option<WindowId>, Vec<WindowId>)) {
+        let (shown, members) = contents;
+        for &w in shown.iter().chain(&members) {
+            self.index_window(w, leaf);
+        }
+        if let Some(NodeKind::Leaf { window, tabs, .. }) = self.kind.get_mut(leaf) {
+            *window = shown;
+            *tabs = members;
+        }
+    }
+
+    /// A new leaf holding the window or tab group of `leaf`.
+    fn copy_leaf(&mut self, leaf: NodeId) -> NodeId {
+        let contents = self.leaf_contents(leaf);
+        let node = self.make_leaf(None);
+        self.set_leaf_contents(node, contents);
+        node
+    }
+
+    /// Drop `wid` from the tab group of `leaf`, showing a neighboring tab if
+    /// it was the shown one. Returns false if `wid` is not grouped there.
+    fn remove_from_group(&mut self, leaf: NodeId, wid: WindowId) -> bool {
+        let Some(NodeKind::Leaf { window, tabs, .. }) = self.kind.get_mut(leaf) else {
+            return false;
+        };
+        let Some(index) = tabs.iter().position(|&w| w == wid) else {
+            return false;
+        };
+        tabs.remove(index);
+        if *window == Some(wid) {
+            *window = tabs.get(index).or(tabs.last()).copied();
+        }
+        if tabs.len() < 2 {
+            tabs.clear();
+        }
+        true
+    }
+
+    /// Put `to` wherever `from` sits in `leaf`, both as the shown window and
+    /// as a tab.
+    fn substitute_in_leaf(&mut self, leaf: NodeId, from: WindowId, to: WindowId) -> bool {
+        let Some(NodeKind::Leaf { window, tabs, .. }) = self.kind.get_mut(leaf) else {
+            return false;
+        };
+        let mut found = false;
+        if *window == Some(from) {
+            *window = Some(to);
+            found = true;
+        }
+        for tab in tabs.iter_mut().filter(|tab| **tab == from) {
+            *tab = to;
+            found = true;
+        }
+        found
+    }
+
     fn descend_to_leaf(&self, mut node: NodeId) -> NodeId {
         loop {
             match self.kind.get(node) {
@@ -268,8 +482,10 @@ impl BspLayoutSystem {
 
     fn collect_windows_under(&self, node: NodeId, out: &mut Vec<WindowId>) {
         match self.kind.get(node) {
-            Some(NodeKind::Leaf { window, .. }) => {
-                if let Some(w) = window {
+            Some(NodeKind::Leaf { window, tabs, .. }) => {
+                if !tabs.is_empty() {
+                    out.extend(tabs.iter().copied());
+                } else if let Some(w) = window {
                     out.push(*w);
                 }
             }
@@ -282,6 +498,46 @@ impl BspLayoutSystem {
         }
     }
 
+    /// Like [`Self::collect_windows_under`], but only the shown window of
+    /// each tab group.
+    fn collect_visible_windows_under(&self, node: NodeId, out: &mut Vec<WindowId>) {
+        out.extend(node.traverse_preorder(&self.tree.map).filter_map(
+            |id| match self.kind.get(id) {
+                Some(NodeKind::Leaf { window, .. }) => *window,
+                _ => None,
+            },
+        ));
+    }
+
+    /// Number of tiles under `node`, a tab group counting as one.
+    fn tile_count(&self, node: NodeId) -> usize {
+        match self.kind.get(node) {
+            Some(NodeKind::Split { .. }) => {
+                node.children(&self.tree.map).map(|child| self.tile_count(child)).sum()
+            }
+            _ => 1,
+        }
+    }
+
+    /// Set every split under `node` so that each leaf gets an equal share of
+    /// the area, and return the number of leaves under `node`.
+    fn equalize_subtree(&mut self, node: NodeId) -> usize {
+        let children: Vec<NodeId> = node.children(&self.tree.map).collect();
+        if !matches!(self.kind.get(node), Some(NodeKind::Split { .. })) {
+            return 1;
+        }
+        let counts: Vec<usize> =
+            children.iter().map(|&child| self.equalize_subtree(child)).collect();
+        let total: usize = counts.iter().sum();
+        if let (Some(&first), Some(NodeKind::Split { ratio, .. })) =
+            (counts.first(), self.kind.get_mut(node))
+            && total > 0
+        {
+            *ratio = first as f32 / total as f32;
+        }
+        total.max(1)
+    }
+
     fn has_fullscreen_in_subtree(&self, node: NodeId) -> bool {
         match self.kind.get(node) {
             Some(NodeKind::Leaf {
@@ -348,8 +604,10 @@ impl BspLayoutSystem {
                 fullscreen,
                 fullscreen_within_gaps,
                 preselected,
+                preselected_ratio,
+                tabs,
             } => {
-                if let Some(w) = window {
+                for w in window.into_iter().chain(tabs.iter().copied()) {
                     self.index_window(w, parent_id);
                 }
                 self.kind.insert(parent_id, NodeKind::Leaf {
@@ -357,6 +615,8 @@ impl BspLayoutSystem {
                     fullscreen,
                     fullscreen_within_gaps,
                     preselected,
+                    preselected_ratio,
+                    tabs,
                 });
             }
         }
@@ -396,13 +656,8 @@ impl BspLayoutSystem {
                     *fullscreen_within_gaps = false;
                     self.index_window(wid, sel);
                 } else {
-                    let existing = *window;
-                    let left = self.make_leaf(existing);
+                    let left = self.copy_leaf(sel);
                     let right = self.make_leaf(Some(wid));
-                    self.index_window(wid, right);
-                    if let Some(w) = existing {
-                        self.index_window(w, left);
-                    }
                     // Use alternating orientations based on depth for fibonacci spiral
                     let depth = self.node_depth(sel);
                     let orientation = self.orientation_for_depth(depth);
@@ -428,6 +683,10 @@ impl BspLayoutSystem {
                     return;
                 }
             }
+            if self.remove_from_group(node_id, wid) {
+                self.unindex_window(wid);
+                return;
+            }
             if let Some(NodeKind::Leaf { window, .. }) = self.kind.get_mut(node_id) {
                 *window = None;
             }
@@ -458,11 +717,19 @@ impl BspLayoutSystem {
         match self.kind.get(node) {
             Some(NodeKind::Leaf {
                 window,
+                tabs,
                 fullscreen,
                 fullscreen_within_gaps,
                 ..
             }) => {
-                if let Some(w) = window {
+                // Every tab of a group takes the whole leaf; only the shown
+                // one is raised.
+                let members = if tabs.is_empty() {
+                    window.as_slice()
+                } else {
+                    tabs.as_slice()
+                };
+                for w in members {
                     let mut target = if *fullscreen {
                         screen
                     } else if *fullscreen_within_gaps {
@@ -695,6 +962,109 @@ impl BspLayoutSystem {
         }
     }
 
+    /// The split whose boundary is `node`'s edge on the `direction` side: the
+    /// nearest split along that axis where `node` is the first child
+    /// (right/down) or the second child (left/up).
+    fn boundary_split(&self, mut node: NodeId, direction: Direction) -> Option<NodeId> {
+        let orientation = direction.orientation();
+        let first_side = matches!(direction, Direction::Right | Direction::Down);
+        loop {
+            let parent = node.parent(&self.tree.map)?;
+            let is_first = Some(node) == parent.first_child(&self.tree.map);
+            if let Some(NodeKind::Split { orientation: o, .. }) = self.kind.get(parent)
+                && *o == orientation
+                && is_first == first_side
+            {
+                return Some(parent);
+            }
+            node = parent;
+        }
+    }
+
+    /// Frame of `node` when `root` fills `rect`, following split ratios only;
+    /// window size constraints are ignored.
+    fn node_rect(
+        &self,
+        root: NodeId,
+        node: NodeId,
+        mut rect: CGRect,
+        gaps: &crate::common::config::GapSettings,
+    ) -> Option<CGRect> {
+        let mut path = vec![node];
+        let mut current = node;
+        while current != root {
+            current = current.parent(&self.tree.map)?;
+            path.push(current);
+        }
+        for pair in path.windows(2).rev() {
+            let (child, parent) = (pair[0], pair[1]);
+            let Some(NodeKind::Split { orientation, ratio }) = self.kind.get(parent) else {
+                continue;
+            };
+            let is_first = Some(child) == parent.first_child(&self.tree.map);
+            let ratio = f64::from(*ratio);
+            match orientation {
+                Orientation::Horizontal => {
+                    let gap = gaps.inner.horizontal;
+                    let available = (rect.size.width - gap).max(0.0);
+                    let first = available * ratio;
+                    if is_first {
+                        rect.size.width = first;
+                    } else {
+                        rect.origin.x += first + gap;
+                        rect.size.width = available - first;
+                    }
+                }
+                Orientation::Vertical => {
+                    let gap = gaps.inner.vertical;
+                    let available = (rect.size.height - gap).max(0.0);
+                    let first = available * ratio;
+                    if is_first {
+                        rect.size.height = first;
+                    } else {
+                        rect.origin.y += first + gap;
+                        rect.size.height = available - first;
+                    }
+                }
+            }
+        }
+        Some(rect)
+    }
+
+    /// Sets the ratio of `split` so that its boundary lands on the screen
+    /// coordinate `edge`, which is the `direction` edge of a window inside it.
+    fn move_split_boundary(
+        &mut self,
+        root: NodeId,
+        split: NodeId,
+        direction: Direction,
+        edge: f64,
+        tiling: CGRect,
+        gaps: &crate::common::config::GapSettings,
+    ) {
+        let Some(rect) = self.node_rect(root, split, tiling, gaps) else {
+            return;
+        };
+        let Some(NodeKind::Split { orientation, ratio }) = self.kind.get_mut(split) else {
+            return;
+        };
+        let (start, total, gap) = match orientation {
+            Orientation::Horizontal => (rect.origin.x, rect.size.width, gaps.inner.horizontal),
+            Orientation::Vertical => (rect.origin.y, rect.size.height, gaps.inner.vertical),
+        };
+        let available = total - gap;
+        if available <= 0.0 {
+            return;
+        }
+        // The first child ends at the boundary and the second starts one gap
+        // after it.
+        let first_len = match direction {
+            Direction::Right | Direction::Down => edge - start,
+            Direction::Left | Direction::Up => edge - gap - start,
+        };
+        *ratio = (first_len / available).clamp(0.05, 0.95) as f32;
+    }
+
     fn apply_outer_gaps(screen: CGRect, gaps: &crate::common::config::GapSettings) -> CGRect {
         compute_tiling_area(screen, gaps)
     }
@@ -745,6 +1115,370 @@ mod tests {
 
     fn w(idx: u32) -> WindowId { WindowId::new(1, idx) }
 
+    #[test]
+    fn preselection_places_next_window_and_is_consumed() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+
+        assert!(system.set_preselection(layout, Direction::Left, None));
+        assert!(system.set_preselection(layout, Direction::Left, None));
+        assert_eq!(system.preselection(layout), None, "same direction toggles off");
+
+        assert!(system.set_preselection(layout, Direction::Left, None));
+        assert_eq!(system.preselection(layout), Some((w(1), Direction::Left, None)));
+
+        system.add_window_after_selection(layout, w(2));
+        assert_eq!(system.all_windows_in_layout(layout), vec![w(2), w(1)]);
+        assert_eq!(system.preselection(layout), None);
+        system.select_window(layout, w(1));
+        assert_eq!(system.preselection(layout), None);
+    }
+
+    #[test]
+    fn preselected_ratio_sizes_the_new_window() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+
+        assert!(system.set_preselection(layout, Direction::Right, Some(0.3)));
+        assert!(system.set_preselection(layout, Direction::Right, Some(0.4)));
+        assert_eq!(
+            system.preselection(layout),
+            Some((w(1), Direction::Right, Some(0.4f32.into()))),
+            "a different ratio replaces the preselection"
+        );
+        system.add_window_after_selection(layout, w(2));
+
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let frames: HashMap<WindowId, CGRect> = system
+            .calculate_layout(
+                layout,
+                screen,
+                0.0,
+                &Default::default(),
+                &Default::default(),
+                0.0,
+                Default::default(),
+                Default::default(),
+            )
+            .into_iter()
+            .collect();
+        assert_eq!(frames[&w(1)].size.width.round(), 600.0);
+        assert_eq!(frames[&w(2)].size.width.round(), 400.0);
+    }
+
+    #[test]
+    fn insert_window_beside_splits_the_target_tile() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+
+        system.insert_window_beside(layout, w(1), w(3), DropZoneKind::Left);
+        assert_eq!(system.selected_window(layout), Some(w(3)));
+        system.insert_window_beside(layout, w(2), w(4), DropZoneKind::Container);
+        assert_eq!(system.selected_window(layout), Some(w(4)));
+
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let frames: HashMap<WindowId, CGRect> = system
+            .calculate_layout(
+                layout,
+                screen,
+                0.0,
+                &Default::default(),
+                &Default::default(),
+                0.0,
+                Default::default(),
+                Default::default(),
+            )
+            .into_iter()
+            .collect();
+        assert_eq!(frames.len(), 4, "no empty tile is left behind");
+        assert_eq!(frames[&w(3)].origin.x.round(), 0.0);
+        assert_eq!(frames[&w(1)].origin.x.round(), 250.0);
+        assert_eq!(frames[&w(4)].origin.x, frames[&w(2)].origin.x);
+        assert_eq!(frames[&w(4)].origin.y.round(), 400.0);
+    }
+
+    #[test]
+    fn set_split_ratio_sizes_the_selection_exactly() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+
+        assert!(system.set_selection_split_ratio(layout, 0.618));
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let frames: HashMap<WindowId, CGRect> = system
+            .calculate_layout(
+                layout,
+                screen,
+                0.0,
+                &HashMap::default(),
+                &Default::default(),
+                0.0,
+                Default::default(),
+                Default::default(),
+            )
+            .into_iter()
+            .collect();
+        assert_eq!(frames[&w(2)].size.width.round(), 618.0);
+        assert_eq!(frames[&w(1)].size.width.round(), 382.0);
+    }
+
+    #[test]
+    fn closing_a_window_resizes_its_neighbors_as_configured() {
+        let widths_after_close = |resize: CloseResize| {
+            let mut system = BspLayoutSystem::default();
+            let layout = system.create_layout();
+            system.add_window_after_selection(layout, w(1));
+            system.add_window_after_selection(layout, w(2));
+            system.select_window(layout, w(1));
+            system.set_selection_split_ratio(layout, 0.6);
+            system.select_window(layout, w(2));
+            system.set_preselection(layout, Direction::Right, None);
+            system.add_window_after_selection(layout, w(3));
+
+            system.remove_closed_window(w(3), resize);
+            let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+            let frames: HashMap<WindowId, CGRect> = system
+                .calculate_layout(
+                    layout,
+                    screen,
+                    0.0,
+                    &HashMap::default(),
+                    &Default::default(),
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                )
+                .into_iter()
+                .collect();
+            (
+                frames[&w(1)].size.width.round(),
+                frames[&w(2)].size.width.round(),
+            )
+        };
+
+        assert_eq!(widths_after_close(CloseResize::Keep), (600.0, 400.0));
+        assert_eq!(widths_after_close(CloseResize::Neighbor), (600.0, 400.0));
+        assert_eq!(widths_after_close(CloseResize::Equalize), (500.0, 500.0));
+        assert_eq!(widths_after_close(CloseResize::Proportional), (750.0, 250.0));
+    }
+
+    #[test]
+    fn equalize_on_close_only_touches_the_split_the_sibling_shares() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        system.select_window(layout, w(1));
+        system.set_preselection(layout, Direction::Down, None);
+        system.add_window_after_selection(layout, w(3));
+        system.select_window(layout, w(1));
+        system.set_selection_split_ratio(layout, 0.7);
+        system.select_window(layout, w(2));
+        system.set_preselection(layout, Direction::Right, None);
+        system.add_window_after_selection(layout, w(4));
+
+        system.remove_closed_window(w(4), CloseResize::Equalize);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(900.0, 800.0));
+        let frames: HashMap<WindowId, CGRect> = system
+            .calculate_layout(
+                layout,
+                screen,
+                0.0,
+                &HashMap::default(),
+                &Default::default(),
+                0.0,
+                Default::default(),
+                Default::default(),
+            )
+            .into_iter()
+            .collect();
+
+        assert_eq!(frames[&w(1)].size.width.round(), 600.0);
+        assert_eq!(frames[&w(2)].size.width.round(), 300.0);
+        assert_eq!(frames[&w(1)].size.height.round(), 560.0, "inner split kept");
+    }
+
+    #[test]
+    fn tab_groups_show_one_window_and_cycle() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        for i in 1..=3 {
+            system.add_window_after_selection(layout, w(i));
+        }
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let frames = |system: &BspLayoutSystem| {
+            system
+                .calculate_layout(
+                    layout,
+                    screen,
+                    0.0,
+                    &HashMap::default(),
+                    &Default::default(),
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                )
+                .into_iter()
+                .collect::<HashMap<_, _>>()
+        };
+
+        system.select_window(layout, w(3));
+        assert!(system.group_selection_with_direction(layout, Direction::Up));
+        assert_eq!(system.all_windows_in_layout(layout), vec![w(1), w(2), w(3)]);
+        assert_eq!(system.visible_windows_in_layout(layout), vec![w(1), w(3)]);
+        assert_eq!(system.selected_window(layout), Some(w(3)));
+        let layout_frames = frames(&system);
+        assert_eq!(layout_frames[&w(2)], layout_frames[&w(3)]);
+
+        assert_eq!(system.cycle_group_of_selection(layout, true), Some(w(2)));
+        assert_eq!(system.cycle_group_of_selection(layout, true), Some(w(3)));
+        assert_eq!(system.cycle_group_of_selection(layout, false), Some(w(2)));
+        system.select_window(layout, w(3));
+        assert_eq!(system.visible_windows_in_layout(layout), vec![w(1), w(3)]);
+
+        system.remove_window(w(3));
+        assert_eq!(system.visible_windows_in_layout(layout), vec![w(1), w(2)]);
+        assert_eq!(system.cycle_group_of_selection(layout, true), None);
+
+        system.add_window_after_selection(layout, w(4));
+        system.select_window(layout, w(4));
+        assert!(system.group_selection_with_direction(layout, Direction::Up));
+        assert!(system.ungroup_selection(layout));
+        assert_eq!(system.visible_windows_in_layout(layout), vec![w(1), w(2), w(4)]);
+        assert_eq!(system.selected_window(layout), Some(w(4)));
+    }
+
+    #[test]
+    fn layout_tree_reports_split_ratios() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+
+        let LayoutTreeNode::Container { children, .. } = system.layout_tree(layout) else {
+            panic!("two windows should produce a split");
+        };
+        let ratios: Vec<f64> = children
+            .iter()
+            .map(|child| match child {
+                LayoutTreeNode::Window { ratio, .. } | LayoutTreeNode::Container { ratio, .. } => {
+                    *ratio
+                }
+            })
+            .collect();
+        assert_eq!(ratios, vec![0.5, 0.5]);
+        assert!(children.iter().any(|child| matches!(
+            child,
+            LayoutTreeNode::Window { window_id, selected: true, .. } if *window_id == w(2)
+        )));
+    }
+
+    #[test]
+    fn resize_selection_edge_moves_the_boundary_on_that_side() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        let first_share = |system: &BspLayoutSystem| {
+            let LayoutTreeNode::Container { children, .. } = system.layout_tree(layout) else {
+                panic!("two windows should produce a split");
+            };
+            match &children[0] {
+                LayoutTreeNode::Window { ratio, .. } | LayoutTreeNode::Container { ratio, .. } => {
+                    *ratio
+                }
+            }
+        };
+
+        system.select_window(layout, w(1));
+        system.resize_selection_edge(layout, Direction::Right, 0.1);
+        assert!((first_share(&system) - 0.6).abs() < 1e-6);
+
+        // The first window has no left neighbour, so its left edge stays put.
+        system.resize_selection_edge(layout, Direction::Left, 0.1);
+        assert!((first_share(&system) - 0.6).abs() < 1e-6);
+
+        system.select_window(layout, w(2));
+        system.resize_selection_edge(layout, Direction::Left, 0.2);
+        assert!((first_share(&system) - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mouse_resize_moves_the_boundary_to_the_dragged_edge() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1210.0, 900.0));
+        let mut gaps = crate::common::config::GapSettings::default();
+        gaps.inner.horizontal = 10.0;
+        let frames = |system: &BspLayoutSystem| {
+            let layout = system.calculate_layout(
+                layout,
+                screen,
+                0.0,
+                &HashMap::default(),
+                &gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            );
+            let frame = |wid| layout.iter().find(|(w, _)| *w == wid).unwrap().1;
+            (frame(w(1)), frame(w(2)))
+        };
+
+        let (old, _) = frames(&system);
+        let mut new = old;
+        new.size.width = 800.0;
+        system.on_window_resized(layout, w(1), old, new, screen, &gaps);
+        let (first, second) = frames(&system);
+        assert!((first.size.width - 800.0).abs() < 0.01);
+        assert!((second.origin.x - 810.0).abs() < 0.01);
+
+        let mut new = second;
+        new.origin.x = 310.0;
+        new.size.width = second.max().x - 310.0;
+        system.on_window_resized(layout, w(2), second, new, screen, &gaps);
+        let (first, second) = frames(&system);
+        assert!((first.size.width - 300.0).abs() < 0.01);
+        assert!((second.origin.x - 310.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn rebalance_gives_every_window_an_equal_area() {
+        let mut system = BspLayoutSystem::default();
+        let layout = system.create_layout();
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+        system.select_window(layout, w(1));
+        system.resize_selection_edge(layout, Direction::Right, 0.2);
+
+        system.rebalance(layout);
+
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 900.0));
+        let frames = system.calculate_layout(
+            layout,
+            screen,
+            0.0,
+            &HashMap::default(),
+            &Default::default(),
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+        assert_eq!(frames.len(), 3);
+        for (_, frame) in frames {
+            let area = frame.size.width * frame.size.height;
+            assert!((area - 1200.0 * 900.0 / 3.0).abs() < 1.0, "unequal area {area}");
+        }
+    }
+
     #[test]
     fn window_in_direction_prefers_leftmost_when_moving_right() {
         let mut system = BspLayoutSystem::default();
@@ -1006,6 +1740,9 @@ impl LayoutSystem for BspLayoutSystem {
                 out.push_str("  ");
             }
             match this.kind.get(node) {
+                Some(NodeKind::Leaf { window, tabs, .. }) if !tabs.is_empty() => {
+                    out.push_str(&format!("Leaf {:?} tabs {:?}\n", window, tabs));
+                }
                 Some(NodeKind::Leaf { window, .. }) => {
                     out.push_str(&format!("Leaf {:?}\n", window));
                 }
@@ -1031,6 +1768,70 @@ impl LayoutSystem for BspLayoutSystem {
         }
     }
 
+    fn layout_tree(&self, layout: LayoutId) -> LayoutTreeNode {
+        fn build(
+            this: &BspLayoutSystem,
+            node: NodeId,
+            ratio: f64,
+            selection_path: &[NodeId],
+        ) -> LayoutTreeNode {
+            let selected = selection_path.contains(&node);
+            match this.kind.get(node) {
+                Some(NodeKind::Leaf { window, tabs, .. }) if !tabs.is_empty() => {
+                    LayoutTreeNode::Container {
+                        kind: LayoutKind::HorizontalStack,
+                        ratio,
+                        selected,
+                        children: tabs
+                            .iter()
+                            .map(|&window_id| LayoutTreeNode::Window {
+                                window_id,
+                                ratio: 1.0,
+                                selected: selected && *window == Some(window_id),
+                            })
+                            .collect(),
+                    }
+                }
+                Some(NodeKind::Leaf { window: Some(window_id), .. }) => LayoutTreeNode::Window {
+                    window_id: *window_id,
+                    ratio,
+                    selected,
+                },
+                Some(NodeKind::Split { orientation, ratio: split }) => {
+                    let split = f64::from(*split);
+                    let children = node
+                        .children(&this.tree.map)
+                        .zip([split, 1.0 - split])
+                        .map(|(child, share)| build(this, child, share, selection_path))
+                        .collect();
+                    LayoutTreeNode::Container {
+                        kind: LayoutKind::from(*orientation),
+                        ratio,
+                        selected,
+                        children,
+                    }
+                }
+                _ => LayoutTreeNode::Container {
+                    kind: LayoutKind::Horizontal,
+                    ratio,
+                    selected,
+                    children: Vec::new(),
+                },
+            }
+        }
+        let Some(state) = self.layouts.get(layout).copied() else {
+            return LayoutTreeNode::Container {
+                kind: LayoutKind::Horizontal,
+                ratio: 1.0,
+                selected: false,
+                children: Vec::new(),
+            };
+        };
+        let selection = self.tree.data.selection.current_selection(state.root);
+        let selection_path: Vec<NodeId> = selection.ancestors(&self.tree.map).collect();
+        build(self, state.root, 1.0, &selection_path)
+    }
+
     fn calculate_layout(
         &self,
         layout: LayoutId,
@@ -1065,7 +1866,7 @@ impl LayoutSystem for BspLayoutSystem {
     fn visible_windows_in_layout(&self, layout: LayoutId) -> Vec<WindowId> {
         let mut out = Vec::new();
         if let Some(state) = self.layouts.get(layout).copied() {
-            self.collect_windows_under(state.root, &mut out);
+            self.collect_visible_windows_under(state.root, &mut out);
         }
         out
     }
@@ -1075,7 +1876,7 @@ impl LayoutSystem for BspLayoutSystem {
         if let Some(sel) = self.selection_of_layout(layout) {
             if self.kind.get(sel).is_some() {
                 let leaf = self.descend_to_leaf(sel);
-                self.collect_windows_under(leaf, &mut out);
+                self.collect_visible_windows_under(leaf, &mut out);
             }
         }
 
@@ -1156,14 +1957,79 @@ impl LayoutSystem for BspLayoutSystem {
         let Some(node) = self.window_to_node.remove(&from) else {
             return;
         };
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get_mut(node)
-            && *window == Some(from)
-        {
-            *window = Some(to);
+        if self.substitute_in_leaf(node, from, to) {
             self.window_to_node.insert(to, node);
         }
     }
 
+    fn remove_closed_window(&mut self, wid: WindowId, resize: CloseResize) {
+        // Note the split the window's tile was part of before removing the
+        // window merges that split away.
+        let closed = self.node_for_window(wid).and_then(|leaf| {
+            let (_, tabs) = self.leaf_contents(leaf);
+            if !tabs.is_empty() {
+                return None;
+            }
+            let parent = leaf.parent(&self.tree.map)?;
+            let is_first = Some(leaf) == parent.first_child(&self.tree.map);
+            match self.kind.get(parent) {
+                Some(NodeKind::Split { orientation, ratio }) => Some((
+                    parent,
+                    *orientation,
+                    if is_first { *ratio } else { 1.0 - *ratio },
+                )),
+                _ => None,
+            }
+        });
+        self.remove_window(wid);
+        // The closed window's sibling now lives in `merged`, the node that
+        // held the split.
+        let Some((merged, orientation, closed_share)) = closed else {
+            return;
+        };
+        if !self.kind.contains_key(merged) {
+            return;
+        }
+        let container = merged.parent(&self.tree.map);
+        match resize {
+            // The sibling already took over the whole split.
+            CloseResize::Keep | CloseResize::Neighbor => {}
+            CloseResize::Equalize => {
+                // Only the split the sibling now shares is evened out; splits
+                // further in or further up keep their ratios.
+                let Some(container) = container else {
+                    return;
+                };
+                let counts: Vec<usize> = container
+                    .children(&self.tree.map)
+                    .map(|child| self.tile_count(child))
+                    .collect();
+                let total: usize = counts.iter().sum();
+                if let (Some(&first), Some(NodeKind::Split { ratio, .. })) =
+                    (counts.first(), self.kind.get_mut(container))
+                    && total > 0
+                {
+                    *ratio = first as f32 / total as f32;
+                }
+            }
+            CloseResize::Proportional => {
+                let Some(container) = container else {
+                    return;
+                };
+                let is_first = Some(merged) == container.first_child(&self.tree.map);
+                if let Some(NodeKind::Split { orientation: o, ratio }) =
+                    self.kind.get_mut(container)
+                    && *o == orientation
+                {
+                    let share = if is_first { *ratio } else { 1.0 - *ratio };
+                    let grown = share * (1.0 - closed_share) / (1.0 - share * closed_share);
+                    let grown = grown.clamp(0.05, 0.95);
+                    *ratio = if is_first { grown } else { 1.0 - grown };
+                }
+            }
+        }
+    }
+
     fn remove_window(&mut self, wid: WindowId) {
         if let Some(node_id) = self.node_for_window_mut(wid) {
             let root = self.find_layout_root(node_id);
@@ -1251,6 +2117,11 @@ impl LayoutSystem for BspLayoutSystem {
             if let Some(state) = self.layouts.get(layout).copied() {
                 let belongs = self.belongs_to_layout(state, node);
                 if belongs {
+                    if let Some(NodeKind::Leaf { window, tabs, .. }) = self.kind.get_mut(node)
+                        && tabs.contains(&wid)
+                    {
+                        *window = Some(wid);
+                    }
                     self.tree.data.selection.select(&self.tree.map, node);
                     return true;
                 }
@@ -1311,50 +2182,24 @@ impl LayoutSystem for BspLayoutSystem {
                     return;
                 }
 
-                let mut current = node;
-                while let Some(parent) = current.parent(&self.tree.map) {
-                    let Some(NodeKind::Split { orientation, ratio }) = self.kind.get_mut(parent)
-                    else {
-                        current = parent;
+                // Move the boundary behind each edge that moved to where the
+                // edge is now, so the next layout reproduces the frame exactly.
+                let (old_max, new_max) = (old_frame.max(), new_frame.max());
+                let edges = [
+                    (Direction::Left, old_frame.origin.x, new_frame.origin.x),
+                    (Direction::Right, old_max.x, new_max.x),
+                    (Direction::Up, old_frame.origin.y, new_frame.origin.y),
+                    (Direction::Down, old_max.y, new_max.y),
+                ];
+                for (direction, old_edge, new_edge) in edges {
+                    if (new_edge - old_edge).abs() <= 0.5 {
                         continue;
-                    };
-
-                    let (old_len, new_len) = match orientation {
-                        Orientation::Horizontal if width_changed => {
-                            (old_frame.size.width, new_frame.size.width)
-                        }
-                        Orientation::Vertical if height_changed => {
-                            (old_frame.size.height, new_frame.size.height)
-                        }
-                        _ => {
-                            current = parent;
-                            continue;
-                        }
-                    };
-
-                    let current_ratio = f64::from(*ratio);
-                    let is_first = Some(current) == parent.first_child(&self.tree.map);
-                    let denom = if is_first {
-                        current_ratio
-                    } else {
-                        1.0 - current_ratio
-                    };
-                    if old_len <= 0.0 || denom <= f64::EPSILON {
-                        break;
                     }
-
-                    let parent_len = old_len / denom;
-                    if parent_len <= 0.0 {
-                        break;
+                    if let Some(split) = self.boundary_split(node, direction) {
+                        self.move_split_boundary(
+                            state.root, split, direction, new_edge, tiling, gaps,
+                        );
                     }
-
-                    let raw_ratio = (new_len / parent_len).clamp(0.05, 0.95);
-                    *ratio = if is_first {
-                        raw_ratio as f32
-                    } else {
-                        (1.0 - raw_ratio) as f32
-                    };
-                    break;
                 }
             }
         }
@@ -1369,28 +2214,13 @@ impl LayoutSystem for BspLayoutSystem {
         let Some(neighbor_leaf) = self.find_neighbor_leaf(sel_leaf, direction) else {
             return false;
         };
-        let (mut a_window, mut b_window) = (None, None);
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get_mut(sel_leaf) {
-            a_window = *window;
-        }
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get_mut(neighbor_leaf) {
-            b_window = *window;
-        }
-        if a_window.is_none() && b_window.is_none() {
+        let a = self.leaf_contents(sel_leaf);
+        let b = self.leaf_contents(neighbor_leaf);
+        if a.0.is_none() && b.0.is_none() {
             return false;
         }
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get_mut(sel_leaf) {
-            *window = b_window;
-        }
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get_mut(neighbor_leaf) {
-            *window = a_window;
-        }
-        if let Some(w) = a_window {
-            self.index_window(w, neighbor_leaf);
-        }
-        if let Some(w) = b_window {
-            self.index_window(w, sel_leaf);
-        }
+        self.set_leaf_contents(sel_leaf, b);
+        self.set_leaf_contents(neighbor_leaf, a);
         self.tree.data.selection.select(&self.tree.map, neighbor_leaf);
         true
     }
@@ -1414,32 +2244,11 @@ impl LayoutSystem for BspLayoutSystem {
             return false;
         }
 
-        let mut a_window = None;
-        let mut b_window = None;
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get(node_a) {
-            a_window = *window;
-        }
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get(node_b) {
-            b_window = *window;
-        }
-
-        if a_window.is_none() && b_window.is_none() {
+        if !self.substitute_in_leaf(node_a, a, b) || !self.substitute_in_leaf(node_b, b, a) {
             return false;
         }
-
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get_mut(node_a) {
-            *window = b_window;
-        }
-        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get_mut(node_b) {
-            *window = a_window;
-        }
-
-        if let Some(w) = a_window {
-            self.index_window(w, node_b);
-        }
-        if let Some(w) = b_window {
-            self.index_window(w, node_a);
-        }
+        self.index_window(a, node_b);
+        self.index_window(b, node_a);
 
         true
     }
@@ -1471,12 +2280,9 @@ impl LayoutSystem for BspLayoutSystem {
         let sel = self.tree.data.selection.current_selection(state.root);
         let target = self.descend_to_leaf(sel);
         match self.kind.get(target).cloned() {
-            Some(NodeKind::Leaf { window, .. }) => {
-                let left = self.make_leaf(window);
+            Some(NodeKind::Leaf { .. }) => {
+                let left = self.copy_leaf(target);
                 let right = self.make_leaf(None);
-                if let Some(w) = window {
-                    self.index_window(w, left);
-                }
                 self.kind.insert(target, NodeKind::Split { orientation, ratio: 0.5 });
                 left.detach(&mut self.tree).push_back(target);
                 right.detach(&mut self.tree).push_back(target);
@@ -1486,6 +2292,29 @@ impl LayoutSystem for BspLayoutSystem {
         }
     }
 
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        target: WindowId,
+        wid: WindowId,
+        zone: DropZoneKind,
+    ) {
+        let Some(leaf) = self.node_for_window_mut(target) else {
+            self.add_window_after_selection(layout, wid);
+            return;
+        };
+        match zone {
+            DropZoneKind::Left => self.split_leaf_in_direction(leaf, Direction::Left, wid),
+            DropZoneKind::Right => self.split_leaf_in_direction(leaf, Direction::Right, wid),
+            DropZoneKind::Container => {
+                // Split the way a window inserted at the target would be,
+                // without consuming the target's preselection.
+                self.tree.data.selection.select(&self.tree.map, leaf);
+                self.insert_window_at_selection(layout, wid);
+            }
+        }
+    }
+
     fn toggle_fullscreen_of_selection(&mut self, layout: LayoutId) -> Vec<WindowId> {
         if let Some(sel) = self.selection_of_layout(layout) {
             let sel_leaf = self.descend_to_leaf(sel);
@@ -1683,7 +2512,64 @@ impl LayoutSystem for BspLayoutSystem {
         }
     }
 
-    fn rebalance(&mut self, _layout: LayoutId) {}
+    fn resize_selection_edge(&mut self, layout: LayoutId, direction: Direction, screen_ratio: f64) {
+        let Some(node) = self.selection_of_layout(layout) else {
+            return;
+        };
+        let orientation = direction.orientation();
+        let grows_first = matches!(direction, Direction::Right | Direction::Down);
+        let Some(split) = self.boundary_split(node, direction) else {
+            return;
+        };
+
+        // Convert from a share of the screen to a share of the split.
+        let mut extent = 1.0;
+        let mut child = split;
+        while let Some(parent) = child.parent(&self.tree.map) {
+            if let Some(NodeKind::Split { orientation: o, ratio }) = self.kind.get(parent)
+                && *o == orientation
+            {
+                let ratio = f64::from(*ratio);
+                extent *= if Some(child) == parent.first_child(&self.tree.map) {
+                    ratio
+                } else {
+                    1.0 - ratio
+                };
+            }
+            child = parent;
+        }
+        if extent <= 0.0 {
+            return;
+        }
+
+        if let Some(NodeKind::Split { ratio, .. }) = self.kind.get_mut(split) {
+            let delta = (screen_ratio / extent) as f32;
+            let delta = if grows_first { delta } else { -delta };
+            *ratio = (*ratio + delta).clamp(0.05, 0.95);
+        }
+    }
+
+    fn set_selection_split_ratio(&mut self, layout: LayoutId, ratio: f64) -> bool {
+        let Some(mut node) = self.selection_of_layout(layout) else {
+            return false;
+        };
+        while let Some(parent) = node.parent(&self.tree.map) {
+            let is_first = Some(node) == parent.first_child(&self.tree.map);
+            if let Some(NodeKind::Split { ratio: split, .. }) = self.kind.get_mut(parent) {
+                let share = ratio.clamp(0.05, 0.95) as f32;
+                *split = if is_first { share } else { 1.0 - share };
+                return true;
+            }
+            node = parent;
+        }
+        false
+    }
+
+    fn rebalance(&mut self, layout: LayoutId) {
+        if let Some(state) = self.layouts.get(layout).copied() {
+            self.equalize_subtree(state.root);
+        }
+    }
 
     fn toggle_tile_orientation(&mut self, layout: LayoutId) {
         let sel_snapshot = self.selection_of_layout(layout);
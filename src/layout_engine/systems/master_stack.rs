@@ -6,10 +6,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::actor::app::WindowId;
 use crate::common::config::{MasterStackNewWindowPlacement, MasterStackSettings, MasterStackSide};
-use crate::layout_engine::systems::WindowLayoutConstraints;
+use crate::layout_engine::systems::{LayoutTreeNode, WindowLayoutConstraints};
 use crate::layout_engine::utils::compute_tiling_area;
 use crate::layout_engine::{
-    Direction, LayoutId, LayoutKind, LayoutSystem, Orientation, ResizeOrientation,
+    Direction, DropZoneKind, LayoutId, LayoutKind, LayoutSystem, Orientation, ResizeOrientation,
     TraditionalLayoutSystem,
 };
 use crate::model::tree::NodeId;
@@ -514,6 +514,8 @@ impl LayoutSystem for MasterStackLayoutSystem {
         self.inner.draw_tree_with_labels(layout, &labels)
     }
 
+    fn layout_tree(&self, layout: LayoutId) -> LayoutTreeNode { self.inner.layout_tree(layout) }
+
     fn calculate_layout(
         &self,
         layout: LayoutId,
@@ -714,6 +716,42 @@ impl LayoutSystem for MasterStackLayoutSystem {
         self.inner.swap_windows(layout, a, b)
     }
 
+    fn move_selection_to_index(&mut self, layout: LayoutId, index: usize) -> bool {
+        let (_root, _master, _stack) = self.ensure_structure(layout);
+        let Some(focused_wid) = self.inner.selected_window(layout) else {
+            return false;
+        };
+        let mut windows = self.windows_in_layout_by_container(layout);
+        let Some(focused_idx) = windows.iter().position(|&w| w == focused_wid) else {
+            return false;
+        };
+        let target_idx = index.min(windows.len() - 1);
+        if focused_idx == target_idx {
+            return false;
+        }
+        windows.remove(focused_idx);
+        windows.insert(target_idx, focused_wid);
+        self.rebuild_layout_with_windows(layout, &windows);
+        true
+    }
+
+    fn swap_selection_with_index(&mut self, layout: LayoutId, index: usize) -> bool {
+        let (_root, _master, _stack) = self.ensure_structure(layout);
+        let Some(focused_wid) = self.inner.selected_window(layout) else {
+            return false;
+        };
+        let mut windows = self.windows_in_layout_by_container(layout);
+        let Some(focused_idx) = windows.iter().position(|&w| w == focused_wid) else {
+            return false;
+        };
+        if index >= windows.len() || index == focused_idx {
+            return false;
+        }
+        windows.swap(focused_idx, index);
+        self.rebuild_layout_with_windows(layout, &windows);
+        true
+    }
+
     fn move_selection(&mut self, layout: LayoutId, direction: Direction) -> bool {
         let (_root, _master, _stack) = self.ensure_structure(layout);
         let Some(focused_wid) = self.inner.selected_window(layout) else {
@@ -851,6 +889,28 @@ impl LayoutSystem for MasterStackLayoutSystem {
         self.normalize_layout(layout);
     }
 
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        target: WindowId,
+        wid: WindowId,
+        zone: DropZoneKind,
+    ) {
+        // Windows fill the master area first and then the stack, so placing
+        // the window before or after the target in that order keeps it next
+        // to the target.
+        let _ = self.ensure_structure(layout);
+        let mut windows = self.windows_in_layout_by_container(layout);
+        let Some(index) = windows.iter().position(|&w| w == target) else {
+            self.add_window_after_selection(layout, wid);
+            return;
+        };
+        let index = index + usize::from(zone != DropZoneKind::Left);
+        windows.insert(index, wid);
+        self.rebuild_layout_with_windows(layout, &windows);
+        let _ = self.inner.select_window(layout, wid);
+    }
+
     fn toggle_fullscreen_of_selection(&mut self, layout: LayoutId) -> Vec<WindowId> {
         self.inner.toggle_fullscreen_of_selection(layout)
     }
@@ -904,6 +964,11 @@ impl LayoutSystem for MasterStackLayoutSystem {
         self.inner.resize_selection_by(layout, amount, orientation);
     }
 
+    fn resize_selection_edge(&mut self, layout: LayoutId, direction: Direction, screen_ratio: f64) {
+        let _ = self.ensure_structure(layout);
+        self.inner.resize_selection_edge(layout, direction, screen_ratio);
+    }
+
     fn rebalance(&mut self, layout: LayoutId) { self.normalize_layout(layout); }
 
     fn toggle_tile_orientation(&mut self, layout: LayoutId) { self.normalize_layout(layout); }
@@ -939,6 +1004,22 @@ mod tests {
         assert_eq!(windows, vec![w(3), w(2), w(1)]);
     }
 
+    #[test]
+    fn insert_window_beside_keeps_the_window_next_to_the_target() {
+        let mut system = MasterStackLayoutSystem::default();
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        // master=[w2], stack=[w1]
+
+        system.insert_window_beside(layout, w(1), w(3), DropZoneKind::Right);
+        assert_eq!(system.windows_in_layout_by_container(layout), vec![w(2), w(1), w(3)]);
+        system.insert_window_beside(layout, w(2), w(4), DropZoneKind::Left);
+        let windows = system.windows_in_layout_by_container(layout);
+        assert_eq!(windows, vec![w(4), w(2), w(1), w(3)]);
+        assert_eq!(system.selected_window(layout), Some(w(4)));
+    }
+
     #[test]
     fn resize_selection_respects_the_requested_axis() {
         let mut system = MasterStackLayoutSystem::default();
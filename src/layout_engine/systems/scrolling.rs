@@ -9,7 +9,7 @@ use crate::common::config::{ScrollingFocusNavigationStyle, ScrollingLayoutSettin
 use crate::layout_engine::systems::constraints::{AxisConstraints, solve_axis_lengths};
 use crate::layout_engine::systems::{LayoutSystem, WindowLayoutConstraints};
 use crate::layout_engine::utils::compute_tiling_area;
-use crate::layout_engine::{Direction, LayoutId, LayoutKind, ResizeOrientation};
+use crate::layout_engine::{Direction, DropZoneKind, LayoutId, LayoutKind, ResizeOrientation};
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 struct Column {
@@ -17,6 +17,10 @@ struct Column {
     width_offset: f64,
     #[serde(default)]
     height_weights: Vec<f64>,
+    /// Index into `column_width_presets` of the width last picked by
+    /// `cycle_column_width`, cleared when the column is resized by hand.
+    #[serde(default)]
+    width_preset: Option<usize>,
 }
 
 impl Column {
@@ -205,22 +209,38 @@ impl LayoutState {
     }
 
     fn insert_column_after(&mut self, index: usize, wid: WindowId) {
+        self.insert_column_at(index + 1, wid);
+    }
+
+    fn insert_column_at(&mut self, index: usize, wid: WindowId) {
         let column = Column {
             windows: vec![wid],
             width_offset: 0.0,
             height_weights: vec![1.0],
+            width_preset: None,
         };
-        let insert_at = (index + 1).min(self.columns.len());
+        let insert_at = index.min(self.columns.len());
         self.columns.insert(insert_at, column);
         self.selected = Some(wid);
         self.align_scroll_to_selected();
     }
 
+    /// Stacks `wid` in column `col_idx` right below row `row_idx`.
+    fn insert_row_after(&mut self, col_idx: usize, row_idx: usize, wid: WindowId) {
+        let col = &mut self.columns[col_idx];
+        col.ensure_height_weights();
+        col.windows.insert(row_idx + 1, wid);
+        col.height_weights.insert(row_idx + 1, 1.0);
+        self.selected = Some(wid);
+        self.align_scroll_to_selected();
+    }
+
     fn insert_column_at_end(&mut self, wid: WindowId) {
         self.columns.push(Column {
             windows: vec![wid],
             width_offset: 0.0,
             height_weights: vec![1.0],
+            width_preset: None,
         });
         self.selected = Some(wid);
         self.align_scroll_to_selected();
@@ -248,6 +268,7 @@ impl LayoutState {
                     windows: vec![window],
                     width_offset: 0.0,
                     height_weights: vec![1.0],
+                    width_preset: None,
                 });
             } else {
                 self.columns[target].ensure_height_weights();
@@ -290,6 +311,34 @@ impl Clone for LayoutState {
     }
 }
 
+/// The preset after (or before) `current`. A column that is not on a preset
+/// moves to the nearest preset wider (or narrower) than `ratio`, wrapping
+/// around at the ends.
+fn next_width_preset(
+    presets: &[f64],
+    current: Option<usize>,
+    ratio: f64,
+    forward: bool,
+) -> Option<usize> {
+    let len = presets.len();
+    if len == 0 {
+        return None;
+    }
+    if let Some(index) = current.filter(|&index| index < len) {
+        return Some(if forward {
+            (index + 1) % len
+        } else {
+            (index + len - 1) % len
+        });
+    }
+    const EPSILON: f64 = 1e-3;
+    Some(if forward {
+        presets.iter().position(|&preset| preset > ratio + EPSILON).unwrap_or(0)
+    } else {
+        presets.iter().rposition(|&preset| preset < ratio - EPSILON).unwrap_or(len - 1)
+    })
+}
+
 fn default_atomic_bool() -> AtomicBool { AtomicBool::new(false) }
 fn default_atomic_i8() -> AtomicI8 { AtomicI8::new(0) }
 fn default_atomic() -> AtomicU64 { AtomicU64::new(0.0f64.to_bits()) }
@@ -466,6 +515,39 @@ impl ScrollingLayoutSystem {
         state.scroll_offset_px.store(next.to_bits(), Ordering::Relaxed);
     }
 
+    /// Resize the selected column to the next (or previous) configured width
+    /// preset.
+    pub fn cycle_column_width(&mut self, layout: LayoutId, forward: bool) {
+        let presets = self.settings.column_width_presets.clone();
+        let min_ratio = self.settings.min_column_width_ratio;
+        let max_ratio = self.settings.max_column_width_ratio;
+        let niri_navigation = matches!(
+            self.settings.focus_navigation_style,
+            ScrollingFocusNavigationStyle::Niri
+        );
+        let Some(state) = self.layout_state_mut(layout) else {
+            return;
+        };
+        let Some((col_idx, _)) = state.selected_location() else {
+            return;
+        };
+        let base_ratio = state.column_width_ratio;
+        let column = &mut state.columns[col_idx];
+        let current = base_ratio + column.width_offset;
+        let Some(preset) = next_width_preset(&presets, column.width_preset, current, forward)
+        else {
+            return;
+        };
+        let ratio = Self::clamp_ratio_with_bounds(presets[preset], min_ratio, max_ratio);
+        column.width_preset = Some(preset);
+        column.width_offset = ratio - base_ratio;
+        if niri_navigation {
+            state.reveal_selected_without_direction();
+        } else {
+            state.align_scroll_to_selected();
+        }
+    }
+
     pub fn center_selected_column(&mut self, layout: LayoutId) {
         let Some(state) = self.layout_state_mut(layout) else {
             return;
@@ -551,6 +633,7 @@ impl ScrollingLayoutSystem {
                 windows: vec![wid],
                 width_offset: 0.0,
                 height_weights: vec![weight],
+                width_preset: None,
             });
             state.selected = Some(wid);
             return true;
@@ -1210,6 +1293,7 @@ impl LayoutSystem for ScrollingLayoutSystem {
             return;
         };
         state.columns[col_idx].width_offset = clamped - base_ratio;
+        state.columns[col_idx].width_preset = None;
 
         // Handle vertical resizing within columns
         let col = &mut state.columns[col_idx];
@@ -1358,6 +1442,34 @@ impl LayoutSystem for ScrollingLayoutSystem {
         // Not applicable for scrolling layout.
     }
 
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        target: WindowId,
+        wid: WindowId,
+        zone: DropZoneKind,
+    ) {
+        let niri_navigation = matches!(
+            self.settings.focus_navigation_style,
+            ScrollingFocusNavigationStyle::Niri
+        );
+        let Some(state) = self.layout_state_mut(layout) else {
+            return;
+        };
+        let Some((col_idx, row_idx)) = state.locate(target) else {
+            self.add_window_after_selection(layout, wid);
+            return;
+        };
+        match zone {
+            DropZoneKind::Left => state.insert_column_at(col_idx, wid),
+            DropZoneKind::Right => state.insert_column_after(col_idx, wid),
+            DropZoneKind::Container => state.insert_row_after(col_idx, row_idx, wid),
+        }
+        if niri_navigation {
+            state.reveal_selected_without_direction();
+        }
+    }
+
     fn toggle_fullscreen_of_selection(&mut self, layout: LayoutId) -> Vec<WindowId> {
         let Some(state) = self.layout_state_mut(layout) else {
             return Vec::new();
@@ -1453,6 +1565,7 @@ impl LayoutSystem for ScrollingLayoutSystem {
             windows: vec![wid],
             width_offset: 0.0,
             height_weights: vec![weight],
+            width_preset: None,
         });
         state.selected = Some(wid);
         if niri_navigation {
@@ -1532,6 +1645,7 @@ impl LayoutSystem for ScrollingLayoutSystem {
                 windows: vec![wid],
                 width_offset: 0.0,
                 height_weights: vec![moved_weights[idx]],
+                width_preset: None,
             });
             insert_at += 1;
         }
@@ -1571,6 +1685,7 @@ impl LayoutSystem for ScrollingLayoutSystem {
             windows: vec![wid],
             width_offset: 0.0,
             height_weights: vec![weight],
+            width_preset: None,
         });
         state.selected = Some(wid);
         if niri_navigation {
@@ -1638,6 +1753,7 @@ impl LayoutSystem for ScrollingLayoutSystem {
         let next = current + amount;
         let clamped = next.clamp(min_ratio, max_ratio).max(0.05);
         state.columns[col_idx].width_offset = clamped - base_ratio;
+        state.columns[col_idx].width_preset = None;
         if niri_navigation {
             state.reveal_selected_without_direction();
         } else {
@@ -1656,13 +1772,13 @@ mod tests {
 
     use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 
-    use super::{Column, ScrollingLayoutSystem};
+    use super::{Column, ScrollingLayoutSystem, next_width_preset};
     use crate::actor::app::{WindowId, pid_t};
     use crate::common::collections::HashMap;
     use crate::common::config::{GapSettings, ScrollingLayoutSettings};
     use crate::layout_engine::systems::{LayoutSystem, WindowLayoutConstraints};
     use crate::layout_engine::utils::compute_tiling_area;
-    use crate::layout_engine::{Direction, LayoutId, ResizeOrientation};
+    use crate::layout_engine::{Direction, DropZoneKind, LayoutId, ResizeOrientation};
 
     fn wid(pid: pid_t, idx: u32) -> WindowId {
         WindowId {
@@ -1725,6 +1841,46 @@ mod tests {
         (system, layout, w1, w2)
     }
 
+    #[test]
+    fn next_width_preset_steps_through_presets_and_wraps() {
+        let presets = [1.0 / 3.0, 0.5, 2.0 / 3.0];
+        assert_eq!(next_width_preset(&presets, None, 0.4, true), Some(1));
+        assert_eq!(next_width_preset(&presets, None, 0.4, false), Some(0));
+        assert_eq!(next_width_preset(&presets, None, 0.7, true), Some(0));
+        assert_eq!(next_width_preset(&presets, Some(2), 0.7, true), Some(0));
+        assert_eq!(next_width_preset(&presets, Some(0), 0.3, false), Some(2));
+        assert_eq!(next_width_preset(&[], None, 0.5, true), None);
+    }
+
+    #[test]
+    fn cycle_column_width_persists_preset_until_manual_resize() {
+        let (mut system, layout, _, _) = setup_two_windows(ScrollingLayoutSettings::default());
+        let column_ratio = |system: &ScrollingLayoutSystem| {
+            let state = system.layouts.get(layout).expect("layout state missing");
+            let column = &state.columns[state.selected_location().unwrap().0];
+            (
+                state.column_width_ratio + column.width_offset,
+                column.width_preset,
+            )
+        };
+
+        system.cycle_column_width(layout, true);
+        let (ratio, preset) = column_ratio(&system);
+        assert!((ratio - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(preset, Some(0));
+
+        system.cycle_column_width(layout, true);
+        assert_eq!(column_ratio(&system).1, Some(1));
+        system.cycle_column_width(layout, false);
+        system.cycle_column_width(layout, false);
+        let (ratio, preset) = column_ratio(&system);
+        assert!((ratio - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(preset, Some(2));
+
+        system.resize_selection_by(layout, 0.05, ResizeOrientation::Horizontal);
+        assert_eq!(column_ratio(&system).1, None);
+    }
+
     #[test]
     fn respects_min_width_and_min_height_independently() {
         let mut system = ScrollingLayoutSystem::new(&ScrollingLayoutSettings::default());
@@ -1776,6 +1932,7 @@ mod tests {
             windows: vec![w1, w2],
             width_offset: 0.0,
             height_weights: vec![1.0, 1.0],
+            width_preset: None,
         }];
         state.selected = Some(w1);
 
@@ -1859,6 +2016,7 @@ mod tests {
             windows: vec![locked, capped],
             width_offset: 0.0,
             height_weights: vec![1.0, 1.0],
+            width_preset: None,
         }];
         state.selected = Some(locked);
 
@@ -1967,6 +2125,24 @@ mod tests {
         assert_eq!(focus, Some(w2));
     }
 
+    #[test]
+    fn insert_window_beside_adds_columns_and_rows_around_the_target() {
+        let (mut system, layout, w1, w2) = setup_two_windows(ScrollingLayoutSettings::default());
+        let (w3, w4, w5) = (wid(1, 3), wid(1, 4), wid(1, 5));
+
+        system.insert_window_beside(layout, w1, w3, DropZoneKind::Left);
+        system.insert_window_beside(layout, w1, w4, DropZoneKind::Right);
+        system.insert_window_beside(layout, w2, w5, DropZoneKind::Container);
+
+        let columns: Vec<_> = system.layouts[layout]
+            .columns
+            .iter()
+            .map(|column| column.windows.clone())
+            .collect();
+        assert_eq!(columns, vec![vec![w3], vec![w1], vec![w4], vec![w2, w5]]);
+        assert_eq!(system.selected_window(layout), Some(w5));
+    }
+
     #[test]
     fn move_selection_swaps_columns_horizontally() {
         let mut system = ScrollingLayoutSystem::new(&ScrollingLayoutSettings::default());
@@ -5,9 +5,9 @@ use serde::{Deserialize, Serialize};
 use crate::actor::app::WindowId;
 use crate::common::collections::HashMap;
 use crate::common::config::{StackDefaultOrientation, default_stack_orientation};
-use crate::layout_engine::systems::{LayoutSystem, WindowLayoutConstraints};
+use crate::layout_engine::systems::{LayoutSystem, LayoutTreeNode, WindowLayoutConstraints};
 use crate::layout_engine::{
-    Direction, LayoutId, LayoutKind, ResizeOrientation, TraditionalLayoutSystem,
+    Direction, DropZoneKind, LayoutId, LayoutKind, ResizeOrientation, TraditionalLayoutSystem,
 };
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -161,6 +161,8 @@ impl LayoutSystem for StackLayoutSystem {
 
     fn draw_tree(&self, layout: LayoutId) -> String { self.inner.draw_tree(layout) }
 
+    fn layout_tree(&self, layout: LayoutId) -> LayoutTreeNode { self.inner.layout_tree(layout) }
+
     fn calculate_layout(
         &self,
         layout: LayoutId,
@@ -298,6 +300,22 @@ impl LayoutSystem for StackLayoutSystem {
         moved
     }
 
+    fn move_selection_to_index(&mut self, layout: LayoutId, index: usize) -> bool {
+        let moved = self.inner.move_selection_to_index(layout, index);
+        if moved {
+            self.normalize_layout(layout);
+        }
+        moved
+    }
+
+    fn swap_selection_with_index(&mut self, layout: LayoutId, index: usize) -> bool {
+        let swapped = self.inner.swap_selection_with_index(layout, index);
+        if swapped {
+            self.normalize_layout(layout);
+        }
+        swapped
+    }
+
     fn move_selection_to_layout_after_selection(
         &mut self,
         from_layout: LayoutId,
@@ -310,6 +328,23 @@ impl LayoutSystem for StackLayoutSystem {
 
     fn split_selection(&mut self, _layout: LayoutId, _kind: LayoutKind) {}
 
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        target: WindowId,
+        wid: WindowId,
+        zone: DropZoneKind,
+    ) {
+        // A stack has no sides, so the zone only decides whether the window
+        // goes before or after the target.
+        let windows = self.windows_in_layout_preorder(layout);
+        self.add_window_after_selection(layout, wid);
+        if let Some(index) = windows.iter().position(|&w| w == target) {
+            let index = index + usize::from(zone != DropZoneKind::Left);
+            let _ = self.move_selection_to_index(layout, index);
+        }
+    }
+
     fn toggle_fullscreen_of_selection(&mut self, layout: LayoutId) -> Vec<WindowId> {
         self.inner.toggle_fullscreen_of_selection(layout)
     }
@@ -407,6 +442,23 @@ mod tests {
         assert!(system.inner.layout(root).is_stacked());
     }
 
+    #[test]
+    fn insert_window_beside_places_the_window_before_or_after_the_target() {
+        let mut system = StackLayoutSystem::new(StackDefaultOrientation::Perpendicular);
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+
+        system.insert_window_beside(layout, w(2), w(3), DropZoneKind::Left);
+        assert_eq!(system.all_windows_in_layout(layout), vec![w(1), w(3), w(2)]);
+        system.insert_window_beside(layout, w(1), w(4), DropZoneKind::Right);
+        assert_eq!(system.all_windows_in_layout(layout), vec![w(1), w(4), w(3), w(2)]);
+        assert_eq!(system.selected_window(layout), Some(w(4)));
+
+        let root = system.inner.root(layout);
+        assert!(system.inner.layout(root).is_stacked());
+    }
+
     #[test]
     fn set_windows_for_app_noop_keeps_fullscreen_state() {
         let mut system = StackLayoutSystem::new(StackDefaultOrientation::Perpendicular);
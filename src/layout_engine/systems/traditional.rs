@@ -4,10 +4,17 @@ use tracing::warn;
 
 use crate::actor::app::{WindowId, pid_t};
 use crate::common::collections::HashMap;
+use crate::common::config::{
+    CloseResize, ContainerOverflow, InsertionPolicy, LayoutTemplate, TraditionalSettings,
+};
 use crate::layout_engine::systems::constraints::{AxisConstraints, solve_axis_lengths};
-use crate::layout_engine::systems::{LayoutSystem, WindowLayoutConstraints};
+use crate::layout_engine::systems::{
+    LayoutSystem, LayoutTreeNode, TilingBookmark, WindowLayoutConstraints,
+};
 use crate::layout_engine::utils::compute_tiling_area;
-use crate::layout_engine::{Direction, LayoutId, LayoutKind, Orientation, ResizeOrientation};
+use crate::layout_engine::{
+    Direction, DropZoneKind, LayoutId, LayoutKind, Orientation, ResizeOrientation,
+};
 use crate::model::selection::*;
 use crate::model::tree::{self, NodeId, NodeMap, OwnedNode, Tree};
 use crate::sys::geometry::Round;
@@ -16,6 +23,14 @@ use crate::sys::geometry::Round;
 pub struct TraditionalLayoutSystem {
     pub(crate) tree: Tree<Components>,
     pub(crate) layout_roots: slotmap::SlotMap<LayoutId, OwnedNode>,
+    #[serde(default)]
+    template: Option<LayoutTemplate>,
+    /// Number of template slots already filled, for layouts still being built
+    /// from the template.
+    #[serde(default)]
+    template_progress: slotmap::SecondaryMap<LayoutId, usize>,
+    #[serde(skip)]
+    settings: TraditionalSettings,
 }
 
 impl Default for TraditionalLayoutSystem {
@@ -23,11 +38,66 @@ impl Default for TraditionalLayoutSystem {
         Self {
             tree: Tree::with_observer(Components::default()),
             layout_roots: Default::default(),
+            template: None,
+            template_progress: Default::default(),
+            settings: TraditionalSettings::default(),
         }
     }
 }
 
 impl TraditionalLayoutSystem {
+    pub fn update_settings(&mut self, settings: &TraditionalSettings) { self.settings = *settings; }
+
+    /// Build layouts created from now on out of `template`.
+    pub(crate) fn set_template(&mut self, template: LayoutTemplate) {
+        self.template = Some(template);
+    }
+
+    /// Place `wid` in the next template slot if `layout` is still being built from
+    /// the template. Any change to the root that the template did not make (such
+    /// as a slot's window closing) abandons the template for that layout.
+    fn add_window_to_template_slot(&mut self, layout: LayoutId, wid: WindowId) -> Option<NodeId> {
+        let filled = *self.template_progress.get(layout)?;
+        let template = self.template.as_ref()?;
+        let root = self.root(layout);
+        let Some(slot) = template.slots.get(filled).cloned() else {
+            self.template_progress.remove(layout);
+            return None;
+        };
+        if root.children(self.map()).count() != filled {
+            self.template_progress.remove(layout);
+            return None;
+        }
+        let orientation = template.orientation;
+        let slot_count = template.slots.len();
+
+        let slot_orientation = match orientation {
+            Orientation::Horizontal => Orientation::Vertical,
+            Orientation::Vertical => Orientation::Horizontal,
+        };
+        let slot_kind = if slot.stacked {
+            LayoutKind::stack_with_offset(slot_orientation)
+        } else {
+            LayoutKind::from(slot_orientation)
+        };
+
+        if filled == 0 {
+            self.set_layout(root, LayoutKind::from(orientation));
+        }
+        let container = self.tree.mk_node().push_back(root);
+        self.set_layout(container, slot_kind);
+        self.tree.data.layout.info[container].size = slot.ratio as f32;
+        self.tree.data.layout.recompute_total(&self.tree.map, root);
+        let node = self.add_window_under(layout, container, wid);
+
+        if filled + 1 == slot_count {
+            self.template_progress.remove(layout);
+        } else {
+            self.template_progress.insert(layout, filled + 1);
+        }
+        Some(node)
+    }
+
     fn find_best_focus_target(&self, node: NodeId) -> Option<(NodeId, WindowId)> {
         if let Some(wid) = self.tree.data.window.at(node) {
             return Some((node, wid));
@@ -70,10 +140,16 @@ impl TraditionalLayoutSystem {
         if let Some(parent) = parent {
             let parent_layout = self.layout(parent);
             let sibling_count = parent.children(self.map()).count();
+            let limit = self.settings.max_windows_per_container;
 
-            if sibling_count >= 4 && !parent_layout.is_group() {
-                let sub_container =
-                    self.nest_in_container_internal(layout, selection, parent_layout);
+            if limit > 0 && sibling_count >= limit && !parent_layout.is_group() {
+                let kind = match self.settings.container_overflow {
+                    ContainerOverflow::Nest => parent_layout,
+                    ContainerOverflow::Stack => {
+                        LayoutKind::stack_with_offset(parent_layout.orientation())
+                    }
+                };
+                let sub_container = self.nest_in_container_internal(layout, selection, kind);
                 let node = self.tree.mk_node().push_back(sub_container);
                 self.split_new_sibling_from_selection(selection, node);
                 self.tree.data.window.set_window(layout, node, wid);
@@ -87,6 +163,43 @@ impl TraditionalLayoutSystem {
         node
     }
 
+    /// Split `anchor`'s tile in two across its container's direction, so
+    /// windows inserted one after another spiral inwards.
+    fn split_tile_of(&mut self, layout: LayoutId, anchor: NodeId, wid: WindowId) -> NodeId {
+        if let Some(parent) = anchor.parent(self.map()) {
+            let parent_layout = self.layout(parent);
+            if !parent_layout.is_group() && parent.children(self.map()).nth(1).is_some() {
+                let across = match parent_layout.orientation() {
+                    Orientation::Horizontal => Orientation::Vertical,
+                    Orientation::Vertical => Orientation::Horizontal,
+                };
+                self.nest_in_container_internal(layout, anchor, LayoutKind::from(across));
+            }
+        }
+        let node = self.tree.mk_node().insert_after(anchor);
+        self.split_new_sibling_from_selection(anchor, node);
+        self.tree.data.window.set_window(layout, node, wid);
+        node
+    }
+
+    /// The window with the largest share of the screen, judged by split
+    /// sizes alone. Stacked windows count as their whole stack.
+    fn largest_window_node(&self, layout: LayoutId) -> Option<NodeId> {
+        let map = self.map();
+        let share = |node: NodeId| -> f64 {
+            node.ancestors(map)
+                .filter(|n| n.parent(map).is_some_and(|parent| !self.layout(parent).is_group()))
+                .map(|n| self.tree.data.layout.proportion(map, n).unwrap_or(1.0))
+                .product()
+        };
+        self.root(layout)
+            .traverse_preorder(map)
+            .filter(|&node| self.window_at(node).is_some())
+            .map(|node| (node, share(node)))
+            .min_by(|a, b| b.1.total_cmp(&a.1))
+            .map(|(node, _)| node)
+    }
+
     fn find_or_create_smart_common_parent(
         &mut self,
         layout: LayoutId,
@@ -431,7 +544,11 @@ impl Drop for TraditionalLayoutSystem {
 impl LayoutSystem for TraditionalLayoutSystem {
     fn create_layout(&mut self) -> LayoutId {
         let root = OwnedNode::new_root_in(&mut self.tree, "layout_root");
-        self.layout_roots.insert(root)
+        let layout = self.layout_roots.insert(root);
+        if self.template.is_some() {
+            self.template_progress.insert(layout, 0);
+        }
+        layout
     }
 
     fn contains_layout(&self, layout: LayoutId) -> bool { self.layout_roots.contains_key(layout) }
@@ -451,10 +568,14 @@ impl LayoutSystem for TraditionalLayoutSystem {
                 dest_layout,
             });
         }
+        if let Some(&filled) = self.template_progress.get(layout) {
+            self.template_progress.insert(dest_layout, filled);
+        }
         dest_layout
     }
 
     fn remove_layout(&mut self, layout: LayoutId) {
+        self.template_progress.remove(layout);
         self.layout_roots.remove(layout).unwrap().remove(&mut self.tree)
     }
 
@@ -465,6 +586,13 @@ impl LayoutSystem for TraditionalLayoutSystem {
         out
     }
 
+    fn layout_tree(&self, layout: LayoutId) -> LayoutTreeNode {
+        let root = self.root(layout);
+        let selection = self.selection(layout);
+        let selection_path: Vec<NodeId> = selection.ancestors(self.map()).collect();
+        self.layout_tree_node(root, &selection_path)
+    }
+
     fn calculate_layout(
         &self,
         layout: LayoutId,
@@ -573,24 +701,72 @@ impl LayoutSystem for TraditionalLayoutSystem {
     }
 
     fn add_window_after_selection(&mut self, layout: LayoutId, wid: WindowId) {
+        if let Some(node) = self.add_window_to_template_slot(layout, wid) {
+            self.select(node);
+            return;
+        }
         let selection = self.selection(layout);
-        let node = if selection.parent(self.map()).is_none() {
+        let anchor = if selection.parent(self.map()).is_none() {
             // If the root is selected but it already has children, split relative to the
             // root's active child instead of appending a fresh full-weight sibling.
-            if let Some(anchor) =
-                self.local_selection(selection).or_else(|| selection.last_child(self.map()))
-            {
+            self.local_selection(selection).or_else(|| selection.last_child(self.map()))
+        } else {
+            Some(selection)
+        };
+        let node = match (anchor, self.settings.insertion_policy) {
+            (None, _) => self.add_window_under(layout, selection, wid),
+            (Some(anchor), InsertionPolicy::AfterSelection) => {
                 self.smart_window_insertion(layout, anchor, wid)
-            } else {
-                self.add_window_under(layout, selection, wid)
             }
-        } else {
-            let node = self.smart_window_insertion(layout, selection, wid);
-            node
+            (Some(anchor), InsertionPolicy::EndOfParent) => {
+                let parent = anchor.parent(self.map()).unwrap_or(selection);
+                self.add_window_under(layout, parent, wid)
+            }
+            (Some(anchor), InsertionPolicy::Spiral) => self.split_tile_of(layout, anchor, wid),
+            (Some(anchor), InsertionPolicy::LargestArea) => {
+                let anchor = self.largest_window_node(layout).unwrap_or(anchor);
+                self.split_tile_of(layout, anchor, wid)
+            }
         };
         self.select(node);
     }
 
+    fn tiling_bookmark(&self, layout: LayoutId, wid: WindowId) -> Option<TilingBookmark> {
+        let node = self.tree.data.window.node_for(layout, wid)?;
+        let container = node.parent(self.map())?;
+        let index = container.children(self.map()).position(|child| child == node)?;
+        Some(TilingBookmark {
+            container: Some(container),
+            index,
+        })
+    }
+
+    fn restore_window(
+        &mut self,
+        layout: LayoutId,
+        wid: WindowId,
+        bookmark: TilingBookmark,
+    ) -> bool {
+        let Some(container) = bookmark.container else {
+            return false;
+        };
+        let root = self.root(layout);
+        if !self.tree.contains(container)
+            || self.window_at(container).is_some()
+            || self.contains_window(layout, wid)
+            || !container.ancestors(self.map()).any(|node| node == root)
+        {
+            return false;
+        }
+        let node = match container.children(self.map()).nth(bookmark.index) {
+            Some(next) => self.tree.mk_node().insert_before(next),
+            None => self.tree.mk_node().push_back(container),
+        };
+        self.tree.data.window.set_window(layout, node, wid);
+        self.select(node);
+        true
+    }
+
     fn replace_window(&mut self, from: WindowId, to: WindowId) {
         self.tree.data.window.replace_window(from, to);
     }
@@ -619,6 +795,16 @@ impl LayoutSystem for TraditionalLayoutSystem {
         }
     }
 
+    fn remove_closed_window(&mut self, wid: WindowId, resize: CloseResize) {
+        match resize {
+            // Sizes are weights, so removing a window already grows its
+            // siblings in proportion to their sizes.
+            CloseResize::Keep | CloseResize::Proportional => self.remove_window(wid),
+            CloseResize::Neighbor => self.remove_window_into_neighbor(wid),
+            CloseResize::Equalize => self.remove_window_and_rebalance_parent(wid),
+        }
+    }
+
     fn remove_windows_for_app(&mut self, pid: pid_t) {
         let nodes: Vec<_> =
             self.tree.data.window.take_nodes_for_app(pid).map(|(_, _, node)| node).collect();
@@ -726,6 +912,31 @@ impl LayoutSystem for TraditionalLayoutSystem {
         self.move_node(layout, selection, direction)
     }
 
+    fn move_selection_to_index(&mut self, layout: LayoutId, index: usize) -> bool {
+        let selection = self.selection(layout);
+        self.move_node_to_index(selection, index)
+    }
+
+    fn swap_selection_with_index(&mut self, layout: LayoutId, index: usize) -> bool {
+        let selection = self.selection(layout);
+        let map = &self.tree.map;
+        let Some(parent) = selection.parent(map) else {
+            return false;
+        };
+        let Some(current) = parent.children(map).position(|node| node == selection) else {
+            return false;
+        };
+        let Some(other) = parent.children(map).nth(index) else {
+            return false;
+        };
+        if other == selection {
+            return false;
+        }
+        self.move_node_to_index(selection, index);
+        self.move_node_to_index(other, current);
+        true
+    }
+
     fn move_selection_to_layout_after_selection(
         &mut self,
         from_layout: LayoutId,
@@ -758,6 +969,33 @@ impl LayoutSystem for TraditionalLayoutSystem {
         self.nest_in_container_internal(layout, selection, kind);
     }
 
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        target: WindowId,
+        wid: WindowId,
+        zone: DropZoneKind,
+    ) {
+        let Some(anchor) = self.tree.data.window.node_for(layout, target) else {
+            self.add_window_after_selection(layout, wid);
+            return;
+        };
+        if zone != DropZoneKind::Container
+            && anchor.parent(self.map()).map(|parent| self.layout(parent))
+                != Some(LayoutKind::Horizontal)
+        {
+            self.nest_in_container_internal(layout, anchor, LayoutKind::Horizontal);
+        }
+        let node = if zone == DropZoneKind::Left {
+            self.tree.mk_node().insert_before(anchor)
+        } else {
+            self.tree.mk_node().insert_after(anchor)
+        };
+        self.split_new_sibling_from_selection(anchor, node);
+        self.tree.data.window.set_window(layout, node, wid);
+        self.select(node);
+    }
+
     fn toggle_fullscreen_of_selection(&mut self, layout: LayoutId) -> Vec<WindowId> {
         let node = self.selection(layout);
         if self.tree.data.layout.toggle_fullscreen(node) {
@@ -1118,6 +1356,30 @@ impl LayoutSystem for TraditionalLayoutSystem {
         }
     }
 
+    fn resize_selection_edge(&mut self, layout: LayoutId, direction: Direction, screen_ratio: f64) {
+        if screen_ratio == 0.0 {
+            return;
+        }
+        let selection = self.selection(layout);
+        if self.window_at(selection).is_some() {
+            self.resize_internal(selection, screen_ratio, direction);
+        }
+    }
+
+    fn set_selection_split_ratio(&mut self, layout: LayoutId, ratio: f64) -> bool {
+        let selection = self.selection(layout);
+        let map = &self.tree.map;
+        let Some(node) = selection.ancestors(map).find(|&node| {
+            node.parent(map).is_some_and(|parent| {
+                !self.layout(parent).is_group() && parent.children(map).nth(1).is_some()
+            })
+        }) else {
+            return false;
+        };
+        self.tree.data.layout.set_share(&self.tree.map, node, ratio as f32);
+        true
+    }
+
     fn rebalance(&mut self, layout: LayoutId) {
         let root = self.root(layout);
         self.rebalance_node(root)
@@ -1554,6 +1816,23 @@ impl TraditionalLayoutSystem {
         }
     }
 
+    fn layout_tree_node(&self, node: NodeId, selection_path: &[NodeId]) -> LayoutTreeNode {
+        let ratio = self.tree.data.layout.proportion(self.map(), node).unwrap_or(1.0);
+        let selected = selection_path.contains(&node);
+        match self.window_at(node) {
+            Some(window_id) => LayoutTreeNode::Window { window_id, ratio, selected },
+            None => LayoutTreeNode::Container {
+                kind: self.layout(node),
+                ratio,
+                selected,
+                children: node
+                    .children(self.map())
+                    .map(|child| self.layout_tree_node(child, selection_path))
+                    .collect(),
+            },
+        }
+    }
+
     pub(crate) fn add_window_under(
         &mut self,
         layout: LayoutId,
@@ -1604,6 +1883,44 @@ impl TraditionalLayoutSystem {
         None
     }
 
+    /// Removes `wid` and gives each of its tiles to the sibling before it (or
+    /// after it, for the first one), so the other windows keep their sizes.
+    fn remove_window_into_neighbor(&mut self, wid: WindowId) {
+        let nodes: Vec<_> =
+            self.tree.data.window.take_nodes_for(wid).map(|(_, node)| node).collect();
+        for node in nodes {
+            let sizes = node.parent(&self.tree.map).map(|parent| {
+                let layout = &self.tree.data.layout;
+                let siblings: Vec<NodeId> = parent.children(&self.tree.map).collect();
+                let index = siblings.iter().position(|&child| child == node).unwrap_or(0);
+                let neighbor = index.checked_sub(1).or((siblings.len() > 1).then_some(1));
+                let freed = layout.info[node].size.max(0.0);
+                let sizes: Vec<(NodeId, f32)> = siblings
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| i != index)
+                    .map(|(i, &child)| {
+                        let size = layout.info[child].size.max(0.0);
+                        (child, if Some(i) == neighbor { size + freed } else { size })
+                    })
+                    .collect();
+                (parent, sizes)
+            });
+            node.detach(&mut self.tree).remove();
+            // With one sibling left the container collapses into it, so there
+            // are no sizes left to share out.
+            if let Some((parent, sizes)) = sizes
+                && sizes.len() > 1
+                && self.tree.data.layout.info.contains_key(parent)
+            {
+                for (child, size) in sizes {
+                    self.tree.data.layout.info[child].size = size;
+                }
+                self.tree.data.layout.recompute_total(&self.tree.map, parent);
+            }
+        }
+    }
+
     fn rebalance_node(&mut self, node: NodeId) {
         let map = &self.tree.map;
         let children: Vec<_> = node.children(map).collect();
@@ -1792,6 +2109,37 @@ impl TraditionalLayoutSystem {
         moved
     }
 
+    /// Reorder `node` among its siblings so it ends up at `index` (clamped to the
+    /// last slot). Like `move_node`, this is geometry-neutral for the parent.
+    fn move_node_to_index(&mut self, node: NodeId, index: usize) -> bool {
+        let map = &self.tree.map;
+        let Some(parent) = node.parent(map) else {
+            return false;
+        };
+        let siblings: Vec<NodeId> = parent.children(map).filter(|&child| child != node).collect();
+        let index = index.min(siblings.len());
+        if parent.children(map).position(|child| child == node) == Some(index) {
+            return false;
+        }
+        let old_sibling_sizes: Vec<_> = parent
+            .children(map)
+            .map(|child| (child, self.tree.data.layout.info[child].size))
+            .collect();
+        let is_selection = self.tree.data.selection.local_selection(map, parent) == Some(node);
+        match siblings.get(index) {
+            Some(&next) => node.detach(&mut self.tree).insert_before(next),
+            None => node.detach(&mut self.tree).push_back(parent),
+        };
+        for (child, size) in old_sibling_sizes {
+            self.tree.data.layout.info[child].size = size;
+        }
+        self.tree.data.layout.recompute_total(&self.tree.map, parent);
+        if is_selection {
+            self.tree.data.selection.select_locally(&self.tree.map, node);
+        }
+        true
+    }
+
     fn move_node_inner(
         &mut self,
         layout: LayoutId,
@@ -2499,6 +2847,30 @@ impl Layout {
         }
     }
 
+    /// Give `node` exactly `share` of its parent, scaling its siblings to
+    /// split the rest in their current proportions.
+    fn set_share(&mut self, map: &NodeMap, node: NodeId, share: f32) {
+        let Some(parent) = node.parent(map) else { return };
+        let children: Vec<_> = parent.children(map).collect();
+        let target_total = children.len() as f32;
+        let share = share.clamp(0.05, 0.95);
+        let others: f32 = children
+            .iter()
+            .filter(|&&child| child != node)
+            .map(|&child| self.info[child].size.max(0.0))
+            .sum();
+        for &child in &children {
+            self.info[child].size = if child == node {
+                share * target_total
+            } else if others > f32::EPSILON {
+                self.info[child].size.max(0.0) / others * (1.0 - share) * target_total
+            } else {
+                (1.0 - share) * target_total / (target_total - 1.0)
+            };
+        }
+        self.info[parent].total = target_total;
+    }
+
     fn set_fullscreen(&mut self, node: NodeId, is_fullscreen: bool) {
         self.info[node].is_fullscreen = is_fullscreen;
         if is_fullscreen {
@@ -3073,6 +3445,128 @@ mod tests {
         assert_eq!(system.window_in_direction(layout, Direction::Up), Some(w(2)));
     }
 
+    #[test]
+    fn move_and_swap_selection_by_index_reorders_siblings() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        system.add_window_under(layout, root, w(1));
+        system.add_window_under(layout, root, w(2));
+        let third = system.add_window_under(layout, root, w(3));
+        system.select(third);
+
+        assert!(system.move_selection_to_index(layout, 0));
+        assert_eq!(system.all_windows_in_layout(layout), vec![w(3), w(1), w(2)]);
+        assert_eq!(system.selected_window(layout), Some(w(3)));
+        assert!(!system.move_selection_to_index(layout, 0));
+
+        assert!(system.swap_selection_with_index(layout, 2));
+        assert_eq!(system.all_windows_in_layout(layout), vec![w(2), w(1), w(3)]);
+        assert_eq!(system.selected_window(layout), Some(w(3)));
+        assert!(!system.swap_selection_with_index(layout, 5));
+    }
+
+    #[test]
+    fn layout_tree_reports_containers_ratios_and_selection_path() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        system.add_window_under(layout, root, w(1));
+        let column = system.tree.mk_node().push_back(root);
+        system.tree.data.layout.set_kind(column, LayoutKind::VerticalStack);
+        system.add_window_under(layout, column, w(2));
+        let selected = system.add_window_under(layout, column, w(3));
+        system.select(selected);
+
+        let tree = system.layout_tree(layout);
+        let LayoutTreeNode::Container { kind, selected, children, .. } = &tree else {
+            panic!("root should be a container: {tree:?}");
+        };
+        assert_eq!((*kind, *selected), (LayoutKind::Horizontal, true));
+        assert_eq!(children[0], LayoutTreeNode::Window {
+            window_id: w(1),
+            ratio: 0.5,
+            selected: false,
+        });
+        let LayoutTreeNode::Container {
+            kind,
+            selected,
+            children,
+            ratio,
+        } = &children[1]
+        else {
+            panic!("second child should be a container");
+        };
+        assert_eq!(
+            (*kind, *selected, *ratio),
+            (LayoutKind::VerticalStack, true, 0.5)
+        );
+        assert_eq!(children[1], LayoutTreeNode::Window {
+            window_id: w(3),
+            ratio: 0.5,
+            selected: true,
+        });
+
+        let json = serde_json::to_value(&tree).unwrap();
+        assert_eq!(json["type"], "container");
+        assert_eq!(json["kind"], "horizontal");
+        assert_eq!(json["children"][1]["children"][0]["type"], "window");
+    }
+
+    #[test]
+    fn template_slots_receive_first_windows() {
+        use crate::common::config::TemplateSlot;
+
+        let mut system = TraditionalLayoutSystem::default();
+        system.set_template(LayoutTemplate {
+            orientation: Orientation::Horizontal,
+            slots: vec![TemplateSlot { ratio: 2.0, stacked: false }, TemplateSlot {
+                ratio: 1.0,
+                stacked: true,
+            }],
+        });
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+
+        let parent_of = |wid| {
+            let node = system.tree.data.window.node_for(layout, wid).unwrap();
+            node.parent(system.map()).unwrap()
+        };
+        let (main, side) = (parent_of(w(1)), parent_of(w(2)));
+        assert_eq!(root.children(system.map()).collect::<Vec<_>>(), vec![main, side]);
+        assert_eq!(system.layout(root), LayoutKind::Horizontal);
+        assert_eq!(system.layout(main), LayoutKind::Vertical);
+        assert_eq!(system.layout(side), LayoutKind::VerticalStack);
+        assert_eq!(system.tree.data.layout.info[main].size, 2.0);
+        assert_eq!(system.tree.data.layout.info[side].size, 1.0);
+        assert_eq!(parent_of(w(3)), side);
+        assert!(system.template_progress.get(layout).is_none());
+    }
+
+    #[test]
+    fn template_is_abandoned_when_a_slot_window_closes() {
+        use crate::common::config::TemplateSlot;
+
+        let mut system = TraditionalLayoutSystem::default();
+        system.set_template(LayoutTemplate {
+            orientation: Orientation::Horizontal,
+            slots: vec![TemplateSlot { ratio: 1.0, stacked: false }; 3],
+        });
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        system.remove_window(w(1));
+        system.add_window_after_selection(layout, w(3));
+
+        assert!(system.template_progress.get(layout).is_none());
+        assert_eq!(system.all_windows_in_layout(layout), vec![w(2), w(3)]);
+    }
+
     struct TestTraditionalLayoutSystem {
         system: TraditionalLayoutSystem,
         _root: OwnedNode,
@@ -3632,6 +4126,123 @@ mod tests {
         assert!((system.tree.data.layout.info[root].total - 10.0).abs() < 0.0001);
     }
 
+    #[test]
+    fn set_split_ratio_gives_the_selection_an_exact_share() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+        let nodes: Vec<_> = root.children(system.map()).collect();
+        system.tree.data.layout.info[nodes[1]].size = 2.0;
+        system.tree.data.layout.info[nodes[2]].size = 0.5;
+        system.tree.data.layout.info[nodes[0]].size = 0.5;
+        assert!(system.select_window(layout, w(1)));
+
+        assert!(system.set_selection_split_ratio(layout, 0.5));
+        let layout_info = &system.tree.data.layout;
+        let proportion = |node| layout_info.proportion(system.map(), node).unwrap();
+        assert!((proportion(nodes[0]) - 0.5).abs() < 0.0001);
+        assert!((proportion(nodes[1]) - 0.4).abs() < 0.0001);
+        assert!((proportion(nodes[2]) - 0.1).abs() < 0.0001);
+    }
+
+    #[test]
+    fn full_containers_overflow_into_a_nested_stack() {
+        let mut system = TraditionalLayoutSystem::default();
+        system.update_settings(&TraditionalSettings {
+            max_windows_per_container: 2,
+            container_overflow: ContainerOverflow::Stack,
+            ..Default::default()
+        });
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+
+        let map = system.map();
+        assert_eq!(root.children(map).count(), 2);
+        let overflow = system.node_for(layout, w(3)).unwrap().parent(map).unwrap();
+        assert_ne!(overflow, root);
+        assert_eq!(
+            system.node_for(layout, w(2)).unwrap().parent(map),
+            Some(overflow)
+        );
+        assert_eq!(system.layout(overflow), LayoutKind::HorizontalStack);
+    }
+
+    #[test]
+    fn spiral_and_largest_area_policies_split_a_single_tile() {
+        let mut system = TraditionalLayoutSystem::default();
+        system.update_settings(&TraditionalSettings {
+            insertion_policy: InsertionPolicy::Spiral,
+            ..Default::default()
+        });
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+
+        let map = system.map();
+        let node = |system: &TraditionalLayoutSystem, idx| system.node_for(layout, w(idx)).unwrap();
+        assert_eq!(root.children(map).count(), 2);
+        let nested = node(&system, 3).parent(map).unwrap();
+        assert_eq!(node(&system, 2).parent(map), Some(nested));
+        assert_eq!(system.layout(nested), LayoutKind::Vertical);
+
+        system.update_settings(&TraditionalSettings {
+            insertion_policy: InsertionPolicy::LargestArea,
+            ..Default::default()
+        });
+        system.add_window_after_selection(layout, w(4));
+        let map = system.map();
+        let split = node(&system, 4).parent(map).unwrap();
+        assert_eq!(node(&system, 1).parent(map), Some(split));
+        assert_eq!(system.layout(split), LayoutKind::Vertical);
+        assert_eq!(system.selected_window(layout), Some(w(4)));
+    }
+
+    #[test]
+    fn close_resize_shares_out_the_closed_window_as_configured() {
+        for (resize, expected) in [
+            (CloseResize::Keep, [0.8, 0.2]),
+            (CloseResize::Neighbor, [2.0 / 3.0, 1.0 / 3.0]),
+            (CloseResize::Proportional, [0.8, 0.2]),
+            (CloseResize::Equalize, [0.5, 0.5]),
+        ] {
+            let mut system = TraditionalLayoutSystem::default();
+            let layout = system.create_layout();
+            let root = system.root(layout);
+            system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+            for idx in 1..=3 {
+                system.add_window_after_selection(layout, w(idx));
+            }
+            let nodes: Vec<_> = root.children(system.map()).collect();
+            system.tree.data.layout.info[nodes[0]].size = 2.0;
+            system.tree.data.layout.info[nodes[1]].size = 0.5;
+            system.tree.data.layout.info[nodes[2]].size = 0.5;
+
+            system.remove_closed_window(w(3), resize);
+            let layout_info = &system.tree.data.layout;
+            let proportions: Vec<f64> = nodes[..2]
+                .iter()
+                .map(|&node| layout_info.proportion(system.map(), node).unwrap())
+                .collect();
+            for (proportion, expected) in proportions.iter().zip(expected) {
+                assert!(
+                    (proportion - expected).abs() < 0.0001,
+                    "{resize:?}: {proportions:?}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn rebalance_evenly_resets_skewed_sibling_sizes() {
         let mut system = TraditionalLayoutSystem::default();
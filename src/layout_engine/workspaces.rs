@@ -202,6 +202,10 @@ impl WorkspaceLayouts {
         }
     }
 
+    pub(crate) fn remove_space(&mut self, space: SpaceId) {
+        self.map.retain(|(s, _), _| *s != space);
+    }
+
     pub(crate) fn active(
         &self,
         space: SpaceId,
@@ -210,6 +214,20 @@ impl WorkspaceLayouts {
         self.map.get(&(space, workspace_id)).and_then(|l| l.active())
     }
 
+    /// The screen size the active layout of a workspace was created for.
+    pub(crate) fn active_size(
+        &self,
+        space: SpaceId,
+        workspace_id: crate::model::VirtualWorkspaceId,
+    ) -> Option<CGSize> {
+        self.map.get(&(space, workspace_id)).map(|info| {
+            CGSize::new(
+                f64::from(info.active_size.width),
+                f64::from(info.active_size.height),
+            )
+        })
+    }
+
     pub(crate) fn mark_last_saved(
         &mut self,
         space: SpaceId,
@@ -7,4 +7,5 @@ pub mod ipc;
 pub mod layout_engine;
 pub mod model;
 pub mod sys;
+pub mod testing;
 pub mod ui;
@@ -1,15 +1,21 @@
 pub mod app_rules;
 pub mod floating_position_store;
+pub mod focus_history;
 pub mod hidden_window_placement;
+pub mod matcher;
+pub mod rule_script;
 pub mod selection;
 pub mod server;
 pub mod tree;
 pub mod tx_store;
 pub mod virtual_workspace;
+pub mod window_heuristics;
 pub mod window_store;
 pub use app_rules::{AppRuleDecision, AppRuleEngine, WindowRuleContext};
 pub use floating_position_store::FloatingPositionStore;
+pub use focus_history::FocusHistory;
 pub use hidden_window_placement::{HiddenWindowPlacement, HideCorner};
+pub use matcher::{MatcherError, WindowMatcher};
 pub use virtual_workspace::{VirtualWorkspace, VirtualWorkspaceId, WorkspaceStore};
 pub use window_store::{
     PendingWindowOperation, WindowPlacement, WindowRecord, WindowStore, WindowVisibility,
@@ -1,7 +1,13 @@
+use std::sync::Arc;
+
 use regex::{Regex, RegexBuilder};
 use tracing::warn;
 
-use crate::common::config::{AppWorkspaceRule, WorkspaceSelector};
+use crate::common::config::{
+    AppWorkspaceRule, FloatingFrame, RuleScriptSettings, WorkspaceSelector,
+};
+use crate::model::matcher::WindowMatcher;
+use crate::model::rule_script::{RuleScript, ScriptFacts};
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct WindowRuleContext<'a> {
@@ -10,6 +16,13 @@ pub struct WindowRuleContext<'a> {
     pub window_title: Option<&'a str>,
     pub ax_role: Option<&'a str>,
     pub ax_subrole: Option<&'a str>,
+    pub app_bundle_path: Option<&'a str>,
+    /// Known for managed windows only, for `floating:`, `minimized:` and
+    /// `workspace:` matcher predicates.
+    pub is_floating: Option<bool>,
+    pub is_minimized: Option<bool>,
+    pub workspace: Option<&'a str>,
+    pub workspace_index: Option<usize>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,6 +39,7 @@ pub enum AppRuleDecision {
 struct CompiledRule {
     rule: AppWorkspaceRule,
     title_regex: Option<Regex>,
+    bundle_path_glob: Option<Regex>,
 }
 
 /// Compiles and evaluates app policy without depending on workspaces, windows,
@@ -33,6 +47,7 @@ struct CompiledRule {
 #[derive(Debug, Clone, Default)]
 pub struct AppRuleEngine {
     rules: Vec<CompiledRule>,
+    script: Option<Arc<RuleScript>>,
 }
 
 impl AppRuleEngine {
@@ -53,20 +68,46 @@ impl AppRuleEngine {
                             .ok()
                         },
                     );
-                CompiledRule { rule, title_regex }
+                let bundle_path_glob =
+                    rule.bundle_path.as_deref().filter(|value| !value.is_empty()).and_then(
+                        |value| {
+                            glob_to_regex(value)
+                                .map_err(|error| {
+                                    warn!(%error, pattern = value, "invalid bundle_path glob in app rule");
+                                })
+                                .ok()
+                        },
+                    );
+                CompiledRule { rule, title_regex, bundle_path_glob }
             })
             .collect();
-        Self { rules }
+        Self { rules, script: None }
+    }
+
+    /// Adds the rule script from `settings`. A script that fails to load is
+    /// reported and left out, so the declarative rules keep working.
+    pub fn with_script(mut self, settings: Option<&RuleScriptSettings>) -> Self {
+        self.script = settings.and_then(|settings| {
+            RuleScript::load(settings)
+                .map_err(|error| warn!(%error, "rule script disabled"))
+                .ok()
+                .map(Arc::new)
+        });
+        self
+    }
+
+    /// Decision of the rule script for a window seen for the first time, if
+    /// there is a script and it made one.
+    pub fn evaluate_script(
+        &self,
+        context: WindowRuleContext<'_>,
+        facts: impl FnOnce() -> ScriptFacts,
+    ) -> Option<AppRuleDecision> {
+        self.script.as_ref()?.on_window_created(context, facts())
     }
 
     pub fn evaluate(&self, context: WindowRuleContext<'_>) -> AppRuleDecision {
-        let best = self
-            .rules
-            .iter()
-            .enumerate()
-            .filter(|(_, rule)| rule.matches(context))
-            .max_by_key(|(index, rule)| (rule.specificity(), std::cmp::Reverse(*index)));
-        let Some((_, matched)) = best else {
+        let Some(matched) = self.best_match(context) else {
             return AppRuleDecision::NoMatch;
         };
         if !matched.rule.manage {
@@ -78,6 +119,29 @@ impl AppRuleEngine {
             }
         }
     }
+
+    /// Opacity of the winning rule. Unmanaged windows are left alone.
+    pub fn opacity(&self, context: WindowRuleContext<'_>) -> Option<f64> {
+        self.best_match(context)
+            .filter(|matched| matched.rule.manage)
+            .and_then(|matched| matched.rule.opacity)
+    }
+
+    /// Floating frame of the winning rule. Unmanaged windows are left alone.
+    pub fn floating_frame(&self, context: WindowRuleContext<'_>) -> Option<FloatingFrame> {
+        self.best_match(context)
+            .filter(|matched| matched.rule.manage)
+            .and_then(|matched| matched.rule.floating_frame)
+    }
+
+    fn best_match(&self, context: WindowRuleContext<'_>) -> Option<&CompiledRule> {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.matches(context))
+            .max_by_key(|(index, rule)| (rule.specificity(), std::cmp::Reverse(*index)))
+            .map(|(_, rule)| rule)
+    }
 }
 
 impl CompiledRule {
@@ -92,22 +156,50 @@ impl CompiledRule {
             && optional_contains(self.rule.title_substring.as_deref(), context.window_title)
             && optional_exact(self.rule.ax_role.as_deref(), context.ax_role)
             && optional_exact(self.rule.ax_subrole.as_deref(), context.ax_subrole)
+            && optional_regex(
+                self.rule.bundle_path.as_deref(),
+                self.bundle_path_glob.as_ref(),
+                context.app_bundle_path,
+            )
+            && self.rule.matcher.as_ref().is_none_or(|matcher| matcher.matches(context))
     }
 
     fn specificity(&self) -> usize {
-        [
+        let fields = [
             self.rule.app_id.as_deref(),
             self.rule.app_name.as_deref(),
             self.rule.title_regex.as_deref(),
             self.rule.title_substring.as_deref(),
             self.rule.ax_role.as_deref(),
             self.rule.ax_subrole.as_deref(),
+            self.rule.bundle_path.as_deref(),
         ]
         .into_iter()
         .flatten()
         .filter(|value| !value.is_empty())
-        .count()
+        .count();
+        fields + self.rule.matcher.as_ref().map_or(0, WindowMatcher::specificity)
+    }
+}
+
+/// Translate a path glob into an anchored regex: `**` matches across `/`,
+/// `*` and `?` match within one path component.
+fn glob_to_regex(glob: &str) -> Result<Regex, regex::Error> {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            _ => pattern.push_str(&regex::escape(ch.encode_utf8(&mut [0; 4]))),
+        }
     }
+    pattern.push('$');
+    Regex::new(&pattern)
 }
 
 fn optional_eq_ignore_case(rule: Option<&str>, actual: Option<&str>) -> bool {
@@ -153,6 +245,10 @@ mod tests {
             title_substring: None,
             ax_role: None,
             ax_subrole: None,
+            matcher: None,
+            bundle_path: None,
+            opacity: None,
+            floating_frame: None,
         };
         let engine = AppRuleEngine::new(&[rule]);
         assert_eq!(
@@ -167,4 +263,75 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn matcher_expression_narrows_rule() {
+        let rule = AppWorkspaceRule {
+            app_id: None,
+            workspace: Some(WorkspaceSelector::Index(2)),
+            floating: false,
+            manage: true,
+            app_name: None,
+            title_regex: None,
+            title_substring: None,
+            ax_role: None,
+            ax_subrole: None,
+            matcher: Some("app:Safari && title~\"github\"".parse().unwrap()),
+            bundle_path: None,
+            opacity: None,
+            floating_frame: None,
+        };
+        let engine = AppRuleEngine::new(&[rule]);
+        let context = |title: &'static str| WindowRuleContext {
+            app_bundle_id: Some("com.apple.Safari"),
+            app_name: Some("Safari"),
+            window_title: Some(title),
+            ..Default::default()
+        };
+        assert_eq!(
+            engine.evaluate(context("rift - GitHub")),
+            AppRuleDecision::Managed {
+                workspace: Some(WorkspaceSelector::Index(2)),
+                floating: false
+            }
+        );
+        assert_eq!(engine.evaluate(context("Apple")), AppRuleDecision::NoMatch);
+    }
+
+    #[test]
+    fn bundle_path_glob_selects_rule_and_its_opacity() {
+        let rule = AppWorkspaceRule {
+            app_id: None,
+            workspace: None,
+            floating: false,
+            manage: true,
+            app_name: None,
+            title_regex: None,
+            title_substring: None,
+            ax_role: None,
+            ax_subrole: None,
+            matcher: None,
+            bundle_path: Some("/Applications/*.app".into()),
+            opacity: Some(0.9),
+            floating_frame: None,
+        };
+        let engine = AppRuleEngine::new(&[rule]);
+        let context = |path: &'static str| WindowRuleContext {
+            app_bundle_path: Some(path),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            engine.opacity(context("/Applications/Visual Studio Code.app")),
+            Some(0.9)
+        );
+        assert_eq!(
+            engine.opacity(context("/Applications/Utilities/Terminal.app")),
+            None
+        );
+        assert_eq!(
+            engine.evaluate(context("/Applications/Utilities/Terminal.app")),
+            AppRuleDecision::NoMatch
+        );
+    }
 }
@@ -1,7 +1,10 @@
+use objc2_core_foundation::CGRect;
 use serde::{Deserialize, Serialize};
 
-use crate::actor::app::WindowId;
+use crate::actor::app::{WindowId, pid_t};
+use crate::common::config::LayoutMode;
 use crate::layout_engine::{LayoutKind, VirtualWorkspaceId};
+use crate::sys::geometry::CGRectDef;
 use crate::sys::screen::SpaceId;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -23,6 +26,14 @@ pub enum BroadcastEvent {
         workspace_name: String,
         display_uuid: Option<String>,
     },
+    WorkspaceRenamed {
+        workspace_id: VirtualWorkspaceId,
+        workspace_index: Option<u64>,
+        previous_name: String,
+        workspace_name: String,
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+    },
     WindowsChanged {
         workspace_id: VirtualWorkspaceId,
         workspace_name: String,
@@ -49,7 +60,129 @@ pub enum BroadcastEvent {
         space_id: SpaceId,
         display_uuid: Option<String>,
     },
+    WindowFocused {
+        window_id: WindowId,
+        title: String,
+        bundle_id: Option<String>,
+        workspace_id: VirtualWorkspaceId,
+        workspace_name: String,
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+    },
+    LayoutChanged {
+        workspace_id: VirtualWorkspaceId,
+        workspace_name: String,
+        layout_mode: LayoutMode,
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+    },
+    /// A window's origin changed and then stayed put for a short while.
+    WindowMoved {
+        window_id: WindowId,
+        #[serde(with = "CGRectDef")]
+        frame: CGRect,
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+    },
+    /// A window's size changed and then stayed put for a short while.
+    WindowResized {
+        window_id: WindowId,
+        #[serde(with = "CGRectDef")]
+        frame: CGRect,
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+    },
+    /// An app stopped answering accessibility requests; its windows are not
+    /// laid out until it recovers.
+    AppUnresponsive {
+        pid: pid_t,
+        bundle_id: Option<String>,
+    },
+    AppRecovered {
+        pid: pid_t,
+        bundle_id: Option<String>,
+    },
+    /// Stage Manager was turned on or off. While `cooperating`, rift leaves
+    /// tiled windows to Stage Manager as set by `settings.stage_manager`.
+    StageManagerChanged { enabled: bool, cooperating: bool },
+}
+
+impl BroadcastEvent {
+    /// Every event name a client may subscribe to, excluding the `*` wildcard.
+    pub const NAMES: &'static [&'static str] = &[
+        "workspace_changed",
+        "workspace_renamed",
+        "windows_changed",
+        "window_title_changed",
+        "stacks_changed",
+        "window_focused",
+        "layout_changed",
+        "window_moved",
+        "window_resized",
+        "app_unresponsive",
+        "app_recovered",
+        "stage_manager_changed",
+    ];
+
+    /// Subscription name of this event; matches the serialized `type` tag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BroadcastEvent::WorkspaceChanged { .. } => "workspace_changed",
+            BroadcastEvent::WorkspaceRenamed { .. } => "workspace_renamed",
+            BroadcastEvent::WindowsChanged { .. } => "windows_changed",
+            BroadcastEvent::WindowTitleChanged { .. } => "window_title_changed",
+            BroadcastEvent::StacksChanged { .. } => "stacks_changed",
+            BroadcastEvent::WindowFocused { .. } => "window_focused",
+            BroadcastEvent::LayoutChanged { .. } => "layout_changed",
+            BroadcastEvent::WindowMoved { .. } => "window_moved",
+            BroadcastEvent::WindowResized { .. } => "window_resized",
+            BroadcastEvent::AppUnresponsive { .. } => "app_unresponsive",
+            BroadcastEvent::AppRecovered { .. } => "app_recovered",
+            BroadcastEvent::StageManagerChanged { .. } => "stage_manager_changed",
+        }
+    }
+
+    /// Whether `name` is a known event name or the `*` wildcard.
+    pub fn is_subscribable(name: &str) -> bool { name == "*" || Self::NAMES.contains(&name) }
+
+    /// Names of the events that concern a single window, see [`Self::window_id`].
+    pub const WINDOW_EVENT_NAMES: &'static [&'static str] =
+        &["window_title_changed", "window_focused", "window_moved", "window_resized"];
+
+    /// The window this event is about, for events that concern a single window.
+    pub fn window_id(&self) -> Option<WindowId> {
+        match self {
+            BroadcastEvent::WindowTitleChanged { window_id, .. }
+            | BroadcastEvent::WindowFocused { window_id, .. }
+            | BroadcastEvent::WindowMoved { window_id, .. }
+            | BroadcastEvent::WindowResized { window_id, .. } => Some(*window_id),
+            _ => None,
+        }
+    }
 }
 
 pub type BroadcastSender = crate::actor::Sender<BroadcastEvent>;
 pub type BroadcastReceiver = crate::actor::Receiver<BroadcastEvent>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_matches_serialized_type_tag() {
+        let event = BroadcastEvent::LayoutChanged {
+            workspace_id: VirtualWorkspaceId::default(),
+            workspace_name: "main".into(),
+            layout_mode: LayoutMode::Bsp,
+            space_id: SpaceId::new(1),
+            display_uuid: None,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], event.name());
+        assert_eq!(json["layout_mode"], "bsp");
+        assert!(BroadcastEvent::is_subscribable("layout_changed"));
+        assert!(BroadcastEvent::is_subscribable("*"));
+        assert!(BroadcastEvent::is_subscribable("window_moved"));
+        assert!(!BroadcastEvent::is_subscribable("window_teleported"));
+    }
+}
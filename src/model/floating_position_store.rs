@@ -74,6 +74,10 @@ impl FloatingPositionStore {
         locations
     }
 
+    pub fn remove_space(&mut self, space: SpaceId) {
+        self.positions.retain(|(s, _, _), _| *s != space);
+    }
+
     pub fn remap_space(&mut self, old_space: SpaceId, new_space: SpaceId) {
         if old_space == new_space {
             return;
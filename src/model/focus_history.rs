@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use crate::actor::app::WindowId;
+use crate::common::collections::HashMap;
+use crate::sys::app::pid_t;
+use crate::sys::screen::SpaceId;
+
+const MAX_FOCUS_HISTORY: usize = 32;
+
+/// Recently focused windows per native space and across all displays, most
+/// recent first. This is runtime state only; it is rebuilt from focus events
+/// after a restart.
+#[derive(Debug, Default, Clone)]
+pub struct FocusHistory {
+    spaces: HashMap<SpaceId, VecDeque<WindowId>>,
+    global: VecDeque<(SpaceId, WindowId)>,
+}
+
+impl FocusHistory {
+    pub fn record(&mut self, space: SpaceId, window: WindowId) {
+        if self.global.front() != Some(&(space, window)) {
+            self.global.retain(|&(_, wid)| wid != window);
+            self.global.push_front((space, window));
+            self.global.truncate(MAX_FOCUS_HISTORY);
+        }
+        let history = self.spaces.entry(space).or_default();
+        if history.front() == Some(&window) {
+            return;
+        }
+        history.retain(|&wid| wid != window);
+        history.push_front(window);
+        history.truncate(MAX_FOCUS_HISTORY);
+    }
+
+    /// Windows focused on `space`, most recent first.
+    pub fn recent(&self, space: SpaceId) -> impl Iterator<Item = WindowId> + '_ {
+        self.spaces.get(&space).into_iter().flatten().copied()
+    }
+
+    /// Windows focused on any space, most recent first, with the space each
+    /// was last focused on.
+    pub fn recent_global(&self) -> impl Iterator<Item = (SpaceId, WindowId)> + '_ {
+        self.global.iter().copied()
+    }
+
+    pub fn forget_window(&mut self, window: WindowId) {
+        self.global.retain(|&(_, wid)| wid != window);
+        for history in self.spaces.values_mut() {
+            history.retain(|&wid| wid != window);
+        }
+    }
+
+    pub fn forget_app(&mut self, pid: pid_t) {
+        self.global.retain(|(_, wid)| wid.pid != pid);
+        for history in self.spaces.values_mut() {
+            history.retain(|wid| wid.pid != pid);
+        }
+    }
+
+    pub fn transfer_window_identity(&mut self, from: WindowId, to: WindowId) {
+        self.global.retain(|&(_, wid)| wid != to);
+        for (_, wid) in self.global.iter_mut().filter(|(_, wid)| *wid == from) {
+            *wid = to;
+        }
+        for history in self.spaces.values_mut() {
+            history.retain(|&wid| wid != to);
+            for wid in history.iter_mut().filter(|wid| **wid == from) {
+                *wid = to;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refocusing_moves_window_to_front_without_duplicates() {
+        let space = SpaceId::new(1);
+        let (a, b, c) = (WindowId::new(1, 1), WindowId::new(1, 2), WindowId::new(2, 1));
+        let mut history = FocusHistory::default();
+        history.record(space, a);
+        history.record(space, b);
+        history.record(space, c);
+        history.record(space, a);
+
+        assert_eq!(history.recent(space).collect::<Vec<_>>(), vec![a, c, b]);
+
+        history.forget_app(2);
+        assert_eq!(history.recent(space).collect::<Vec<_>>(), vec![a, b]);
+        assert_eq!(history.recent(SpaceId::new(2)).count(), 0);
+    }
+
+    #[test]
+    fn global_history_spans_spaces_in_focus_order() {
+        let (left, right) = (SpaceId::new(1), SpaceId::new(2));
+        let (editor, browser) = (WindowId::new(1, 1), WindowId::new(2, 1));
+        let mut history = FocusHistory::default();
+        history.record(left, editor);
+        history.record(right, browser);
+        history.record(left, editor);
+
+        assert_eq!(history.recent_global().collect::<Vec<_>>(), vec![
+            (left, editor),
+            (right, browser)
+        ]);
+        assert_eq!(history.recent(right).collect::<Vec<_>>(), vec![browser]);
+    }
+}
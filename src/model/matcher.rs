@@ -0,0 +1,594 @@
+//! Window matcher expressions.
+//!
+//! A matcher is a small boolean expression over window properties, e.g.
+//! `app:"Safari" && title~"GitHub"`. The same parser backs app rules, CLI
+//! window filters, and anything else that needs to select windows, so each
+//! feature does not grow its own set of ad hoc match fields.
+//!
+//! Grammar:
+//!
+//! ```text
+//! expr      := or
+//! or        := and ( "||" and )*
+//! and       := unary ( "&&" unary )*
+//! unary     := "!" unary | "(" expr ")" | predicate
+//! predicate := field ( ":" | "~" ) value
+//! field     := "app" | "bundle_id" | "title" | "role" | "subrole"
+//!            | "floating" | "minimized" | "workspace"
+//! value     := '"' quoted '"' | bare-word
+//! ```
+//!
+//! `:` compares case-insensitively for equality and `~` is a case-insensitive
+//! regular expression search. `app` matches either the bundle identifier or
+//! the localized application name.
+//!
+//! `floating` and `minimized` take `true` or `false`, and `workspace` matches
+//! a workspace name or its 0-based index. Those describe a managed window, so
+//! they only work in window queries; app rules reject them.
+//!
+//! Expressions may nest at most [`MAX_DEPTH`] levels, counting `!`,
+//! parentheses and each `&&`/`||`, since parsing, matching and dropping an
+//! expression all recurse once per level.
+
+use std::fmt;
+use std::str::FromStr;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::model::app_rules::WindowRuleContext;
+
+/// Deepest expression tree [`WindowMatcher::parse`] accepts.
+pub const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MatcherError {
+    #[error("unexpected end of matcher expression")]
+    UnexpectedEnd,
+    #[error("unexpected `{found}` at offset {offset}")]
+    Unexpected { found: String, offset: usize },
+    #[error("unknown matcher field `{0}`")]
+    UnknownField(String),
+    #[error("unterminated string starting at offset {0}")]
+    UnterminatedString(usize),
+    #[error("invalid regex `{pattern}`: {reason}")]
+    InvalidRegex { pattern: String, reason: String },
+    #[error("`{field}` expects true or false, not `{value}`")]
+    ExpectedBool { field: String, value: String },
+    #[error("matcher expression nests more than {} levels deep", MAX_DEPTH)]
+    TooDeep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    App,
+    BundleId,
+    Title,
+    Role,
+    Subrole,
+    Floating,
+    Minimized,
+    Workspace,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "app" => Some(Field::App),
+            "bundle_id" | "bundle" => Some(Field::BundleId),
+            "title" => Some(Field::Title),
+            "role" => Some(Field::Role),
+            "subrole" => Some(Field::Subrole),
+            "floating" => Some(Field::Floating),
+            "minimized" => Some(Field::Minimized),
+            "workspace" => Some(Field::Workspace),
+            _ => None,
+        }
+    }
+
+    fn is_bool(self) -> bool { matches!(self, Field::Floating | Field::Minimized) }
+
+    fn is_window_state(self) -> bool {
+        matches!(self, Field::Floating | Field::Minimized | Field::Workspace)
+    }
+
+    fn values<'a>(self, context: &WindowRuleContext<'a>) -> [Option<&'a str>; 2] {
+        match self {
+            Field::App => [context.app_bundle_id, context.app_name],
+            Field::BundleId => [context.app_bundle_id, None],
+            Field::Title => [context.window_title, None],
+            Field::Role => [context.ax_role, None],
+            Field::Subrole => [context.ax_subrole, None],
+            Field::Floating => [context.is_floating.map(bool_str), None],
+            Field::Minimized => [context.is_minimized.map(bool_str), None],
+            Field::Workspace => [context.workspace, None],
+        }
+    }
+
+    fn matches(self, context: &WindowRuleContext<'_>, predicate: &Predicate) -> bool {
+        if self == Field::Workspace
+            && let Some(index) = context.workspace_index
+            && predicate.matches(&index.to_string())
+        {
+            return true;
+        }
+        self.values(context)
+            .into_iter()
+            .flatten()
+            .any(|actual| predicate.matches(actual))
+    }
+}
+
+fn bool_str(value: bool) -> &'static str { if value { "true" } else { "false" } }
+
+#[derive(Debug, Clone)]
+enum Predicate {
+    Equals(String),
+    Regex(Regex),
+}
+
+impl Predicate {
+    fn matches(&self, actual: &str) -> bool {
+        match self {
+            Predicate::Equals(expected) => expected.eq_ignore_ascii_case(actual),
+            Predicate::Regex(regex) => regex.is_match(actual),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Match(Field, Predicate),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, context: &WindowRuleContext<'_>) -> bool {
+        match self {
+            Expr::Match(field, predicate) => field.matches(context, predicate),
+            Expr::Not(inner) => !inner.eval(context),
+            Expr::And(lhs, rhs) => lhs.eval(context) && rhs.eval(context),
+            Expr::Or(lhs, rhs) => lhs.eval(context) || rhs.eval(context),
+        }
+    }
+
+    fn predicate_count(&self) -> usize {
+        match self {
+            Expr::Match(..) => 1,
+            Expr::Not(inner) => inner.predicate_count(),
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.predicate_count() + rhs.predicate_count()
+            }
+        }
+    }
+
+    fn mentions(&self, wanted: fn(Field) -> bool) -> bool {
+        match self {
+            Expr::Match(field, _) => wanted(*field),
+            Expr::Not(inner) => inner.mentions(wanted),
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.mentions(wanted) || rhs.mentions(wanted)
+            }
+        }
+    }
+}
+
+/// A parsed matcher expression. Keeps its source text so it round-trips
+/// through config and IPC unchanged.
+#[derive(Debug, Clone)]
+pub struct WindowMatcher {
+    source: String,
+    expr: Expr,
+}
+
+impl WindowMatcher {
+    pub fn parse(source: &str) -> Result<Self, MatcherError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.parse_or()?;
+        if let Some((token, offset)) = parser.tokens.get(parser.pos) {
+            return Err(MatcherError::Unexpected {
+                found: token.to_string(),
+                offset: *offset,
+            });
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    pub fn matches(&self, context: WindowRuleContext<'_>) -> bool { self.expr.eval(&context) }
+
+    pub fn as_str(&self) -> &str { &self.source }
+
+    /// Number of field predicates in the expression, used to rank matcher
+    /// rules against each other the same way plain rule fields are ranked.
+    pub fn specificity(&self) -> usize { self.expr.predicate_count() }
+
+    /// Whether the expression selects by workspace, so windows outside the
+    /// active workspace have to be considered.
+    pub fn mentions_workspace(&self) -> bool {
+        self.expr.mentions(|field| field == Field::Workspace)
+    }
+
+    /// Whether the expression uses `floating`, `minimized` or `workspace`,
+    /// which are only known for windows that are already managed.
+    pub fn mentions_window_state(&self) -> bool { self.expr.mentions(Field::is_window_state) }
+
+    /// Quotes `value` as a matcher string literal.
+    pub fn quote(value: &str) -> String {
+        let mut quoted = String::with_capacity(value.len() + 2);
+        quoted.push('"');
+        for c in value.chars() {
+            if matches!(c, '"' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    }
+}
+
+impl PartialEq for WindowMatcher {
+    fn eq(&self, other: &Self) -> bool { self.source == other.source }
+}
+
+impl fmt::Display for WindowMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.source) }
+}
+
+impl FromStr for WindowMatcher {
+    type Err = MatcherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::parse(s) }
+}
+
+impl Serialize for WindowMatcher {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for WindowMatcher {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Self::parse(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Colon,
+    Tilde,
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => f.write_str(word),
+            Token::Str(value) => write!(f, "{value:?}"),
+            Token::Colon => f.write_str(":"),
+            Token::Tilde => f.write_str("~"),
+            Token::And => f.write_str("&&"),
+            Token::Or => f.write_str("||"),
+            Token::Not => f.write_str("!"),
+            Token::LParen => f.write_str("("),
+            Token::RParen => f.write_str(")"),
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool { !c.is_whitespace() && !"():~&|!\"".contains(c) }
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, MatcherError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(offset, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            ':' | '~' | '!' | '(' | ')' => {
+                chars.next();
+                let token = match c {
+                    ':' => Token::Colon,
+                    '~' => Token::Tilde,
+                    '!' => Token::Not,
+                    '(' => Token::LParen,
+                    _ => Token::RParen,
+                };
+                tokens.push((token, offset));
+            }
+            '&' | '|' => {
+                chars.next();
+                if chars.next_if(|&(_, next)| next == c).is_none() {
+                    return Err(MatcherError::Unexpected { found: c.to_string(), offset });
+                }
+                let token = if c == '&' { Token::And } else { Token::Or };
+                tokens.push((token, offset));
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped)) => value.push(escaped),
+                            None => return Err(MatcherError::UnterminatedString(offset)),
+                        },
+                        Some((_, other)) => value.push(other),
+                        None => return Err(MatcherError::UnterminatedString(offset)),
+                    }
+                }
+                tokens.push((Token::Str(value), offset));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some((_, next)) = chars.next_if(|&(_, next)| is_word_char(next)) {
+                    word.push(next);
+                }
+                tokens.push((Token::Word(word), offset));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [(Token, usize)],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Result<&'a (Token, usize), MatcherError> {
+        let token = self.tokens.get(self.pos).ok_or(MatcherError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, expected: &Token) -> bool {
+        if self.tokens.get(self.pos).is_some_and(|(token, _)| token == expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Go one level deeper into the expression tree. Callers restore
+    /// `depth` once the level is parsed.
+    fn descend(&mut self) -> Result<(), MatcherError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(MatcherError::TooDeep);
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, MatcherError> {
+        let depth = self.depth;
+        let mut expr = self.parse_and()?;
+        while self.eat(&Token::Or) {
+            self.descend()?;
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, MatcherError> {
+        let depth = self.depth;
+        let mut expr = self.parse_unary()?;
+        while self.eat(&Token::And) {
+            self.descend()?;
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, MatcherError> {
+        let depth = self.depth;
+        if self.eat(&Token::Not) {
+            self.descend()?;
+            let expr = Expr::Not(Box::new(self.parse_unary()?));
+            self.depth = depth;
+            return Ok(expr);
+        }
+        if self.eat(&Token::LParen) {
+            self.descend()?;
+            let expr = self.parse_or()?;
+            let (token, offset) = self.next()?;
+            if *token != Token::RParen {
+                return Err(MatcherError::Unexpected {
+                    found: token.to_string(),
+                    offset: *offset,
+                });
+            }
+            self.depth = depth;
+            return Ok(expr);
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> Result<Expr, MatcherError> {
+        let (token, offset) = self.next()?;
+        let Token::Word(name) = token else {
+            return Err(MatcherError::Unexpected {
+                found: token.to_string(),
+                offset: *offset,
+            });
+        };
+        let field = Field::parse(name).ok_or_else(|| MatcherError::UnknownField(name.clone()))?;
+
+        let (op, op_offset) = self.next()?;
+        let is_regex = match op {
+            Token::Colon => false,
+            Token::Tilde if !field.is_bool() => true,
+            other => {
+                return Err(MatcherError::Unexpected {
+                    found: other.to_string(),
+                    offset: *op_offset,
+                });
+            }
+        };
+
+        let (value, value_offset) = self.next()?;
+        let value = match value {
+            Token::Word(value) | Token::Str(value) => value.clone(),
+            other => {
+                return Err(MatcherError::Unexpected {
+                    found: other.to_string(),
+                    offset: *value_offset,
+                });
+            }
+        };
+
+        if field.is_bool() && !matches!(value.as_str(), "true" | "false") {
+            return Err(MatcherError::ExpectedBool { field: name.clone(), value });
+        }
+
+        let predicate = if is_regex {
+            let regex =
+                RegexBuilder::new(&value).case_insensitive(true).build().map_err(|error| {
+                    MatcherError::InvalidRegex {
+                        pattern: value.clone(),
+                        reason: error.to_string(),
+                    }
+                })?;
+            Predicate::Regex(regex)
+        } else {
+            Predicate::Equals(value)
+        };
+        Ok(Expr::Match(field, predicate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safari(title: &str) -> WindowRuleContext<'_> {
+        WindowRuleContext {
+            app_bundle_id: Some("com.apple.Safari"),
+            app_name: Some("Safari"),
+            window_title: Some(title),
+            ax_role: Some("AXWindow"),
+            ax_subrole: Some("AXStandardWindow"),
+            app_bundle_path: Some("/Applications/Safari.app"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn combines_predicates_with_boolean_operators() {
+        let matcher = WindowMatcher::parse(r#"app:"Safari" && title~"GitHub""#).unwrap();
+        assert!(matcher.matches(safari("acsandmann/rift - GitHub")));
+        assert!(!matcher.matches(safari("Apple")));
+
+        let matcher =
+            WindowMatcher::parse(r#"!(title~"^private") && (app:com.apple.safari || app:Firefox)"#)
+                .unwrap();
+        assert!(matcher.matches(safari("Docs")));
+        assert!(!matcher.matches(safari("Private Browsing")));
+        assert_eq!(matcher.specificity(), 3);
+    }
+
+    #[test]
+    fn matches_window_state_predicates() {
+        let managed = WindowRuleContext {
+            is_floating: Some(true),
+            is_minimized: Some(false),
+            workspace: Some("Code"),
+            workspace_index: Some(2),
+            ..safari("Docs")
+        };
+        let matcher = WindowMatcher::parse("floating:true && minimized:false").unwrap();
+        assert!(matcher.matches(managed));
+        assert!(!matcher.matches(safari("Docs")), "unknown state never matches");
+
+        let matcher = WindowMatcher::parse("workspace:code || workspace:0").unwrap();
+        assert!(matcher.mentions_workspace());
+        assert!(matcher.matches(managed));
+        assert!(WindowMatcher::parse("workspace:2").unwrap().matches(managed));
+        assert!(!WindowMatcher::parse("app:Safari").unwrap().mentions_workspace());
+        assert!(WindowMatcher::parse("!minimized:true").unwrap().mentions_window_state());
+        assert!(!WindowMatcher::parse("title~floating").unwrap().mentions_window_state());
+
+        assert_eq!(
+            WindowMatcher::parse("floating:yes").unwrap_err(),
+            MatcherError::ExpectedBool {
+                field: "floating".into(),
+                value: "yes".into()
+            }
+        );
+        assert!(WindowMatcher::parse("minimized~true").is_err());
+    }
+
+    #[test]
+    fn quoted_values_round_trip() {
+        let title = r#"say "hi" \ bye"#;
+        let matcher =
+            WindowMatcher::parse(&format!("title:{}", WindowMatcher::quote(title))).unwrap();
+        assert!(matcher.matches(safari(title)));
+    }
+
+    #[test]
+    fn reports_malformed_expressions() {
+        assert_eq!(
+            WindowMatcher::parse("color:red").unwrap_err(),
+            MatcherError::UnknownField("color".into())
+        );
+        assert_eq!(
+            WindowMatcher::parse(r#"title:"unterminated"#).unwrap_err(),
+            MatcherError::UnterminatedString(6)
+        );
+        assert_eq!(
+            WindowMatcher::parse("app:Safari &&").unwrap_err(),
+            MatcherError::UnexpectedEnd
+        );
+        assert!(matches!(
+            WindowMatcher::parse("title~\"(\"").unwrap_err(),
+            MatcherError::InvalidRegex { .. }
+        ));
+    }
+
+    #[test]
+    fn rejects_deeply_nested_expressions() {
+        let nested = |depth: usize| format!("{}app:Safari{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(WindowMatcher::parse(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(
+            WindowMatcher::parse(&nested(MAX_DEPTH + 1)).unwrap_err(),
+            MatcherError::TooDeep
+        );
+        assert_eq!(
+            WindowMatcher::parse(&format!("{}app:Safari", "!".repeat(100_000))).unwrap_err(),
+            MatcherError::TooDeep
+        );
+        assert_eq!(
+            WindowMatcher::parse(&"(".repeat(100_000)).unwrap_err(),
+            MatcherError::TooDeep
+        );
+        assert_eq!(
+            WindowMatcher::parse(&vec!["app:Safari"; 100_000].join(" || ")).unwrap_err(),
+            MatcherError::TooDeep
+        );
+    }
+}
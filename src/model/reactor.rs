@@ -1,7 +1,10 @@
-use objc2_core_foundation::CGRect;
+use std::str::FromStr;
+
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use serde::{Deserialize, Serialize};
 
 use crate::actor::app::{AppInfo, AppThreadHandle, WindowId, pid_t};
+use crate::common::collections::HashSet;
 use crate::common::log::MetricsCommand;
 use crate::layout_engine::{Direction, LayoutCommand, RestoreScope, RestoreSource};
 use crate::model::WindowStore;
@@ -15,9 +18,11 @@ use crate::sys::window_server::WindowServerId;
 /// migration, but window identity, native-space observations, and workspace
 /// assignments have one explicit owner here. Cross-store operations receive
 /// this store by reference instead of retaining an alias to it.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct RiftState {
     pub windows: WindowStore,
+    /// Floating windows kept above other windows by `toggle_always_on_top`.
+    pub always_on_top: HashSet<WindowId>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -36,11 +41,13 @@ pub enum Command {
 pub enum DisplaySelector {
     Direction(Direction),
     Index(usize),
+    /// A display UUID or localized name.
     Uuid(String),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, strum_macros::VariantNames)]
 #[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
 pub enum ReactorCommand {
     Debug,
     Serialize,
@@ -56,10 +63,33 @@ pub enum ReactorCommand {
     },
     SwitchSpace(Direction),
     ToggleSpaceActivated,
+    /// Launch (or activate) an app and insert its next window at the current
+    /// selection, honoring any preselection.
+    LaunchAppIntoSelection {
+        bundle_id: String,
+    },
+    /// Toggle management for every space on one display.
+    ToggleDisplayActivated(DisplaySelector),
+    /// Suspend (or resume) all window management, mouse handling and overlays
+    /// without discarding any layout state.
+    ToggleSuspended,
+    /// Move windows whose frames are outside every display into the active
+    /// workspace of the current space and lay everything out again.
+    RescueWindows,
     FocusWindow {
         window_id: WindowId,
         window_server_id: Option<WindowServerId>,
+        /// Switch to the window's virtual workspace first if it is hidden.
+        /// Set by the overview and mission control; plain focus requests
+        /// leave the current workspace alone.
+        #[serde(default)]
+        switch_workspace: bool,
     },
+    /// Focus the most recently focused window other than the current one on any
+    /// display, switching to its virtual workspace if needed.
+    FocusPreviousGlobal,
+    /// Toggle the overview of every workspace on the current space.
+    ToggleOverview,
     ShowMissionControlAll,
     ShowMissionControlCurrent,
     DismissMissionControl,
@@ -68,10 +98,125 @@ pub enum ReactorCommand {
     CloseWindow {
         window_server_id: Option<WindowServerId>,
     },
+    /// Minimize the focused window. Its tile is remembered so deminiaturizing
+    /// puts it back in the same place.
+    MinimizeSelection,
+    /// Deminiaturize the most recently minimized window.
+    RestoreLastMinimized,
+    /// Move the focused window into (or back out of) a macOS fullscreen space.
+    ToggleNativeFullscreen,
+    /// Keep the focused floating window above normal windows, or stop doing so.
+    ToggleAlwaysOnTop,
+    /// Center the focused floating window on its display, keeping its size.
+    CenterFloat,
+    /// Resize the focused floating window to a preset share of its display,
+    /// keeping it centered where it was.
+    ResizeFloat(FloatSize),
+    /// Label the other tiled windows of the focused window's workspace with
+    /// letter hints; typing one swaps the focused window with that window.
+    SwapWithHint,
+    /// Label every window showing on the active displays, floating and
+    /// unmanaged ones included; typing a hint raises and focuses that window.
+    FocusWithHint,
+    /// Act on the window showing hint `key`, as the hints were shown for.
+    PickHint(char),
+    /// Hide the hints without acting on any window.
+    CancelHints,
     MoveWindowToDisplay {
         selector: DisplaySelector,
         window_id: Option<u32>,
     },
+    /// Switch the virtual workspace shown on one display, leaving the other
+    /// displays' workspaces untouched.
+    SwitchWorkspaceOnDisplay {
+        workspace: usize,
+        selector: DisplaySelector,
+    },
+}
+
+/// Target size for `resize_float`, relative to the display the window is on.
+/// The presets follow the golden ratio: small is 1/φ², medium 1/φ and large
+/// 1/√φ of the display in each direction.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FloatSize {
+    Small,
+    Medium,
+    Large,
+    /// Width and height as percentages of the display.
+    Percent {
+        width: f64,
+        height: f64,
+    },
+}
+
+impl FloatSize {
+    /// Fractions of the display's width and height.
+    fn fractions(self) -> (f64, f64) {
+        const PHI: f64 = 1.618_033_988_749_895;
+        let preset = match self {
+            FloatSize::Small => 1.0 / (PHI * PHI),
+            FloatSize::Medium => 1.0 / PHI,
+            FloatSize::Large => 1.0 / PHI.sqrt(),
+            FloatSize::Percent { width, height } => {
+                return (width.clamp(1.0, 100.0) / 100.0, height.clamp(1.0, 100.0) / 100.0);
+            }
+        };
+        (preset, preset)
+    }
+}
+
+impl FromStr for FloatSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "small" => return Ok(FloatSize::Small),
+            "medium" => return Ok(FloatSize::Medium),
+            "large" => return Ok(FloatSize::Large),
+            _ => {}
+        }
+        let invalid = || format!("expected small, medium, large or WxH percentages, got `{s}`");
+        let (width, height) = s.trim().split_once('x').ok_or_else(invalid)?;
+        let percent = |value: &str| {
+            value
+                .trim()
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .ok()
+                .filter(|value| *value > 0.0 && *value <= 100.0)
+                .ok_or_else(invalid)
+        };
+        Ok(FloatSize::Percent {
+            width: percent(width)?,
+            height: percent(height)?,
+        })
+    }
+}
+
+/// Where `center_float` (no `size`) or `resize_float` puts a floating window
+/// currently at `current` on a display whose usable area is `area`. The
+/// result always fits inside `area`.
+pub fn floating_frame(area: CGRect, current: CGRect, size: Option<FloatSize>) -> CGRect {
+    let (size, center) = match size {
+        None => (current.size, area.mid()),
+        Some(size) => {
+            let (width, height) = size.fractions();
+            (
+                CGSize::new(area.size.width * width, area.size.height * height),
+                current.mid(),
+            )
+        }
+    };
+    let size = CGSize::new(
+        size.width.min(area.size.width),
+        size.height.min(area.size.height),
+    );
+    let x = (center.x - size.width / 2.0)
+        .clamp(area.origin.x, area.origin.x + area.size.width - size.width);
+    let y = (center.y - size.height / 2.0)
+        .clamp(area.origin.y, area.origin.y + area.size.height - size.height);
+    CGRect::new(CGPoint::new(x, y), size)
 }
 
 #[derive(Debug, Clone)]
@@ -102,6 +247,15 @@ pub enum MissionControlState {
     Transitioning,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageManagerState {
+    Inactive,
+    /// Stage Manager is on but `settings.stage_manager` is `ignore`.
+    Ignored,
+    /// Stage Manager is on and rift holds back from tiling.
+    Cooperating,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MenuState {
     Closed,
@@ -139,7 +293,7 @@ pub(crate) struct AppState {
     pub(crate) handle: AppThreadHandle,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct WindowState {
     pub(crate) info: WindowInfo,
     /// The last known frame of the window. Always includes the last write.
@@ -223,4 +377,24 @@ mod tests {
             source: RestoreSource::SavedActiveSpace,
         });
     }
+
+    #[test]
+    fn floating_frames_are_centered_and_kept_on_the_display() {
+        let area = CGRect::new(CGPoint::new(0.0, 25.0), CGSize::new(1000.0, 800.0));
+        let window = CGRect::new(CGPoint::new(700.0, 600.0), CGSize::new(400.0, 300.0));
+
+        let centered = floating_frame(area, window, None);
+        assert_eq!(centered.origin, CGPoint::new(300.0, 275.0));
+        assert_eq!(centered.size, window.size);
+
+        let medium = floating_frame(area, window, Some(FloatSize::Medium));
+        assert!((medium.size.width - 618.0).abs() < 0.1);
+        assert_eq!(medium.max().x, 1000.0);
+        assert_eq!(medium.max().y, 825.0);
+
+        let custom = floating_frame(area, window, Some("50x25".parse().unwrap()));
+        assert_eq!(custom.size, CGSize::new(500.0, 200.0));
+        assert!("huge".parse::<FloatSize>().is_err());
+        assert!("0x50".parse::<FloatSize>().is_err());
+    }
 }
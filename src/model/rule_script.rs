@@ -0,0 +1,253 @@
+//! Window placement decided by a user script.
+//!
+//! Some placements cannot be written as declarative app rules, e.g. "once
+//! three Chrome windows are open, send new ones to workspace 9". For those the
+//! config can point at a [Rhai](https://rhai.rs) script defining
+//!
+//! ```rhai
+//! fn on_window_created(window) {
+//!     if window.bundle_id == "com.google.Chrome" && window.app_window_count >= 3 {
+//!         return #{ workspace: 9 };
+//!     }
+//! }
+//! ```
+//!
+//! The function sees a map describing the new window and returns `()` to
+//! leave it to the app rules, or a map with any of `workspace` (index or
+//! name), `floating` and `manage`. Scripts are sandboxed: they cannot load
+//! modules or `eval`, their data structures are bounded, and a call that runs
+//! past its time budget is aborted and treated as no decision.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, Dynamic, Engine, Map, Scope};
+use tracing::{info, warn};
+
+use crate::common::config::{RuleScriptSettings, WorkspaceSelector};
+use crate::model::app_rules::{AppRuleDecision, WindowRuleContext};
+
+const ENTRY_POINT: &str = "on_window_created";
+
+/// What a script knows about a window besides what app rules match on.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScriptFacts {
+    /// Other windows of the same app already managed on the window's space.
+    pub app_window_count: usize,
+}
+
+pub struct RuleScript {
+    path: PathBuf,
+    engine: Engine,
+    ast: AST,
+    timeout: Duration,
+    deadline: Arc<Mutex<Instant>>,
+}
+
+impl fmt::Debug for RuleScript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuleScript")
+            .field("path", &self.path)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RuleScript {
+    pub fn load(settings: &RuleScriptSettings) -> anyhow::Result<Self> {
+        let path = settings.resolved_path();
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("cannot read rule script {}: {e}", path.display()))?;
+        Self::compile(&path, &source, Duration::from_millis(settings.timeout_ms))
+    }
+
+    fn compile(path: &Path, source: &str, timeout: Duration) -> anyhow::Result<Self> {
+        let deadline = Arc::new(Mutex::new(Instant::now()));
+        let engine = sandboxed_engine(deadline.clone());
+        let ast = engine
+            .compile(source)
+            .map_err(|e| anyhow::anyhow!("rule script {}: {e}", path.display()))?;
+        let has_entry_point =
+            ast.iter_functions().any(|f| f.name == ENTRY_POINT && f.params.len() == 1);
+        if !has_entry_point {
+            anyhow::bail!(
+                "rule script {} does not define `fn {ENTRY_POINT}(window)`",
+                path.display()
+            );
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            engine,
+            ast,
+            timeout,
+            deadline,
+        })
+    }
+
+    /// Runs the script for a newly seen window. `None` means the script made
+    /// no decision, failed, or ran out of time; failures are logged.
+    pub fn on_window_created(
+        &self,
+        context: WindowRuleContext<'_>,
+        facts: ScriptFacts,
+    ) -> Option<AppRuleDecision> {
+        let mut window = Map::new();
+        let text = |value: Option<&str>| {
+            value.map_or(Dynamic::UNIT, |value| Dynamic::from(value.to_string()))
+        };
+        window.insert("app_name".into(), text(context.app_name));
+        window.insert("bundle_id".into(), text(context.app_bundle_id));
+        window.insert("bundle_path".into(), text(context.app_bundle_path));
+        window.insert("title".into(), text(context.window_title));
+        window.insert("role".into(), text(context.ax_role));
+        window.insert("subrole".into(), text(context.ax_subrole));
+        window.insert(
+            "app_window_count".into(),
+            Dynamic::from(facts.app_window_count as rhai::INT),
+        );
+
+        *self.deadline.lock() = Instant::now() + self.timeout;
+        let result = self.engine.call_fn::<Dynamic>(
+            &mut Scope::new(),
+            &self.ast,
+            ENTRY_POINT,
+            (Dynamic::from_map(window),),
+        );
+        let script = self.path.display();
+        match result.map_err(|error| error.to_string()).and_then(parse_decision) {
+            Ok(decision) => decision,
+            Err(error) => {
+                warn!(%script, %error, "rule script failed");
+                None
+            }
+        }
+    }
+}
+
+fn sandboxed_engine(deadline: Arc<Mutex<Instant>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(64 * 1024)
+        .set_max_array_size(10_000)
+        .set_max_map_size(10_000)
+        .on_progress(move |_| (Instant::now() > *deadline.lock()).then_some(Dynamic::UNIT))
+        .on_print(|text| info!(target: "rift::rule_script", "{text}"))
+        .on_debug(|text, _, position| info!(target: "rift::rule_script", %position, "{text}"));
+    engine.disable_symbol("eval");
+    engine
+}
+
+fn parse_decision(value: Dynamic) -> Result<Option<AppRuleDecision>, String> {
+    if value.is_unit() {
+        return Ok(None);
+    }
+    let Some(map) = value.try_cast::<Map>() else {
+        return Err("expected () or a map".to_string());
+    };
+    let flag = |key: &str| match map.get(key) {
+        None => Ok(None),
+        Some(value) => value.as_bool().map(Some).map_err(|_| format!("`{key}` must be a bool")),
+    };
+    if flag("manage")? == Some(false) {
+        return Ok(Some(AppRuleDecision::Unmanaged));
+    }
+    let workspace = match map.get("workspace") {
+        None => None,
+        Some(value) if value.is_unit() => None,
+        Some(value) => match (value.as_int(), value.clone().into_string()) {
+            (Ok(index), _) => Some(WorkspaceSelector::Index(
+                usize::try_from(index).map_err(|_| "`workspace` must not be negative")?,
+            )),
+            (_, Ok(name)) => Some(WorkspaceSelector::Name(name)),
+            _ => return Err("`workspace` must be an index or a name".to_string()),
+        },
+    };
+    Ok(Some(AppRuleDecision::Managed {
+        workspace,
+        floating: flag("floating")?.unwrap_or(false),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(source: &str) -> RuleScript {
+        RuleScript::compile(Path::new("test.rhai"), source, Duration::from_millis(50)).unwrap()
+    }
+
+    fn chrome() -> WindowRuleContext<'static> {
+        WindowRuleContext {
+            app_bundle_id: Some("com.google.Chrome"),
+            app_name: Some("Google Chrome"),
+            window_title: Some("New Tab"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn script_decides_from_window_facts() {
+        let script = script(
+            r#"
+            fn on_window_created(window) {
+                if window.bundle_id == "com.google.Chrome" && window.app_window_count >= 3 {
+                    return #{ workspace: 9, floating: true };
+                }
+                if window.title == "Preferences" {
+                    return #{ manage: false };
+                }
+            }
+            "#,
+        );
+        assert_eq!(
+            script.on_window_created(chrome(), ScriptFacts { app_window_count: 2 }),
+            None
+        );
+        assert_eq!(
+            script.on_window_created(chrome(), ScriptFacts { app_window_count: 3 }),
+            Some(AppRuleDecision::Managed {
+                workspace: Some(WorkspaceSelector::Index(9)),
+                floating: true,
+            })
+        );
+        let preferences = WindowRuleContext {
+            window_title: Some("Preferences"),
+            ..Default::default()
+        };
+        assert_eq!(
+            script.on_window_created(preferences, ScriptFacts::default()),
+            Some(AppRuleDecision::Unmanaged)
+        );
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        let script = script("fn on_window_created(window) { loop {} }");
+        let started = Instant::now();
+        assert_eq!(script.on_window_created(chrome(), ScriptFacts::default()), None);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn scripts_cannot_escape_the_sandbox() {
+        let path = Path::new("test.rhai");
+        let timeout = Duration::from_millis(50);
+        assert!(
+            RuleScript::compile(path, "fn on_window_created(w) { eval(\"1\") }", timeout).is_err()
+        );
+        assert!(RuleScript::compile(path, "fn placement(w) {}", timeout).is_err());
+
+        let importing = script(r#"fn on_window_created(w) { import "os" as os; }"#);
+        assert_eq!(
+            importing.on_window_created(chrome(), ScriptFacts::default()),
+            None
+        );
+    }
+}
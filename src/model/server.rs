@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use crate::actor::app::{WindowId, pid_t};
+use crate::common::config::GapSettings;
+use crate::layout_engine::LayoutTreeNode;
+use crate::model::window_heuristics::IgnoreReason;
+use crate::model::{WindowMatcher, WindowRuleContext};
 use crate::sys::app::WindowInfo;
 use crate::sys::geometry::CGRectDef;
 use crate::sys::screen::{ScreenId, ScreenInfo, SpaceId};
@@ -18,6 +22,13 @@ pub struct WorkspaceData {
     pub is_active: bool,
     pub window_count: usize,
     pub windows: Vec<WindowData>,
+    /// UUID of the display showing this workspace's space, usable in
+    /// `per_display` config tables and display commands.
+    #[serde(default)]
+    pub display_uuid: Option<String>,
+    /// Localized name of that display, usable in the same places.
+    #[serde(default)]
+    pub display_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,10 +45,82 @@ pub struct WindowData {
     pub id: WindowId,
     pub is_floating: bool,
     pub is_focused: bool,
+    /// The window is in its own macOS fullscreen space, out of rift's layout.
+    pub is_native_fullscreen: bool,
+    /// The window covers the tiling area after `toggle_zoom`.
+    pub is_zoomed: bool,
+    /// The floating window is kept above others by `toggle_always_on_top`.
+    pub is_always_on_top: bool,
     pub app_name: Option<String>,
+    /// Name of the workspace holding the window, if it is on a known space.
+    pub workspace_name: Option<String>,
+    /// UUID and localized name of the display showing the window's space.
+    pub display_uuid: Option<String>,
+    pub display_name: Option<String>,
     pub info: WindowInfo,
 }
 
+impl WindowData {
+    pub fn rule_context(&self) -> WindowRuleContext<'_> {
+        WindowRuleContext {
+            app_bundle_id: self.info.bundle_id.as_deref(),
+            app_name: self.app_name.as_deref(),
+            window_title: Some(self.info.title.as_str()),
+            ax_role: self.info.ax_role.as_deref(),
+            ax_subrole: self.info.ax_subrole.as_deref(),
+            app_bundle_path: self.info.path.as_deref().and_then(|path| path.to_str()),
+            is_floating: Some(self.is_floating),
+            is_minimized: Some(self.info.is_minimized),
+            workspace: self.workspace_name.as_deref(),
+            workspace_index: None,
+        }
+    }
+}
+
+/// Filter and ordering for a window query, evaluated by the server. Without a
+/// filter every window of the active workspace matches.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowQuery {
+    pub filter: Option<WindowMatcher>,
+    pub sort: Option<WindowSort>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowSort {
+    /// By application name (or bundle id), then title.
+    App,
+    Title,
+    /// Left to right, then top to bottom.
+    Position,
+}
+
+impl WindowQuery {
+    pub fn matches(&self, context: WindowRuleContext<'_>) -> bool {
+        self.filter.as_ref().is_none_or(|filter| filter.matches(context))
+    }
+
+    pub fn sort(&self, windows: &mut [WindowData]) {
+        match self.sort {
+            None => {}
+            Some(WindowSort::App) => windows.sort_by_cached_key(|window| {
+                let app = window.app_name.as_deref().or(window.info.bundle_id.as_deref());
+                (
+                    app.unwrap_or_default().to_lowercase(),
+                    window.info.title.to_lowercase(),
+                )
+            }),
+            Some(WindowSort::Title) => {
+                windows.sort_by_cached_key(|window| window.info.title.to_lowercase())
+            }
+            Some(WindowSort::Position) => windows.sort_by(|a, b| {
+                let (a, b) = (a.info.frame.origin, b.info.frame.origin);
+                a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApplicationData {
     pub pid: pid_t,
@@ -56,6 +139,178 @@ pub struct LayoutStateData {
     pub focused_window: Option<WindowId>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutTreeData {
+    pub space_id: u64,
+    pub workspace_id: String,
+    pub workspace_name: String,
+    pub layout_mode: String,
+    pub root: LayoutTreeNode,
+}
+
+/// A window rift knows about but leaves alone, and why.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IgnoredWindowData {
+    pub id: WindowId,
+    pub app_name: Option<String>,
+    pub bundle_id: Option<String>,
+    pub title: String,
+    pub ax_subrole: Option<String>,
+    #[serde(with = "CGRectDef")]
+    pub frame: objc2_core_foundation::CGRect,
+    pub reason: IgnoreReason,
+}
+
+/// The reactor's part of `rift-cli doctor`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReactorHealthData {
+    pub managed_windows: usize,
+    /// Names of apps whose accessibility requests keep timing out.
+    pub unresponsive_apps: Vec<String>,
+    pub stage_manager_cooperating: bool,
+}
+
+/// One entry of the cross-display focus history, most recent first.
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusHistoryEntry {
+    pub space_id: u64,
+    pub workspace_id: Option<String>,
+    pub workspace_name: Option<String>,
+    pub window: WindowData,
+}
+
+/// An event the reactor handled, as kept for `rift-cli debug events`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventLogEntry {
+    pub sequence: u64,
+    /// When handling started, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub event: &'static str,
+    pub duration_us: u64,
+    /// The event's debug description, cut off when long.
+    pub detail: String,
+}
+
+/// What happened to a command sent over IPC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandResult {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<CommandErrorKind>,
+    pub message: String,
+    /// Windows the command named, plus the focused window before and after.
+    #[serde(default)]
+    pub window_ids: Vec<WindowId>,
+    /// Active workspace of the command's space once the command ran.
+    #[serde(default)]
+    pub workspace_id: Option<String>,
+    #[serde(default)]
+    pub space_id: Option<u64>,
+    /// Only set for dry runs: the frames the command would change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_changes: Option<Vec<FrameChange>>,
+}
+
+/// A window a dry-run command would move. `from` is empty for a window that
+/// was not laid out before, `to` for one the command stops laying out.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameChange {
+    pub window_id: WindowId,
+    pub space_id: u64,
+    #[serde_as(as = "Option<CGRectDef>")]
+    pub from: Option<objc2_core_foundation::CGRect>,
+    #[serde_as(as = "Option<CGRectDef>")]
+    pub to: Option<objc2_core_foundation::CGRect>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandErrorKind {
+    InvalidCommand,
+    WindowNotFound,
+    NoFocusedWindow,
+    SpaceNotManaged,
+    WorkspaceNotFound,
+    /// The command ran but its workflow reported an error.
+    Failed,
+    /// The reactor did not take the command, so nothing was applied.
+    Unavailable,
+    /// The reactor took the command but did not answer in time. It may still
+    /// be applied, so retrying can run it twice.
+    TimedOut,
+    /// The command was not applied because another command in its batch failed.
+    BatchAborted,
+}
+
+impl CommandResult {
+    pub fn success(message: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            error: None,
+            message: message.into(),
+            window_ids: Vec::new(),
+            workspace_id: None,
+            space_id: None,
+            frame_changes: None,
+        }
+    }
+
+    pub fn error(kind: CommandErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(kind),
+            ..Self::success(message)
+        }
+    }
+}
+
+/// The focused window, kept small for status bars that poll it.
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusedWindowData {
+    pub id: WindowId,
+    pub window_server_id: Option<u32>,
+    pub title: String,
+    pub app_name: Option<String>,
+    pub bundle_id: Option<String>,
+    pub is_floating: bool,
+    pub space_id: Option<u64>,
+    pub workspace_name: Option<String>,
+}
+
+/// The active workspace of one display, without its windows.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveWorkspaceData {
+    pub id: String,
+    pub index: usize,
+    pub name: String,
+    pub layout_mode: String,
+    pub window_count: usize,
+    pub space_id: u64,
+    pub display_uuid: Option<String>,
+    pub display_name: Option<String>,
+}
+
+/// Which displays Rift currently manages.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ActivationData {
+    /// Set by `toggle_suspended`; while suspended nothing is managed.
+    pub suspended: bool,
+    pub displays: Vec<DisplayActivationData>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DisplayActivationData {
+    pub display_uuid: String,
+    pub name: Option<String>,
+    /// The macOS space currently shown on the display.
+    pub space_id: Option<u64>,
+    /// True if that space is managed right now.
+    pub activated: bool,
+    /// The display-wide choice made with `toggle_display_activated`, if any.
+    pub display_activated: Option<bool>,
+}
+
 #[derive(Debug, Clone)]
 pub struct DisplayData {
     pub info: ScreenInfo,
@@ -67,6 +322,8 @@ pub struct DisplayData {
     pub active_space_ids: Vec<u64>,
     /// Inactive space ids for this display (empty if none).
     pub inactive_space_ids: Vec<u64>,
+    /// Gaps in effect on this display, after its `per_display` override.
+    pub gaps: GapSettings,
 }
 
 impl Serialize for WindowData {
@@ -81,9 +338,21 @@ impl Serialize for WindowData {
             frame: &'a objc2_core_foundation::CGRect,
             is_floating: bool,
             is_focused: bool,
+            #[serde(skip_serializing_if = "std::ops::Not::not")]
+            is_native_fullscreen: bool,
+            #[serde(skip_serializing_if = "std::ops::Not::not")]
+            is_zoomed: bool,
+            #[serde(skip_serializing_if = "std::ops::Not::not")]
+            is_always_on_top: bool,
             bundle_id: Option<&'a String>,
             app_name: Option<&'a String>,
             window_server_id: Option<u32>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            workspace_name: Option<&'a String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            display_uuid: Option<&'a String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            display_name: Option<&'a String>,
         }
 
         let helper = WindowDataSer {
@@ -92,9 +361,15 @@ impl Serialize for WindowData {
             frame: &self.info.frame,
             is_floating: self.is_floating,
             is_focused: self.is_focused,
+            is_native_fullscreen: self.is_native_fullscreen,
+            is_zoomed: self.is_zoomed,
+            is_always_on_top: self.is_always_on_top,
             bundle_id: self.info.bundle_id.as_ref(),
             app_name: self.app_name.as_ref(),
             window_server_id: self.info.sys_id.map(|id| id.as_u32()),
+            workspace_name: self.workspace_name.as_ref(),
+            display_uuid: self.display_uuid.as_ref(),
+            display_name: self.display_name.as_ref(),
         };
 
         helper.serialize(serializer)
@@ -113,9 +388,21 @@ impl<'de> Deserialize<'de> for WindowData {
             frame: objc2_core_foundation::CGRect,
             is_floating: bool,
             is_focused: bool,
+            #[serde(default)]
+            is_native_fullscreen: bool,
+            #[serde(default)]
+            is_zoomed: bool,
+            #[serde(default)]
+            is_always_on_top: bool,
             bundle_id: Option<String>,
             app_name: Option<String>,
             window_server_id: Option<u32>,
+            #[serde(default)]
+            workspace_name: Option<String>,
+            #[serde(default)]
+            display_uuid: Option<String>,
+            #[serde(default)]
+            display_name: Option<String>,
         }
 
         let helper = WindowDataDe::deserialize(deserializer)?;
@@ -139,7 +426,13 @@ impl<'de> Deserialize<'de> for WindowData {
             id: helper.id,
             is_floating: helper.is_floating,
             is_focused: helper.is_focused,
+            is_native_fullscreen: helper.is_native_fullscreen,
+            is_zoomed: helper.is_zoomed,
+            is_always_on_top: helper.is_always_on_top,
             app_name: helper.app_name,
+            workspace_name: helper.workspace_name,
+            display_uuid: helper.display_uuid,
+            display_name: helper.display_name,
             info,
         })
     }
@@ -161,6 +454,7 @@ impl Serialize for DisplayData {
             is_active_context: bool,
             active_space_ids: &'a [u64],
             inactive_space_ids: &'a [u64],
+            gaps: &'a GapSettings,
         }
 
         let helper = DisplayDataSer {
@@ -173,6 +467,7 @@ impl Serialize for DisplayData {
             is_active_context: self.is_active_context,
             active_space_ids: &self.active_space_ids,
             inactive_space_ids: &self.inactive_space_ids,
+            gaps: &self.gaps,
         };
 
         helper.serialize(serializer)
@@ -195,6 +490,8 @@ impl<'de> Deserialize<'de> for DisplayData {
             is_active_context: bool,
             active_space_ids: Vec<u64>,
             inactive_space_ids: Vec<u64>,
+            #[serde(default)]
+            gaps: GapSettings,
         }
 
         let helper = DisplayDataDe::deserialize(deserializer)?;
@@ -212,6 +509,7 @@ impl<'de> Deserialize<'de> for DisplayData {
             is_active_context: helper.is_active_context,
             active_space_ids: helper.active_space_ids,
             inactive_space_ids: helper.inactive_space_ids,
+            gaps: helper.gaps,
         })
     }
 }
@@ -244,7 +542,13 @@ mod tests {
             id: WindowId::new(123, 7),
             is_floating: true,
             is_focused: false,
+            is_native_fullscreen: false,
+            is_zoomed: false,
+            is_always_on_top: false,
             app_name: Some("Test App".to_string()),
+            workspace_name: None,
+            display_uuid: None,
+            display_name: None,
             info,
         };
 
@@ -262,6 +566,26 @@ mod tests {
         assert_eq!(value, expected);
     }
 
+    #[test]
+    fn native_fullscreen_windows_are_flagged() {
+        let legacy = json!({
+            "id": { "pid": 1, "idx": 2 },
+            "title": "",
+            "frame": { "origin": { "x": 0.0, "y": 0.0 }, "size": { "width": 1.0, "height": 1.0 } },
+            "is_floating": false,
+            "is_focused": true,
+            "bundle_id": null,
+            "app_name": null,
+            "window_server_id": null,
+        });
+        let mut data: WindowData = serde_json::from_value(legacy).expect("deserialize WindowData");
+        assert!(!data.is_native_fullscreen);
+
+        data.is_native_fullscreen = true;
+        let value = serde_json::to_value(&data).expect("serialize WindowData");
+        assert_eq!(value["is_native_fullscreen"], json!(true));
+    }
+
     #[test]
     fn display_data_serializes_with_legacy_shape() {
         let info = ScreenInfo {
@@ -277,6 +601,7 @@ mod tests {
             is_active_context: false,
             active_space_ids: vec![42],
             inactive_space_ids: vec![43, 44],
+            gaps: GapSettings::default(),
         };
 
         let value = serde_json::to_value(&data).expect("serialize DisplayData");
@@ -290,7 +615,75 @@ mod tests {
             "is_active_context": false,
             "active_space_ids": [42],
             "inactive_space_ids": [43, 44],
+            "gaps": {
+                "outer": { "top": 0.0, "left": 0.0, "bottom": 0.0, "right": 0.0 },
+                "inner": { "horizontal": 0.0, "vertical": 0.0 },
+                "per_display": {},
+                "smart_gaps": false,
+            },
         });
         assert_eq!(value, expected);
     }
+
+    fn window(idx: u32, bundle_id: &str, title: &str, x: f64, is_floating: bool) -> WindowData {
+        WindowData {
+            id: WindowId::new(1, idx),
+            is_floating,
+            is_focused: false,
+            is_native_fullscreen: false,
+            is_zoomed: false,
+            is_always_on_top: false,
+            app_name: None,
+            workspace_name: None,
+            display_uuid: None,
+            display_name: None,
+            info: WindowInfo {
+                is_standard: true,
+                is_root: true,
+                is_minimized: false,
+                is_resizable: true,
+                min_size: None,
+                max_size: None,
+                title: title.to_string(),
+                frame: CGRect::new(CGPoint::new(x, 0.0), CGSize::new(100.0, 100.0)),
+                sys_id: None,
+                bundle_id: Some(bundle_id.to_string()),
+                path: None,
+                ax_role: None,
+                ax_subrole: None,
+            },
+        }
+    }
+
+    #[test]
+    fn window_query_filters_and_sorts() {
+        let windows = vec![
+            window(1, "com.apple.Safari", "Inbox - Mail", 300.0, false),
+            window(2, "com.apple.Safari", "Docs", 100.0, true),
+            window(3, "com.apple.Terminal", "mail server", 200.0, false),
+        ];
+        let run = |filter: &str, sort: Option<WindowSort>| {
+            let query = WindowQuery {
+                filter: Some(WindowMatcher::parse(filter).unwrap()),
+                sort,
+            };
+            let mut matched: Vec<_> = windows
+                .iter()
+                .filter(|window| query.matches(window.rule_context()))
+                .cloned()
+                .collect();
+            query.sort(&mut matched);
+            matched.iter().map(|w| w.id.idx.get()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(run("app:COM.APPLE.SAFARI", Some(WindowSort::Position)), vec![
+            2, 1
+        ]);
+        assert_eq!(
+            run("title~mail && floating:false", Some(WindowSort::Title)),
+            vec![1, 3]
+        );
+        assert_eq!(run(r#"title~"^mail""#, None), vec![3]);
+        assert_eq!(run("minimized:false && floating:true", None), vec![2]);
+    }
 }
@@ -24,6 +24,11 @@ pub struct SpaceActivationPolicy {
     last_known_display_by_screen: HashMap<ScreenId, String>,
 
     pub login_window_active: bool,
+
+    /// Global kill switch. While set every space is inactive, but the per-space
+    /// and per-display activation state above is left untouched so resuming
+    /// restores it exactly.
+    suspended: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -50,11 +55,17 @@ impl SpaceActivationPolicy {
             last_known_space_by_screen: HashMap::default(),
             last_known_display_by_screen: HashMap::default(),
             login_window_active: false,
+            suspended: false,
         }
     }
 
     pub fn set_login_window_active(&mut self, active: bool) { self.login_window_active = active; }
 
+    pub fn is_suspended(&self) -> bool { self.suspended }
+
+    /// Note: like `toggle_space_activated`, Reactor must recompute active spaces afterwards.
+    pub fn set_suspended(&mut self, suspended: bool) { self.suspended = suspended; }
+
     #[allow(dead_code)]
     pub fn on_space_created(&mut self, space: SpaceId) { self.known_user_spaces.insert(space); }
 
@@ -132,14 +143,10 @@ impl SpaceActivationPolicy {
                 .or_else(|| self.last_known_display_by_screen.get(&screen.id).map(|v| v.as_str()));
             let Some(display_uuid) = display_uuid else { continue };
 
-            if cfg.default_disable {
-                if self.enabled_displays.contains(display_uuid) {
-                    self.enabled_spaces.insert(space);
-                }
-            } else {
-                if self.disabled_displays.contains(display_uuid) {
-                    self.disabled_spaces.insert(space);
-                }
+            // Deactivated displays are applied in `compute_active_spaces` so that
+            // reactivating the display does not leave its spaces behind.
+            if cfg.default_disable && self.enabled_displays.contains(display_uuid) {
+                self.enabled_spaces.insert(space);
             }
         }
 
@@ -157,11 +164,20 @@ impl SpaceActivationPolicy {
     /// This mutates the policy state only; Reactor is responsible for recomputing
     /// active spaces and performing any follow-up actions.
     pub fn toggle_space_activated(&mut self, cfg: SpaceActivationConfig, ctx: ToggleSpaceContext) {
-        let space_currently_enabled = if cfg.default_disable {
-            self.enabled_spaces.contains(&ctx.space)
-        } else {
-            !self.disabled_spaces.contains(&ctx.space)
-        };
+        let display_deactivated = ctx
+            .display_uuid
+            .as_ref()
+            .is_some_and(|uuid| self.disabled_displays.contains(uuid));
+        let space_currently_enabled = !display_deactivated
+            && if cfg.default_disable {
+                self.enabled_spaces.contains(&ctx.space)
+            } else {
+                !self.disabled_spaces.contains(&ctx.space)
+            };
+        if display_deactivated && let Some(uuid) = ctx.display_uuid.as_ref() {
+            // Activating a space on a deactivated display lifts the display-wide choice.
+            self.disabled_displays.remove(uuid);
+        }
 
         if space_currently_enabled {
             if cfg.default_disable {
@@ -182,6 +198,64 @@ impl SpaceActivationPolicy {
         }
     }
 
+    /// Toggle every space on a display at once, including spaces the display
+    /// switches to later. A deactivated display wins over per-space activation
+    /// until it is reactivated or one of its spaces is toggled back on.
+    ///
+    /// Like `toggle_space_activated`, Reactor must recompute active spaces afterwards.
+    pub fn toggle_display_activated(
+        &mut self,
+        cfg: SpaceActivationConfig,
+        display_uuid: &str,
+        space: Option<SpaceId>,
+    ) {
+        if self.is_display_activated(cfg, display_uuid, space) {
+            self.enabled_displays.remove(display_uuid);
+            self.disabled_displays.insert(display_uuid.to_string());
+            return;
+        }
+        self.disabled_displays.remove(display_uuid);
+        if let Some(space) = space {
+            self.disabled_spaces.remove(&space);
+        }
+        if cfg.default_disable {
+            self.enabled_displays.insert(display_uuid.to_string());
+            if let Some(space) = space {
+                self.enabled_spaces.insert(space);
+            }
+        }
+    }
+
+    /// Whether the display (showing `space`) is activated, ignoring suspension
+    /// and the login window.
+    pub fn is_display_activated(
+        &self,
+        cfg: SpaceActivationConfig,
+        display_uuid: &str,
+        space: Option<SpaceId>,
+    ) -> bool {
+        if self.disabled_displays.contains(display_uuid) {
+            return false;
+        }
+        if cfg.default_disable {
+            self.enabled_displays.contains(display_uuid)
+                || space.is_some_and(|space| self.enabled_spaces.contains(&space))
+        } else {
+            space.is_none_or(|space| !self.disabled_spaces.contains(&space))
+        }
+    }
+
+    /// The explicit display-wide activation choice, if one was made.
+    pub fn display_override(&self, display_uuid: &str) -> Option<bool> {
+        if self.disabled_displays.contains(display_uuid) {
+            Some(false)
+        } else if self.enabled_displays.contains(display_uuid) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
     pub fn compute_active_spaces(
         &self,
         cfg: SpaceActivationConfig,
@@ -192,18 +266,13 @@ impl SpaceActivationPolicy {
 
         for (idx, space_opt) in out.iter_mut().enumerate() {
             let display_uuid = cur_display_uuids.get(idx).and_then(|v| v.as_ref());
-            let (display_enabled, display_disabled) = if cfg.default_disable {
-                (
-                    display_uuid.map(|u| self.enabled_displays.contains(u)).unwrap_or(false),
-                    display_uuid.map(|u| self.disabled_displays.contains(u)).unwrap_or(false),
-                )
-            } else {
-                (false, false)
-            };
+            let display_enabled = cfg.default_disable
+                && display_uuid.is_some_and(|u| self.enabled_displays.contains(u));
+            let display_disabled = display_uuid.is_some_and(|u| self.disabled_displays.contains(u));
 
             // this is the core logic for deciding whats what
             let enabled = match *space_opt {
-                _ if self.login_window_active => false,
+                _ if self.login_window_active || self.suspended => false,
                 Some(space) if cfg.one_space && Some(space) != self.starting_space => false,
                 Some(space) if self.disabled_spaces.contains(&space) => false,
                 _ if display_disabled => false,
@@ -293,6 +362,44 @@ mod tests {
         assert!(!policy.enabled_displays.contains("display-a"));
     }
 
+    #[test]
+    fn toggle_display_activation_covers_every_space_on_the_display() {
+        for default_disable in [false, true] {
+            let mut policy = SpaceActivationPolicy::new();
+            let cfg = SpaceActivationConfig {
+                default_disable,
+                one_space: false,
+            };
+            let spaces = [
+                Some(SpaceId::new(1)),
+                Some(SpaceId::new(2)),
+                Some(SpaceId::new(3)),
+            ];
+            let displays = [
+                Some("display-a".to_string()),
+                Some("display-a".to_string()),
+                Some("display-b".to_string()),
+            ];
+            policy.on_spaces_updated(cfg, &[
+                input(1, Some(1), Some("display-a")),
+                input(2, Some(3), Some("display-b")),
+            ]);
+
+            let activated_before = !default_disable;
+            policy.toggle_display_activated(cfg, "display-a", Some(SpaceId::new(1)));
+            assert_eq!(policy.display_override("display-a"), Some(!activated_before));
+            let active = policy.compute_active_spaces(cfg, &spaces, &displays);
+            assert_eq!(active[0].is_some(), !activated_before);
+            assert_eq!(active[1].is_some(), !activated_before);
+            assert_eq!(active[2].is_some(), activated_before);
+
+            policy.toggle_display_activated(cfg, "display-a", Some(SpaceId::new(1)));
+            let active = policy.compute_active_spaces(cfg, &spaces, &displays);
+            assert_eq!(active[0].is_some(), activated_before);
+            assert_eq!(active[1].is_some(), activated_before);
+        }
+    }
+
     #[test]
     fn preserves_display_state_when_uuid_missing() {
         let mut policy = SpaceActivationPolicy::new();
@@ -453,6 +560,37 @@ mod tests {
         assert_eq!(active, vec![None]);
     }
 
+    #[test]
+    fn suspension_disables_all_spaces_and_resume_keeps_per_space_state() {
+        let mut policy = SpaceActivationPolicy::new();
+        let cfg = SpaceActivationConfig {
+            default_disable: false,
+            one_space: false,
+        };
+        let spaces = [Some(SpaceId::new(1)), Some(SpaceId::new(2))];
+        let displays = [Some("display-a".to_string()), Some("display-b".to_string())];
+
+        policy.on_spaces_updated(cfg, &[
+            input(1, Some(1), Some("display-a")),
+            input(2, Some(2), Some("display-b")),
+        ]);
+        policy.toggle_space_activated(cfg, ToggleSpaceContext {
+            space: SpaceId::new(2),
+            display_uuid: Some("display-b".to_string()),
+        });
+
+        policy.set_suspended(true);
+        assert_eq!(policy.compute_active_spaces(cfg, &spaces, &displays), vec![
+            None, None
+        ]);
+
+        policy.set_suspended(false);
+        assert_eq!(policy.compute_active_spaces(cfg, &spaces, &displays), vec![
+            Some(SpaceId::new(1)),
+            None
+        ]);
+    }
+
     #[test]
     fn disabled_space_persists_across_space_switches_default_enable() {
         let mut policy = SpaceActivationPolicy::new();
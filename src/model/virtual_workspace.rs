@@ -8,7 +8,8 @@ use crate::common::collections::{HashMap, HashSet};
 #[cfg(test)]
 use crate::common::config::AppWorkspaceRule;
 use crate::common::config::{
-    LayoutMode, LayoutSettings, MAX_WORKSPACES, VirtualWorkspaceSettings, WorkspaceSelector,
+    DisplayWorkspaceSettings, LayoutMode, LayoutSettings, LayoutTemplate, MAX_WORKSPACES,
+    VirtualWorkspaceSettings, WorkspaceSelector,
 };
 use crate::common::log::trace_misc;
 use crate::layout_engine::Direction;
@@ -17,7 +18,7 @@ use crate::model::app_rules::AppRuleDecision;
 use crate::model::hidden_window_placement::{HiddenWindowPlacement, HideCorner};
 use crate::model::{WindowStore, WindowWorkspaceInfo};
 use crate::sys::app::pid_t;
-use crate::sys::screen::SpaceId;
+use crate::sys::screen::{SpaceId, lookup_display};
 
 new_key_type! {
     pub struct VirtualWorkspaceId;
@@ -74,6 +75,9 @@ pub struct VirtualWorkspace {
     pub layout_system: LayoutSystemKind,
     #[serde(default)]
     pub layout_mode: LayoutMode,
+    /// Renamed at runtime; the configured name no longer applies.
+    #[serde(default)]
+    renamed: bool,
 }
 
 fn default_layout_system_kind() -> LayoutSystemKind {
@@ -81,14 +85,26 @@ fn default_layout_system_kind() -> LayoutSystemKind {
 }
 
 impl VirtualWorkspace {
-    fn new(name: String, space: SpaceId, mode: LayoutMode, settings: &LayoutSettings) -> Self {
-        let layout_system = Self::create_layout_system(mode, settings);
+    fn new(
+        name: String,
+        space: SpaceId,
+        mode: LayoutMode,
+        settings: &LayoutSettings,
+        template: Option<&LayoutTemplate>,
+    ) -> Self {
+        let mut layout_system = Self::create_layout_system(mode, settings);
+        if let (LayoutSystemKind::Traditional(system), Some(template)) =
+            (&mut layout_system, template)
+        {
+            system.set_template(template.clone());
+        }
         Self {
             name,
             space,
             last_focused: None,
             layout_system,
             layout_mode: mode,
+            renamed: false,
         }
     }
 
@@ -100,9 +116,11 @@ impl VirtualWorkspace {
 
     pub fn create_layout_system(mode: LayoutMode, settings: &LayoutSettings) -> LayoutSystemKind {
         match mode {
-            LayoutMode::Traditional => LayoutSystemKind::Traditional(
-                crate::layout_engine::systems::TraditionalLayoutSystem::default(),
-            ),
+            LayoutMode::Traditional => {
+                let mut system = crate::layout_engine::systems::TraditionalLayoutSystem::default();
+                system.update_settings(&settings.traditional);
+                LayoutSystemKind::Traditional(system)
+            }
             LayoutMode::Bsp => {
                 LayoutSystemKind::Bsp(crate::layout_engine::systems::BspLayoutSystem::default())
             }
@@ -143,6 +161,9 @@ pub struct WorkspaceStore {
     pub active_workspace_per_space:
         HashMap<SpaceId, (Option<VirtualWorkspaceId>, VirtualWorkspaceId)>,
     workspace_counter: usize,
+    /// Windows shown on every workspace of the space they are assigned to.
+    #[serde(default)]
+    sticky_windows: HashSet<WindowId>,
     #[cfg(test)]
     #[serde(skip)]
     test_app_rules: crate::model::AppRuleEngine,
@@ -155,6 +176,10 @@ pub struct WorkspaceStore {
     #[serde(skip)]
     default_workspace: usize,
     #[serde(skip)]
+    per_display: HashMap<String, DisplayWorkspaceSettings>,
+    #[serde(skip)]
+    space_displays: HashMap<SpaceId, SpaceDisplay>,
+    #[serde(skip)]
     pub workspace_auto_back_and_forth: bool,
     #[serde(skip)]
     prevent_wrapping: bool,
@@ -166,6 +191,14 @@ pub struct WorkspaceStore {
     pub layout_settings: LayoutSettings,
 }
 
+/// The display a space is on, by UUID and localized name, either of which
+/// can key its `per_display` settings.
+#[derive(Debug, Clone, PartialEq)]
+struct SpaceDisplay {
+    uuid: String,
+    name: Option<String>,
+}
+
 impl Default for WorkspaceStore {
     fn default() -> Self { Self::new() }
 }
@@ -187,12 +220,15 @@ impl WorkspaceStore {
             workspaces_by_space: HashMap::default(),
             active_workspace_per_space: HashMap::default(),
             workspace_counter: 1,
+            sticky_windows: HashSet::default(),
             #[cfg(test)]
             test_app_rules: crate::model::AppRuleEngine::new(&config.app_rules),
             max_workspaces: MAX_WORKSPACES,
             default_workspace_count: config.default_workspace_count,
             default_workspace_names: config.workspace_names.clone(),
             default_workspace,
+            per_display: config.per_display.clone(),
+            space_displays: HashMap::default(),
             workspace_auto_back_and_forth: config.workspace_auto_back_and_forth,
             prevent_wrapping: config.prevent_wrapping,
             workspace_rules: config.workspace_rules.clone(),
@@ -216,6 +252,7 @@ impl WorkspaceStore {
         self.layout_settings = layout_settings.clone();
         self.default_workspace_count = config.default_workspace_count;
         self.default_workspace_names = config.workspace_names.clone();
+        self.per_display = config.per_display.clone();
         self.workspace_auto_back_and_forth = config.workspace_auto_back_and_forth;
         self.prevent_wrapping = config.prevent_wrapping;
 
@@ -224,35 +261,102 @@ impl WorkspaceStore {
 
         let spaces: Vec<SpaceId> = self.workspaces_by_space.keys().copied().collect();
         for space in spaces {
-            if let Some(workspaces) = self.workspaces_by_space.get_mut(&space) {
-                workspaces.sort_unstable();
-            }
-            // Persisted workspace names are historical display metadata. Explicit names in the
-            // current config remain authoritative after startup restore and config reload.
-            if let Some(workspaces) = self.workspaces_by_space.get(&space) {
-                for (index, &workspace) in workspaces.iter().enumerate() {
-                    if let Some(name) = self.default_workspace_names.get(index)
-                        && let Some(workspace) = self.workspaces.get_mut(workspace)
-                    {
-                        workspace.name = name.clone();
-                    }
+            self.reconcile_space_workspaces(space);
+        }
+    }
+
+    /// Record which display a space belongs to so it picks up that display's
+    /// `per_display` workspace set.
+    pub fn set_space_display(
+        &mut self,
+        space: SpaceId,
+        display_uuid: Option<&str>,
+        display_name: Option<&str>,
+    ) {
+        let display = display_uuid.map(|uuid| SpaceDisplay {
+            uuid: uuid.to_string(),
+            name: display_name.map(str::to_string),
+        });
+        let previous = match display.clone() {
+            Some(display) => self.space_displays.insert(space, display),
+            None => self.space_displays.remove(&space),
+        };
+        if previous != display
+            && self.workspaces_by_space.contains_key(&space)
+            && [previous.as_ref(), display.as_ref()]
+                .into_iter()
+                .flatten()
+                .any(|display| self.settings_for_display(display).is_some())
+        {
+            self.reconcile_space_workspaces(space);
+        }
+    }
+
+    fn settings_for_display(&self, display: &SpaceDisplay) -> Option<&DisplayWorkspaceSettings> {
+        lookup_display(&self.per_display, &display.uuid, display.name.as_deref())
+    }
+
+    fn display_settings(&self, space: SpaceId) -> Option<&DisplayWorkspaceSettings> {
+        self.settings_for_display(self.space_displays.get(&space)?)
+    }
+
+    fn workspace_names_for_space(&self, space: SpaceId) -> &[String] {
+        self.display_settings(space)
+            .and_then(|display| display.workspace_names.as_deref())
+            .unwrap_or(&self.default_workspace_names)
+    }
+
+    fn workspace_count_for_space(&self, space: SpaceId) -> usize {
+        let count = match self.display_settings(space) {
+            Some(display) => display.workspace_count(self.default_workspace_count),
+            None => self.default_workspace_count,
+        };
+        count.max(1).min(self.max_workspaces)
+    }
+
+    fn default_workspace_for_space(&self, space: SpaceId) -> usize {
+        self.display_settings(space)
+            .and_then(|display| display.default_workspace)
+            .unwrap_or(self.default_workspace)
+    }
+
+    /// Apply configured names to an initialized space and create any missing
+    /// workspaces. Existing workspaces are never removed here.
+    fn reconcile_space_workspaces(&mut self, space: SpaceId) {
+        let target_count = self.workspace_count_for_space(space);
+        let names = self.workspace_names_for_space(space).to_vec();
+        if let Some(workspaces) = self.workspaces_by_space.get_mut(&space) {
+            workspaces.sort_unstable();
+        }
+        // Persisted workspace names are historical display metadata. Explicit names in the
+        // current config remain authoritative after startup restore and config reload, except
+        // for workspaces renamed at runtime.
+        if let Some(workspaces) = self.workspaces_by_space.get(&space) {
+            for (index, &workspace) in workspaces.iter().enumerate() {
+                if let Some(name) = names.get(index)
+                    && let Some(workspace) = self.workspaces.get_mut(workspace)
+                    && !workspace.renamed
+                {
+                    workspace.name = name.clone();
                 }
             }
-            while self.workspaces_by_space.get(&space).unwrap().len() < target_count {
-                let idx = self.workspaces_by_space.get(&space).unwrap().len();
-                let name = if let Some(n) = self.default_workspace_names.get(idx) {
-                    n.clone()
-                } else {
-                    let name = format!("Workspace {}", self.workspace_counter);
-                    self.workspace_counter += 1;
-                    name
-                };
+        }
+        while self.workspaces_by_space.get(&space).unwrap().len() < target_count {
+            let idx = self.workspaces_by_space.get(&space).unwrap().len();
+            let name = if let Some(n) = names.get(idx) {
+                n.clone()
+            } else {
+                let name = format!("Workspace {}", self.workspace_counter);
+                self.workspace_counter += 1;
+                name
+            };
 
-                let mode = self.resolve_layout_mode_for_workspace(idx, &name);
-                let ws = VirtualWorkspace::new(name, space, mode, &self.layout_settings);
-                let id = self.workspaces.insert(ws);
-                self.workspaces_by_space.get_mut(&space).unwrap().push(id);
-            }
+            let mode = self.resolve_layout_mode_for_workspace(idx, &name);
+            let template = self.resolve_template_for_workspace(idx, &name).cloned();
+            let ws =
+                VirtualWorkspace::new(name, space, mode, &self.layout_settings, template.as_ref());
+            let id = self.workspaces.insert(ws);
+            self.workspaces_by_space.get_mut(&space).unwrap().push(id);
         }
     }
 
@@ -262,22 +366,21 @@ impl WorkspaceStore {
         }
 
         let mut ids = Vec::new();
-        let count = self.default_workspace_count.max(1).min(self.max_workspaces);
+        let count = self.workspace_count_for_space(space);
+        let names = self.workspace_names_for_space(space).to_vec();
         for i in 0..count {
-            let name = self
-                .default_workspace_names
-                .get(i)
-                .cloned()
-                .unwrap_or_else(|| format!("Workspace {}", i + 1));
+            let name = names.get(i).cloned().unwrap_or_else(|| format!("Workspace {}", i + 1));
 
             let mode = self.resolve_layout_mode_for_workspace(i, &name);
-            let ws = VirtualWorkspace::new(name, space, mode, &self.layout_settings);
+            let template = self.resolve_template_for_workspace(i, &name).cloned();
+            let ws =
+                VirtualWorkspace::new(name, space, mode, &self.layout_settings, template.as_ref());
             let id = self.workspaces.insert(ws);
             ids.push(id);
         }
         self.workspaces_by_space.insert(space, ids.clone());
 
-        let default_idx = self.default_workspace.min(ids.len() - 1);
+        let default_idx = self.default_workspace_for_space(space).min(ids.len() - 1);
         if let Some(&default_id) = ids.get(default_idx) {
             self.active_workspace_per_space.insert(space, (None, default_id));
         }
@@ -296,6 +399,16 @@ impl WorkspaceStore {
         self.default_layout_mode
     }
 
+    /// Template from the last workspace rule matching this workspace, if that
+    /// rule defines one.
+    fn resolve_template_for_workspace(&self, index: usize, name: &str) -> Option<&LayoutTemplate> {
+        let rule = self.workspace_rules.iter().rev().find(|rule| match &rule.workspace {
+            WorkspaceSelector::Index(idx) => *idx == index,
+            WorkspaceSelector::Name(n) => n == name,
+        })?;
+        rule.template.as_ref()
+    }
+
     pub fn desired_layout_mode_for_workspace(&self, index: usize, name: &str) -> LayoutMode {
         self.resolve_layout_mode_for_workspace(index, name)
     }
@@ -395,6 +508,9 @@ impl WorkspaceStore {
             }
         }
         self.active_workspace_per_space.remove(&new_space);
+        if let Some(uuid) = self.space_displays.remove(&old_space) {
+            self.space_displays.insert(new_space, uuid);
+        }
 
         if !deleted_target_workspace_ids.is_empty() {
             let stale_windows: Vec<_> = window_store
@@ -427,6 +543,30 @@ impl WorkspaceStore {
         window_store.remap_space(old_space, new_space);
     }
 
+    /// Drop every workspace of a space that no longer exists, along with any
+    /// window assignments that pointed at them.
+    pub fn remove_space(&mut self, window_store: &mut WindowStore, space: SpaceId) {
+        let removed = self.workspaces_by_space.remove(&space).unwrap_or_default();
+        for &ws_id in &removed {
+            self.workspaces.remove(ws_id);
+        }
+        self.active_workspace_per_space.remove(&space);
+        self.space_displays.remove(&space);
+
+        if removed.is_empty() {
+            return;
+        }
+        let stale_windows: Vec<_> = window_store
+            .iter_workspace_assignments()
+            .filter_map(|(window_id, assignment)| {
+                removed.contains(&assignment.workspace_id).then_some(window_id)
+            })
+            .collect();
+        for window_id in stale_windows {
+            let _ = window_store.remove_window_assignment(window_id);
+        }
+    }
+
     pub fn create_workspace(
         &mut self,
         space: SpaceId,
@@ -457,8 +597,10 @@ impl WorkspaceStore {
             .map(|v: &Vec<VirtualWorkspaceId>| v.len())
             .unwrap_or(0);
         let mode = self.resolve_layout_mode_for_workspace(idx, &name);
+        let template = self.resolve_template_for_workspace(idx, &name).cloned();
 
-        let workspace = VirtualWorkspace::new(name, space, mode, &self.layout_settings);
+        let workspace =
+            VirtualWorkspace::new(name, space, mode, &self.layout_settings, template.as_ref());
         let workspace_id = self.workspaces.insert(workspace);
         self.workspaces_by_space.entry(space).or_default().push(workspace_id);
 
@@ -669,6 +811,7 @@ impl WorkspaceStore {
     pub fn remove_window(&mut self, window_store: &mut WindowStore, window_id: WindowId) {
         let _ = window_store.remove_window_assignment(window_id);
         window_store.clear_rule_metadata(window_id);
+        self.sticky_windows.remove(&window_id);
     }
 
     pub fn remove_windows_for_app(&mut self, window_store: &mut WindowStore, pid: pid_t) {
@@ -682,18 +825,48 @@ impl WorkspaceStore {
             let _ = window_store.remove_window_assignment(window_id);
             window_store.clear_rule_metadata(window_id);
         }
+        self.sticky_windows.retain(|wid| wid.pid != pid);
+    }
+
+    pub fn is_sticky(&self, window_id: WindowId) -> bool {
+        self.sticky_windows.contains(&window_id)
+    }
+
+    /// Marks or unmarks a window as sticky. A sticky window keeps its workspace
+    /// assignment, and is listed only there, but is shown on every workspace of
+    /// its space.
+    pub fn set_sticky(&mut self, window_id: WindowId, sticky: bool) {
+        if sticky {
+            self.sticky_windows.insert(window_id);
+        } else {
+            self.sticky_windows.remove(&window_id);
+        }
     }
 
-    /// Gets all windows in the active virtual workspace for a given native space.
+    /// Gets all windows shown in the active virtual workspace for a given
+    /// native space, including sticky windows assigned to other workspaces.
     pub fn windows_in_active_workspace(
         &self,
         window_store: &WindowStore,
         space: SpaceId,
     ) -> Vec<WindowId> {
-        if let Some(workspace_id) = self.active_workspace(space) {
-            return self.workspace_windows(window_store, space, workspace_id);
-        }
-        Vec::new()
+        let Some(workspace_id) = self.active_workspace(space) else {
+            return Vec::new();
+        };
+        let mut windows = self.workspace_windows(window_store, space, workspace_id);
+        let mut sticky: Vec<_> = self
+            .sticky_windows
+            .iter()
+            .copied()
+            .filter(|wid| {
+                window_store
+                    .workspace_info_for_window(*wid)
+                    .is_some_and(|info| info.space == space && info.workspace_id != workspace_id)
+            })
+            .collect();
+        sticky.sort();
+        windows.extend(sticky);
+        windows
     }
 
     pub fn is_window_in_active_workspace(
@@ -704,7 +877,7 @@ impl WorkspaceStore {
     ) -> bool {
         if let Some(active_workspace_id) = self.active_workspace(space) {
             if let Some(window_workspace_id) = window_store.workspace_for_window(space, window_id) {
-                return window_workspace_id == active_workspace_id;
+                return window_workspace_id == active_workspace_id || self.is_sticky(window_id);
             }
         }
         true
@@ -721,6 +894,7 @@ impl WorkspaceStore {
             .iter()
             .filter(|(id, workspace)| workspace.space == space && Some(*id) != active_workspace_id)
             .flat_map(|(id, _)| self.workspace_windows(window_store, space, id))
+            .filter(|wid| !self.is_sticky(*wid))
             .collect()
     }
 
@@ -838,6 +1012,9 @@ impl WorkspaceStore {
         if from == to {
             return;
         }
+        if self.sticky_windows.remove(&from) {
+            self.sticky_windows.insert(to);
+        }
         for workspace in self.workspaces.values_mut() {
             let contained_from = workspace.last_focused() == Some(from);
             if workspace.last_focused() == Some(to) {
@@ -850,6 +1027,7 @@ impl WorkspaceStore {
     }
 
     pub(crate) fn forget_window_identity(&mut self, window: WindowId) {
+        self.sticky_windows.remove(&window);
         for workspace in self.workspaces.values_mut() {
             if workspace.last_focused() == Some(window) {
                 workspace.set_last_focused(None);
@@ -902,6 +1080,8 @@ impl WorkspaceStore {
         ids
     }
 
+    /// Rename a workspace of `space`. Names must be non-empty and unique on the
+    /// space; the new name outlives config reloads and is saved with the layout.
     pub fn rename_workspace(
         &mut self,
         space: SpaceId,
@@ -911,8 +1091,19 @@ impl WorkspaceStore {
         if self.workspaces.get(workspace_id).map(|w| w.space) != Some(space) {
             return false;
         }
+        let new_name = new_name.trim().to_string();
+        if new_name.is_empty()
+            || WorkspaceSelector::is_index_like(&new_name)
+            || self
+                .existing_workspaces(space)
+                .iter()
+                .any(|(id, name)| *id != workspace_id && *name == new_name)
+        {
+            return false;
+        }
         if let Some(workspace) = self.workspaces.get_mut(workspace_id) {
             workspace.name = new_name;
+            workspace.renamed = true;
 
             true
         } else {
@@ -920,6 +1111,21 @@ impl WorkspaceStore {
         }
     }
 
+    /// Resolve a workspace of `space` by index or name.
+    pub fn find_workspace(
+        &self,
+        space: SpaceId,
+        selector: &WorkspaceSelector,
+    ) -> Option<VirtualWorkspaceId> {
+        let workspaces = self.existing_workspaces(space);
+        match selector {
+            WorkspaceSelector::Index(index) => workspaces.get(*index).map(|(id, _)| *id),
+            WorkspaceSelector::Name(name) => {
+                workspaces.iter().find_map(|(id, n)| (n == name).then_some(*id))
+            }
+        }
+    }
+
     pub fn workspace_windows(
         &self,
         window_store: &WindowStore,
@@ -1154,6 +1360,8 @@ impl WorkspaceStore {
             window_title,
             ax_role,
             ax_subrole,
+            app_bundle_path: None,
+            ..Default::default()
         });
         self.apply_app_rule_decision(window_store, window_id, space, decision)
     }
@@ -1335,6 +1543,40 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn sticky_windows_are_shown_in_every_workspace_but_listed_once() {
+        let mut window_store = WindowStore::default();
+        let mut manager = WorkspaceStore::new();
+        let space = SpaceId::new(1);
+        let other_space = SpaceId::new(2);
+        let ws1_id = manager.create_workspace(space, Some("WS1".to_string())).unwrap();
+        let ws2_id = manager.create_workspace(space, Some("WS2".to_string())).unwrap();
+        let other_ws = manager.create_workspace(other_space, Some("Other".to_string())).unwrap();
+        let sticky = WindowId::new(3, 1);
+        let regular = WindowId::new(3, 2);
+
+        assert!(manager.assign_window_to_workspace(&mut window_store, space, sticky, ws1_id));
+        assert!(manager.assign_window_to_workspace(&mut window_store, space, regular, ws1_id));
+        manager.set_active_workspace(space, ws2_id);
+        manager.set_sticky(sticky, true);
+
+        assert_eq!(manager.windows_in_active_workspace(&window_store, space), vec![sticky]);
+        assert!(manager.workspace_windows(&window_store, space, ws2_id).is_empty());
+        assert_eq!(manager.workspace_windows(&window_store, space, ws1_id), vec![
+            sticky, regular
+        ]);
+        assert!(manager.windows_in_active_workspace(&window_store, other_space).is_empty());
+        assert!(manager.workspace_windows(&window_store, other_space, other_ws).is_empty());
+        assert!(manager.is_window_in_active_workspace(&window_store, space, sticky));
+        assert_eq!(
+            manager.windows_in_inactive_workspaces(&window_store, space),
+            vec![regular]
+        );
+
+        manager.remove_window(&mut window_store, sticky);
+        assert!(!manager.is_sticky(sticky));
+    }
+
     #[test]
     fn reassignment_clears_stale_last_focused_on_source_workspace() {
         let mut window_store = WindowStore::default();
@@ -1399,6 +1641,40 @@ mod tests {
         assert!(manager.workspace_info(new_space, transient_ws).is_none());
     }
 
+    #[test]
+    fn remove_space_drops_its_workspaces_and_assignments() {
+        let mut window_store = WindowStore::default();
+        let mut manager = WorkspaceStore::new();
+        let gone_space = SpaceId::new(1);
+        let kept_space = SpaceId::new(2);
+        let gone_ws = manager.create_workspace(gone_space, Some("Gone".to_string())).unwrap();
+        let kept_ws = manager.create_workspace(kept_space, Some("Kept".to_string())).unwrap();
+        let gone_window = WindowId::new(10, 1);
+        let kept_window = WindowId::new(11, 1);
+        assert!(manager.assign_window_to_workspace(
+            &mut window_store,
+            gone_space,
+            gone_window,
+            gone_ws
+        ));
+        assert!(manager.assign_window_to_workspace(
+            &mut window_store,
+            kept_space,
+            kept_window,
+            kept_ws
+        ));
+
+        manager.remove_space(&mut window_store, gone_space);
+
+        assert!(manager.workspace_info(gone_space, gone_ws).is_none());
+        assert!(manager.existing_workspaces(gone_space).is_empty());
+        assert_eq!(manager.workspace_info_for_window_any(&window_store, gone_window), None);
+        assert_eq!(
+            manager.workspace_for_window(&window_store, kept_space, kept_window),
+            Some(kept_ws)
+        );
+    }
+
     #[test]
     fn preserves_workspace_ordinal_across_transient_space_id_churn() {
         let mut window_store = WindowStore::default();
@@ -1550,6 +1826,10 @@ mod tests {
             title_substring: None,
             ax_role: None,
             ax_subrole: None,
+            matcher: None,
+            bundle_path: None,
+            opacity: None,
+            floating_frame: None,
         }];
         let mut manager = WorkspaceStore::new_with_config(&settings, &LayoutSettings::default());
         let old_space = SpaceId::new(1);
@@ -1714,6 +1994,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn renamed_workspaces_keep_their_name_across_config_reloads() {
+        let settings = VirtualWorkspaceSettings {
+            default_workspace_count: 3,
+            workspace_names: vec!["A".into(), "B".into(), "C".into()],
+            ..VirtualWorkspaceSettings::default()
+        };
+        let mut manager = WorkspaceStore::new_with_config(&settings, &LayoutSettings::default());
+        let space = SpaceId::new(2);
+        let workspaces = manager.list_workspaces(space);
+        let (a, c) = (workspaces[0].0, workspaces[2].0);
+
+        assert!(!manager.rename_workspace(space, c, "A".into()));
+        assert!(!manager.rename_workspace(space, c, "  ".into()));
+        assert!(!manager.rename_workspace(space, c, "2".into()));
+        assert!(manager.rename_workspace(space, c, " mail ".into()));
+        assert_eq!(
+            manager.find_workspace(space, &WorkspaceSelector::Name("mail".into())),
+            Some(c)
+        );
+        assert_eq!(
+            manager.find_workspace(space, &WorkspaceSelector::Index(0)),
+            Some(a)
+        );
+
+        manager.update_settings(&settings, &LayoutSettings::default());
+        assert_eq!(
+            manager
+                .list_workspaces(space)
+                .iter()
+                .map(|(_, name)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["A", "B", "mail"],
+        );
+    }
+
     #[test]
     fn workspace_navigation_wraps_by_default() {
         let window_store = WindowStore::default();
@@ -1773,6 +2089,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn per_display_settings_give_each_display_its_own_workspace_set() {
+        let mut settings = VirtualWorkspaceSettings {
+            default_workspace_count: 3,
+            workspace_names: vec!["A".into(), "B".into(), "C".into()],
+            ..VirtualWorkspaceSettings::default()
+        };
+        settings.per_display.insert("side".into(), DisplayWorkspaceSettings {
+            workspace_names: Some(vec!["chat".into(), "mail".into()]),
+            default_workspace: Some(1),
+            ..DisplayWorkspaceSettings::default()
+        });
+        let mut manager = WorkspaceStore::new_with_config(&settings, &LayoutSettings::default());
+        let (main, side) = (SpaceId::new(1), SpaceId::new(2));
+        manager.set_space_display(main, Some("main"), None);
+        manager.set_space_display(side, Some("side"), None);
+
+        let names = |manager: &mut WorkspaceStore, space| {
+            manager
+                .list_workspaces(space)
+                .into_iter()
+                .map(|(_, name)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&mut manager, main), ["A", "B", "C"]);
+        assert_eq!(names(&mut manager, side), ["chat", "mail"]);
+        assert_eq!(manager.active_workspace_idx(side), Some(1));
+
+        let side_workspaces = manager.list_workspaces(side);
+        assert!(manager.set_active_workspace(side, side_workspaces[0].0));
+        assert_eq!(manager.active_workspace_idx(main), Some(0));
+        assert_eq!(manager.active_workspace_idx(side), Some(0));
+    }
+
     #[test]
     fn prevent_wrapping_updates_on_config_reload() {
         let window_store = WindowStore::default();
@@ -1826,6 +2176,10 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             // Match by app_name -> workspace 1
             AppWorkspaceRule {
@@ -1838,6 +2192,10 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             // Title substring -> workspace 0
             AppWorkspaceRule {
@@ -1850,6 +2208,10 @@ mod tests {
                 title_substring: Some("Preferences".into()),
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             // Title regex -> workspace 2
             AppWorkspaceRule {
@@ -1862,6 +2224,10 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             // AX role + subrole floating
             AppWorkspaceRule {
@@ -1874,6 +2240,10 @@ mod tests {
                 title_substring: None,
                 ax_role: Some("AXWindow".into()),
                 ax_subrole: Some("AXDialog".into()),
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             // Workspace by name
             AppWorkspaceRule {
@@ -1886,6 +2256,10 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             // Specificity tie breaking generic vs substring (generic workspace 0, specific workspace 2)
             AppWorkspaceRule {
@@ -1898,6 +2272,10 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             AppWorkspaceRule {
                 app_id: Some("com.example.tie".into()),
@@ -1909,6 +2287,10 @@ mod tests {
                 title_substring: Some("Editor".into()),
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             // Reapplication: Bitwarden title becomes floating
             AppWorkspaceRule {
@@ -1921,6 +2303,10 @@ mod tests {
                 title_substring: Some("Bitwarden".into()),
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             AppWorkspaceRule {
                 app_id: Some("app.zen-browser.zen".into()),
@@ -1932,6 +2318,10 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             // Workspace override when specific rule matches different workspace + floating
             AppWorkspaceRule {
@@ -1944,6 +2334,10 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
             AppWorkspaceRule {
                 app_id: Some("app.zen-browser.zen".into()),
@@ -1955,6 +2349,10 @@ mod tests {
                 title_substring: Some("bitwarden".into()),
                 ax_role: None,
                 ax_subrole: None,
+                matcher: None,
+                bundle_path: None,
+                opacity: None,
+                floating_frame: None,
             },
         ];
 
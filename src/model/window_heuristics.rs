@@ -0,0 +1,145 @@
+//! Built-in rules for windows that should never be tiled.
+//!
+//! Picture-in-picture players, inspectors and tool palettes usually look like
+//! ordinary windows to the accessibility API. Tiling them squeezes a tiny
+//! palette into a full column, so they are left alone unless their app is
+//! exempted in `settings.window_heuristics`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::config::WindowHeuristicsSettings;
+use crate::sys::app::WindowInfo;
+
+/// Bundle identifier of the agent that owns Safari's and Apple TV's
+/// picture-in-picture windows.
+const PIP_AGENT_BUNDLE_ID: &str = "com.apple.PIPAgent";
+/// AX subroles of floating inspectors and palettes.
+const PANEL_SUBROLES: &[&str] = &["AXFloatingWindow", "AXSystemFloatingWindow"];
+
+/// Why a window is not managed, as reported by `rift-cli query ignored`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IgnoreReason {
+    Minimized,
+    PictureInPicture,
+    UtilityPanel,
+    /// The window is not on the normal window layer, like the Dock or menus.
+    WindowLayer,
+    /// The window is shown on every space.
+    Sticky,
+    /// The window floats above normal windows on its own.
+    WindowLevel,
+    /// The window is not an AX standard window, e.g. a sheet or dialog.
+    NotStandard,
+    /// The window is a child of another window.
+    NotRoot,
+    /// An app rule in the config leaves the window unmanaged.
+    AppRule,
+}
+
+/// Returns why the heuristics keep `info` out of the layout, if they do.
+pub fn heuristic_ignore_reason(
+    info: &WindowInfo,
+    settings: &WindowHeuristicsSettings,
+) -> Option<IgnoreReason> {
+    if !settings.enabled {
+        return None;
+    }
+    if let Some(bundle_id) = info.bundle_id.as_deref()
+        && settings.exempt_apps.iter().any(|exempt| exempt == bundle_id)
+    {
+        return None;
+    }
+
+    if is_picture_in_picture(info) {
+        return Some(IgnoreReason::PictureInPicture);
+    }
+    let is_panel = info
+        .ax_subrole
+        .as_deref()
+        .is_some_and(|subrole| PANEL_SUBROLES.contains(&subrole));
+    let is_palette = !info.is_resizable
+        && (info.frame.size.width < settings.palette_max_width
+            || info.frame.size.height < settings.palette_max_height);
+    if is_panel || is_palette {
+        return Some(IgnoreReason::UtilityPanel);
+    }
+    None
+}
+
+fn is_picture_in_picture(info: &WindowInfo) -> bool {
+    if info.bundle_id.as_deref() == Some(PIP_AGENT_BUNDLE_ID) {
+        return true;
+    }
+    // Browsers title their players "Picture in Picture" or
+    // "Picture-in-Picture", in varying case.
+    info.title.to_lowercase().replace('-', " ") == "picture in picture"
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+
+    use super::*;
+
+    fn window(title: &str, subrole: &str, size: (f64, f64), resizable: bool) -> WindowInfo {
+        WindowInfo {
+            is_standard: subrole == "AXStandardWindow",
+            is_root: true,
+            is_minimized: false,
+            is_resizable: resizable,
+            title: title.to_string(),
+            frame: CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(size.0, size.1)),
+            min_size: None,
+            max_size: None,
+            sys_id: None,
+            bundle_id: Some("com.example.app".to_string()),
+            path: None,
+            ax_role: Some("AXWindow".to_string()),
+            ax_subrole: Some(subrole.to_string()),
+        }
+    }
+
+    #[test]
+    fn pip_players_and_palettes_are_ignored() {
+        let settings = WindowHeuristicsSettings::default();
+        let reason = |info: &WindowInfo| heuristic_ignore_reason(info, &settings);
+
+        assert_eq!(
+            reason(&window(
+                "Picture-in-Picture",
+                "AXStandardWindow",
+                (480.0, 270.0),
+                true
+            )),
+            Some(IgnoreReason::PictureInPicture)
+        );
+        assert_eq!(
+            reason(&window("Inspector", "AXFloatingWindow", (300.0, 600.0), true)),
+            Some(IgnoreReason::UtilityPanel)
+        );
+        assert_eq!(
+            reason(&window("Colors", "AXStandardWindow", (220.0, 400.0), false)),
+            Some(IgnoreReason::UtilityPanel)
+        );
+        assert_eq!(
+            reason(&window("Document", "AXStandardWindow", (220.0, 400.0), true)),
+            None
+        );
+    }
+
+    #[test]
+    fn exempt_apps_and_disabled_heuristics_ignore_nothing() {
+        let palette = window("Colors", "AXFloatingWindow", (220.0, 400.0), false);
+        let exempt = WindowHeuristicsSettings {
+            exempt_apps: vec!["com.example.app".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(heuristic_ignore_reason(&palette, &exempt), None);
+        let disabled = WindowHeuristicsSettings {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(heuristic_ignore_reason(&palette, &disabled), None);
+    }
+}
@@ -34,7 +34,7 @@ pub struct PendingWindowOperation {
 }
 
 /// The complete reactor-owned state for one AX window identity.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct WindowRecord {
     state: Option<WindowState>,
     window_server_id: Option<WindowServerId>,
@@ -119,7 +119,7 @@ struct PendingNativeFullscreenState {
     transition: NativeFullscreenTransition,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct WindowServerRecord {
     window_id: Option<WindowId>,
     visible: bool,
@@ -143,7 +143,7 @@ pub struct WindowWorkspaceInfo {
 /// assignment index here avoids the old class of bugs where a window could be
 /// present in multiple workspace-owned sets after sleep/wake or same-space
 /// workspace moves, which then leaked into queries and layout recovery.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct WindowStore {
     windows: HashMap<WindowId, WindowRecord>,
     app_windows: HashMap<i32, HashSet<WindowId>>,
@@ -237,7 +237,6 @@ impl WindowStore {
     }
 
     pub(crate) fn insert_window(&mut self, window_id: WindowId, window: WindowState) {
-        let wsid = window.info.sys_id;
         let retained_workspace = {
             let record = self.windows.entry(window_id).or_default();
             record.state = Some(window);
@@ -881,10 +880,10 @@ impl WindowStore {
     }
 
     pub fn remove_window(&mut self, window_id: WindowId) {
-        if let Some(record) = self.windows.remove(&window_id)
-            && let Some(assignment) = record.workspace
-        {
-            self.remove_window_from_workspace_index(window_id, assignment);
+        if let Some(record) = self.windows.remove(&window_id) {
+            if let Some(assignment) = record.workspace {
+                self.remove_window_from_workspace_index(window_id, assignment);
+            }
         }
         let server_ids: Vec<_> = self
             .window_servers
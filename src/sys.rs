@@ -8,6 +8,7 @@ pub mod axuielement;
 pub mod carbon;
 
 pub mod cgs_window;
+pub mod crash_restore;
 pub mod dispatch;
 pub mod display_churn;
 pub mod display_link;
@@ -27,6 +28,7 @@ pub mod screen;
 pub mod service;
 pub mod skylight;
 pub mod space_switch;
+pub mod stage_manager;
 pub mod timer;
 pub mod window_notify;
 pub mod window_server;
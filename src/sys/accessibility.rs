@@ -58,6 +58,8 @@ unsafe fn prompt_ax_trust_dialog() {
     });
 }
 
+pub fn has_accessibility_permission() -> bool { ax_is_trusted() }
+
 pub fn ensure_accessibility_permission() {
     if ax_is_trusted() {
         return;
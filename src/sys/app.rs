@@ -314,6 +314,19 @@ pub fn running_apps(bundle: Option<String>) -> impl Iterator<Item = (pid_t, AppI
         })
 }
 
+/// Bundle identifiers of all running apps, including background ones.
+pub fn running_bundle_ids() -> Vec<String> {
+    NSWorkspace::sharedWorkspace()
+        .runningApplications()
+        .into_iter()
+        .filter_map(|app| app.bundle_id().map(|bundle_id| bundle_id.to_string()))
+        .collect()
+}
+
+pub fn frontmost_app_pid() -> Option<pid_t> {
+    NSWorkspace::sharedWorkspace().frontmostApplication().map(|app| app.pid())
+}
+
 /// Check one bundle identity without producing a process inventory.
 ///
 /// Persistence uses this only to decide whether a stale saved window still has a running
@@ -323,6 +336,24 @@ pub fn is_bundle_running(bundle_id: &str) -> bool {
         .is_empty()
 }
 
+/// Launch the app with `bundle_id`, or bring it to the front if it is already
+/// running. Runs `open -b` off the calling thread; failures are only logged.
+pub fn launch_application(bundle_id: &str) {
+    let bundle_id = bundle_id.to_string();
+    std::thread::spawn(move || {
+        match std::process::Command::new("/usr/bin/open").arg("-b").arg(&bundle_id).output() {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => tracing::error!(
+                bundle_id,
+                status = %output.status,
+                stderr = %String::from_utf8_lossy(&output.stderr),
+                "Failed to launch application"
+            ),
+            Err(error) => tracing::error!(bundle_id, %error, "Failed to launch application"),
+        }
+    });
+}
+
 pub trait NSRunningApplicationExt {
     fn with_process_id(pid: pid_t) -> Option<Retained<Self>>;
     fn pid(&self) -> pid_t;
@@ -385,9 +416,10 @@ impl WindowInfo {
         element: &AXUIElement,
         server_info_hint: Option<WindowServerInfo>,
     ) -> Result<(Self, Option<WindowServerInfo>), AxError> {
-        let frame = element.frame()?;
-        let role = element.role()?;
-        let subrole = element.subrole()?;
+        let attributes = element.window_attributes()?;
+        let frame = attributes.frame?;
+        let role = attributes.role?;
+        let subrole = attributes.subrole?;
         let is_standard = role == AX_WINDOW_ROLE && subrole == AX_STANDARD_WINDOW_SUBROLE;
 
         let ax_role = Some(role.clone());
@@ -398,7 +430,7 @@ impl WindowInfo {
             .map(|info| info.id)
             .filter(|id| id.as_nonzero().is_some())
             .or_else(|| WindowServerId::try_from(element).ok());
-        let is_minimized = element.minimized().unwrap_or_default();
+        let is_minimized = attributes.minimized.unwrap_or_default();
         let is_resizable = element.can_resize().unwrap_or(true);
 
         let (bundle_id, path) = if !is_standard {
@@ -421,7 +453,7 @@ impl WindowInfo {
             is_resizable,
             min_size,
             max_size,
-            title: element.title().unwrap_or_default(),
+            title: attributes.title.unwrap_or_default(),
             frame,
             sys_id: id,
             bundle_id,
@@ -446,10 +478,12 @@ fn bundle_info_for_pid(pid: pid_t) -> (Option<String>, Option<PathBuf>) {
     NSRunningApplication::with_process_id(pid)
         .map(|app| {
             let bundle_id = app.bundle_id().as_deref().map(|b| b.to_string());
-            let path = app.bundleURL().as_ref().and_then(|url| {
-                let abs_str = url.absoluteString();
-                abs_str.as_deref().map(|s| PathBuf::from(s.to_string()))
-            });
+            // A filesystem path rather than a `file://` URL so app rules can glob it.
+            let path = app
+                .bundleURL()
+                .as_ref()
+                .and_then(|url| url.path())
+                .map(|path| PathBuf::from(path.to_string()));
             (bundle_id, path)
         })
         .unwrap_or((None, None))
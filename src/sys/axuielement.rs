@@ -4,12 +4,16 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 use std::ptr::{self, NonNull};
+use std::time::Duration;
 
-use objc2_application_services::{AXError, AXUIElement as RawAXUIElement, AXValue, AXValueType};
+use objc2_application_services::{
+    AXCopyMultipleAttributeOptions, AXError, AXUIElement as RawAXUIElement, AXValue, AXValueType,
+};
 use objc2_core_foundation::{
     CFArray, CFBoolean, CFData, CFRetained, CFString, CFType, CGPoint, CGRect, CGSize, ConcreteType,
 };
 
+use super::geometry::sanitize_rect;
 use super::skylight::{CGSGetWindowBounds, G_CONNECTION};
 use crate::actor::app::WindowId;
 use crate::sys::app::pid_t;
@@ -18,6 +22,21 @@ use crate::sys::skylight::_AXUIElementCreateWithRemoteToken;
 pub const AX_WINDOW_ROLE: &str = "AXWindow";
 pub const AX_STANDARD_WINDOW_SUBROLE: &str = "AXStandardWindow";
 
+/// Attributes read by `AXUIElement::window_attributes`, in request order.
+const WINDOW_ATTRIBUTES: [&str; 5] = ["AXTitle", "AXRole", "AXSubrole", "AXFrame", "AXMinimized"];
+
+/// Window attributes fetched in a single `AXUIElementCopyMultipleAttributeValues`
+/// round trip. Each field carries the same result the matching single-attribute
+/// getter (`title`, `role`, ...) would have returned.
+#[derive(Debug, Clone)]
+pub struct WindowAttributes {
+    pub title: Result<String>,
+    pub role: Result<String>,
+    pub subrole: Result<String>,
+    pub frame: Result<CGRect>,
+    pub minimized: Result<bool>,
+}
+
 #[derive(Clone)]
 pub struct AXUIElement {
     inner: CFRetained<RawAXUIElement>,
@@ -152,6 +171,58 @@ impl AXUIElement {
         self.copy_attribute(name)?.ok_or(Error::NotFound)
     }
 
+    /// Copies several attributes in one accessibility round trip. Per-attribute
+    /// failures are reported in place; a missing value is `Ok(None)`, matching
+    /// `copy_attribute`.
+    fn copy_multiple_attributes(
+        &self,
+        names: &[&'static str],
+    ) -> Result<Vec<Result<Option<CFRetained<CFType>>>>> {
+        let names: Vec<CFRetained<CFString>> =
+            names.iter().map(|&name| CFString::from_static_str(name)).collect();
+        let name_refs: Vec<&CFString> = names.iter().map(|name| &**name).collect();
+        let attributes = CFArray::from_objects(&name_refs);
+        let mut values: *const CFArray = ptr::null();
+        let status = unsafe {
+            self.inner.copy_multiple_attribute_values(
+                attributes.as_opaque(),
+                AXCopyMultipleAttributeOptions(0),
+                NonNull::new((&mut values) as *mut *const CFArray)
+                    .expect("pointer to local is never null"),
+            )
+        };
+        if status != AXError::Success {
+            return Err(Error::Ax(status));
+        }
+        let Some(values) = NonNull::new(values as *mut CFArray) else {
+            return Err(Error::NotFound);
+        };
+        // SAFETY: The function follows the Copy rule and returns an array the
+        // caller owns, with one entry per requested attribute.
+        let values = unsafe { CFRetained::from_raw(values) };
+        let values = unsafe { CFRetained::cast_unchecked::<CFArray<CFType>>(values) };
+        let mut out: Vec<_> = values
+            .iter()
+            .map(|value| {
+                let error = value.downcast_ref::<AXValue>().and_then(ax_error_from_value);
+                match error {
+                    Some(AXError::NoValue) => Ok(None),
+                    Some(err) => Err(Error::Ax(err)),
+                    None => Ok(Some(value)),
+                }
+            })
+            .collect();
+        out.resize_with(names.len(), || Err(Error::NotFound));
+        Ok(out)
+    }
+
+    fn required_value<T: ConcreteType>(
+        &self,
+        value: Result<Option<CFRetained<CFType>>>,
+    ) -> Result<CFRetained<T>> {
+        self.downcast::<T>(value?.ok_or(Error::NotFound)?)
+    }
+
     fn downcast<T: ConcreteType>(&self, value: CFRetained<CFType>) -> Result<CFRetained<T>> {
         value.downcast::<T>().map_err(|_| Error::Ax(AXError::Failure))
     }
@@ -186,7 +257,7 @@ impl AXUIElement {
         let mut frame = CGRect::default();
         let result = unsafe { CGSGetWindowBounds(*G_CONNECTION, wid.idx.get(), &mut frame) };
         if result == 0 {
-            Ok(frame)
+            sanitize_rect(frame, "CGSGetWindowBounds").ok_or(Error::Ax(AXError::Failure))
         } else {
             Err(Error::Ax(AXError(result)))
         }
@@ -206,6 +277,32 @@ impl AXUIElement {
 
     pub fn minimized(&self) -> Result<bool> { self.bool_attribute("AXMinimized") }
 
+    pub fn set_minimized(&self, minimized: bool) -> Result<()> {
+        self.set_bool_attribute("AXMinimized", minimized)
+    }
+
+    pub fn fullscreen(&self) -> Result<bool> { self.bool_attribute("AXFullScreen") }
+
+    pub fn set_fullscreen(&self, fullscreen: bool) -> Result<()> {
+        self.set_bool_attribute("AXFullScreen", fullscreen)
+    }
+
+    /// Title, role, subrole, frame and minimized state in one round trip
+    /// instead of five separate attribute reads.
+    pub fn window_attributes(&self) -> Result<WindowAttributes> {
+        let mut values = self.copy_multiple_attributes(&WINDOW_ATTRIBUTES)?.into_iter();
+        let mut next = || values.next().unwrap_or(Err(Error::NotFound));
+        Ok(WindowAttributes {
+            title: self.required_value::<CFString>(next()).map(|s| s.to_string()),
+            role: self.required_value::<CFString>(next()).map(|s| s.to_string()),
+            subrole: self.required_value::<CFString>(next()).map(|s| s.to_string()),
+            frame: self
+                .required_value::<AXValue>(next())
+                .and_then(|value| rect_from_axvalue(&value)),
+            minimized: self.required_value::<CFBoolean>(next()).map(|b| b.value()),
+        })
+    }
+
     pub fn fullscreen(&self) -> Result<bool> { self.bool_attribute("AXFullscreen") }
 
     pub fn title(&self) -> Result<String> {
@@ -290,6 +387,18 @@ impl AXUIElement {
         self.set_attribute_value(attr.as_ref(), cf_bool.as_ref())
     }
 
+    /// How long requests to this element wait for the app to reply before
+    /// failing with `CannotComplete`. Set on an application element, it applies
+    /// to every element of that app.
+    pub fn set_messaging_timeout(&self, timeout: Duration) -> Result<()> {
+        let status = unsafe { self.inner.set_messaging_timeout(timeout.as_secs_f32()) };
+        if status == AXError::Success {
+            Ok(())
+        } else {
+            Err(Error::Ax(status))
+        }
+    }
+
     pub fn can_move(&self) -> Result<bool> { self.is_settable("AXPosition") }
 
     pub fn can_resize(&self) -> Result<bool> { self.is_settable("AXSize") }
@@ -366,11 +475,23 @@ fn rect_from_axvalue(value: &AXValue) -> Result<CGRect> {
             NonNull::new((&mut rect as *mut CGRect).cast::<c_void>()).expect("rect pointer"),
         )
     };
-    if success {
-        Ok(rect)
-    } else {
-        Err(Error::Ax(AXError::Failure))
+    if !success {
+        return Err(Error::Ax(AXError::Failure));
     }
+    sanitize_rect(rect, "AXFrame").ok_or(Error::Ax(AXError::Failure))
+}
+
+/// Entries of a multiple-attribute copy that failed are AXValues wrapping the
+/// error instead of the attribute value.
+fn ax_error_from_value(value: &AXValue) -> Option<AXError> {
+    let mut error = AXError::Success;
+    let is_error = unsafe {
+        value.value(
+            AXValueType::AXError,
+            NonNull::new((&mut error as *mut AXError).cast::<c_void>()).expect("error pointer"),
+        )
+    };
+    is_error.then_some(error)
 }
 
 fn make_axvalue<T>(ty: AXValueType, value: &mut T) -> Result<CFRetained<AXValue>> {
@@ -0,0 +1,179 @@
+//! Frames windows had before rift first moved them, so the panic hook can put
+//! windows back before the process aborts. Without this a crash leaves every
+//! window on a hidden workspace parked off-screen.
+
+use std::collections::HashMap;
+use std::mem::MaybeUninit;
+use std::time::Duration;
+
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use objc2_core_graphics::{CGDirectDisplayID, CGDisplayBounds, CGError, CGGetActiveDisplayList};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use super::app::pid_t;
+use super::axuielement::AXUIElement;
+use super::geometry::{CGRectExt, sanitize_rect};
+use super::window_server::WindowServerId;
+
+/// How much of a window has to be on a screen, in each direction, for it to
+/// count as reachable. Hidden workspace windows keep a sliver visible.
+const MIN_VISIBLE: f64 = 40.0;
+
+/// Apps that stop answering must not keep a crashing rift alive for long.
+const RESTORE_AX_TIMEOUT: Duration = Duration::from_millis(250);
+
+static ORIGINAL_FRAMES: Lazy<Mutex<HashMap<pid_t, HashMap<WindowServerId, CGRect>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Records `frame` as the window's frame before rift managed it. Only the
+/// first frame seen for a window is kept.
+pub fn remember(pid: pid_t, wsid: WindowServerId, frame: CGRect) {
+    ORIGINAL_FRAMES.lock().entry(pid).or_default().entry(wsid).or_insert(frame);
+}
+
+pub fn forget(pid: pid_t, wsid: WindowServerId) {
+    let mut frames = ORIGINAL_FRAMES.lock();
+    if let Some(windows) = frames.get_mut(&pid) {
+        windows.remove(&wsid);
+        if windows.is_empty() {
+            frames.remove(&pid);
+        }
+    }
+}
+
+pub fn forget_app(pid: pid_t) { ORIGINAL_FRAMES.lock().remove(&pid); }
+
+#[cfg(test)]
+pub(crate) fn original_frame(pid: pid_t, wsid: WindowServerId) -> Option<CGRect> {
+    ORIGINAL_FRAMES.lock().get(&pid)?.get(&wsid).copied()
+}
+
+/// Moves the windows of every app rift has seen back to their recorded
+/// frames, and any other window of those apps that is off every screen onto
+/// the main display. Meant for the panic hook, so it never blocks on the
+/// registry and ignores every error.
+pub fn restore_windows() {
+    // The panicking thread may be the one holding the lock.
+    let Some(frames) = ORIGINAL_FRAMES.try_lock().map(|frames| frames.clone()) else {
+        return;
+    };
+    let displays = active_display_bounds();
+    if displays.is_empty() {
+        return;
+    }
+    for (pid, original) in frames {
+        let app = AXUIElement::application(pid);
+        _ = app.set_messaging_timeout(RESTORE_AX_TIMEOUT);
+        let Ok(windows) = app.windows() else { continue };
+        for window in windows {
+            let Ok(wsid) = WindowServerId::try_from(&window) else {
+                continue;
+            };
+            let Ok(current) = window.frame() else { continue };
+            let Some(frame) = restored_frame(original.get(&wsid).copied(), current, &displays)
+            else {
+                continue;
+            };
+            _ = window.set_position(frame.origin);
+            _ = window.set_size(frame.size);
+        }
+    }
+}
+
+/// Bounds of the active displays, main display first.
+pub fn active_display_bounds() -> Vec<CGRect> {
+    const MAX_SCREENS: usize = 64;
+    let mut ids: MaybeUninit<[CGDirectDisplayID; MAX_SCREENS]> = MaybeUninit::uninit();
+    let mut count: u32 = 0;
+    unsafe {
+        let err = CGGetActiveDisplayList(
+            MAX_SCREENS as u32,
+            ids.as_mut_ptr() as *mut CGDirectDisplayID,
+            &mut count,
+        );
+        if err != CGError::Success {
+            return Vec::new();
+        }
+        std::slice::from_raw_parts(ids.as_ptr() as *const CGDirectDisplayID, count as usize)
+            .iter()
+            .filter_map(|&id| sanitize_rect(CGDisplayBounds(id), "display bounds"))
+            .collect()
+    }
+}
+
+/// Whether enough of `frame` is on one of `displays` to be grabbed.
+pub fn is_on_screen(frame: CGRect, displays: &[CGRect]) -> bool {
+    displays.iter().any(|display| {
+        let visible = frame.intersection(display);
+        visible.size.width >= MIN_VISIBLE && visible.size.height >= MIN_VISIBLE
+    })
+}
+
+/// Where a window at `current` should go: its `original` frame if that is on
+/// a screen, otherwise the middle of the main display if it is stranded.
+fn restored_frame(
+    original: Option<CGRect>,
+    current: CGRect,
+    displays: &[CGRect],
+) -> Option<CGRect> {
+    if let Some(original) = original
+        && is_on_screen(original, displays)
+    {
+        return (original != current).then_some(original);
+    }
+    if is_on_screen(current, displays) {
+        return None;
+    }
+    let main = displays.first()?;
+    let size = CGSize::new(
+        current.size.width.min(main.size.width),
+        current.size.height.min(main.size.height),
+    );
+    Some(CGRect::new(
+        CGPoint::new(
+            main.origin.x + (main.size.width - size.width) / 2.0,
+            main.origin.y + (main.size.height - size.height) / 2.0,
+        ),
+        size,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> CGRect {
+        CGRect::new(CGPoint::new(x, y), CGSize::new(w, h))
+    }
+
+    #[test]
+    fn windows_go_back_to_their_original_frame() {
+        let displays = [rect(0., 0., 1440., 900.)];
+        let original = rect(100., 100., 800., 600.);
+        let hidden = rect(1435., 895., 800., 600.);
+
+        assert_eq!(restored_frame(Some(original), hidden, &displays), Some(original));
+        assert_eq!(restored_frame(Some(original), original, &displays), None);
+    }
+
+    #[test]
+    fn stranded_windows_are_centered_on_the_main_display() {
+        let displays = [rect(0., 0., 1440., 900.), rect(1440., 0., 1920., 1080.)];
+        let unplugged = rect(-3000., 0., 800., 600.);
+
+        assert_eq!(
+            restored_frame(Some(unplugged), unplugged, &displays),
+            Some(rect(320., 150., 800., 600.))
+        );
+        assert_eq!(
+            restored_frame(None, rect(1430., 0., 3000., 2000.), &displays),
+            None,
+            "windows partly on a second display are left alone"
+        );
+        assert_eq!(
+            restored_frame(None, rect(5000., 0., 3000., 2000.), &displays),
+            Some(rect(0., 0., 1440., 900.))
+        );
+    }
+}
@@ -3,6 +3,7 @@
 use objc2_core_foundation as ic;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_with::{DeserializeAs, SerializeAs};
+use tracing::warn;
 
 pub trait Round {
     fn round(&self) -> Self;
@@ -108,6 +109,52 @@ impl CGRectExt for ic::CGRect {
     fn area(&self) -> f64 { self.size.width * self.size.height }
 }
 
+/// Checks a rect read from the window server, AX or the display APIs before
+/// layout and animation math sees it, since clamping or interpolating
+/// garbage either panics or spreads it to every window.
+///
+/// NaN or infinite components cannot be repaired and the rect is rejected. A
+/// negative size is repaired by moving the origin to the opposite corner, as
+/// `CGRectStandardize` does. Either case is logged as a warning naming
+/// `source`.
+pub fn sanitize_rect(rect: ic::CGRect, source: &'static str) -> Option<ic::CGRect> {
+    let components = [
+        rect.origin.x,
+        rect.origin.y,
+        rect.size.width,
+        rect.size.height,
+    ];
+    if !components.iter().all(|component| component.is_finite()) {
+        warn!(source, ?rect, "Ignoring a frame with NaN or infinite components");
+        return None;
+    }
+    if rect.size.width >= 0.0 && rect.size.height >= 0.0 {
+        return Some(rect);
+    }
+    let repaired = standardize(rect);
+    warn!(source, ?rect, ?repaired, "Repaired a frame with a negative size");
+    Some(repaired)
+}
+
+/// [`sanitize_rect`] for a size on its own, such as a window's size
+/// constraints.
+pub fn sanitize_size(size: ic::CGSize, source: &'static str) -> Option<ic::CGSize> {
+    sanitize_rect(ic::CGRect::new(ic::CGPoint::ZERO, size), source).map(|rect| rect.size)
+}
+
+fn standardize(rect: ic::CGRect) -> ic::CGRect {
+    let axis = |origin: f64, length: f64| {
+        if length < 0.0 {
+            (origin + length, -length)
+        } else {
+            (origin, length)
+        }
+    };
+    let (x, width) = axis(rect.origin.x, rect.size.width);
+    let (y, height) = axis(rect.origin.y, rect.size.height);
+    ic::CGRect::new(ic::CGPoint::new(x, y), ic::CGSize::new(width, height))
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(remote = "ic::CGRect")]
 pub struct CGRectDef {
@@ -144,3 +191,48 @@ impl<'de> DeserializeAs<'de, ic::CGRect> for CGRectDef {
         CGRectDef::deserialize(deserializer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> ic::CGRect {
+        ic::CGRect::new(ic::CGPoint::new(x, y), ic::CGSize::new(width, height))
+    }
+
+    #[test]
+    fn sanitize_rect_passes_valid_rects_through() {
+        let frame = rect(-100., 25., 800., 0.);
+        assert_eq!(sanitize_rect(frame, "test"), Some(frame));
+    }
+
+    #[test]
+    fn sanitize_rect_rejects_non_finite_rects() {
+        assert_eq!(sanitize_rect(rect(f64::NAN, 0., 800., 600.), "test"), None);
+        assert_eq!(sanitize_rect(rect(0., 0., f64::INFINITY, 600.), "test"), None);
+        assert_eq!(
+            sanitize_rect(rect(0., 0., 800., f64::NEG_INFINITY), "test"),
+            None
+        );
+    }
+
+    #[test]
+    fn sanitize_rect_standardizes_negative_sizes() {
+        assert_eq!(
+            sanitize_rect(rect(900., 700., -800., -600.), "test"),
+            Some(rect(100., 100., 800., 600.))
+        );
+        assert_eq!(
+            sanitize_rect(rect(0., 700., 800., -600.), "test"),
+            Some(rect(0., 100., 800., 600.))
+        );
+    }
+
+    #[test]
+    fn sanitize_size_goes_through_sanitize_rect() {
+        let size = |width, height| ic::CGSize::new(width, height);
+        assert_eq!(sanitize_size(size(800., 600.), "test"), Some(size(800., 600.)));
+        assert_eq!(sanitize_size(size(-800., 600.), "test"), Some(size(800., 600.)));
+        assert_eq!(sanitize_size(size(f64::NAN, 600.), "test"), None);
+    }
+}
@@ -658,6 +658,13 @@ impl HotkeySpec {
             }
         }
     }
+
+    pub fn modifiers(&self) -> Modifiers {
+        match self {
+            HotkeySpec::Hotkey(h) => h.modifiers,
+            HotkeySpec::ModifiersOnly { modifiers } => *modifiers,
+        }
+    }
 }
 
 impl From<HotkeySpec> for Hotkey {
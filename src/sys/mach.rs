@@ -210,6 +210,39 @@ const _: [(); 92] = [(); size_of::<create_connection_server_port_message_t>()];
 #[repr(C, align(8))]
 struct aligned_message_t<T>(T);
 
+/// `mach_msg_trailer_t`, the part every trailer starts with. Only this much
+/// is known to follow a message until its size has been checked.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct mach_msg_trailer_t {
+    msgh_trailer_type: u32,
+    msgh_trailer_size: u32,
+}
+
+const MACH_MSG_TRAILER_FORMAT_0: u32 = 0;
+
+/// `mach_msg_audit_trailer_t`, which CFMachPort asks the kernel to append
+/// after every message it receives (`MACH_RCV_TRAILER_AV` includes it).
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct mach_msg_audit_trailer_t {
+    msgh_trailer_type: u32,
+    msgh_trailer_size: u32,
+    msgh_seqno: u32,
+    msgh_sender: [u32; 2],
+    msgh_audit: [u32; 8],
+}
+const _: [(); 52] = [(); size_of::<mach_msg_audit_trailer_t>()];
+
+/// The sending process of a Mach message, from its audit token.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct mach_peer {
+    /// Effective user id.
+    pub uid: u32,
+    pub pid: i32,
+}
+
 #[repr(C)]
 struct mach_port_limits {
     mpl_qlimit: u32,
@@ -1080,11 +1113,13 @@ pub unsafe fn mach_receive_message_on_port(
     receive_message_on_port(reply_port, response_buf, "mach_receive_message_on_port")
 }
 
+/// `peer` is null when the message arrived without an audit trailer.
 pub type mach_handler = unsafe extern "C" fn(
     context: *mut c_void,
     message: *mut c_char,
     len: u32,
     original_msg: *mut mach_msg_header_t,
+    peer: *const mach_peer,
 );
 
 #[repr(C)]
@@ -1153,8 +1188,29 @@ extern "C" fn mach_message_callback(
             payload_ptr = (message as *mut u8).add(size_of::<mach_msg_header_t>()) as *mut c_char;
         }
 
+        // The trailer starts at the message size rounded up to 4 bytes. The
+        // audit token holds the effective uid at index 1 and the pid at 5.
+        let trailer_ptr = (message as *const u8).add(((header_val.msgh_size + 3) & !3) as usize);
+        let trailer = core::ptr::read_unaligned(trailer_ptr as *const mach_msg_trailer_t);
+        let peer = (trailer.msgh_trailer_type == MACH_MSG_TRAILER_FORMAT_0
+            && trailer.msgh_trailer_size as usize >= size_of::<mach_msg_audit_trailer_t>())
+        .then(|| {
+            let audit = core::ptr::read_unaligned(trailer_ptr as *const mach_msg_audit_trailer_t);
+            mach_peer {
+                uid: audit.msgh_audit[1],
+                pid: audit.msgh_audit[5] as i32,
+            }
+        });
+        let peer_ptr = peer.as_ref().map_or(null(), |peer| peer as *const mach_peer);
+
         if let Some(handler) = mach_server.handler {
-            handler(mach_server.context, payload_ptr, payload_len, header_ptr);
+            handler(
+                mach_server.context,
+                payload_ptr,
+                payload_len,
+                header_ptr,
+                peer_ptr,
+            );
         }
 
         let _ = mach_msg_destroy(message as *mut mach_msg_header_t);
@@ -1,8 +1,17 @@
+use std::ffi::c_void;
+
+use nix::libc::{
+    PROC_PIDPATHINFO_MAXSIZE, PROC_PIDTBSDINFO, proc_bsdinfo, proc_listallpids, proc_name,
+    proc_pidinfo, proc_pidpath,
+};
 use objc2_core_graphics::CGError;
 
 use super::app::pid_t;
 use crate::sys::cg_ok;
 
+/// Guards against walking a corrupted or cyclic process tree forever.
+const MAX_ANCESTRY_DEPTH: usize = 64;
+
 pub struct ProcessInfo {
     pub is_xpc: bool,
 }
@@ -21,6 +30,58 @@ impl ProcessInfo {
     }
 }
 
+/// Returns the parent of `pid`, or `None` if the process no longer exists.
+pub fn parent_pid(pid: pid_t) -> Option<pid_t> {
+    let mut info: proc_bsdinfo = unsafe { std::mem::zeroed() };
+    let size = size_of::<proc_bsdinfo>() as i32;
+    let written = unsafe {
+        proc_pidinfo(
+            pid,
+            PROC_PIDTBSDINFO,
+            0,
+            (&mut info as *mut proc_bsdinfo).cast::<c_void>(),
+            size,
+        )
+    };
+    (written == size).then_some(info.pbi_ppid as pid_t)
+}
+
+/// Iterates over the ancestors of `pid`, nearest first, stopping before
+/// launchd.
+pub fn ancestors(pid: pid_t) -> impl Iterator<Item = pid_t> {
+    std::iter::successors(parent_pid(pid), |&parent| parent_pid(parent))
+        .take_while(|&parent| parent > 1)
+        .take(MAX_ANCESTRY_DEPTH)
+}
+
+/// Names of all running processes, including those without an app bundle.
+pub fn process_names() -> Vec<String> {
+    let count = unsafe { proc_listallpids(std::ptr::null_mut(), 0) };
+    if count <= 0 {
+        return Vec::new();
+    }
+    // Leave room for processes started since counting.
+    let mut pids: Vec<pid_t> = vec![0; count as usize + 32];
+    let size = (pids.len() * size_of::<pid_t>()) as i32;
+    let written = unsafe { proc_listallpids(pids.as_mut_ptr().cast::<c_void>(), size) };
+    pids.truncate(written.max(0) as usize);
+
+    pids.into_iter().filter_map(process_name).collect()
+}
+
+pub fn process_name(pid: pid_t) -> Option<String> {
+    let mut name = [0u8; 256];
+    let len = unsafe { proc_name(pid, name.as_mut_ptr().cast::<c_void>(), name.len() as u32) };
+    (len > 0).then(|| String::from_utf8_lossy(&name[..len as usize]).into_owned())
+}
+
+/// Full path of the executable `pid` is running.
+pub fn process_path(pid: pid_t) -> Option<String> {
+    let mut path = vec![0u8; PROC_PIDPATHINFO_MAXSIZE as usize];
+    let len = unsafe { proc_pidpath(pid, path.as_mut_ptr().cast::<c_void>(), path.len() as u32) };
+    (len > 0).then(|| String::from_utf8_lossy(&path[..len as usize]).into_owned())
+}
+
 type FourCharCode = u32;
 type OSType = FourCharCode;
 
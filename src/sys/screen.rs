@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::f64;
+use std::hash::BuildHasher;
 use std::mem::MaybeUninit;
 use std::ptr::NonNull;
 
@@ -23,7 +24,7 @@ use super::skylight::{
     SLSGetMenuBarAutohideEnabled, SLSGetSpaceManagementMode, SLSMainConnectionID,
 };
 use crate::common::collections::HashMap;
-use crate::sys::geometry::CGRectDef;
+use crate::sys::geometry::{CGRectDef, sanitize_rect};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[repr(transparent)]
@@ -80,6 +81,32 @@ impl ScreenInfo {
     pub fn display_uuid_owned(&self) -> Option<String> {
         self.display_uuid_opt().map(|uuid| uuid.to_string())
     }
+
+    /// Whether `key`, as written in a config file or command, refers to this
+    /// display: either its UUID or its localized name, e.g. "LG HDR 4K".
+    pub fn matches_display(&self, key: &str) -> bool {
+        (!self.display_uuid.is_empty() && self.display_uuid.eq_ignore_ascii_case(key))
+            || self.name.as_deref() == Some(key)
+    }
+}
+
+/// Settings configured for the display with `display_uuid`, localized as
+/// `name`, in a map keyed by display UUID or name. A UUID key wins over a name
+/// key.
+pub fn lookup_display<'a, T, S: BuildHasher>(
+    map: &'a std::collections::HashMap<String, T, S>,
+    display_uuid: &str,
+    name: Option<&str>,
+) -> Option<&'a T> {
+    if map.is_empty() {
+        return None;
+    }
+    map.get(display_uuid)
+        .or_else(|| {
+            map.iter()
+                .find_map(|(key, value)| key.eq_ignore_ascii_case(display_uuid).then_some(value))
+        })
+        .or_else(|| map.get(name?))
 }
 
 impl ScreenCache<Actual> {
@@ -155,6 +182,13 @@ impl<S: System> ScreenCache<S> {
         debug!("ns_screens={ns_screens:?}");
         let mut cg_screens = self.system.cg_screens().ok()?;
         debug!("cg_screens={cg_screens:?}");
+        cg_screens.retain_mut(|screen| match sanitize_rect(screen.bounds, "display bounds") {
+            Some(bounds) => {
+                screen.bounds = bounds;
+                true
+            }
+            None => false,
+        });
 
         if cg_screens.is_empty() {
             self.uuids.clear();
@@ -229,7 +263,7 @@ impl<S: System> ScreenCache<S> {
 
         self.uuids = uuids;
         self.processed_generation = self.pending_generation;
-        let screens = screens
+        let screens: Vec<ScreenInfo> = screens
             .into_iter()
             .zip(spaces)
             .map(|(mut screen, space)| {
@@ -271,14 +305,14 @@ fn dock_rect() -> CGRect {
     let mut rect = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(0.0, 0.0));
     let mut reason = 0;
     unsafe { SLSGetDockRectWithReason(*G_CONNECTION, &mut rect, &mut reason) };
-    rect
+    sanitize_rect(rect, "dock").unwrap_or_default()
 }
 
 fn dock_rect_with_reason() -> (CGRect, i32) {
     let mut rect = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(0.0, 0.0));
     let mut reason = 0;
     unsafe { SLSGetDockRectWithReason(*G_CONNECTION, &mut rect, &mut reason) };
-    (rect, reason)
+    (sanitize_rect(rect, "dock").unwrap_or_default(), reason)
 }
 
 fn dock_display_id() -> Option<u32> {
@@ -499,9 +533,11 @@ impl System for Actual {
             .iter()
             .flat_map(|s| {
                 let name = s.localizedName().to_string();
+                let frame = sanitize_rect(s.frame(), "screen frame")?;
                 Some(NSScreenInfo {
-                    frame: s.frame(),
-                    visible_frame: s.visibleFrame(),
+                    frame,
+                    visible_frame: sanitize_rect(s.visibleFrame(), "visible frame")
+                        .unwrap_or(frame),
                     cg_id: s.get_number().ok()?,
                     name: Some(name),
                 })
@@ -723,7 +759,10 @@ mod test {
     use objc2_core_foundation::{CFRetained, CFString, CGPoint, CGRect, CGSize};
     use objc2_core_graphics::CGError;
 
-    use super::{CGScreenInfo, NSScreenInfo, ScreenCache, ScreenId, System};
+    use super::{
+        CGScreenInfo, NSScreenInfo, ScreenCache, ScreenId, ScreenInfo, System, lookup_display,
+    };
+    use crate::common::collections::HashMap;
     use crate::sys::screen::{SpaceId, order_visible_spaces_by_position};
 
     struct Stub {
@@ -781,6 +820,29 @@ mod test {
         fn notch_height(&self, _did: u32) -> f64 { 0.0 }
     }
 
+    #[test]
+    fn displays_with_unusable_bounds_are_ignored() {
+        let stub = Stub {
+            cg_screens: vec![
+                CGScreenInfo {
+                    cg_id: ScreenId(1),
+                    bounds: CGRect::new(CGPoint::new(f64::NAN, 0.0), CGSize::new(1512.0, 982.0)),
+                },
+                CGScreenInfo {
+                    cg_id: ScreenId(2),
+                    bounds: CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1920.0, 1080.0)),
+                },
+            ],
+            ns_screens: Vec::new(),
+        };
+        let mut sc = ScreenCache::new_with(stub);
+        let (screens, _) = sc.refresh().unwrap();
+
+        assert_eq!(screens.iter().map(|screen| screen.id).collect::<Vec<_>>(), [
+            ScreenId(2)
+        ]);
+    }
+
     #[test]
     fn it_calculates_the_visible_frame() {
         let stub = Stub {
@@ -893,4 +955,32 @@ mod test {
         let ordered = order_visible_spaces_by_position(spaces);
         assert_eq!(ordered, vec![SpaceId::new(10), SpaceId::new(11)]);
     }
+
+    #[test]
+    fn displays_can_be_referred_to_by_uuid_or_name() {
+        let screen = ScreenInfo {
+            id: ScreenId(1),
+            frame: CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1920.0, 1080.0)),
+            display_uuid: "37D8832A-2D66-02CA-B9F7-8F30A301B230".into(),
+            name: Some("LG HDR 4K".into()),
+            space: None,
+        };
+        assert!(screen.matches_display("37d8832a-2d66-02ca-b9f7-8f30a301b230"));
+        assert!(screen.matches_display("LG HDR 4K"));
+        assert!(!screen.matches_display("Built-in Retina Display"));
+
+        let mut settings = HashMap::default();
+        settings.insert("LG HDR 4K".to_string(), "by name");
+        let uuid = screen.display_uuid.as_str();
+        assert_eq!(
+            lookup_display(&settings, uuid, screen.name.as_deref()),
+            Some(&"by name")
+        );
+        settings.insert(uuid.to_string(), "by uuid");
+        assert_eq!(
+            lookup_display(&settings, uuid, screen.name.as_deref()),
+            Some(&"by uuid")
+        );
+        assert_eq!(lookup_display(&settings, "other", None), None);
+    }
 }
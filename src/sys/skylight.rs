@@ -368,6 +368,7 @@ unsafe extern "C" {
 
     pub safe fn CGSetLocalEventsSuppressionInterval(int: f32);
     pub safe fn CGEnableEventStateCombining(enable: bool);
+    pub safe fn CGPreflightScreenCaptureAccess() -> bool;
 
     pub fn SLSMainConnectionID() -> cid_t;
     pub fn SLSServerPort(zero: *mut c_void) -> u32;
@@ -0,0 +1,31 @@
+//! Detects whether Stage Manager is turned on.
+//!
+//! Stage Manager arranges windows itself, so tiling while it is active only
+//! ends in rift and the Dock moving the same windows back and forth.
+
+use std::ptr::NonNull;
+
+use objc2_core_foundation::{CFBoolean, CFRetained, CFString, CFType};
+
+unsafe extern "C" {
+    fn CFPreferencesAppSynchronize(application_id: &CFString) -> bool;
+    fn CFPreferencesCopyAppValue(key: &CFString, application_id: &CFString) -> *mut CFType;
+}
+
+/// Reads the preference from disk, so callers should only ask when the Dock
+/// reports a preference change rather than on every space switch.
+pub fn is_stage_manager_enabled() -> bool {
+    let domain = CFString::from_static_str("com.apple.WindowManager");
+    let key = CFString::from_static_str("GloballyEnabled");
+    unsafe {
+        // The preference is written by the Dock; without synchronizing we
+        // would keep reading the value cached when rift started.
+        CFPreferencesAppSynchronize(&domain);
+        let Some(value) = NonNull::new(CFPreferencesCopyAppValue(&key, &domain)) else {
+            return false;
+        };
+        CFRetained::from_raw(value)
+            .downcast::<CFBoolean>()
+            .is_ok_and(|value| value.value())
+    }
+}
@@ -303,12 +303,14 @@ impl Timer {
     pub fn next(&mut self) -> impl Future<Output = Option<()>> {
         tokio_stream::StreamExt::next(self)
     }
-}
 
-impl Stream for Timer {
-    type Item = ();
+    /// Like [`Timer::next`], for a timer that is shared and re-armed through
+    /// `&self` while it is being awaited.
+    pub fn fired(&self) -> impl Future<Output = Option<()>> + '_ {
+        std::future::poll_fn(move |cx| self.poll_fired(cx))
+    }
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    fn poll_fired(&self, cx: &mut Context<'_>) -> Poll<Option<()>> {
         let mut state = self.inner.lock();
         match state.status {
             TimerStatus::Cancelled => Poll::Ready(None),
@@ -325,6 +327,14 @@ impl Stream for Timer {
     }
 }
 
+impl Stream for Timer {
+    type Item = ();
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_fired(cx)
+    }
+}
+
 impl Future for Timer {
     type Output = ();
 
@@ -16,15 +16,16 @@ use objc2_core_foundation::{
 use objc2_core_graphics::{
     CGBitmapInfo, CGColorSpace, CGContext, CGError, CGImage, CGInterpolationQuality, CGWindowID,
     CGWindowListCopyWindowInfo, CGWindowListOption, kCGNullWindowID, kCGWindowLayer, kCGWindowName,
-    kCGWindowOwnerName,
+    kCGWindowOwnerName, kCGWindowOwnerPID,
 };
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
-use super::geometry::{CGRectDef, CGSizeDef};
+use super::geometry::{CGRectDef, CGSizeDef, sanitize_rect, sanitize_size};
 use crate::actor::app::WindowId;
 #[cfg(test)]
 use crate::common::collections::HashMap;
+use crate::common::collections::HashSet;
 use crate::sys::app::pid_t;
 use crate::sys::axuielement::{AXUIElement, Error as AxError};
 use crate::sys::cg_ok;
@@ -400,6 +401,38 @@ pub fn mission_control_dock_overlay_visible() -> bool {
         })
 }
 
+/// Processes owning a normal-layer window in the global on-screen snapshot.
+///
+/// Like `mission_control_dock_overlay_visible` this is not space-aware; startup
+/// only uses it to decide which apps to discover first.
+pub fn pids_with_onscreen_windows() -> HashSet<pid_t> {
+    get_visible_windows_raw::<CFDictionary<CFString, CFType>>()
+        .iter()
+        .filter(|window| get_num(window, unsafe { kCGWindowLayer }) == Some(0))
+        .filter_map(|window| get_num(&window, unsafe { kCGWindowOwnerPID }))
+        .map(|pid| pid as pid_t)
+        .collect()
+}
+
+/// Whether rift may read other apps' window titles and contents. Checking
+/// does not prompt the user.
+pub fn has_screen_recording_permission() -> bool { CGPreflightScreenCaptureAccess() }
+
+/// Whether the main WindowServer connection was established and answers.
+pub fn connection_is_usable() -> bool {
+    *G_CONNECTION != 0 && unsafe { CGSGetActiveSpace(*G_CONNECTION) } != 0
+}
+
+/// Set another application's window alpha. The window server may refuse this for
+/// windows not owned by our connection, which callers treat as best-effort.
+#[cfg(not(test))]
+pub fn set_window_alpha(id: WindowServerId, alpha: f32) -> Result<(), CGError> {
+    cg_ok(unsafe { SLSSetWindowAlpha(*G_CONNECTION, id.as_u32(), alpha) })
+}
+
+#[cfg(test)]
+pub fn set_window_alpha(_id: WindowServerId, _alpha: f32) -> Result<(), CGError> { Ok(()) }
+
 pub fn window_parent(id: WindowServerId) -> Option<WindowServerId> {
     let query = WindowIterator::new(&[id])?;
     if query.count() == 1 {
@@ -594,13 +627,16 @@ fn window_info_from_query(query: &WindowIterator) -> Option<WindowServerInfo> {
         return None;
     }
     let (min_frame, max_frame) = query.constraints();
+    // A garbage constraint is dropped rather than the whole window; zero
+    // means no constraint.
+    let constraint = |size| sanitize_size(size, "window constraints").unwrap_or(CGSize::ZERO);
     Some(WindowServerInfo {
         id: WindowServerId::new(query.window_id()),
         pid: query.pid() as i32,
         layer,
-        frame: query.bounds(),
-        min_frame,
-        max_frame,
+        frame: sanitize_rect(query.bounds(), "window server")?,
+        min_frame: constraint(min_frame),
+        max_frame: constraint(max_frame),
     })
 }
 
@@ -0,0 +1,73 @@
+//! Regression testing against recorded event traces.
+//!
+//! Start rift with `--record <file>` to capture a trace, then replay it here
+//! without any real applications involved:
+//!
+//! ```ignore
+//! use rift_wm::testing::{Trace, assert_json_subset};
+//!
+//! let replay = Trace::load("bug.trace".as_ref())?
+//!     .with_config(rift_wm::common::config::Config::read("rift.toml".as_ref())?)
+//!     .run_headless()?;
+//! assert_json_subset(&replay.layout_json(), &serde_json::json!([{ "layout_mode": "bsp" }]));
+//! ```
+//!
+//! Requests the reactor makes of applications (moving, raising or resizing
+//! windows) are collected instead of being sent through the accessibility
+//! API; see [`HeadlessReactor::take_requests`].
+
+use serde_json::Value;
+
+pub use crate::actor::reactor::{HeadlessReactor, Trace};
+
+/// Asserts that every field present in `expected` has the same value in
+/// `actual`. Objects may carry extra keys; arrays must match element-wise.
+#[track_caller]
+pub fn assert_json_subset(actual: &Value, expected: &Value) {
+    if let Err(path) = json_subset(actual, expected, "$") {
+        panic!(
+            "layout differs at {path}\nexpected: {expected:#}\n  actual: {actual:#}",
+            expected = expected,
+            actual = actual,
+        );
+    }
+}
+
+fn json_subset(actual: &Value, expected: &Value, path: &str) -> Result<(), String> {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            for (key, expected) in expected {
+                let path = format!("{path}.{key}");
+                let actual = actual.get(key).ok_or_else(|| path.clone())?;
+                json_subset(actual, expected, &path)?;
+            }
+            Ok(())
+        }
+        (Value::Array(actual), Value::Array(expected)) if actual.len() == expected.len() => {
+            actual.iter().zip(expected).enumerate().try_for_each(|(i, (actual, expected))| {
+                json_subset(actual, expected, &format!("{path}[{i}]"))
+            })
+        }
+        _ if actual == expected => Ok(()),
+        _ => Err(path.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn subset_ignores_extra_keys_but_not_differing_values() {
+        let actual = json!([{ "layout_mode": "bsp", "space_id": 3, "root": { "ratio": 0.5 } }]);
+
+        assert!(json_subset(&actual, &json!([{ "layout_mode": "bsp" }]), "$").is_ok());
+        assert_eq!(
+            json_subset(&actual, &json!([{ "root": { "ratio": 0.6 } }]), "$"),
+            Err("$[0].root.ratio".to_string())
+        );
+        assert_eq!(json_subset(&actual, &json!([{}, {}]), "$"), Err("$".to_string()));
+    }
+}
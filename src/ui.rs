@@ -1,4 +1,6 @@
+pub mod borders;
 pub mod common;
+pub mod hint_overlay;
 pub mod menu_bar;
 pub mod mission_control;
 pub mod stack_line;
@@ -0,0 +1,152 @@
+use objc2::rc::Retained;
+use objc2_app_kit::NSNormalWindowLevel;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use objc2_quartz_core::CALayer;
+use tracing::warn;
+
+use crate::common::config::BorderSettings;
+use crate::sys::cgs_window::{CgsWindow, CgsWindowError};
+use crate::ui::common::{render_layer_to_cgs_window, with_disabled_actions};
+use crate::ui::stack_line::Color;
+
+/// Opacity of an insertion overlay's fill relative to its outline.
+const INSERTION_FILL_ALPHA: f64 = 0.25;
+const INSERTION_OUTLINE_WIDTH: f64 = 2.0;
+const INSERTION_RADIUS: f64 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BorderStyle {
+    pub width: f64,
+    pub radius: f64,
+    pub color: Color,
+    /// Translucent fill inside the ring, if any.
+    pub fill: Option<Color>,
+}
+
+impl BorderStyle {
+    pub fn active(settings: &BorderSettings) -> Self {
+        Self {
+            width: settings.width,
+            radius: settings.radius,
+            color: Color::from_hex(&settings.active_color).unwrap_or_else(Color::blue),
+            fill: None,
+        }
+    }
+
+    /// `None` when inactive windows should not be decorated at all.
+    pub fn inactive(settings: &BorderSettings) -> Option<Self> {
+        let color = Color::from_hex(settings.inactive_color.as_deref()?)?;
+        Some(Self {
+            width: settings.width,
+            radius: settings.radius,
+            color,
+            fill: None,
+        })
+    }
+
+    /// A filled outline marking where the next window will be inserted.
+    pub fn insertion(color: Color) -> Self {
+        Self {
+            width: INSERTION_OUTLINE_WIDTH,
+            radius: INSERTION_RADIUS,
+            color,
+            fill: Some(Color {
+                a: color.a * INSERTION_FILL_ALPHA,
+                ..color
+            }),
+        }
+    }
+
+    /// Frame of the overlay for a window frame: the ring sits just outside the window.
+    pub fn outer_frame(&self, window_frame: CGRect) -> CGRect {
+        CGRect::new(
+            CGPoint::new(
+                window_frame.origin.x - self.width,
+                window_frame.origin.y - self.width,
+            ),
+            CGSize::new(
+                window_frame.size.width + 2.0 * self.width,
+                window_frame.size.height + 2.0 * self.width,
+            ),
+        )
+    }
+}
+
+/// A click-through overlay window that draws a rounded ring around a managed
+/// window, or an insertion marker over part of one.
+pub struct BorderWindow {
+    frame: CGRect,
+    style: Option<BorderStyle>,
+    root_layer: Retained<CALayer>,
+    ring_layer: Retained<CALayer>,
+    cgs_window: CgsWindow,
+}
+
+impl BorderWindow {
+    pub fn new(frame: CGRect) -> Result<Self, CgsWindowError> {
+        let root_layer = CALayer::layer();
+        let ring_layer = CALayer::layer();
+        root_layer.addSublayer(&ring_layer);
+
+        let cgs_window = CgsWindow::new(frame)?;
+        if let Err(err) = cgs_window.set_opacity(false) {
+            warn!(error=?err, "failed to set border window opacity");
+        }
+        if let Err(err) = cgs_window.set_alpha(1.0) {
+            warn!(error=?err, "failed to set border window alpha");
+        }
+        if let Err(err) = cgs_window.set_level(NSNormalWindowLevel as i32) {
+            warn!(error=?err, "failed to set border window level");
+        }
+        if let Err(err) = cgs_window.set_tags(1 << 3) {
+            warn!(error=?err, "failed to disable border window shadow");
+        }
+
+        Ok(Self {
+            frame,
+            style: None,
+            root_layer,
+            ring_layer,
+            cgs_window,
+        })
+    }
+
+    /// Reposition and restyle the border, then order it directly above
+    /// `above` (the window server id of the decorated window) when known.
+    pub fn update(
+        &mut self,
+        frame: CGRect,
+        style: BorderStyle,
+        above: Option<u32>,
+    ) -> Result<(), CgsWindowError> {
+        if frame != self.frame {
+            self.cgs_window.set_shape(frame)?;
+            self.frame = frame;
+        }
+        if self.style != Some(style) || self.ring_layer.frame().size != frame.size {
+            self.style = Some(style);
+            self.redraw(style);
+        }
+        self.cgs_window.order_above(above)
+    }
+
+    pub fn hide(&self) -> Result<(), CgsWindowError> { self.cgs_window.order_out() }
+
+    /// Window server id of the overlay, used to stack another ring above it.
+    pub fn id(&self) -> u32 { self.cgs_window.id() }
+
+    fn redraw(&self, style: BorderStyle) {
+        let bounds = CGRect::new(CGPoint::new(0.0, 0.0), self.frame.size);
+        with_disabled_actions(|| {
+            self.root_layer.setFrame(bounds);
+            self.ring_layer.setFrame(bounds);
+            self.ring_layer.setBorderWidth(style.width);
+            self.ring_layer.setCornerRadius(style.radius + style.width);
+            let color = style.color.to_nscolor();
+            self.ring_layer.setBorderColor(Some(&color.CGColor()));
+            let fill = style.fill.map(|fill| fill.to_nscolor().CGColor());
+            self.ring_layer.setBackgroundColor(fill.as_deref());
+        });
+        render_layer_to_cgs_window(self.cgs_window.id(), self.frame.size, &self.root_layer);
+    }
+}
@@ -0,0 +1,112 @@
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2_app_kit::NSNormalWindowLevel;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use objc2_foundation::NSString;
+use objc2_quartz_core::{CALayer, CATextLayer, kCAAlignmentCenter};
+use tracing::warn;
+
+use crate::sys::cgs_window::{CgsWindow, CgsWindowError};
+use crate::ui::common::{render_layer_to_cgs_window, with_disabled_actions};
+use crate::ui::stack_line::Color;
+
+/// Side of the square badge a hint is drawn in.
+pub const BADGE_SIZE: f64 = 56.0;
+const FONT_SIZE: f64 = 30.0;
+const CORNER_RADIUS: f64 = 10.0;
+
+/// The badge's frame when centered on `window`.
+pub fn badge_frame(window: CGRect) -> CGRect {
+    CGRect::new(
+        CGPoint::new(
+            window.origin.x + (window.size.width - BADGE_SIZE) / 2.0,
+            window.origin.y + (window.size.height - BADGE_SIZE) / 2.0,
+        ),
+        CGSize::new(BADGE_SIZE, BADGE_SIZE),
+    )
+}
+
+/// A click-through badge showing the key that picks the window under it.
+pub struct HintWindow {
+    frame: CGRect,
+    drawn: Option<(char, Color)>,
+    root_layer: Retained<CALayer>,
+    text_layer: Retained<CATextLayer>,
+    cgs_window: CgsWindow,
+}
+
+impl HintWindow {
+    pub fn new(frame: CGRect) -> Result<Self, CgsWindowError> {
+        let root_layer = CALayer::layer();
+        let text_layer = CATextLayer::layer();
+        root_layer.addSublayer(&text_layer);
+
+        let cgs_window = CgsWindow::new(frame)?;
+        if let Err(err) = cgs_window.set_opacity(false) {
+            warn!(error=?err, "failed to set hint overlay opacity");
+        }
+        if let Err(err) = cgs_window.set_alpha(1.0) {
+            warn!(error=?err, "failed to set hint overlay alpha");
+        }
+        if let Err(err) = cgs_window.set_level(NSNormalWindowLevel as i32) {
+            warn!(error=?err, "failed to set hint overlay level");
+        }
+        if let Err(err) = cgs_window.set_tags(1 << 3) {
+            warn!(error=?err, "failed to disable hint overlay shadow");
+        }
+
+        Ok(Self {
+            frame,
+            drawn: None,
+            root_layer,
+            text_layer,
+            cgs_window,
+        })
+    }
+
+    /// Move the badge and redraw it with `key`, ordering it directly above
+    /// `above` (the window server id of the hinted window) when known.
+    pub fn update(
+        &mut self,
+        frame: CGRect,
+        key: char,
+        color: Color,
+        above: Option<u32>,
+    ) -> Result<(), CgsWindowError> {
+        if frame != self.frame {
+            self.cgs_window.set_shape(frame)?;
+            self.frame = frame;
+        }
+        if self.drawn != Some((key, color)) {
+            self.drawn = Some((key, color));
+            self.redraw(key, color);
+        }
+        self.cgs_window.order_above(above)
+    }
+
+    pub fn hide(&self) -> Result<(), CgsWindowError> { self.cgs_window.order_out() }
+
+    fn redraw(&self, key: char, color: Color) {
+        let bounds = CGRect::new(CGPoint::new(0.0, 0.0), self.frame.size);
+        let text_height = FONT_SIZE * 1.2;
+        with_disabled_actions(|| {
+            self.root_layer.setFrame(bounds);
+            self.root_layer.setCornerRadius(CORNER_RADIUS);
+            self.root_layer.setBackgroundColor(Some(&color.to_nscolor().CGColor()));
+            self.text_layer.setFrame(CGRect::new(
+                CGPoint::new(0.0, (bounds.size.height - text_height) / 2.0),
+                CGSize::new(bounds.size.width, text_height),
+            ));
+            self.text_layer.setFontSize(FONT_SIZE);
+            self.text_layer
+                .setForegroundColor(Some(&Color::new(1.0, 1.0, 1.0, 1.0).to_nscolor().CGColor()));
+            let text = NSString::from_str(&key.to_uppercase().to_string());
+            let text: &AnyObject = &text;
+            unsafe {
+                self.text_layer.setAlignmentMode(kCAAlignmentCenter);
+                self.text_layer.setString(Some(text));
+            }
+        });
+        render_layer_to_cgs_window(self.cgs_window.id(), self.frame.size, &self.root_layer);
+    }
+}
@@ -1,5 +1,5 @@
 // many ideas for how this works were taken from https://github.com/xiamaz/YabaiIndicator
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -24,6 +24,7 @@ use objc2_foundation::{
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::debug;
 
+use crate::actor::app::WindowId;
 use crate::actor::reactor::{Command as ReactorTopCommand, ReactorCommand};
 use crate::actor::wm_controller::{WmCmd, WmCommand};
 use crate::common::config::{
@@ -35,6 +36,7 @@ use crate::model::VirtualWorkspaceId;
 use crate::model::server::{WindowData, WorkspaceData};
 use crate::sys::hotkey::{Hotkey, KeyCode, Modifiers};
 use crate::sys::screen::SpaceId;
+use crate::sys::window_server::WindowServerId;
 use crate::ui::common::compute_window_layout_metrics;
 
 const CELL_WIDTH: f64 = 20.0;
@@ -52,6 +54,13 @@ pub enum MenuAction {
     NextWorkspace,
     PrevWorkspace,
     SwitchToWorkspace(usize),
+    ToggleWindowFloating,
+    ToggleStack,
+    MoveWindowToWorkspace {
+        window: WindowId,
+        workspace: usize,
+    },
+    CloseWindow(Option<WindowServerId>),
     SaveLayout(PathBuf),
     SaveMasterFile,
     RestoreLayout {
@@ -96,6 +105,7 @@ impl MenuIcon {
             SpaceId::new(0),
             true,
             &[],
+            None,
             &MenuShortcuts::default(),
             layout_folder,
         );
@@ -122,7 +132,7 @@ impl MenuIcon {
         active_space_is_activated: bool,
         workspaces: &[WorkspaceData],
         _active_workspace: Option<VirtualWorkspaceId>,
-        _windows: &[WindowData],
+        windows: &[WindowData],
         settings: &MenuBarSettings,
         hotkeys: &[(Hotkey, WmCommand)],
     ) {
@@ -131,6 +141,7 @@ impl MenuIcon {
             .find(|w| w.is_active)
             .and_then(|w| parse_layout_mode(&w.layout_mode));
         let shortcuts = MenuShortcuts::from_hotkeys(hotkeys);
+        let focused_window = windows.iter().find(|w| w.is_focused);
         let menu = build_status_menu(
             self.mtm,
             &self.menu_handler,
@@ -138,6 +149,7 @@ impl MenuIcon {
             active_space,
             active_space_is_activated,
             workspaces,
+            focused_window,
             &shortcuts,
             &settings.resolved_layout_folder(),
         );
@@ -146,16 +158,7 @@ impl MenuIcon {
 
         let mode = settings.mode;
         let style = settings.display_style;
-        let label_for = |workspace: &WorkspaceData| match settings.active_label {
-            ActiveWorkspaceLabel::Index => format!("{}", workspace.index + 1),
-            ActiveWorkspaceLabel::Name => {
-                if workspace.name.is_empty() {
-                    format!("{}", workspace.index + 1)
-                } else {
-                    workspace.name.clone()
-                }
-            }
-        };
+        let label_for = |workspace: &WorkspaceData| workspace_label(workspace, settings);
 
         let render_inputs = match (mode, style) {
             (MenuBarDisplayMode::All, WorkspaceDisplayStyle::Layout) => {
@@ -305,6 +308,46 @@ struct MenuIconViewIvars {
     inactive_text_attrs: Retained<NSDictionary<NSAttributedStringKey, AnyObject>>,
 }
 
+/// Expands `settings.label_format` for one workspace.
+fn workspace_label(workspace: &WorkspaceData, settings: &MenuBarSettings) -> String {
+    let index = format!("{}", workspace.index + 1);
+    let label = match settings.active_label {
+        ActiveWorkspaceLabel::Name if !workspace.name.is_empty() => workspace.name.as_str(),
+        _ => index.as_str(),
+    };
+    settings
+        .label_format
+        .replace("{label}", label)
+        .replace("{index}", &index)
+        .replace("{name}", &workspace.name)
+        .replace("{count}", &workspace.window_count.to_string())
+        .replace("{apps}", &app_glyphs(&workspace.windows, settings))
+        .trim()
+        .to_string()
+}
+
+/// One glyph per distinct app, in window order.
+fn app_glyphs(windows: &[WindowData], settings: &MenuBarSettings) -> String {
+    let mut seen = Vec::new();
+    let mut glyphs = Vec::new();
+    for window in windows {
+        let bundle_id = window.info.bundle_id.as_deref();
+        let app_name = window.app_name.as_deref();
+        let key = bundle_id.or(app_name).unwrap_or_default();
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+        let glyph = [bundle_id, app_name]
+            .into_iter()
+            .flatten()
+            .find_map(|key| settings.app_glyphs.get(key))
+            .unwrap_or(&settings.default_app_glyph);
+        glyphs.push(glyph.as_str());
+    }
+    glyphs.join(" ")
+}
+
 fn as_any_object<T: Message>(obj: &T) -> &AnyObject {
     unsafe { &*(obj as *const T as *const AnyObject) }
 }
@@ -413,6 +456,7 @@ fn build_status_menu(
     _active_space: SpaceId,
     active_space_is_activated: bool,
     workspaces: &[WorkspaceData],
+    focused_window: Option<&WindowData>,
     shortcuts: &MenuShortcuts,
     layout_folder: &Path,
 ) -> Retained<NSMenu> {
@@ -504,6 +548,13 @@ fn build_status_menu(
         workspace_item.setSubmenu(Some(&ws_submenu));
     }
     menu.addItem(&workspace_item);
+    menu.addItem(&build_window_menu_item(
+        mtm,
+        handler,
+        focused_window,
+        workspaces,
+        shortcuts,
+    ));
 
     let layouts_item = make_menu_item(mtm, "Layout Files", None, None, None, None, None);
     let layouts_title = NSString::from_str("Layout Files");
@@ -681,6 +732,97 @@ fn build_status_menu(
     menu
 }
 
+/// Actions for the focused window, so they are reachable without knowing the
+/// keybindings. Disabled when nothing is focused.
+fn build_window_menu_item(
+    mtm: MainThreadMarker,
+    handler: &MenuActionHandler,
+    focused_window: Option<&WindowData>,
+    workspaces: &[WorkspaceData],
+    shortcuts: &MenuShortcuts,
+) -> Retained<NSMenuItem> {
+    let window_item = make_menu_item(mtm, "Window", None, None, None, None, None);
+    handler.set_focused_window(focused_window.map(|w| (w.id, w.info.sys_id)));
+    let Some(window) = focused_window else {
+        window_item.setEnabled(false);
+        return window_item;
+    };
+
+    let title = NSString::from_str("Window");
+    let submenu: Retained<NSMenu> =
+        unsafe { msg_send![NSMenu::alloc(mtm), initWithTitle: &*title] };
+
+    let heading = match window.app_name.as_deref() {
+        Some(app) if !window.info.title.is_empty() => format!("{app} — {}", window.info.title),
+        Some(app) => app.to_string(),
+        None => window.info.title.clone(),
+    };
+    let heading_item = make_menu_item(mtm, &heading, None, None, None, None, None);
+    heading_item.setEnabled(false);
+    submenu.addItem(&heading_item);
+    add_separator(&submenu);
+
+    submenu.addItem(&make_menu_item(
+        mtm,
+        "Float Window",
+        Some(sel!(onToggleWindowFloating:)),
+        Some(handler),
+        Some(window.is_floating),
+        shortcuts.toggle_window_floating.as_ref(),
+        None,
+    ));
+    submenu.addItem(&make_menu_item(
+        mtm,
+        "Toggle Stack",
+        Some(sel!(onToggleStack:)),
+        Some(handler),
+        None,
+        shortcuts.toggle_stack.as_ref(),
+        None,
+    ));
+
+    let move_item = make_menu_item(mtm, "Move to Workspace", None, None, None, None, None);
+    let move_title = NSString::from_str("Move to Workspace");
+    let move_submenu: Retained<NSMenu> =
+        unsafe { msg_send![NSMenu::alloc(mtm), initWithTitle: &*move_title] };
+    for ws in workspaces.iter().filter(|ws| !ws.is_active) {
+        let ws_label = if ws.name.is_empty() {
+            format!("Workspace {}", ws.index + 1)
+        } else {
+            format!("{} ({})", ws.name, ws.index + 1)
+        };
+        move_submenu.addItem(&make_menu_item(
+            mtm,
+            &ws_label,
+            Some(sel!(onMoveWindowToWorkspace:)),
+            Some(handler),
+            None,
+            None,
+            Some(ws.index as isize),
+        ));
+    }
+    if move_submenu.numberOfItems() == 0 {
+        move_item.setEnabled(false);
+    } else {
+        move_item.setSubmenu(Some(&move_submenu));
+    }
+    submenu.addItem(&move_item);
+
+    add_separator(&submenu);
+    submenu.addItem(&make_menu_item(
+        mtm,
+        "Close Window",
+        Some(sel!(onCloseWindow:)),
+        Some(handler),
+        None,
+        shortcuts.close_window.as_ref(),
+        None,
+    ));
+
+    window_item.setSubmenu(Some(&submenu));
+    window_item
+}
+
 #[derive(Default)]
 struct MenuShortcuts {
     toggle_space_activation: Option<Hotkey>,
@@ -690,6 +832,9 @@ struct MenuShortcuts {
     switch_workspace_by_index: HashMap<usize, Hotkey>,
     switch_workspace_by_name: HashMap<String, Hotkey>,
     reload_config: Option<Hotkey>,
+    toggle_window_floating: Option<Hotkey>,
+    toggle_stack: Option<Hotkey>,
+    close_window: Option<Hotkey>,
 }
 
 impl MenuShortcuts {
@@ -743,6 +888,22 @@ impl MenuShortcuts {
                 WmCommand::Wm(WmCmd::ReloadConfig) => {
                     out.reload_config.get_or_insert_with(|| hotkey.clone());
                 }
+                WmCommand::ReactorCommand(ReactorTopCommand::Layout(
+                    LayoutCommand::ToggleWindowFloating,
+                )) => {
+                    out.toggle_window_floating.get_or_insert_with(|| hotkey.clone());
+                }
+                WmCommand::ReactorCommand(ReactorTopCommand::Layout(
+                    LayoutCommand::ToggleStack,
+                )) => {
+                    out.toggle_stack.get_or_insert_with(|| hotkey.clone());
+                }
+                WmCommand::Wm(WmCmd::CloseWindow)
+                | WmCommand::ReactorCommand(ReactorTopCommand::Reactor(
+                    ReactorCommand::CloseWindow { window_server_id: None },
+                )) => {
+                    out.close_window.get_or_insert_with(|| hotkey.clone());
+                }
 
                 _ => {}
             }
@@ -825,6 +986,7 @@ struct MenuActionHandlerIvars {
     action_tx: UnboundedSender<MenuAction>,
     layout_files: RefCell<Vec<PathBuf>>,
     layout_folder: RefCell<PathBuf>,
+    focused_window: Cell<Option<(WindowId, Option<WindowServerId>)>>,
 }
 
 impl MenuActionHandler {
@@ -833,6 +995,7 @@ impl MenuActionHandler {
             action_tx,
             layout_files: RefCell::new(Vec::new()),
             layout_folder: RefCell::new(PathBuf::new()),
+            focused_window: Cell::new(None),
         });
         unsafe { msg_send![super(this), init] }
     }
@@ -845,6 +1008,10 @@ impl MenuActionHandler {
 
     fn set_layout_folder(&self, path: PathBuf) { *self.ivars().layout_folder.borrow_mut() = path; }
 
+    fn set_focused_window(&self, window: Option<(WindowId, Option<WindowServerId>)>) {
+        self.ivars().focused_window.set(window);
+    }
+
     fn layout_file_for_item(&self, item: Option<&NSMenuItem>) -> Option<PathBuf> {
         let index = usize::try_from(item?.tag()).ok()?;
         self.ivars().layout_files.borrow().get(index).cloned()
@@ -972,6 +1139,35 @@ define_class!(
             }
         }
 
+        #[unsafe(method(onToggleWindowFloating:))]
+        fn on_toggle_window_floating(&self, _sender: Option<&AnyObject>) {
+            self.emit(MenuAction::ToggleWindowFloating);
+        }
+
+        #[unsafe(method(onToggleStack:))]
+        fn on_toggle_stack(&self, _sender: Option<&AnyObject>) {
+            self.emit(MenuAction::ToggleStack);
+        }
+
+        #[unsafe(method(onMoveWindowToWorkspace:))]
+        fn on_move_window_to_workspace(&self, sender: Option<&NSMenuItem>) {
+            let Some((window, _)) = self.ivars().focused_window.get() else {
+                return;
+            };
+            if let Some(sender) = sender
+                && let Ok(workspace) = usize::try_from(sender.tag())
+            {
+                self.emit(MenuAction::MoveWindowToWorkspace { window, workspace });
+            }
+        }
+
+        #[unsafe(method(onCloseWindow:))]
+        fn on_close_window(&self, _sender: Option<&AnyObject>) {
+            if let Some((_, window_server_id)) = self.ivars().focused_window.get() {
+                self.emit(MenuAction::CloseWindow(window_server_id));
+            }
+        }
+
         #[unsafe(method(onRestoreWorkspace:))]
         fn on_restore_workspace(&self, _sender: Option<&AnyObject>) {
             if let Some(path) = self.choose_layout_path().and_then(Self::validate_layout_path) {
@@ -1383,3 +1579,71 @@ define_class!(
         }
     }
 );
+
+#[cfg(test)]
+mod tests {
+    use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+
+    use super::*;
+    use crate::sys::app::WindowInfo;
+
+    fn window(idx: u32, bundle_id: &str, app_name: &str) -> WindowData {
+        WindowData {
+            id: WindowId::new(1, idx),
+            is_floating: false,
+            is_focused: false,
+            is_native_fullscreen: false,
+            is_zoomed: false,
+            is_always_on_top: false,
+            app_name: Some(app_name.to_string()),
+            workspace_name: None,
+            display_uuid: None,
+            display_name: None,
+            info: WindowInfo {
+                is_standard: true,
+                is_root: true,
+                is_minimized: false,
+                is_resizable: true,
+                min_size: None,
+                max_size: None,
+                title: String::new(),
+                frame: CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(100.0, 100.0)),
+                sys_id: None,
+                bundle_id: Some(bundle_id.to_string()),
+                path: None,
+                ax_role: None,
+                ax_subrole: None,
+            },
+        }
+    }
+
+    #[test]
+    fn label_format_expands_counts_and_app_glyphs() {
+        let workspace = WorkspaceData {
+            id: "VirtualWorkspaceId(1v1)".to_string(),
+            index: 1,
+            name: "web".to_string(),
+            layout_mode: "bsp".to_string(),
+            is_active: true,
+            window_count: 3,
+            windows: vec![
+                window(1, "com.apple.Safari", "Safari"),
+                window(2, "com.apple.Terminal", "Terminal"),
+                window(3, "com.apple.Safari", "Safari"),
+            ],
+            display_uuid: None,
+            display_name: None,
+        };
+        let mut settings = MenuBarSettings::default();
+        assert_eq!(workspace_label(&workspace, &settings), "2");
+
+        settings.active_label = ActiveWorkspaceLabel::Name;
+        settings.label_format = "{label}:{count} {apps}".to_string();
+        settings.app_glyphs.insert("com.apple.Safari".to_string(), "S".to_string());
+        assert_eq!(workspace_label(&workspace, &settings), "web:3 S •");
+
+        settings.app_glyphs.insert("Terminal".to_string(), "T".to_string());
+        settings.label_format = "{index} {apps}".to_string();
+        assert_eq!(workspace_label(&workspace, &settings), "2 S T");
+    }
+}
@@ -644,6 +644,22 @@ impl MissionControlOverlay {
         None
     }
 
+    /// The window thumbnail under `point` in the all-workspaces grid, laid
+    /// out the same way `draw_workspaces` draws it.
+    fn workspace_window_at_point(
+        workspaces: &[WorkspaceData],
+        point: CGPoint,
+        bounds: CGRect,
+    ) -> Option<&WindowData> {
+        let (order_idx, original_idx) = Self::workspace_index_at_point(workspaces, point, bounds)?;
+        let visible_count = Self::visible_workspaces(workspaces).len();
+        let tile = WorkspaceGrid::new(visible_count, bounds)?.rect_for(order_idx);
+        let windows = &workspaces[original_idx].windows;
+        let (idx, _) =
+            Self::window_at_point(windows, point, tile, WindowLayoutKind::PreserveOriginal)?;
+        windows.get(idx)
+    }
+
     fn window_at_point(
         windows: &[WindowData],
         point: CGPoint,
@@ -2047,6 +2063,20 @@ impl MissionControlOverlay {
             CGSize::new(self.frame.size.width, self.frame.size.height),
         ));
 
+        // A click on a window thumbnail inside a workspace tile focuses that
+        // window, switching to its workspace, rather than only the workspace.
+        let window_action = match mode {
+            MissionControlMode::AllWorkspaces(workspaces) => {
+                Self::workspace_window_at_point(workspaces, pt, content_bounds).map(|window| {
+                    MissionControlAction::FocusWindow {
+                        window_id: window.id,
+                        window_server_id: window.info.sys_id,
+                    }
+                })
+            }
+            MissionControlMode::CurrentWorkspace(_) => None,
+        };
+
         let new_sel = match mode {
             MissionControlMode::AllWorkspaces(workspaces) => {
                 Self::workspace_index_at_point(workspaces, pt, content_bounds)
@@ -2063,7 +2093,10 @@ impl MissionControlOverlay {
                 state.set_selection(sel);
                 drop(state);
                 self.draw_and_present();
-                self.activate_selection_action();
+                match window_action {
+                    Some(action) => self.emit_action(action),
+                    None => self.activate_selection_action(),
+                }
             }
             None => {
                 drop(state);
@@ -2,18 +2,20 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
 use objc2_app_kit::NSNormalWindowLevel;
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
-use objc2_quartz_core::CALayer;
+use objc2_foundation::NSString;
+use objc2_quartz_core::{CALayer, CATextLayer};
 use tracing::warn;
 
 use crate::actor::app::WindowId;
-use crate::common::config::{HorizontalPlacement, VerticalPlacement};
+use crate::common::config::{HorizontalPlacement, StackLineStyle, VerticalPlacement};
 use crate::sys::cgs_window::{CgsWindow, CgsWindowError};
 use crate::sys::screen::SpaceId;
 use crate::ui::common::{render_layer_to_cgs_window, with_disabled_actions};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
     pub r: f64,
     pub g: f64,
@@ -30,6 +32,10 @@ impl Color {
 
     pub fn gray() -> Self { Self::new(0.6, 0.6, 0.6, 1.0) }
 
+    pub fn from_hex(value: &str) -> Option<Self> {
+        crate::common::config::parse_hex_color(value).map(|[r, g, b, a]| Self::new(r, g, b, a))
+    }
+
     pub fn to_nscolor(&self) -> Retained<objc2_app_kit::NSColor> {
         objc2_app_kit::NSColor::colorWithRed_green_blue_alpha(self.r, self.g, self.b, self.a)
     }
@@ -45,6 +51,7 @@ pub struct IndicatorConfig {
     pub horizontal_placement: HorizontalPlacement,
     pub vertical_placement: VerticalPlacement,
     pub spacing: f64,
+    pub style: StackLineStyle,
 }
 
 impl Default for IndicatorConfig {
@@ -58,6 +65,7 @@ impl Default for IndicatorConfig {
             horizontal_placement: HorizontalPlacement::Top,
             vertical_placement: VerticalPlacement::Right,
             spacing: 4.0,
+            style: StackLineStyle::Line,
         }
     }
 }
@@ -73,6 +81,7 @@ impl From<&crate::common::config::StackLineSettings> for IndicatorConfig {
             horizontal_placement: config.horiz_placement,
             vertical_placement: config.vert_placement,
             spacing: config.spacing,
+            style: config.style,
         }
     }
 }
@@ -96,6 +105,20 @@ pub struct GroupDisplayData {
     pub total_count: usize,
     pub selected_index: usize,
     pub window_ids: Vec<WindowId>,
+    /// Window titles, parallel to `window_ids`; shown in the tabs style.
+    pub titles: Vec<String>,
+}
+
+/// The text drawn on a tab. Vertical tabs are only as wide as the bar, so
+/// they get the first letter of the title.
+pub fn tab_label(title: &str, group_kind: GroupKind) -> String {
+    let title = title.trim();
+    match group_kind {
+        GroupKind::Horizontal => title.to_owned(),
+        GroupKind::Vertical => {
+            title.chars().next().map(|c| c.to_uppercase().collect()).unwrap_or_default()
+        }
+    }
 }
 
 pub type SegmentClickCallback = Rc<dyn Fn(usize)>;
@@ -106,6 +129,7 @@ struct IndicatorState {
     background_layer: Option<Retained<CALayer>>,
     separator_layers: Vec<Retained<CALayer>>,
     selected_layer: Option<Retained<CALayer>>,
+    title_layers: Vec<Retained<CATextLayer>>,
     click_callback: Option<SegmentClickCallback>,
     space_id: Option<SpaceId>,
     is_visible: bool,
@@ -119,6 +143,7 @@ impl IndicatorState {
             background_layer: None,
             separator_layers: Vec::new(),
             selected_layer: None,
+            title_layers: Vec::new(),
             click_callback: None,
             space_id: None,
             is_visible: false,
@@ -276,6 +301,7 @@ impl GroupIndicatorWindow {
         state.background_layer = None;
         state.separator_layers.clear();
         state.selected_layer = None;
+        state.title_layers.clear();
     }
 
     fn update_layers(&self) {
@@ -299,6 +325,7 @@ impl GroupIndicatorWindow {
             self.update_separator_layers(&group_data, adjusted_bounds);
 
             self.update_selected_layer(&group_data, bounds);
+            self.update_title_layers(&group_data, adjusted_bounds);
         });
     }
 
@@ -585,6 +612,63 @@ impl GroupIndicatorWindow {
         state.selected_layer = Some(selected_layer);
     }
 
+    /// Label every segment with its window title in the tabs style. The
+    /// labels are re-added on top since the selected layer is rebuilt on
+    /// each update.
+    fn update_title_layers(&self, group_data: &GroupDisplayData, bounds: CGRect) {
+        let mut state = self.state.borrow_mut();
+        let config = state.config;
+        let needed = if config.style == StackLineStyle::Tabs {
+            group_data.total_count
+        } else {
+            0
+        };
+
+        while state.title_layers.len() > needed {
+            if let Some(layer) = state.title_layers.pop() {
+                layer.removeFromSuperlayer();
+            }
+        }
+        while state.title_layers.len() < needed {
+            state.title_layers.push(CATextLayer::layer());
+        }
+
+        let font_size = (config.bar_thickness * 0.55).clamp(8.0, 13.0);
+        let text_height = (font_size * 1.3).min(bounds.size.height);
+        let padding = 4.0;
+        for (index, layer) in state.title_layers.iter().enumerate() {
+            let segment = Self::calculate_segment_frame(group_data, bounds, index);
+            let inset = if group_data.group_kind == GroupKind::Horizontal {
+                padding.min(segment.size.width / 4.0)
+            } else {
+                0.0
+            };
+            layer.setFrame(CGRect::new(
+                CGPoint::new(
+                    segment.origin.x + inset,
+                    segment.origin.y + (segment.size.height - text_height) / 2.0,
+                ),
+                CGSize::new((segment.size.width - 2.0 * inset).max(0.0), text_height),
+            ));
+            layer.setFontSize(font_size);
+            layer.setMasksToBounds(true);
+            let text_color = if index == group_data.selected_index {
+                Color::new(1.0, 1.0, 1.0, 1.0)
+            } else {
+                Color::new(0.15, 0.15, 0.15, 1.0)
+            };
+            layer.setForegroundColor(Some(&text_color.to_nscolor().CGColor()));
+
+            let title = group_data.titles.get(index).map(String::as_str).unwrap_or_default();
+            let text = NSString::from_str(&tab_label(title, group_data.group_kind));
+            let text: &AnyObject = &text;
+            unsafe { layer.setString(Some(text)) };
+
+            layer.removeFromSuperlayer();
+            self.root_layer.addSublayer(layer);
+        }
+    }
+
     fn animate_selection_change(&self, to_index: usize) {
         let state = self.state.borrow();
         let Some(selected_layer) = state.selected_layer.clone() else {